@@ -0,0 +1,201 @@
+use kanga_sexpr::sexpr;
+
+sexpr! {
+    /// Color
+    /// 
+    /// An RGB color with an optional alpha channel. Each value is in the range 0.0 to 1.0.
+    /// The format of this is `(color <red> <green> <blue> [<alpha>])`.
+    #[derive(Debug)]
+    pub struct Color {
+        (color
+            red: f64
+            green: f64
+            blue: f64
+            [alpha: f64]
+        )
+    }
+}
+
+sexpr! {
+    /// Font
+    /// 
+    /// The font to use for text. The format of this is
+    /// `(font [(face <string>)] (size <height_mm> <width_mm>) [(thickness <mm>)] [bold] [italic] [(line_spacing <mm>)])`.
+    #[derive(Debug)]
+    pub struct Font {
+        (font
+            [(face: String)]
+            (size: FontSize
+                /// The height of the characters in nanometers.
+                height: f64 as nm
+
+                /// The width of the characters in nanometers.
+                width: f64 as nm
+            )
+            [(thickness: f64 as nm)]
+            [bold]
+            [italic]
+            [(line_spacing:f64)]
+        )
+    }
+}
+
+sexpr! {
+    /// Coordinate Point List
+    /// 
+    /// A list of X/Y coordinate points formatted as `(pts (xy <x> <y>)...)`.
+
+    #[derive(Debug)]
+    pub struct Points {
+        (pts (xy:XY)*)
+    }
+}
+
+sexpr! {
+    /// Test justification
+    ///
+    /// Defines how text is justified. Formatted as `(justify [left|right] [top|bottom] [mirror])`.
+    #[derive(Debug)]
+    pub struct TextJustify {
+        (justify
+            [h_justify: HJustify]
+            [v_justify: VJustify]
+            [mirror]
+        )
+    }
+}
+
+sexpr! {
+    #[derive(Debug)]
+    pub enum HJustify {
+        left => Left,
+        right => Right,
+    }
+}
+
+sexpr! {
+    #[derive(Debug)]
+    pub enum VJustify {
+        top => Top,
+        bottom => Bottom,
+    }
+}
+
+sexpr! {
+    #[derive(Debug)]
+    pub struct XY {
+        (xy
+            x: f64 as nm
+            y: f64 as nm
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, lexpr::sexp};
+
+    #[test]
+    fn test_color() {
+        let color = Color::try_from(&sexp!((color 0.1 0.2 0.3 0.4))).unwrap();
+        assert_eq!(color.red, 0.1);
+        assert_eq!(color.green, 0.2);
+        assert_eq!(color.blue, 0.3);
+        assert_eq!(color.alpha, Some(0.4));
+
+        let color = Color::try_from(&sexp!((color 0.1 0.2 0.3))).unwrap();
+        assert_eq!(color.red, 0.1);
+        assert_eq!(color.green, 0.2);
+        assert_eq!(color.blue, 0.3);
+        assert!(color.alpha.is_none());
+    }
+
+        #[test]
+        fn test_points() {
+            let pts = Points::try_from(&sexp!((pts (xy 1.0 2.0) (xy 3.0 4.0)))).unwrap();
+            assert_eq!(pts.xy.len(), 2);
+            assert_eq!(pts.xy[0].x, 1_000_000);
+            assert_eq!(pts.xy[0].y, 2_000_000);
+            assert_eq!(pts.xy[1].x, 3_000_000);
+            assert_eq!(pts.xy[1].y, 4_000_000);
+        }
+
+        #[test]
+        fn test_error_breadcrumb() {
+            let err = Points::try_from(&sexp!((pts (xy 1.0 2.0) (xy 3.0 "x")))).unwrap_err();
+            assert_eq!(err.breadcrumb(), vec!["pts", "xy"]);
+            assert!(matches!(err.root_cause(), kanga_sexpr::ParseError::ExpectedFloat(_)));
+
+            // Elements left over after the declared ones are rejected.
+            let err = Points::try_from(&sexp!((pts (xy 1.0 2.0) (xy 3.0 4.0 5.0)))).unwrap_err();
+            assert_eq!(err.breadcrumb(), vec!["pts", "xy"]);
+            assert!(matches!(err.root_cause(), kanga_sexpr::ParseError::Unexpected(_)));
+        }
+
+        #[test]
+        fn test_walk_nodes() {
+            let mut font = Font::try_from(&sexp!((font (size 1.27 1.27) bold))).unwrap();
+
+            let mut names = Vec::new();
+            kanga_sexpr::walk_nodes(&font, |node| {
+                names.push(node.sexpr_name());
+                true
+            });
+            assert_eq!(names, vec!["font", "size"]);
+
+            kanga_sexpr::walk_nodes_mut(&mut font, |node| {
+                if let Some(size) = node.as_any_mut().downcast_mut::<FontSize>() {
+                    size.width = 1_000_000;
+                }
+                true
+            });
+            assert_eq!(font.size.width, 1_000_000);
+
+            let pts = Points::try_from(&sexp!((pts (xy 1.0 2.0) (xy 3.0 4.0)))).unwrap();
+            assert_eq!(kanga_sexpr::Node::children(&pts).len(), 2);
+        }
+
+        #[test]
+        fn test_enum_payload() {
+            use kanga_sexpr::{Node, ToSexpr};
+
+            sexpr! {
+                #[derive(Debug)]
+                pub enum Outline {
+                    pts => Path(Points)
+                    color => Colored(Color)
+                    none => Empty
+                }
+            }
+
+            let pts = sexp!((pts (xy 1.0 2.0) (xy 3.0 4.0)));
+            let outline = Outline::try_from(&pts).unwrap();
+            assert!(matches!(&outline, Outline::Path(path) if path.xy.len() == 2));
+            assert_eq!(outline.to_value(), pts);
+            assert_eq!(outline.sexpr_name(), "pts");
+            assert_eq!(outline.children().len(), 2);
+            assert!(outline.as_any().is::<Points>());
+
+            assert!(matches!(Outline::try_from(&sexp!(none)).unwrap(), Outline::Empty));
+            assert_eq!(Outline::Empty.to_string(), "none");
+
+            let err = Outline::try_from(&sexp!((xy 1.0 2.0))).unwrap_err();
+            assert!(matches!(err, kanga_sexpr::ParseError::ExpectedEnumSymbol(_, names) if names.len() == 3));
+        }
+
+        #[test]
+        fn test_to_sexpr() {
+            use kanga_sexpr::ToSexpr;
+
+            let justify = sexp!((justify left mirror));
+            assert_eq!(TextJustify::try_from(&justify).unwrap().to_value(), justify);
+
+            let pts = sexp!((pts (xy 1.0 2.0) (xy 3.0 4.0)));
+            assert_eq!(Points::try_from(&pts).unwrap().to_value(), pts);
+
+            let color = Color::try_from(&sexp!((color 0.1 0.2 0.3 0.4))).unwrap();
+            assert_eq!(color.to_string(), "(color 0.1 0.2 0.3 0.4)");
+            assert_eq!(HJustify::Right.to_string(), "right");
+        }
+
+}
@@ -1,4 +1,5 @@
 use {
+    crate::gen_error_type,
     proc_macro2::TokenStream,
     quote::{quote, ToTokens},
     std::{
@@ -7,9 +8,9 @@ use {
         ops::{Deref, DerefMut},
     },
     syn::{
-        braced,
+        braced, parenthesized,
         parse::{Parse, ParseStream, Result as ParseResult},
-        Attribute, Ident, Token, Visibility,
+        Attribute, Ident, Path, Token, Type, Visibility,
     },
 };
 
@@ -20,14 +21,22 @@ pub(crate) struct EnumDecl {
     vis: Visibility,
     rust_name: Ident,
     variants: VariantVec,
+
+    /// The error type given with `#[sexpr(error = Type)]`, if not `kanga_sexpr::ParseError`.
+    error: Option<Path>,
 }
 
 /// A variant within an `enum` declaration.
+///
+/// A unit variant is written as a bare symbol. A variant with a payload, declared as
+/// `arc => Arc(SymbolGraphicArc)`, is written as a list with the symbol as its head, which is
+/// parsed as the payload type.
 #[derive(Debug)]
 struct Variant {
     meta: Vec<Attribute>,
     sexpr_name: Ident,
     rust_name: Ident,
+    payload: Option<Type>,
 }
 
 /// A `Vec<Variant>` that can be parsed.
@@ -40,6 +49,8 @@ impl EnumDecl {
         let mut result = TokenStream::new();
         result.extend(self.gen_enum_decl());
         result.extend(self.gen_parse_impl());
+        result.extend(self.gen_node_impl());
+        result.extend(self.gen_to_sexpr_impl());
         result
     }
 
@@ -62,11 +73,15 @@ impl EnumDecl {
     }
 
     /// Generate the parse implementation for the enum.
+    ///
+    /// Unit variants are matched against a symbol. If any variant has a payload, lists are also
+    /// accepted and dispatched on their head to the payload type's parser.
     fn gen_parse_impl(&self) -> TokenStream {
-        let mut result = TokenStream::new();
         let rust_name = &self.rust_name;
+        let error = gen_error_type(self.error.as_ref());
         let mut enum_expected = TokenStream::new(); // The expected symbols for the enum.
         let mut match_arms = TokenStream::new(); // Handlers for the `match sym` statement.
+        let mut head_arms = TokenStream::new(); // Handlers for the `match head` statement.
 
         for variant in &self.variants {
             // Add this variant's sexpr name to the array of expected symbols for the enum.
@@ -75,31 +90,163 @@ impl EnumDecl {
             enum_expected.extend(quote! { #sexpr_name, });
 
             // Add a match arm for this variant.
-            match_arms.extend(quote! {
-                #sexpr_name => Ok(Self::#rust_name),
-            })
+            match &variant.payload {
+                None => match_arms.extend(quote! {
+                    #sexpr_name => Ok(Self::#rust_name),
+                }),
+                Some(ty) => head_arms.extend(quote! {
+                    #sexpr_name => <#ty>::try_from(value).map(Self::#rust_name).map_err(Into::into),
+                }),
+            }
         }
 
+        let list_parser = if head_arms.is_empty() {
+            TokenStream::new()
+        } else {
+            quote! {
+                if let Some(head) = value.as_cons().and_then(|λ| λ.car().as_symbol()) {
+                    return match head {
+                        #head_arms
+                        _ => Err(::kanga_sexpr::ParseError::ExpectedEnumSymbol(value.clone(), EXPECTED).into()),
+                    };
+                }
+            }
+        };
+
         quote! {
-            impl ::std::convert::TryFrom<&::lexpr::Value> for #rust_name {
-                type Error = ::kanga_sexpr::ParseError;
+            impl ::core::convert::TryFrom<&::lexpr::Value> for #rust_name {
+                type Error = #error;
 
-                fn try_from(value: &::lexpr::Value) -> ::std::result::Result<Self, Self::Error> {
+                fn try_from(value: &::lexpr::Value) -> ::core::result::Result<Self, Self::Error> {
                     const EXPECTED: &'static [&'static str] = &[#enum_expected];
 
+                    #list_parser
+
                     let Some(sym) = value.as_symbol() else {
-                        return Err(::kanga_sexpr::ParseError::ExpectedEnumSymbol(value.clone(), EXPECTED));
+                        return Err(::kanga_sexpr::ParseError::ExpectedEnumSymbol(value.clone(), EXPECTED).into());
                     };
 
                     match sym {
                         #match_arms
-                        _ => Err(::kanga_sexpr::ParseError::ExpectedEnumSymbol(value.clone(), EXPECTED)),
+                        _ => Err(::kanga_sexpr::ParseError::ExpectedEnumSymbol(value.clone(), EXPECTED).into()),
                     }
                 }
             }
         }
     }
 
+    /// Generate the `Node` implementation for the enum. Unit variants are leaves named by their
+    /// symbol; variants with a payload defer to the payload, so that walks see it in their place.
+    fn gen_node_impl(&self) -> TokenStream {
+        let rust_name = &self.rust_name;
+        let mut name_arms = TokenStream::new();
+        let mut children_arms = TokenStream::new();
+        let mut children_mut_arms = TokenStream::new();
+        let mut any_arms = TokenStream::new();
+        let mut any_mut_arms = TokenStream::new();
+
+        for variant in &self.variants {
+            let sexpr_name = variant.sexpr_name.to_string();
+            let rust_name = &variant.rust_name;
+            name_arms.extend(quote! {
+                Self::#rust_name { .. } => #sexpr_name,
+            });
+
+            if variant.payload.is_some() {
+                children_arms.extend(quote! {
+                    Self::#rust_name(φ) => ::kanga_sexpr::Node::children(φ),
+                });
+                children_mut_arms.extend(quote! {
+                    Self::#rust_name(φ) => ::kanga_sexpr::Node::children_mut(φ),
+                });
+                any_arms.extend(quote! {
+                    Self::#rust_name(φ) => ::kanga_sexpr::Node::as_any(φ),
+                });
+                any_mut_arms.extend(quote! {
+                    Self::#rust_name(φ) => ::kanga_sexpr::Node::as_any_mut(φ),
+                });
+            }
+        }
+
+        // Unit variants have no children and are their own `Any`.
+        if self.variants.iter().any(|variant| variant.payload.is_none()) {
+            children_arms.extend(quote! { _ => ::kanga_sexpr::__private::Vec::new(), });
+            children_mut_arms.extend(quote! { _ => ::kanga_sexpr::__private::Vec::new(), });
+            any_arms.extend(quote! { _ => self, });
+            any_mut_arms.extend(quote! { _ => self, });
+        }
+
+        quote! {
+            impl ::kanga_sexpr::Node for #rust_name {
+                fn sexpr_name(&self) -> &'static str {
+                    match self {
+                        #name_arms
+                    }
+                }
+
+                fn children(&self) -> ::kanga_sexpr::__private::Vec<&dyn ::kanga_sexpr::Node> {
+                    match self {
+                        #children_arms
+                    }
+                }
+
+                fn children_mut(&mut self) -> ::kanga_sexpr::__private::Vec<&mut dyn ::kanga_sexpr::Node> {
+                    match self {
+                        #children_mut_arms
+                    }
+                }
+
+                fn as_any(&self) -> &dyn ::core::any::Any {
+                    match self {
+                        #any_arms
+                    }
+                }
+
+                fn as_any_mut(&mut self) -> &mut dyn ::core::any::Any {
+                    match self {
+                        #any_mut_arms
+                    }
+                }
+            }
+        }
+    }
+
+    /// Generate the `ToSexpr` and `Display` implementations for the enum, which write the symbol of
+    /// a unit variant or the payload of any other.
+    fn gen_to_sexpr_impl(&self) -> TokenStream {
+        let rust_name = &self.rust_name;
+        let mut match_arms = TokenStream::new();
+
+        for variant in &self.variants {
+            let sexpr_name = variant.sexpr_name.to_string();
+            let rust_name = &variant.rust_name;
+            match_arms.extend(match &variant.payload {
+                None => quote! {
+                    Self::#rust_name => ::lexpr::Value::symbol(#sexpr_name),
+                },
+                Some(_) => quote! {
+                    Self::#rust_name(φ) => ::kanga_sexpr::ToSexpr::to_value(φ),
+                },
+            });
+        }
+
+        quote! {
+            impl ::kanga_sexpr::ToSexpr for #rust_name {
+                fn to_value(&self) -> ::lexpr::Value {
+                    match self {
+                        #match_arms
+                    }
+                }
+            }
+
+            impl ::core::fmt::Display for #rust_name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    ::core::fmt::Display::fmt(&::kanga_sexpr::ToSexpr::to_value(self), f)
+                }
+            }
+        }
+    }
+
     /// Parse a struct declaration when the attributes and visibility have already been parsed.
     pub(crate) fn parse_with_attr_vis(input: ParseStream, meta: Vec<Attribute>, vis: Visibility) -> ParseResult<Self> {
         let _: Token![enum] = input.parse()?;
@@ -109,11 +256,30 @@ impl EnumDecl {
         braced!(content in input);
         let variants = content.parse()?;
 
+        // Options for this macro are given in `#[sexpr(...)]` and are not passed on to the enum.
+        let mut error = None;
+        let mut other_meta = Vec::with_capacity(meta.len());
+        for attr in meta {
+            if attr.path().is_ident("sexpr") {
+                attr.parse_nested_meta(|option| {
+                    if option.path.is_ident("error") {
+                        error = Some(option.value()?.parse()?);
+                        Ok(())
+                    } else {
+                        Err(option.error("Unknown sexpr option"))
+                    }
+                })?;
+            } else {
+                other_meta.push(attr);
+            }
+        }
+
         Ok(Self {
-            meta,
+            meta: other_meta,
             vis,
             rust_name,
             variants,
+            error,
         })
     }
 }
@@ -134,8 +300,9 @@ impl Variant {
         }
 
         let rust_name = &self.rust_name;
-        result.extend(quote! {
-            #rust_name,
+        result.extend(match &self.payload {
+            None => quote! { #rust_name, },
+            Some(ty) => quote! { #rust_name(#ty), },
         });
 
         result
@@ -145,10 +312,16 @@ impl Variant {
 impl Display for Variant {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         if self.rust_name == self.sexpr_name {
-            write!(f, "{}", self.rust_name)
+            write!(f, "{}", self.rust_name)?;
         } else {
-            write!(f, "{} => {}", self.sexpr_name, self.rust_name)
+            write!(f, "{} => {}", self.sexpr_name, self.rust_name)?;
+        }
+
+        if let Some(ty) = &self.payload {
+            write!(f, "({})", ty.to_token_stream())?;
         }
+
+        Ok(())
     }
 }
 
@@ -164,6 +337,14 @@ impl Parse for Variant {
             (name.clone(), name)
         };
 
+        let payload = if input.peek(syn::token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            Some(content.parse()?)
+        } else {
+            None
+        };
+
         if input.peek(Token![,]) {
             input.parse::<Token![,]>()?;
         }
@@ -172,6 +353,7 @@ impl Parse for Variant {
             meta,
             sexpr_name,
             rust_name,
+            payload,
         })
     }
 }
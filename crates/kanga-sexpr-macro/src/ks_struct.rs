@@ -4,6 +4,7 @@ mod shape;
 use self::{field::*, shape::*};
 
 use {
+    crate::gen_error_type,
     proc_macro2::TokenStream,
     quote::{quote, ToTokens},
     std::{
@@ -11,9 +12,11 @@ use {
         ops::{Deref, DerefMut},
     },
     syn::{
-        braced, parenthesized,
+        braced,
+        ext::IdentExt,
+        parenthesized,
         parse::{Parse, ParseBuffer, ParseStream, Result as ParseResult},
-        Attribute, Ident, Token, Visibility,
+        parse_quote, Attribute, Ident, Path, Token, Visibility,
     },
 };
 
@@ -24,6 +27,18 @@ pub(crate) struct StructDecl {
     rust_name: Ident,
     sexpr_name: Ident,
     fields: FieldVec,
+
+    /// Whether `#[sexpr(unordered)]` was given: the keyed fields may appear in any order.
+    unordered: bool,
+
+    /// The error type given with `#[sexpr(error = Type)]`, if not `kanga_sexpr::ParseError`.
+    error: Option<Path>,
+
+    /// The function given with `#[sexpr(check = path)]` that checks the parsed struct.
+    check: Option<Path>,
+
+    /// The structs declared inline within the fields, as in `(size: FontSize x: f64 y: f64)`.
+    nested: Vec<StructDecl>,
 }
 
 /// Types of modifiers that can be applied to a field.
@@ -41,6 +56,13 @@ impl StructDecl {
         let mut result = TokenStream::new();
         result.extend(self.gen_struct_decl());
         result.extend(self.gen_parse_impl());
+        result.extend(self.gen_node_impl());
+        result.extend(self.gen_to_sexpr_impl());
+
+        for nested in &self.nested {
+            result.extend(nested.gen());
+        }
+
         result
     }
 
@@ -75,8 +97,9 @@ impl StructDecl {
 
     /// Generate the parse implementation for the struct.
     fn gen_parse_impl(&self) -> TokenStream {
-        let mut result = TokenStream::new();
         let rust_name = &self.rust_name;
+        let sexpr_name = self.sexpr_name.unraw().to_string();
+        let error = gen_error_type(self.error.as_ref());
 
         let mut field_parsers = TokenStream::new();
         let mut field_var_decls = TokenStream::new();
@@ -88,20 +111,218 @@ impl StructDecl {
             struct_field_setters.extend(field.gen_struct_field_setters());
         }
 
+        // Unordered structs sort their elements into declaration order first, then parse them in
+        // sequence like any other struct.
+        let (reorder_fn, parse_call) = if self.unordered {
+            (self.gen_reorder_fn(), quote! { reorder_fields(λ.cdr()).and_then(|λr| parse_fields(&λr)) })
+        } else {
+            (quote! {}, quote! { parse_fields(λ.cdr()) })
+        };
+
+        let check = match &self.check {
+            Some(check) => quote! {
+                .and_then(|φ| -> ::core::result::Result<#rust_name, #error> {
+                    #check(&φ)?;
+                    Ok(φ)
+                })
+            },
+            None => quote! {},
+        };
+
         // We use Greek letters to avoid conflicts with field names.
         // λv = the remaining cons expression as a value
         // λ = the remaining cons expression
+        // λr = the elements sorted into declaration order
         // α = the car of the cons expression, our current element
+        // β = the cons cell of α when it is itself a list
+        // ψ = the cons cell following the head of β
         // φ = the value being extracted from the car
+        // ε = an error from a speculative parse
+
+        quote! {
+            impl ::core::convert::TryFrom<&::lexpr::Value> for #rust_name {
+                type Error = #error;
+
+                fn try_from(λv: &::lexpr::Value) -> ::core::result::Result<Self, Self::Error> {
+                    // Parse the fields following the head; errors are tagged with this element's
+                    // name so nested failures carry a breadcrumb path. Anything left over once
+                    // the fields are parsed is not part of the declaration.
+                    fn parse_fields(
+                        mut λv: &::lexpr::Value,
+                    ) -> ::core::result::Result<#rust_name, #error> {
+                        #field_var_decls
+                        #field_parsers
+
+                        if let Some(λ) = λv.as_cons() {
+                            return Err(::kanga_sexpr::ParseError::Unexpected(λ.car().clone()).into());
+                        }
+
+                        Ok(#rust_name { #struct_field_setters })
+                    }
+
+                    #reorder_fn
+
+                    let Some(λ) = λv.as_cons() else {
+                        return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()).into());
+                    };
+
+                    if λ.car().as_symbol() != Some(#sexpr_name) {
+                        return Err(::kanga_sexpr::ParseError::ExpectedNamedSym(λv.clone(), ::kanga_sexpr::__private::String::from(#sexpr_name)).into());
+                    }
+
+                    #parse_call #check .map_err(|ε| ::kanga_sexpr::SexprError::within_list(ε, #sexpr_name, λ))
+                }
+            }
+        }
+    }
+
+    /// Generate the `reorder_fields` function for an unordered struct, which returns the elements
+    /// following the head with the positional ones first, in their original order, followed by the
+    /// keyed ones in the order their fields are declared.
+    ///
+    /// Elements are dispatched by their head symbol, as the hand-written parsers do. A keyed field
+    /// that is not a vector may appear only once.
+    fn gen_reorder_fn(&self) -> TokenStream {
+        let struct_name = self.rust_name.to_string();
+        let error = gen_error_type(self.error.as_ref());
+        let mut arms = TokenStream::new();
+        let mut keys = TokenStream::new();
+
+        // Slot 0 holds the positional elements, and each keyed field gets the next slot.
+        let mut slot = 0usize;
+        keys.extend(quote! { ("", true), });
+
+        for field in &self.fields {
+            let Some(key) = field.key() else {
+                continue;
+            };
+
+            slot += 1;
+            let repeated = field.is_repeated();
+            arms.extend(match &key {
+                Key::List(head) => quote! { (Some(#head), _) => #slot, },
+                Key::Symbol(name) => quote! { (Some(#name), _) | (_, Some(#name)) => #slot, },
+            });
+
+            let (Key::List(name) | Key::Symbol(name)) = key;
+            keys.extend(quote! { (#name, #repeated), });
+        }
+
+        let slots = slot + 1;
+
+        // λs = the elements in each slot
+        // λk = the name of each slot, and whether it may hold more than one element
+        // λn = the slot of the current element
 
         quote! {
-            impl ::std::convert::TryFrom<&::lexpr::Value> for #rust_name {
-                type Error = ::kanga_sexpr::ParseError;
+            fn reorder_fields(
+                mut λv: &::lexpr::Value,
+            ) -> ::core::result::Result<::lexpr::Value, #error> {
+                let λk: [(&str, bool); #slots] = [#keys];
+                let mut λs: [::kanga_sexpr::__private::Vec<::lexpr::Value>; #slots] =
+                    ::core::array::from_fn(|_| ::kanga_sexpr::__private::Vec::new());
+
+                while let Some(λ) = λv.as_cons() {
+                    let α = λ.car();
+                    let λn = match (α.as_cons().and_then(|β| β.car().as_symbol()), α.as_symbol()) {
+                        #arms
+                        _ => 0,
+                    };
 
-                fn try_from(mut λv: &::lexpr::Value) -> ::std::result::Result<Self, Self::Error> {
-                    #field_var_decls
-                    #field_parsers
-                    Ok(Self { #struct_field_setters })
+                    if !λk[λn].1 && !λs[λn].is_empty() {
+                        return Err(::kanga_sexpr::ParseError::DuplicateField(
+                            ::kanga_sexpr::__private::String::from(#struct_name),
+                            ::kanga_sexpr::__private::String::from(λk[λn].0),
+                            α.clone(),
+                        ).into());
+                    }
+
+                    λs[λn].push(α.clone());
+                    λv = λ.cdr();
+                }
+
+                Ok(::lexpr::Value::list(λs.into_iter().flatten()))
+            }
+        }
+    }
+
+    /// Generate the `Node` implementation for the struct, which lists the fields that are themselves
+    /// nodes so the struct participates in generic traversal.
+    fn gen_node_impl(&self) -> TokenStream {
+        let rust_name = &self.rust_name;
+        let sexpr_name = self.sexpr_name.unraw().to_string();
+
+        let mut children = TokenStream::new();
+        let mut children_mut = TokenStream::new();
+
+        for field in &self.fields {
+            children.extend(field.gen_children(false));
+            children_mut.extend(field.gen_children(true));
+        }
+
+        // λc = the list of children being collected
+        // φ = an element of an optional or vector field
+
+        quote! {
+            impl ::kanga_sexpr::Node for #rust_name {
+                fn sexpr_name(&self) -> &'static str {
+                    #sexpr_name
+                }
+
+                fn children(&self) -> ::kanga_sexpr::__private::Vec<&dyn ::kanga_sexpr::Node> {
+                    #[allow(unused_mut)]
+                    let mut λc: ::kanga_sexpr::__private::Vec<&dyn ::kanga_sexpr::Node> =
+                        ::kanga_sexpr::__private::Vec::new();
+                    #children
+                    λc
+                }
+
+                fn children_mut(&mut self) -> ::kanga_sexpr::__private::Vec<&mut dyn ::kanga_sexpr::Node> {
+                    #[allow(unused_mut)]
+                    let mut λc: ::kanga_sexpr::__private::Vec<&mut dyn ::kanga_sexpr::Node> =
+                        ::kanga_sexpr::__private::Vec::new();
+                    #children_mut
+                    λc
+                }
+
+                fn as_any(&self) -> &dyn ::core::any::Any {
+                    self
+                }
+
+                fn as_any_mut(&mut self) -> &mut dyn ::core::any::Any {
+                    self
+                }
+            }
+        }
+    }
+
+    /// Generate the `ToSexpr` and `Display` implementations for the struct, which write the fields
+    /// back in the shape they were declared with.
+    fn gen_to_sexpr_impl(&self) -> TokenStream {
+        let rust_name = &self.rust_name;
+        let sexpr_name = self.sexpr_name.unraw().to_string();
+
+        let mut field_writers = TokenStream::new();
+        for field in &self.fields {
+            field_writers.extend(field.gen_writer());
+        }
+
+        // λw = the list of values being written
+        // φ = the field value being written
+
+        quote! {
+            impl ::kanga_sexpr::ToSexpr for #rust_name {
+                fn to_value(&self) -> ::lexpr::Value {
+                    let mut λw = ::kanga_sexpr::__private::Vec::new();
+                    λw.push(::lexpr::Value::symbol(#sexpr_name));
+                    #field_writers
+                    ::lexpr::Value::list(λw)
+                }
+            }
+
+            impl ::core::fmt::Display for #rust_name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    ::core::fmt::Display::fmt(&::kanga_sexpr::ToSexpr::to_value(self), f)
                 }
             }
         }
@@ -119,16 +340,99 @@ impl StructDecl {
         parenthesized!(struct_outer in &content);
 
         let sexpr_name: Ident = struct_outer.parse()?;
-        let fields: FieldVec = struct_outer.parse()?;
+        let mut fields: FieldVec = struct_outer.parse()?;
+
+        // Options for this macro are given in `#[sexpr(...)]` and are not passed on to the struct.
+        let mut unordered = false;
+        let mut error = None;
+        let mut check = None;
+        let mut other_meta = Vec::with_capacity(meta.len());
+        for attr in meta {
+            if attr.path().is_ident("sexpr") {
+                attr.parse_nested_meta(|option| {
+                    if option.path.is_ident("unordered") {
+                        unordered = true;
+                    } else if option.path.is_ident("error") {
+                        error = Some(option.value()?.parse()?);
+                    } else if option.path.is_ident("check") {
+                        check = Some(option.value()?.parse()?);
+                    } else {
+                        return Err(option.error("Unknown sexpr option"));
+                    }
+
+                    Ok(())
+                })?;
+            } else {
+                other_meta.push(attr);
+            }
+        }
+
+        // Positional fields are matched by position alone, so they cannot follow fields that may
+        // move around.
+        if unordered {
+            let first_keyed = fields.iter().position(|field| field.key().is_some()).unwrap_or(fields.len());
+            if let Some(field) = fields[first_keyed..].iter().find(|field| field.key().is_none()) {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "Positional fields must precede keyed fields in an unordered struct",
+                ));
+            }
+        }
+
+        let nested = Self::take_nested(&mut fields, &rust_name, &other_meta, &vis, error.as_ref());
 
         Ok(Self {
-            meta,
+            meta: other_meta,
             vis,
             rust_name,
             sexpr_name,
             fields,
+            unordered,
+            error,
+            check,
+            nested,
         })
     }
+
+    /// Take the structs declared inline out of `fields`, recursively, and declare each one with
+    /// the visibility, attributes other than documentation, and error type of the struct it is
+    /// declared in.
+    fn take_nested(
+        fields: &mut FieldVec,
+        parent: &Ident,
+        meta: &[Attribute],
+        vis: &Visibility,
+        error: Option<&Path>,
+    ) -> Vec<Self> {
+        let mut inline = Vec::new();
+        for field in fields.iter_mut() {
+            field.take_inline(&mut inline);
+        }
+
+        let meta: Vec<Attribute> = meta.iter().filter(|attr| !attr.path().is_ident("doc")).cloned().collect();
+
+        inline
+            .into_iter()
+            .map(|(sexpr_name, rust_name, mut fields)| {
+                let doc = format!("The `({sexpr_name} ...)` list of a [`{parent}`].");
+                let nested = Self::take_nested(&mut fields, &rust_name, &meta, vis, error);
+                let mut nested_meta = vec![parse_quote! { #[doc = #doc] }];
+                nested_meta.extend(meta.iter().cloned());
+
+                Self {
+                    meta: nested_meta,
+                    vis: vis.clone(),
+                    rust_name,
+                    sexpr_name,
+                    fields,
+                    unordered: false,
+                    error: error.cloned(),
+                    check: None,
+                    nested,
+                }
+            })
+            .collect()
+    }
 }
 
 impl Parse for StructDecl {
@@ -147,4 +451,34 @@ mod tests {
     fn test_basic_struct_parse() {
         let s: StructDecl = parse2(quote! { struct Foo { (foo x:i64) } }).unwrap();
     }
+
+    #[test]
+    fn test_unordered_struct_parse() {
+        let s: StructDecl =
+            parse2(quote! { #[sexpr(unordered)] #[derive(Debug)] struct Foo { (foo x:i64 (y:f64)) } }).unwrap();
+        assert!(s.unordered);
+        assert_eq!(s.meta.len(), 1);
+
+        let err = parse2::<StructDecl>(quote! { #[sexpr(unordered)] struct Foo { (foo (y:f64) x:i64) } });
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_inline_struct_parse() {
+        let s: StructDecl = parse2(quote! {
+            /// A font.
+            #[derive(Debug)]
+            pub struct Font { (font (size: FontSize (inner: Inner x: f64) y: f64) [bold]) }
+        })
+        .unwrap();
+        assert_eq!(s.nested.len(), 1);
+
+        let size = &s.nested[0];
+        assert_eq!((size.rust_name.to_string(), size.sexpr_name.to_string()), ("FontSize".into(), "size".into()));
+        assert_eq!(size.fields.len(), 2);
+        assert_eq!(size.meta.len(), 2);
+        assert!(size.meta[0].path().is_ident("doc") && size.meta[1].path().is_ident("derive"));
+        assert_eq!(size.nested[0].rust_name, "Inner");
+        assert_eq!(s.fields[0].to_string(), "(size: FontSize)");
+    }
 }
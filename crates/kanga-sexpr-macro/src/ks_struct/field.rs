@@ -1,6 +1,6 @@
 use {
-    super::{FieldMod, Shape},
-    proc_macro2::TokenStream,
+    super::{FieldMod, Key, Shape},
+    proc_macro2::{Span, TokenStream},
     quote::{quote, ToTokens},
     std::{
         fmt::{Display, Formatter, Result as FmtResult},
@@ -14,6 +14,7 @@ use {
 };
 
 /// A field within a `struct` declaration.
+#[derive(Clone, Debug)]
 pub(super) struct Field {
     meta: Vec<Attribute>,
     shape: Shape,
@@ -22,6 +23,7 @@ pub(super) struct Field {
 /// A `Vec<[Field]>` that can be parsed.
 ///
 /// This expects a list of fields within braces (`{}`) that denote the interior of a struct.
+#[derive(Clone, Debug)]
 pub(super) struct FieldVec(Vec<Field>);
 
 impl Field {
@@ -49,6 +51,37 @@ impl Field {
         self.shape.gen_struct_field_setters(FieldMod::None)
     }
 
+    /// Generate statements that push the s-expressions for this field onto the list `λw`.
+    pub(super) fn gen_writer(&self) -> TokenStream {
+        self.shape.gen_writer(FieldMod::None)
+    }
+
+    /// Generate statements that push this field onto the children list `λc` if it is a node.
+    pub(super) fn gen_children(&self, mutable: bool) -> TokenStream {
+        self.shape.gen_children(FieldMod::None, mutable)
+    }
+
+    /// Return how the element for this field is recognized when fields may appear in any order,
+    /// or `None` if the field is positional.
+    pub(super) fn key(&self) -> Option<Key> {
+        self.shape.key()
+    }
+
+    /// Return the span of the start of this field, for reporting errors.
+    pub(super) fn span(&self) -> Span {
+        self.shape.span()
+    }
+
+    /// Take out the structs declared inline within this field. See [`Shape::take_inline`].
+    pub(super) fn take_inline(&mut self, inline: &mut Vec<(Ident, Ident, FieldVec)>) {
+        self.shape.take_inline(inline);
+    }
+
+    /// Indicates whether the field may appear more than once.
+    pub(super) fn is_repeated(&self) -> bool {
+        self.shape.is_repeated()
+    }
+
     /// Return the field names used for the s-expression representing this
     /// field in the struct.
     ///
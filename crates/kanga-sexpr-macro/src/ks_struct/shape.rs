@@ -1,20 +1,23 @@
 use {
-    super::FieldMod,
+    super::{FieldMod, FieldVec},
     crate::{TypeCat, TypeExt},
-    proc_macro2::TokenStream,
-    quote::{quote, ToTokens},
+    proc_macro2::{Span, TokenStream},
+    quote::{quote, quote_spanned, ToTokens},
     std::fmt::{Display, Formatter, Result as FmtResult},
     syn::{
-        bracketed, parenthesized,
+        bracketed,
+        ext::IdentExt,
+        parenthesized,
         parse::{discouraged::Speculative, Parse, ParseStream, Result as ParseResult},
         parse2,
+        spanned::Spanned,
         token::{Bracket, Paren},
-        Attribute, Ident, Token, Type, Visibility,
+        parse_quote_spanned, Attribute, Expr, Ident, Path, Token, Type, TypePath, Visibility,
     },
 };
 
 /// The shape of an s-expression for a struct field.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub(super) enum Shape {
     /// List of items with a symbol head whose contents are destructured into struct fields.
     DesList(DesList),
@@ -36,7 +39,7 @@ pub(super) enum Shape {
 }
 
 /// List of items with a symbol head whose contents are destructured into struct fields.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub(super) struct DesList {
     /// The symbolic head of the list.
     pub(super) sexpr_head: Ident,
@@ -46,7 +49,7 @@ pub(super) struct DesList {
 }
 
 /// List of items with a symbol head represented by a type.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub(super) struct TypedList {
     /// The symbolic head of the list
     pub(super) sexpr_head: Ident,
@@ -56,23 +59,50 @@ pub(super) struct TypedList {
 
     /// The type of the list.
     pub(super) ty: Type,
+
+    /// The unit policy converting the number in the list, given with `as unit`.
+    pub(super) unit: Option<Type>,
+
+    /// The fields of a struct declared inline for the list, as in `(size: FontSize x: f64 y: f64)`.
+    /// These are taken out when the enclosing struct is parsed and declared as a struct of their
+    /// own, named by the type.
+    pub(super) inline: Option<FieldVec>,
+
+    /// The value used when an optional list is absent, given with `= expr`.
+    pub(super) default: Option<Expr>,
 }
 
 /// A symbol without a type in an s-expression that might have a different name in Rust.
 ///
 /// The s-expression syntax is `[symbol => rust_name]` or `[symbol]` if the Rust name is the same.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub(super) struct SymbolFlag {
     pub(super) rust_name: Ident,
     pub(super) sexpr_name: Ident,
 }
 
 /// A symbol and type in an s-expression that might have a different name in Rust.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub(super) struct TypedSymbol {
     pub(super) rust_name: Ident,
     pub(super) sexpr_name: Ident,
     pub(super) ty: Type,
+
+    /// The unit policy converting the number, given with `as unit`.
+    pub(super) unit: Option<Type>,
+
+    /// The value used when an optional symbol is absent, given with `= expr`.
+    pub(super) default: Option<Expr>,
+}
+
+/// How the element for a field is recognized when fields may appear in any order.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) enum Key {
+    /// A list with this symbol as its head.
+    List(String),
+
+    /// This bare symbol.
+    Symbol(String),
 }
 
 impl Shape {
@@ -82,8 +112,8 @@ impl Shape {
             Shape::DesList(ls) => ls.gen_decl(meta, vis, m),
             Shape::TypedList(ls) => ls.gen_decl(meta, vis, m),
             Shape::Option(inner) => {
-                assert!(m == FieldMod::None, "Cannot apply field mod {m:?} to optional shape");
-                inner.gen_decl(meta, vis, FieldMod::Optional)
+                assert!(m != FieldMod::Vectored, "Cannot apply field mod {m:?} to optional shape");
+                inner.gen_decl(meta, vis, inner.option_mod())
             }
             Shape::SymbolFlag(sym) => sym.gen_decl(meta, vis, m),
             Shape::TypedSymbol(sym) => {
@@ -106,8 +136,11 @@ impl Shape {
             Self::DesList(dl) => dl.gen_parser(m),
             Self::TypedList(tl) => tl.gen_parser(m),
             Self::Option(inner) => {
-                assert!(m == FieldMod::None, "Cannot apply field mod {m:?} to optional shape");
-                inner.gen_parser(FieldMod::Optional)
+                assert!(m != FieldMod::Vectored, "Cannot apply field mod {m:?} to optional shape");
+                match inner.default_value() {
+                    Some(default) => inner.gen_defaulted_parser(default),
+                    None => inner.gen_parser(FieldMod::Optional),
+                }
             }
             Self::SymbolFlag(sym) => sym.gen_parser(m),
             Self::TypedSymbol(sym) => sym.gen_parser(m),
@@ -124,8 +157,8 @@ impl Shape {
             Self::DesList(dl) => dl.gen_parser_var_decls(m),
             Self::TypedList(tl) => tl.gen_parser_var_decls(m),
             Self::Option(inner) => {
-                assert!(m == FieldMod::None, "Cannot apply field mod {m:?} to optional shape");
-                inner.gen_parser_var_decls(FieldMod::Optional)
+                assert!(m != FieldMod::Vectored, "Cannot apply field mod {m:?} to optional shape");
+                inner.gen_parser_var_decls(inner.option_mod())
             }
             Self::SymbolFlag(sym) => sym.gen_parser_var_decls(m),
             Self::TypedSymbol(sym) => sym.gen_parser_var_decls(m),
@@ -142,8 +175,8 @@ impl Shape {
             Self::DesList(dl) => dl.gen_struct_field_setters(m),
             Self::TypedList(tl) => tl.gen_struct_field_setters(m),
             Self::Option(inner) => {
-                assert!(m == FieldMod::None, "Cannot apply field mod {m:?} to optional shape");
-                inner.gen_struct_field_setters(FieldMod::Optional)
+                assert!(m != FieldMod::Vectored, "Cannot apply field mod {m:?} to optional shape");
+                inner.gen_struct_field_setters(inner.option_mod())
             }
             Self::SymbolFlag(sym) => sym.gen_struct_field_setters(m),
             Self::TypedSymbol(sym) => sym.gen_struct_field_setters(m),
@@ -154,6 +187,33 @@ impl Shape {
         }
     }
 
+    /// Generate statements that push the s-expressions for this shape onto `λw`, the list being
+    /// written.
+    pub(super) fn gen_writer(&self, m: FieldMod) -> TokenStream {
+        match self {
+            Self::DesList(dl) => dl.gen_writer(m),
+            Self::TypedList(tl) => tl.gen_writer(m),
+            Self::Option(inner) => inner.gen_writer(inner.option_mod()),
+            Self::SymbolFlag(sym) => sym.gen_writer(),
+            Self::TypedSymbol(sym) => sym.gen_writer(m),
+            Self::Vec(inner) => inner.gen_writer(FieldMod::Vectored),
+        }
+    }
+
+    /// Generate statements that push the fields of this shape that are themselves nodes onto `λc`.
+    ///
+    /// If `mutable` is set, the fields are borrowed mutably for `children_mut()`.
+    pub(super) fn gen_children(&self, m: FieldMod, mutable: bool) -> TokenStream {
+        match self {
+            Self::DesList(dl) => dl.gen_children(m, mutable),
+            Self::TypedList(tl) => gen_child_push(&tl.rust_name, &tl.ty, m, mutable),
+            Self::Option(inner) => inner.gen_children(inner.option_mod(), mutable),
+            Self::SymbolFlag(_) => quote! {},
+            Self::TypedSymbol(sym) => gen_child_push(&sym.rust_name, &sym.ty, m, mutable),
+            Self::Vec(inner) => inner.gen_children(FieldMod::Vectored, mutable),
+        }
+    }
+
     /// Return the field names used for the s-expression representing this shape.
     pub(super) fn field_names(&self) -> Vec<Ident> {
         match self {
@@ -166,6 +226,108 @@ impl Shape {
         }
     }
 
+    /// Return the span of the first token of this shape, for reporting errors.
+    pub(super) fn span(&self) -> Span {
+        match self {
+            Self::DesList(dl) => dl.sexpr_head.span(),
+            Self::TypedList(tl) => tl.sexpr_head.span(),
+            Self::Option(inner) | Self::Vec(inner) => inner.span(),
+            Self::SymbolFlag(sym) => sym.sexpr_name.span(),
+            Self::TypedSymbol(sym) => sym.sexpr_name.span(),
+        }
+    }
+
+    /// Return the default value of an optional typed symbol or list, if it has one.
+    fn default_value(&self) -> Option<&Expr> {
+        match self {
+            Self::TypedList(tl) => tl.default.as_ref(),
+            Self::TypedSymbol(sym) => sym.default.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Return the field mod for this shape within an option: none if it has a default value, since
+    /// the field then always holds a value, and optional otherwise.
+    fn option_mod(&self) -> FieldMod {
+        if self.default_value().is_some() {
+            FieldMod::None
+        } else {
+            FieldMod::Optional
+        }
+    }
+
+    /// Generate a parser for this shape within an option that assigns `default` when the element
+    /// is absent.
+    ///
+    /// The optional parser is generated into a temporary, `λo`, which is then unwrapped into the
+    /// field.
+    fn gen_defaulted_parser(&self, default: &Expr) -> TokenStream {
+        let temp = Ident::new("λo", Span::call_site());
+        let (rust_name, parser) = match self {
+            Self::TypedList(tl) => (
+                &tl.rust_name,
+                TypedList {
+                    rust_name: temp,
+                    ..tl.clone()
+                }
+                .gen_parser(FieldMod::Optional),
+            ),
+            Self::TypedSymbol(sym) => (
+                &sym.rust_name,
+                TypedSymbol {
+                    rust_name: temp,
+                    ..sym.clone()
+                }
+                .gen_parser(FieldMod::Optional),
+            ),
+            _ => panic!("Default values are only supported on typed symbols and lists"),
+        };
+
+        quote! {
+            {
+                let λo;
+                #parser
+                #rust_name = match λo {
+                    Some(φ) => φ,
+                    None => #default,
+                };
+            }
+        }
+    }
+
+    /// Return how the element for this shape is recognized out of order, or `None` if the shape is
+    /// positional.
+    pub(super) fn key(&self) -> Option<Key> {
+        match self {
+            Self::DesList(dl) => Some(Key::List(dl.sexpr_head.unraw().to_string())),
+            Self::TypedList(tl) => Some(Key::List(tl.sexpr_head.unraw().to_string())),
+            Self::Option(inner) | Self::Vec(inner) => inner.key(),
+            Self::SymbolFlag(sym) => Some(Key::Symbol(sym.sexpr_name.unraw().to_string())),
+            Self::TypedSymbol(_) => None,
+        }
+    }
+
+    /// Indicates whether the shape may appear more than once.
+    pub(super) fn is_repeated(&self) -> bool {
+        matches!(self, Self::Vec(_))
+    }
+
+    /// Take out the structs declared inline within this shape, returning the list head, the name of
+    /// the struct, and its fields for each one.
+    pub(super) fn take_inline(&mut self, inline: &mut Vec<(Ident, Ident, FieldVec)>) {
+        match self {
+            Self::DesList(dl) => dl.items.iter_mut().for_each(|item| item.take_inline(inline)),
+            Self::TypedList(tl) => {
+                if let Some(fields) = tl.inline.take() {
+                    let name = tl.ty.inline_name().expect("inline structs are named when parsing").clone();
+                    inline.push((tl.sexpr_head.clone(), name, fields));
+                }
+            }
+            Self::Option(inner) | Self::Vec(inner) => inner.take_inline(inline),
+            Self::SymbolFlag(_) | Self::TypedSymbol(_) => (),
+        }
+    }
+
     /// If the shape is a list, return the inner [`ListShape`].
     pub(super) fn as_list_shape(&self) -> Option<&DesList> {
         if let Shape::DesList(ls) = self {
@@ -202,6 +364,33 @@ impl Shape {
         }
     }
 
+    /// Parse a shape, including any following `*` indicating a vectored shape.
+    ///
+    /// A typed symbol or list may only have a default value if it is not vectored and
+    /// `allow_default` is set, as it is directly within the brackets of an optional shape. Only
+    /// typed lists may be vectored.
+    fn parse_with_default(input: ParseStream, allow_default: bool) -> ParseResult<Self> {
+        let inner = Self::parse_non_vec(input)?;
+        let vectored = input.peek(Token![*]);
+
+        if let Some(default) = inner.default_value() {
+            if vectored || !allow_default {
+                return Err(syn::Error::new_spanned(default, "Default values are only allowed on optional fields"));
+            }
+        }
+
+        if vectored {
+            let _: Token![*] = input.parse()?;
+            if !matches!(inner, Self::TypedList(_)) {
+                return Err(syn::Error::new(inner.span(), "Only typed lists, such as `(xy: XY)*`, can be repeated"));
+            }
+
+            Ok(Self::Vec(Box::new(inner)))
+        } else {
+            Ok(inner)
+        }
+    }
+
     /// Parse shape innards, ignoring any following '*' indicating a vectored shape.
     fn parse_non_vec(input: ParseStream) -> ParseResult<Self> {
         if input.peek(Bracket) {
@@ -215,12 +404,21 @@ impl Shape {
                 return Ok(Self::SymbolFlag(sf));
             }
 
-            let inner = Self::parse(&content)?;
-            if matches!(inner, Shape::SymbolFlag(_)) {
-                Ok(inner)
-            } else {
-                Ok(Self::Option(Box::new(inner)))
+            let inner = Self::parse_with_default(&content, true)?;
+            match &inner {
+                Self::SymbolFlag(_) => return Ok(inner),
+                Self::Option(_) => return Err(syn::Error::new(inner.span(), "Optional shapes cannot be nested")),
+                Self::Vec(_) => {
+                    return Err(syn::Error::new(
+                        inner.span(),
+                        "A repeated list may already be absent, so it cannot also be optional",
+                    ))
+                }
+                Self::DesList(dl) => dl.check_optional()?,
+                Self::TypedList(_) | Self::TypedSymbol(_) => (),
             }
+
+            Ok(Self::Option(Box::new(inner)))
         } else if input.peek(Paren) {
             let content;
             parenthesized!(content in input);
@@ -250,6 +448,154 @@ impl Shape {
     }
 }
 
+/// Check that a field's type is one the generated code can parse: a path, such as `f64`,
+/// `String`, or the name of another type declared with `sexpr!`.
+fn check_type(ty: &Type) -> ParseResult<()> {
+    if ty.category() == TypeCat::Unsupported {
+        Err(syn::Error::new_spanned(
+            ty,
+            "Unsupported field type; expected a type path such as `f64`, `String`, or `Color`",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Parse the `= expr` default value that may follow the type of a typed symbol or list.
+fn parse_default(input: ParseStream) -> ParseResult<Option<Expr>> {
+    if input.peek(Token![=]) && !input.peek(Token![=>]) {
+        let _: Token![=] = input.parse()?;
+        Ok(Some(input.parse()?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Parse the `as unit` that may follow the type of a typed symbol or list.
+///
+/// A bare lowercase name, such as `nm`, refers to a unit in `kanga_sexpr::units`; any other path
+/// names a type implementing `kanga_sexpr::units::Unit`. Units convert numbers, so the type must
+/// be `f64`.
+fn parse_unit(input: ParseStream, ty: &Type) -> ParseResult<Option<Type>> {
+    if !input.peek(Token![as]) {
+        return Ok(None);
+    }
+
+    let _: Token![as] = input.parse()?;
+    let unit: Path = input.parse()?;
+
+    if ty.category() != TypeCat::Float {
+        return Err(syn::Error::new_spanned(ty, "Only `f64` fields can be given a unit"));
+    }
+
+    match unit.get_ident() {
+        Some(name) if name.to_string().starts_with(|c: char| c.is_ascii_lowercase()) => {
+            Ok(Some(parse_quote_spanned! { name.span()=> ::kanga_sexpr::units::#name }))
+        }
+        _ => Ok(Some(Type::Path(TypePath {
+            qself: None,
+            path: unit,
+        }))),
+    }
+}
+
+/// Return the type of a field holding a value of type `ty`, or the value type of `unit` if the
+/// field has one.
+fn field_type(ty: &Type, unit: Option<&Type>) -> TokenStream {
+    match unit {
+        Some(unit) => quote_spanned! { unit.span()=> <#unit as ::kanga_sexpr::units::Unit>::Value },
+        None => ty.to_token_stream(),
+    }
+}
+
+/// Generate an expression that reads the number `value` from the document, converting it with
+/// `unit` if there is one, as an `Option` that is `None` if `value` is not a number.
+fn gen_float_reader(unit: Option<&Type>, value: TokenStream) -> TokenStream {
+    match unit {
+        Some(unit) => quote_spanned! { unit.span()=> <#unit as ::kanga_sexpr::units::Unit>::from_sexpr(#value) },
+        None => quote! { #value.as_f64() },
+    }
+}
+
+/// Generate a statement that pushes the field `rust_name` onto `λc` if its type is a node.
+///
+/// Scalar types (numbers, strings, and UUIDs) are not nodes and generate nothing.
+fn gen_child_push(rust_name: &Ident, ty: &Type, m: FieldMod, mutable: bool) -> TokenStream {
+    if rust_name == "_" || ty.category() != TypeCat::General {
+        return quote! {};
+    }
+
+    let borrow = if mutable {
+        quote! { &mut }
+    } else {
+        quote! { & }
+    };
+
+    // The pushes coerce the field to `dyn Node`, so they are spanned to the type to point errors at
+    // it when it is not a node.
+    match m {
+        FieldMod::None => quote_spanned! { ty.span()=>
+            let φ = #borrow self.#rust_name;
+            λc.push(φ);
+        },
+        FieldMod::Optional => quote_spanned! { ty.span()=>
+            if let Some(φ) = #borrow self.#rust_name {
+                λc.push(φ);
+            }
+        },
+        FieldMod::Vectored => quote_spanned! { ty.span()=>
+            for φ in #borrow self.#rust_name {
+                λc.push(φ);
+            }
+        },
+    }
+}
+
+/// Generate statements that push a value for the field `rust_name` onto `λw` for each value the
+/// field holds, converting each one, bound to `φ`, with `writer`.
+///
+/// Fields named `_` are not stored and generate nothing.
+fn gen_field_writer(rust_name: &Ident, m: FieldMod, writer: TokenStream) -> TokenStream {
+    if rust_name == "_" {
+        return quote! {};
+    }
+
+    match m {
+        FieldMod::None => quote! {
+            let φ = &self.#rust_name;
+            λw.push(#writer);
+        },
+        FieldMod::Optional => quote! {
+            if let Some(φ) = &self.#rust_name {
+                λw.push(#writer);
+            }
+        },
+        FieldMod::Vectored => quote! {
+            for φ in &self.#rust_name {
+                λw.push(#writer);
+            }
+        },
+    }
+}
+
+/// Generate an expression that converts the scalar or node `φ`, a reference to a value of type `ty`,
+/// into an s-expression, converting it back with `unit` if it has one.
+fn gen_value_writer(ty: &Type, unit: Option<&Type>) -> TokenStream {
+    if let Some(unit) = unit {
+        return quote_spanned! { unit.span()=>
+            <#unit as ::kanga_sexpr::units::Unit>::to_sexpr(φ)
+        };
+    }
+
+    match ty.category() {
+        TypeCat::Float | TypeCat::Int => quote! { ::lexpr::Value::from(*φ) },
+        TypeCat::String => quote! { ::lexpr::Value::string(φ.as_str()) },
+        TypeCat::Uuid => quote! { ::lexpr::Value::string(::kanga_sexpr::__private::ToString::to_string(φ)) },
+        TypeCat::General => quote_spanned! { ty.span()=> ::kanga_sexpr::ToSexpr::to_value(φ) },
+        TypeCat::Unsupported => unreachable!("unsupported types are rejected when parsing"),
+    }
+}
+
 impl Display for Shape {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         match self {
@@ -265,17 +611,32 @@ impl Display for Shape {
 
 impl Parse for Shape {
     fn parse(input: ParseStream) -> ParseResult<Self> {
-        let inner = Self::parse_non_vec(input)?;
-        if input.peek(Token![*]) {
-            let _: Token![*] = input.parse()?;
-            Ok(Self::Vec(Box::new(inner)))
-        } else {
-            Ok(inner)
-        }
+        Self::parse_with_default(input, false)
     }
 }
 
 impl DesList {
+    /// Check that this list can be optional: each item becomes an optional field, so the items must
+    /// be typed symbols or lists, optional ones without defaults, or destructured lists that can
+    /// themselves be optional.
+    fn check_optional(&self) -> ParseResult<()> {
+        for item in &self.items {
+            match item {
+                Shape::TypedList(_) | Shape::TypedSymbol(_) => (),
+                Shape::Option(inner) if inner.default_value().is_none() && !matches!(**inner, Shape::DesList(_)) => (),
+                Shape::DesList(dl) => dl.check_optional()?,
+                _ => {
+                    return Err(syn::Error::new(
+                        item.span(),
+                        "An optional destructured list may only contain typed symbols and lists",
+                    ))
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn gen_decl(&self, meta: &[Attribute], vis: &Visibility, m: FieldMod) -> TokenStream {
         let mut result = TokenStream::new();
         for item in &self.items {
@@ -286,40 +647,51 @@ impl DesList {
     }
 
     /// Generate a parser for this destructured list.
+    ///
+    /// The items are parsed from the contents of the list. If an optional list is absent, they are
+    /// parsed from an empty list instead, which leaves each of them `None`.
     fn gen_parser(&self, m: FieldMod) -> TokenStream {
-        let sexpr_head = &self.sexpr_head;
+        let sexpr_head = self.sexpr_head.unraw().to_string();
         let mut item_parsers = TokenStream::new();
         for item in &self.items {
             item_parsers.extend(item.gen_parser(m));
         }
 
-        let not_list_else = match m {
-            FieldMod::None => quote! {
-                else { return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone())); }
-            },
-            _ => quote! {},
-        };
-
-        let not_sym_else = match m {
-            FieldMod::None => quote! {
-                else { return Err(::kanga_sexpr::ParseError::ExpectedSym(α.clone())); }
-            },
-            _ => quote! {},
+        // λn = an empty list standing in for an absent optional list
+        // λi = the items of the list
+        let (empty, missing) = match m {
+            FieldMod::None => (
+                quote! {},
+                quote! {
+                    return Err(match λv.as_cons() {
+                        Some(λ) => ::kanga_sexpr::ParseError::ExpectedNamedSym(λ.car().clone(), ::kanga_sexpr::__private::String::from(#sexpr_head)),
+                        None => ::kanga_sexpr::ParseError::ExpectedList(λv.clone()),
+                    }.into())
+                },
+            ),
+            _ => (quote! { let λn = ::lexpr::Value::Null; }, quote! { &λn }),
         };
 
         quote! {
-            if let Some(λ) = λv.as_cons() {
-                let α = λ.car();
-                if α.as_symbol() == Some(stringify!(#sexpr_head)) {
-                    {
-                        let mut λv = λ.cdr();
-                        #item_parsers
-                    }
+            #empty
+            let λi = match λv.as_cons().and_then(|λ| {
+                λ.car().as_cons().filter(|β| β.car().as_symbol() == Some(#sexpr_head)).map(|β| (λ, β))
+            }) {
+                Some((λ, β)) => {
                     λv = λ.cdr();
+                    β.cdr()
+                }
+                None => #missing,
+            };
+
+            {
+                let mut λv = λi;
+                #item_parsers
+
+                if let Some(λ) = λv.as_cons() {
+                    return Err(::kanga_sexpr::ParseError::Unexpected(λ.car().clone()).into());
                 }
-                #not_sym_else
             }
-            #not_list_else
         }
     }
 
@@ -341,6 +713,44 @@ impl DesList {
         result
     }
 
+    /// Generate a writer for this destructured list. Optional lists whose items are all absent are
+    /// omitted.
+    fn gen_writer(&self, m: FieldMod) -> TokenStream {
+        let sexpr_head = self.sexpr_head.unraw().to_string();
+        let mut item_writers = TokenStream::new();
+        for item in &self.items {
+            item_writers.extend(item.gen_writer(m));
+        }
+
+        let push = match m {
+            FieldMod::Optional => quote! {
+                if φ.len() > 1 {
+                    λw.push(::lexpr::Value::list(φ));
+                }
+            },
+            _ => quote! { λw.push(::lexpr::Value::list(φ)); },
+        };
+
+        quote! {
+            let φ = {
+                let mut λw = ::kanga_sexpr::__private::Vec::new();
+                λw.push(::lexpr::Value::symbol(#sexpr_head));
+                #item_writers
+                λw
+            };
+            #push
+        }
+    }
+
+    /// Generate statements that push the node fields of this destructured list onto `λc`.
+    fn gen_children(&self, m: FieldMod, mutable: bool) -> TokenStream {
+        let mut result = TokenStream::new();
+        for item in &self.items {
+            result.extend(item.gen_children(m, mutable));
+        }
+        result
+    }
+
     /// Return the field names used for the s-expression representing this list shape.
     fn field_names(&self) -> Vec<Ident> {
         let mut result = Vec::new();
@@ -392,10 +802,11 @@ impl TypedList {
 
             result.extend(quote! { #vis #rust_name: });
 
+            let ty = field_type(ty, self.unit.as_ref());
             result.extend(match m {
                 FieldMod::None => quote! { #ty, },
-                FieldMod::Optional => quote! { ::std::option::Option<#ty>, },
-                FieldMod::Vectored => quote! { ::std::vec::Vec<#ty>, },
+                FieldMod::Optional => quote! { ::core::option::Option<#ty>, },
+                FieldMod::Vectored => quote! { ::kanga_sexpr::__private::Vec<#ty>, },
             });
         }
 
@@ -416,7 +827,7 @@ impl TypedList {
         let rust_name = &self.rust_name;
         if rust_name != "_" {
             match m {
-                FieldMod::Vectored => quote! { let mut #rust_name = Vec::new(); },
+                FieldMod::Vectored => quote! { let mut #rust_name = ::kanga_sexpr::__private::Vec::new(); },
                 _ => quote! { let #rust_name; },
             }
         } else {
@@ -434,86 +845,84 @@ impl TypedList {
         }
     }
 
-    /// Generate a standard parser for this typed list.
-    fn gen_std_parser(&self) -> TokenStream {
-        let sexpr_name = &self.sexpr_head;
-        let rust_name = &self.rust_name;
+    /// Generate an expression that converts the list element `α` (whose cons cell is `β`) into
+    /// the field's type.
+    ///
+    /// Primitive types take the single value following the head; general types are handed the
+    /// entire list first, falling back to that single value (e.g., for enums like `(type dash)`).
+    fn gen_value_parser(&self) -> TokenStream {
         let ty = &self.ty;
 
-        let field_parser = match ty.category() {
-            TypeCat::Float => quote! {
-                α.as_f64().ok_or(::kanga_sexpr::ParseError::ExpectedFloat(α.clone()))?
-            },
+        match ty.category() {
+            TypeCat::Float => {
+                let reader = gen_float_reader(self.unit.as_ref(), quote! { ψ.car() });
+                quote! {
+                    β.cdr().as_cons().filter(|ψ| ψ.cdr().is_null()).and_then(|ψ| #reader)
+                        .ok_or_else(|| ::kanga_sexpr::ParseError::ExpectedFloat(α.clone()))?
+                }
+            }
             TypeCat::Int => quote! {
-                α.as_i64().ok_or(::kanga_sexpr::ParseError::ExpectedInt(α.clone()))?
+                β.cdr().as_cons().filter(|ψ| ψ.cdr().is_null()).and_then(|ψ| ψ.car().as_i64())
+                    .ok_or_else(|| ::kanga_sexpr::ParseError::ExpectedInt(α.clone()))?
             },
             TypeCat::String => quote! {
-                α.as_str().ok_or(::kanga_sexpr::ParseError::ExpectedStr(α.clone()))?.to_string()
+                ::kanga_sexpr::__private::String::from(
+                    β.cdr().as_cons().filter(|ψ| ψ.cdr().is_null()).and_then(|ψ| ψ.car().as_str())
+                        .ok_or_else(|| ::kanga_sexpr::ParseError::ExpectedStr(α.clone()))?
+                )
             },
             TypeCat::Uuid => quote! {
-                Uuid::from_str(
-                    α.as_str().ok_or(::kanga_sexpr::ParseError::ExpectedUuid(α.clone()))?)
-                    .map_err(|_| ::kanga_sexpr::ParseError::ExpectedUuid(α.clone()))?
+                β.cdr().as_cons().filter(|ψ| ψ.cdr().is_null())
+                    .and_then(|ψ| ψ.car().as_str().or_else(|| ψ.car().as_symbol()))
+                    .and_then(|φ| ::uuid::Uuid::parse_str(φ).ok())
+                    .ok_or_else(|| ::kanga_sexpr::ParseError::ExpectedUuid(α.clone()))?
             },
-            TypeCat::General => quote! {
-                #ty::try_from(α)?
+            TypeCat::General => quote_spanned! { ty.span()=>
+                match <#ty>::try_from(α) {
+                    Ok(φ) => φ,
+                    Err(ε) => match β.cdr().as_cons() {
+                        Some(ψ) if ψ.cdr().is_null() => <#ty>::try_from(ψ.car()).map_err(|_| ε)?,
+                        _ => Err(ε)?,
+                    },
+                }
             },
-            TypeCat::Unsupported => panic!("Unsupported type category for typed list: {:?}", ty),
-        };
+            TypeCat::Unsupported => unreachable!("unsupported types are rejected when parsing"),
+        }
+    }
+
+    /// Generate a standard parser for this typed list.
+    fn gen_std_parser(&self) -> TokenStream {
+        let sexpr_name = self.sexpr_head.unraw().to_string();
+        let rust_name = &self.rust_name;
+        let value_parser = self.gen_value_parser();
 
         quote! {
             let Some(λ) = λv.as_cons() else {
-                return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()));
+                return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()).into());
             };
 
             let α = λ.car();
-            if α.as_symbol() == Some(stringify!(#sexpr_name)) {
-                #rust_name = #field_parser;
-                λv = λ.cdr();
-            } else {
-                return Err(::kanga_sexpr::ParseError::ExpectedSym(α.clone()));
-            }
-            drop(α);
-            drop(λ);
+            let Some(β) = α.as_cons().filter(|β| β.car().as_symbol() == Some(#sexpr_name)) else {
+                return Err(::kanga_sexpr::ParseError::ExpectedNamedSym(α.clone(), ::kanga_sexpr::__private::String::from(#sexpr_name)).into());
+            };
+
+            #rust_name = #value_parser;
+            λv = λ.cdr();
         }
     }
 
     fn gen_optional_parser(&self) -> TokenStream {
-        let sexpr_name = &self.sexpr_head;
+        let sexpr_name = self.sexpr_head.unraw().to_string();
         let rust_name = &self.rust_name;
-        let ty = &self.ty;
-
-        let field_parser = match ty.category() {
-            TypeCat::Float => quote! {
-                α.as_f64().ok_or(::kanga_sexpr::ParseError::ExpectedFloat(α.clone()))?
-            },
-            TypeCat::Int => quote! {
-                α.as_i64().ok_or(::kanga_sexpr::ParseError::ExpectedInt(α.clone()))?
-            },
-            TypeCat::String => quote! {
-                α.as_str().ok_or(::kanga_sexpr::ParseError::ExpectedStr(α.clone()))?.to_string()
-            },
-            TypeCat::Uuid => quote! {
-                Uuid::from_str(
-                    α.as_str().ok_or(::kanga_sexpr::ParseError::ExpectedUuid(α.clone()))?)
-                    .map_err(|_| ::kanga_sexpr::ParseError::ExpectedUuid(α.clone()))?
-            },
-            TypeCat::General => quote! {
-                #ty::try_from(α)?
-            },
-            TypeCat::Unsupported => panic!("Unsupported type category for typed list: {:?}", ty),
-        };
+        let value_parser = self.gen_value_parser();
 
         quote! {
-            if let Some(λ) = λv.as_cons() {
+            if let Some((λ, β)) = λv.as_cons().and_then(|λ| {
+                λ.car().as_cons().filter(|β| β.car().as_symbol() == Some(#sexpr_name)).map(|β| (λ, β))
+            }) {
                 let α = λ.car();
-
-                if α.as_symbol() == Some(stringify!(#sexpr_name)) {
-                    #rust_name = Some(#field_parser);
-                    λv = λ.cdr();
-                } else {
-                    #rust_name = None;
-                }
+                #rust_name = Some(#value_parser);
+                λv = λ.cdr();
             } else {
                 #rust_name = None;
             }
@@ -521,44 +930,41 @@ impl TypedList {
     }
 
     fn gen_vectored_parser(&self) -> TokenStream {
-        let sexpr_name = &self.sexpr_head;
+        let sexpr_name = self.sexpr_head.unraw().to_string();
         let rust_name = &self.rust_name;
-        let ty = &self.ty;
-
-        let field_parser = match ty.category() {
-            TypeCat::Float => quote! {
-                α.as_f64().ok_or(::kanga_sexpr::ParseError::ExpectedFloat(α.clone()))?
-            },
-            TypeCat::Int => quote! {
-                α.as_i64().ok_or(::kanga_sexpr::ParseError::ExpectedInt(α.clone()))?
-            },
-            TypeCat::String => quote! {
-                α.as_str().ok_or(::kanga_sexpr::ParseError::ExpectedStr(α.clone()))?.to_string()
-            },
-            TypeCat::Uuid => quote! {
-                Uuid::from_str(
-                    α.as_str().ok_or(::kanga_sexpr::ParseError::ExpectedUuid(α.clone()))?)
-                    .map_err(|_| ::kanga_sexpr::ParseError::ExpectedUuid(α.clone()))?
-            },
-            TypeCat::General => quote! {
-                #ty::try_from(α)?
-            },
-            TypeCat::Unsupported => panic!("Unsupported type category for typed list: {:?}", ty),
-        };
+        let value_parser = self.gen_value_parser();
 
         quote! {
             // TypedList::gen_vectored_parser
-            if let Some(λ) = λv.as_cons() {
+            while let Some(λ) = λv.as_cons() {
                 let α = λ.car();
 
-                if α.as_symbol() == Some(stringify!(#sexpr_name)) {
-                    #rust_name.push(#field_parser);
-                    λv = λ.cdr();
-                }
+                let Some(β) = α.as_cons().filter(|β| β.car().as_symbol() == Some(#sexpr_name)) else {
+                    break;
+                };
+
+                #rust_name.push(#value_parser);
+                λv = λ.cdr();
             }
         }
     }
 
+    /// Generate a writer for this typed list.
+    ///
+    /// Primitive types are written as the single value following the head. General types are
+    /// written as themselves if they produce a list with this head, and wrapped in one otherwise.
+    fn gen_writer(&self, m: FieldMod) -> TokenStream {
+        let sexpr_name = self.sexpr_head.unraw().to_string();
+        let value_writer = gen_value_writer(&self.ty, self.unit.as_ref());
+
+        let writer = match self.ty.category() {
+            TypeCat::General => quote! { ::kanga_sexpr::list_with_head(#sexpr_name, #value_writer) },
+            _ => quote! { ::lexpr::Value::list([::lexpr::Value::symbol(#sexpr_name), #value_writer]) },
+        };
+
+        gen_field_writer(&self.rust_name, m, writer)
+    }
+
     /// Return the field names used for the s-expression representing this list shape.
     fn field_names(&self) -> Vec<Ident> {
         if self.rust_name == "_" {
@@ -579,6 +985,18 @@ impl Display for TypedList {
 
         write!(f, ": {}", self.ty.to_token_stream())?;
 
+        for field in self.inline.iter().flatten() {
+            write!(f, " {field}")?;
+        }
+
+        if let Some(unit) = &self.unit {
+            write!(f, " as {}", unit.to_token_stream())?;
+        }
+
+        if let Some(default) = &self.default {
+            write!(f, " = {}", default.to_token_stream())?;
+        }
+
         write!(f, ")")
     }
 }
@@ -596,6 +1014,24 @@ impl Parse for TypedList {
 
         let _: Token![:] = input.parse()?;
         let ty: Type = input.parse()?;
+        check_type(&ty)?;
+
+        // Fields following the type declare the type inline.
+        let inline = if input.is_empty() || input.peek(Token![as]) || input.peek(Token![=]) {
+            None
+        } else {
+            if ty.inline_name().is_none() {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    "A struct declared inline must be named by a single identifier",
+                ));
+            }
+
+            Some(input.parse()?)
+        };
+
+        let unit = parse_unit(input, &ty)?;
+        let default = parse_default(input)?;
 
         if !input.is_empty() {
             return Err(input.error("Unexpected tokens after typed list"));
@@ -605,6 +1041,9 @@ impl Parse for TypedList {
             sexpr_head,
             rust_name,
             ty,
+            unit,
+            inline,
+            default,
         })
     }
 }
@@ -628,41 +1067,32 @@ impl SymbolFlag {
     }
 
     /// Generate a parser for this symbol flag.
+    ///
+    /// The flag is set by the bare symbol, as in `hide`, or by a list with a `yes` or `no` value, as
+    /// in `(hide yes)`, which KiCad 9 writes instead. It is clear if neither is next.
     fn gen_parser(&self, m: FieldMod) -> TokenStream {
         assert_eq!(m, FieldMod::None, "Cannot apply field mod {m:?} to symbol flag");
         let sexpr_name = &self.sexpr_name;
         let rust_name = &self.rust_name;
 
-        match m {
-            FieldMod::None => quote! {
-                let Some(λ) = λv.as_cons() else {
-                    return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()));
-                };
+        quote! {
+            let mut φ = false;
+            if let Some(λ) = λv.as_cons() {
                 let α = λ.car();
                 if α.as_symbol() == Some(stringify!(#sexpr_name)) {
-                    #rust_name = true;
+                    φ = true;
+                    λv = λ.cdr();
+                } else if let Some(β) = α.as_cons().filter(|β| β.car().as_symbol() == Some(stringify!(#sexpr_name))) {
+                    φ = match β.cdr().as_cons().filter(|ψ| ψ.cdr().is_null()).and_then(|ψ| ψ.car().as_symbol()) {
+                        Some("yes") => true,
+                        Some("no") => false,
+                        _ => return Err(::kanga_sexpr::ParseError::Unexpected(α.clone()).into()),
+                    };
                     λv = λ.cdr();
-                } else {
-                    #rust_name = false;
-                }
-    
-                drop(α);
-                drop(λ);
-            },
-            FieldMod::Optional => quote! {
-                if let Some(λ) = λv.as_cons() {
-                    let α = λ.car();
-                    if α.as_symbol() == Some(stringify!(#sexpr_name)) {
-                        #rust_name = true;
-                        λv = λ.cdr();
-                    } else {
-                        #rust_name = false;
-                    }
-                } else {
-                    #rust_name = false;
                 }
-            },
-            _ => panic!("Cannot apply field mod {m:?} to symbol flag"),
+            }
+
+            #rust_name = φ;
         }
     }
 
@@ -688,6 +1118,22 @@ impl SymbolFlag {
         }
     }
 
+    /// Generate a writer for this symbol flag, which writes the symbol if the flag is set.
+    fn gen_writer(&self) -> TokenStream {
+        let sexpr_name = self.sexpr_name.unraw().to_string();
+        let rust_name = &self.rust_name;
+
+        if rust_name == "_" {
+            return quote! {};
+        }
+
+        quote! {
+            if self.#rust_name {
+                λw.push(::lexpr::Value::symbol(#sexpr_name));
+            }
+        }
+    }
+
     /// Return the field names used for the s-expression representing this list shape.
     fn field_names(&self) -> Vec<Ident> {
         if self.rust_name == "_" {
@@ -743,10 +1189,11 @@ impl TypedSymbol {
             }
 
             result.extend(quote! {#vis #rust_name: });
+            let ty = field_type(ty, self.unit.as_ref());
             result.extend(match m {
                 FieldMod::None => quote! { #ty, },
-                FieldMod::Optional => quote! { ::std::option::Option<#ty>, },
-                FieldMod::Vectored => quote! { ::std::vec::Vec<#ty>, },
+                FieldMod::Optional => quote! { ::core::option::Option<#ty>, },
+                FieldMod::Vectored => quote! { ::kanga_sexpr::__private::Vec<#ty>, },
             })
         }
 
@@ -766,14 +1213,15 @@ impl TypedSymbol {
         let sexpr_name = &self.sexpr_name;
         let rust_name = &self.rust_name;
         let ty = &self.ty;
+        let reader = gen_float_reader(self.unit.as_ref(), quote! { α });
 
         let ty_parser = match ty.category() {
             TypeCat::Float => quote! {
-                if let Some(φ) = α.as_f64() {
+                if let Some(φ) = #reader {
                     #rust_name = φ;
                     λv = λ.cdr();
                 } else {
-                    return Err(::kanga_sexpr::ParseError::ExpectedFloat(α.clone()));
+                    return Err(::kanga_sexpr::ParseError::ExpectedFloat(α.clone()).into());
                 }
             },
             TypeCat::Int => quote! {
@@ -781,15 +1229,15 @@ impl TypedSymbol {
                     #rust_name = φ;
                     λv = λ.cdr();
                 } else {
-                    return Err(::kanga_sexpr::ParseError::ExpectedInt(α.clone()));
+                    return Err(::kanga_sexpr::ParseError::ExpectedInt(α.clone()).into());
                 }
             },
             TypeCat::String => quote! {
                 if let Some(φ) = α.as_str() {
-                    #rust_name = φ.to_string();
+                    #rust_name = ::kanga_sexpr::__private::String::from(φ);
                     λv = λ.cdr();
                 } else {
-                    return Err(::kanga_sexpr::ParseError::ExpectedStr(α.clone()));
+                    return Err(::kanga_sexpr::ParseError::ExpectedStr(α.clone()).into());
                 }
             },
             TypeCat::Uuid => quote! {
@@ -799,21 +1247,21 @@ impl TypedSymbol {
                             #rust_name = φ;
                             λv = λ.cdr();
                         },
-                        Err(_) => return Err(::kanga_sexpr::ParseError::ExpectedUuid(α.clone())),
+                        Err(_) => return Err(::kanga_sexpr::ParseError::ExpectedUuid(α.clone()).into()),
                     }
                 } else {
-                    return Err(::kanga_sexpr::ParseError::ExpectedUuid(α.clone()));
+                    return Err(::kanga_sexpr::ParseError::ExpectedUuid(α.clone()).into());
                 }
             },
-            TypeCat::General => quote! {
+            TypeCat::General => quote_spanned! { ty.span()=>
                 #rust_name = #ty :: try_from(α)?;
                 λv = λ.cdr();
             },
-            TypeCat::Unsupported => panic!("Unsupported type category for typed symbol: {:?}", ty),
+            TypeCat::Unsupported => unreachable!("unsupported types are rejected when parsing"),
         };
         quote! {
             let Some(λ) = λv.as_cons() else {
-                return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()));
+                return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()).into());
             };
             let α = λ.car();
             #ty_parser
@@ -826,10 +1274,11 @@ impl TypedSymbol {
         let sexpr_name = &self.sexpr_name;
         let rust_name = &self.rust_name;
         let ty = &self.ty;
+        let reader = gen_float_reader(self.unit.as_ref(), quote! { α });
 
         let ty_parser = match ty.category() {
             TypeCat::Float => quote! {
-                if let Some(φ) = α.as_f64() {
+                if let Some(φ) = #reader {
                     #rust_name = Some(φ);
                     λv = λ.cdr();
                 } else {
@@ -846,7 +1295,7 @@ impl TypedSymbol {
             },
             TypeCat::String => quote! {
                 if let Some(φ) = α.as_str() {
-                    #rust_name = Some(φ.to_string());
+                    #rust_name = Some(::kanga_sexpr::__private::String::from(φ));
                     λv = λ.cdr();
                 } else {
                     #rust_name = None;
@@ -867,7 +1316,7 @@ impl TypedSymbol {
                     #rust_name = None;
                 }
             },
-            TypeCat::General => quote! {
+            TypeCat::General => quote_spanned! { ty.span()=>
                 if let Ok(φ) = #ty::try_from(α) {
                     #rust_name = Some(φ);
                     λv = λ.cdr();
@@ -875,7 +1324,7 @@ impl TypedSymbol {
                     #rust_name = None;
                 }
             },
-            TypeCat::Unsupported => panic!("Unsupported type category for typed symbol: {:?}", ty),
+            TypeCat::Unsupported => unreachable!("unsupported types are rejected when parsing"),
         };
 
         quote! {
@@ -894,10 +1343,11 @@ impl TypedSymbol {
         let sexpr_name = &self.sexpr_name;
         let rust_name = &self.rust_name;
         let ty = &self.ty;
+        let reader = gen_float_reader(self.unit.as_ref(), quote! { α });
 
         let ty_parser = match ty.category() {
             TypeCat::Float => quote! {
-                if let Some(φ) = α.as_f64() {
+                if let Some(φ) = #reader {
                     #rust_name.push(φ);
                     λv = λ.cdr();
                 }
@@ -910,7 +1360,7 @@ impl TypedSymbol {
             },
             TypeCat::String => quote! {
                 if let Some(φ) = α.as_str() {
-                    #rust_name.push(φ.to_string());
+                    #rust_name.push(::kanga_sexpr::__private::String::from(φ));
                     λv = λ.cdr();
                 }
             },
@@ -922,13 +1372,13 @@ impl TypedSymbol {
                     }
                 }
             },
-            TypeCat::General => quote! {
+            TypeCat::General => quote_spanned! { ty.span()=>
                 if let Ok(φ) = #ty::try_from(α) {
                     #rust_name.push(φ);
                     λv = λ.cdr();
                 }
             },
-            TypeCat::Unsupported => panic!("Unsupported type category for typed symbol: {:?}", ty),
+            TypeCat::Unsupported => unreachable!("unsupported types are rejected when parsing"),
         };
 
         quote! {
@@ -948,7 +1398,7 @@ impl TypedSymbol {
         let rust_name = &self.rust_name;
         if rust_name != "_" {
             match m {
-                FieldMod::Vectored => quote! { let mut #rust_name = Vec::new(); },
+                FieldMod::Vectored => quote! { let mut #rust_name = ::kanga_sexpr::__private::Vec::new(); },
                 _ => quote! { let #rust_name; },
             }
         } else {
@@ -966,6 +1416,11 @@ impl TypedSymbol {
         }
     }
     
+    /// Generate a writer for this typed symbol.
+    fn gen_writer(&self, m: FieldMod) -> TokenStream {
+        gen_field_writer(&self.rust_name, m, gen_value_writer(&self.ty, self.unit.as_ref()))
+    }
+
     /// Return the field names used for the s-expression representing this typed symbol.
     fn field_names(&self) -> Vec<Ident> {
         if self.rust_name == "_" {
@@ -984,7 +1439,17 @@ impl Display for TypedSymbol {
             write!(f, " => {}", self.rust_name)?;
         }
 
-        write!(f, ": {}", self.ty.to_string())
+        write!(f, ": {}", self.ty.to_string())?;
+
+        if let Some(unit) = &self.unit {
+            write!(f, " as {}", unit.to_token_stream())?;
+        }
+
+        if let Some(default) = &self.default {
+            write!(f, " = {}", default.to_token_stream())?;
+        }
+
+        Ok(())
     }
 }
 
@@ -1000,11 +1465,16 @@ impl Parse for TypedSymbol {
 
         let _: Token![:] = input.parse()?;
         let ty: Type = input.parse()?;
+        check_type(&ty)?;
+        let unit = parse_unit(input, &ty)?;
+        let default = parse_default(input)?;
 
         Ok(Self {
             rust_name,
             sexpr_name,
             ty,
+            unit,
+            default,
         })
     }
 }
@@ -1088,6 +1558,35 @@ mod tests {
         assert_eq!(i1.ty.category(), TypeCat::String, "Not a string: {:?}", i1.ty);
     }
 
+    #[test]
+    fn option_default_good() {
+        let s: Shape = parse2(quote! { [(width: f64 = 0.0)] }).unwrap();
+        let o = s.option_inner().expect("Expected an option");
+        assert_eq!(o.default_value().map(|d| d.to_token_stream().to_string()), Some("0.0".to_string()));
+        assert_eq!(o.option_mod(), FieldMod::None);
+
+        assert!(parse2::<Shape>(quote! { width: f64 = 0.0 }).is_err());
+        assert!(parse2::<Shape>(quote! { [width: f64 = 0.0 y: f64] }).is_err());
+    }
+
+    #[test]
+    fn unit_good() {
+        let s: Shape = parse2(quote! { x: f64 as nm }).unwrap();
+        let ts = s.as_typed_symbol().expect("Expected a typed symbol");
+        assert_eq!(ts.unit.to_token_stream().to_string(), quote! { ::kanga_sexpr::units::nm }.to_string());
+        assert_eq!(ts.to_string(), "x: f64 as :: kanga_sexpr :: units :: nm");
+
+        // Other paths name a unit type as is, and a default follows the unit.
+        let s: Shape = parse2(quote! { [(width: f64 as units::Mils = 0)] }).unwrap();
+        let Some(Shape::TypedList(tl)) = s.option_inner() else {
+            panic!("Expected an optional typed list");
+        };
+        assert_eq!(tl.unit.to_token_stream().to_string(), quote! { units::Mils }.to_string());
+        assert!(tl.default.is_some());
+
+        assert!(parse2::<Shape>(quote! { x: i64 as nm }).is_err());
+    }
+
     #[test]
     fn symbol_flag_good() {
         let s: Shape = parse2(quote! { [hello] }).unwrap();
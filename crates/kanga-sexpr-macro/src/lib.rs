@@ -11,7 +11,7 @@ use {
     std::ops::Deref,
     syn::{
         parse::{Parse, ParseStream, Result as ParseResult},
-        parse2, Attribute, Token,
+        parse2, Attribute, Path, Token,
     },
 };
 
@@ -21,6 +21,15 @@ pub(crate) enum Decl {
     Struct(StructDecl),
 }
 
+/// Generate the error type of the parsers for a declaration: the type given with
+/// `#[sexpr(error = Type)]`, or `kanga_sexpr::ParseError`.
+pub(crate) fn gen_error_type(error: Option<&Path>) -> TokenStream {
+    match error {
+        Some(error) => quote! { #error },
+        None => quote! { ::kanga_sexpr::ParseError },
+    }
+}
+
 /// A `Vec<[Decl]>` that can be parsed.
 pub(crate) struct DeclVec(Vec<Decl>);
 
@@ -44,7 +53,7 @@ impl Parse for Decl {
         } else if input.peek(Token![enum]) {
             EnumDecl::parse_with_attr_vis(input, attr, vis).map(Self::Enum)
         } else {
-            return Err(input.error("Expected 'struct' or 'enum'"));
+            Err(input.error("Expected 'struct' or 'enum'"))
         }
     }
 }
@@ -84,6 +93,36 @@ impl Parse for DeclVec {
 
 /// Entry point for the `#[sexpr]` attribute macro.
 ///
+/// A struct marked `#[sexpr(unordered)]` accepts its keyed fields (lists and flags) in any order,
+/// as KiCad writes some elements; its positional fields must come first.
+///
+/// A struct or enum marked `#[sexpr(error = Type)]` reports errors as `Type`, which must implement
+/// `kanga_sexpr::SexprError`; structs declared inline inherit it. A struct marked
+/// `#[sexpr(check = path)]` passes each parsed value to `path`, a function taking a reference to
+/// it and returning `Result<(), Type>`, to reject values the declaration alone cannot.
+///
+/// A list or struct accepts exactly the elements declared; anything left over is an error.
+///
+/// An optional typed symbol or list may give a default value, as in `[(width: f64 = 0.0)]`; the
+/// field then has the plain type and takes the default when the element is absent.
+///
+/// A list may be declared as a struct of its own by naming a type before its items, as in
+/// `(size: FontSize height: f64 width: f64)`; the struct is declared alongside, with the same
+/// visibility and attributes. Without a type, the items of a list are flattened into the enclosing
+/// struct.
+///
+/// A number may be converted by a unit policy, as in `width: f64 as nm`, which reads millimeters
+/// into whole nanometers; the field then has the policy's value type. See `kanga_sexpr::units`.
+///
+/// A flag such as `[hide]` is set by its bare symbol or by a `(hide yes)` list, as KiCad 9 writes
+/// it; it is written back as the bare symbol.
+///
+/// An enum variant may wrap a payload type, as in `arc => Arc(SymbolGraphicArc)`; it is then
+/// parsed from a list whose head is the variant's symbol, by the payload type's own parser.
+///
+/// Malformed declarations, such as a repeated flag or a field type the macro cannot parse, are
+/// reported as compile errors at the offending shape.
+///
 /// This just converts the `proc_macro` types into `proc_macro2` types and invokes
 /// [`sexpr_impl`].
 #[proc_macro]
@@ -106,7 +145,17 @@ fn sexpr_impl(input: TokenStream) -> TokenStream {
 
 #[cfg(test)]
 mod tests {
-    use {super::*, pretty_assertions::assert_eq, quote::quote, syn::parse2};
+    use {
+        super::*,
+        pretty_assertions::assert_eq,
+        quote::quote,
+        std::{
+            env,
+            fs::{read_dir, read_to_string, write},
+            path::{Path, PathBuf},
+        },
+        syn::{parse2, parse_file, File, Item},
+    };
 
     #[test]
     fn test_enum() {
@@ -134,6 +183,32 @@ mod tests {
         assert_eq!(generated.to_string(), expected.to_string());
     }
 
+    #[test]
+    fn test_enum_payload() {
+        let input = quote! {
+            pub enum Graphic {
+                arc => Arc(SymbolGraphicArc)
+                circle => Circle(SymbolGraphicCircle),
+                none => Empty
+            }
+        };
+
+        let decls: DeclVec = parse2(input).unwrap();
+        let generated = decls.generate().to_string();
+        let impl_ops = generated.find("impl").unwrap();
+        let generated = generated[..impl_ops].trim();
+
+        let expected = quote! {
+            pub enum Graphic {
+                Arc(SymbolGraphicArc),
+                Circle(SymbolGraphicCircle),
+                Empty,
+            }
+        };
+
+        assert_eq!(generated.to_string(), expected.to_string());
+    }
+
     #[test]
     fn test_struct() {
         let input = quote! {
@@ -159,10 +234,73 @@ mod tests {
                 pub red: f64,
                 pub green: f64,
                 pub blue: f64,
-                pub alpha: ::std::option::Option<f64>,
+                pub alpha: ::core::option::Option<f64>,
             }
         };
 
         assert_eq!(generated.to_string(), expected.to_string());
     }
+
+    /// Check the code generated for each file in `tests/expand` against its `.expanded.rs`
+    /// snapshot. Run with `SNAPSHOTS=overwrite` to rewrite the snapshots after an intended change
+    /// to the generated code.
+    #[test]
+    fn test_expand_snapshots() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("expand");
+        let overwrite = env::var_os("SNAPSHOTS").is_some_and(|value| value == "overwrite");
+
+        let mut inputs: Vec<PathBuf> = read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+            .filter(|path| !path.to_string_lossy().ends_with(".expanded.rs"))
+            .collect();
+        inputs.sort();
+        assert!(!inputs.is_empty(), "no inputs in {}", dir.display());
+
+        for input in inputs {
+            let actual = expand_file(&input);
+            let snapshot = input.with_extension("expanded.rs");
+
+            if overwrite {
+                write(&snapshot, actual).unwrap();
+                continue;
+            }
+
+            let Ok(expected) = read_to_string(&snapshot) else {
+                write(&snapshot, actual).unwrap();
+                panic!("wrote new snapshot {}; review it and run the tests again", snapshot.display());
+            };
+
+            assert_eq!(
+                expected,
+                actual,
+                "{} is out of date; run with SNAPSHOTS=overwrite to update it",
+                snapshot.display()
+            );
+        }
+    }
+
+    /// Expand the `sexpr!` invocations in a file, leaving other items as they are, and format the
+    /// result.
+    fn expand_file(path: &Path) -> String {
+        let file = parse_file(&read_to_string(path).unwrap()).unwrap();
+        let mut items = Vec::new();
+
+        for item in file.items {
+            match item {
+                Item::Macro(item) if item.mac.path.is_ident("sexpr") => {
+                    let expanded: File = parse2(sexpr_impl(item.mac.tokens)).unwrap();
+                    items.extend(expanded.items);
+                }
+                item => items.push(item),
+            }
+        }
+
+        prettyplease::unparse(&File {
+            shebang: None,
+            attrs: Vec::new(),
+            items,
+        })
+    }
 }
@@ -27,6 +27,9 @@ pub(crate) trait TypeExt {
 
     /// Return a string representing the Rust expression for this type.
     fn to_string(&self) -> String;
+
+    /// If the type is a single identifier, which can name a struct declared inline, return it.
+    fn inline_name(&self) -> Option<&Ident>;
 }
 
 impl TypeExt for Type {
@@ -113,4 +116,16 @@ impl TypeExt for Type {
     fn to_string(&self) -> String {
         self.to_token_stream().to_string()
     }
+
+    fn inline_name(&self) -> Option<&Ident> {
+        let Type::Path(path) = self else {
+            return None;
+        };
+
+        if path.qself.is_some() {
+            return None;
+        }
+
+        path.path.get_ident()
+    }
 }
@@ -0,0 +1,134 @@
+/// An RGB color with an optional alpha channel.
+#[derive(Debug)]
+pub struct Color {
+    pub red: f64,
+    pub green: f64,
+    pub blue: f64,
+    pub alpha: ::core::option::Option<f64>,
+}
+impl ::core::convert::TryFrom<&::lexpr::Value> for Color {
+    type Error = ::kanga_sexpr::ParseError;
+    fn try_from(λv: &::lexpr::Value) -> ::core::result::Result<Self, Self::Error> {
+        fn parse_fields(
+            mut λv: &::lexpr::Value,
+        ) -> ::core::result::Result<Color, ::kanga_sexpr::ParseError> {
+            let red;
+            let green;
+            let blue;
+            let alpha;
+            let Some(λ) = λv.as_cons() else {
+                return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()).into());
+            };
+            let α = λ.car();
+            if let Some(φ) = α.as_f64() {
+                red = φ;
+                λv = λ.cdr();
+            } else {
+                return Err(::kanga_sexpr::ParseError::ExpectedFloat(α.clone()).into());
+            }
+            drop(α);
+            drop(λ);
+            let Some(λ) = λv.as_cons() else {
+                return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()).into());
+            };
+            let α = λ.car();
+            if let Some(φ) = α.as_f64() {
+                green = φ;
+                λv = λ.cdr();
+            } else {
+                return Err(::kanga_sexpr::ParseError::ExpectedFloat(α.clone()).into());
+            }
+            drop(α);
+            drop(λ);
+            let Some(λ) = λv.as_cons() else {
+                return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()).into());
+            };
+            let α = λ.car();
+            if let Some(φ) = α.as_f64() {
+                blue = φ;
+                λv = λ.cdr();
+            } else {
+                return Err(::kanga_sexpr::ParseError::ExpectedFloat(α.clone()).into());
+            }
+            drop(α);
+            drop(λ);
+            if let Some(λ) = λv.as_cons() {
+                let α = λ.car();
+                if let Some(φ) = α.as_f64() {
+                    alpha = Some(φ);
+                    λv = λ.cdr();
+                } else {
+                    alpha = None;
+                }
+                drop(α);
+                drop(λ);
+            } else {
+                alpha = None;
+            }
+            if let Some(λ) = λv.as_cons() {
+                return Err(
+                    ::kanga_sexpr::ParseError::Unexpected(λ.car().clone()).into(),
+                );
+            }
+            Ok(Color { red, green, blue, alpha })
+        }
+        let Some(λ) = λv.as_cons() else {
+            return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()).into());
+        };
+        if λ.car().as_symbol() != Some("color") {
+            return Err(
+                ::kanga_sexpr::ParseError::ExpectedNamedSym(
+                        λv.clone(),
+                        ::kanga_sexpr::__private::String::from("color"),
+                    )
+                    .into(),
+            );
+        }
+        parse_fields(λ.cdr())
+            .map_err(|ε| ::kanga_sexpr::SexprError::within_list(ε, "color", λ))
+    }
+}
+impl ::kanga_sexpr::Node for Color {
+    fn sexpr_name(&self) -> &'static str {
+        "color"
+    }
+    fn children(&self) -> ::kanga_sexpr::__private::Vec<&dyn ::kanga_sexpr::Node> {
+        #[allow(unused_mut)]
+        let mut λc: ::kanga_sexpr::__private::Vec<&dyn ::kanga_sexpr::Node> = ::kanga_sexpr::__private::Vec::new();
+        λc
+    }
+    fn children_mut(
+        &mut self,
+    ) -> ::kanga_sexpr::__private::Vec<&mut dyn ::kanga_sexpr::Node> {
+        #[allow(unused_mut)]
+        let mut λc: ::kanga_sexpr::__private::Vec<&mut dyn ::kanga_sexpr::Node> = ::kanga_sexpr::__private::Vec::new();
+        λc
+    }
+    fn as_any(&self) -> &dyn ::core::any::Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn ::core::any::Any {
+        self
+    }
+}
+impl ::kanga_sexpr::ToSexpr for Color {
+    fn to_value(&self) -> ::lexpr::Value {
+        let mut λw = ::kanga_sexpr::__private::Vec::new();
+        λw.push(::lexpr::Value::symbol("color"));
+        let φ = &self.red;
+        λw.push(::lexpr::Value::from(*φ));
+        let φ = &self.green;
+        λw.push(::lexpr::Value::from(*φ));
+        let φ = &self.blue;
+        λw.push(::lexpr::Value::from(*φ));
+        if let Some(φ) = &self.alpha {
+            λw.push(::lexpr::Value::from(*φ));
+        }
+        ::lexpr::Value::list(λw)
+    }
+}
+impl ::core::fmt::Display for Color {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        ::core::fmt::Display::fmt(&::kanga_sexpr::ToSexpr::to_value(self), f)
+    }
+}
@@ -0,0 +1,13 @@
+// A struct with positional fields, one of them optional.
+sexpr! {
+    /// An RGB color with an optional alpha channel.
+    #[derive(Debug)]
+    pub struct Color {
+        (color
+            red: f64
+            green: f64
+            blue: f64
+            [alpha: f64]
+        )
+    }
+}
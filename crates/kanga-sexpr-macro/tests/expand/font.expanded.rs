@@ -0,0 +1,303 @@
+#[derive(Debug)]
+pub struct Font {
+    pub size: FontSize,
+    pub left: ::core::option::Option<f64>,
+    pub right: ::core::option::Option<f64>,
+}
+impl ::core::convert::TryFrom<&::lexpr::Value> for Font {
+    type Error = ::kanga_sexpr::ParseError;
+    fn try_from(λv: &::lexpr::Value) -> ::core::result::Result<Self, Self::Error> {
+        fn parse_fields(
+            mut λv: &::lexpr::Value,
+        ) -> ::core::result::Result<Font, ::kanga_sexpr::ParseError> {
+            let size;
+            let left;
+            let right;
+            let Some(λ) = λv.as_cons() else {
+                return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()).into());
+            };
+            let α = λ.car();
+            let Some(β) = α.as_cons().filter(|β| β.car().as_symbol() == Some("size"))
+            else {
+                return Err(
+                    ::kanga_sexpr::ParseError::ExpectedNamedSym(
+                            α.clone(),
+                            ::kanga_sexpr::__private::String::from("size"),
+                        )
+                        .into(),
+                );
+            };
+            size = match <FontSize>::try_from(α) {
+                Ok(φ) => φ,
+                Err(ε) => {
+                    match β.cdr().as_cons() {
+                        Some(ψ) if ψ.cdr().is_null() => {
+                            <FontSize>::try_from(ψ.car()).map_err(|_| ε)?
+                        }
+                        _ => Err(ε)?,
+                    }
+                }
+            };
+            λv = λ.cdr();
+            let λn = ::lexpr::Value::Null;
+            let λi = match λv
+                .as_cons()
+                .and_then(|λ| {
+                    λ.car()
+                        .as_cons()
+                        .filter(|β| β.car().as_symbol() == Some("margins"))
+                        .map(|β| (λ, β))
+                })
+            {
+                Some((λ, β)) => {
+                    λv = λ.cdr();
+                    β.cdr()
+                }
+                None => &λn,
+            };
+            {
+                let mut λv = λi;
+                if let Some((λ, β)) = λv
+                    .as_cons()
+                    .and_then(|λ| {
+                        λ.car()
+                            .as_cons()
+                            .filter(|β| β.car().as_symbol() == Some("left"))
+                            .map(|β| (λ, β))
+                    })
+                {
+                    let α = λ.car();
+                    left = Some(
+                        β
+                            .cdr()
+                            .as_cons()
+                            .filter(|ψ| ψ.cdr().is_null())
+                            .and_then(|ψ| ψ.car().as_f64())
+                            .ok_or_else(|| ::kanga_sexpr::ParseError::ExpectedFloat(
+                                α.clone(),
+                            ))?,
+                    );
+                    λv = λ.cdr();
+                } else {
+                    left = None;
+                }
+                if let Some((λ, β)) = λv
+                    .as_cons()
+                    .and_then(|λ| {
+                        λ.car()
+                            .as_cons()
+                            .filter(|β| β.car().as_symbol() == Some("right"))
+                            .map(|β| (λ, β))
+                    })
+                {
+                    let α = λ.car();
+                    right = Some(
+                        β
+                            .cdr()
+                            .as_cons()
+                            .filter(|ψ| ψ.cdr().is_null())
+                            .and_then(|ψ| ψ.car().as_f64())
+                            .ok_or_else(|| ::kanga_sexpr::ParseError::ExpectedFloat(
+                                α.clone(),
+                            ))?,
+                    );
+                    λv = λ.cdr();
+                } else {
+                    right = None;
+                }
+                if let Some(λ) = λv.as_cons() {
+                    return Err(
+                        ::kanga_sexpr::ParseError::Unexpected(λ.car().clone()).into(),
+                    );
+                }
+            }
+            if let Some(λ) = λv.as_cons() {
+                return Err(
+                    ::kanga_sexpr::ParseError::Unexpected(λ.car().clone()).into(),
+                );
+            }
+            Ok(Font { size, left, right })
+        }
+        let Some(λ) = λv.as_cons() else {
+            return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()).into());
+        };
+        if λ.car().as_symbol() != Some("font") {
+            return Err(
+                ::kanga_sexpr::ParseError::ExpectedNamedSym(
+                        λv.clone(),
+                        ::kanga_sexpr::__private::String::from("font"),
+                    )
+                    .into(),
+            );
+        }
+        parse_fields(λ.cdr())
+            .map_err(|ε| ::kanga_sexpr::SexprError::within_list(ε, "font", λ))
+    }
+}
+impl ::kanga_sexpr::Node for Font {
+    fn sexpr_name(&self) -> &'static str {
+        "font"
+    }
+    fn children(&self) -> ::kanga_sexpr::__private::Vec<&dyn ::kanga_sexpr::Node> {
+        #[allow(unused_mut)]
+        let mut λc: ::kanga_sexpr::__private::Vec<&dyn ::kanga_sexpr::Node> = ::kanga_sexpr::__private::Vec::new();
+        let φ = &self.size;
+        λc.push(φ);
+        λc
+    }
+    fn children_mut(
+        &mut self,
+    ) -> ::kanga_sexpr::__private::Vec<&mut dyn ::kanga_sexpr::Node> {
+        #[allow(unused_mut)]
+        let mut λc: ::kanga_sexpr::__private::Vec<&mut dyn ::kanga_sexpr::Node> = ::kanga_sexpr::__private::Vec::new();
+        let φ = &mut self.size;
+        λc.push(φ);
+        λc
+    }
+    fn as_any(&self) -> &dyn ::core::any::Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn ::core::any::Any {
+        self
+    }
+}
+impl ::kanga_sexpr::ToSexpr for Font {
+    fn to_value(&self) -> ::lexpr::Value {
+        let mut λw = ::kanga_sexpr::__private::Vec::new();
+        λw.push(::lexpr::Value::symbol("font"));
+        let φ = &self.size;
+        λw.push(
+            ::kanga_sexpr::list_with_head("size", ::kanga_sexpr::ToSexpr::to_value(φ)),
+        );
+        let φ = {
+            let mut λw = ::kanga_sexpr::__private::Vec::new();
+            λw.push(::lexpr::Value::symbol("margins"));
+            if let Some(φ) = &self.left {
+                λw.push(
+                    ::lexpr::Value::list([
+                        ::lexpr::Value::symbol("left"),
+                        ::lexpr::Value::from(*φ),
+                    ]),
+                );
+            }
+            if let Some(φ) = &self.right {
+                λw.push(
+                    ::lexpr::Value::list([
+                        ::lexpr::Value::symbol("right"),
+                        ::lexpr::Value::from(*φ),
+                    ]),
+                );
+            }
+            λw
+        };
+        if φ.len() > 1 {
+            λw.push(::lexpr::Value::list(φ));
+        }
+        ::lexpr::Value::list(λw)
+    }
+}
+impl ::core::fmt::Display for Font {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        ::core::fmt::Display::fmt(&::kanga_sexpr::ToSexpr::to_value(self), f)
+    }
+}
+///The `(size ...)` list of a [`Font`].
+#[derive(Debug)]
+pub struct FontSize {
+    pub height: f64,
+    pub width: f64,
+}
+impl ::core::convert::TryFrom<&::lexpr::Value> for FontSize {
+    type Error = ::kanga_sexpr::ParseError;
+    fn try_from(λv: &::lexpr::Value) -> ::core::result::Result<Self, Self::Error> {
+        fn parse_fields(
+            mut λv: &::lexpr::Value,
+        ) -> ::core::result::Result<FontSize, ::kanga_sexpr::ParseError> {
+            let height;
+            let width;
+            let Some(λ) = λv.as_cons() else {
+                return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()).into());
+            };
+            let α = λ.car();
+            if let Some(φ) = α.as_f64() {
+                height = φ;
+                λv = λ.cdr();
+            } else {
+                return Err(::kanga_sexpr::ParseError::ExpectedFloat(α.clone()).into());
+            }
+            drop(α);
+            drop(λ);
+            let Some(λ) = λv.as_cons() else {
+                return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()).into());
+            };
+            let α = λ.car();
+            if let Some(φ) = α.as_f64() {
+                width = φ;
+                λv = λ.cdr();
+            } else {
+                return Err(::kanga_sexpr::ParseError::ExpectedFloat(α.clone()).into());
+            }
+            drop(α);
+            drop(λ);
+            if let Some(λ) = λv.as_cons() {
+                return Err(
+                    ::kanga_sexpr::ParseError::Unexpected(λ.car().clone()).into(),
+                );
+            }
+            Ok(FontSize { height, width })
+        }
+        let Some(λ) = λv.as_cons() else {
+            return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()).into());
+        };
+        if λ.car().as_symbol() != Some("size") {
+            return Err(
+                ::kanga_sexpr::ParseError::ExpectedNamedSym(
+                        λv.clone(),
+                        ::kanga_sexpr::__private::String::from("size"),
+                    )
+                    .into(),
+            );
+        }
+        parse_fields(λ.cdr())
+            .map_err(|ε| ::kanga_sexpr::SexprError::within_list(ε, "size", λ))
+    }
+}
+impl ::kanga_sexpr::Node for FontSize {
+    fn sexpr_name(&self) -> &'static str {
+        "size"
+    }
+    fn children(&self) -> ::kanga_sexpr::__private::Vec<&dyn ::kanga_sexpr::Node> {
+        #[allow(unused_mut)]
+        let mut λc: ::kanga_sexpr::__private::Vec<&dyn ::kanga_sexpr::Node> = ::kanga_sexpr::__private::Vec::new();
+        λc
+    }
+    fn children_mut(
+        &mut self,
+    ) -> ::kanga_sexpr::__private::Vec<&mut dyn ::kanga_sexpr::Node> {
+        #[allow(unused_mut)]
+        let mut λc: ::kanga_sexpr::__private::Vec<&mut dyn ::kanga_sexpr::Node> = ::kanga_sexpr::__private::Vec::new();
+        λc
+    }
+    fn as_any(&self) -> &dyn ::core::any::Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn ::core::any::Any {
+        self
+    }
+}
+impl ::kanga_sexpr::ToSexpr for FontSize {
+    fn to_value(&self) -> ::lexpr::Value {
+        let mut λw = ::kanga_sexpr::__private::Vec::new();
+        λw.push(::lexpr::Value::symbol("size"));
+        let φ = &self.height;
+        λw.push(::lexpr::Value::from(*φ));
+        let φ = &self.width;
+        λw.push(::lexpr::Value::from(*φ));
+        ::lexpr::Value::list(λw)
+    }
+}
+impl ::core::fmt::Display for FontSize {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        ::core::fmt::Display::fmt(&::kanga_sexpr::ToSexpr::to_value(self), f)
+    }
+}
@@ -0,0 +1,10 @@
+// A nested list declared as a struct of its own, and a flattened optional list.
+sexpr! {
+    #[derive(Debug)]
+    pub struct Font {
+        (font
+            (size: FontSize height: f64 width: f64)
+            [(margins (left: f64) [(right: f64)])]
+        )
+    }
+}
@@ -0,0 +1,183 @@
+#[derive(Debug, Default)]
+pub enum StrokeType {
+    Dash,
+    #[default]
+    Default,
+    solid,
+}
+impl ::core::convert::TryFrom<&::lexpr::Value> for StrokeType {
+    type Error = ::kanga_sexpr::ParseError;
+    fn try_from(value: &::lexpr::Value) -> ::core::result::Result<Self, Self::Error> {
+        const EXPECTED: &'static [&'static str] = &["dash", "default", "solid"];
+        let Some(sym) = value.as_symbol() else {
+            return Err(
+                ::kanga_sexpr::ParseError::ExpectedEnumSymbol(value.clone(), EXPECTED)
+                    .into(),
+            );
+        };
+        match sym {
+            "dash" => Ok(Self::Dash),
+            "default" => Ok(Self::Default),
+            "solid" => Ok(Self::solid),
+            _ => {
+                Err(
+                    ::kanga_sexpr::ParseError::ExpectedEnumSymbol(
+                            value.clone(),
+                            EXPECTED,
+                        )
+                        .into(),
+                )
+            }
+        }
+    }
+}
+impl ::kanga_sexpr::Node for StrokeType {
+    fn sexpr_name(&self) -> &'static str {
+        match self {
+            Self::Dash { .. } => "dash",
+            Self::Default { .. } => "default",
+            Self::solid { .. } => "solid",
+        }
+    }
+    fn children(&self) -> ::kanga_sexpr::__private::Vec<&dyn ::kanga_sexpr::Node> {
+        match self {
+            _ => ::kanga_sexpr::__private::Vec::new(),
+        }
+    }
+    fn children_mut(
+        &mut self,
+    ) -> ::kanga_sexpr::__private::Vec<&mut dyn ::kanga_sexpr::Node> {
+        match self {
+            _ => ::kanga_sexpr::__private::Vec::new(),
+        }
+    }
+    fn as_any(&self) -> &dyn ::core::any::Any {
+        match self {
+            _ => self,
+        }
+    }
+    fn as_any_mut(&mut self) -> &mut dyn ::core::any::Any {
+        match self {
+            _ => self,
+        }
+    }
+}
+impl ::kanga_sexpr::ToSexpr for StrokeType {
+    fn to_value(&self) -> ::lexpr::Value {
+        match self {
+            Self::Dash => ::lexpr::Value::symbol("dash"),
+            Self::Default => ::lexpr::Value::symbol("default"),
+            Self::solid => ::lexpr::Value::symbol("solid"),
+        }
+    }
+}
+impl ::core::fmt::Display for StrokeType {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        ::core::fmt::Display::fmt(&::kanga_sexpr::ToSexpr::to_value(self), f)
+    }
+}
+pub enum Graphic {
+    Arc(SymbolGraphicArc),
+    Circle(SymbolGraphicCircle),
+    Empty,
+}
+impl ::core::convert::TryFrom<&::lexpr::Value> for Graphic {
+    type Error = ::kanga_sexpr::ParseError;
+    fn try_from(value: &::lexpr::Value) -> ::core::result::Result<Self, Self::Error> {
+        const EXPECTED: &'static [&'static str] = &["arc", "circle", "none"];
+        if let Some(head) = value.as_cons().and_then(|λ| λ.car().as_symbol()) {
+            return match head {
+                "arc" => {
+                    <SymbolGraphicArc>::try_from(value)
+                        .map(Self::Arc)
+                        .map_err(Into::into)
+                }
+                "circle" => {
+                    <SymbolGraphicCircle>::try_from(value)
+                        .map(Self::Circle)
+                        .map_err(Into::into)
+                }
+                _ => {
+                    Err(
+                        ::kanga_sexpr::ParseError::ExpectedEnumSymbol(
+                                value.clone(),
+                                EXPECTED,
+                            )
+                            .into(),
+                    )
+                }
+            };
+        }
+        let Some(sym) = value.as_symbol() else {
+            return Err(
+                ::kanga_sexpr::ParseError::ExpectedEnumSymbol(value.clone(), EXPECTED)
+                    .into(),
+            );
+        };
+        match sym {
+            "none" => Ok(Self::Empty),
+            _ => {
+                Err(
+                    ::kanga_sexpr::ParseError::ExpectedEnumSymbol(
+                            value.clone(),
+                            EXPECTED,
+                        )
+                        .into(),
+                )
+            }
+        }
+    }
+}
+impl ::kanga_sexpr::Node for Graphic {
+    fn sexpr_name(&self) -> &'static str {
+        match self {
+            Self::Arc { .. } => "arc",
+            Self::Circle { .. } => "circle",
+            Self::Empty { .. } => "none",
+        }
+    }
+    fn children(&self) -> ::kanga_sexpr::__private::Vec<&dyn ::kanga_sexpr::Node> {
+        match self {
+            Self::Arc(φ) => ::kanga_sexpr::Node::children(φ),
+            Self::Circle(φ) => ::kanga_sexpr::Node::children(φ),
+            _ => ::kanga_sexpr::__private::Vec::new(),
+        }
+    }
+    fn children_mut(
+        &mut self,
+    ) -> ::kanga_sexpr::__private::Vec<&mut dyn ::kanga_sexpr::Node> {
+        match self {
+            Self::Arc(φ) => ::kanga_sexpr::Node::children_mut(φ),
+            Self::Circle(φ) => ::kanga_sexpr::Node::children_mut(φ),
+            _ => ::kanga_sexpr::__private::Vec::new(),
+        }
+    }
+    fn as_any(&self) -> &dyn ::core::any::Any {
+        match self {
+            Self::Arc(φ) => ::kanga_sexpr::Node::as_any(φ),
+            Self::Circle(φ) => ::kanga_sexpr::Node::as_any(φ),
+            _ => self,
+        }
+    }
+    fn as_any_mut(&mut self) -> &mut dyn ::core::any::Any {
+        match self {
+            Self::Arc(φ) => ::kanga_sexpr::Node::as_any_mut(φ),
+            Self::Circle(φ) => ::kanga_sexpr::Node::as_any_mut(φ),
+            _ => self,
+        }
+    }
+}
+impl ::kanga_sexpr::ToSexpr for Graphic {
+    fn to_value(&self) -> ::lexpr::Value {
+        match self {
+            Self::Arc(φ) => ::kanga_sexpr::ToSexpr::to_value(φ),
+            Self::Circle(φ) => ::kanga_sexpr::ToSexpr::to_value(φ),
+            Self::Empty => ::lexpr::Value::symbol("none"),
+        }
+    }
+}
+impl ::core::fmt::Display for Graphic {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        ::core::fmt::Display::fmt(&::kanga_sexpr::ToSexpr::to_value(self), f)
+    }
+}
@@ -0,0 +1,16 @@
+// Enums of bare symbols, with a default variant, and of variants wrapping a payload.
+sexpr! {
+    #[derive(Debug, Default)]
+    pub enum StrokeType {
+        dash => Dash,
+        #[default]
+        default => Default,
+        solid
+    }
+
+    pub enum Graphic {
+        arc => Arc(SymbolGraphicArc)
+        circle => Circle(SymbolGraphicCircle),
+        none => Empty
+    }
+}
@@ -0,0 +1,223 @@
+#[derive(Debug)]
+pub struct Polyline {
+    pub xy: ::kanga_sexpr::__private::Vec<XY>,
+    pub hide: bool,
+    pub fill: ::core::option::Option<Fill>,
+}
+impl ::core::convert::TryFrom<&::lexpr::Value> for Polyline {
+    type Error = ::kanga_sexpr::ParseError;
+    fn try_from(λv: &::lexpr::Value) -> ::core::result::Result<Self, Self::Error> {
+        fn parse_fields(
+            mut λv: &::lexpr::Value,
+        ) -> ::core::result::Result<Polyline, ::kanga_sexpr::ParseError> {
+            let mut xy = ::kanga_sexpr::__private::Vec::new();
+            let hide;
+            let fill;
+            let λi = match λv
+                .as_cons()
+                .and_then(|λ| {
+                    λ.car()
+                        .as_cons()
+                        .filter(|β| β.car().as_symbol() == Some("pts"))
+                        .map(|β| (λ, β))
+                })
+            {
+                Some((λ, β)) => {
+                    λv = λ.cdr();
+                    β.cdr()
+                }
+                None => {
+                    return Err(
+                        match λv.as_cons() {
+                            Some(λ) => {
+                                ::kanga_sexpr::ParseError::ExpectedNamedSym(
+                                    λ.car().clone(),
+                                    ::kanga_sexpr::__private::String::from("pts"),
+                                )
+                            }
+                            None => ::kanga_sexpr::ParseError::ExpectedList(λv.clone()),
+                        }
+                            .into(),
+                    );
+                }
+            };
+            {
+                let mut λv = λi;
+                while let Some(λ) = λv.as_cons() {
+                    let α = λ.car();
+                    let Some(β) = α
+                        .as_cons()
+                        .filter(|β| β.car().as_symbol() == Some("xy")) else {
+                        break;
+                    };
+                    xy.push(
+                        match <XY>::try_from(α) {
+                            Ok(φ) => φ,
+                            Err(ε) => {
+                                match β.cdr().as_cons() {
+                                    Some(ψ) if ψ.cdr().is_null() => {
+                                        <XY>::try_from(ψ.car()).map_err(|_| ε)?
+                                    }
+                                    _ => Err(ε)?,
+                                }
+                            }
+                        },
+                    );
+                    λv = λ.cdr();
+                }
+                if let Some(λ) = λv.as_cons() {
+                    return Err(
+                        ::kanga_sexpr::ParseError::Unexpected(λ.car().clone()).into(),
+                    );
+                }
+            }
+            let mut φ = false;
+            if let Some(λ) = λv.as_cons() {
+                let α = λ.car();
+                if α.as_symbol() == Some(stringify!(hide)) {
+                    φ = true;
+                    λv = λ.cdr();
+                } else if let Some(β) = α
+                    .as_cons()
+                    .filter(|β| β.car().as_symbol() == Some(stringify!(hide)))
+                {
+                    φ = match β
+                        .cdr()
+                        .as_cons()
+                        .filter(|ψ| ψ.cdr().is_null())
+                        .and_then(|ψ| ψ.car().as_symbol())
+                    {
+                        Some("yes") => true,
+                        Some("no") => false,
+                        _ => {
+                            return Err(
+                                ::kanga_sexpr::ParseError::Unexpected(α.clone()).into(),
+                            );
+                        }
+                    };
+                    λv = λ.cdr();
+                }
+            }
+            hide = φ;
+            if let Some((λ, β)) = λv
+                .as_cons()
+                .and_then(|λ| {
+                    λ.car()
+                        .as_cons()
+                        .filter(|β| β.car().as_symbol() == Some("fill"))
+                        .map(|β| (λ, β))
+                })
+            {
+                let α = λ.car();
+                fill = Some(
+                    match <Fill>::try_from(α) {
+                        Ok(φ) => φ,
+                        Err(ε) => {
+                            match β.cdr().as_cons() {
+                                Some(ψ) if ψ.cdr().is_null() => {
+                                    <Fill>::try_from(ψ.car()).map_err(|_| ε)?
+                                }
+                                _ => Err(ε)?,
+                            }
+                        }
+                    },
+                );
+                λv = λ.cdr();
+            } else {
+                fill = None;
+            }
+            if let Some(λ) = λv.as_cons() {
+                return Err(
+                    ::kanga_sexpr::ParseError::Unexpected(λ.car().clone()).into(),
+                );
+            }
+            Ok(Polyline { xy, hide, fill })
+        }
+        let Some(λ) = λv.as_cons() else {
+            return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()).into());
+        };
+        if λ.car().as_symbol() != Some("polyline") {
+            return Err(
+                ::kanga_sexpr::ParseError::ExpectedNamedSym(
+                        λv.clone(),
+                        ::kanga_sexpr::__private::String::from("polyline"),
+                    )
+                    .into(),
+            );
+        }
+        parse_fields(λ.cdr())
+            .map_err(|ε| ::kanga_sexpr::SexprError::within_list(ε, "polyline", λ))
+    }
+}
+impl ::kanga_sexpr::Node for Polyline {
+    fn sexpr_name(&self) -> &'static str {
+        "polyline"
+    }
+    fn children(&self) -> ::kanga_sexpr::__private::Vec<&dyn ::kanga_sexpr::Node> {
+        #[allow(unused_mut)]
+        let mut λc: ::kanga_sexpr::__private::Vec<&dyn ::kanga_sexpr::Node> = ::kanga_sexpr::__private::Vec::new();
+        for φ in &self.xy {
+            λc.push(φ);
+        }
+        if let Some(φ) = &self.fill {
+            λc.push(φ);
+        }
+        λc
+    }
+    fn children_mut(
+        &mut self,
+    ) -> ::kanga_sexpr::__private::Vec<&mut dyn ::kanga_sexpr::Node> {
+        #[allow(unused_mut)]
+        let mut λc: ::kanga_sexpr::__private::Vec<&mut dyn ::kanga_sexpr::Node> = ::kanga_sexpr::__private::Vec::new();
+        for φ in &mut self.xy {
+            λc.push(φ);
+        }
+        if let Some(φ) = &mut self.fill {
+            λc.push(φ);
+        }
+        λc
+    }
+    fn as_any(&self) -> &dyn ::core::any::Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn ::core::any::Any {
+        self
+    }
+}
+impl ::kanga_sexpr::ToSexpr for Polyline {
+    fn to_value(&self) -> ::lexpr::Value {
+        let mut λw = ::kanga_sexpr::__private::Vec::new();
+        λw.push(::lexpr::Value::symbol("polyline"));
+        let φ = {
+            let mut λw = ::kanga_sexpr::__private::Vec::new();
+            λw.push(::lexpr::Value::symbol("pts"));
+            for φ in &self.xy {
+                λw.push(
+                    ::kanga_sexpr::list_with_head(
+                        "xy",
+                        ::kanga_sexpr::ToSexpr::to_value(φ),
+                    ),
+                );
+            }
+            λw
+        };
+        λw.push(::lexpr::Value::list(φ));
+        if self.hide {
+            λw.push(::lexpr::Value::symbol("hide"));
+        }
+        if let Some(φ) = &self.fill {
+            λw.push(
+                ::kanga_sexpr::list_with_head(
+                    "fill",
+                    ::kanga_sexpr::ToSexpr::to_value(φ),
+                ),
+            );
+        }
+        ::lexpr::Value::list(λw)
+    }
+}
+impl ::core::fmt::Display for Polyline {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        ::core::fmt::Display::fmt(&::kanga_sexpr::ToSexpr::to_value(self), f)
+    }
+}
@@ -0,0 +1,11 @@
+// A repeated list and symbol flags.
+sexpr! {
+    #[derive(Debug)]
+    pub struct Polyline {
+        (polyline
+            (pts (xy: XY)*)
+            [hide]
+            [(fill: Fill)]
+        )
+    }
+}
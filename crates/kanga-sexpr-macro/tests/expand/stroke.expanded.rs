@@ -0,0 +1,231 @@
+#[derive(Debug)]
+pub struct Stroke {
+    pub width: f64,
+    pub stroke_type: StrokeType,
+    pub color: Color,
+}
+impl ::core::convert::TryFrom<&::lexpr::Value> for Stroke {
+    type Error = ::kanga_sexpr::ParseError;
+    fn try_from(λv: &::lexpr::Value) -> ::core::result::Result<Self, Self::Error> {
+        fn parse_fields(
+            mut λv: &::lexpr::Value,
+        ) -> ::core::result::Result<Stroke, ::kanga_sexpr::ParseError> {
+            let width;
+            let stroke_type;
+            let color;
+            {
+                let λo;
+                if let Some((λ, β)) = λv
+                    .as_cons()
+                    .and_then(|λ| {
+                        λ.car()
+                            .as_cons()
+                            .filter(|β| β.car().as_symbol() == Some("width"))
+                            .map(|β| (λ, β))
+                    })
+                {
+                    let α = λ.car();
+                    λo = Some(
+                        β
+                            .cdr()
+                            .as_cons()
+                            .filter(|ψ| ψ.cdr().is_null())
+                            .and_then(|ψ| ψ.car().as_f64())
+                            .ok_or_else(|| ::kanga_sexpr::ParseError::ExpectedFloat(
+                                α.clone(),
+                            ))?,
+                    );
+                    λv = λ.cdr();
+                } else {
+                    λo = None;
+                }
+                width = match λo {
+                    Some(φ) => φ,
+                    None => 0.0,
+                };
+            }
+            {
+                let λo;
+                if let Some((λ, β)) = λv
+                    .as_cons()
+                    .and_then(|λ| {
+                        λ.car()
+                            .as_cons()
+                            .filter(|β| β.car().as_symbol() == Some("type"))
+                            .map(|β| (λ, β))
+                    })
+                {
+                    let α = λ.car();
+                    λo = Some(
+                        match <StrokeType>::try_from(α) {
+                            Ok(φ) => φ,
+                            Err(ε) => {
+                                match β.cdr().as_cons() {
+                                    Some(ψ) if ψ.cdr().is_null() => {
+                                        <StrokeType>::try_from(ψ.car()).map_err(|_| ε)?
+                                    }
+                                    _ => Err(ε)?,
+                                }
+                            }
+                        },
+                    );
+                    λv = λ.cdr();
+                } else {
+                    λo = None;
+                }
+                stroke_type = match λo {
+                    Some(φ) => φ,
+                    None => StrokeType::Default,
+                };
+            }
+            let Some(λ) = λv.as_cons() else {
+                return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()).into());
+            };
+            let α = λ.car();
+            let Some(β) = α
+                .as_cons()
+                .filter(|β| β.car().as_symbol() == Some("color")) else {
+                return Err(
+                    ::kanga_sexpr::ParseError::ExpectedNamedSym(
+                            α.clone(),
+                            ::kanga_sexpr::__private::String::from("color"),
+                        )
+                        .into(),
+                );
+            };
+            color = match <Color>::try_from(α) {
+                Ok(φ) => φ,
+                Err(ε) => {
+                    match β.cdr().as_cons() {
+                        Some(ψ) if ψ.cdr().is_null() => {
+                            <Color>::try_from(ψ.car()).map_err(|_| ε)?
+                        }
+                        _ => Err(ε)?,
+                    }
+                }
+            };
+            λv = λ.cdr();
+            if let Some(λ) = λv.as_cons() {
+                return Err(
+                    ::kanga_sexpr::ParseError::Unexpected(λ.car().clone()).into(),
+                );
+            }
+            Ok(Stroke {
+                width,
+                stroke_type,
+                color,
+            })
+        }
+        fn reorder_fields(
+            mut λv: &::lexpr::Value,
+        ) -> ::core::result::Result<::lexpr::Value, ::kanga_sexpr::ParseError> {
+            let λk: [(&str, bool); 4usize] = [
+                ("", true),
+                ("width", false),
+                ("type", false),
+                ("color", false),
+            ];
+            let mut λs: [::kanga_sexpr::__private::Vec<::lexpr::Value>; 4usize] = ::core::array::from_fn(|
+                _|
+            ::kanga_sexpr::__private::Vec::new());
+            while let Some(λ) = λv.as_cons() {
+                let α = λ.car();
+                let λn = match (
+                    α.as_cons().and_then(|β| β.car().as_symbol()),
+                    α.as_symbol(),
+                ) {
+                    (Some("width"), _) => 1usize,
+                    (Some("type"), _) => 2usize,
+                    (Some("color"), _) => 3usize,
+                    _ => 0,
+                };
+                if !λk[λn].1 && !λs[λn].is_empty() {
+                    return Err(
+                        ::kanga_sexpr::ParseError::DuplicateField(
+                                ::kanga_sexpr::__private::String::from("Stroke"),
+                                ::kanga_sexpr::__private::String::from(λk[λn].0),
+                                α.clone(),
+                            )
+                            .into(),
+                    );
+                }
+                λs[λn].push(α.clone());
+                λv = λ.cdr();
+            }
+            Ok(::lexpr::Value::list(λs.into_iter().flatten()))
+        }
+        let Some(λ) = λv.as_cons() else {
+            return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()).into());
+        };
+        if λ.car().as_symbol() != Some("stroke") {
+            return Err(
+                ::kanga_sexpr::ParseError::ExpectedNamedSym(
+                        λv.clone(),
+                        ::kanga_sexpr::__private::String::from("stroke"),
+                    )
+                    .into(),
+            );
+        }
+        reorder_fields(λ.cdr())
+            .and_then(|λr| parse_fields(&λr))
+            .map_err(|ε| ::kanga_sexpr::SexprError::within_list(ε, "stroke", λ))
+    }
+}
+impl ::kanga_sexpr::Node for Stroke {
+    fn sexpr_name(&self) -> &'static str {
+        "stroke"
+    }
+    fn children(&self) -> ::kanga_sexpr::__private::Vec<&dyn ::kanga_sexpr::Node> {
+        #[allow(unused_mut)]
+        let mut λc: ::kanga_sexpr::__private::Vec<&dyn ::kanga_sexpr::Node> = ::kanga_sexpr::__private::Vec::new();
+        let φ = &self.stroke_type;
+        λc.push(φ);
+        let φ = &self.color;
+        λc.push(φ);
+        λc
+    }
+    fn children_mut(
+        &mut self,
+    ) -> ::kanga_sexpr::__private::Vec<&mut dyn ::kanga_sexpr::Node> {
+        #[allow(unused_mut)]
+        let mut λc: ::kanga_sexpr::__private::Vec<&mut dyn ::kanga_sexpr::Node> = ::kanga_sexpr::__private::Vec::new();
+        let φ = &mut self.stroke_type;
+        λc.push(φ);
+        let φ = &mut self.color;
+        λc.push(φ);
+        λc
+    }
+    fn as_any(&self) -> &dyn ::core::any::Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn ::core::any::Any {
+        self
+    }
+}
+impl ::kanga_sexpr::ToSexpr for Stroke {
+    fn to_value(&self) -> ::lexpr::Value {
+        let mut λw = ::kanga_sexpr::__private::Vec::new();
+        λw.push(::lexpr::Value::symbol("stroke"));
+        let φ = &self.width;
+        λw.push(
+            ::lexpr::Value::list([
+                ::lexpr::Value::symbol("width"),
+                ::lexpr::Value::from(*φ),
+            ]),
+        );
+        let φ = &self.stroke_type;
+        λw.push(
+            ::kanga_sexpr::list_with_head("type", ::kanga_sexpr::ToSexpr::to_value(φ)),
+        );
+        let φ = &self.color;
+        λw.push(
+            ::kanga_sexpr::list_with_head("color", ::kanga_sexpr::ToSexpr::to_value(φ)),
+        );
+        ::lexpr::Value::list(λw)
+    }
+}
+impl ::core::fmt::Display for Stroke {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        ::core::fmt::Display::fmt(&::kanga_sexpr::ToSexpr::to_value(self), f)
+    }
+}
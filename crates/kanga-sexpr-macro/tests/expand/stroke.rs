@@ -0,0 +1,12 @@
+// An unordered struct with defaulted optional lists, a renamed field, and a nested type.
+sexpr! {
+    #[sexpr(unordered)]
+    #[derive(Debug)]
+    pub struct Stroke {
+        (stroke
+            [(width: f64 = 0.0)]
+            [(r#type => stroke_type: StrokeType = StrokeType::Default)]
+            (color: Color)
+        )
+    }
+}
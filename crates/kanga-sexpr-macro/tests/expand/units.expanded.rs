@@ -0,0 +1,218 @@
+#[derive(Debug)]
+pub struct Circle {
+    pub center: XY,
+    pub radius: <::kanga_sexpr::units::nm as ::kanga_sexpr::units::Unit>::Value,
+    pub width: <::kanga_sexpr::units::nm as ::kanga_sexpr::units::Unit>::Value,
+}
+impl ::core::convert::TryFrom<&::lexpr::Value> for Circle {
+    type Error = ::kanga_sexpr::ParseError;
+    fn try_from(λv: &::lexpr::Value) -> ::core::result::Result<Self, Self::Error> {
+        fn parse_fields(
+            mut λv: &::lexpr::Value,
+        ) -> ::core::result::Result<Circle, ::kanga_sexpr::ParseError> {
+            let center;
+            let radius;
+            let width;
+            let Some(λ) = λv.as_cons() else {
+                return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()).into());
+            };
+            let α = λ.car();
+            let Some(β) = α
+                .as_cons()
+                .filter(|β| β.car().as_symbol() == Some("center")) else {
+                return Err(
+                    ::kanga_sexpr::ParseError::ExpectedNamedSym(
+                            α.clone(),
+                            ::kanga_sexpr::__private::String::from("center"),
+                        )
+                        .into(),
+                );
+            };
+            center = match <XY>::try_from(α) {
+                Ok(φ) => φ,
+                Err(ε) => {
+                    match β.cdr().as_cons() {
+                        Some(ψ) if ψ.cdr().is_null() => {
+                            <XY>::try_from(ψ.car()).map_err(|_| ε)?
+                        }
+                        _ => Err(ε)?,
+                    }
+                }
+            };
+            λv = λ.cdr();
+            let Some(λ) = λv.as_cons() else {
+                return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()).into());
+            };
+            let α = λ.car();
+            let Some(β) = α
+                .as_cons()
+                .filter(|β| β.car().as_symbol() == Some("radius")) else {
+                return Err(
+                    ::kanga_sexpr::ParseError::ExpectedNamedSym(
+                            α.clone(),
+                            ::kanga_sexpr::__private::String::from("radius"),
+                        )
+                        .into(),
+                );
+            };
+            radius = β
+                .cdr()
+                .as_cons()
+                .filter(|ψ| ψ.cdr().is_null())
+                .and_then(|ψ| <::kanga_sexpr::units::nm as ::kanga_sexpr::units::Unit>::from_sexpr(
+                    ψ.car(),
+                ))
+                .ok_or_else(|| ::kanga_sexpr::ParseError::ExpectedFloat(α.clone()))?;
+            λv = λ.cdr();
+            {
+                let λo;
+                if let Some((λ, β)) = λv
+                    .as_cons()
+                    .and_then(|λ| {
+                        λ.car()
+                            .as_cons()
+                            .filter(|β| β.car().as_symbol() == Some("width"))
+                            .map(|β| (λ, β))
+                    })
+                {
+                    let α = λ.car();
+                    λo = Some(
+                        β
+                            .cdr()
+                            .as_cons()
+                            .filter(|ψ| ψ.cdr().is_null())
+                            .and_then(|ψ| <::kanga_sexpr::units::nm as ::kanga_sexpr::units::Unit>::from_sexpr(
+                                ψ.car(),
+                            ))
+                            .ok_or_else(|| ::kanga_sexpr::ParseError::ExpectedFloat(
+                                α.clone(),
+                            ))?,
+                    );
+                    λv = λ.cdr();
+                } else {
+                    λo = None;
+                }
+                width = match λo {
+                    Some(φ) => φ,
+                    None => 0,
+                };
+            }
+            if let Some(λ) = λv.as_cons() {
+                return Err(
+                    ::kanga_sexpr::ParseError::Unexpected(λ.car().clone()).into(),
+                );
+            }
+            Ok(Circle { center, radius, width })
+        }
+        fn reorder_fields(
+            mut λv: &::lexpr::Value,
+        ) -> ::core::result::Result<::lexpr::Value, ::kanga_sexpr::ParseError> {
+            let λk: [(&str, bool); 4usize] = [
+                ("", true),
+                ("center", false),
+                ("radius", false),
+                ("width", false),
+            ];
+            let mut λs: [::kanga_sexpr::__private::Vec<::lexpr::Value>; 4usize] = ::core::array::from_fn(|
+                _|
+            ::kanga_sexpr::__private::Vec::new());
+            while let Some(λ) = λv.as_cons() {
+                let α = λ.car();
+                let λn = match (
+                    α.as_cons().and_then(|β| β.car().as_symbol()),
+                    α.as_symbol(),
+                ) {
+                    (Some("center"), _) => 1usize,
+                    (Some("radius"), _) => 2usize,
+                    (Some("width"), _) => 3usize,
+                    _ => 0,
+                };
+                if !λk[λn].1 && !λs[λn].is_empty() {
+                    return Err(
+                        ::kanga_sexpr::ParseError::DuplicateField(
+                                ::kanga_sexpr::__private::String::from("Circle"),
+                                ::kanga_sexpr::__private::String::from(λk[λn].0),
+                                α.clone(),
+                            )
+                            .into(),
+                    );
+                }
+                λs[λn].push(α.clone());
+                λv = λ.cdr();
+            }
+            Ok(::lexpr::Value::list(λs.into_iter().flatten()))
+        }
+        let Some(λ) = λv.as_cons() else {
+            return Err(::kanga_sexpr::ParseError::ExpectedList(λv.clone()).into());
+        };
+        if λ.car().as_symbol() != Some("circle") {
+            return Err(
+                ::kanga_sexpr::ParseError::ExpectedNamedSym(
+                        λv.clone(),
+                        ::kanga_sexpr::__private::String::from("circle"),
+                    )
+                    .into(),
+            );
+        }
+        reorder_fields(λ.cdr())
+            .and_then(|λr| parse_fields(&λr))
+            .map_err(|ε| ::kanga_sexpr::SexprError::within_list(ε, "circle", λ))
+    }
+}
+impl ::kanga_sexpr::Node for Circle {
+    fn sexpr_name(&self) -> &'static str {
+        "circle"
+    }
+    fn children(&self) -> ::kanga_sexpr::__private::Vec<&dyn ::kanga_sexpr::Node> {
+        #[allow(unused_mut)]
+        let mut λc: ::kanga_sexpr::__private::Vec<&dyn ::kanga_sexpr::Node> = ::kanga_sexpr::__private::Vec::new();
+        let φ = &self.center;
+        λc.push(φ);
+        λc
+    }
+    fn children_mut(
+        &mut self,
+    ) -> ::kanga_sexpr::__private::Vec<&mut dyn ::kanga_sexpr::Node> {
+        #[allow(unused_mut)]
+        let mut λc: ::kanga_sexpr::__private::Vec<&mut dyn ::kanga_sexpr::Node> = ::kanga_sexpr::__private::Vec::new();
+        let φ = &mut self.center;
+        λc.push(φ);
+        λc
+    }
+    fn as_any(&self) -> &dyn ::core::any::Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn ::core::any::Any {
+        self
+    }
+}
+impl ::kanga_sexpr::ToSexpr for Circle {
+    fn to_value(&self) -> ::lexpr::Value {
+        let mut λw = ::kanga_sexpr::__private::Vec::new();
+        λw.push(::lexpr::Value::symbol("circle"));
+        let φ = &self.center;
+        λw.push(
+            ::kanga_sexpr::list_with_head("center", ::kanga_sexpr::ToSexpr::to_value(φ)),
+        );
+        let φ = &self.radius;
+        λw.push(
+            ::lexpr::Value::list([
+                ::lexpr::Value::symbol("radius"),
+                <::kanga_sexpr::units::nm as ::kanga_sexpr::units::Unit>::to_sexpr(φ),
+            ]),
+        );
+        let φ = &self.width;
+        λw.push(
+            ::lexpr::Value::list([
+                ::lexpr::Value::symbol("width"),
+                <::kanga_sexpr::units::nm as ::kanga_sexpr::units::Unit>::to_sexpr(φ),
+            ]),
+        );
+        ::lexpr::Value::list(λw)
+    }
+}
+impl ::core::fmt::Display for Circle {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        ::core::fmt::Display::fmt(&::kanga_sexpr::ToSexpr::to_value(self), f)
+    }
+}
@@ -0,0 +1,12 @@
+// Lengths written in millimeters and stored in nanometers, one with a default.
+sexpr! {
+    #[sexpr(unordered)]
+    #[derive(Debug)]
+    pub struct Circle {
+        (circle
+            (center: XY)
+            (radius: f64 as nm)
+            [(width: f64 as nm = 0)]
+        )
+    }
+}
@@ -1,9 +1,10 @@
 use {
-    lexpr::Value,
-    std::{
+    alloc::{boxed::Box, string::String, vec::Vec},
+    core::{
         error::Error,
         fmt::{Display, Formatter, Result as FmtResult},
     },
+    lexpr::{Cons, Value},
 };
 
 #[derive(Debug)]
@@ -23,6 +24,7 @@ pub enum ParseError {
     InvalidWidth(f64),
     MissingField(String, String, Value),
     Unexpected(Value),
+    Within(String, Box<ParseError>),
 }
 
 impl ParseError {
@@ -34,6 +36,35 @@ impl ParseError {
     {
         Self::MissingField(struct_name.into(), field_name.into(), value.into())
     }
+
+    /// Record that this error occurred within the element `element`.
+    pub fn within<E>(self, element: E) -> Self
+    where
+        E: Into<String>,
+    {
+        Self::Within(element.into(), Box::new(self))
+    }
+
+    /// Return the path of elements, outermost first, in which this error occurred.
+    pub fn breadcrumb(&self) -> Vec<&str> {
+        let mut path = Vec::new();
+        let mut error = self;
+
+        while let Self::Within(element, inner) = error {
+            path.push(element.as_str());
+            error = inner;
+        }
+
+        path
+    }
+
+    /// Return the underlying error without any element context.
+    pub fn root_cause(&self) -> &ParseError {
+        match self {
+            Self::Within(_, inner) => inner.root_cause(),
+            _ => self,
+        }
+    }
 }
 
 impl Display for ParseError {
@@ -55,8 +86,25 @@ impl Display for ParseError {
             Self::InvalidWidth(width) => write!(f, "Invalid width value {width}"),
             Self::MissingField(struct_name, field_name, value) => write!(f, "Missing {struct_name} field {field_name}: {value}"),
             Self::Unexpected(value) => write!(f, "Unexpected value {value}"),
+            Self::Within(..) => write!(f, "{}: {}", self.breadcrumb().join(" > "), self.root_cause()),
         }
     }
 }
 
 impl Error for ParseError {}
+
+/// The error returned by the parsers [`sexpr!`](crate::sexpr) generates.
+///
+/// This is [`ParseError`] unless a declaration names another type with `#[sexpr(error = Type)]`,
+/// so that the types a crate declares return the same error as the parsers it writes by hand. The
+/// generated code reports what it finds wrong as a [`ParseError`] and converts it.
+pub trait SexprError: From<ParseError> {
+    /// Record that this error occurred within `element`, the list for the type `name`.
+    fn within_list(self, name: &'static str, element: &Cons) -> Self;
+}
+
+impl SexprError for ParseError {
+    fn within_list(self, name: &'static str, _element: &Cons) -> Self {
+        self.within(name)
+    }
+}
@@ -1,5 +1,6 @@
 use {
     crate::ParseError,
+    alloc::string::ToString,
     lexpr::{Cons, Value},
 };
 
@@ -1,4 +1,23 @@
+// The crate only needs `core` and `alloc`. `lexpr` still links `std`, so this does not yet build
+// for targets without it.
+#![no_std]
+
+extern crate alloc;
+
 mod error;
 mod lexpr_ext;
+mod node;
+mod to_sexpr;
+pub mod units;
+
+pub use {error::*, lexpr_ext::*, kanga_sexpr_macro::sexpr, node::*, to_sexpr::*};
 
-pub use {error::*, lexpr_ext::*, kanga_sexpr_macro::sexpr};
+/// Paths used by code generated by [`sexpr!`], so that it resolves the same way in `no_std`
+/// crates, which have no `alloc` types in their prelude.
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::{
+        string::{String, ToString},
+        vec::Vec,
+    };
+}
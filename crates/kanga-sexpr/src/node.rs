@@ -0,0 +1,64 @@
+use {alloc::vec::Vec, core::any::Any};
+
+/// A type declared with [`sexpr!`](crate::sexpr) that can be traversed generically.
+///
+/// `sexpr!` implements this for every struct and enum it declares. A struct's children are its
+/// fields whose types are not scalars (numbers, strings, UUIDs, or flags), in declaration order;
+/// optional fields that are absent are skipped and vector fields contribute each element. Field
+/// types that are not scalars must therefore implement `Node` themselves, which they do if they
+/// were also declared with `sexpr!`. An enum variant with a payload stands in for the payload,
+/// returning its name, children, and [`Any`].
+pub trait Node: Any {
+    /// Return the s-expression name of this node: the list head for a struct, or the symbol for an
+    /// enum variant.
+    fn sexpr_name(&self) -> &'static str;
+
+    /// Return the nodes directly contained in this node.
+    fn children(&self) -> Vec<&dyn Node>;
+
+    /// Return the nodes directly contained in this node, mutably.
+    fn children_mut(&mut self) -> Vec<&mut dyn Node>;
+
+    /// Return this node as [`Any`] so a visitor can downcast it to its concrete type.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Return this node as mutable [`Any`] so a visitor can downcast it to its concrete type.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+/// Visit `node` and all of its descendants, depth first in declaration order.
+///
+/// If `f` returns `false`, the children of that node are skipped.
+pub fn walk_nodes<F>(node: &dyn Node, mut f: F)
+where
+    F: FnMut(&dyn Node) -> bool,
+{
+    fn walk(node: &dyn Node, f: &mut dyn FnMut(&dyn Node) -> bool) {
+        if f(node) {
+            for child in node.children() {
+                walk(child, f);
+            }
+        }
+    }
+
+    walk(node, &mut f);
+}
+
+/// Visit `node` and all of its descendants mutably, depth first in declaration order.
+///
+/// If `f` returns `false`, the children of that node are skipped. Children are listed after `f`
+/// returns, so changes `f` makes to a node are reflected in what is visited below it.
+pub fn walk_nodes_mut<F>(node: &mut dyn Node, mut f: F)
+where
+    F: FnMut(&mut dyn Node) -> bool,
+{
+    fn walk(node: &mut dyn Node, f: &mut dyn FnMut(&mut dyn Node) -> bool) {
+        if f(&mut *node) {
+            for child in node.children_mut() {
+                walk(child, f);
+            }
+        }
+    }
+
+    walk(node, &mut f);
+}
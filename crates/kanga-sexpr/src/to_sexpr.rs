@@ -0,0 +1,23 @@
+use lexpr::Value;
+
+/// A type declared with [`sexpr!`](crate::sexpr) that can be written back as an s-expression.
+///
+/// `sexpr!` implements this, along with [`Display`](core::fmt::Display), for every struct and enum
+/// it declares. A struct is written as a list headed by its s-expression name, with its fields in
+/// declaration order: optional fields that are absent and flags that are not set are omitted, and
+/// vector fields contribute each element. An enum is written as the symbol of its variant, or as its payload if it has one. Fields
+/// named `_` are not stored, so they cannot be written back.
+pub trait ToSexpr {
+    /// Return the s-expression for this value.
+    fn to_value(&self) -> Value;
+}
+
+/// Return `value` as the list `(head ...)`: unchanged if it is already a list with that head, as
+/// a struct declared with [`sexpr!`](crate::sexpr) is, or wrapped otherwise, as for an enum
+/// written as `(type dash)`.
+pub fn list_with_head(head: &'static str, value: Value) -> Value {
+    match value.as_cons() {
+        Some(cons) if cons.car().as_symbol() == Some(head) => value,
+        _ => Value::list([Value::symbol(head), value]),
+    }
+}
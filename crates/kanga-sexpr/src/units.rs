@@ -0,0 +1,59 @@
+//! Unit policies for numeric fields declared with `as`, such as `width: f64 as nm`.
+//!
+//! KiCad writes lengths in millimeters but works in whole nanometers, so that coordinates compare
+//! exactly. A unit policy converts between the number written in a document and the value stored
+//! in the field, and back again when the field is written.
+
+use lexpr::Value;
+
+/// A conversion between a number written in a document and the value stored in a field.
+///
+/// A field declared as `name: f64 as U` has the type `U::Value`. A bare lowercase unit name, such
+/// as `nm`, refers to a policy in this module; any other path names a type implementing this
+/// trait, so crates can supply their own.
+///
+/// The policy is handed the value as read, rather than an `f64`, so that it can convert the number
+/// exactly or accept numbers kept in another form, such as the text of a decimal.
+pub trait Unit {
+    /// The type of the field.
+    type Value;
+
+    /// Convert a number read from a document into a field value, or return `None` if the value is
+    /// not a number.
+    fn from_sexpr(value: &Value) -> Option<Self::Value>;
+
+    /// Convert a field value into the number written to a document.
+    fn to_sexpr(value: &Self::Value) -> Value;
+}
+
+/// Millimeters in the document, stored as whole nanometers in an `i64`.
+#[allow(non_camel_case_types)]
+#[derive(Debug)]
+pub enum nm {}
+
+impl Unit for nm {
+    type Value = i64;
+
+    fn from_sexpr(value: &Value) -> Option<i64> {
+        value.as_f64().map(mm_to_nm)
+    }
+
+    fn to_sexpr(value: &i64) -> Value {
+        Value::from(*value as f64 / 1e6)
+    }
+}
+
+/// Convert millimeters to the nearest whole nanometer.
+///
+/// Multiplying by 1e6 can land just short of a whole nanometer, as for `1.27`, so the result is
+/// rounded rather than truncated.
+pub fn mm_to_nm(mm: f64) -> i64 {
+    let nm = mm * 1e6;
+
+    // `f64::round` needs `std`; casting truncates toward zero, so offset by a half first.
+    if nm < 0.0 {
+        (nm - 0.5) as i64
+    } else {
+        (nm + 0.5) as i64
+    }
+}
@@ -0,0 +1,9 @@
+//! Compile tests for the `sexpr!` macro: declarations that must build, and malformed ones that
+//! must fail with a useful error. Run with `TRYBUILD=overwrite` to update the expected errors.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass/*.rs");
+    t.compile_fail("tests/ui/fail/*.rs");
+}
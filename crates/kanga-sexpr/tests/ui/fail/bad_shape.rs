@@ -0,0 +1,9 @@
+use kanga_sexpr::sexpr;
+
+sexpr! {
+    pub struct Color {
+        (color "red" green: f64 blue: f64)
+    }
+}
+
+fn main() {}
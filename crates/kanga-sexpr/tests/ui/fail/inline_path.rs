@@ -0,0 +1,9 @@
+use kanga_sexpr::sexpr;
+
+sexpr! {
+    pub struct Sheet {
+        (sheet (size: geometry::Size width: f64 height: f64))
+    }
+}
+
+fn main() {}
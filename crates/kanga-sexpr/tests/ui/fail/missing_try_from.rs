@@ -0,0 +1,11 @@
+use kanga_sexpr::sexpr;
+
+pub struct Color;
+
+sexpr! {
+    pub struct Stroke {
+        (stroke (width: f64) (color: Color))
+    }
+}
+
+fn main() {}
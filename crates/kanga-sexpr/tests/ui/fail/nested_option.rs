@@ -0,0 +1,9 @@
+use kanga_sexpr::sexpr;
+
+sexpr! {
+    pub struct Stroke {
+        (stroke [[(width: f64)]])
+    }
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use kanga_sexpr::sexpr;
+
+sexpr! {
+    pub fn color() {}
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use kanga_sexpr::sexpr;
+
+sexpr! {
+    pub struct Points {
+        (pts [(xy: Xy)*])
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use kanga_sexpr::sexpr;
+
+sexpr! {
+    #[sexpr(unordered)]
+    pub struct Property {
+        (property (id: i64) name: String)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use kanga_sexpr::sexpr;
+
+sexpr! {
+    pub struct Text {
+        (text value: String [hide]*)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use kanga_sexpr::sexpr;
+
+sexpr! {
+    pub struct Property {
+        (property name: String as nm)
+    }
+}
+
+fn main() {}
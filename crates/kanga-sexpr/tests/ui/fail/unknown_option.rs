@@ -0,0 +1,10 @@
+use kanga_sexpr::sexpr;
+
+sexpr! {
+    #[sexpr(sorted)]
+    pub struct Color {
+        (color red: f64 green: f64 blue: f64)
+    }
+}
+
+fn main() {}
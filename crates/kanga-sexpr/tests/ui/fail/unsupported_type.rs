@@ -0,0 +1,9 @@
+use kanga_sexpr::sexpr;
+
+sexpr! {
+    pub struct Size {
+        (size dimensions: (f64, f64))
+    }
+}
+
+fn main() {}
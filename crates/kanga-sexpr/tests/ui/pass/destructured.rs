@@ -0,0 +1,35 @@
+use {
+    kanga_sexpr::{sexpr, ToSexpr},
+    lexpr::sexp,
+};
+
+sexpr! {
+    #[derive(Debug)]
+    pub struct Sheet {
+        (sheet
+            // Flattened into the struct.
+            (at x: f64 y: f64)
+
+            // Declared as a struct of its own.
+            (size: SheetSize width: f64 height: f64)
+
+            // Absent items are left `None`.
+            [(margins (left: f64) [(right: f64)])]
+        )
+    }
+}
+
+fn main() {
+    let value = sexp!((sheet (at 1.0 2.0) (size 30.0 40.0) (margins (left 5.0))));
+    let sheet = Sheet::try_from(&value).unwrap();
+    assert_eq!((sheet.x, sheet.y), (1.0, 2.0));
+    assert_eq!((sheet.size.width, sheet.size.height), (30.0, 40.0));
+    assert_eq!((sheet.left, sheet.right), (Some(5.0), None));
+    assert_eq!(sheet.to_value(), value);
+    assert_eq!(sheet.size.to_string(), "(size 30.0 40.0)");
+
+    let sheet = Sheet::try_from(&sexp!((sheet (at 1.0 2.0) (size 30.0 40.0)))).unwrap();
+    assert_eq!(sheet.left, None);
+
+    assert!(Sheet::try_from(&sexp!((sheet (size 30.0 40.0)))).is_err());
+}
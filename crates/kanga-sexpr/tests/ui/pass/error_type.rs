@@ -0,0 +1,67 @@
+use {
+    kanga_sexpr::{sexpr, ParseError, SexprError},
+    lexpr::{sexp, Cons},
+};
+
+#[derive(Debug)]
+pub enum MyError {
+    Parse(ParseError),
+    Within(&'static str, Box<MyError>),
+    Negative,
+}
+
+impl From<ParseError> for MyError {
+    fn from(e: ParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+impl SexprError for MyError {
+    fn within_list(self, name: &'static str, _element: &Cons) -> Self {
+        Self::Within(name, Box::new(self))
+    }
+}
+
+sexpr! {
+    #[sexpr(error = MyError, check = Size::check)]
+    #[derive(Debug)]
+    pub struct Size {
+        (size
+            height: f64
+            width: f64
+        )
+    }
+}
+
+sexpr! {
+    #[sexpr(error = MyError)]
+    #[derive(Debug)]
+    pub enum Shape {
+        size => Sized(Size),
+        none => Empty,
+    }
+}
+
+impl Size {
+    fn check(&self) -> Result<(), MyError> {
+        if self.height < 0.0 || self.width < 0.0 {
+            Err(MyError::Negative)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn main() {
+    let size = Size::try_from(&sexp!((size 1.0 2.0))).unwrap();
+    assert_eq!((size.height, size.width), (1.0, 2.0));
+
+    assert!(
+        matches!(Size::try_from(&sexp!((size 1.0 -2.0))), Err(MyError::Within("size", e)) if matches!(*e, MyError::Negative))
+    );
+    assert!(
+        matches!(Size::try_from(&sexp!((size 1.0 2.0 3.0))), Err(MyError::Within("size", e)) if matches!(*e, MyError::Parse(ParseError::Unexpected(_))))
+    );
+    assert!(matches!(Shape::try_from(&sexp!((size 1.0 2.0))), Ok(Shape::Sized(_))));
+    assert!(matches!(Shape::try_from(&sexp!((size 1.0 -2.0))), Err(MyError::Within("size", _))));
+}
@@ -0,0 +1,48 @@
+use {
+    kanga_sexpr::{sexpr, ToSexpr},
+    lexpr::sexp,
+};
+
+sexpr! {
+    #[derive(Debug)]
+    pub struct Color {
+        (color
+            red: f64
+            green: f64
+            blue: f64
+            [alpha: f64]
+        )
+    }
+}
+
+sexpr! {
+    #[sexpr(unordered)]
+    #[derive(Debug)]
+    pub struct Stroke {
+        (stroke
+            [(width: f64 = 0.0)]
+            [(r#type => stroke_type: StrokeType = StrokeType::Default)]
+            (color: Color)
+        )
+    }
+}
+
+sexpr! {
+    #[derive(Debug, Default, PartialEq)]
+    pub enum StrokeType {
+        dash => Dash,
+        #[default]
+        default => Default,
+        solid => Solid,
+    }
+}
+
+fn main() {
+    let stroke = Stroke::try_from(&sexp!((stroke (color 0 0 0 1) (type dash)))).unwrap();
+    assert_eq!(stroke.width, 0.0);
+    assert_eq!(stroke.stroke_type, StrokeType::Dash);
+    assert_eq!(stroke.color.alpha, Some(1.0));
+
+    let reread = Stroke::try_from(&stroke.to_value()).unwrap();
+    assert_eq!(reread.stroke_type, StrokeType::Dash);
+}
@@ -0,0 +1,39 @@
+use {
+    kanga_sexpr::{sexpr, units::Unit, ToSexpr},
+    lexpr::{sexp, Value},
+};
+
+/// Thousandths of an inch in the document, stored as whole nanometers.
+pub enum Mils {}
+
+impl Unit for Mils {
+    type Value = i64;
+
+    fn from_sexpr(value: &Value) -> Option<i64> {
+        value.as_f64().map(|mils| kanga_sexpr::units::mm_to_nm(mils * 0.0254))
+    }
+
+    fn to_sexpr(value: &i64) -> Value {
+        Value::from(*value as f64 / 25_400.0)
+    }
+}
+
+sexpr! {
+    #[derive(Debug)]
+    pub struct Pad {
+        (pad
+            x: f64 as nm
+            y: f64 as nm
+            (drill: f64 as Mils)
+            [(clearance: f64 as nm = 200_000)]
+        )
+    }
+}
+
+fn main() {
+    let pad = Pad::try_from(&sexp!((pad 1.27 -2.54 (drill 40)))).unwrap();
+    assert_eq!((pad.x, pad.y), (1_270_000, -2_540_000));
+    assert_eq!(pad.drill, 1_016_000);
+    assert_eq!(pad.clearance, 200_000);
+    assert_eq!(pad.to_value(), sexp!((pad 1.27 -2.54 (drill 40.0) (clearance 0.2))));
+}
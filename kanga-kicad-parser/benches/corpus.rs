@@ -0,0 +1,30 @@
+//! Runs this crate's parser and the other parsers it is compared against over a corpus of KiCad
+//! documents, reporting the success rate and speed of each and the documents each cannot read. Run
+//! with `cargo bench --bench corpus`; set `KANGA_CORPUS` to a directory of documents to use instead
+//! of the test fixtures.
+//!
+//! Another parser is compared by adding it under `[dev-dependencies]`, implementing
+//! `CorpusParser` for it here, and adding it to `parsers`.
+
+use {
+    kanga_kicad_parser::compat::{find_corpus, run_corpus, CorpusParser, KangaParser, LexprParser},
+    std::{env, path::PathBuf, thread},
+};
+
+fn main() {
+    let dir = env::var_os("KANGA_CORPUS")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests"));
+
+    let corpus = find_corpus(&dir).expect("Unable to read corpus");
+
+    // See run_corpus: large boards need more stack than a thread gets by default.
+    let report = thread::scope(|scope| {
+        let run = || {
+            let parsers: [&dyn CorpusParser; 2] = [&KangaParser, &LexprParser];
+            run_corpus(&corpus, &parsers, 10).expect("Unable to read corpus document")
+        };
+        thread::Builder::new().stack_size(64 << 20).spawn_scoped(scope, run).unwrap().join().unwrap()
+    });
+    println!("{}", report.to_markdown());
+}
@@ -0,0 +1,144 @@
+use {
+    crate::{
+        read_str,
+        repair::{repair, RepairChange},
+        Config, Error,
+    },
+    lexpr::Value,
+    std::{
+        fs::read_to_string,
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Mutex,
+        },
+        thread::scope,
+    },
+};
+
+/// The outcome of parsing many documents with [`parse_many`].
+#[derive(Debug, Default)]
+pub struct BatchResult {
+    /// One entry per input path, in the order the paths were given.
+    pub entries: Vec<BatchEntry>,
+}
+
+/// The outcome of parsing one document in a batch.
+#[derive(Debug)]
+pub struct BatchEntry {
+    /// The path of the document.
+    pub path: PathBuf,
+
+    /// The parsed document, or the error that prevented parsing it.
+    pub result: Result<Value, Error>,
+
+    /// Repairs made to the document. These are only made when parsing is not strict.
+    pub warnings: Vec<RepairChange>,
+}
+
+impl BatchResult {
+    /// Indicates whether every document was parsed.
+    pub fn is_ok(&self) -> bool {
+        self.entries.iter().all(|entry| entry.result.is_ok())
+    }
+
+    /// Return the documents that were parsed, with their paths.
+    pub fn successes(&self) -> impl Iterator<Item = (&Path, &Value)> {
+        self.entries.iter().filter_map(|entry| Some((entry.path.as_path(), entry.result.as_ref().ok()?)))
+    }
+
+    /// Return the documents that could not be parsed, with their errors.
+    pub fn errors(&self) -> impl Iterator<Item = (&Path, &Error)> {
+        self.entries.iter().filter_map(|entry| Some((entry.path.as_path(), entry.result.as_ref().err()?)))
+    }
+
+    /// Return the repairs made to each document that needed them.
+    pub fn warnings(&self) -> impl Iterator<Item = (&Path, &[RepairChange])> {
+        self.entries
+            .iter()
+            .filter(|entry| !entry.warnings.is_empty())
+            .map(|entry| (entry.path.as_path(), entry.warnings.as_slice()))
+    }
+}
+
+/// Parse many s-expression documents, collecting a result for each instead of stopping at the first
+/// failure.
+///
+/// Documents are parsed on up to [`Config::threads`] threads. If [`ParseOptions::strict`] is
+/// false, a document that fails to parse is passed through [`repair`], and the repairs are
+/// reported as warnings.
+///
+/// [`ParseOptions::strict`]: crate::ParseOptions::strict
+pub fn parse_many<I, P>(paths: I, config: &Config) -> BatchResult
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let paths: Vec<PathBuf> = paths.into_iter().map(|path| path.as_ref().to_path_buf()).collect();
+    let slots: Vec<Mutex<Option<BatchEntry>>> = paths.iter().map(|_| Mutex::new(None)).collect();
+    let next = AtomicUsize::new(0);
+
+    scope(|s| {
+        for _ in 0..config.threads().min(paths.len()) {
+            s.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(path) = paths.get(i) else {
+                    break;
+                };
+
+                let entry = parse_one(path, config);
+                *slots[i].lock().unwrap_or_else(|e| e.into_inner()) = Some(entry);
+            });
+        }
+    });
+
+    BatchResult {
+        entries: slots.into_iter().filter_map(|slot| slot.into_inner().unwrap_or_else(|e| e.into_inner())).collect(),
+    }
+}
+
+/// Parse a single document for [`parse_many`].
+fn parse_one(path: &Path, config: &Config) -> BatchEntry {
+    let mut warnings = Vec::new();
+    let result = read_to_string(path).map_err(Error::from).and_then(|text| match read_str(&text) {
+        Ok(value) => Ok(value),
+        Err(_) if !config.parse.strict => {
+            let (value, report) = repair(&text)?;
+            warnings = report.changes;
+            Ok(value)
+        }
+        Err(e) => Err(e),
+    });
+
+    BatchEntry {
+        path: path.to_path_buf(),
+        result,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::fs::write};
+
+    #[test]
+    fn test_parse_many() {
+        let dir = std::env::temp_dir().join(format!("kanga-batch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        write(dir.join("good.kicad_sch"), "(kicad_sch (version 20231120))").unwrap();
+        write(dir.join("truncated.kicad_sch"), "(kicad_sch (version 20231120)").unwrap();
+
+        let paths = ["good.kicad_sch", "truncated.kicad_sch", "missing.kicad_sch"].map(|name| dir.join(name));
+        let strict = parse_many(&paths, &Config::builder().parallelism(2).build());
+        let lenient = parse_many(&paths, &Config::builder().strict(false).build());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(!strict.is_ok());
+        assert_eq!(strict.successes().count(), 1);
+        assert_eq!(strict.errors().count(), 2);
+        assert!(matches!(strict.entries[2].result, Err(Error::Io(_))));
+
+        assert_eq!(lenient.successes().count(), 2);
+        assert_eq!(lenient.warnings().next().map(|(path, _)| path), Some(paths[1].as_path()));
+    }
+}
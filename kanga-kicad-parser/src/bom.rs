@@ -0,0 +1,446 @@
+use {
+    crate::{bundle::csv_field, sch::Schematic, Project},
+    serde::{Deserialize, Serialize},
+    std::{
+        cmp::Ordering,
+        collections::{BTreeMap, HashMap},
+        fmt::Write as _,
+    },
+};
+
+#[cfg(feature = "xlsx")]
+use {crate::Error, std::io::Error as IoError};
+
+/// The property that holds per-variant fitting rules, following the KiBoM convention.
+pub const VARIANT_PROPERTY: &str = "Config";
+
+/// Options controlling how a bill of materials is built.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BomOptions {
+    /// The properties whose values must all match for components to share a line, e.g. `Value`,
+    /// `Footprint`, and `MPN`. These are also the columns of the output.
+    pub group_by: Vec<String>,
+
+    /// The assembly variant to build the BOM for. Components are fitted according to their
+    /// [`VARIANT_PROPERTY`] property: a comma-separated list where `-name` excludes the component
+    /// from variant `name` and `+name` fits it only in the listed variants.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+
+    /// Whether to list components marked "do not populate" (DNP).
+    #[serde(default)]
+    pub include_dnp: bool,
+
+    /// Whether DNP components are kept on separate lines from fitted components with the same
+    /// values. Only meaningful with [`include_dnp`](Self::include_dnp).
+    #[serde(default)]
+    pub group_dnp: bool,
+}
+
+/// A component in the bill of materials.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BomComponent {
+    /// The reference designator, e.g. `R1`.
+    pub reference: String,
+
+    /// The component's properties, keyed by name.
+    pub properties: BTreeMap<String, String>,
+
+    /// Whether the component is marked "do not populate".
+    #[serde(default)]
+    pub dnp: bool,
+}
+
+/// A line of the bill of materials: components sharing the same grouping values.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BomGroup {
+    /// The reference designators, in natural order.
+    pub references: Vec<String>,
+
+    /// The value of each [`BomOptions::group_by`] property.
+    pub values: Vec<String>,
+
+    /// Whether the line's components are marked "do not populate". Lines mix fitted and DNP
+    /// components, and this is `false`, unless [`BomOptions::group_dnp`] is set.
+    #[serde(default)]
+    pub dnp: bool,
+}
+
+/// A line of the bill of materials with its common fields broken out.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BomEntry {
+    /// The reference designators, in natural order.
+    pub references: Vec<String>,
+
+    /// The number of components on the line.
+    pub quantity: usize,
+
+    /// The `Value` property of the line's first component.
+    pub value: String,
+
+    /// The `Footprint` property of the line's first component.
+    pub footprint: String,
+
+    /// Whether the line's components are marked "do not populate". See [`BomGroup::dnp`].
+    #[serde(default)]
+    pub dnp: bool,
+
+    /// The value of each [`BomOptions::group_by`] property, keyed by name.
+    pub fields: BTreeMap<String, String>,
+}
+
+/// A bill of materials.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Bom {
+    /// The grouping properties, in column order.
+    pub columns: Vec<String>,
+
+    /// The fitted components, in natural reference order.
+    pub components: Vec<BomComponent>,
+
+    /// The grouped lines, ordered by their first reference.
+    pub groups: Vec<BomGroup>,
+}
+
+impl Default for BomOptions {
+    fn default() -> Self {
+        Self {
+            group_by: ["Value", "Footprint"].map(String::from).to_vec(),
+            variant: None,
+            include_dnp: false,
+            group_dnp: false,
+        }
+    }
+}
+
+impl BomComponent {
+    /// Return the value of a property, or an empty string if it is absent.
+    pub fn property(&self, name: &str) -> &str {
+        self.properties.get(name).map(String::as_str).unwrap_or_default()
+    }
+
+    /// Indicates whether the component is fitted in the given variant.
+    pub fn is_fitted(&self, variant: Option<&str>) -> bool {
+        let rules: Vec<&str> =
+            self.property(VARIANT_PROPERTY).split(',').map(str::trim).filter(|rule| !rule.is_empty()).collect();
+
+        let Some(variant) = variant else {
+            return !rules.iter().any(|rule| rule.starts_with('+'));
+        };
+
+        if rules.iter().any(|rule| rule.strip_prefix('-') == Some(variant)) {
+            return false;
+        }
+
+        let mut included = rules.iter().filter_map(|rule| rule.strip_prefix('+')).peekable();
+        included.peek().is_none() || included.any(|name| name == variant)
+    }
+}
+
+impl Bom {
+    /// Build the bill of materials for a project's schematics, as [`Bom::from_schematics`] does.
+    pub fn from_project(project: &Project, options: &BomOptions) -> Self {
+        Self::from_schematics(project.schematics.values(), options)
+    }
+
+    /// Build the bill of materials for parsed schematics.
+    ///
+    /// A symbol on a sheet that is used more than once is a component under each reference its
+    /// instances give it; otherwise its `Reference` property is used. Units of the same component
+    /// are counted once. Power symbols and symbols excluded from the BOM are skipped.
+    pub fn from_schematics<'a, I>(schematics: I, options: &BomOptions) -> Self
+    where
+        I: IntoIterator<Item = &'a Schematic>,
+    {
+        let mut components: BTreeMap<String, BomComponent> = BTreeMap::new();
+
+        for symbol in schematics.into_iter().flat_map(|schematic| &schematic.symbols) {
+            if symbol.in_bom == Some(false) {
+                continue;
+            }
+
+            let mut references: Vec<&str> = symbol
+                .instances
+                .iter()
+                .flat_map(|project| &project.paths)
+                .map(|path| path.reference.as_str())
+                .collect();
+            if references.is_empty() {
+                references.extend(symbol.property("Reference"));
+            }
+
+            for reference in references {
+                if reference.is_empty() || reference.starts_with('#') || components.contains_key(reference) {
+                    continue;
+                }
+
+                let mut properties: BTreeMap<String, String> =
+                    symbol.properties.iter().map(|property| (property.key.clone(), property.value.clone())).collect();
+                properties.insert("Reference".to_string(), reference.to_string());
+
+                components.insert(
+                    reference.to_string(),
+                    BomComponent {
+                        reference: reference.to_string(),
+                        properties,
+                        dnp: symbol.dnp == Some(true),
+                    },
+                );
+            }
+        }
+
+        Self::from_components(components.into_values(), options)
+    }
+
+    /// Build the bill of materials from a list of components, each listed once.
+    pub fn from_components<I>(components: I, options: &BomOptions) -> Self
+    where
+        I: IntoIterator<Item = BomComponent>,
+    {
+        let mut components: Vec<BomComponent> = components
+            .into_iter()
+            .filter(|component| options.include_dnp || !component.dnp)
+            .filter(|component| component.is_fitted(options.variant.as_deref()))
+            .collect();
+        components.sort_by(|a, b| natural_cmp(&a.reference, &b.reference));
+
+        let mut groups: Vec<BomGroup> = Vec::new();
+        let mut index: HashMap<(Vec<String>, bool), usize> = HashMap::new();
+        for component in &components {
+            let values: Vec<String> =
+                options.group_by.iter().map(|name| component.property(name).to_string()).collect();
+            let dnp = options.group_dnp && component.dnp;
+            let i = *index.entry((values.clone(), dnp)).or_insert_with(|| {
+                groups.push(BomGroup {
+                    references: Vec::new(),
+                    values,
+                    dnp,
+                });
+                groups.len() - 1
+            });
+            groups[i].references.push(component.reference.clone());
+        }
+
+        Self {
+            columns: options.group_by.clone(),
+            components,
+            groups,
+        }
+    }
+
+    /// Return the grouped lines with their common fields broken out.
+    pub fn entries(&self) -> Vec<BomEntry> {
+        let by_reference: HashMap<&str, &BomComponent> =
+            self.components.iter().map(|component| (component.reference.as_str(), component)).collect();
+
+        self.groups
+            .iter()
+            .map(|group| {
+                let first = group.references.first().and_then(|reference| by_reference.get(reference.as_str()));
+                let property = |name| first.map(|component| component.property(name)).unwrap_or_default().to_string();
+
+                BomEntry {
+                    references: group.references.clone(),
+                    quantity: group.references.len(),
+                    value: property("Value"),
+                    footprint: property("Footprint"),
+                    dnp: group.dnp,
+                    fields: self.columns.iter().cloned().zip(group.values.iter().cloned()).collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Render the grouped BOM as CSV with a `Reference` column, the grouping columns, and a
+    /// `Quantity` column.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("Reference");
+        for column in &self.columns {
+            let _ = write!(csv, ",{}", csv_field(column));
+        }
+        csv.push_str(",Quantity\n");
+
+        for group in &self.groups {
+            csv.push_str(&csv_field(&group.references.join(" ")));
+            for value in &group.values {
+                let _ = write!(csv, ",{}", csv_field(value));
+            }
+            let _ = writeln!(csv, ",{}", group.references.len());
+        }
+
+        csv
+    }
+
+    /// Render the components in the XML format KiCad passes to BOM generator scripts.
+    pub fn to_kicad_xml(&self) -> String {
+        let mut xml =
+            String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<export version=\"E\">\n  <components>\n");
+
+        for component in &self.components {
+            let _ = writeln!(xml, "    <comp ref=\"{}\">", xml_escape(&component.reference));
+            for (tag, name) in [("value", "Value"), ("footprint", "Footprint"), ("datasheet", "Datasheet")] {
+                let _ = writeln!(xml, "      <{tag}>{}</{tag}>", xml_escape(component.property(name)));
+            }
+
+            let fields: Vec<(&String, &String)> = component
+                .properties
+                .iter()
+                .filter(|(name, _)| !["Reference", "Value", "Footprint", "Datasheet"].contains(&name.as_str()))
+                .collect();
+            if !fields.is_empty() {
+                xml.push_str("      <fields>\n");
+                for (name, value) in fields {
+                    let _ = writeln!(xml, "        <field name=\"{}\">{}</field>", xml_escape(name), xml_escape(value));
+                }
+                xml.push_str("      </fields>\n");
+            }
+            xml.push_str("    </comp>\n");
+        }
+
+        xml.push_str("  </components>\n</export>\n");
+        xml
+    }
+
+    /// Render the grouped BOM as an Excel workbook with the same columns as [`Bom::to_csv`].
+    #[cfg(feature = "xlsx")]
+    pub fn to_xlsx(&self) -> Result<Vec<u8>, Error> {
+        use rust_xlsxwriter::Workbook;
+
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet();
+        let err = |e: rust_xlsxwriter::XlsxError| Error::from(IoError::other(e.to_string()));
+
+        let headers = std::iter::once("Reference").chain(self.columns.iter().map(String::as_str)).chain(["Quantity"]);
+        for (col, header) in headers.enumerate() {
+            sheet.write_string(0, col as u16, header).map_err(err)?;
+        }
+
+        for (row, group) in self.groups.iter().enumerate() {
+            let row = row as u32 + 1;
+            sheet.write_string(row, 0, group.references.join(" ")).map_err(err)?;
+            for (col, value) in group.values.iter().enumerate() {
+                sheet.write_string(row, col as u16 + 1, value).map_err(err)?;
+            }
+            sheet.write_number(row, self.columns.len() as u16 + 1, group.references.len() as f64).map_err(err)?;
+        }
+
+        workbook.save_to_buffer().map_err(err)
+    }
+}
+
+/// Compare reference designators so that numeric suffixes sort by value: `R2` before `R10`.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let split = |s: &str| {
+        let digits = s.len() - s.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+        let (prefix, number) = s.split_at(s.len() - digits);
+        (prefix.to_string(), number.parse::<u64>().ok())
+    };
+
+    split(a).cmp(&split(b)).then_with(|| a.cmp(b))
+}
+
+/// Escape text for use in XML content or attribute values.
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, lexpr::sexp, std::path::PathBuf};
+
+    #[test]
+    fn test_bom() {
+        let schematic = sexp!((kicad_sch
+            (symbol (lib_id "Device:R") (at 0 0 0) (uuid "0c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")
+                (property "Reference" "R10") (property "Value" "10k") (property "MPN" "RC0603-10K"))
+            (symbol (lib_id "Device:R") (at 0 0 0) (uuid "0c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")
+                (property "Reference" "R2") (property "Value" "10k") (property "MPN" "RC0603-10K"))
+            (symbol (lib_id "Device:R") (at 0 0 0) (uuid "0c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")
+                (property "Reference" "R3") (property "Value" "10k") (property "MPN" "ERJ-3-10K"))
+            (symbol (lib_id "Device:R") (at 0 0 0) (uuid "0c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")
+                (property "Reference" "R4") (property "Value" "1k") (property "Config" "-LITE"))
+            (symbol (lib_id "Device:R") (at 0 0 0) (dnp yes) (uuid "0c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")
+                (property "Reference" "R5") (property "Value" "1k"))
+            (symbol (lib_id "Device:TP") (at 0 0 0) (in_bom no) (uuid "0c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")
+                (property "Reference" "TP1") (property "Value" "TP"))));
+
+        let project = Project {
+            dir: PathBuf::from("/demo"),
+            name: "demo".to_string(),
+            settings: serde_json::Value::Null,
+            root_schematic: PathBuf::from("/demo/demo.kicad_sch"),
+            schematics: [(PathBuf::from("/demo/demo.kicad_sch"), Schematic::try_from(&schematic).unwrap())]
+                .into_iter()
+                .collect(),
+            sheets: Vec::new(),
+            board: None,
+            symbol_lib_table: None,
+            footprint_lib_table: None,
+            worksheets: BTreeMap::new(),
+        };
+
+        let options = BomOptions {
+            group_by: ["Value", "MPN"].map(String::from).to_vec(),
+            variant: Some("LITE".to_string()),
+            include_dnp: false,
+            group_dnp: false,
+        };
+
+        let bom = Bom::from_project(&project, &options);
+        assert_eq!(bom.to_csv(), "Reference,Value,MPN,Quantity\nR2 R10,10k,RC0603-10K,2\nR3,10k,ERJ-3-10K,1\n");
+        assert!(bom.to_kicad_xml().contains("    <comp ref=\"R2\">\n      <value>10k</value>\n"));
+
+        #[cfg(feature = "xlsx")]
+        assert!(bom.to_xlsx().unwrap().starts_with(b"PK"));
+
+        let full = Bom::from_project(&project, &BomOptions::default());
+        assert_eq!(full.groups.len(), 2);
+        assert_eq!(full.groups[1].references, ["R4"]);
+    }
+
+    #[test]
+    fn test_bom_from_schematics() {
+        let symbol = |reference: &str, value: &str, extra: &str| {
+            format!(
+                "(symbol (lib_id \"Device:R\") (at 0 0 0) (unit 1) {extra} (uuid \"0c6b985a-999e-45ae-b1a7-41cfbe8ba8fa\")
+                    (property \"Reference\" \"{reference}\" (at 0 0 0))
+                    (property \"Value\" \"{value}\" (at 0 0 0))
+                    (property \"Footprint\" \"Resistor_SMD:R_0603_1608Metric\" (at 0 0 0)))"
+            )
+        };
+
+        let schematic = lexpr::from_str(&format!(
+            "(kicad_sch {} {} {} {} {})",
+            symbol("R2", "10k", ""),
+            symbol("R1", "10k", "(dnp yes)"),
+            symbol("R3", "1k", "(in_bom no)"),
+            symbol("#PWR01", "GND", ""),
+            symbol(
+                "R4",
+                "10k",
+                "(instances (project \"demo\" (path \"/a\" (reference \"R5\") (unit 1)) (path \"/b\" (reference \"R6\") (unit 1))))"
+            ),
+        ))
+        .unwrap();
+        let schematic = Schematic::try_from(&schematic).unwrap();
+
+        let options = BomOptions {
+            include_dnp: true,
+            group_dnp: true,
+            ..BomOptions::default()
+        };
+        let entries = Bom::from_schematics([&schematic], &options).entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].references, ["R1"]);
+        assert!(entries[0].dnp);
+        assert_eq!(entries[1].references, ["R2", "R5", "R6"]);
+        assert_eq!(entries[1].quantity, 3);
+        assert_eq!(entries[1].value, "10k");
+        assert_eq!(entries[1].footprint, "Resistor_SMD:R_0603_1608Metric");
+        assert_eq!(entries[1].fields["Value"], "10k");
+
+        let entries = Bom::from_schematics([&schematic], &BomOptions::default()).entries();
+        assert_eq!(entries.len(), 1);
+        assert!(!entries[0].dnp);
+    }
+}
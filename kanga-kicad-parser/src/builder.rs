@@ -0,0 +1,598 @@
+use crate::{
+    common::{
+        Angle, Color, Font, LineStyle, Nm, Paper, PaperSize, Point, Points, Position, Size, Stroke, Symbol,
+        SymbolProperty, TextEffects, TitleBlock,
+    },
+    context::OutputContext,
+    lazy::Lazy,
+    sch::{
+        Schematic, SchematicJunction, SchematicLabel, SchematicNoConnect, SchematicSheetPath, SchematicSymbolInstance,
+        SchematicSymbolMirror, SchematicSymbolPath, SchematicSymbolPin, SchematicSymbolProject, SchematicWire,
+    },
+    version::NEWEST_FORMAT_VERSION,
+    ParseError,
+};
+
+/// The height and width of field and label text, as KiCad creates it.
+const TEXT_SIZE: Nm = Nm(1_270_000);
+
+/// How far above and below a placed symbol its reference and value fields are put.
+const FIELD_OFFSET: Nm = Nm(2_540_000);
+
+/// Return the stroke KiCad gives new wires: the default width and line style of the schematic.
+pub fn default_stroke() -> Stroke {
+    Stroke {
+        width: Some(Nm::ZERO),
+        line_style: Some(LineStyle::Default),
+        color: None,
+    }
+}
+
+/// Return the text effects KiCad gives new fields and labels: the stroke font at 1.27 mm.
+pub fn default_text_effects() -> TextEffects {
+    TextEffects {
+        font: Some(Font {
+            face: None,
+            size: Size::new(TEXT_SIZE, TEXT_SIZE),
+            thickness: None,
+            bold: false,
+            italic: false,
+            line_spacing: None,
+            color: None,
+        }),
+        justify: None,
+        hide: false,
+    }
+}
+
+/// Builder for a [`Schematic`] generated from code.
+///
+/// Elements are described with [`SymbolBuilder`], [`WireBuilder`], and [`LabelBuilder`] and are
+/// checked when the schematic is built. Every element is given a new UUID from the builder's
+/// [`OutputContext`], so a deterministic context produces the same schematic each time.
+///
+/// ```ignore
+/// let schematic = SchematicBuilder::new()
+///     .lib_symbol(resistor)
+///     .symbol(SymbolBuilder::new("Device:R").reference("R1").value("10k").at(Nm::from_mm(100.0), Nm::from_mm(50.0)))
+///     .wire(WireBuilder::new().start(Nm::from_mm(100.0), Nm::from_mm(46.19)).end(Nm::from_mm(100.0), Nm::from_mm(40.0)))
+///     .build()?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct SchematicBuilder {
+    context: OutputContext,
+    project: String,
+    paper: Paper,
+    title_block: Option<TitleBlock>,
+    lib_symbols: Vec<Symbol>,
+    symbols: Vec<SymbolBuilder>,
+    wires: Vec<WireBuilder>,
+    labels: Vec<LabelBuilder>,
+    junctions: Vec<(Nm, Nm)>,
+    no_connects: Vec<(Nm, Nm)>,
+}
+
+/// Builder for a symbol placed by a [`SchematicBuilder`].
+#[derive(Clone, Debug)]
+pub struct SymbolBuilder {
+    lib_id: String,
+    reference: Option<String>,
+    value: Option<String>,
+    footprint: Option<String>,
+    position: Option<Position>,
+    mirror: Option<SchematicSymbolMirror>,
+    unit: i64,
+    properties: Vec<(String, String)>,
+    in_bom: bool,
+    on_board: bool,
+    dnp: bool,
+}
+
+/// Builder for a wire drawn by a [`SchematicBuilder`].
+#[derive(Clone, Debug, Default)]
+pub struct WireBuilder {
+    start: Option<(Nm, Nm)>,
+    end: Option<(Nm, Nm)>,
+    stroke: Option<Stroke>,
+}
+
+/// Builder for a local label placed by a [`SchematicBuilder`].
+#[derive(Clone, Debug)]
+pub struct LabelBuilder {
+    text: String,
+    position: Option<Position>,
+}
+
+impl SchematicBuilder {
+    /// Create a builder for an empty A4 schematic that generates random UUIDs.
+    pub fn new() -> Self {
+        Self::with_context(OutputContext::new())
+    }
+
+    /// Create a builder for an empty A4 schematic that generates UUIDs from `context`.
+    pub fn with_context(context: OutputContext) -> Self {
+        Self {
+            context,
+            project: String::new(),
+            paper: Paper::new(PaperSize::IsoA4, false),
+            title_block: None,
+            lib_symbols: Vec::new(),
+            symbols: Vec::new(),
+            wires: Vec::new(),
+            labels: Vec::new(),
+            junctions: Vec::new(),
+            no_connects: Vec::new(),
+        }
+    }
+
+    /// Set the name of the project the schematic belongs to, which is recorded in the instances of
+    /// each symbol.
+    pub fn project<S: Into<String>>(mut self, project: S) -> Self {
+        self.project = project.into();
+        self
+    }
+
+    /// Set the paper size and orientation.
+    pub fn paper(mut self, paper: Paper) -> Self {
+        self.paper = paper;
+        self
+    }
+
+    /// Set the title block.
+    pub fn title_block(mut self, title_block: TitleBlock) -> Self {
+        self.title_block = Some(title_block);
+        self
+    }
+
+    /// Add a library symbol to the schematic's `lib_symbols`. Its `id` must be the library
+    /// identifier placed symbols refer to, such as `Device:R`.
+    pub fn lib_symbol(mut self, symbol: Symbol) -> Self {
+        self.lib_symbols.push(symbol);
+        self
+    }
+
+    /// Place a symbol.
+    pub fn symbol(mut self, symbol: SymbolBuilder) -> Self {
+        self.symbols.push(symbol);
+        self
+    }
+
+    /// Draw a wire.
+    pub fn wire(mut self, wire: WireBuilder) -> Self {
+        self.wires.push(wire);
+        self
+    }
+
+    /// Place a local label.
+    pub fn label(mut self, label: LabelBuilder) -> Self {
+        self.labels.push(label);
+        self
+    }
+
+    /// Place a junction dot with the default size and color.
+    pub fn junction(mut self, x: Nm, y: Nm) -> Self {
+        self.junctions.push((x, y));
+        self
+    }
+
+    /// Place a no-connect flag.
+    pub fn no_connect(mut self, x: Nm, y: Nm) -> Self {
+        self.no_connects.push((x, y));
+        self
+    }
+
+    /// Check the elements and build the schematic.
+    ///
+    /// Each placed symbol must have a reference and position, a unit of at least 1, and a library
+    /// symbol added with [`lib_symbol`](Self::lib_symbol); it is given a pin for each of the
+    /// library symbol's pins and an instance on the root sheet. Each wire must have distinct
+    /// start and end points, and each label non-empty text and a position.
+    pub fn build(mut self) -> Result<Schematic, ParseError> {
+        let uuid = self.context.new_uuid();
+        let sheet_path = format!("/{uuid}");
+
+        let mut symbols = Vec::with_capacity(self.symbols.len());
+        for symbol in &self.symbols {
+            symbols.push(symbol.build(&self.lib_symbols, &self.project, &sheet_path, &mut self.context)?);
+        }
+
+        let wires: Vec<SchematicWire> =
+            self.wires.iter().map(|wire| wire.build(&mut self.context)).collect::<Result<_, _>>()?;
+        let labels: Vec<SchematicLabel> =
+            self.labels.iter().map(|label| label.build(&mut self.context)).collect::<Result<_, _>>()?;
+
+        let junctions = self
+            .junctions
+            .iter()
+            .map(|&(x, y)| SchematicJunction {
+                position: point(x, y),
+                diameter: 0,
+                color: Color {
+                    red: 0.0,
+                    green: 0.0,
+                    blue: 0.0,
+                    alpha: Some(0.0),
+                },
+                uuid: self.context.new_uuid(),
+            })
+            .collect();
+
+        let no_connects = self
+            .no_connects
+            .iter()
+            .map(|&(x, y)| SchematicNoConnect {
+                position: point(x, y),
+                uuid: self.context.new_uuid(),
+            })
+            .collect();
+
+        Ok(Schematic {
+            version: Some(NEWEST_FORMAT_VERSION),
+            generator: "kanga".to_string(),
+            generator_version: String::new(),
+            uuid: Some(uuid),
+            paper: Some(self.paper),
+            title_block: self.title_block.map(Lazy::from_value),
+            lib_symbols: Lazy::from_value(self.lib_symbols),
+            junctions,
+            no_connects,
+            bus_entries: Vec::new(),
+            wires,
+            buses: Vec::new(),
+            polylines: Vec::new(),
+            texts: Vec::new(),
+            images: Vec::new(),
+            labels,
+            global_labels: Vec::new(),
+            symbols,
+            sheets: Vec::new(),
+            sheet_instances: vec![SchematicSheetPath {
+                path: "/".to_string(),
+                page: "1".to_string(),
+            }],
+            embedded_fonts: None,
+            embedded_files: Vec::new(),
+            raw_sections: Vec::new(),
+        })
+    }
+}
+
+impl Default for SchematicBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SymbolBuilder {
+    /// Create a builder for unit 1 of the library symbol `lib_id`, such as `Device:R`, included in
+    /// the bill of materials and on the board.
+    pub fn new<S: Into<String>>(lib_id: S) -> Self {
+        Self {
+            lib_id: lib_id.into(),
+            reference: None,
+            value: None,
+            footprint: None,
+            position: None,
+            mirror: None,
+            unit: 1,
+            properties: Vec::new(),
+            in_bom: true,
+            on_board: true,
+            dnp: false,
+        }
+    }
+
+    /// Set the reference designator, such as `R1`. This is required.
+    pub fn reference<S: Into<String>>(mut self, reference: S) -> Self {
+        self.reference = Some(reference.into());
+        self
+    }
+
+    /// Set the value. By default this is the library symbol's value.
+    pub fn value<S: Into<String>>(mut self, value: S) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// Set the footprint, as `library:footprint`. By default this is the library symbol's.
+    pub fn footprint<S: Into<String>>(mut self, footprint: S) -> Self {
+        self.footprint = Some(footprint.into());
+        self
+    }
+
+    /// Set the position of the symbol's origin. This is required.
+    pub fn at(mut self, x: Nm, y: Nm) -> Self {
+        let angle = self.position.as_ref().and_then(|position| position.angle);
+        self.position = Some(Position {
+            x,
+            y,
+            angle: angle.or(Some(Angle::ZERO)),
+        });
+        self
+    }
+
+    /// Set the rotation, counterclockwise in degrees. Set the position first.
+    pub fn angle(mut self, degrees: f64) -> Self {
+        if let Some(position) = &mut self.position {
+            position.angle = Some(Angle::from_degrees(degrees));
+        }
+        self
+    }
+
+    /// Mirror the symbol about an axis.
+    pub fn mirror(mut self, mirror: SchematicSymbolMirror) -> Self {
+        self.mirror = Some(mirror);
+        self
+    }
+
+    /// Set the unit of a multi-unit symbol, starting at 1.
+    pub fn unit(mut self, unit: i64) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    /// Set a property other than the reference, value, or footprint, such as `MPN`. The property
+    /// is hidden.
+    pub fn property<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.properties.push((key.into(), value.into()));
+        self
+    }
+
+    /// Set whether the symbol is included in the bill of materials.
+    pub fn in_bom(mut self, in_bom: bool) -> Self {
+        self.in_bom = in_bom;
+        self
+    }
+
+    /// Set whether the symbol is exported to the board.
+    pub fn on_board(mut self, on_board: bool) -> Self {
+        self.on_board = on_board;
+        self
+    }
+
+    /// Set whether the symbol is marked do-not-populate.
+    pub fn dnp(mut self, dnp: bool) -> Self {
+        self.dnp = dnp;
+        self
+    }
+
+    fn build(
+        &self,
+        lib_symbols: &[Symbol],
+        project: &str,
+        sheet_path: &str,
+        context: &mut OutputContext,
+    ) -> Result<SchematicSymbolInstance, ParseError> {
+        let invalid = |reason: &str| ParseError::InvalidElement(format!("symbol {}", self.lib_id), reason.to_string());
+
+        let reference = self.reference.as_deref().filter(|reference| !reference.is_empty());
+        let reference = reference.ok_or_else(|| invalid("missing reference"))?;
+        let position = self.position.clone().ok_or_else(|| invalid("missing position"))?;
+        if self.unit < 1 {
+            return Err(invalid("units start at 1"));
+        }
+        let lib_symbol = lib_symbols
+            .iter()
+            .find(|symbol| symbol.id == self.lib_id)
+            .ok_or_else(|| invalid("library symbol is not in lib_symbols"))?;
+
+        let field = |key: &str, value: &str, y: Nm, hide: bool| SymbolProperty {
+            key: key.to_string(),
+            value: value.to_string(),
+            identifier: None,
+            position: Some(Position {
+                x: position.x,
+                y: position.y + y,
+                angle: Some(Angle::ZERO),
+            }),
+            text_effects: Some(TextEffects {
+                hide,
+                ..default_text_effects()
+            }),
+            show_name: false,
+            do_not_autoplace: false,
+        };
+
+        let value = self.value.as_deref().or_else(|| lib_symbol.property("Value")).unwrap_or_default();
+        let footprint = self.footprint.as_deref().or_else(|| lib_symbol.property("Footprint")).unwrap_or_default();
+        let datasheet = lib_symbol.property("Datasheet").unwrap_or_default();
+        let mut properties = vec![
+            field("Reference", reference, -FIELD_OFFSET, false),
+            field("Value", value, FIELD_OFFSET, false),
+            field("Footprint", footprint, Nm::ZERO, true),
+            field("Datasheet", datasheet, Nm::ZERO, true),
+        ];
+        properties.extend(self.properties.iter().map(|(key, value)| field(key, value, Nm::ZERO, true)));
+
+        let uuid = context.new_uuid();
+        let pins = lib_symbol
+            .unit_pins(Some(self.unit))
+            .map(|pin| SchematicSymbolPin {
+                number: pin.number.number.clone(),
+                uuid: context.new_uuid(),
+                alternate: None,
+            })
+            .collect();
+
+        Ok(SchematicSymbolInstance {
+            lib_id: self.lib_id.clone(),
+            lib_name: None,
+            position,
+            mirror: self.mirror,
+            unit: self.unit,
+            exclude_from_sim: Some(false),
+            in_bom: Some(self.in_bom),
+            on_board: Some(self.on_board),
+            dnp: Some(self.dnp),
+            fields_autoplaced: false,
+            uuid,
+            properties,
+            pins,
+            instances: vec![SchematicSymbolProject {
+                name: project.to_string(),
+                paths: vec![SchematicSymbolPath {
+                    path: sheet_path.to_string(),
+                    reference: reference.to_string(),
+                    unit: self.unit,
+                }],
+            }],
+        })
+    }
+}
+
+impl WireBuilder {
+    /// Create a builder for a wire with the default stroke.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the start point. This is required.
+    pub fn start(mut self, x: Nm, y: Nm) -> Self {
+        self.start = Some((x, y));
+        self
+    }
+
+    /// Set the end point. This is required.
+    pub fn end(mut self, x: Nm, y: Nm) -> Self {
+        self.end = Some((x, y));
+        self
+    }
+
+    /// Set the stroke, in place of [`default_stroke`].
+    pub fn stroke(mut self, stroke: Stroke) -> Self {
+        self.stroke = Some(stroke);
+        self
+    }
+
+    fn build(&self, context: &mut OutputContext) -> Result<SchematicWire, ParseError> {
+        let invalid = |reason: &str| ParseError::InvalidElement("wire".to_string(), reason.to_string());
+        let (start, end) = match (self.start, self.end) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return Err(invalid("missing start or end point")),
+        };
+
+        if start == end {
+            return Err(invalid("start and end points are the same"));
+        }
+
+        Ok(SchematicWire {
+            points: Points {
+                points: vec![Point::new(start.0, start.1), Point::new(end.0, end.1)],
+            },
+            stroke: self.stroke.clone().unwrap_or_else(default_stroke),
+            uuid: context.new_uuid(),
+        })
+    }
+}
+
+impl LabelBuilder {
+    /// Create a builder for a label naming the net `text`.
+    pub fn new<S: Into<String>>(text: S) -> Self {
+        Self {
+            text: text.into(),
+            position: None,
+        }
+    }
+
+    /// Set the position of the label's connection point. This is required.
+    pub fn at(mut self, x: Nm, y: Nm) -> Self {
+        self.position = Some(Position {
+            x,
+            y,
+            angle: Some(Angle::ZERO),
+        });
+        self
+    }
+
+    /// Set the rotation, counterclockwise in degrees. Set the position first.
+    pub fn angle(mut self, degrees: f64) -> Self {
+        if let Some(position) = &mut self.position {
+            position.angle = Some(Angle::from_degrees(degrees));
+        }
+        self
+    }
+
+    fn build(&self, context: &mut OutputContext) -> Result<SchematicLabel, ParseError> {
+        let invalid = |reason: &str| ParseError::InvalidElement(format!("label {:?}", self.text), reason.to_string());
+        if self.text.is_empty() {
+            return Err(invalid("missing text"));
+        }
+
+        Ok(SchematicLabel {
+            text: self.text.clone(),
+            fields_autoplaced: false,
+            position: self.position.clone().ok_or_else(|| invalid("missing position"))?,
+            text_effects: default_text_effects(),
+            uuid: context.new_uuid(),
+        })
+    }
+}
+
+/// Return an unrotated position.
+fn point(x: Nm, y: Nm) -> Position {
+    Position {
+        x,
+        y,
+        angle: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::symgen::{generate_symbol, parse_pin_table, SymbolGeneratorOptions},
+        std::collections::HashSet,
+        uuid::Uuid,
+    };
+
+    fn uuids(schematic: &Schematic) -> Vec<Uuid> {
+        let mut uuids = Vec::new();
+        schematic.for_each_identified(|element| uuids.extend(element.uuid()));
+        uuids
+    }
+
+    fn build(seed: u64, symbol: SymbolBuilder) -> Result<Schematic, ParseError> {
+        let pins = parse_pin_table("number,name,type,side\n1,~,passive,top\n2,~,passive,bottom\n").unwrap();
+        let resistor = generate_symbol("Device:R", &pins, &SymbolGeneratorOptions::default());
+        let mm = Nm::from_mm;
+
+        SchematicBuilder::with_context(OutputContext::deterministic(seed))
+            .project("demo")
+            .lib_symbol(resistor)
+            .symbol(symbol)
+            .wire(WireBuilder::new().start(mm(100.0), mm(40.0)).end(mm(120.0), mm(40.0)))
+            .label(LabelBuilder::new("VIN").at(mm(120.0), mm(40.0)))
+            .no_connect(mm(100.0), mm(60.0))
+            .build()
+    }
+
+    #[test]
+    fn test_build_schematic() {
+        let r1 = SymbolBuilder::new("Device:R").reference("R1").value("10k").at(Nm::from_mm(100.0), Nm::from_mm(50.0));
+        let schematic = build(1, r1.clone()).unwrap();
+
+        let symbol = &schematic.symbols[0];
+        assert_eq!(symbol.property("Reference"), Some("R1"));
+        assert_eq!(symbol.property("Value"), Some("10k"));
+        assert_eq!(symbol.property("Footprint"), Some(""));
+        assert_eq!(symbol.pins.len(), 2);
+        assert_eq!(symbol.instances[0].paths[0].path, format!("/{}", schematic.uuid.unwrap()));
+        assert!(matches!(schematic.wires[0].stroke.line_style, Some(LineStyle::Default)));
+        assert_eq!(schematic.labels[0].text, "VIN");
+
+        let ids = uuids(&schematic);
+        assert_eq!(ids.len(), 7);
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), ids.len());
+        assert_eq!(uuids(&build(1, r1.clone()).unwrap()), ids);
+        assert_ne!(uuids(&build(2, r1.clone()).unwrap()), ids);
+
+        assert!(build(1, SymbolBuilder::new("Device:R").reference("R1")).is_err());
+        assert!(build(1, SymbolBuilder::new("Device:C").reference("C1").at(Nm::ZERO, Nm::ZERO)).is_err());
+        assert!(build(1, r1.clone().unit(0)).is_err());
+
+        let zero_length = WireBuilder::new().start(Nm::ZERO, Nm::ZERO).end(Nm::ZERO, Nm::ZERO);
+        assert!(SchematicBuilder::new().wire(zero_length).build().is_err());
+        assert!(SchematicBuilder::new().label(LabelBuilder::new("VIN")).build().is_err());
+    }
+}
@@ -0,0 +1,372 @@
+use {
+    crate::{
+        bom::{Bom, BomOptions},
+        context::OutputContext,
+        frame::{BoardOrigins, CoordinateFrame},
+        impact::board_nets,
+        pcb::Pcb,
+        Error, Project,
+    },
+    lexpr::Value,
+    serde::{Deserialize, Serialize},
+    sha2::{Digest, Sha256},
+    std::{
+        fmt::Write as _,
+        fs::{create_dir_all, write},
+        path::Path,
+    },
+};
+
+/// The name of the manifest written to the bundle directory.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Which outputs to produce in a release bundle.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BundleOptions {
+    /// Write a bill of materials as CSV.
+    #[serde(default = "default_true")]
+    pub bom: bool,
+
+    /// How to group and filter the bill of materials.
+    #[serde(default)]
+    pub bom_options: BomOptions,
+
+    /// Write a component position (pick and place) file as CSV.
+    #[serde(default = "default_true")]
+    pub positions: bool,
+
+    /// The coordinate frame of the position file. Defaults to the page origin with Y increasing
+    /// upwards, as KiCad writes position files.
+    #[serde(default = "default_position_frame")]
+    pub position_frame: CoordinateFrame,
+
+    /// Write a netlist in KiCad's s-expression netlist format.
+    #[serde(default = "default_true")]
+    pub netlist: bool,
+
+    /// Write PDF/SVG plots of the schematics and board.
+    #[serde(default = "default_true")]
+    pub drawings: bool,
+
+    /// Write Gerber fabrication files.
+    #[serde(default = "default_true")]
+    pub gerbers: bool,
+}
+
+/// The record of a generated release bundle, written as `manifest.json`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BundleManifest {
+    /// The project name.
+    pub project: String,
+
+    /// When the bundle was generated, in seconds since the Unix epoch.
+    pub generated: u64,
+
+    /// The files written, in the order they were generated.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<BundleFile>,
+
+    /// Requested outputs that could not be produced.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<SkippedOutput>,
+}
+
+/// A file written to a release bundle.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BundleFile {
+    /// The file name, relative to the bundle directory.
+    pub path: String,
+
+    /// The kind of output.
+    pub kind: OutputKind,
+
+    /// The size of the file in bytes.
+    pub size: u64,
+
+    /// The SHA-256 checksum of the file, as lowercase hex.
+    pub sha256: String,
+}
+
+/// A requested output that was not produced.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SkippedOutput {
+    /// The kind of output.
+    pub kind: OutputKind,
+
+    /// Why the output was not produced.
+    pub reason: String,
+}
+
+/// The kinds of outputs in a release bundle.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputKind {
+    Bom,
+    Positions,
+    Netlist,
+    Drawing,
+    Gerber,
+}
+
+impl Default for BundleOptions {
+    fn default() -> Self {
+        Self {
+            bom: true,
+            bom_options: BomOptions::default(),
+            positions: true,
+            position_frame: default_position_frame(),
+            netlist: true,
+            drawings: true,
+            gerbers: true,
+        }
+    }
+}
+
+/// Generate the requested fabrication outputs for a project into `dir`, along with a manifest
+/// listing each file and its checksum.
+///
+/// Outputs that cannot be produced (because the project lacks a board, or because the output is
+/// not supported by this crate) are recorded in [`BundleManifest::skipped`] rather than failing the
+/// whole bundle.
+pub fn generate_bundle<P>(project: &Project, dir: P, options: &BundleOptions) -> Result<BundleManifest, Error>
+where
+    P: AsRef<Path>,
+{
+    generate_bundle_with(project, dir, options, &OutputContext::new())
+}
+
+/// Generate a release bundle as [`generate_bundle`] does, taking the generation time from
+/// `context`.
+pub fn generate_bundle_with<P>(
+    project: &Project,
+    dir: P,
+    options: &BundleOptions,
+    context: &OutputContext,
+) -> Result<BundleManifest, Error>
+where
+    P: AsRef<Path>,
+{
+    let dir = dir.as_ref();
+    create_dir_all(dir)?;
+
+    let mut manifest = BundleManifest {
+        project: project.name.clone(),
+        generated: context.unix_timestamp(),
+        files: Vec::new(),
+        skipped: Vec::new(),
+    };
+
+    if options.bom {
+        let name = format!("{}-bom.csv", project.name);
+        manifest.add_file(dir, name, OutputKind::Bom, Bom::from_project(project, &options.bom_options).to_csv())?;
+    }
+
+    for (enabled, kind) in [(options.positions, OutputKind::Positions), (options.netlist, OutputKind::Netlist)] {
+        if !enabled {
+            continue;
+        }
+
+        let Some(board) = &project.board else {
+            manifest.skip(kind, "The project has no board");
+            continue;
+        };
+
+        let (name, contents) = match kind {
+            OutputKind::Positions => {
+                (format!("{}-pos.csv", project.name), positions_csv(board, &options.position_frame))
+            }
+            _ => (format!("{}.net", project.name), netlist(board)),
+        };
+        manifest.add_file(dir, name, kind, contents)?;
+    }
+
+    if options.drawings {
+        manifest.skip(OutputKind::Drawing, "Plotting is not supported");
+    }
+
+    if options.gerbers {
+        manifest.skip(OutputKind::Gerber, "Gerber generation is not supported");
+    }
+
+    let json = serde_json::to_string_pretty(&manifest)?;
+    write(dir.join(MANIFEST_FILE), json)?;
+
+    Ok(manifest)
+}
+
+impl BundleManifest {
+    /// Write a file to the bundle and record it.
+    fn add_file(&mut self, dir: &Path, name: String, kind: OutputKind, contents: String) -> Result<(), Error> {
+        let sha256 = sha256_hex(contents.as_bytes());
+        write(dir.join(&name), &contents)?;
+        self.files.push(BundleFile {
+            path: name,
+            kind,
+            size: contents.len() as u64,
+            sha256,
+        });
+        Ok(())
+    }
+
+    /// Record that an output was not produced.
+    fn skip(&mut self, kind: OutputKind, reason: &str) {
+        self.skipped.push(SkippedOutput {
+            kind,
+            reason: reason.to_string(),
+        });
+    }
+}
+
+/// Render the position of each footprint in KiCad's CSV position file format, with coordinates in
+/// millimeters in `frame`.
+fn positions_csv(board: &Pcb, frame: &CoordinateFrame) -> String {
+    let origins = BoardOrigins::from_pcb(board);
+    let mut csv = String::from("Ref,Val,Package,PosX,PosY,Rot,Side\n");
+
+    for footprint in &board.footprints {
+        let property = |name| footprint.properties.get(name).map(String::as_str).unwrap_or_default();
+        let package = footprint.lib_id.rsplit(':').next().unwrap_or_default();
+        let side = if footprint.layer == "B.Cu" {
+            "bottom"
+        } else {
+            "top"
+        };
+
+        let position = &footprint.position;
+        let (x, y) = frame.apply(&origins, position.x.0, position.y.0);
+        let _ = writeln!(
+            csv,
+            "{},{},{},{:.4},{:.4},{:.4},{side}",
+            csv_field(property("Reference")),
+            csv_field(property("Value")),
+            csv_field(package),
+            x as f64 / 1e6,
+            y as f64 / 1e6,
+            position.angle.map(|angle| angle.degrees()).unwrap_or_default()
+        );
+    }
+
+    csv
+}
+
+/// Render the board's connectivity in KiCad's s-expression netlist format.
+fn netlist(board: &Pcb) -> String {
+    let mut nets = vec![Value::symbol("nets")];
+
+    for (code, (name, nodes)) in board_nets(board).into_iter().enumerate() {
+        let mut net = vec![
+            Value::symbol("net"),
+            Value::list(vec![Value::symbol("code"), Value::string((code + 1).to_string())]),
+            Value::list(vec![Value::symbol("name"), Value::string(name)]),
+        ];
+
+        for (reference, pin) in nodes {
+            net.push(Value::list(vec![
+                Value::symbol("node"),
+                Value::list(vec![Value::symbol("ref"), Value::string(reference)]),
+                Value::list(vec![Value::symbol("pin"), Value::string(pin)]),
+            ]));
+        }
+
+        nets.push(Value::list(net));
+    }
+
+    let export = Value::list(vec![
+        Value::symbol("export"),
+        Value::list(vec![Value::symbol("version"), Value::string("E")]),
+        Value::list(nets),
+    ]);
+
+    format!("{export}\n")
+}
+
+/// Return the SHA-256 checksum of `bytes` as lowercase hex.
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+/// Quote a CSV field if it contains a delimiter, quote, or newline.
+pub(crate) fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[inline(always)]
+fn default_true() -> bool {
+    true
+}
+
+fn default_position_frame() -> CoordinateFrame {
+    CoordinateFrame::PAGE_Y_UP
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            frame::{FrameOrigin, YAxis},
+            sch::Schematic,
+        },
+        lexpr::sexp,
+        std::{collections::BTreeMap, path::PathBuf},
+    };
+
+    #[test]
+    fn test_generate_bundle() {
+        let project = Project {
+            dir: PathBuf::from("/demo"),
+            name: "demo".to_string(),
+            settings: serde_json::Value::Null,
+            root_schematic: PathBuf::from("/demo/demo.kicad_sch"),
+            schematics: [(
+                PathBuf::from("/demo/demo.kicad_sch"),
+                Schematic::try_from(&sexp!((kicad_sch
+                    (symbol (lib_id "Device:R") (at 0 0 0) (uuid "0c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")
+                        (property "Reference" "R1") (property "Value" "10k") (property "Footprint" "R:R_0603"))
+                    (symbol (lib_id "Device:R") (at 0 0 0) (uuid "0c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")
+                        (property "Reference" "R2") (property "Value" "10k") (property "Footprint" "R:R_0603")))))
+                .unwrap(),
+            )]
+            .into_iter()
+            .collect(),
+            sheets: Vec::new(),
+            board: Some(
+                Pcb::try_from(&sexp!((kicad_pcb
+                    (footprint "R:R_0603" (layer "B.Cu") (at 10 20 90) (property "Reference" "R1") (property "Value" "10k")
+                        (pad "1" smd rect (at 0 0) (size 1 1) (net 1 "VCC"))))))
+                .unwrap(),
+            ),
+            symbol_lib_table: None,
+            footprint_lib_table: None,
+            worksheets: BTreeMap::new(),
+        };
+
+        let dir = std::env::temp_dir().join(format!("kanga-bundle-{}", std::process::id()));
+        let context = OutputContext::deterministic(0);
+        let manifest = generate_bundle_with(&project, &dir, &BundleOptions::default(), &context).unwrap();
+        let json = std::fs::read_to_string(dir.join(MANIFEST_FILE)).unwrap();
+        let bom = std::fs::read_to_string(dir.join("demo-bom.csv")).unwrap();
+        let pos = std::fs::read_to_string(dir.join("demo-pos.csv")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(bom, "Reference,Value,Footprint,Quantity\nR1 R2,10k,R:R_0603,2\n");
+        assert_eq!(pos.lines().nth(1), Some("R1,10k,R_0603,10.0000,-20.0000,90.0000,bottom"));
+        assert_eq!(manifest.files.len(), 3);
+        assert_eq!(manifest.files[0].sha256.len(), 64);
+        assert_eq!(manifest.skipped.len(), 2);
+        assert!(json.contains("\"generated\": 0,"));
+
+        let board =
+            sexp!((kicad_pcb (setup (aux_axis_origin 5 30)) (footprint "R:R_0603" (layer "F.Cu") (at 10 20 90))));
+        let board = Pcb::try_from(&board).unwrap();
+        let frame = CoordinateFrame::new(FrameOrigin::AuxAxis, YAxis::Up);
+        assert_eq!(positions_csv(&board, &frame).lines().nth(1), Some(",,R_0603,5.0000,10.0000,90.0000,top"));
+    }
+}
@@ -0,0 +1,314 @@
+use {
+    crate::{
+        common::{Point, Position},
+        sch::Schematic,
+        ParseError,
+    },
+    lexpr::Value,
+    uuid::Uuid,
+};
+
+/// How deeply bus aliases may refer to one another before expansion gives up, which also stops
+/// aliases that refer to themselves.
+const MAX_ALIAS_DEPTH: usize = 8;
+
+/// A named set of bus members, declared in a schematic as
+/// `(bus_alias "USB" (members "DP" "DM"))`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct BusAlias {
+    /// The alias name.
+    pub name: String,
+
+    /// The members, each a net name, a vector bus, or another alias.
+    pub members: Vec<String>,
+}
+
+/// The net a bus entry connects to a bus, and whether the bus carries it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct BusEntryConnection {
+    /// The UUID of the bus entry.
+    pub entry: Uuid,
+
+    /// The name of the bus the entry touches, from a label on it, if any.
+    pub bus: Option<String>,
+
+    /// The name of the net on the wire the entry touches, from a label on it, if any.
+    pub net: Option<String>,
+
+    /// Whether the net is a member of the bus. False if either is unnamed.
+    pub member: bool,
+}
+
+impl TryFrom<&Value> for BusAlias {
+    type Error = ParseError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        let mut elements = value.list_iter().into_iter().flatten();
+        if elements.next().and_then(Value::as_symbol) != Some("bus_alias") {
+            return Err(ParseError::Unexpected(value.clone()));
+        }
+
+        let name = elements.next().and_then(Value::as_str).ok_or_else(|| ParseError::Unexpected(value.clone()))?;
+        let members = elements
+            .find(|element| element.as_cons().and_then(|cons| cons.car().as_symbol()) == Some("members"))
+            .map(|members| {
+                members.list_iter().into_iter().flatten().skip(1).filter_map(Value::as_str).map(String::from)
+            })
+            .into_iter()
+            .flatten()
+            .collect();
+
+        Ok(Self {
+            name: name.to_string(),
+            members,
+        })
+    }
+}
+
+/// Return the bus aliases declared in a schematic. Malformed declarations are skipped.
+pub fn bus_aliases(schematic: &Schematic) -> Vec<BusAlias> {
+    schematic.raw_sections.iter().filter_map(|element| BusAlias::try_from(element).ok()).collect()
+}
+
+/// Indicates whether a label names a bus rather than a single net.
+pub fn is_bus_name(name: &str, aliases: &[BusAlias]) -> bool {
+    expand_bus(name, aliases).is_some()
+}
+
+/// Expand a bus name into the names of its member nets, or return `None` if it does not name a
+/// bus.
+///
+/// Three forms are recognized, as in KiCad:
+///
+/// * A vector bus, `D[0..7]`, whose members are `D0` through `D7`. The range may run downwards,
+///   as in `D[7..0]`, which lists the members in that order.
+/// * A group bus, `USB{DP DM}`, whose members are `USB.DP` and `USB.DM`. Members may be vector
+///   buses or aliases, which are expanded in turn. An unnamed group, `{DP DM}`, does not prefix
+///   its members.
+/// * The name of a bus alias, whose members are expanded without a prefix.
+///
+/// Overbars, superscripts, and subscripts, such as `~{RESET}`, are markup rather than groups.
+pub fn expand_bus(name: &str, aliases: &[BusAlias]) -> Option<Vec<String>> {
+    expand(name.trim(), aliases, 0)
+}
+
+/// Expand a bus name, or return `None` if it does not name a bus or nests aliases more than
+/// [`MAX_ALIAS_DEPTH`] deep.
+fn expand(name: &str, aliases: &[BusAlias], depth: usize) -> Option<Vec<String>> {
+    if let Some(members) = expand_vector(name) {
+        return Some(members);
+    }
+
+    if let Some((prefix, body)) = split_group(name) {
+        let mut members = Vec::new();
+        for member in body.split(|c: char| c.is_whitespace() || c == ',').filter(|member| !member.is_empty()) {
+            let expanded = expand_member(member, aliases, depth + 1)?;
+            members.extend(expanded.into_iter().map(|net| match prefix {
+                "" => net,
+                _ => format!("{prefix}.{net}"),
+            }));
+        }
+        return Some(members);
+    }
+
+    let alias = aliases.iter().find(|alias| alias.name == name)?;
+    let mut members = Vec::new();
+    for member in &alias.members {
+        members.extend(expand_member(member, aliases, depth + 1)?);
+    }
+    Some(members)
+}
+
+/// Expand a member of a group bus or alias, which is a single net unless it names a bus.
+fn expand_member(member: &str, aliases: &[BusAlias], depth: usize) -> Option<Vec<String>> {
+    if depth > MAX_ALIAS_DEPTH {
+        return None;
+    }
+
+    match expand(member, aliases, depth) {
+        Some(members) => Some(members),
+        // A bus that did not expand was nested too deeply.
+        None if split_group(member).is_some() || aliases.iter().any(|alias| alias.name == member) => None,
+        None => Some(vec![member.to_string()]),
+    }
+}
+
+/// Expand a vector bus, `PREFIX[M..N]`.
+fn expand_vector(name: &str) -> Option<Vec<String>> {
+    let (prefix, range) = name.strip_suffix(']')?.split_once('[')?;
+    let (start, end) = range.split_once("..")?;
+    let (start, end): (i64, i64) = (start.trim().parse().ok()?, end.trim().parse().ok()?);
+
+    let indices: Vec<i64> = if start <= end {
+        (start..=end).collect()
+    } else {
+        (end..=start).rev().collect()
+    };
+
+    Some(indices.into_iter().map(|index| format!("{prefix}{index}")).collect())
+}
+
+/// Split a group bus, `PREFIX{MEMBERS}`, into its prefix and members, skipping text markup.
+fn split_group(name: &str) -> Option<(&str, &str)> {
+    let body = name.strip_suffix('}')?;
+    let open = body
+        .char_indices()
+        .find(|&(index, c)| c == '{' && !matches!(body[..index].chars().next_back(), Some('~' | '^' | '_')))?
+        .0;
+    Some((&body[..open], &body[open + 1..]))
+}
+
+/// Find what each bus entry of a schematic connects: the bus at one end and the wire at the other,
+/// each named by a label placed anywhere on the buses or wires joined to it on the sheet.
+pub fn bus_entry_connections(schematic: &Schematic, aliases: &[BusAlias]) -> Vec<BusEntryConnection> {
+    let label_points = label_points(schematic);
+    let bus_paths: Vec<&[Point]> = schematic.buses.iter().map(|bus| bus.points.points.as_slice()).collect();
+    let wire_paths: Vec<&[Point]> = schematic.wires.iter().map(|wire| wire.points.points.as_slice()).collect();
+    let bus_names = name_groups(&bus_paths, &label_points);
+    let wire_names = name_groups(&wire_paths, &label_points);
+
+    schematic
+        .bus_entries
+        .iter()
+        .map(|entry| {
+            let start = (entry.position.x.0, entry.position.y.0);
+            let end = ((entry.position.x + entry.size.x).0, (entry.position.y + entry.size.y).0);
+
+            // Either end of an entry may be the one on the bus.
+            let on_bus = |point| bus_paths.iter().position(|path| on_path(path, point));
+            let on_wire = |point| wire_paths.iter().position(|path| on_path(path, point));
+            let (bus, wire) = match (on_bus(start), on_bus(end)) {
+                (Some(bus), _) => (Some(bus), on_wire(end)),
+                (None, Some(bus)) => (Some(bus), on_wire(start)),
+                (None, None) => (None, on_wire(start).or_else(|| on_wire(end))),
+            };
+
+            let bus = bus.and_then(|bus| bus_names[bus].clone());
+            let net = wire.and_then(|wire| wire_names[wire].clone());
+            let member = match (&bus, &net) {
+                (Some(bus), Some(net)) => expand_bus(bus, aliases).is_some_and(|members| members.contains(net)),
+                _ => false,
+            };
+
+            BusEntryConnection {
+                entry: entry.uuid,
+                bus,
+                net,
+                member,
+            }
+        })
+        .collect()
+}
+
+/// Return the text and position of each local and global label of a schematic.
+pub(crate) fn label_points(schematic: &Schematic) -> Vec<(&str, &Position)> {
+    let labels = schematic.labels.iter().map(|label| (label.text.as_str(), &label.position));
+    let global_labels = schematic.global_labels.iter().map(|label| (label.text.as_str(), &label.position));
+    labels.chain(global_labels).collect()
+}
+
+/// Name each path by the first label on it or on any path joined to it, where paths are joined if
+/// an end of one lies on the other.
+pub(crate) fn name_groups(paths: &[&[Point]], labels: &[(&str, &Position)]) -> Vec<Option<String>> {
+    let mut group: Vec<usize> = (0..paths.len()).collect();
+
+    fn root(group: &mut [usize], mut i: usize) -> usize {
+        while group[i] != i {
+            group[i] = group[group[i]];
+            i = group[i];
+        }
+        i
+    }
+
+    for (i, path) in paths.iter().enumerate() {
+        let ends = [path.first(), path.last()];
+        for (j, other) in paths.iter().enumerate() {
+            if i != j && ends.iter().flatten().any(|end| on_path(other, (end.x.0, end.y.0))) {
+                let (a, b) = (root(&mut group, i), root(&mut group, j));
+                group[a] = b;
+            }
+        }
+    }
+
+    let mut names: Vec<Option<String>> = vec![None; paths.len()];
+    for (text, position) in labels {
+        if let Some(i) = paths.iter().position(|path| on_path(path, (position.x.0, position.y.0))) {
+            let r = root(&mut group, i);
+            names[r].get_or_insert_with(|| text.to_string());
+        }
+    }
+
+    (0..paths.len()).map(|i| names[root(&mut group, i)].clone()).collect()
+}
+
+/// Indicates whether a point lies on any segment of a path.
+pub(crate) fn on_path(path: &[Point], point: (i64, i64)) -> bool {
+    path.windows(2).any(|segment| on_segment((segment[0].x.0, segment[0].y.0), (segment[1].x.0, segment[1].y.0), point))
+}
+
+/// Indicates whether a point lies on the segment from `a` to `b`, inclusive of its ends.
+fn on_segment(a: (i64, i64), b: (i64, i64), p: (i64, i64)) -> bool {
+    let cross = (b.0 - a.0) as i128 * (p.1 - a.1) as i128 - (b.1 - a.1) as i128 * (p.0 - a.0) as i128;
+    cross == 0 && p.0 >= a.0.min(b.0) && p.0 <= a.0.max(b.0) && p.1 >= a.1.min(b.1) && p.1 <= a.1.max(b.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::parse_str};
+
+    #[test]
+    fn test_expand_bus() {
+        let aliases = vec![
+            BusAlias {
+                name: "USB".to_string(),
+                members: vec!["DP".to_string(), "DM".to_string()],
+            },
+            BusAlias {
+                name: "LOOP".to_string(),
+                members: vec!["LOOP".to_string()],
+            },
+        ];
+
+        assert_eq!(expand_bus("D[0..2]", &aliases).unwrap(), ["D0", "D1", "D2"]);
+        assert_eq!(expand_bus("A[3..1]", &aliases).unwrap(), ["A3", "A2", "A1"]);
+        assert_eq!(expand_bus("MEM{A[0..1] CLK}", &aliases).unwrap(), ["MEM.A0", "MEM.A1", "MEM.CLK"]);
+        assert_eq!(expand_bus("HOST{USB}", &aliases).unwrap(), ["HOST.DP", "HOST.DM"]);
+        assert_eq!(expand_bus("{SDA, SCL}", &aliases).unwrap(), ["SDA", "SCL"]);
+        assert_eq!(expand_bus("USB", &aliases).unwrap(), ["DP", "DM"]);
+        assert!(!is_bus_name("~{RESET}", &aliases));
+        assert!(!is_bus_name("CLK", &aliases));
+        assert!(!is_bus_name("LOOP", &aliases));
+
+        let text = r#"(kicad_sch (version 20231120) (generator "eeschema")
+            (bus_alias "USB" (members "DP" "DM"))
+            (bus_entry (at 10 0) (size 2.54 2.54) (stroke (width 0) (type default))
+                (uuid "00000000-0000-0000-0000-000000000001"))
+            (bus_entry (at 20 0) (size 2.54 2.54) (stroke (width 0) (type default))
+                (uuid "00000000-0000-0000-0000-000000000002"))
+            (wire (pts (xy 12.54 2.54) (xy 12.54 10)) (stroke (width 0) (type default))
+                (uuid "00000000-0000-0000-0000-000000000003"))
+            (wire (pts (xy 22.54 2.54) (xy 22.54 10)) (stroke (width 0) (type default))
+                (uuid "00000000-0000-0000-0000-000000000004"))
+            (bus (pts (xy 0 0) (xy 15 0)) (stroke (width 0) (type default))
+                (uuid "00000000-0000-0000-0000-000000000005"))
+            (bus (pts (xy 15 0) (xy 30 0)) (stroke (width 0) (type default))
+                (uuid "00000000-0000-0000-0000-000000000006"))
+            (label "HOST{USB}" (at 5 0 0) (effects (font (size 1.27 1.27)))
+                (uuid "00000000-0000-0000-0000-000000000007"))
+            (label "HOST.DP" (at 12.54 10 0) (effects (font (size 1.27 1.27)))
+                (uuid "00000000-0000-0000-0000-000000000008"))
+            (label "HOST.TX" (at 22.54 10 0) (effects (font (size 1.27 1.27)))
+                (uuid "00000000-0000-0000-0000-000000000009")))"#;
+
+        let schematic: Schematic = parse_str(text).unwrap();
+        let aliases = bus_aliases(&schematic);
+        assert_eq!(aliases.len(), 1);
+
+        // The label on the first bus segment names the segment it joins, too.
+        let connections = bus_entry_connections(&schematic, &aliases);
+        let found: Vec<_> = connections.iter().map(|c| (c.bus.as_deref(), c.net.as_deref(), c.member)).collect();
+        assert_eq!(found, [(Some("HOST{USB}"), Some("HOST.DP"), true), (Some("HOST{USB}"), Some("HOST.TX"), false)]);
+    }
+}
@@ -0,0 +1,347 @@
+use {
+    crate::{
+        bus::on_path,
+        common::{Nm, Point, Positioned},
+        sch::Schematic,
+        Config,
+    },
+    std::collections::HashMap,
+    uuid::Uuid,
+};
+
+/// The standard schematic grid, 50 mil.
+pub const SCHEMATIC_GRID: Nm = Nm(1_270_000);
+
+/// Options for [`cleanup`], set through [`Config::cleanup`].
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct CleanupOptions {
+    /// The grid to snap to. Defaults to [`SCHEMATIC_GRID`].
+    pub grid: Nm,
+
+    /// How far a wire or bus segment may stray from horizontal or vertical and still be
+    /// straightened, measured across its length. Defaults to a quarter of the standard grid.
+    pub straighten_tolerance: Nm,
+}
+
+/// The kinds of schematic elements moved by [`cleanup`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CleanupKind {
+    /// A placed symbol, moved together with its fields.
+    Symbol,
+
+    /// A point of a wire.
+    Wire,
+
+    /// A point of a bus.
+    Bus,
+
+    /// A bus entry.
+    BusEntry,
+
+    /// A junction.
+    Junction,
+
+    /// A no-connect flag.
+    NoConnect,
+
+    /// A local label.
+    Label,
+
+    /// A global label, moved together with its fields.
+    GlobalLabel,
+}
+
+/// An element moved by [`cleanup`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct CleanupMove {
+    /// The kind of element.
+    pub kind: CleanupKind,
+
+    /// The UUID of the element.
+    pub uuid: Uuid,
+
+    /// For wires and buses, the index of the point that moved.
+    pub point: Option<usize>,
+
+    /// Where the element was.
+    pub from: (Nm, Nm),
+
+    /// Where the element is now.
+    pub to: (Nm, Nm),
+}
+
+impl Default for CleanupOptions {
+    fn default() -> Self {
+        Self {
+            grid: SCHEMATIC_GRID,
+            straighten_tolerance: SCHEMATIC_GRID / 4,
+        }
+    }
+}
+
+/// Snap a schematic to the grid and straighten its wiring, so that it can be edited in KiCad
+/// without connections coming apart. Imported and generated schematics are often slightly off
+/// the grid, which KiCad's editor cannot connect to.
+///
+/// Wire and bus segments that are nearly horizontal or vertical are straightened, then every
+/// wire and bus point is snapped to the grid. Points that coincided before still coincide, so
+/// connections between wires are kept; points within [`Config::tolerance`] of each other are
+/// taken to coincide, which joins wire ends that nearly meet. Junctions, no-connect flags, bus entries, and labels at
+/// a wire point follow it; labels part way along a segment stay on it. Symbols are snapped by
+/// their anchor, and their fields move with them. Every move is reported, in document order by
+/// kind.
+///
+/// Symbol pins are not checked against the wires that meet them, so a symbol whose pins are off
+/// the grid relative to its anchor may still need attention.
+pub fn cleanup(schematic: &mut Schematic, config: &Config) -> Vec<CleanupMove> {
+    let options = &config.cleanup;
+    let tolerance = config.tolerance as i64;
+    let grid = options.grid.0.max(1);
+    let snap = |value: i64| (value as f64 / grid as f64).round() as i64 * grid;
+    let mut moves = Vec::new();
+
+    // Map every wire and bus point to its new location, straightening before snapping.
+    let paths: Vec<Vec<(i64, i64)>> = schematic
+        .wires
+        .iter()
+        .map(|wire| &wire.points.points)
+        .chain(schematic.buses.iter().map(|bus| &bus.points.points))
+        .map(|points| points.iter().map(|point| (point.x.0, point.y.0)).collect())
+        .collect();
+
+    let joined = join_points(&paths, tolerance);
+    let mut mapped: HashMap<(i64, i64), (i64, i64)> = HashMap::new();
+    for path in &paths {
+        for segment in path.windows(2) {
+            let (start, end) = (joined[&segment[0]], joined[&segment[1]]);
+            let start = *mapped.entry(start).or_insert(start);
+            let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+
+            let straightened = if dy != 0 && dy.abs() <= options.straighten_tolerance.0 && dx.abs() > dy.abs() {
+                (end.0, start.1)
+            } else if dx != 0 && dx.abs() <= options.straighten_tolerance.0 && dy.abs() > dx.abs() {
+                (start.0, end.1)
+            } else {
+                end
+            };
+            mapped.entry(end).or_insert(straightened);
+        }
+    }
+
+    for point in paths.iter().flatten() {
+        let point = joined[point];
+        let target = mapped.entry(point).or_insert(point);
+        *target = (snap(target.0), snap(target.1));
+    }
+
+    let old_paths = paths;
+    let new_paths: Vec<Vec<(i64, i64)>> =
+        old_paths.iter().map(|path| path.iter().map(|point| mapped[&joined[point]]).collect()).collect();
+
+    let wire_paths = schematic.wires.iter_mut().map(|wire| (CleanupKind::Wire, wire.uuid, &mut wire.points.points));
+    let bus_paths = schematic.buses.iter_mut().map(|bus| (CleanupKind::Bus, bus.uuid, &mut bus.points.points));
+    for ((kind, uuid, points), new_path) in wire_paths.chain(bus_paths).zip(&new_paths) {
+        for (index, (point, &to)) in points.iter_mut().zip(new_path).enumerate() {
+            let from = (point.x.0, point.y.0);
+            if from != to {
+                (point.x, point.y) = (Nm(to.0), Nm(to.1));
+                moves.push(moved(kind, uuid, Some(index), from, to));
+            }
+        }
+    }
+
+    // Place an element that was on the wiring at the same spot on the new wiring.
+    let old_points: Vec<Vec<Point>> = old_paths.iter().map(|path| points(path)).collect();
+    let reanchor = |(x, y): (i64, i64)| {
+        let near = match joined.get(&(x, y)) {
+            Some(point) => Some(point),
+            None if tolerance > 0 => old_paths.iter().flatten().find(|&&point| within(point, (x, y), tolerance)),
+            None => None,
+        };
+        if let Some(point) = near {
+            return mapped[&joined[point]];
+        }
+
+        let on = old_points.iter().zip(&new_paths).find_map(|(old, new)| {
+            let index = old.windows(2).position(|segment| on_path(segment, (x, y)))?;
+            Some((new[index], new[index + 1]))
+        });
+        match on {
+            Some((a, b)) if a.1 == b.1 => (snap(x).clamp(a.0.min(b.0), a.0.max(b.0)), a.1),
+            Some((a, b)) if a.0 == b.0 => (a.0, snap(y).clamp(a.1.min(b.1), a.1.max(b.1))),
+            _ => (snap(x), snap(y)),
+        }
+    };
+
+    let mut place = |kind: CleanupKind, uuid: Uuid, element: &mut dyn Positioned, to: (i64, i64)| {
+        let position = element.position()?;
+        let from = (position.x.0, position.y.0);
+        if from == to {
+            return None;
+        }
+
+        let delta = (Nm(to.0 - from.0), Nm(to.1 - from.1));
+        element.translate(delta.0, delta.1);
+        moves.push(moved(kind, uuid, None, from, to));
+        Some(delta)
+    };
+
+    for entry in &mut schematic.bus_entries {
+        let to = reanchor((entry.position.x.0, entry.position.y.0));
+        place(CleanupKind::BusEntry, entry.uuid, entry, to);
+    }
+
+    for junction in &mut schematic.junctions {
+        let to = reanchor((junction.position.x.0, junction.position.y.0));
+        place(CleanupKind::Junction, junction.uuid, junction, to);
+    }
+
+    for no_connect in &mut schematic.no_connects {
+        let to = reanchor((no_connect.position.x.0, no_connect.position.y.0));
+        place(CleanupKind::NoConnect, no_connect.uuid, no_connect, to);
+    }
+
+    for label in &mut schematic.labels {
+        let to = reanchor((label.position.x.0, label.position.y.0));
+        place(CleanupKind::Label, label.uuid, label, to);
+    }
+
+    for label in &mut schematic.global_labels {
+        let to = reanchor((label.position.x.0, label.position.y.0));
+        if let Some((dx, dy)) = place(CleanupKind::GlobalLabel, label.uuid, label, to) {
+            label.properties.iter_mut().for_each(|property| property.translate(dx, dy));
+        }
+    }
+
+    for symbol in &mut schematic.symbols {
+        let to = (snap(symbol.position.x.0), snap(symbol.position.y.0));
+        if let Some((dx, dy)) = place(CleanupKind::Symbol, symbol.uuid, symbol, to) {
+            symbol.properties.iter_mut().for_each(|property| property.translate(dx, dy));
+        }
+    }
+
+    moves
+}
+
+/// Map every point of the paths to the first point within `tolerance` of it.
+fn join_points(paths: &[Vec<(i64, i64)>], tolerance: i64) -> HashMap<(i64, i64), (i64, i64)> {
+    let mut first_points = Vec::new();
+    let mut joined = HashMap::new();
+
+    for &point in paths.iter().flatten() {
+        if joined.contains_key(&point) {
+            continue;
+        }
+
+        let first = match tolerance {
+            0 => None,
+            _ => first_points.iter().copied().find(|&first| within(first, point, tolerance)),
+        };
+        joined.insert(point, first.unwrap_or(point));
+        if first.is_none() {
+            first_points.push(point);
+        }
+    }
+
+    joined
+}
+
+/// Indicates whether two points are within `tolerance` of each other along both axes.
+fn within(a: (i64, i64), b: (i64, i64), tolerance: i64) -> bool {
+    (a.0 - b.0).abs() <= tolerance && (a.1 - b.1).abs() <= tolerance
+}
+
+/// Build the record of a move.
+fn moved(kind: CleanupKind, uuid: Uuid, point: Option<usize>, from: (i64, i64), to: (i64, i64)) -> CleanupMove {
+    CleanupMove {
+        kind,
+        uuid,
+        point,
+        from: (Nm(from.0), Nm(from.1)),
+        to: (Nm(to.0), Nm(to.1)),
+    }
+}
+
+/// Convert a path of coordinates to points, for use with [`on_path`].
+fn points(path: &[(i64, i64)]) -> Vec<Point> {
+    path.iter().map(|&(x, y)| Point::new(Nm(x), Nm(y))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::parse_str};
+
+    #[test]
+    fn test_cleanup() {
+        let text = r#"(kicad_sch (version 20231120) (generator "eeschema")
+            (junction (at 25.5 10.1) (diameter 0) (color 0 0 0 0) (uuid "00000000-0000-0000-0000-000000000001"))
+            (wire (pts (xy 0.1 10) (xy 25.5 10.1)) (stroke (width 0) (type default))
+                (uuid "00000000-0000-0000-0000-000000000002"))
+            (wire (pts (xy 25.5 10.1) (xy 25.5 30)) (stroke (width 0) (type default))
+                (uuid "00000000-0000-0000-0000-000000000003"))
+            (label "CLK" (at 12.8 10.05 0) (effects (font (size 1.27 1.27)))
+                (uuid "00000000-0000-0000-0000-000000000004"))
+            (symbol (lib_id "Device:R") (at 50.9 50.8 0) (unit 1) (uuid "00000000-0000-0000-0000-000000000005")
+                (property "Reference" "R1" (at 53.0 50.8 0))))"#;
+
+        let mut schematic: Schematic = parse_str(text).unwrap();
+        let moves = cleanup(&mut schematic, &Config::default());
+
+        // The nearly horizontal wire is straightened and both wires still meet at the junction.
+        let points = |i: usize| -> Vec<(i64, i64)> {
+            schematic.wires[i].points.points.iter().map(|point| (point.x.0, point.y.0)).collect()
+        };
+        assert_eq!(points(0), [(0, 10_160_000), (25_400_000, 10_160_000)]);
+        assert_eq!(points(1), [(25_400_000, 10_160_000), (25_400_000, 30_480_000)]);
+        assert_eq!(
+            (schematic.junctions[0].position.x, schematic.junctions[0].position.y),
+            (Nm(25_400_000), Nm(10_160_000))
+        );
+
+        // The label stays on its wire, and the symbol's field moves with it.
+        assert_eq!((schematic.labels[0].position.x, schematic.labels[0].position.y), (Nm(12_700_000), Nm(10_160_000)));
+        assert_eq!(schematic.symbols[0].position.x, Nm(50_800_000));
+        assert_eq!(schematic.symbols[0].properties[0].position.as_ref().map(|at| at.x), Some(Nm(52_900_000)));
+
+        let kinds: Vec<_> = moves.iter().map(|m| (m.kind, m.point)).collect();
+        assert_eq!(
+            kinds,
+            [
+                (CleanupKind::Wire, Some(0)),
+                (CleanupKind::Wire, Some(1)),
+                (CleanupKind::Wire, Some(0)),
+                (CleanupKind::Wire, Some(1)),
+                (CleanupKind::Junction, None),
+                (CleanupKind::Label, None),
+                (CleanupKind::Symbol, None)
+            ]
+        );
+        assert!(cleanup(&mut schematic, &Config::default()).is_empty());
+    }
+
+    #[test]
+    fn test_cleanup_tolerance() {
+        let text = r#"(kicad_sch (version 20231120) (generator "eeschema")
+            (wire (pts (xy 0 10.16) (xy 25.4 10.16)) (stroke (width 0) (type default))
+                (uuid "00000000-0000-0000-0000-000000000001"))
+            (wire (pts (xy 26.1 10.16) (xy 50.8 10.16)) (stroke (width 0) (type default))
+                (uuid "00000000-0000-0000-0000-000000000002")))"#;
+        let start =
+            |schematic: &Schematic| (schematic.wires[1].points.points[0].x, schematic.wires[1].points.points[0].y);
+
+        // Without a tolerance, the nearly meeting ends snap to different grid points.
+        let mut schematic: Schematic = parse_str(text).unwrap();
+        cleanup(&mut schematic, &Config::default());
+        assert_eq!(start(&schematic), (Nm(26_670_000), Nm(10_160_000)));
+
+        // Within the tolerance, they are joined.
+        let mut schematic: Schematic = parse_str(text).unwrap();
+        cleanup(&mut schematic, &Config::builder().tolerance(1_000_000).build());
+        assert_eq!(start(&schematic), (Nm(25_400_000), Nm(10_160_000)));
+    }
+}
@@ -0,0 +1,391 @@
+use {
+    crate::Error,
+    serde::{Deserialize, Serialize},
+    std::{
+        ffi::OsString,
+        io::Error as IoError,
+        path::{Path, PathBuf},
+        process::Command,
+    },
+};
+
+/// The program run when no path is given.
+pub const DEFAULT_PROGRAM: &str = "kicad-cli";
+
+/// A `kicad-cli` installation, used for capabilities this crate does not implement natively,
+/// such as rendering and plotting.
+///
+/// This is only available with the `kicad-cli` feature; nothing else in the crate runs KiCad.
+#[derive(Clone, Debug)]
+pub struct KicadCli {
+    program: PathBuf,
+}
+
+/// A `kicad-cli` invocation.
+pub trait CliCommand {
+    /// Return the arguments following the program name.
+    fn args(&self) -> Vec<OsString>;
+}
+
+/// The side of the board a 3D render is viewed from.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RenderSide {
+    #[default]
+    Top,
+    Bottom,
+    Left,
+    Right,
+    Front,
+    Back,
+}
+
+/// A 3D render of a board: `kicad-cli pcb render` (KiCad 9+).
+#[derive(Clone, Debug)]
+pub struct Render3d {
+    input: PathBuf,
+    output: PathBuf,
+    width: u32,
+    height: u32,
+    side: RenderSide,
+    zoom: Option<f64>,
+    transparent: bool,
+    high_quality: bool,
+}
+
+/// A PDF plot of a schematic: `kicad-cli sch export pdf`.
+#[derive(Clone, Debug)]
+pub struct SchematicPdf {
+    input: PathBuf,
+    output: PathBuf,
+    black_and_white: bool,
+    exclude_drawing_sheet: bool,
+}
+
+/// A design rules check: `kicad-cli pcb drc` with a JSON report.
+#[derive(Clone, Debug)]
+pub struct Drc {
+    input: PathBuf,
+    output: PathBuf,
+    schematic_parity: bool,
+}
+
+/// A DRC report, as written by [`Drc`].
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct DrcReport {
+    /// Rule violations.
+    #[serde(default)]
+    pub violations: Vec<DrcViolation>,
+
+    /// Pads and tracks that should be connected but are not.
+    #[serde(default)]
+    pub unconnected_items: Vec<DrcViolation>,
+
+    /// Differences between the board and the schematic, if parity was checked.
+    #[serde(default)]
+    pub schematic_parity: Vec<DrcViolation>,
+}
+
+/// A violation in a [`DrcReport`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DrcViolation {
+    /// The rule identifier, e.g. `clearance`.
+    #[serde(rename = "type")]
+    pub kind: String,
+
+    /// A human-readable description.
+    pub description: String,
+
+    /// `error`, `warning`, or `exclusion`.
+    pub severity: String,
+}
+
+impl KicadCli {
+    /// Use `kicad-cli` from the search path.
+    pub fn new() -> Self {
+        Self::with_program(DEFAULT_PROGRAM)
+    }
+
+    /// Use the `kicad-cli` program at `program`.
+    pub fn with_program<P>(program: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self {
+            program: program.into(),
+        }
+    }
+
+    /// Return the version of KiCad, e.g. `8.0.4`.
+    pub fn version(&self) -> Result<String, Error> {
+        let stdout = self.run_args(vec!["version".into()])?;
+        Ok(stdout.trim().to_string())
+    }
+
+    /// Run a command, returning its standard output.
+    pub fn run(&self, command: &impl CliCommand) -> Result<String, Error> {
+        self.run_args(command.args())
+    }
+
+    /// Run a DRC and parse its report.
+    pub fn drc(&self, drc: &Drc) -> Result<DrcReport, Error> {
+        self.run(drc)?;
+        let path = &drc.output;
+        let json = std::fs::read_to_string(path)?;
+        parse_drc_report(&json)
+    }
+
+    /// Run the program with `args`, returning its standard output.
+    fn run_args(&self, args: Vec<OsString>) -> Result<String, Error> {
+        let output = Command::new(&self.program).args(&args).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(IoError::other(format!(
+                "{} failed ({}): {}",
+                self.program.display(),
+                output.status,
+                stderr.trim()
+            ))
+            .into());
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+impl Default for KicadCli {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RenderSide {
+    /// Return the value of the `--side` option.
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Top => "top",
+            Self::Bottom => "bottom",
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Front => "front",
+            Self::Back => "back",
+        }
+    }
+}
+
+impl Render3d {
+    /// Render a board to a PNG or JPEG image, 1600x900 from the top by default.
+    pub fn new<I, O>(input: I, output: O) -> Self
+    where
+        I: Into<PathBuf>,
+        O: Into<PathBuf>,
+    {
+        Self {
+            input: input.into(),
+            output: output.into(),
+            width: 1600,
+            height: 900,
+            side: RenderSide::Top,
+            zoom: None,
+            transparent: false,
+            high_quality: false,
+        }
+    }
+
+    /// Set the image size in pixels.
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Set the side the board is viewed from.
+    pub fn side(mut self, side: RenderSide) -> Self {
+        self.side = side;
+        self
+    }
+
+    /// Set the zoom factor.
+    pub fn zoom(mut self, zoom: f64) -> Self {
+        self.zoom = Some(zoom);
+        self
+    }
+
+    /// Render with a transparent background.
+    pub fn transparent(mut self, transparent: bool) -> Self {
+        self.transparent = transparent;
+        self
+    }
+
+    /// Use raytracing rather than the basic renderer.
+    pub fn high_quality(mut self, high_quality: bool) -> Self {
+        self.high_quality = high_quality;
+        self
+    }
+}
+
+impl CliCommand for Render3d {
+    fn args(&self) -> Vec<OsString> {
+        let mut args: Vec<OsString> = ["pcb", "render", "--output"].map(OsString::from).into();
+        args.push(self.output.clone().into());
+        for (flag, value) in [("--width", self.width), ("--height", self.height)] {
+            args.extend([flag.into(), value.to_string().into()]);
+        }
+        args.extend(["--side".into(), self.side.as_str().into()]);
+        if let Some(zoom) = self.zoom {
+            args.extend(["--zoom".into(), zoom.to_string().into()]);
+        }
+        if self.transparent {
+            args.extend(["--background".into(), "transparent".into()]);
+        }
+        if self.high_quality {
+            args.extend(["--quality".into(), "high".into()]);
+        }
+        args.push(self.input.clone().into());
+        args
+    }
+}
+
+impl SchematicPdf {
+    /// Plot every sheet of a schematic to a single PDF.
+    pub fn new<I, O>(input: I, output: O) -> Self
+    where
+        I: Into<PathBuf>,
+        O: Into<PathBuf>,
+    {
+        Self {
+            input: input.into(),
+            output: output.into(),
+            black_and_white: false,
+            exclude_drawing_sheet: false,
+        }
+    }
+
+    /// Plot in black and white.
+    pub fn black_and_white(mut self, black_and_white: bool) -> Self {
+        self.black_and_white = black_and_white;
+        self
+    }
+
+    /// Omit the drawing sheet (frame and title block).
+    pub fn exclude_drawing_sheet(mut self, exclude: bool) -> Self {
+        self.exclude_drawing_sheet = exclude;
+        self
+    }
+}
+
+impl CliCommand for SchematicPdf {
+    fn args(&self) -> Vec<OsString> {
+        let mut args: Vec<OsString> = ["sch", "export", "pdf", "--output"].map(OsString::from).into();
+        args.push(self.output.clone().into());
+        if self.black_and_white {
+            args.push("--black-and-white".into());
+        }
+        if self.exclude_drawing_sheet {
+            args.push("--exclude-drawing-sheet".into());
+        }
+        args.push(self.input.clone().into());
+        args
+    }
+}
+
+impl Drc {
+    /// Check a board, writing a JSON report to `output`.
+    pub fn new<I, O>(input: I, output: O) -> Self
+    where
+        I: Into<PathBuf>,
+        O: Into<PathBuf>,
+    {
+        Self {
+            input: input.into(),
+            output: output.into(),
+            schematic_parity: false,
+        }
+    }
+
+    /// Also check that the board matches its schematic.
+    pub fn schematic_parity(mut self, parity: bool) -> Self {
+        self.schematic_parity = parity;
+        self
+    }
+
+    /// Return the path of the report.
+    pub fn output(&self) -> &Path {
+        &self.output
+    }
+}
+
+impl CliCommand for Drc {
+    fn args(&self) -> Vec<OsString> {
+        let mut args: Vec<OsString> =
+            ["pcb", "drc", "--format", "json", "--severity-all", "--output"].map(OsString::from).into();
+        args.push(self.output.clone().into());
+        if self.schematic_parity {
+            args.push("--schematic-parity".into());
+        }
+        args.push(self.input.clone().into());
+        args
+    }
+}
+
+impl DrcReport {
+    /// Return the number of items with `error` severity across all sections.
+    pub fn error_count(&self) -> usize {
+        [&self.violations, &self.unconnected_items, &self.schematic_parity]
+            .into_iter()
+            .flatten()
+            .filter(|violation| violation.severity == "error")
+            .count()
+    }
+}
+
+/// Parse a DRC report written by `kicad-cli pcb drc --format json`.
+pub fn parse_drc_report(json: &str) -> Result<DrcReport, Error> {
+    Ok(serde_json::from_str(json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_commands() {
+        let render =
+            Render3d::new("demo.kicad_pcb", "demo.png").size(800, 600).side(RenderSide::Bottom).transparent(true);
+        assert_eq!(
+            render.args(),
+            [
+                "pcb",
+                "render",
+                "--output",
+                "demo.png",
+                "--width",
+                "800",
+                "--height",
+                "600",
+                "--side",
+                "bottom",
+                "--background",
+                "transparent",
+                "demo.kicad_pcb"
+            ]
+            .map(OsString::from)
+        );
+
+        let drc = Drc::new("demo.kicad_pcb", "drc.json").schematic_parity(true);
+        assert_eq!(drc.args().last(), Some(&OsString::from("demo.kicad_pcb")));
+        assert!(drc.args().contains(&OsString::from("--schematic-parity")));
+
+        let report = parse_drc_report(
+            r#"{"source": "demo.kicad_pcb", "violations": [
+                {"type": "clearance", "description": "Clearance violation", "severity": "error", "items": []},
+                {"type": "silk_overlap", "description": "Silkscreen overlap", "severity": "warning", "items": []}],
+               "unconnected_items": [{"type": "unconnected_items", "description": "Missing connection",
+                "severity": "error", "items": []}]}"#,
+        )
+        .unwrap();
+        assert_eq!(report.error_count(), 2);
+        assert!(report.schematic_parity.is_empty());
+    }
+}
@@ -0,0 +1,160 @@
+use {
+    crate::LexprExt,
+    kanga_sexpr::units::Unit,
+    lexpr::Value,
+    std::{
+        fmt::{Display, Formatter, Result as FmtResult},
+        ops::{Add, Neg, Sub},
+    },
+};
+
+/// A rotation angle in degrees, normalized to the range `[0, 360)`.
+///
+/// KiCad's Y axis points down, so a positive angle rotates counterclockwise as displayed on screen
+/// (clockwise in conventional Cartesian coordinates). [`Angle::rotate`] follows this convention.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(from = "f64", into = "f64"))]
+pub struct Angle(f64);
+
+impl Angle {
+    /// No rotation.
+    pub const ZERO: Self = Self(0.0);
+
+    /// Create an angle from a value in degrees, normalizing it to `[0, 360)`.
+    pub fn from_degrees(degrees: f64) -> Self {
+        let degrees = degrees.rem_euclid(360.0);
+
+        // rem_euclid can round up to exactly 360 for tiny negative inputs; -0.0 is also folded here.
+        if degrees >= 360.0 || degrees == 0.0 {
+            Self(0.0)
+        } else {
+            Self(degrees)
+        }
+    }
+
+    /// Create an angle from a number of 90° steps.
+    pub fn from_quarter_turns(turns: i32) -> Self {
+        Self(f64::from(turns.rem_euclid(4) * 90))
+    }
+
+    /// Return the angle in degrees, in the range `[0, 360)`.
+    pub fn degrees(self) -> f64 {
+        self.0
+    }
+
+    /// Return the angle in radians, in the range `[0, 2π)`.
+    pub fn radians(self) -> f64 {
+        self.0.to_radians()
+    }
+
+    /// If this angle is an exact multiple of 90°, return the number of 90° steps (0 to 3).
+    pub fn quarter_turns(self) -> Option<u8> {
+        match self.0 {
+            0.0 => Some(0),
+            90.0 => Some(1),
+            180.0 => Some(2),
+            270.0 => Some(3),
+            _ => None,
+        }
+    }
+
+    /// Indicates whether this angle is an exact multiple of 90°.
+    pub fn is_orthogonal(self) -> bool {
+        self.quarter_turns().is_some()
+    }
+
+    /// Rotate a point about the origin by this angle using KiCad's conventions (Y axis pointing down,
+    /// positive angles counterclockwise on screen).
+    ///
+    /// Multiples of 90° are computed exactly; other angles are rounded to the nearest unit.
+    pub fn rotate(self, x: i64, y: i64) -> (i64, i64) {
+        match self.quarter_turns() {
+            Some(0) => (x, y),
+            Some(1) => (y, -x),
+            Some(2) => (-x, -y),
+            Some(3) => (-y, x),
+            _ => {
+                let (sin, cos) = self.radians().sin_cos();
+                let (x, y) = (x as f64, y as f64);
+                ((x * cos + y * sin).round() as i64, (y * cos - x * sin).round() as i64)
+            }
+        }
+    }
+}
+
+impl Add for Angle {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::from_degrees(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Angle {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::from_degrees(self.0 - rhs.0)
+    }
+}
+
+impl Neg for Angle {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::from_degrees(-self.0)
+    }
+}
+
+impl Display for Angle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Angles declared with `as Angle` are written in degrees and normalized when read.
+impl Unit for Angle {
+    type Value = Angle;
+
+    fn from_sexpr(value: &Value) -> Option<Angle> {
+        value.as_float().map(Self::from_degrees)
+    }
+
+    fn to_sexpr(value: &Angle) -> Value {
+        Value::from(value.degrees())
+    }
+}
+
+impl From<f64> for Angle {
+    fn from(degrees: f64) -> Self {
+        Self::from_degrees(degrees)
+    }
+}
+
+impl From<Angle> for f64 {
+    fn from(angle: Angle) -> Self {
+        angle.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_angle() {
+        assert_eq!(Angle::from_degrees(-90.0).degrees(), 270.0);
+        assert_eq!(Angle::from_degrees(720.0), Angle::ZERO);
+        assert_eq!(Angle::from_degrees(-1e-20), Angle::ZERO);
+        assert_eq!(Angle::from_degrees(450.0).quarter_turns(), Some(1));
+        assert_eq!(Angle::from_quarter_turns(-1).degrees(), 270.0);
+        assert!(!Angle::from_degrees(45.0).is_orthogonal());
+        assert_eq!(Angle::from_degrees(270.0) + Angle::from_degrees(180.0), Angle::from_degrees(90.0));
+
+        // A point to the right of the origin moves up the screen (negative Y) when rotated by 90°.
+        assert_eq!(Angle::from_degrees(90.0).rotate(1_000, 0), (0, -1_000));
+        assert_eq!(Angle::from_degrees(45.0).rotate(1_000, 0), (707, -707));
+    }
+}
@@ -1,12 +1,14 @@
 use {
-    crate::{impl_try_from_cons_value, LexprExt, ParseError},
-    lexpr::Cons,
-    serde::{Deserialize, Serialize},
+    super::sexpr_list,
+    crate::{impl_sexpr_leaf, impl_try_from_cons_value, LexprExt, ParseError},
+    lexpr::{Cons, Value},
 };
 
 /// Color in RGBA format
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "color")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "color", deny_unknown_fields))]
 pub struct Color {
     /// Red component
     pub red: f64,
@@ -48,3 +50,11 @@ impl TryFrom<&Cons> for Color {
 }
 
 impl_try_from_cons_value!(Color);
+impl_sexpr_leaf!(Color, "color");
+
+impl From<&Color> for Value {
+    fn from(color: &Color) -> Self {
+        let channels = [Some(color.red), Some(color.green), Some(color.blue), color.alpha];
+        sexpr_list("color", channels.into_iter().flatten().map(Value::from))
+    }
+}
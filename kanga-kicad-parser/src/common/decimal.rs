@@ -0,0 +1,238 @@
+use {
+    super::Nm,
+    crate::ParseError,
+    lexpr::Value,
+    std::{
+        fmt::{Display, Formatter, Result as FmtResult},
+        str::FromStr,
+    },
+};
+
+/// The number of decimal places in a millimeter value that map onto whole nanometers.
+const NM_SCALE: u32 = 6;
+
+/// The most decimal places a float needs to round-trip.
+const MAX_F64_SCALE: u32 = 17;
+
+/// A decimal number kept as written: an integer mantissa and the number of digits after the
+/// decimal point.
+///
+/// Binary floating point cannot represent most millimeter values exactly, so converting them to
+/// nanometers by multiplying by 1e6 can land one nanometer short. A `Decimal` converts to
+/// nanometers exactly, and its [`Display`] reproduces the original text, including trailing
+/// zeros. [`read_str`](crate::read_str) keeps every non-integer number as the text of a `Decimal`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Decimal {
+    /// The digits of the number, without the decimal point.
+    pub mantissa: i64,
+
+    /// The number of digits after the decimal point.
+    pub scale: u32,
+}
+
+impl Decimal {
+    /// Create a decimal with the value `mantissa * 10^-scale`.
+    pub fn new(mantissa: i64, scale: u32) -> Self {
+        Self {
+            mantissa,
+            scale,
+        }
+    }
+
+    /// Return the millimeter value of a length in nanometers, with trailing zeros removed as KiCad
+    /// writes it.
+    pub fn from_nm(nm: i64) -> Self {
+        Self::new(nm, NM_SCALE).normalized()
+    }
+
+    /// Convert a millimeter value to nanometers. Returns `None` if the value has more precision
+    /// than a nanometer or is out of range.
+    pub fn to_nm(self) -> Option<i64> {
+        if self.scale <= NM_SCALE {
+            self.mantissa.checked_mul(10i64.checked_pow(NM_SCALE - self.scale)?)
+        } else {
+            let divisor = 10i64.checked_pow(self.scale - NM_SCALE)?;
+            (self.mantissa % divisor == 0).then_some(self.mantissa / divisor)
+        }
+    }
+
+    /// Convert a millimeter value to a length, exactly when it has at most six decimal places and
+    /// rounded half away from zero to the nearest nanometer otherwise.
+    pub fn to_length(self) -> Nm {
+        if self.scale <= NM_SCALE {
+            return Nm(self.to_nm().unwrap_or_else(|| mm_to_nm(self.to_f64())));
+        }
+
+        // Digits this far past the nanometer leave nothing to round up.
+        let Some(divisor) = 10i64.checked_pow(self.scale - NM_SCALE) else {
+            return Nm::ZERO;
+        };
+        let half = divisor / 2 * self.mantissa.signum();
+        Nm(self.mantissa.saturating_add(half) / divisor)
+    }
+
+    /// Return this value with trailing fractional zeros removed.
+    pub fn normalized(mut self) -> Self {
+        while self.scale > 0 && self.mantissa % 10 == 0 {
+            self.mantissa /= 10;
+            self.scale -= 1;
+        }
+        self
+    }
+
+    /// Return this value as a float.
+    pub fn to_f64(self) -> f64 {
+        self.mantissa as f64 / 10f64.powi(self.scale as i32)
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let sign = if self.mantissa < 0 {
+            "-"
+        } else {
+            ""
+        };
+
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{sign}{digits}");
+        }
+
+        let digits = format!("{digits:0>width$}", width = scale + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{sign}{whole}.{fraction}")
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = ParseError;
+
+    /// Parse a decimal number such as `-1.270` or `2.5e-3`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseError::InvalidElement("decimal number".to_string(), format!("{s:?}"));
+
+        let (number, exponent) = match s.find(['e', 'E']) {
+            Some(index) => (&s[..index], s[index + 1..].parse::<i32>().map_err(|_| invalid())?),
+            None => (s, 0),
+        };
+
+        let (negative, number) = match number.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, number.strip_prefix('+').unwrap_or(number)),
+        };
+
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+        if whole.is_empty() && fraction.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut mantissa: i64 = 0;
+        for c in whole.chars().chain(fraction.chars()) {
+            let digit = c.to_digit(10).ok_or_else(invalid)?;
+            mantissa = mantissa.checked_mul(10).and_then(|m| m.checked_add(digit as i64)).ok_or_else(invalid)?;
+        }
+
+        let mut scale = fraction.len() as i64 - exponent as i64;
+        while scale < 0 {
+            mantissa = mantissa.checked_mul(10).ok_or_else(invalid)?;
+            scale += 1;
+        }
+
+        Ok(Self::new(
+            if negative {
+                -mantissa
+            } else {
+                mantissa
+            },
+            u32::try_from(scale).map_err(|_| invalid())?,
+        ))
+    }
+}
+
+impl TryFrom<f64> for Decimal {
+    type Error = ParseError;
+
+    /// Convert a float to the decimal with the fewest places that reads back as the same float,
+    /// which recovers the number as it was written.
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        for scale in 0..=MAX_F64_SCALE {
+            let power = 10f64.powi(scale as i32);
+            let mantissa = (value * power).round();
+            if mantissa.abs() >= i64::MAX as f64 {
+                break;
+            }
+            if mantissa / power == value {
+                return Ok(Self::new(mantissa as i64, scale));
+            }
+        }
+
+        Err(ParseError::Unexpected(Value::from(value)))
+    }
+}
+
+impl TryFrom<&Value> for Decimal {
+    type Error = ParseError;
+
+    /// Convert a number, or a symbol holding the text of a decimal number as kept by
+    /// [`read_str`](crate::read_str) and written by `From<Decimal> for Value`.
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Number(number) => match number.as_i64() {
+                Some(int) => Ok(Self::new(int, 0)),
+                None => number.as_f64().ok_or_else(|| ParseError::Unexpected(value.clone()))?.try_into(),
+            },
+            Value::Symbol(text) => text.parse(),
+            _ => Err(ParseError::Unexpected(value.clone())),
+        }
+    }
+}
+
+impl From<Decimal> for Value {
+    /// Write the number exactly as it is displayed. The value is a bare symbol, since a float would
+    /// lose trailing zeros and may be written in exponent form.
+    fn from(decimal: Decimal) -> Self {
+        Value::symbol(decimal.to_string())
+    }
+}
+
+/// Convert a millimeter value to nanometers, rounding to the nearest nanometer.
+///
+/// A value with at most six decimal places converts exactly: the product is within a rounding
+/// error of a whole number, where truncating it could land one nanometer short.
+pub(crate) fn mm_to_nm(mm: f64) -> i64 {
+    (mm * 1e6).round() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimal() {
+        let decimal: Decimal = "-1.270".parse().unwrap();
+        assert_eq!(decimal, Decimal::new(-1270, 3));
+        assert_eq!(decimal.to_string(), "-1.270");
+        assert_eq!(decimal.to_nm(), Some(-1_270_000));
+        assert_eq!("0.05".parse::<Decimal>().unwrap().to_string(), "0.05");
+        assert_eq!("2.5e-3".parse::<Decimal>().unwrap().to_nm(), Some(2_500));
+        assert_eq!("0.0000001".parse::<Decimal>().unwrap().to_nm(), None);
+        assert!("1.2.3".parse::<Decimal>().is_err());
+
+        // 1.001 * 1e6 is 1000999.9999999999 in binary floating point.
+        assert_eq!(mm_to_nm(1.001), 1_001_000);
+        assert_eq!(Decimal::from_nm(1_001_000).to_string(), "1.001");
+        assert_eq!(Decimal::from_nm(-2_000_000).to_string(), "-2");
+
+        let value = Value::from(Decimal::from_nm(1_270_000));
+        assert_eq!(lexpr::to_string(&value).unwrap(), "1.27");
+        assert_eq!(Decimal::try_from(&value).unwrap().to_nm(), Some(1_270_000));
+        assert_eq!(Decimal::try_from(&Value::from(0.57)).unwrap(), Decimal::new(57, 2));
+        assert_eq!(Decimal::try_from(&Value::from(-3)).unwrap(), Decimal::new(-3, 0));
+        assert_eq!(Decimal::try_from(1e-7).unwrap(), Decimal::new(1, 7));
+        assert!(Decimal::try_from(f64::NAN).is_err());
+        assert_eq!(Decimal::new(12_345_675, 7).to_length(), Nm(1_234_568));
+        assert_eq!(Decimal::new(-15, 7).to_length(), Nm(-2));
+    }
+}
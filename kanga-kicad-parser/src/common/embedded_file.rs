@@ -0,0 +1,275 @@
+use {
+    super::sexpr_list,
+    crate::{impl_try_from_cons_value, LexprExt, ParseError},
+    lexpr::{Cons, Value},
+};
+
+#[cfg(feature = "tools")]
+use {
+    crate::Error,
+    std::{
+        fs::{create_dir_all, write},
+        io::Read,
+        path::{Path, PathBuf},
+    },
+};
+
+/// The seed KiCad uses when hashing embedded file contents.
+#[cfg(feature = "tools")]
+const CHECKSUM_SEED: u32 = 0xABBA_2345;
+
+/// The purpose of a file embedded in a document.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "type", rename_all = "snake_case"))]
+pub enum EmbeddedFileType {
+    /// A file with no particular purpose.
+    #[default]
+    Other,
+
+    /// A 3D model of a footprint.
+    Model,
+
+    /// A drawing sheet.
+    Worksheet,
+
+    /// A font used by text in the document.
+    Font,
+
+    /// A datasheet.
+    Datasheet,
+}
+
+/// A file embedded in a document (version 9+).
+///
+/// The contents are kept as KiCad stores them, Zstandard-compressed and base64-encoded, so that
+/// documents round-trip without recompressing their files. With the `tools` feature,
+/// [`EmbeddedFile::decode`] and [`EmbeddedFile::encode`] convert to and from the raw contents.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "file", deny_unknown_fields))]
+pub struct EmbeddedFile {
+    /// The file name, without a directory.
+    pub name: String,
+
+    /// The purpose of the file.
+    #[cfg_attr(feature = "serde", serde(rename = "type", default))]
+    pub file_type: EmbeddedFileType,
+
+    /// The compressed, base64-encoded contents, without whitespace. Files in libraries may be
+    /// listed without their contents.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub data: Option<String>,
+
+    /// The hash of the uncompressed contents, as written by KiCad.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub checksum: Option<String>,
+}
+
+impl EmbeddedFileType {
+    /// Return the name KiCad uses for this type.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Other => "other",
+            Self::Model => "model",
+            Self::Worksheet => "worksheet",
+            Self::Font => "font",
+            Self::Datasheet => "datasheet",
+        }
+    }
+}
+
+impl TryFrom<&Cons> for EmbeddedFile {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let mut name = None;
+        let mut file_type = EmbeddedFileType::Other;
+        let mut data = None;
+        let mut checksum = None;
+
+        let rest = cons.expect_cons_with_symbol_head("file")?;
+        for element in rest.list_iter().into_iter().flatten() {
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+            let arg = cdr.as_cons().map(Cons::car);
+            let text = arg.and_then(|arg| arg.as_str().or_else(|| arg.as_symbol()));
+
+            match (key, text) {
+                ("name", Some(text)) => name = Some(text.to_string()),
+                ("type", Some(text)) => {
+                    file_type = match text {
+                        "other" => EmbeddedFileType::Other,
+                        "model" => EmbeddedFileType::Model,
+                        "worksheet" => EmbeddedFileType::Worksheet,
+                        "font" => EmbeddedFileType::Font,
+                        "datasheet" => EmbeddedFileType::Datasheet,
+                        _ => return Err(ParseError::Unexpected(element.clone())),
+                    }
+                }
+                ("data", Some(text)) => data = Some(text.chars().filter(|c| !c.is_ascii_whitespace()).collect()),
+                ("checksum", Some(text)) => checksum = Some(text.to_string()),
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        Ok(Self {
+            name: name.ok_or_else(|| ParseError::missing_field("EmbeddedFile", "name", Value::Cons(cons.clone())))?,
+            file_type,
+            data,
+            checksum,
+        })
+    }
+}
+
+impl_try_from_cons_value!(EmbeddedFile);
+
+impl From<&EmbeddedFile> for Value {
+    fn from(file: &EmbeddedFile) -> Self {
+        let mut items = vec![
+            sexpr_list("name", [Value::string(file.name.as_str())]),
+            sexpr_list("type", [Value::symbol(file.file_type.as_str())]),
+        ];
+
+        // KiCad delimits the data with bars rather than quotes; the writer copies symbols verbatim.
+        if let Some(data) = &file.data {
+            items.push(sexpr_list("data", [Value::symbol(format!("|{data}|"))]));
+        }
+
+        if let Some(checksum) = &file.checksum {
+            items.push(sexpr_list("checksum", [Value::string(checksum.as_str())]));
+        }
+
+        sexpr_list("file", items)
+    }
+}
+
+#[cfg(feature = "tools")]
+impl EmbeddedFile {
+    /// Embed a file, compressing and encoding its contents and computing its checksum.
+    pub fn encode(name: &str, file_type: EmbeddedFileType, contents: &[u8]) -> Self {
+        use {
+            base64::{engine::general_purpose::STANDARD, Engine},
+            ruzstd::encoding::{compress_to_vec, CompressionLevel},
+        };
+
+        Self {
+            name: name.to_string(),
+            file_type,
+            data: Some(STANDARD.encode(compress_to_vec(contents, CompressionLevel::Fastest))),
+            checksum: Some(checksum(contents)),
+        }
+    }
+
+    /// Decode and decompress the contents of the file, verifying them against the checksum if it
+    /// is present.
+    pub fn decode(&self) -> Result<Vec<u8>, ParseError> {
+        use {
+            base64::{engine::general_purpose::STANDARD, Engine},
+            ruzstd::decoding::StreamingDecoder,
+        };
+
+        let invalid = |reason: String| ParseError::InvalidEmbeddedFile(self.name.clone(), reason);
+
+        let data = self.data.as_deref().ok_or_else(|| invalid("no data".to_string()))?;
+        let compressed = STANDARD.decode(data).map_err(|e| invalid(e.to_string()))?;
+
+        let mut contents = Vec::new();
+        StreamingDecoder::new(compressed.as_slice())
+            .map_err(|e| invalid(e.to_string()))?
+            .read_to_end(&mut contents)
+            .map_err(|e| invalid(e.to_string()))?;
+
+        match &self.checksum {
+            Some(expected) if !expected.eq_ignore_ascii_case(&checksum(&contents)) => {
+                Err(invalid("checksum mismatch".to_string()))
+            }
+            _ => Ok(contents),
+        }
+    }
+
+    /// Decode the file and write it into `dir` under its name, returning the path written.
+    pub fn extract_to(&self, dir: &Path) -> Result<PathBuf, Error> {
+        // Names come from the document; never let one escape the directory.
+        let name = Path::new(&self.name)
+            .file_name()
+            .ok_or_else(|| ParseError::InvalidEmbeddedFile(self.name.clone(), "invalid file name".to_string()))?;
+        let path = dir.join(name);
+
+        let contents = self.decode()?;
+        create_dir_all(dir)?;
+        write(&path, contents)?;
+        Ok(path)
+    }
+}
+
+/// Return the checksum KiCad records for file contents: the 128-bit MurmurHash3 of the contents,
+/// as hexadecimal bytes in memory order.
+#[cfg(feature = "tools")]
+fn checksum(contents: &[u8]) -> String {
+    use std::fmt::Write as _;
+
+    let hash = murmur3::murmur3_x64_128(&mut &contents[..], CHECKSUM_SEED).unwrap_or_default();
+    let (h1, h2) = (hash as u64, (hash >> 64) as u64);
+    h1.to_le_bytes().iter().chain(&h2.to_le_bytes()).fold(String::with_capacity(32), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{parse_str, sch::Schematic, writer::to_kicad_string},
+    };
+
+    #[test]
+    fn test_embedded_files() {
+        let text = "(kicad_sch (version 20250114) (generator \"eeschema\")
+            (text \"a|b\" (at 0 0 0) (effects (font (size 1.27 1.27))) (uuid \"ca81037f-ceb2-46ec-819c-e386c1f2833e\"))
+            (embedded_fonts no)
+            (embedded_files
+                (file (name \"notes.txt\") (type datasheet)
+                    (data |KLUv/QBYSQAA
+                        aGVsbG8=|)
+                    (checksum \"0123\"))))";
+
+        let schematic: Schematic = parse_str(text).unwrap();
+        assert_eq!(schematic.texts[0].text, "a|b");
+        assert_eq!(schematic.embedded_fonts, Some(false));
+
+        let file = &schematic.embedded_files[0];
+        assert_eq!((file.name.as_str(), file.file_type), ("notes.txt", EmbeddedFileType::Datasheet));
+        assert_eq!(file.data.as_deref(), Some("KLUv/QBYSQAAaGVsbG8="));
+        assert!(to_kicad_string(&Value::from(file)).contains("(data |KLUv/QBYSQAAaGVsbG8=|)"));
+
+        #[cfg(feature = "tools")]
+        {
+            let file = EmbeddedFile::encode("notes.txt", EmbeddedFileType::Other, b"hello, world");
+            assert_eq!(file.decode().unwrap(), b"hello, world");
+            assert_eq!(file.checksum.as_ref().map(String::len), Some(32));
+
+            let reread =
+                EmbeddedFile::try_from(&crate::span::read_str(&to_kicad_string(&Value::from(&file))).unwrap()).unwrap();
+            assert_eq!(reread, file);
+
+            let tampered = EmbeddedFile {
+                checksum: Some("0".repeat(32)),
+                ..file.clone()
+            };
+            assert!(matches!(tampered.decode(), Err(ParseError::InvalidEmbeddedFile(..))));
+
+            let dir = std::env::temp_dir().join(format!("kanga-embedded-{}", std::process::id()));
+            let escaping = EmbeddedFile {
+                name: "../notes.txt".to_string(),
+                ..file
+            };
+            let path = escaping.extract_to(&dir).unwrap();
+            assert_eq!(path, dir.join("notes.txt"));
+            assert_eq!(std::fs::read(&path).unwrap(), b"hello, world");
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+}
@@ -1,83 +1,75 @@
-use {
-    crate::{impl_try_from_cons_value, LexprExt, ParseError},
-    lexpr::{Cons, Value},
-    serde::{Deserialize, Serialize},
-};
+use {super::Color, kanga_sexpr::sexpr};
 
-/// KiCad schematic and symbol graphical fill definition.
-///
-/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_symbol_arc)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "fill")]
-pub struct Fill {
-    /// Fill type.
-    #[serde(rename = "type")]
-    pub fill_type: FillType,
-}
-
-/// KiCad fill type.
-///
-/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_symbol_arc)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "snake_case")]
-pub enum FillType {
-    /// No fill.
-    None,
-
-    /// Filled with the outline color.
-    Outline,
+sexpr! {
+    /// KiCad schematic and symbol graphical fill definition.
+    ///
+    /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_symbol_arc)
+    #[sexpr(unordered, error = crate::ParseError)]
+    #[derive(Clone, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+    #[cfg_attr(feature = "serde", serde(rename = "fill", deny_unknown_fields))]
+    pub struct Fill {
+        (fill
+            /// Fill type. Hierarchical sheets are filled with a color and give no type.
+            #[cfg_attr(feature = "serde", serde(rename = "type"))]
+            [(r#type => fill_type: FillType = FillType::None)]
 
-    /// Filled with the theme background color.
-    Background,
+            /// Fill color. Hierarchical sheets are filled with a color instead of a fill type.
+            #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+            [(color: Color)]
+        )
+    }
 }
 
-impl TryFrom<&Cons> for Fill {
-    type Error = ParseError;
+sexpr! {
+    /// KiCad fill type.
+    ///
+    /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_symbol_arc)
+    #[sexpr(error = crate::ParseError)]
+    #[derive(Clone, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+    #[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+    pub enum FillType {
+        /// No fill.
+        none => None,
+
+        /// Filled with the outline color.
+        outline => Outline,
 
-    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
-        let mut fill_type = FillType::None;
+        /// Filled with the theme background color.
+        background => Background,
 
-        let mut rest = cons.expect_cons_with_symbol_head("fill")?;
-        while !rest.is_null() {
-            let r_cons = rest.expect_cons()?;
-            let element = r_cons.car();
-            rest = r_cons.cdr();
-            let (key, _) = element.expect_cons_with_any_symbol_head()?;
+        /// Filled with the fill's color (version 8+).
+        color => Color,
 
-            match key {
-                "type" => {
-                    fill_type = FillType::try_from(element)?;
-                }
+        /// Hatched with lines rising to the right (version 9+).
+        hatch => Hatch,
 
-                _ => {
-                    return Err(ParseError::Unexpected(element.clone()));
-                }
-            }
-        }
+        /// Hatched with lines falling to the right (version 9+).
+        reverse_hatch => ReverseHatch,
 
-        Ok(Self {
-            fill_type,
-        })
+        /// Cross-hatched (version 9+).
+        cross_hatch => CrossHatch,
     }
 }
 
-impl_try_from_cons_value!(Fill);
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::parse_str};
 
-impl TryFrom<&Cons> for FillType {
-    type Error = ParseError;
+    #[test]
+    fn test_fill() {
+        let fill: Fill = parse_str("(fill (type background))").unwrap();
+        assert!(matches!(fill.fill_type, FillType::Background));
+        assert!(fill.color.is_none());
 
-    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
-        let rest = cons.expect_cons_with_symbol_head("type")?;
-        let (value, rest) = rest.expect_cons_with_any_symbol_head()?;
-        rest.expect_null()?;
+        // Hierarchical sheets give a color and no type.
+        let fill: Fill = parse_str("(fill (color 1 1 0.8 1))").unwrap();
+        assert!(matches!(fill.fill_type, FillType::None));
+        assert!(fill.color.is_some());
 
-        match value {
-            "none" => Ok(FillType::None),
-            "outline" => Ok(FillType::Outline),
-            "background" => Ok(FillType::Background),
-            _ => Err(ParseError::Unexpected(Value::Cons(cons.clone()))),
-        }
+        assert!(parse_str::<Fill>("(fill (type plaid))").is_err());
     }
 }
-
-impl_try_from_cons_value!(FillType);
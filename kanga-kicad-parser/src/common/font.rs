@@ -1,17 +1,22 @@
 use {
-    super::{deserialize_mm_to_opt_nm, serialize_opt_nm_to_mm, Size},
-    crate::{impl_try_from_cons_value, LexprExt, ParseError},
+    super::{nm_to_mm_value, sexpr_list, Color, Size},
+    crate::{impl_sexpr_leaf, impl_try_from_cons_value, LexprExt, ParseError},
+    kanga_sexpr::ToSexpr,
     lexpr::{Cons, Value},
-    serde::{Deserialize, Serialize},
 };
 
+#[cfg(feature = "serde")]
+use super::{deserialize_mm_to_opt_nm, serialize_opt_nm_to_mm};
+
 /// KiCad font for text effects.
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "font")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "font", deny_unknown_fields))]
 pub struct Font {
     /// Font family name. Either a TrueType font family name or `"KiCad Font"` for the KiCad stroke
     /// font.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub face: Option<String>,
 
     /// The size; this is specified as a height x width pair.
@@ -20,24 +25,37 @@ pub struct Font {
     /// Thickness in nanometers.
     ///
     /// KiCad serializes in millimeters but uses nanometers internally.
-    #[serde(deserialize_with = "deserialize_mm_to_opt_nm", serialize_with = "serialize_opt_nm_to_mm")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<f64>"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, deserialize_with = "deserialize_mm_to_opt_nm", serialize_with = "serialize_opt_nm_to_mm")
+    )]
     pub thickness: Option<i64>,
 
     /// Whether the font is in boldface type.
-    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
     pub bold: bool,
 
     /// Whether the font is in italic type.
-    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
     pub italic: bool,
 
     /// Line spacing in nanometers.
-    #[serde(
-        deserialize_with = "deserialize_mm_to_opt_nm",
-        serialize_with = "serialize_opt_nm_to_mm",
-        skip_serializing_if = "Option::is_none"
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<f64>"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "deserialize_mm_to_opt_nm",
+            serialize_with = "serialize_opt_nm_to_mm",
+            skip_serializing_if = "Option::is_none"
+        )
     )]
     pub line_spacing: Option<i64>,
+
+    /// Text color, written by KiCad 7 and later. KiCad uses the default color when omitted.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub color: Option<Color>,
 }
 
 impl TryFrom<&Cons> for Font {
@@ -51,6 +69,7 @@ impl TryFrom<&Cons> for Font {
         let mut bold = false;
         let mut italic = false;
         let mut line_spacing = None;
+        let mut color = None;
 
         while !rest.is_null() {
             let r_cons = rest.expect_cons()?;
@@ -68,19 +87,34 @@ impl TryFrom<&Cons> for Font {
                     }
 
                     "size" => {
-                        size = Some(Size::try_from(e_cons)?);
+                        size = Some(Size::try_from(element)?);
                     }
 
                     "thickness" => {
-                        let (value, cdr) = cdr.expect_cons_with_any_float_head()?;
+                        let (value, cdr) = cdr.expect_cons_with_any_length_head()?;
                         cdr.expect_null()?;
-                        thickness = Some((value * 1e6) as i64);
+                        thickness = Some(value.0);
                     }
 
                     "line_spacing" => {
-                        let (value, cdr) = cdr.expect_cons_with_any_float_head()?;
+                        let (value, cdr) = cdr.expect_cons_with_any_length_head()?;
                         cdr.expect_null()?;
-                        line_spacing = Some((value * 1e6) as i64);
+                        line_spacing = Some(value.0);
+                    }
+
+                    "color" => {
+                        color = Some(Color::try_from(e_cons)?);
+                    }
+
+                    // KiCad 8 and later write (bold yes) and (italic yes) instead of bare flags.
+                    "bold" | "italic" => {
+                        let value = cdr.expect_cons()?;
+                        value.cdr().expect_null()?;
+                        match key {
+                            "bold" => bold = value.car().expect_bool()?,
+                            "italic" => italic = value.car().expect_bool()?,
+                            _ => unreachable!(),
+                        }
                     }
 
                     _ => return Err(ParseError::Unexpected(element.clone())),
@@ -107,8 +141,34 @@ impl TryFrom<&Cons> for Font {
             bold,
             italic,
             line_spacing,
+            color,
         })
     }
 }
 
 impl_try_from_cons_value!(Font);
+impl_sexpr_leaf!(Font, "font");
+
+impl From<&Font> for Value {
+    fn from(font: &Font) -> Self {
+        let mut items = Vec::new();
+        if let Some(face) = &font.face {
+            items.push(sexpr_list("face", [Value::string(face.as_str())]));
+        }
+        items.push(font.size.to_value());
+        if let Some(thickness) = font.thickness {
+            items.push(sexpr_list("thickness", [nm_to_mm_value(thickness)]));
+        }
+        if font.bold {
+            items.push(Value::symbol("bold"));
+        }
+        if font.italic {
+            items.push(Value::symbol("italic"));
+        }
+        if let Some(line_spacing) = font.line_spacing {
+            items.push(sexpr_list("line_spacing", [nm_to_mm_value(line_spacing)]));
+        }
+        items.extend(font.color.as_ref().map(Value::from));
+        sexpr_list("font", items)
+    }
+}
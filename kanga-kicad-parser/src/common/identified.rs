@@ -0,0 +1,153 @@
+use {
+    super::sexpr_list,
+    crate::{FormatVersion, ParseError},
+    lexpr::Value,
+    std::{
+        fmt::{Display, Formatter, Result as FmtResult},
+        str::FromStr,
+    },
+    uuid::Uuid,
+};
+
+/// An element with a UUID that identifies it within its document.
+///
+/// Implemented by every element type that carries a `uuid`, usually with
+/// [`impl_identified!`](crate::impl_identified).
+pub trait Identified {
+    /// Return the element's UUID, or `None` if the element does not have one. Elements from older
+    /// boards may lack a UUID.
+    fn uuid(&self) -> Option<Uuid>;
+
+    /// Set the element's UUID.
+    fn set_uuid(&mut self, uuid: Uuid);
+
+    /// Return the timestamp the element was identified by in a KiCad 5 document, if its UUID was
+    /// converted from one.
+    fn legacy_timestamp(&self) -> Option<LegacyTimestamp> {
+        self.uuid().and_then(LegacyTimestamp::from_uuid)
+    }
+}
+
+/// The identifier KiCad 5 gave elements before UUIDs: a 32-bit timestamp, written as
+/// hexadecimal, e.g. `(tstamp 5C5A1B2F)`.
+///
+/// Like KiCad, this crate converts timestamps into UUIDs whose last four bytes hold the timestamp
+/// and whose other bytes are zero, so that legacy elements can be identified alongside newer ones
+/// and their timestamps recovered when writing a KiCad 5 document.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LegacyTimestamp(pub u32);
+
+impl LegacyTimestamp {
+    /// Return the UUID KiCad converts this timestamp into.
+    pub fn to_uuid(&self) -> Uuid {
+        let mut bytes = [0; 16];
+        bytes[12..].copy_from_slice(&self.0.to_be_bytes());
+        Uuid::from_bytes(bytes)
+    }
+
+    /// Return the timestamp a UUID was converted from, or `None` if it is not a converted
+    /// timestamp. As in KiCad, a UUID is taken to be one if bytes 8 through 11 are zero, which
+    /// the variant bits of a generated UUID rule out.
+    pub fn from_uuid(uuid: Uuid) -> Option<Self> {
+        let bytes = uuid.as_bytes();
+        (bytes[8..12] == [0; 4]).then(|| Self(u32::from_be_bytes([bytes[12], bytes[13], bytes[14], bytes[15]])))
+    }
+}
+
+impl Display for LegacyTimestamp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{:08X}", self.0)
+    }
+}
+
+impl FromStr for LegacyTimestamp {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.len() {
+            1..=8 => u32::from_str_radix(s, 16).map(Self).map_err(|_| ParseError::InvalidUuid(s.to_string())),
+            _ => Err(ParseError::InvalidUuid(s.to_string())),
+        }
+    }
+}
+
+/// Read an element's identifier: a UUID, or a KiCad 5 timestamp, which is converted into a UUID.
+/// Timestamps made only of decimal digits are read by lexpr as numbers and are accepted as such.
+pub fn identifier_value(value: &Value) -> Result<Uuid, ParseError> {
+    let text = match value {
+        Value::String(text) | Value::Symbol(text) => text.to_string(),
+        Value::Number(number) if number.is_u64() => number.to_string(),
+        _ => return Err(ParseError::ExpectedListStrHead(value.clone())),
+    };
+
+    Uuid::parse_str(&text).or_else(|_| text.parse::<LegacyTimestamp>().map(|timestamp| timestamp.to_uuid()))
+}
+
+/// Build the element identifying an element in a board of the given format: `(tstamp ...)`
+/// before KiCad 8 and `(uuid ...)` after. In a KiCad 5 board, identifiers converted from
+/// timestamps are written back as bare timestamps.
+pub fn identifier_element(uuid: Uuid, version: FormatVersion) -> Value {
+    match (version, LegacyTimestamp::from_uuid(uuid)) {
+        (FormatVersion::Kicad5, Some(timestamp)) => sexpr_list("tstamp", [Value::symbol(timestamp.to_string())]),
+        (FormatVersion::Kicad5 | FormatVersion::Kicad6 | FormatVersion::Kicad7, _) => {
+            sexpr_list("tstamp", [Value::string(uuid.to_string())])
+        }
+        _ => sexpr_list("uuid", [Value::string(uuid.to_string())]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{parse_str, pcb::Pcb, sch::Schematic, span::read_str, writer::to_kicad_string},
+    };
+
+    #[test]
+    fn test_legacy_timestamps() {
+        let board = "(kicad_pcb (version 20171130) (host pcbnew 5.1.5)
+            (module R_0603 (layer F.Cu) (tedit 5B301BBD) (tstamp 5C5A1B2F) (at 10 20))
+            (segment (start 10 20) (end 15 20) (width 0.25) (layer F.Cu) (net 1) (tstamp 12345678)))";
+        let pcb: Pcb = parse_str(board).unwrap();
+        assert_eq!(pcb.format_version(), Some(FormatVersion::Kicad5));
+
+        let footprint = &pcb.footprints[0];
+        assert_eq!(footprint.legacy_timestamp(), Some(LegacyTimestamp(0x5C5A_1B2F)));
+        assert_eq!(
+            footprint.uuid.map(|uuid| uuid.to_string()).as_deref(),
+            Some("00000000-0000-0000-0000-00005c5a1b2f")
+        );
+        assert_eq!(
+            pcb.segments[0].legacy_timestamp().map(|timestamp| timestamp.to_string()).as_deref(),
+            Some("12345678")
+        );
+
+        // Timestamps are written back as timestamps only in KiCad 5 boards.
+        let uuid = footprint.uuid.unwrap();
+        assert_eq!(to_kicad_string(&identifier_element(uuid, FormatVersion::Kicad5)), "(tstamp 5C5A1B2F)\n");
+        assert_eq!(
+            to_kicad_string(&identifier_element(uuid, FormatVersion::Kicad8)),
+            "(uuid \"00000000-0000-0000-0000-00005c5a1b2f\")\n"
+        );
+
+        let generated = Uuid::parse_str("0e9fb8ab-4b2c-4c6b-9d62-6b1b1e0f3a2d").unwrap();
+        assert_eq!(LegacyTimestamp::from_uuid(generated), None);
+        assert_eq!(
+            to_kicad_string(&identifier_element(generated, FormatVersion::Kicad6)),
+            "(tstamp \"0e9fb8ab-4b2c-4c6b-9d62-6b1b1e0f3a2d\")\n"
+        );
+
+        // KiCad 9 writes UUIDs unquoted, so some begin with digits.
+        let schematic: Schematic = parse_str(
+            "(kicad_sch (version 20250114) (generator \"eeschema\")
+                (junction (at 10 20) (diameter 0) (color 0 0 0 0) (uuid 0e9fb8ab-4b2c-4c6b-9d62-6b1b1e0f3a2d)))",
+        )
+        .unwrap();
+        assert_eq!(schematic.junctions[0].uuid, generated);
+
+        // Syntax errors are located in the original text, not the rewritten one.
+        let text = "(a 5C5A1B2F 0e9fb8ab 2A) (b)";
+        let location = read_str(text).unwrap_err().location().unwrap();
+        assert_eq!(&text[location.offset..], "(b)");
+    }
+}
@@ -1,53 +1,32 @@
-use {
-    crate::{impl_try_from_cons_value, LexprExt, ParseError},
-    lexpr::{Cons, Value},
-    serde::{Deserialize, Serialize},
-};
-
-/// KiCad stroke line styles.
-///
-/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_stroke_definition)
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
-#[serde(rename = "type", rename_all = "snake_case")]
-pub enum LineStyle {
-    /// Dash
-    Dash,
-
-    /// Dash dot
-    DashDot,
-
-    /// Dash Dot Dot (version 7+)
-    DashDotDot,
-
-    /// Dot
-    Dot,
-
-    /// Default
-    #[default]
-    Default,
-
-    /// Solid
-    Solid,
-}
-
-impl TryFrom<&Cons> for LineStyle {
-    type Error = ParseError;
-
-    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
-        let rest = cons.expect_cons_with_symbol_head("type")?;
-        let (value, rest) = rest.expect_cons_with_any_symbol_head()?;
-        rest.expect_null()?;
-
-        match value {
-            "dash" => Ok(LineStyle::Dash),
-            "dash_dot" => Ok(LineStyle::DashDot),
-            "dash_dot_dot" => Ok(LineStyle::DashDotDot),
-            "dot" => Ok(LineStyle::Dot),
-            "default" => Ok(LineStyle::Default),
-            "solid" => Ok(LineStyle::Solid),
-            _ => Err(ParseError::Unexpected(Value::Cons(cons.clone()))),
-        }
+use kanga_sexpr::sexpr;
+
+sexpr! {
+    /// KiCad stroke line styles, written as `(type <style>)` in a stroke.
+    ///
+    /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_stroke_definition)
+    #[sexpr(error = crate::ParseError)]
+    #[derive(Clone, Debug, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+    #[cfg_attr(feature = "serde", serde(rename = "type", rename_all = "snake_case"))]
+    pub enum LineStyle {
+        /// Dash
+        dash => Dash,
+
+        /// Dash dot
+        dash_dot => DashDot,
+
+        /// Dash Dot Dot (version 7+)
+        dash_dot_dot => DashDotDot,
+
+        /// Dot
+        dot => Dot,
+
+        /// Default
+        #[default]
+        default => Default,
+
+        /// Solid
+        solid => Solid,
     }
 }
-
-impl_try_from_cons_value!(LineStyle);
@@ -1,21 +1,20 @@
-use {
-    super::{deserialize_mm_to_nm, serialize_nm_to_mm},
-    serde::{Deserialize, Serialize},
-};
+use super::Nm;
 
 /// KiCad offset.
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "offset")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "offset", deny_unknown_fields))]
 pub struct Offset {
     /// X offset in nanometers.
     ///
     /// KiCad serializes in millimeters but uses nanometers internally.
-    #[serde(deserialize_with = "deserialize_mm_to_nm", serialize_with = "serialize_nm_to_mm")]
-    pub x: i64,
+    #[cfg_attr(feature = "schemars", schemars(with = "f64"))]
+    pub x: Nm,
 
     /// Y offset in nanometers.
     ///
     /// KiCad serializes in millimeters but uses nanometers internally.
-    #[serde(deserialize_with = "deserialize_mm_to_nm", serialize_with = "serialize_nm_to_mm")]
-    pub y: i64,
+    #[cfg_attr(feature = "schemars", schemars(with = "f64"))]
+    pub y: Nm,
 }
@@ -1,138 +1,170 @@
 use {
-    super::Size,
-    crate::{impl_try_from_cons_value, LexprExt, ParseError},
-    lexpr::Cons,
-    serde::{Deserialize, Serialize},
+    super::{Nm, Size},
+    crate::ParseError,
+    kanga_sexpr::sexpr,
 };
 
-/// KiCad page settings: page size and orientation.
-///
-/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_page_settings)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "paper")]
-pub struct Paper {
-    /// Page size; either a standard size or custom.
-    pub paper_size: PaperSize,
-
-    /// Page orientation.
-    #[serde(default, skip_serializing_if = "PaperOrientation::is_landscape")]
-    pub orientation: PaperOrientation,
-}
-
-/// KiCad page orientation.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
-pub enum PaperOrientation {
-    /// Landscape orientation.
-    #[default]
-    Landscape,
-
-    /// Portrait orientation.
-    Portrait,
+sexpr! {
+    /// KiCad page settings: page size and orientation.
+    ///
+    /// A standard size is written as `(paper "A4" [portrait])`, and a custom size as
+    /// `(paper "User" <width> <height>)`.
+    ///
+    /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_page_settings)
+    #[sexpr(error = crate::ParseError, check = Paper::check)]
+    #[derive(Clone, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+    #[cfg_attr(feature = "serde", serde(rename = "paper", deny_unknown_fields))]
+    pub struct Paper {
+        (paper
+            /// The name of the page size: a standard size such as `A4`, or `User` for a custom size.
+            size: String
+
+            /// The width of a custom page in nanometers.
+            #[cfg_attr(feature = "schemars", schemars(with = "Option<f64>"))]
+            #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+            [width: f64 as Nm]
+
+            /// The height of a custom page in nanometers.
+            #[cfg_attr(feature = "schemars", schemars(with = "Option<f64>"))]
+            #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+            [height: f64 as Nm]
+
+            /// Whether a standard page is in portrait rather than landscape orientation.
+            #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+            [portrait]
+        )
+    }
 }
 
 /// KiCad page size. This is either a standard ISO or ANSI size, or a custom size.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum PaperSize {
     /// ISO A0: 841 x 1189 mm.
-    #[serde(rename = "A0")]
+    #[cfg_attr(feature = "serde", serde(rename = "A0"))]
     IsoA0,
 
     /// ISO A1: 594 x 841 mm.
-    #[serde(rename = "A1")]
+    #[cfg_attr(feature = "serde", serde(rename = "A1"))]
     IsoA1,
 
     /// ISO A2: 420 x 594 mm.
-    #[serde(rename = "A2")]
+    #[cfg_attr(feature = "serde", serde(rename = "A2"))]
     IsoA2,
 
     /// ISO A3: 297 x 420 mm.
-    #[serde(rename = "A3")]
+    #[cfg_attr(feature = "serde", serde(rename = "A3"))]
     IsoA3,
 
     /// ISO A4: 210 x 297 mm.
-    #[serde(rename = "A4")]
+    #[cfg_attr(feature = "serde", serde(rename = "A4"))]
     IsoA4,
 
     /// ISO A5: 148 x 210 mm.
-    #[serde(rename = "A5")]
+    #[cfg_attr(feature = "serde", serde(rename = "A5"))]
     IsoA5,
 
     /// ANSI A (Letter): 8.5 x 11 in, 216 x 279 mm.
-    #[serde(rename = "A")]
+    #[cfg_attr(feature = "serde", serde(rename = "A"))]
     AnsiA,
 
     /// ANSI B (Tabloid): 11 x 17 in, 279 x 432 mm.
-    #[serde(rename = "B")]
+    #[cfg_attr(feature = "serde", serde(rename = "B"))]
     AnsiB,
 
     /// ANSI C: 17 x 22 in, 432 x 559 mm.
-    #[serde(rename = "C")]
+    #[cfg_attr(feature = "serde", serde(rename = "C"))]
     AnsiC,
 
     /// ANSI D: 22 x 34 in, 559 x 864 mm.
-    #[serde(rename = "D")]
+    #[cfg_attr(feature = "serde", serde(rename = "D"))]
     AnsiD,
 
     /// ANSI E: 34 x 44 in, 864 x 1118 mm.
-    #[serde(rename = "E")]
+    #[cfg_attr(feature = "serde", serde(rename = "E"))]
     AnsiE,
 
     /// Custom size.
     User(Size),
 }
 
-impl PaperOrientation {
-    /// Indicates whether this is landscape orientation.
-    #[inline(always)]
-    pub fn is_landscape(&self) -> bool {
-        matches!(self, PaperOrientation::Landscape)
+impl Paper {
+    /// Create the page settings for a page size, in portrait orientation if `portrait` is set.
+    pub fn new(paper_size: PaperSize, portrait: bool) -> Self {
+        let (width, height) = match &paper_size {
+            PaperSize::User(size) => (Some(size.width), Some(size.height)),
+            _ => (None, None),
+        };
+
+        Self {
+            size: paper_size.name().to_string(),
+            width,
+            height,
+            portrait,
+        }
     }
 
-    /// Indicates whether this is portrait orientation.
-    #[inline(always)]
-    pub fn is_portrait(&self) -> bool {
-        matches!(self, PaperOrientation::Portrait)
+    /// Return the page size, or an error if the name is not a standard size or `User`, or only a
+    /// custom size gives (and requires) a width and height.
+    pub fn paper_size(&self) -> Result<PaperSize, ParseError> {
+        match (self.size.as_str(), self.width, self.height) {
+            ("User", Some(width), Some(height)) => Ok(PaperSize::User(Size::try_new(width, height)?)),
+            ("User", _, _) | (_, Some(_), _) | (_, _, Some(_)) => Err(ParseError::InvalidPaperSize(self.size.clone())),
+            (name, None, None) => PaperSize::from_name(name),
+        }
     }
-}
 
-impl TryFrom<&Cons> for Paper {
-    type Error = ParseError;
-
-    fn try_from(cons: &Cons) -> Result<Self, ParseError> {
-        let rest = cons.expect_cons_with_symbol_head("paper")?;
-        let (paper_size_str, rest) = rest.expect_cons_with_any_str_head()?;
-        if paper_size_str == "User" {
-            let (height, rest) = rest.expect_cons_with_any_float_head()?;
-            let (width, rest) = rest.expect_cons_with_any_float_head()?;
-            rest.expect_null()?;
-
-            let paper_size = PaperSize::User(Size::from_mm(width, height)?);
-
-            Ok(Self {
-                paper_size,
-                orientation: PaperOrientation::Landscape,
-            })
-        } else {
-            let paper_size = PaperSize::from_str(paper_size_str)?;
-            let mut orientation = PaperOrientation::Landscape;
-
-            if !rest.is_null() {
-                let rest = rest.expect_cons_with_symbol_head("portrait")?;
-                rest.expect_null()?;
-                orientation = PaperOrientation::Portrait;
-            }
-
-            Ok(Self {
-                paper_size,
-                orientation,
-            })
+    /// Return the size of the page in nanometers, accounting for the orientation.
+    ///
+    /// Standard sizes are landscape by default; portrait orientation swaps the width and height.
+    /// Custom sizes are returned as specified. Settings that [`paper_size`](Self::paper_size)
+    /// rejects, which are never parsed, are taken as A4.
+    pub fn page_size(&self) -> Size {
+        let paper_size = self.paper_size().unwrap_or(PaperSize::IsoA4);
+        let size = paper_size.landscape_size();
+        match paper_size {
+            PaperSize::User(_) => size,
+            _ if self.portrait => Size::new(size.height, size.width),
+            _ => size,
         }
     }
+
+    /// Reject a page size that is not known or a custom size without its width and height.
+    fn check(&self) -> Result<(), ParseError> {
+        self.paper_size().map(drop)
+    }
 }
 
 impl PaperSize {
-    fn from_str(s: &str) -> Result<Self, ParseError> {
-        match s {
+    /// Return the size of the page in landscape orientation, in nanometers.
+    ///
+    /// These match the dimensions KiCad uses for each standard size.
+    pub fn landscape_size(&self) -> Size {
+        let (width_mm, height_mm): (f64, f64) = match self {
+            Self::IsoA0 => (1189.0, 841.0),
+            Self::IsoA1 => (841.0, 594.0),
+            Self::IsoA2 => (594.0, 420.0),
+            Self::IsoA3 => (420.0, 297.0),
+            Self::IsoA4 => (297.0, 210.0),
+            Self::IsoA5 => (210.0, 148.0),
+            Self::AnsiA => (279.4, 215.9),
+            Self::AnsiB => (431.8, 279.4),
+            Self::AnsiC => (558.8, 431.8),
+            Self::AnsiD => (863.6, 558.8),
+            Self::AnsiE => (1117.6, 863.6),
+            Self::User(size) => return size.clone(),
+        };
+
+        Size::new(Nm::from_mm(width_mm), Nm::from_mm(height_mm))
+    }
+
+    /// Return the standard size with the name KiCad gives it, such as `A4`, or an error if the name
+    /// is not a standard size.
+    pub fn from_name(name: &str) -> Result<Self, ParseError> {
+        match name {
             "A0" => Ok(Self::IsoA0),
             "A1" => Ok(Self::IsoA1),
             "A2" => Ok(Self::IsoA2),
@@ -144,9 +176,65 @@ impl PaperSize {
             "C" => Ok(Self::AnsiC),
             "D" => Ok(Self::AnsiD),
             "E" => Ok(Self::AnsiE),
-            _ => Err(ParseError::InvalidPaperSize(s.to_string())),
+            _ => Err(ParseError::InvalidPaperSize(name.to_string())),
+        }
+    }
+
+    /// Return the name KiCad gives this size, such as `A4`, or `User` for a custom size.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::IsoA0 => "A0",
+            Self::IsoA1 => "A1",
+            Self::IsoA2 => "A2",
+            Self::IsoA3 => "A3",
+            Self::IsoA4 => "A4",
+            Self::IsoA5 => "A5",
+            Self::AnsiA => "A",
+            Self::AnsiB => "B",
+            Self::AnsiC => "C",
+            Self::AnsiD => "D",
+            Self::AnsiE => "E",
+            Self::User(_) => "User",
         }
     }
 }
 
-impl_try_from_cons_value!(Paper);
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::parse_str, kanga_sexpr::ToSexpr};
+
+    fn page_size(text: &str) -> (Nm, Nm) {
+        let paper: Paper = parse_str(text).unwrap();
+        let size = paper.page_size();
+        (size.width, size.height)
+    }
+
+    #[test]
+    fn test_page_size() {
+        assert_eq!(page_size(r#"(paper "A4")"#), (Nm(297_000_000), Nm(210_000_000)));
+        assert_eq!(page_size(r#"(paper "A4" portrait)"#), (Nm(210_000_000), Nm(297_000_000)));
+        assert_eq!(page_size(r#"(paper "A")"#), (Nm(279_400_000), Nm(215_900_000)));
+        assert_eq!(page_size(r#"(paper "E" portrait)"#), (Nm(863_600_000), Nm(1_117_600_000)));
+
+        // KiCad writes the width of a custom size first.
+        assert_eq!(page_size(r#"(paper "User" 431.8 279.4)"#), (Nm(431_800_000), Nm(279_400_000)));
+
+        // Custom sizes are used as given, whatever the orientation.
+        let mut paper = Paper::new(PaperSize::User(Size::new(Nm(100_000_000), Nm(300_000_000))), true);
+        let size = paper.page_size();
+        assert_eq!((size.width, size.height), (Nm(100_000_000), Nm(300_000_000)));
+
+        paper.portrait = false;
+        assert_eq!(paper.to_value(), lexpr::sexp!((paper "User" 100.0 300.0)));
+    }
+
+    #[test]
+    fn test_invalid_paper() {
+        for text in [r#"(paper "A9")"#, r#"(paper "User" 100)"#, r#"(paper "A4" 100 200)"#, r#"(paper "User" -1 2)"#] {
+            assert!(parse_str::<Paper>(text).is_err(), "{text}");
+        }
+
+        let error = parse_str::<Paper>(r#"(paper "A4" landscape)"#).unwrap_err().to_string();
+        assert!(error.contains("landscape"), "{error}");
+    }
+}
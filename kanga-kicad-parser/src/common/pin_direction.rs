@@ -0,0 +1,140 @@
+use crate::common::{Angle, ParentOrientation, SymbolPin, SymbolPinElectricalType};
+
+/// A direction on screen, as used for pin orientations.
+///
+/// A pin's direction is the way it extends from its connection point toward the symbol body, which
+/// is what the angle in a pin's `(at x y angle)` encodes: 0° points right and 90° points up on
+/// screen. Wires attach from the [opposite](PinDirection::opposite) side.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PinDirection {
+    /// Toward positive X.
+    Right,
+
+    /// Toward the top of the screen.
+    Up,
+
+    /// Toward negative X.
+    Left,
+
+    /// Toward the bottom of the screen.
+    Down,
+}
+
+impl PinDirection {
+    /// Return the direction nearest to an angle.
+    pub fn from_angle(angle: Angle) -> Self {
+        match ((angle.degrees() + 45.0) / 90.0) as u32 % 4 {
+            0 => Self::Right,
+            1 => Self::Up,
+            2 => Self::Left,
+            _ => Self::Down,
+        }
+    }
+
+    /// Return the angle of this direction.
+    pub fn angle(self) -> Angle {
+        Angle::from_quarter_turns(self as i32)
+    }
+
+    /// Return the opposite direction.
+    pub fn opposite(self) -> Self {
+        Self::from_angle(self.angle() + Angle::from_degrees(180.0))
+    }
+
+    /// Return the unit vector for this direction in screen coordinates, where Y points down.
+    pub fn unit_vector(self) -> (i64, i64) {
+        match self {
+            Self::Right => (1, 0),
+            Self::Up => (0, -1),
+            Self::Left => (-1, 0),
+            Self::Down => (0, 1),
+        }
+    }
+
+    /// Return this direction after the owning symbol is rotated and mirrored.
+    ///
+    /// As with [`TextJustify::effective_justification`][crate::common::TextJustify::effective_justification],
+    /// the rotation is applied first and mirroring is then applied about the screen axes.
+    pub fn transformed(self, parent: ParentOrientation) -> Self {
+        let mut angle = self.angle() + parent.angle;
+
+        if parent.mirror_y {
+            angle = Angle::from_degrees(180.0) - angle;
+        }
+
+        if parent.mirror_x {
+            angle = -angle;
+        }
+
+        Self::from_angle(angle)
+    }
+}
+
+impl SymbolPin {
+    /// Return the direction the pin extends from its connection point toward the symbol body, in
+    /// the symbol's own coordinates.
+    pub fn direction(&self) -> PinDirection {
+        PinDirection::from_angle(self.position.angle.unwrap_or_default())
+    }
+
+    /// Return the direction the pin extends toward the symbol body once the symbol is placed with the
+    /// given orientation.
+    pub fn placed_direction(&self, parent: ParentOrientation) -> PinDirection {
+        self.direction().transformed(parent)
+    }
+
+    /// Return the direction signals flow through the pin once placed, for drawing arrows and for
+    /// driver/receiver heuristics.
+    ///
+    /// Inputs point toward the body and driving outputs point away from it. Pins whose type does not
+    /// imply a direction, such as bidirectional and passive pins, return `None`.
+    pub fn signal_direction(&self, parent: ParentOrientation) -> Option<PinDirection> {
+        let inward = self.placed_direction(parent);
+
+        match self.electrical_type {
+            SymbolPinElectricalType::Input | SymbolPinElectricalType::PowerIn => Some(inward),
+            SymbolPinElectricalType::Output
+            | SymbolPinElectricalType::TriState
+            | SymbolPinElectricalType::PowerOut
+            | SymbolPinElectricalType::OpenCollector
+            | SymbolPinElectricalType::OpenEmitter => Some(inward.opposite()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pin_direction() {
+        assert_eq!(PinDirection::from_angle(Angle::from_degrees(91.0)), PinDirection::Up);
+        assert_eq!(PinDirection::from_angle(Angle::from_degrees(350.0)), PinDirection::Right);
+        assert_eq!(PinDirection::Up.opposite(), PinDirection::Down);
+        assert_eq!(PinDirection::Up.unit_vector(), (0, -1));
+
+        let rotated = ParentOrientation {
+            angle: Angle::from_degrees(90.0),
+            ..ParentOrientation::default()
+        };
+        assert_eq!(PinDirection::Right.transformed(rotated), PinDirection::Up);
+
+        // Rotation applies before mirroring: right turns up, then flips down.
+        let rotated_mirrored = ParentOrientation {
+            mirror_x: true,
+            ..rotated
+        };
+        assert_eq!(PinDirection::Right.transformed(rotated_mirrored), PinDirection::Down);
+
+        let mirrored = ParentOrientation {
+            mirror_y: true,
+            ..ParentOrientation::default()
+        };
+        assert_eq!(PinDirection::Right.transformed(mirrored), PinDirection::Left);
+        assert_eq!(PinDirection::Up.transformed(mirrored), PinDirection::Up);
+    }
+}
@@ -0,0 +1,68 @@
+use {
+    super::{nm_to_mm_value, sexpr_list, Nm},
+    crate::{LexprExt, ParseError},
+    lexpr::Value,
+};
+
+/// KiCad coordinate point, as written in `(xy ...)`, `(start ...)`, `(mid ...)`, `(end ...)`, and
+/// `(center ...)` elements.
+///
+/// Unlike a [`Position`](super::Position), a point has no angle.
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_coordinate_point_list)
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "xy", deny_unknown_fields))]
+pub struct Point {
+    /// X coordinate in nanometers.
+    ///
+    /// KiCad serializes in millimeters but uses nanometers internally.
+    #[cfg_attr(feature = "schemars", schemars(with = "f64"))]
+    pub x: Nm,
+
+    /// Y coordinate in nanometers.
+    ///
+    /// KiCad serializes in millimeters but uses nanometers internally.
+    #[cfg_attr(feature = "schemars", schemars(with = "f64"))]
+    pub y: Nm,
+}
+
+impl Point {
+    /// Create a point from its coordinates.
+    pub fn new(x: Nm, y: Nm) -> Self {
+        Self {
+            x,
+            y,
+        }
+    }
+
+    /// Parse the `x y` arguments of a point element, whatever its head.
+    pub(crate) fn try_from_xy_cons(cons: &Value) -> Result<Self, ParseError> {
+        let (x, rest) = cons.expect_cons_with_any_length_head()?;
+        let (y, rest) = rest.expect_cons_with_any_length_head()?;
+        rest.expect_null()?;
+
+        Ok(Self::new(x, y))
+    }
+
+    /// Write this point as `(<head> x y)`.
+    pub(crate) fn to_xy_value(self, head: &str) -> Value {
+        sexpr_list(head, [nm_to_mm_value(self.x), nm_to_mm_value(self.y)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, lexpr::sexp};
+
+    #[test]
+    fn test_point() {
+        let point = Point::try_from_xy_cons(sexp!((xy 1.27 -2.54)).as_cons().unwrap().cdr()).unwrap();
+        assert_eq!(point, Point::new(Nm(1_270_000), Nm(-2_540_000)));
+        assert_eq!(point.to_xy_value("start"), sexp!((start 1.27 -2.54)));
+
+        // A point has no angle.
+        assert!(Point::try_from_xy_cons(sexp!((xy 1 2 90)).as_cons().unwrap().cdr()).is_err());
+    }
+}
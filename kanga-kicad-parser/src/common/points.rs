@@ -1,14 +1,43 @@
 use {
-    super::Position,
-    serde::{Deserialize, Serialize},
+    super::{sexpr_list, Point},
+    crate::{impl_try_from_cons_value, LexprExt, ParseError},
+    lexpr::{Cons, Value},
 };
 
 /// KiCad coordinate point list.
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_coordinate_point_list)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "pts")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "pts", deny_unknown_fields))]
 pub struct Points {
     /// List of points.
-    pub points: Vec<Position>,
+    pub points: Vec<Point>,
+}
+
+impl TryFrom<&Cons> for Points {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let rest = cons.expect_cons_with_symbol_head("pts")?;
+        let points = rest
+            .list_iter()
+            .into_iter()
+            .flatten()
+            .map(|point| Point::try_from_xy_cons(point.expect_cons_with_symbol_head("xy")?))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            points,
+        })
+    }
+}
+
+impl_try_from_cons_value!(Points);
+
+impl From<&Points> for Value {
+    fn from(points: &Points) -> Self {
+        sexpr_list("pts", points.points.iter().map(|point| point.to_xy_value("xy")))
+    }
 }
@@ -1,65 +1,72 @@
 use {
-    super::{deserialize_mm_to_nm, serialize_nm_to_mm},
-    crate::{impl_try_from_cons_value, LexprExt, ParseError},
-    lexpr::{Cons, Value},
-    serde::{Deserialize, Serialize},
+    super::{Angle, Nm, Point},
+    kanga_sexpr::sexpr,
 };
 
-/// KiCad position identifier.
-///
-/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_position_identifier)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "at")]
-pub struct Position {
-    /// X coordinate in nanometers.
+sexpr! {
+    /// KiCad position identifier.
     ///
-    /// KiCad serializes in millimeters but uses nanometers internally.
-    #[serde(deserialize_with = "deserialize_mm_to_nm", serialize_with = "serialize_nm_to_mm")]
-    pub x: i64,
+    /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_position_identifier)
+    #[sexpr(error = crate::ParseError)]
+    #[derive(Clone, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+    #[cfg_attr(feature = "serde", serde(rename = "at", deny_unknown_fields))]
+    pub struct Position {
+        (at
+            /// X coordinate in nanometers.
+            ///
+            /// KiCad serializes in millimeters but uses nanometers internally.
+            #[cfg_attr(feature = "schemars", schemars(with = "f64"))]
+            x: f64 as Nm
 
-    /// Y coordinate in nanometers.
-    ///
-    /// KiCad serializes in millimeters but uses nanometers internally.
-    #[serde(deserialize_with = "deserialize_mm_to_nm", serialize_with = "serialize_nm_to_mm")]
-    pub y: i64,
+            /// Y coordinate in nanometers.
+            ///
+            /// KiCad serializes in millimeters but uses nanometers internally.
+            #[cfg_attr(feature = "schemars", schemars(with = "f64"))]
+            y: f64 as Nm
 
-    /// Angle in degrees.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub angle: Option<f64>,
+            /// Angle in degrees.
+            #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+            [angle: f64 as Angle]
+        )
+    }
 }
 
 impl Position {
-    pub(crate) fn try_from_xy_cons(cons: &Value) -> Result<Self, ParseError> {
-        let (x, rest) = cons.expect_cons_with_any_float_head()?;
-        let (y, rest) = rest.expect_cons_with_any_float_head()?;
-        let mut angle = None;
+    /// Return the coordinates of this position, without its angle.
+    pub fn point(&self) -> Point {
+        Point::new(self.x, self.y)
+    }
+}
 
-        if !rest.is_null() {
-            let (value, rest) = rest.expect_cons_with_any_float_head()?;
-            rest.expect_null()?;
-            angle = Some(value);
-        } else {
-            rest.expect_null()?;
+impl From<Point> for Position {
+    fn from(point: Point) -> Self {
+        Self {
+            x: point.x,
+            y: point.y,
+            angle: None,
         }
-
-        let x = (x * 1e6) as i64;
-        let y = (y * 1e6) as i64;
-
-        Ok(Self {
-            x,
-            y,
-            angle,
-        })
     }
 }
 
-impl TryFrom<&Cons> for Position {
-    type Error = ParseError;
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::parse_str, kanga_sexpr::ToSexpr};
+
+    #[test]
+    fn test_position() {
+        // Lengths convert exactly from the text of the number.
+        let position: Position = parse_str("(at 1.001 -2.54 450)").unwrap();
+        assert_eq!((position.x, position.y), (Nm(1_001_000), Nm(-2_540_000)));
+        assert_eq!(position.angle, Some(Angle::from_degrees(90.0)));
+        assert_eq!(position.to_value(), lexpr::sexp!((at 1.001 -2.54 90.0)));
+
+        let position: Position = parse_str("(at 0 5)").unwrap();
+        assert!(position.angle.is_none());
 
-    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
-        let rest = cons.expect_cons_with_symbol_head("at")?;
-        Self::try_from_xy_cons(rest)
+        for text in ["(at 1)", "(at 1 2 3 4)", "(at x 2)"] {
+            assert!(parse_str::<Position>(text).is_err(), "{text}");
+        }
     }
 }
-
-impl_try_from_cons_value!(Position);
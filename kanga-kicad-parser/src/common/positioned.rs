@@ -0,0 +1,38 @@
+use super::{Angle, Nm, Position};
+
+/// An element placed at a position, such as a label, pin, or footprint.
+///
+/// Implemented by every element type that carries a `position`, usually with
+/// [`impl_positioned!`](crate::impl_positioned), so transforms can move elements without matching
+/// on their concrete types.
+pub trait Positioned {
+    /// Return the element's position, or `None` if the element has not been placed. Properties
+    /// without an `(at ...)` are not placed.
+    fn position(&self) -> Option<&Position>;
+
+    /// Return the element's position mutably, or `None` if the element has not been placed.
+    fn position_mut(&mut self) -> Option<&mut Position>;
+
+    /// Set the element's position, placing it if it was not placed.
+    fn set_position(&mut self, position: Position);
+
+    /// Return the element's rotation; elements without one are not rotated.
+    fn angle(&self) -> Angle {
+        self.position().and_then(|position| position.angle).unwrap_or_default()
+    }
+
+    /// Set the element's rotation. Elements that have not been placed are left unchanged.
+    fn set_angle(&mut self, angle: Angle) {
+        if let Some(position) = self.position_mut() {
+            position.angle = Some(angle);
+        }
+    }
+
+    /// Move the element by an offset. Elements that have not been placed are left unchanged.
+    fn translate(&mut self, dx: Nm, dy: Nm) {
+        if let Some(position) = self.position_mut() {
+            position.x += dx;
+            position.y += dy;
+        }
+    }
+}
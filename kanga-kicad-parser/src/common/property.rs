@@ -1,8 +1,8 @@
-use serde::{Deserialize, Serialize};
-
 /// KiCad key-value property.
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "property")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "property", deny_unknown_fields))]
 pub struct Property {
     /// Property name.
     pub key: String,
@@ -0,0 +1,98 @@
+use {
+    super::Nm,
+    serde::{de::Deserializer, ser::Serializer, Deserialize},
+};
+
+/// Convert from millimeters to nanometers.
+pub fn deserialize_mm_to_nm<'de, D>(d: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let v: f64 = Deserialize::deserialize(d)?;
+    Ok((v * 1e6).round() as i64)
+}
+
+/// Convert from millimeters to nanometers, unsigned.
+pub fn deserialize_mm_to_unsigned_nm<'de, D>(d: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let v: f64 = Deserialize::deserialize(d)?;
+    if v < 0.0 {
+        return Err(serde::de::Error::custom("negative value"));
+    }
+    Ok((v * 1e6).round() as u64)
+}
+
+/// Read a millimeter value as a length in nanometers, rejecting negative values.
+pub fn deserialize_unsigned_mm<'de, D>(d: D) -> Result<Nm, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let v: f64 = Deserialize::deserialize(d)?;
+    if v < 0.0 {
+        return Err(serde::de::Error::custom("negative value"));
+    }
+    Ok(Nm::from_mm(v))
+}
+
+/// Convert from millimeters to nanometers, wrapping in an `Option<i64>` type.
+pub fn deserialize_mm_to_opt_nm<'de, D>(d: D) -> Result<Option<i64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let v: Option<f64> = Deserialize::deserialize(d)?;
+    Ok(v.map(|v| (v * 1e6).round() as i64))
+}
+
+/// Convert from millimeters to nanometers, unsigned, wrapping in an `Option<u64>` type.
+pub fn deserialize_mm_to_opt_unsigned_nm<'de, D>(d: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let v: Option<f64> = Deserialize::deserialize(d)?;
+    match v {
+        Some(v) if v < 0.0 => Err(serde::de::Error::custom("negative value")),
+        _ => Ok(v.map(|v| (v * 1e6).round() as u64)),
+    }
+}
+
+/// Convert from nanometers to millimeters
+pub fn serialize_nm_to_mm<S>(v: &i64, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    s.serialize_f64((*v as f64) * 1e-6)
+}
+
+/// Convert from unsigned nanometers to millimeters.
+pub fn serialize_unsigned_nm_to_mm<S>(v: &u64, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    s.serialize_f64((*v as f64) * 1e-6)
+}
+
+/// Convert from nanometers to millimeters if the value is `Some<i64>`.
+pub fn serialize_opt_nm_to_mm<S>(v: &Option<i64>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if let Some(v) = v {
+        s.serialize_f64((*v as f64) * 1e-6)
+    } else {
+        s.serialize_none()
+    }
+}
+
+/// Convert from unsigned nanometers to millimeters if the value is `Some<u64>`.
+pub fn serialize_opt_unsigned_nm_to_mm<S>(v: &Option<u64>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if let Some(v) = v {
+        s.serialize_f64((*v as f64) * 1e-6)
+    } else {
+        s.serialize_none()
+    }
+}
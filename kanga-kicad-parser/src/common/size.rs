@@ -1,69 +1,89 @@
-use {
-    super::{deserialize_mm_to_unsigned_nm, serialize_unsigned_nm_to_mm},
-    crate::{impl_try_from_cons_value, LexprExt, ParseError},
-    lexpr::{Cons, Value},
-    serde::{Deserialize, Serialize},
-};
+use {super::Nm, crate::ParseError, kanga_sexpr::sexpr};
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "size")]
-pub struct Size {
-    /// Width in nanometers.
-    ///
-    /// KiCad serializes in millimeters but uses nanometers internally.
-    #[serde(deserialize_with = "deserialize_mm_to_unsigned_nm", serialize_with = "serialize_unsigned_nm_to_mm")]
-    pub width: u64,
+#[cfg(feature = "serde")]
+use super::deserialize_unsigned_mm;
 
-    /// Height in nanometers.
+sexpr! {
+    /// KiCad size, such as the size of the characters of a font.
     ///
-    /// KiCad serializes in millimeters but uses nanometers internally.
-    #[serde(deserialize_with = "deserialize_mm_to_unsigned_nm", serialize_with = "serialize_unsigned_nm_to_mm")]
-    pub height: u64,
+    /// KiCad doesn't follow normal conventions and places the height first, as `(size <height>
+    /// <width>)`. A negative width or height is rejected.
+    #[sexpr(error = crate::ParseError, check = Size::check)]
+    #[derive(Clone, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+    #[cfg_attr(feature = "serde", serde(rename = "size", deny_unknown_fields))]
+    pub struct Size {
+        (size
+            /// Height in nanometers.
+            ///
+            /// KiCad serializes in millimeters but uses nanometers internally.
+            #[cfg_attr(feature = "schemars", schemars(with = "f64"))]
+            #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_unsigned_mm"))]
+            height: f64 as Nm
+
+            /// Width in nanometers.
+            ///
+            /// KiCad serializes in millimeters but uses nanometers internally.
+            #[cfg_attr(feature = "schemars", schemars(with = "f64"))]
+            #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_unsigned_mm"))]
+            width: f64 as Nm
+        )
+    }
 }
 
 impl Size {
-    /// Create a new `Size` object with the specified width and height in nm.
-    pub fn new(width: u64, height: u64) -> Self {
+    /// Create a new `Size` object with the specified width and height.
+    pub fn new(width: Nm, height: Nm) -> Self {
         Self {
-            width,
             height,
+            width,
         }
     }
 
     /// Create a new `Size` object with the specified width and height in mm.
     pub fn from_mm(width: f64, height: f64) -> Result<Self, ParseError> {
-        if width < 0.0 {
-            return Err(ParseError::InvalidWidth(width));
-        }
-
-        if height < 0.0 {
-            return Err(ParseError::InvalidHeight(height));
-        }
+        Self::try_new(Nm::from_mm(width), Nm::from_mm(height))
+    }
 
-        Ok(Self {
-            width: (width * 1e6) as u64,
-            height: (height * 1e6) as u64,
-        })
+    /// Create a new `Size` object, rejecting a negative width or height.
+    pub(crate) fn try_new(width: Nm, height: Nm) -> Result<Self, ParseError> {
+        let size = Self::new(width, height);
+        size.check()?;
+        Ok(size)
     }
 
-    /// Attempt to create a Size value from a (height width) cons cell.
-    pub(crate) fn try_from_hw_cons(rest: &Value) -> Result<Self, ParseError> {
-        // KiCad doesn't follow normal conventions and places height first.
-        let (height, rest) = rest.expect_cons_with_any_float_head()?;
-        let (width, rest) = rest.expect_cons_with_any_float_head()?;
-        rest.expect_null()?;
+    /// Reject a negative width or height.
+    fn check(&self) -> Result<(), ParseError> {
+        if self.width < Nm::ZERO {
+            return Err(ParseError::InvalidWidth(self.width.to_mm()));
+        }
 
-        Self::from_mm(width, height)
+        if self.height < Nm::ZERO {
+            return Err(ParseError::InvalidHeight(self.height.to_mm()));
+        }
+
+        Ok(())
     }
 }
 
-impl TryFrom<&Cons> for Size {
-    type Error = ParseError;
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{parse_str, Error},
+        kanga_sexpr::ToSexpr,
+    };
+
+    #[test]
+    fn test_size() {
+        // The height comes first, as KiCad writes it.
+        let size: Size = parse_str("(size 1.27 2)").unwrap();
+        assert_eq!((size.width, size.height), (Nm(2_000_000), Nm(1_270_000)));
+        assert_eq!(size.to_value(), lexpr::sexp!((size 1.27 2.0)));
 
-    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
-        let rest = cons.expect_cons_with_symbol_head("size")?;
-        Size::try_from_hw_cons(rest)
+        let error = parse_str::<Size>("(size 1 -2)").unwrap_err();
+        assert!(matches!(error.as_parse_error().map(ParseError::root_cause), Some(ParseError::InvalidWidth(_))));
+        assert!(matches!(parse_str::<Size>("(size 1)"), Err(Error::Parse(_))));
     }
 }
-
-impl_try_from_cons_value!(Size);
@@ -1,72 +1,68 @@
 use {
-    super::{deserialize_mm_to_opt_nm, serialize_opt_nm_to_mm, Color, LineStyle},
-    crate::{impl_try_from_cons_value, LexprExt, ParseError},
-    lexpr::Cons,
-    serde::{Deserialize, Serialize},
+    super::{Color, LineStyle, Nm},
+    kanga_sexpr::sexpr,
 };
 
-/// KiCad stroke definition.
-///
-/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_stroke_definition)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "stroke")]
-pub struct Stroke {
-    /// Width in nanometers.
+sexpr! {
+    /// KiCad stroke definition.
     ///
-    /// KiCad serializes in millimeters but uses nanometers internally.
-    #[serde(deserialize_with = "deserialize_mm_to_opt_nm", serialize_with = "serialize_opt_nm_to_mm")]
-    pub width: Option<i64>,
+    /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_stroke_definition)
+    #[sexpr(unordered, error = crate::ParseError)]
+    #[derive(Clone, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+    #[cfg_attr(feature = "serde", serde(rename = "stroke", deny_unknown_fields))]
+    pub struct Stroke {
+        (stroke
+            /// Width in nanometers.
+            ///
+            /// KiCad serializes in millimeters but uses nanometers internally.
+            #[cfg_attr(feature = "schemars", schemars(with = "Option<f64>"))]
+            #[cfg_attr(feature = "serde", serde(default))]
+            [(width: f64 as Nm)]
 
-    /// Line style.
-    #[serde(rename = "type")]
-    pub line_style: Option<LineStyle>,
+            /// Line style.
+            #[cfg_attr(feature = "serde", serde(rename = "type"))]
+            [(r#type => line_style: LineStyle)]
 
-    /// Color in RGBA format.
-    pub color: Option<Color>,
+            /// Color in RGBA format.
+            [(color: Color)]
+        )
+    }
 }
 
-impl TryFrom<&Cons> for Stroke {
-    type Error = ParseError;
-
-    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
-        let mut width = None;
-        let mut line_style = None;
-        let mut color = None;
-
-        let mut rest = cons.expect_cons_with_symbol_head("stroke")?;
-        while !rest.is_null() {
-            let r_cons = rest.expect_cons()?;
-            let element = r_cons.car();
-            rest = r_cons.cdr();
-            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{parse_str, ParseError},
+        kanga_sexpr::ToSexpr,
+    };
 
-            match key {
-                "width" => {
-                    let (value, cdr) = cdr.expect_cons_with_any_float_head()?;
-                    cdr.expect_null()?;
-                    width = Some((value * 1e6) as i64);
-                }
+    #[test]
+    fn test_stroke() {
+        // The elements may appear in any order and are written back in the usual one.
+        let stroke: Stroke = parse_str("(stroke (color 0 0 0 1) (type dash) (width 0.1524))").unwrap();
+        assert_eq!(stroke.width, Some(Nm(152_400)));
+        assert!(matches!(stroke.line_style, Some(LineStyle::Dash)));
+        assert!(stroke.color.is_some());
+        assert_eq!(stroke.to_value().to_string(), "(stroke (width 0.1524) (type dash) (color 0.0 0.0 0.0 1.0))");
 
-                "type" => {
-                    line_style = Some(LineStyle::try_from(element)?);
-                }
+        let stroke: Stroke = parse_str("(stroke)").unwrap();
+        assert!(stroke.width.is_none() && stroke.line_style.is_none() && stroke.color.is_none());
 
-                "color" => {
-                    color = Some(Color::try_from(element)?);
-                }
+        // Errors name the element they occurred in.
+        let error = parse_str::<Stroke>("(stroke (width 0.1) (type wavy))").unwrap_err();
+        assert_eq!(error.as_parse_error().unwrap().breadcrumb(), ["stroke"]);
 
-                _ => {
-                    return Err(ParseError::Unexpected(element.clone()));
-                }
-            }
+        for (text, expected) in [
+            ("(stroke (width 0.1) (width 0.2))", "Duplicate Stroke field width"),
+            ("(stroke (width 0.1) (dash))", "Unexpected value (dash)"),
+            ("(stroke (width 0.1 0.2))", "Expected list with floating-point head"),
+        ] {
+            let error = parse_str::<Stroke>(text).unwrap_err();
+            let root = error.as_parse_error().map(ParseError::root_cause).unwrap().to_string();
+            assert!(root.starts_with(expected), "{text}: {root}");
         }
-
-        Ok(Self {
-            width,
-            line_style,
-            color,
-        })
     }
 }
-
-impl_try_from_cons_value!(Stroke);
@@ -1,65 +1,94 @@
 use {
-    super::{
-        deserialize_mm_to_nm, deserialize_mm_to_unsigned_nm, serialize_nm_to_mm, serialize_unsigned_nm_to_mm, Fill,
-        Offset, Points, Position, Stroke, TextEffects,
-    },
-    crate::{impl_try_from_cons_value, LexprExt, ParseError},
+    super::{nm_to_mm_value, sexpr_flag, sexpr_list, Fill, Nm, Point, Points, Position, Stroke, TextEffects},
+    crate::{impl_positioned, impl_try_from_cons_value, LexprExt, ParseError},
+    kanga_sexpr::ToSexpr,
     lexpr::{Cons, Value},
-    serde::{Deserialize, Serialize},
     std::str::FromStr,
 };
 
 /// KiCad symbol or sub-unit of a parent symbol.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
 pub struct Symbol {
     /// The library id or unit id.
     pub id: String,
 
     /// If this symbol extends another symbol, this is the id of the parent symbol.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
     pub extends: Option<String>,
 
+    /// Whether this is a power symbol, which names a global net.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+    pub power: bool,
+
     /// How to treat pin numbers in this symbol.
-    #[serde(default, skip_serializing_if = "SymbolPinNumberDefaults::is_default")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "SymbolPinNumberDefaults::is_default"))]
     pub pin_numbers: SymbolPinNumberDefaults,
 
     /// How to treat pin names in this symbol.
-    #[serde(default, skip_serializing_if = "SymbolPinNameDefaults::is_default")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "SymbolPinNameDefaults::is_default"))]
     pub pin_names: SymbolPinNameDefaults,
 
     /// Whether this symbol should be excluded from simulation.
     ///
     /// KiCad does not document this, but it is present in some schematic files.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
     pub exclude_from_sim: Option<bool>,
 
     /// Whether this symbol is included in the BOM.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
     pub in_bom: Option<bool>,
 
     /// Whether this symbol is included on the PCB.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
     pub on_board: Option<bool>,
 
-    /// Properties associated with this symbol. Note that these are extended from the regular
-    /// [`Property`][crate::Property] type.
-    #[serde(default)]
+    /// Whether fonts used by this symbol are embedded, written by KiCad 9.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub embedded_fonts: Option<bool>,
+
+    /// The search keywords, from the hidden `ki_keywords` property.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub keywords: Vec<String>,
+
+    /// The description, from the hidden `ki_description` property written by KiCad 6 and 7. KiCad 8
+    /// writes a `Description` property instead; [`Symbol::description`] reads either.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub description: Option<String>,
+
+    /// Wildcard patterns for the footprints that suit this symbol, such as `R_*`, from the hidden
+    /// `ki_fp_filters` property.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub fp_filters: Vec<String>,
+
+    /// Properties associated with this symbol, other than the hidden `ki_` properties above. Note
+    /// that these are extended from the regular [`Property`][crate::Property] type.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub properties: Vec<SymbolProperty>,
 
     /// Symbol graphics.
-    #[serde(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub graphics: Vec<SymbolGraphic>,
 
     /// Symbol pins.
-    #[serde(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub pins: Vec<SymbolPin>,
+
+    /// Units of the symbol, written as nested `(symbol "<id>_<unit>_<style>" ...)` blocks. Unit 0
+    /// holds the graphics and pins shared by all units.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub units: Vec<Symbol>,
 }
 
 /// KiCad symbol graphic.
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_symbol_graphic_items)///
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "snake_case")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum SymbolGraphic {
     /// Graphical arc.
     Arc(SymbolGraphicArc),
@@ -83,17 +112,19 @@ pub enum SymbolGraphic {
 /// Symbol graphic arc.
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_symbol_arc)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "arc")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "arc", deny_unknown_fields))]
 pub struct SymbolGraphicArc {
     /// The starting point of the arc.
-    pub start: Position,
+    pub start: Point,
 
     /// The midpoint of the arc.
-    pub mid: Position,
+    pub mid: Point,
 
     /// The ending point of the arc.
-    pub end: Position,
+    pub end: Point,
 
     /// The stroke definition of the arc.
     pub stroke: Stroke,
@@ -105,11 +136,13 @@ pub struct SymbolGraphicArc {
 /// Symbol graphic bezier curve.
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_symbol_curve)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "bezier")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "bezier", deny_unknown_fields))]
 pub struct SymbolGraphicBezier {
     /// The four X/Y coordinates of the curve.
-    #[serde(rename = "pts")]
+    #[cfg_attr(feature = "serde", serde(rename = "pts"))]
     pub points: Points,
 
     /// The stroke definition of the curve.
@@ -122,15 +155,17 @@ pub struct SymbolGraphicBezier {
 /// Symbol graphic circle.
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_symbol_circle)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "circle")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "circle", deny_unknown_fields))]
 pub struct SymbolGraphicCircle {
     /// The center of the circle.
-    pub center: Position,
+    pub center: Point,
 
-    /// The radius of the circle in nm.
-    #[serde(deserialize_with = "deserialize_mm_to_unsigned_nm", serialize_with = "serialize_unsigned_nm_to_mm")]
-    pub radius: u64,
+    /// The radius of the circle.
+    #[cfg_attr(feature = "schemars", schemars(with = "f64"))]
+    pub radius: Nm,
 
     /// The stroke definition of the circle.
     pub stroke: Stroke,
@@ -142,11 +177,13 @@ pub struct SymbolGraphicCircle {
 /// Symbol graphic polyline, which is not necessarily a closed polygon.
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_symbol_line)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "polyline")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "polyline", deny_unknown_fields))]
 pub struct SymbolGraphicPolyline {
     /// The points of the polyline.
-    #[serde(rename = "pts")]
+    #[cfg_attr(feature = "serde", serde(rename = "pts"))]
     pub points: Points,
 
     /// The stroke definition of the polyline.
@@ -159,14 +196,16 @@ pub struct SymbolGraphicPolyline {
 /// Symbol graphic rectangle.
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_symbol_rectangle)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "rectangle")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "rectangle", deny_unknown_fields))]
 pub struct SymbolGraphicRectangle {
     /// The start point of the rectangle.
-    pub start: Position,
+    pub start: Point,
 
     /// The end point of the rectangle.
-    pub end: Position,
+    pub end: Point,
 
     /// The stroke definition of the rectangle.
     pub stroke: Stroke,
@@ -178,26 +217,30 @@ pub struct SymbolGraphicRectangle {
 /// Symbol graphic text.
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_symbol_text)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "text")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "text", deny_unknown_fields))]
 pub struct SymbolGraphicText {
     /// The text to display.
     pub text: String,
 
     /// The position of the text.
-    #[serde(rename = "at")]
+    #[cfg_attr(feature = "serde", serde(rename = "at"))]
     pub position: Position,
 
     /// Text effects for displaying the text.
-    #[serde(rename = "effects")]
+    #[cfg_attr(feature = "serde", serde(rename = "effects"))]
     pub text_effects: TextEffects,
 }
 
 /// Pin in a symbol definition.
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_symbol_pin)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "pin")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "pin", deny_unknown_fields))]
 pub struct SymbolPin {
     /// The electrical type of the pin.
     pub electrical_type: SymbolPinElectricalType,
@@ -206,27 +249,33 @@ pub struct SymbolPin {
     pub graphical_style: SymbolPinGraphicalStyle,
 
     /// The position of the pin.
-    #[serde(rename = "at")]
+    #[cfg_attr(feature = "serde", serde(rename = "at"))]
     pub position: Position,
 
     /// The length of the pin in nm.
     ///
     /// It is possible, though not exactly sensical, for this value to be negative.
-    #[serde(deserialize_with = "deserialize_mm_to_nm", serialize_with = "serialize_nm_to_mm")]
-    pub length: i64,
+    #[cfg_attr(feature = "schemars", schemars(with = "f64"))]
+    pub length: Nm,
 
     /// The name of the pin.
     pub name: SymbolPinName,
 
     /// The number of the pin.
     pub number: SymbolPinNumber,
+
+    /// Whether the pin is hidden.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+    pub hide: bool,
 }
 
 /// KiCad symbol pin electrical type.
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_symbol_pin)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum SymbolPinElectricalType {
     /// Pin is an input
     Input,
@@ -268,8 +317,10 @@ pub enum SymbolPinElectricalType {
 /// KiCad symbol pin graphical style
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_symbol_pin)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "snake_case")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum SymbolPinGraphicalStyle {
     /// Line: `|---`
     Line,
@@ -300,53 +351,66 @@ pub enum SymbolPinGraphicalStyle {
 }
 
 /// The name of a symbol pin.
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "name")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "name", deny_unknown_fields))]
 pub struct SymbolPinName {
     /// The name of the pin.
     pub name: String,
 
     /// Text effects for displaying the pin name.
-    #[serde(rename = "effects")]
+    #[cfg_attr(feature = "serde", serde(rename = "effects"))]
     pub text_effects: TextEffects,
 }
 
 /// The number of a symbol pin.
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "number")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "number", deny_unknown_fields))]
 pub struct SymbolPinNumber {
     /// The number of the pin.
     pub number: String,
 
     /// Text effects for displaying the pin number.
-    #[serde(rename = "effects")]
+    #[cfg_attr(feature = "serde", serde(rename = "effects"))]
     pub text_effects: TextEffects,
 }
 
 /// How to treat pin names in this symbol by default.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
 pub struct SymbolPinNameDefaults {
     /// The offset of the pin name in nm.
-    #[serde(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub offset: i64,
 
     /// Whether pin names are hidden.
-    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
     pub hide: bool,
 }
 
 /// How to treat pin numbers in this symbol by default.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
 pub struct SymbolPinNumberDefaults {
     /// Whether pin numbers are hidden.
-    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
     pub hide: bool,
 }
 
 /// KiCad symbol property.
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_symbol_properties)
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
 pub struct SymbolProperty {
     /// Property name.
     pub key: String,
@@ -357,16 +421,25 @@ pub struct SymbolProperty {
     /// Unique integer identifier for the property.
     ///
     /// KiCad documents this as required, but it is not present in schematic lib_symbols symbols.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
     pub identifier: Option<i64>,
 
     /// Position of the property.
-    #[serde(rename = "at")]
+    #[cfg_attr(feature = "serde", serde(rename = "at"))]
     pub position: Option<Position>,
 
     /// Text effects for displaying the property.
-    #[serde(rename = "effects")]
+    #[cfg_attr(feature = "serde", serde(rename = "effects"))]
     pub text_effects: Option<TextEffects>,
+
+    /// Whether the property name is shown along with its value. Only used in schematics.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+    pub show_name: bool,
+
+    /// Whether KiCad should leave the property where it is when automatically placing fields. Only
+    /// used in schematics.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+    pub do_not_autoplace: bool,
 }
 
 impl TryFrom<&Cons> for Symbol {
@@ -378,14 +451,20 @@ impl TryFrom<&Cons> for Symbol {
         let id = id.to_string();
 
         let mut extends = None;
+        let mut power = false;
         let mut pin_numbers = SymbolPinNumberDefaults::default();
         let mut pin_names = SymbolPinNameDefaults::default();
         let mut exclude_from_sim = None;
         let mut in_bom = None;
         let mut on_board = None;
+        let mut embedded_fonts = None;
+        let mut keywords = Vec::new();
+        let mut description = None;
+        let mut fp_filters = Vec::new();
         let mut properties = Vec::new();
         let mut graphics = Vec::new();
         let mut pins = Vec::new();
+        let mut units = Vec::new();
 
         while !rest.is_null() {
             let r_cons = rest.expect_cons()?;
@@ -395,7 +474,7 @@ impl TryFrom<&Cons> for Symbol {
             let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
 
             match key {
-                "exclude_from_sim" | "in_bom" | "on_board" => {
+                "exclude_from_sim" | "in_bom" | "on_board" | "embedded_fonts" => {
                     let v = cdr.expect_cons()?;
                     let v_car = v.car();
                     v.cdr().expect_null()?;
@@ -405,16 +484,22 @@ impl TryFrom<&Cons> for Symbol {
                         "exclude_from_sim" => exclude_from_sim = Some(value),
                         "in_bom" => in_bom = Some(value),
                         "on_board" => on_board = Some(value),
+                        "embedded_fonts" => embedded_fonts = Some(value),
                         _ => unreachable!(),
                     }
                 }
 
                 "extends" => {
                     let (value, cdr) = cdr.expect_cons_with_any_str_head()?;
-                    cdr.expect_null();
+                    cdr.expect_null()?;
                     extends = Some(value.to_string());
                 }
 
+                "power" => {
+                    cdr.expect_null()?;
+                    power = true;
+                }
+
                 "pin_names" => {
                     pin_names = SymbolPinNameDefaults::try_from(element)?;
                 }
@@ -424,17 +509,47 @@ impl TryFrom<&Cons> for Symbol {
                 }
 
                 "property" => {
-                    properties.push(SymbolProperty::try_from(element)?);
+                    let property = SymbolProperty::try_from(element)?;
+                    match property.key.as_str() {
+                        "ki_keywords" => keywords = property.value.split_whitespace().map(String::from).collect(),
+                        "ki_description" => description = Some(property.value),
+                        "ki_fp_filters" => fp_filters = property.value.split_whitespace().map(String::from).collect(),
+                        _ => properties.push(property),
+                    }
                 }
 
                 "arc" => {
                     graphics.push(SymbolGraphicArc::try_from(element)?.into());
                 }
 
+                "bezier" => {
+                    graphics.push(SymbolGraphicBezier::try_from(element)?.into());
+                }
+
+                "circle" => {
+                    graphics.push(SymbolGraphicCircle::try_from(element)?.into());
+                }
+
+                "polyline" => {
+                    graphics.push(SymbolGraphicPolyline::try_from(element)?.into());
+                }
+
+                "rectangle" => {
+                    graphics.push(SymbolGraphicRectangle::try_from(element)?.into());
+                }
+
+                "text" => {
+                    graphics.push(SymbolGraphicText::try_from(element)?.into());
+                }
+
                 "pin" => {
                     pins.push(SymbolPin::try_from(element)?);
                 }
 
+                "symbol" => {
+                    units.push(Symbol::try_from(element)?);
+                }
+
                 _ => {
                     return Err(ParseError::Unexpected(element.clone()));
                 }
@@ -444,14 +559,20 @@ impl TryFrom<&Cons> for Symbol {
         Ok(Self {
             id,
             extends,
+            power,
             pin_numbers,
             pin_names,
             exclude_from_sim,
             in_bom,
             on_board,
+            embedded_fonts,
+            keywords,
+            description,
+            fp_filters,
             properties,
             graphics,
             pins,
+            units,
         })
     }
 }
@@ -500,6 +621,8 @@ impl From<SymbolGraphicText> for SymbolGraphic {
     }
 }
 
+impl_positioned!(SymbolGraphicText);
+
 impl TryFrom<&Cons> for SymbolGraphicArc {
     type Error = ParseError;
 
@@ -520,9 +643,9 @@ impl TryFrom<&Cons> for SymbolGraphicArc {
             let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
 
             match key {
-                "start" => start = Some(Position::try_from_xy_cons(cdr)?),
-                "mid" => mid = Some(Position::try_from_xy_cons(cdr)?),
-                "end" => end = Some(Position::try_from_xy_cons(cdr)?),
+                "start" => start = Some(Point::try_from_xy_cons(cdr)?),
+                "mid" => mid = Some(Point::try_from_xy_cons(cdr)?),
+                "end" => end = Some(Point::try_from_xy_cons(cdr)?),
                 "stroke" => stroke = Some(Stroke::try_from(element)?),
                 "fill" => fill = Some(Fill::try_from(element)?),
                 _ => return Err(ParseError::Unexpected(element.clone())),
@@ -561,6 +684,251 @@ impl TryFrom<&Cons> for SymbolGraphicArc {
 
 impl_try_from_cons_value!(SymbolGraphicArc);
 
+impl TryFrom<&Cons> for SymbolGraphicBezier {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let mut points = None;
+        let mut stroke = None;
+        let mut fill = None;
+
+        let mut rest = cons.expect_cons_with_symbol_head("bezier")?;
+
+        while !rest.is_null() {
+            let r_cons = rest.expect_cons()?;
+            let element = r_cons.car();
+            rest = r_cons.cdr();
+
+            let (key, _) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "pts" => points = Some(Points::try_from(element)?),
+                "stroke" => stroke = Some(Stroke::try_from(element)?),
+                "fill" => fill = Some(Fill::try_from(element)?),
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        let Some(points) = points else {
+            return Err(ParseError::missing_field("bezier", "pts", cons.clone()));
+        };
+
+        let Some(stroke) = stroke else {
+            return Err(ParseError::missing_field("bezier", "stroke", cons.clone()));
+        };
+
+        let Some(fill) = fill else {
+            return Err(ParseError::missing_field("bezier", "fill", cons.clone()));
+        };
+
+        Ok(Self {
+            points,
+            stroke,
+            fill,
+        })
+    }
+}
+
+impl_try_from_cons_value!(SymbolGraphicBezier);
+
+impl TryFrom<&Cons> for SymbolGraphicCircle {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let mut center = None;
+        let mut radius = None;
+        let mut stroke = None;
+        let mut fill = None;
+
+        let mut rest = cons.expect_cons_with_symbol_head("circle")?;
+
+        while !rest.is_null() {
+            let r_cons = rest.expect_cons()?;
+            let element = r_cons.car();
+            rest = r_cons.cdr();
+
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "center" => center = Some(Point::try_from_xy_cons(cdr)?),
+                "radius" => radius = Some(radius_arg(cdr)?),
+                "stroke" => stroke = Some(Stroke::try_from(element)?),
+                "fill" => fill = Some(Fill::try_from(element)?),
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        let Some(center) = center else {
+            return Err(ParseError::missing_field("circle", "center", cons.clone()));
+        };
+
+        let Some(radius) = radius else {
+            return Err(ParseError::missing_field("circle", "radius", cons.clone()));
+        };
+
+        let Some(stroke) = stroke else {
+            return Err(ParseError::missing_field("circle", "stroke", cons.clone()));
+        };
+
+        let Some(fill) = fill else {
+            return Err(ParseError::missing_field("circle", "fill", cons.clone()));
+        };
+
+        Ok(Self {
+            center,
+            radius,
+            stroke,
+            fill,
+        })
+    }
+}
+
+impl_try_from_cons_value!(SymbolGraphicCircle);
+
+impl TryFrom<&Cons> for SymbolGraphicPolyline {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let mut points = None;
+        let mut stroke = None;
+        let mut fill = None;
+
+        let mut rest = cons.expect_cons_with_symbol_head("polyline")?;
+
+        while !rest.is_null() {
+            let r_cons = rest.expect_cons()?;
+            let element = r_cons.car();
+            rest = r_cons.cdr();
+
+            let (key, _) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "pts" => points = Some(Points::try_from(element)?),
+                "stroke" => stroke = Some(Stroke::try_from(element)?),
+                "fill" => fill = Some(Fill::try_from(element)?),
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        let Some(points) = points else {
+            return Err(ParseError::missing_field("polyline", "pts", cons.clone()));
+        };
+
+        let Some(stroke) = stroke else {
+            return Err(ParseError::missing_field("polyline", "stroke", cons.clone()));
+        };
+
+        let Some(fill) = fill else {
+            return Err(ParseError::missing_field("polyline", "fill", cons.clone()));
+        };
+
+        Ok(Self {
+            points,
+            stroke,
+            fill,
+        })
+    }
+}
+
+impl_try_from_cons_value!(SymbolGraphicPolyline);
+
+impl TryFrom<&Cons> for SymbolGraphicRectangle {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let mut start = None;
+        let mut end = None;
+        let mut stroke = None;
+        let mut fill = None;
+
+        let mut rest = cons.expect_cons_with_symbol_head("rectangle")?;
+
+        while !rest.is_null() {
+            let r_cons = rest.expect_cons()?;
+            let element = r_cons.car();
+            rest = r_cons.cdr();
+
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "start" => start = Some(Point::try_from_xy_cons(cdr)?),
+                "end" => end = Some(Point::try_from_xy_cons(cdr)?),
+                "stroke" => stroke = Some(Stroke::try_from(element)?),
+                "fill" => fill = Some(Fill::try_from(element)?),
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        let Some(start) = start else {
+            return Err(ParseError::missing_field("rectangle", "start", cons.clone()));
+        };
+
+        let Some(end) = end else {
+            return Err(ParseError::missing_field("rectangle", "end", cons.clone()));
+        };
+
+        let Some(stroke) = stroke else {
+            return Err(ParseError::missing_field("rectangle", "stroke", cons.clone()));
+        };
+
+        let Some(fill) = fill else {
+            return Err(ParseError::missing_field("rectangle", "fill", cons.clone()));
+        };
+
+        Ok(Self {
+            start,
+            end,
+            stroke,
+            fill,
+        })
+    }
+}
+
+impl_try_from_cons_value!(SymbolGraphicRectangle);
+
+impl TryFrom<&Cons> for SymbolGraphicText {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let rest = cons.expect_cons_with_symbol_head("text")?;
+        let (text, mut rest) = rest.expect_cons_with_any_str_head()?;
+        let text = text.to_string();
+
+        let mut position = None;
+        let mut text_effects = None;
+
+        while !rest.is_null() {
+            let r_cons = rest.expect_cons()?;
+            let element = r_cons.car();
+            rest = r_cons.cdr();
+
+            let (key, _) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "at" => position = Some(Position::try_from(element)?),
+                "effects" => text_effects = Some(TextEffects::try_from(element)?),
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        let Some(position) = position else {
+            return Err(ParseError::missing_field("text", "at", cons.clone()));
+        };
+
+        let Some(text_effects) = text_effects else {
+            return Err(ParseError::missing_field("text", "effects", cons.clone()));
+        };
+
+        Ok(Self {
+            text,
+            position,
+            text_effects,
+        })
+    }
+}
+
+impl_try_from_cons_value!(SymbolGraphicText);
+
 impl TryFrom<&Cons> for SymbolPin {
     type Error = ParseError;
 
@@ -577,12 +945,19 @@ impl TryFrom<&Cons> for SymbolPin {
         let mut length = None;
         let mut name = None;
         let mut number = None;
+        let mut hide = false;
 
         while !rest.is_null() {
             let r_cons = rest.expect_cons()?;
             let element = r_cons.car();
             rest = r_cons.cdr();
 
+            // Before KiCad 8 a hidden pin was marked with a bare hide.
+            if element.as_symbol() == Some("hide") {
+                hide = true;
+                continue;
+            }
+
             let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
 
             match key {
@@ -591,9 +966,9 @@ impl TryFrom<&Cons> for SymbolPin {
                 }
 
                 "length" => {
-                    let (value, cdr) = cdr.expect_cons_with_any_float_head()?;
+                    let (value, cdr) = cdr.expect_cons_with_any_length_head()?;
                     cdr.expect_null()?;
-                    length = Some((value * 1e6) as i64);
+                    length = Some(value);
                 }
 
                 "name" => {
@@ -604,6 +979,12 @@ impl TryFrom<&Cons> for SymbolPin {
                     number = Some(SymbolPinNumber::try_from(element)?);
                 }
 
+                "hide" => {
+                    let value = cdr.expect_cons()?;
+                    value.cdr().expect_null()?;
+                    hide = value.car().expect_bool()?;
+                }
+
                 _ => return Err(ParseError::Unexpected(element.clone())),
             }
         }
@@ -631,11 +1012,13 @@ impl TryFrom<&Cons> for SymbolPin {
             length,
             name,
             number,
+            hide,
         })
     }
 }
 
 impl_try_from_cons_value!(SymbolPin);
+impl_positioned!(SymbolPin);
 
 impl FromStr for SymbolPinElectricalType {
     type Err = ParseError;
@@ -729,9 +1112,16 @@ impl TryFrom<&Cons> for SymbolPinNameDefaults {
                 let (key, cdr) = econs.expect_cons_with_any_symbol_head()?;
                 match key {
                     "offset" => {
-                        let (value, cdr) = cdr.expect_cons_with_any_float_head()?;
+                        let (value, cdr) = cdr.expect_cons_with_any_length_head()?;
                         cdr.expect_null()?;
-                        offset = (value * 1e6) as i64;
+                        offset = value.0;
+                    }
+
+                    // KiCad 9 writes `(hide yes)` rather than a bare `hide`.
+                    "hide" => {
+                        let value = cdr.expect_cons()?;
+                        value.cdr().expect_null()?;
+                        hide = value.car().expect_bool()?;
                     }
 
                     _ => return Err(ParseError::Unexpected(element.clone())),
@@ -790,6 +1180,42 @@ impl TryFrom<&Cons> for SymbolPinNumber {
 
 impl_try_from_cons_value!(SymbolPinNumber);
 
+impl Symbol {
+    /// Return the value of the property with the given name, such as `Reference` or `Value`.
+    pub fn property(&self, key: &str) -> Option<&str> {
+        self.properties.iter().find(|property| property.key == key).map(|property| property.value.as_str())
+    }
+
+    /// Return the description, from `ki_description` in KiCad 6 and 7 files or the `Description`
+    /// property in later ones. An empty description is treated as missing.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref().or_else(|| self.property("Description")).filter(|text| !text.is_empty())
+    }
+
+    /// Return the unit number and body style at the end of a unit's id, such as `(1, 2)` for
+    /// `R_1_2`, or `None` if the id does not end in them.
+    pub fn unit_number(&self) -> Option<(i64, i64)> {
+        let (rest, style) = self.id.rsplit_once('_')?;
+        let (_, unit) = rest.rsplit_once('_')?;
+        Some((unit.parse().ok()?, style.parse().ok()?))
+    }
+
+    /// Return the units drawn for unit `unit` in the normal body style: unit 0, which all units
+    /// share, and unit `unit` itself. `None` selects every unit in the normal body style.
+    pub fn units_of(&self, unit: Option<i64>) -> impl Iterator<Item = &Symbol> {
+        self.units.iter().filter(move |symbol| match symbol.unit_number() {
+            Some((number, 0 | 1)) => number == 0 || unit.is_none_or(|unit| number == unit),
+            _ => false,
+        })
+    }
+
+    /// Return the pins of unit `unit` in the normal body style, including any pins declared on the
+    /// symbol itself. `None` selects the pins of every unit.
+    pub fn unit_pins(&self, unit: Option<i64>) -> impl Iterator<Item = &SymbolPin> {
+        self.pins.iter().chain(self.units_of(unit).flat_map(|symbol| &symbol.pins))
+    }
+}
+
 impl SymbolPinNameDefaults {
     /// Indicates whether this is the default pin name treatment.
     #[inline(always)]
@@ -810,7 +1236,12 @@ impl TryFrom<&Cons> for SymbolPinNumberDefaults {
             let element = cons.car();
             rest = cons.cdr();
 
-            if let Some(key) = element.as_symbol() {
+            if let Some(econs) = element.as_cons() {
+                // KiCad 9 writes `(hide yes)` rather than a bare `hide`.
+                let value = econs.expect_cons_with_symbol_head("hide")?.expect_cons()?;
+                value.cdr().expect_null()?;
+                hide = value.car().expect_bool()?;
+            } else if let Some(key) = element.as_symbol() {
                 if key == "hide" {
                     hide = true
                 } else {
@@ -850,6 +1281,8 @@ impl TryFrom<&Cons> for SymbolProperty {
         let mut identifier = None;
         let mut position = None;
         let mut text_effects = None;
+        let mut show_name = false;
+        let mut do_not_autoplace = false;
 
         while !rest.is_null() {
             let cons = rest.expect_cons()?;
@@ -872,6 +1305,26 @@ impl TryFrom<&Cons> for SymbolProperty {
                     text_effects = Some(TextEffects::try_from(element)?);
                 }
 
+                // Before KiCad 8 these flags were written without a value.
+                "show_name" | "do_not_autoplace" => {
+                    let value = match cdr.as_cons() {
+                        Some(cdr) => {
+                            cdr.cdr().expect_null()?;
+                            cdr.car().expect_bool()?
+                        }
+                        None => {
+                            cdr.expect_null()?;
+                            true
+                        }
+                    };
+
+                    match id {
+                        "show_name" => show_name = value,
+                        "do_not_autoplace" => do_not_autoplace = value,
+                        _ => unreachable!(),
+                    }
+                }
+
                 _ => {
                     return Err(ParseError::Unexpected(element.clone()));
                 }
@@ -884,8 +1337,327 @@ impl TryFrom<&Cons> for SymbolProperty {
             identifier,
             position,
             text_effects,
+            show_name,
+            do_not_autoplace,
         })
     }
 }
 
 impl_try_from_cons_value!(SymbolProperty);
+impl_positioned!(SymbolProperty, optional);
+
+impl SymbolPinElectricalType {
+    /// Return the name KiCad uses for this type.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Input => "input",
+            Self::Output => "output",
+            Self::Bidirectional => "bidirectional",
+            Self::TriState => "tri_state",
+            Self::Passive => "passive",
+            Self::Free => "free",
+            Self::Unspecified => "unspecified",
+            Self::PowerIn => "power_in",
+            Self::PowerOut => "power_out",
+            Self::OpenCollector => "open_collector",
+            Self::OpenEmitter => "open_emitter",
+            Self::NoConnect => "no_connect",
+        }
+    }
+}
+
+impl SymbolPinGraphicalStyle {
+    /// Return the name KiCad uses for this style.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Line => "line",
+            Self::Inverted => "inverted",
+            Self::Clock => "clock",
+            Self::InvertedClock => "inverted_clock",
+            Self::InputLow => "input_low",
+            Self::ClockLow => "clock_low",
+            Self::OutputLow => "output_low",
+            Self::EdgeClockHigh => "edge_clock_high",
+            Self::NonLogic => "non_logic",
+        }
+    }
+}
+
+impl From<&Symbol> for Value {
+    fn from(symbol: &Symbol) -> Self {
+        let mut items = vec![Value::string(symbol.id.as_str())];
+
+        if let Some(extends) = &symbol.extends {
+            items.push(sexpr_list("extends", [Value::string(extends.as_str())]));
+        }
+
+        if symbol.power {
+            items.push(sexpr_list("power", []));
+        }
+
+        if !symbol.pin_numbers.is_default() {
+            items.push(Value::from(&symbol.pin_numbers));
+        }
+
+        if !symbol.pin_names.is_default() {
+            items.push(Value::from(&symbol.pin_names));
+        }
+
+        for (key, value) in
+            [("exclude_from_sim", symbol.exclude_from_sim), ("in_bom", symbol.in_bom), ("on_board", symbol.on_board)]
+        {
+            if let Some(value) = value {
+                items.push(sexpr_flag(key, value));
+            }
+        }
+
+        items.extend(symbol.properties.iter().map(Value::from));
+
+        // KiCad writes these last among the properties, hidden at the symbol origin.
+        let hidden = [
+            ("ki_keywords", (!symbol.keywords.is_empty()).then(|| symbol.keywords.join(" "))),
+            ("ki_description", symbol.description.clone()),
+            ("ki_fp_filters", (!symbol.fp_filters.is_empty()).then(|| symbol.fp_filters.join(" "))),
+        ];
+        for (key, value) in hidden {
+            if let Some(value) = value {
+                items.push(hidden_property(key, &value));
+            }
+        }
+
+        items.extend(symbol.graphics.iter().map(Value::from));
+        items.extend(symbol.pins.iter().map(Value::from));
+        items.extend(symbol.units.iter().map(Value::from));
+        if let Some(embedded_fonts) = symbol.embedded_fonts {
+            items.push(sexpr_flag("embedded_fonts", embedded_fonts));
+        }
+        sexpr_list("symbol", items)
+    }
+}
+
+/// Parse the radius of a circle, `(radius <mm>)`, into nanometers. KiCad does not write negative
+/// radii.
+fn radius_arg(args: &Value) -> Result<Nm, ParseError> {
+    let (value, rest) = args.expect_cons_with_any_length_head()?;
+    rest.expect_null()?;
+
+    match value {
+        radius if radius < Nm::ZERO => Err(ParseError::Unexpected(args.clone())),
+        radius => Ok(radius),
+    }
+}
+
+/// Build a hidden `ki_` property as KiCad writes it.
+fn hidden_property(key: &str, value: &str) -> Value {
+    let font = sexpr_list("font", [sexpr_list("size", [nm_to_mm_value(1_270_000), nm_to_mm_value(1_270_000)])]);
+    sexpr_list(
+        "property",
+        [
+            Value::string(key),
+            Value::string(value),
+            sexpr_list("at", [Value::from(0), Value::from(0), Value::from(0)]),
+            sexpr_list("effects", [font, Value::symbol("hide")]),
+        ],
+    )
+}
+
+impl From<&SymbolGraphic> for Value {
+    fn from(graphic: &SymbolGraphic) -> Self {
+        match graphic {
+            SymbolGraphic::Arc(arc) => sexpr_list(
+                "arc",
+                [
+                    arc.start.to_xy_value("start"),
+                    arc.mid.to_xy_value("mid"),
+                    arc.end.to_xy_value("end"),
+                    arc.stroke.to_value(),
+                    arc.fill.to_value(),
+                ],
+            ),
+
+            SymbolGraphic::Bezier(bezier) => {
+                sexpr_list("bezier", [Value::from(&bezier.points), bezier.stroke.to_value(), bezier.fill.to_value()])
+            }
+
+            SymbolGraphic::Circle(circle) => sexpr_list(
+                "circle",
+                [
+                    circle.center.to_xy_value("center"),
+                    sexpr_list("radius", [nm_to_mm_value(circle.radius.0)]),
+                    circle.stroke.to_value(),
+                    circle.fill.to_value(),
+                ],
+            ),
+
+            SymbolGraphic::Polyline(line) => {
+                sexpr_list("polyline", [Value::from(&line.points), line.stroke.to_value(), line.fill.to_value()])
+            }
+
+            SymbolGraphic::Rectangle(rect) => sexpr_list(
+                "rectangle",
+                [
+                    rect.start.to_xy_value("start"),
+                    rect.end.to_xy_value("end"),
+                    rect.stroke.to_value(),
+                    rect.fill.to_value(),
+                ],
+            ),
+
+            SymbolGraphic::Text(text) => sexpr_list(
+                "text",
+                [Value::string(text.text.as_str()), text.position.to_value(), text.text_effects.to_value()],
+            ),
+        }
+    }
+}
+
+impl From<&SymbolPin> for Value {
+    fn from(pin: &SymbolPin) -> Self {
+        let mut items = vec![
+            Value::symbol(pin.electrical_type.as_str()),
+            Value::symbol(pin.graphical_style.as_str()),
+            pin.position.to_value(),
+            sexpr_list("length", [nm_to_mm_value(pin.length)]),
+        ];
+        if pin.hide {
+            items.push(sexpr_flag("hide", true));
+        }
+        items.push(sexpr_list("name", [Value::string(pin.name.name.as_str()), pin.name.text_effects.to_value()]));
+        items.push(sexpr_list(
+            "number",
+            [Value::string(pin.number.number.as_str()), pin.number.text_effects.to_value()],
+        ));
+        sexpr_list("pin", items)
+    }
+}
+
+impl From<&SymbolPinNameDefaults> for Value {
+    fn from(defaults: &SymbolPinNameDefaults) -> Self {
+        let offset = (defaults.offset != 0).then(|| sexpr_list("offset", [nm_to_mm_value(defaults.offset)]));
+        let hide = defaults.hide.then(|| Value::symbol("hide"));
+        sexpr_list("pin_names", [offset, hide].into_iter().flatten())
+    }
+}
+
+impl From<&SymbolPinNumberDefaults> for Value {
+    fn from(defaults: &SymbolPinNumberDefaults) -> Self {
+        sexpr_list("pin_numbers", defaults.hide.then(|| Value::symbol("hide")))
+    }
+}
+
+impl From<&SymbolProperty> for Value {
+    fn from(property: &SymbolProperty) -> Self {
+        let mut items = vec![Value::string(property.key.as_str()), Value::string(property.value.as_str())];
+        if let Some(identifier) = property.identifier {
+            items.push(sexpr_list("id", [Value::from(identifier)]));
+        }
+        items.extend(property.position.as_ref().map(ToSexpr::to_value));
+        if property.show_name {
+            items.push(sexpr_flag("show_name", true));
+        }
+        if property.do_not_autoplace {
+            items.push(sexpr_flag("do_not_autoplace", true));
+        }
+        items.extend(property.text_effects.as_ref().map(ToSexpr::to_value));
+        sexpr_list("property", items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, lexpr::sexp};
+
+    #[test]
+    fn test_symbol_graphics() {
+        let symbol = sexp!((symbol "Demo"
+            (polyline (pts (xy -1.27 0) (xy 1.27 0)) (stroke (width 0.254) (type default)) (fill (type none)))
+            (bezier (pts (xy 0 0) (xy 1 1) (xy 2 1) (xy 3 0)) (stroke (width 0) (type default)) (fill (type none)))
+            (circle (center 0 1.27) (radius 0.635) (stroke (width 0) (type default)) (fill (type background)))
+            (rectangle (start -2.54 2.54) (end 2.54 -2.54) (stroke (width 0.254) (type default))
+                (fill (type background)))
+            (text "Demo" (at 0 -3.81 0) (effects (font (size 1.27 1.27))))));
+
+        let symbol = Symbol::try_from(&symbol).unwrap();
+        assert_eq!(symbol.graphics.len(), 5);
+        let SymbolGraphic::Polyline(polyline) = &symbol.graphics[0] else {
+            panic!("expected a polyline: {:?}", symbol.graphics[0]);
+        };
+        let SymbolGraphic::Bezier(bezier) = &symbol.graphics[1] else {
+            panic!("expected a bezier: {:?}", symbol.graphics[1]);
+        };
+        let SymbolGraphic::Circle(circle) = &symbol.graphics[2] else {
+            panic!("expected a circle: {:?}", symbol.graphics[2]);
+        };
+        let SymbolGraphic::Text(text) = &symbol.graphics[4] else {
+            panic!("expected text: {:?}", symbol.graphics[4]);
+        };
+
+        assert_eq!(polyline.points.points, [Point::new(Nm(-1_270_000), Nm(0)), Point::new(Nm(1_270_000), Nm(0))]);
+        assert_eq!(bezier.points.points.len(), 4);
+        assert_eq!((circle.center, circle.radius), (Point::new(Nm(0), Nm(1_270_000)), Nm(635_000)));
+        assert_eq!((text.text.as_str(), text.position.y), ("Demo", Nm(-3_810_000)));
+
+        // Each graphic is written back in a form that reads the same.
+        for graphic in &symbol.graphics {
+            let written = Value::from(graphic);
+            let reread = Symbol::try_from(&sexpr_list("symbol", [Value::string("Demo"), written])).unwrap();
+            assert_eq!(format!("{:?}", reread.graphics[0]), format!("{graphic:?}"));
+        }
+
+        let missing_radius =
+            sexp!((symbol "Demo" (circle (center 0 0) (stroke (width 0) (type default)) (fill (type none)))));
+        assert!(Symbol::try_from(&missing_radius).is_err());
+    }
+    #[test]
+    fn test_symbol_units() {
+        // Device:C from the lib_symbols of tests/HDMI Breakout.kicad_sch.
+        let symbol = lexpr::from_str(
+            r#"(symbol "Device:C"
+                (pin_numbers hide)
+                (pin_names (offset 0.254))
+                (exclude_from_sim no)
+                (in_bom yes)
+                (on_board yes)
+                (property "Reference" "C" (at 0.635 2.54 0) (effects (font (size 1.27 1.27)) (justify left)))
+                (property "Value" "C" (at 0.635 -2.54 0) (effects (font (size 1.27 1.27)) (justify left)))
+                (property "Footprint" "" (at 0.9652 -3.81 0) (effects (font (size 1.27 1.27)) (hide yes)))
+                (property "Datasheet" "~" (at 0 0 0) (effects (font (size 1.27 1.27)) (hide yes)))
+                (property "Description" "Unpolarized capacitor" (at 0 0 0)
+                    (effects (font (size 1.27 1.27)) (hide yes)))
+                (property "ki_keywords" "cap capacitor" (at 0 0 0) (effects (font (size 1.27 1.27)) (hide yes)))
+                (property "ki_fp_filters" "C_*" (at 0 0 0) (effects (font (size 1.27 1.27)) (hide yes)))
+                (symbol "C_0_1"
+                    (polyline (pts (xy -2.032 -0.762) (xy 2.032 -0.762)) (stroke (width 0.508) (type default))
+                        (fill (type none)))
+                    (polyline (pts (xy -2.032 0.762) (xy 2.032 0.762)) (stroke (width 0.508) (type default))
+                        (fill (type none))))
+                (symbol "C_1_1"
+                    (pin passive line (at 0 3.81 270) (length 2.794)
+                        (name "~" (effects (font (size 1.27 1.27))))
+                        (number "1" (effects (font (size 1.27 1.27)))))
+                    (pin passive line (at 0 -3.81 90) (length 2.794)
+                        (name "~" (effects (font (size 1.27 1.27))))
+                        (number "2" (effects (font (size 1.27 1.27)))))))"#,
+        )
+        .unwrap();
+
+        let symbol = Symbol::try_from(&symbol).unwrap();
+        assert_eq!(symbol.keywords, ["cap", "capacitor"]);
+        assert!(symbol.graphics.is_empty() && symbol.pins.is_empty());
+
+        let [common, unit] = &symbol.units[..] else {
+            panic!("expected two units: {:?}", symbol.units);
+        };
+        assert_eq!((common.id.as_str(), common.unit_number()), ("C_0_1", Some((0, 1))));
+        assert_eq!((unit.id.as_str(), unit.unit_number()), ("C_1_1", Some((1, 1))));
+        assert_eq!(common.graphics.len(), 2);
+
+        let numbers: Vec<_> = symbol.unit_pins(Some(1)).map(|pin| pin.number.number.as_str()).collect();
+        assert_eq!(numbers, ["1", "2"]);
+        assert_eq!(symbol.unit_pins(Some(2)).count(), 0);
+
+        // Units are written back as nested symbols.
+        let reread = Symbol::try_from(&Value::from(&symbol)).unwrap();
+        assert_eq!(format!("{:?}", reread.units), format!("{:?}", symbol.units));
+    }
+}
@@ -1,64 +1,47 @@
 use {
     super::{Font, TextJustify},
-    crate::{impl_try_from_cons_value, LexprExt, ParseError},
-    lexpr::Cons,
-    serde::{Deserialize, Serialize},
+    kanga_sexpr::sexpr,
 };
 
-/// KiCad text effects definition.
-///
-/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_text_effects)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "effects")]
-pub struct TextEffects {
-    /// Text font.
-    pub font: Option<Font>,
-
-    /// Text justification.
-    pub justify: Option<TextJustify>,
-
-    /// Whether the text is hidden.
-    #[serde(default)]
-    pub hide: bool,
+sexpr! {
+    /// KiCad text effects definition. The elements may appear in any order, and KiCad 8 and later
+    /// write `(hide yes)` instead of a bare `hide`.
+    ///
+    /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_text_effects)
+    #[sexpr(unordered, error = crate::ParseError)]
+    #[derive(Clone, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+    #[cfg_attr(feature = "serde", serde(rename = "effects", deny_unknown_fields))]
+    pub struct TextEffects {
+        (effects
+            /// Text font.
+            [(font: Font)]
+
+            /// Text justification.
+            [(justify: TextJustify)]
+
+            /// Whether the text is hidden.
+            #[cfg_attr(feature = "serde", serde(default))]
+            [hide]
+        )
+    }
 }
 
-impl TryFrom<&Cons> for TextEffects {
-    type Error = ParseError;
-
-    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
-        let mut rest = cons.expect_cons_with_symbol_head("effects")?;
-        let mut font = None;
-        let mut justify = None;
-        let mut hide = false;
-
-        while !rest.is_null() {
-            let cons = rest.expect_cons()?;
-            let element = cons.car();
-            rest = cons.cdr();
-
-            if let Some(e_cons) = element.as_cons() {
-                let (key, _) = e_cons.expect_cons_with_any_symbol_head()?;
-
-                match key {
-                    "font" => font = Some(Font::try_from(e_cons)?),
-                    "justify" => justify = Some(TextJustify::try_from(e_cons)?),
-                    _ => return Err(ParseError::Unexpected(element.clone())),
-                }
-            } else if let Some(sym) = element.as_symbol() {
-                if sym == "hide" {
-                    hide = true;
-                } else {
-                    return Err(ParseError::Unexpected(element.clone()));
-                }
-            }
-        }
-
-        Ok(Self {
-            font,
-            justify,
-            hide,
-        })
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::parse_str, kanga_sexpr::ToSexpr};
+
+    #[test]
+    fn test_text_effects() {
+        let effects: TextEffects = parse_str("(effects hide (font (size 1.27 1.27)))").unwrap();
+        assert!(effects.hide);
+        assert!(effects.font.is_some() && effects.justify.is_none());
+        assert_eq!(effects.to_value().to_string(), "(effects (font (size 1.27 1.27)) hide)");
+
+        // KiCad 8 and later write the flag as a list.
+        assert!(parse_str::<TextEffects>("(effects (justify left) (hide yes))").unwrap().hide);
+        assert!(!parse_str::<TextEffects>("(effects (hide no))").unwrap().hide);
+        assert!(parse_str::<TextEffects>("(effects (hide maybe))").is_err());
     }
 }
-
-impl_try_from_cons_value!(TextEffects);
@@ -1,28 +1,60 @@
 use {
-    crate::{impl_try_from_cons_value, LexprExt, ParseError},
-    lexpr::Cons,
-    serde::{Deserialize, Serialize},
+    super::{sexpr_list, Angle},
+    crate::{impl_sexpr_leaf, impl_try_from_cons_value, LexprExt, ParseError},
+    lexpr::{Cons, Value},
 };
 
 /// KiCad text justification definition.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
 pub struct TextJustify {
     /// Horizontal justification.
-    #[serde(skip_serializing_if = "HorizJustify::is_default")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "HorizJustify::is_default"))]
     pub horiz_justify: HorizJustify,
 
     /// Vertical justification.
-    #[serde(skip_serializing_if = "VertJustify::is_default")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "VertJustify::is_default"))]
     pub vert_justify: VertJustify,
 
     /// Whether the text is mirrored.
-    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
     pub mirror: bool,
 }
 
+/// The orientation of the object (e.g. a placed symbol) that owns a piece of text.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ParentOrientation {
+    /// The rotation of the parent.
+    pub angle: Angle,
+
+    /// Whether the parent is mirrored about the X axis (flipped vertically).
+    pub mirror_x: bool,
+
+    /// Whether the parent is mirrored about the Y axis (flipped horizontally).
+    pub mirror_y: bool,
+}
+
+/// How text is actually drawn once mirroring and parent transforms are taken into account.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EffectiveJustification {
+    /// Horizontal justification, relative to the text's reading direction.
+    pub horiz_justify: HorizJustify,
+
+    /// Vertical justification, relative to the text's reading direction.
+    pub vert_justify: VertJustify,
+
+    /// The angle at which the text is drawn. This is always in `[0, 90]` or `(270, 360)` so that the
+    /// text reads left-to-right or bottom-to-top.
+    pub angle: Angle,
+}
+
 /// Horizontal justification.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
-#[serde(rename_all = "lowercase")]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum HorizJustify {
     /// Left justified.
     Left,
@@ -36,8 +68,10 @@ pub enum HorizJustify {
 }
 
 /// Vertical justification.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
-#[serde(rename_all = "lowercase")]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum VertJustify {
     /// Top justified.
     Top,
@@ -50,12 +84,75 @@ pub enum VertJustify {
     Bottom,
 }
 
+impl TextJustify {
+    /// Return how text with this justification is drawn at `text_angle` when owned by an object with
+    /// the given orientation.
+    ///
+    /// KiCad flips justification in three cases, all handled here:
+    /// * Mirrored text (`(justify mirror)`) swaps left and right.
+    /// * Mirroring the parent swaps the justification along the mirrored screen axis, which is the
+    ///   horizontal justification for horizontal text and the vertical justification for vertical text.
+    ///   Glyphs are never drawn mirrored, so this does not change the text angle. Text at other angles
+    ///   is treated as horizontal or vertical according to the nearest axis.
+    /// * Text that would be drawn upside down (between 90° and 270°, exclusive of 90°) is turned by
+    ///   180° to stay readable, swapping both justifications.
+    pub fn effective_justification(&self, text_angle: Angle, parent: ParentOrientation) -> EffectiveJustification {
+        let mut horiz_justify = self.horiz_justify;
+        let mut vert_justify = self.vert_justify;
+        let mut angle = text_angle + parent.angle;
+
+        if self.mirror {
+            horiz_justify = horiz_justify.flipped();
+        }
+
+        let (sin, cos) = angle.radians().sin_cos();
+        let is_horizontal = cos.abs() >= sin.abs();
+
+        if parent.mirror_y {
+            if is_horizontal {
+                horiz_justify = horiz_justify.flipped();
+            } else {
+                vert_justify = vert_justify.flipped();
+            }
+        }
+
+        if parent.mirror_x {
+            if is_horizontal {
+                vert_justify = vert_justify.flipped();
+            } else {
+                horiz_justify = horiz_justify.flipped();
+            }
+        }
+
+        if angle.degrees() > 90.0 && angle.degrees() <= 270.0 {
+            angle = angle - Angle::from_degrees(180.0);
+            horiz_justify = horiz_justify.flipped();
+            vert_justify = vert_justify.flipped();
+        }
+
+        EffectiveJustification {
+            horiz_justify,
+            vert_justify,
+            angle,
+        }
+    }
+}
+
 impl HorizJustify {
     /// Indicates whether this is the default justification.
     #[inline(always)]
     pub fn is_default(&self) -> bool {
         matches!(self, HorizJustify::Center)
     }
+
+    /// Return the justification with left and right swapped.
+    pub fn flipped(self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Center => Self::Center,
+            Self::Right => Self::Left,
+        }
+    }
 }
 
 impl VertJustify {
@@ -64,6 +161,15 @@ impl VertJustify {
     pub fn is_default(&self) -> bool {
         matches!(self, VertJustify::Center)
     }
+
+    /// Return the justification with top and bottom swapped.
+    pub fn flipped(self) -> Self {
+        match self {
+            Self::Top => Self::Bottom,
+            Self::Center => Self::Center,
+            Self::Bottom => Self::Top,
+        }
+    }
 }
 
 impl TryFrom<&Cons> for TextJustify {
@@ -103,3 +209,60 @@ impl TryFrom<&Cons> for TextJustify {
 }
 
 impl_try_from_cons_value!(TextJustify);
+impl_sexpr_leaf!(TextJustify, "justify");
+
+impl From<&TextJustify> for Value {
+    fn from(justify: &TextJustify) -> Self {
+        let horiz = match justify.horiz_justify {
+            HorizJustify::Left => Some("left"),
+            HorizJustify::Center => None,
+            HorizJustify::Right => Some("right"),
+        };
+        let vert = match justify.vert_justify {
+            VertJustify::Top => Some("top"),
+            VertJustify::Center => None,
+            VertJustify::Bottom => Some("bottom"),
+        };
+        let mirror = justify.mirror.then_some("mirror");
+        sexpr_list("justify", [horiz, vert, mirror].into_iter().flatten().map(Value::symbol))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effective_justification() {
+        let justify = TextJustify {
+            horiz_justify: HorizJustify::Left,
+            vert_justify: VertJustify::Top,
+            mirror: false,
+        };
+
+        let plain = justify.effective_justification(Angle::ZERO, ParentOrientation::default());
+        assert_eq!((plain.horiz_justify, plain.vert_justify), (HorizJustify::Left, VertJustify::Top));
+
+        let mirrored = ParentOrientation {
+            mirror_y: true,
+            ..Default::default()
+        };
+        let effective = justify.effective_justification(Angle::ZERO, mirrored);
+        assert_eq!((effective.horiz_justify, effective.vert_justify), (HorizJustify::Right, VertJustify::Top));
+        assert_eq!(effective.angle, Angle::ZERO);
+
+        // Vertical text in a symbol mirrored about the Y axis keeps its reading direction.
+        let effective = justify.effective_justification(Angle::from_degrees(90.0), mirrored);
+        assert_eq!((effective.horiz_justify, effective.vert_justify), (HorizJustify::Left, VertJustify::Bottom));
+        assert_eq!(effective.angle, Angle::from_degrees(90.0));
+
+        // Upside-down text is turned to stay readable.
+        let rotated = ParentOrientation {
+            angle: Angle::from_degrees(180.0),
+            ..Default::default()
+        };
+        let effective = justify.effective_justification(Angle::ZERO, rotated);
+        assert_eq!((effective.horiz_justify, effective.vert_justify), (HorizJustify::Right, VertJustify::Bottom));
+        assert_eq!(effective.angle, Angle::ZERO);
+    }
+}
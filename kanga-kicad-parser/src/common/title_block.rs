@@ -1,85 +1,85 @@
 use {
-    crate::{impl_try_from_cons_value, LexprExt, ParseError},
-    lexpr::Cons,
-    serde::{Deserialize, Serialize},
-    std::collections::BTreeMap,
+    crate::{lazy::FromRaw, ParseError},
+    kanga_sexpr::sexpr,
+    lexpr::Value,
 };
 
-/// KiCad title block.
-///
-/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_title_block)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "title_block", rename_all = "snake_case")]
-pub struct TitleBlock {
-    /// Title
-    #[serde(default, skip_serializing_if = "String::is_empty")]
-    pub title: String,
+sexpr! {
+    /// KiCad title block. The elements may appear in any order.
+    ///
+    /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_title_block)
+    #[sexpr(unordered, error = crate::ParseError)]
+    #[derive(Clone, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+    #[cfg_attr(feature = "serde", serde(rename = "title_block", rename_all = "snake_case", deny_unknown_fields))]
+    pub struct TitleBlock {
+        (title_block
+            /// Title
+            #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "String::is_empty"))]
+            [(title: String = String::new())]
 
-    /// Date. Despite the KiCad documentation, this does not have to be a YYYY-MM-DD date (i.e.,
-    /// KiCad does not validate the user input here).
-    #[serde(default, skip_serializing_if = "String::is_empty")]
-    pub date: String,
+            /// Date. Despite the KiCad documentation, this does not have to be a YYYY-MM-DD date (i.e.,
+            /// KiCad does not validate the user input here).
+            #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "String::is_empty"))]
+            [(date: String = String::new())]
 
-    /// Revision
-    #[serde(default, skip_serializing_if = "String::is_empty")]
-    pub rev: String,
+            /// Revision
+            #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "String::is_empty"))]
+            [(rev: String = String::new())]
 
-    /// Company name
-    #[serde(default, skip_serializing_if = "String::is_empty")]
-    pub company: String,
+            /// Company name
+            #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "String::is_empty"))]
+            [(company: String = String::new())]
 
-    /// Comments   
-    #[serde(default)]
-    pub comments: BTreeMap<i64, String>,
+            /// Comments, in the order they are written.
+            #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+            (comment => comments: TitleBlockComment)*
+        )
+    }
 }
 
-impl TryFrom<&Cons> for TitleBlock {
-    type Error = ParseError;
-
-    fn try_from(cons: &Cons) -> Result<Self, ParseError> {
-        let mut title = String::new();
-        let mut date = String::new();
-        let mut rev = String::new();
-        let mut company = String::new();
-        let mut comments = BTreeMap::new();
+sexpr! {
+    /// A numbered comment in a title block.
+    #[sexpr(error = crate::ParseError)]
+    #[derive(Clone, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+    #[cfg_attr(feature = "serde", serde(rename = "comment", deny_unknown_fields))]
+    pub struct TitleBlockComment {
+        (comment
+            /// The comment number, from 1 to 9.
+            number: i64
 
-        let mut rest = cons.expect_cons_with_symbol_head("title_block")?;
+            /// The comment text.
+            text: String
+        )
+    }
+}
 
-        while !rest.is_null() {
-            let cons = rest.expect_cons()?;
-            let element = cons.car();
-            rest = cons.cdr();
-            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+impl FromRaw for TitleBlock {
+    fn from_raw(raw: &Value) -> Result<Self, ParseError> {
+        Self::try_from(raw)
+    }
+}
 
-            if key == "comment" {
-                let (comment_id, cdr) = cdr.expect_cons_with_any_int_head()?;
-                let (comment, cdr) = cdr.expect_cons_with_any_str_head()?;
-                cdr.expect_null()?;
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::parse_str};
 
-                comments.insert(comment_id, comment.to_string());
-            } else {
-                let (value, cdr) = cdr.expect_cons_with_any_str_head()?;
-                cdr.expect_null()?;
-                let value = value.to_string();
+    #[test]
+    fn test_title_block() {
+        let title_block: TitleBlock = parse_str(
+            r#"(title_block (comment 2 "Second") (title "Power") (date "Q3") (comment 1 "First") (rev "B"))"#,
+        )
+        .unwrap();
+        assert_eq!((title_block.title.as_str(), title_block.date.as_str()), ("Power", "Q3"));
+        assert_eq!((title_block.rev.as_str(), title_block.company.as_str()), ("B", ""));
 
-                match key {
-                    "title" => title = value,
-                    "date" => date = value,
-                    "rev" => rev = value,
-                    "company" => company = value,
-                    _ => (),
-                }
-            }
-        }
+        let comments: Vec<_> = title_block.comments.iter().map(|c| (c.number, c.text.as_str())).collect();
+        assert_eq!(comments, [(2, "Second"), (1, "First")]);
 
-        Ok(Self {
-            title,
-            date,
-            rev,
-            company,
-            comments,
-        })
+        assert!(parse_str::<TitleBlock>(r#"(title_block (title "A") (title "B"))"#).is_err());
+        assert!(parse_str::<TitleBlock>(r#"(title_block (comment "First"))"#).is_err());
     }
 }
-
-impl_try_from_cons_value!(TitleBlock);
@@ -0,0 +1,213 @@
+use {
+    super::{mm_to_nm, nm_to_mm_value, Decimal},
+    kanga_sexpr::units::Unit,
+    lexpr::Value,
+    std::{
+        fmt::{Display, Formatter, Result as FmtResult},
+        iter::Sum,
+        ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
+    },
+};
+
+/// A length or coordinate in nanometers, the unit KiCad uses internally.
+///
+/// Files and serialized documents give lengths in millimeters; an `Nm` is read from and written as
+/// a millimeter value. Arithmetic between lengths stays in nanometers, so that a length cannot be
+/// added to a millimeter value by mistake.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(from = "Mm", into = "Mm"))]
+pub struct Nm(pub i64);
+
+/// A length or coordinate in millimeters, as KiCad writes it in files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Mm(pub f64);
+
+impl Nm {
+    /// A length of zero.
+    pub const ZERO: Self = Self(0);
+
+    /// Convert a millimeter value, exactly when it has at most six decimal places and rounded
+    /// otherwise.
+    pub fn from_mm(mm: f64) -> Self {
+        Self(mm_to_nm(mm))
+    }
+
+    /// Return the length in millimeters.
+    pub fn to_mm(self) -> f64 {
+        self.0 as f64 / 1e6
+    }
+
+    /// Return the magnitude of the length.
+    pub fn abs(self) -> Self {
+        Self(self.0.abs())
+    }
+}
+
+impl Add for Nm {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Nm {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for Nm {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign for Nm {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Neg for Nm {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+impl Mul<i64> for Nm {
+    type Output = Self;
+
+    fn mul(self, rhs: i64) -> Self {
+        Self(self.0 * rhs)
+    }
+}
+
+impl Div<i64> for Nm {
+    type Output = Self;
+
+    fn div(self, rhs: i64) -> Self {
+        Self(self.0 / rhs)
+    }
+}
+
+impl Sum for Nm {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self(iter.map(|nm| nm.0).sum())
+    }
+}
+
+impl Display for Nm {
+    /// Write the length in millimeters, as KiCad writes it in files.
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", Decimal::from_nm(self.0))
+    }
+}
+
+impl From<i64> for Nm {
+    fn from(nm: i64) -> Self {
+        Self(nm)
+    }
+}
+
+impl From<Nm> for i64 {
+    fn from(nm: Nm) -> Self {
+        nm.0
+    }
+}
+
+/// Lengths declared with `as Nm` are read from the text of the number, so that they convert
+/// exactly, and written in millimeters.
+impl Unit for Nm {
+    type Value = Nm;
+
+    fn from_sexpr(value: &Value) -> Option<Nm> {
+        Decimal::try_from(value).ok().map(Decimal::to_length)
+    }
+
+    fn to_sexpr(value: &Nm) -> Value {
+        nm_to_mm_value(*value)
+    }
+}
+
+impl From<Mm> for Nm {
+    fn from(mm: Mm) -> Self {
+        Self::from_mm(mm.0)
+    }
+}
+
+impl Add for Mm {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Mm {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl Neg for Mm {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+impl Mul<f64> for Mm {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self {
+        Self(self.0 * rhs)
+    }
+}
+
+impl Div<f64> for Mm {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Self {
+        Self(self.0 / rhs)
+    }
+}
+
+impl Display for Mm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<Nm> for Mm {
+    fn from(nm: Nm) -> Self {
+        Self(nm.to_mm())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_units() {
+        assert_eq!(Nm::from_mm(1.27), Nm(1_270_000));
+        assert_eq!(Nm::from(Mm(0.1) + Mm(0.2)), Nm(300_000));
+        assert_eq!(Mm::from(Nm(2_540_000)), Mm(2.54));
+        assert_eq!(Nm(1_000) * 3 - Nm(500), Nm(2_500));
+        assert_eq!(-Nm(5), Nm(-5));
+        assert_eq!([Nm(1), Nm(2)].into_iter().sum::<Nm>(), Nm(3));
+        assert_eq!(Nm(-1_270_000).to_string(), "-1.27");
+    }
+}
@@ -0,0 +1,301 @@
+use {
+    crate::{pcb::Pcb, sch::Schematic, span::parse_str, symlib::SymbolLibrary, Error},
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::BTreeMap,
+        fmt::Write as _,
+        fs::read_to_string,
+        path::{Path, PathBuf},
+        time::{Duration, Instant},
+    },
+};
+
+/// The kinds of documents in a compatibility corpus, identified by file extension.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentKind {
+    /// A `.kicad_sch` schematic.
+    Schematic,
+
+    /// A `.kicad_pcb` board.
+    Board,
+
+    /// A `.kicad_sym` symbol library.
+    SymbolLibrary,
+}
+
+/// A KiCad parser run over a compatibility corpus.
+///
+/// The `corpus` bench implements this for the parsers it compares this crate against; adapters for
+/// parsers this crate does not depend on belong there, behind dev-dependencies.
+pub trait CorpusParser {
+    /// Return the name the parser is reported under.
+    fn name(&self) -> &str;
+
+    /// Parse a document, returning a description of the failure if it cannot be read.
+    fn parse(&self, kind: DocumentKind, text: &str) -> Result<(), String>;
+}
+
+/// This crate's parser, converting each document into its typed model.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KangaParser;
+
+/// A plain s-expression reader: lexpr with its default options, as used by parsers built directly
+/// on it. It only checks the syntax of each document.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LexprParser;
+
+/// The outcome of one parser on one document.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CorpusEntry {
+    /// The parser's name.
+    pub parser: String,
+
+    /// The document parsed.
+    pub path: PathBuf,
+
+    /// The kind of document.
+    pub kind: DocumentKind,
+
+    /// The error, if the parser failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    /// The fastest of the timed parses.
+    pub time: Duration,
+}
+
+/// The results of running parsers over a corpus.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct CompatReport {
+    /// One entry per parser and document, in corpus order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entries: Vec<CorpusEntry>,
+}
+
+/// Per-parser, per-kind totals in a [`CompatReport`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CompatSummary {
+    /// The number of documents attempted.
+    pub attempted: usize,
+
+    /// The number of documents parsed without error.
+    pub succeeded: usize,
+
+    /// The total time spent on the documents that parsed.
+    pub time: Duration,
+
+    /// The total size of the documents that parsed, in bytes.
+    pub bytes: usize,
+}
+
+impl DocumentKind {
+    /// Return the kind of a file from its extension, or `None` if it is not a corpus document.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "kicad_sch" => Some(Self::Schematic),
+            "kicad_pcb" => Some(Self::Board),
+            "kicad_sym" => Some(Self::SymbolLibrary),
+            _ => None,
+        }
+    }
+}
+
+impl CorpusParser for KangaParser {
+    fn name(&self) -> &str {
+        "kanga-kicad-parser"
+    }
+
+    fn parse(&self, kind: DocumentKind, text: &str) -> Result<(), String> {
+        let converted = match kind {
+            DocumentKind::Schematic => parse_str::<Schematic>(text).map(drop),
+            DocumentKind::Board => parse_str::<Pcb>(text).map(drop),
+            DocumentKind::SymbolLibrary => parse_str::<SymbolLibrary>(text).map(drop),
+        };
+        converted.map_err(|e| e.to_string())
+    }
+}
+
+impl CorpusParser for LexprParser {
+    fn name(&self) -> &str {
+        "lexpr"
+    }
+
+    fn parse(&self, _kind: DocumentKind, text: &str) -> Result<(), String> {
+        lexpr::from_str(text).map(drop).map_err(|e| e.to_string())
+    }
+}
+
+impl CompatSummary {
+    /// Return the fraction of the attempted documents that parsed, or 0 if none were attempted.
+    pub fn success_rate(&self) -> f64 {
+        if self.attempted == 0 {
+            0.0
+        } else {
+            self.succeeded as f64 / self.attempted as f64
+        }
+    }
+}
+
+impl CompatReport {
+    /// Return the totals for each parser and kind of document.
+    pub fn summary(&self) -> BTreeMap<(&str, DocumentKind), CompatSummary> {
+        let mut summary: BTreeMap<_, CompatSummary> = BTreeMap::new();
+
+        for entry in &self.entries {
+            let totals = summary.entry((entry.parser.as_str(), entry.kind)).or_default();
+            totals.attempted += 1;
+            if entry.error.is_none() {
+                totals.succeeded += 1;
+                totals.time += entry.time;
+                totals.bytes += std::fs::metadata(&entry.path).map(|m| m.len() as usize).unwrap_or_default();
+            }
+        }
+
+        summary
+    }
+
+    /// Return the documents a parser failed on, with their errors.
+    pub fn failures<'a>(&'a self, parser: &'a str) -> impl Iterator<Item = (&'a Path, &'a str)> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.parser == parser)
+            .filter_map(|entry| Some((entry.path.as_path(), entry.error.as_deref()?)))
+    }
+
+    /// Render the summary and the failures as Markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::from(
+            "| Parser | Kind | Parsed | Success | Time | Throughput |\n| --- | --- | --- | --- | --- | --- |\n",
+        );
+
+        for ((parser, kind), totals) in self.summary() {
+            let seconds = totals.time.as_secs_f64();
+            let throughput = if seconds > 0.0 {
+                format!("{:.1} MB/s", totals.bytes as f64 / seconds / 1e6)
+            } else {
+                "-".to_string()
+            };
+            let _ = writeln!(
+                md,
+                "| {parser} | {kind:?} | {}/{} | {:.0}% | {:.3} ms | {throughput} |",
+                totals.succeeded,
+                totals.attempted,
+                totals.success_rate() * 100.0,
+                seconds * 1e3
+            );
+        }
+
+        let mut parsers: Vec<&str> = Vec::new();
+        for entry in &self.entries {
+            if !parsers.contains(&entry.parser.as_str()) {
+                parsers.push(&entry.parser);
+            }
+        }
+
+        for parser in parsers {
+            let mut failures = self.failures(parser).peekable();
+            if failures.peek().is_some() {
+                let _ = writeln!(md, "\n### {parser} failures\n");
+                for (path, error) in failures {
+                    let _ = writeln!(md, "- `{}`: {}", path.display(), error.replace('\n', " "));
+                }
+            }
+        }
+
+        md
+    }
+}
+
+/// Collect the corpus documents under a directory, recursively, in path order.
+pub fn find_corpus(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut found = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = std::fs::read_dir(&dir)?;
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if DocumentKind::from_path(&path).is_some() {
+                found.push(path);
+            }
+        }
+    }
+
+    found.sort();
+    Ok(found)
+}
+
+/// Run each parser over each corpus document, timing the fastest of `iterations` parses. Files
+/// that are not corpus documents are skipped; a document that cannot be read is an error.
+///
+/// lexpr frees lists recursively, so reading a large board needs more stack than the 2 MiB a
+/// spawned thread gets by default.
+pub fn run_corpus(paths: &[PathBuf], parsers: &[&dyn CorpusParser], iterations: u32) -> Result<CompatReport, Error> {
+    let mut report = CompatReport::default();
+
+    for path in paths {
+        let Some(kind) = DocumentKind::from_path(path) else {
+            continue;
+        };
+        let text = read_to_string(path)?;
+
+        for parser in parsers {
+            let mut time = Duration::MAX;
+            let mut error = None;
+
+            for _ in 0..iterations.max(1) {
+                let start = Instant::now();
+                let result = parser.parse(kind, &text);
+                time = time.min(start.elapsed());
+
+                if let Err(e) = result {
+                    error = Some(e);
+                    break;
+                }
+            }
+
+            report.entries.push(CorpusEntry {
+                parser: parser.name().to_string(),
+                path: path.clone(),
+                kind,
+                error,
+                time,
+            });
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_corpus() {
+        let corpus = find_corpus(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").as_path()).unwrap();
+        assert!(corpus.iter().any(|path| path.ends_with("ports.kicad_sch")));
+
+        let report = std::thread::scope(|scope| {
+            let run = || run_corpus(&corpus, &[&KangaParser, &LexprParser], 1).unwrap();
+            std::thread::Builder::new().stack_size(64 << 20).spawn_scoped(scope, run).unwrap().join().unwrap()
+        });
+        assert_eq!(report.entries.len(), corpus.len() * 2);
+
+        let summary = report.summary();
+        let schematics = summary[&("kanga-kicad-parser", DocumentKind::Schematic)];
+        assert_eq!(schematics.attempted, 3);
+        assert_eq!(summary[&("lexpr", DocumentKind::Schematic)].succeeded, 2);
+        assert!((summary[&("lexpr", DocumentKind::Schematic)].success_rate() - 2.0 / 3.0).abs() < 1e-9);
+
+        // Failures point at the offending element.
+        assert!(report.failures("kanga-kicad-parser").all(|(_, error)| error.starts_with("line ")));
+
+        // lexpr cannot read KiCad 9's unquoted UUIDs; only this crate's parser rewrites them.
+        assert!(report.failures("lexpr").any(|(path, _)| path.ends_with("KiCadSchemaSample.kicad_sch")));
+        assert!(report.to_markdown().contains("| lexpr | Schematic | 2/3 | 67% |"));
+    }
+}
@@ -0,0 +1,285 @@
+use {
+    crate::{cleanup::CleanupOptions, version::FormatVersion},
+    std::{collections::BTreeSet, num::NonZeroUsize, thread::available_parallelism},
+};
+
+#[cfg(feature = "tools")]
+use crate::context::OutputContext;
+
+#[cfg(feature = "svg")]
+use crate::svg::SvgOptions;
+
+/// Top-level elements that are always converted, regardless of [`ParseOptions::sections`].
+pub const HEADER_SECTIONS: &[&str] = &["version", "generator", "generator_version", "uuid"];
+
+/// Global options shared by the parsing, analysis, and export entry points.
+///
+/// Construct one with [`Config::builder`], or use [`Config::default`] for KiCad's own behavior.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Config {
+    /// Options controlling how documents are parsed.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub parse: ParseOptions,
+
+    /// The distance in nanometers within which two coordinates are considered equal by geometric
+    /// comparisons, such as finding duplicate elements and joining wire ends during cleanup.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tolerance: u64,
+
+    /// Options controlling how schematics are snapped to the grid by
+    /// [`cleanup`](crate::cleanup::cleanup).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cleanup: CleanupOptions,
+
+    /// The colors used when drawing documents.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub theme: Theme,
+
+    /// Options controlling how documents are drawn as SVG images.
+    #[cfg(feature = "svg")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub svg: SvgOptions,
+
+    /// The maximum number of threads to use for operations that can run in parallel. If `None`,
+    /// the available parallelism of the host is used.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub parallelism: Option<NonZeroUsize>,
+
+    /// If set, generated UUIDs are derived from this seed and timestamps are fixed, so outputs
+    /// are byte-reproducible. See [`OutputContext::deterministic`].
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub deterministic_seed: Option<u64>,
+}
+
+/// Options controlling how documents are parsed.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ParseOptions {
+    /// Whether deviations from the file format are errors. This is the default.
+    #[cfg_attr(feature = "serde", serde(default = "default_strict"))]
+    pub strict: bool,
+
+    /// The top-level sections to convert into typed models, by element name (e.g. `lib_symbols`).
+    /// Other sections are kept as raw values for later conversion. If `None`, every section is
+    /// converted. The [`HEADER_SECTIONS`] are always converted.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub sections: Option<BTreeSet<String>>,
+
+    /// Whether to record where in the source each element came from. See
+    /// [`read_document`](crate::provenance::read_document).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub track_provenance: bool,
+
+    /// The newest KiCad release whose documents are accepted. With [`strict`](Self::strict) set,
+    /// documents written by a newer release are rejected even if this crate can read them. If
+    /// `None`, every release this crate supports is accepted.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub version_profile: Option<FormatVersion>,
+}
+
+/// The colors used when drawing documents, as CSS colors.
+///
+/// The defaults follow KiCad's default schematic theme.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Theme {
+    /// The color of symbol outlines, pins, sheet frames, and text without a color of their own.
+    pub color: String,
+
+    /// The color of symbol bodies filled with the background fill.
+    pub background_color: String,
+
+    /// The color of wires and junctions.
+    pub wire_color: String,
+
+    /// The color of buses.
+    pub bus_color: String,
+
+    /// The color of no-connect flags.
+    pub no_connect_color: String,
+}
+
+/// Builder for [`Config`].
+#[derive(Clone, Debug, Default)]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl Config {
+    /// Create a builder starting from the default configuration.
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+
+    /// Return the number of threads to use for parallel operations.
+    pub fn threads(&self) -> usize {
+        self.parallelism.or_else(|| available_parallelism().ok()).map(NonZeroUsize::get).unwrap_or(1)
+    }
+
+    /// Create the context used to generate outputs under this configuration.
+    #[cfg(feature = "tools")]
+    pub fn output_context(&self) -> OutputContext {
+        self.deterministic_seed.map(OutputContext::deterministic).unwrap_or_default()
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            strict: default_strict(),
+            sections: None,
+            track_provenance: false,
+            version_profile: None,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            color: "#840000".to_string(),
+            background_color: "#ffffc2".to_string(),
+            wire_color: "#008400".to_string(),
+            bus_color: "#000084".to_string(),
+            no_connect_color: "#0000ff".to_string(),
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Set whether deviations from the file format are errors. See [`Schematic::parse_with_warnings`]
+    /// for how a lenient parse reports them.
+    ///
+    /// [`Schematic::parse_with_warnings`]: crate::sch::Schematic::parse_with_warnings
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Convert only the given top-level sections, plus the [`HEADER_SECTIONS`].
+    pub fn sections<S>(mut self, sections: &[S]) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.sections = Some(sections.iter().map(|section| section.as_ref().to_string()).collect());
+        self
+    }
+
+    /// Set whether to record where in the source each element came from.
+    pub fn track_provenance(mut self, track: bool) -> Self {
+        self.track_provenance = track;
+        self
+    }
+
+    /// Accept documents written by releases up to and including `version` only.
+    pub fn version_profile(mut self, version: FormatVersion) -> Self {
+        self.version_profile = Some(version);
+        self
+    }
+
+    /// Indicates whether the top-level section with the given element name should be converted.
+    pub fn includes_section(&self, name: &str) -> bool {
+        match &self.sections {
+            Some(sections) => sections.contains(name) || HEADER_SECTIONS.contains(&name),
+            None => true,
+        }
+    }
+}
+
+impl ConfigBuilder {
+    /// Set whether deviations from the file format are errors.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.config.parse.strict = strict;
+        self
+    }
+
+    /// Convert only the given top-level sections when parsing.
+    pub fn sections<S>(mut self, sections: &[S]) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.config.parse = self.config.parse.sections(sections);
+        self
+    }
+
+    /// Record where in the source each element came from when parsing.
+    pub fn track_provenance(mut self, track: bool) -> Self {
+        self.config.parse.track_provenance = track;
+        self
+    }
+
+    /// Accept documents written by releases up to and including `version` only.
+    pub fn version_profile(mut self, version: FormatVersion) -> Self {
+        self.config.parse.version_profile = Some(version);
+        self
+    }
+
+    /// Replace all parsing options.
+    pub fn parse_options(mut self, parse: ParseOptions) -> Self {
+        self.config.parse = parse;
+        self
+    }
+
+    /// Set the geometric comparison tolerance in nanometers.
+    pub fn tolerance(mut self, tolerance: u64) -> Self {
+        self.config.tolerance = tolerance;
+        self
+    }
+
+    /// Set the options used to snap schematics to the grid.
+    pub fn cleanup_options(mut self, cleanup: CleanupOptions) -> Self {
+        self.config.cleanup = cleanup;
+        self
+    }
+
+    /// Set the colors used when drawing documents.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.config.theme = theme;
+        self
+    }
+
+    /// Set the options used to draw SVG images.
+    #[cfg(feature = "svg")]
+    pub fn svg_options(mut self, svg: SvgOptions) -> Self {
+        self.config.svg = svg;
+        self
+    }
+
+    /// Set the maximum number of threads to use. Zero selects the host's available parallelism.
+    pub fn parallelism(mut self, threads: usize) -> Self {
+        self.config.parallelism = NonZeroUsize::new(threads);
+        self
+    }
+
+    /// Make generated outputs byte-reproducible, deriving UUIDs from `seed`.
+    pub fn deterministic(mut self, seed: u64) -> Self {
+        self.config.deterministic_seed = Some(seed);
+        self
+    }
+
+    /// Finish building the configuration.
+    pub fn build(self) -> Config {
+        self.config
+    }
+}
+
+#[inline(always)]
+fn default_strict() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sections() {
+        let config = Config::builder().sections(&["lib_symbols", "title_block"]).build();
+        assert!(config.parse.includes_section("lib_symbols"));
+        assert!(config.parse.includes_section("version"));
+        assert!(!config.parse.includes_section("wire"));
+        assert!(ParseOptions::default().includes_section("wire"));
+    }
+}
@@ -0,0 +1,96 @@
+use {
+    std::time::{SystemTime, UNIX_EPOCH},
+    uuid::{Builder, Uuid},
+};
+
+/// The sources of nondeterminism used when generating documents and reports: new UUIDs and the
+/// current time.
+///
+/// By default UUIDs are random and the time is read from the system clock. A deterministic
+/// context instead draws UUIDs from a seeded generator and reports a fixed time, so the same
+/// inputs always produce byte-identical outputs, e.g. for reproducible builds in CI. Outputs are
+/// always written in document or sorted order, so no other state is needed.
+#[derive(Clone, Debug, Default)]
+pub struct OutputContext {
+    seed: Option<u64>,
+    timestamp: Option<SystemTime>,
+}
+
+impl OutputContext {
+    /// Create a context that generates random UUIDs and reads the system clock.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a context that generates UUIDs from `seed` and reports the Unix epoch as the
+    /// current time.
+    pub fn deterministic(seed: u64) -> Self {
+        Self {
+            seed: Some(seed),
+            timestamp: Some(UNIX_EPOCH),
+        }
+    }
+
+    /// Report `timestamp` as the current time.
+    pub fn with_timestamp(mut self, timestamp: SystemTime) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Indicates whether UUIDs are generated from a seed.
+    pub fn is_deterministic(&self) -> bool {
+        self.seed.is_some()
+    }
+
+    /// Generate a version 4 UUID.
+    pub fn new_uuid(&mut self) -> Uuid {
+        let Some(state) = &mut self.seed else {
+            return Uuid::new_v4();
+        };
+
+        let mut bytes = [0; 16];
+        for chunk in bytes.chunks_mut(8) {
+            chunk.copy_from_slice(&splitmix64(state).to_le_bytes());
+        }
+        Builder::from_random_bytes(bytes).into_uuid()
+    }
+
+    /// Return the current time, or the injected timestamp.
+    pub fn timestamp(&self) -> SystemTime {
+        self.timestamp.unwrap_or_else(SystemTime::now)
+    }
+
+    /// Return the current time as whole seconds since the Unix epoch.
+    pub fn unix_timestamp(&self) -> u64 {
+        self.timestamp().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+    }
+}
+
+/// Advance a SplitMix64 generator and return its next output.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::time::Duration};
+
+    #[test]
+    fn test_deterministic() {
+        let mut a = OutputContext::deterministic(7);
+        let mut b = OutputContext::deterministic(7);
+        let first = a.new_uuid();
+        assert_eq!(first, b.new_uuid());
+        assert_ne!(first, a.new_uuid());
+        assert_eq!(first.get_version_num(), 4);
+        assert_ne!(first, OutputContext::deterministic(8).new_uuid());
+
+        let context = OutputContext::deterministic(7).with_timestamp(UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+        assert_eq!(context.unix_timestamp(), 1_700_000_000);
+        assert!(!OutputContext::new().is_deterministic());
+    }
+}
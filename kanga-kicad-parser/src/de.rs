@@ -0,0 +1,585 @@
+use {
+    crate::{span::read_tracked, Error, LexprExt, ParseError},
+    lexpr::Value,
+    serde::de::{
+        self, value::BorrowedStrDeserializer, Deserialize, DeserializeOwned, DeserializeSeed, Deserializer as _,
+        EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor,
+    },
+    std::{cell::Cell, fmt::Display, vec::IntoIter},
+};
+
+/// Reads KiCad s-expressions into any type implementing [`Deserialize`].
+///
+/// The model's serde names follow the KiCad file format, so the same derives that write JSON
+/// also describe the s-expressions, and types outside this crate can be read from KiCad files
+/// without a hand-written `TryFrom<&Cons>`. Values are mapped as follows:
+///
+/// * A struct is a list whose members are matched to fields: `(key ...)` lists by their head,
+///   bare symbols naming a field as flags, and any other atoms by position, filling the fields
+///   not otherwise given in declaration order. A head symbol naming the list itself is skipped.
+/// * A field whose key appears more than once is a sequence of its occurrences. A field that
+///   appears once is read as a sequence of its arguments, `(layers "F.Cu" "B.Cu")`, unless its
+///   items are structs, in which case it is a sequence of one.
+/// * A number, string, or boolean is read from an atom or from the single argument of a list.
+///   Booleans are `yes`/`no`, a bare flag, or an empty list such as `(hide)`.
+/// * An enum is a symbol naming a unit variant, or a list whose head names the variant.
+#[derive(Debug)]
+pub struct Deserializer<'a> {
+    input: Input<'a>,
+}
+
+/// What a [`Deserializer`] reads.
+#[derive(Debug)]
+enum Input<'a> {
+    /// An atom or list.
+    Value(&'a Value),
+
+    /// Every occurrence of a struct member with the same key.
+    Field(Vec<&'a Value>),
+}
+
+/// The members of a struct, grouped by the field they belong to.
+struct Members<'a> {
+    fields: IntoIter<(&'a str, Vec<&'a Value>)>,
+    pending: Vec<&'a Value>,
+}
+
+/// The items of a sequence.
+struct Items<'a>(IntoIter<&'a Value>);
+
+/// The items of a field that appears once: the field itself if its items are structs, or its
+/// arguments otherwise. The first item decides which.
+struct OneOrArguments<'a> {
+    element: &'a Value,
+    whole: Cell<Option<bool>>,
+    arguments: Option<IntoIter<&'a Value>>,
+}
+
+/// The first item of a [`OneOrArguments`], deciding how the sequence is read.
+struct Probe<'a, 'b> {
+    element: &'a Value,
+    whole: &'b Cell<Option<bool>>,
+}
+
+/// An enum variant and the value it was read from.
+struct Variant<'a> {
+    name: &'a str,
+    content: &'a Value,
+}
+
+/// Read a value from the text of an s-expression, recording where in the text any error occurred.
+pub fn from_str<T>(text: &str) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    let (value, provenance) = read_tracked(text)?;
+    from_value(&value).map_err(|e| e.locate(&value, &provenance, text).into())
+}
+
+/// Read a value from an s-expression. See [`Deserializer`] for how values are mapped.
+pub fn from_value<'a, T>(value: &'a Value) -> Result<T, ParseError>
+where
+    T: Deserialize<'a>,
+{
+    T::deserialize(Deserializer::new(value)).map_err(|e| match value.as_cons() {
+        Some(cons) => e.within_element(cons),
+        None => e,
+    })
+}
+
+impl de::Error for ParseError {
+    fn custom<T: Display>(message: T) -> Self {
+        Self::Deserialize(message.to_string())
+    }
+}
+
+impl<'a> Deserializer<'a> {
+    /// Create a deserializer reading an s-expression.
+    pub fn new(value: &'a Value) -> Self {
+        Self {
+            input: Input::Value(value),
+        }
+    }
+
+    /// Return the value read, taking the last occurrence of a repeated field as KiCad does.
+    fn value(&self) -> &'a Value {
+        match &self.input {
+            Input::Value(value) => value,
+            Input::Field(occurrences) => occurrences[occurrences.len() - 1],
+        }
+    }
+}
+
+impl<'a> de::Deserializer<'a> for Deserializer<'a> {
+    type Error = ParseError;
+
+    fn deserialize_any<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, ParseError> {
+        let value = self.value();
+        match atom(value) {
+            Ok(atom) => visit_atom(atom, visitor),
+            Err(_) if value.is_cons() => self.deserialize_seq(visitor),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, ParseError> {
+        visitor.visit_bool(flag(self.value())?)
+    }
+
+    fn deserialize_str<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, ParseError> {
+        match atom(self.value())? {
+            Value::Number(number) => visitor.visit_string(number.to_string()),
+            Value::Symbol(text) => visitor.visit_borrowed_str(text),
+            atom => visit_atom(atom, visitor),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, ParseError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_identifier<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, ParseError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, ParseError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, ParseError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'a>>(self, _name: &'static str, visitor: V) -> Result<V::Value, ParseError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'a>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ParseError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, ParseError> {
+        match self.input {
+            Input::Field(occurrences) if occurrences.len() > 1 => visitor.visit_seq(Items(occurrences.into_iter())),
+            Input::Field(occurrences) if occurrences[0].is_cons() => visitor.visit_seq(OneOrArguments {
+                element: occurrences[0],
+                whole: Cell::new(None),
+                arguments: None,
+            }),
+            Input::Field(occurrences) => visitor.visit_seq(Items(occurrences.into_iter())),
+            Input::Value(value) if value.is_cons() => {
+                visitor.visit_seq(Items(members(value).collect::<Vec<_>>().into_iter()))
+            }
+            Input::Value(value) => Err(ParseError::ExpectedList(value.clone())),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'a>>(self, _len: usize, visitor: V) -> Result<V::Value, ParseError> {
+        Deserializer::new(self.value()).deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'a>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, ParseError> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, ParseError> {
+        visitor.visit_map(Members::new(self.value(), &[])?)
+    }
+
+    fn deserialize_struct<V: Visitor<'a>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ParseError> {
+        visitor.visit_map(Members::new(self.value(), fields)?)
+    }
+
+    fn deserialize_enum<V: Visitor<'a>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ParseError> {
+        let mut value = self.value();
+
+        // Look through lists that wrap the variant, such as `(type dash)`.
+        let variant = loop {
+            match head(value) {
+                Some(name) if variants.contains(&name) => {
+                    break Variant {
+                        name,
+                        content: value,
+                    }
+                }
+                _ if value.is_cons() => {
+                    let mut arguments = members(value);
+                    match (arguments.next(), arguments.next()) {
+                        (Some(argument), None) => value = argument,
+                        _ => return Err(ParseError::Unexpected(value.clone())),
+                    }
+                }
+                _ => match value.as_symbol().or_else(|| value.as_str()) {
+                    Some(name) => {
+                        break Variant {
+                            name,
+                            content: value,
+                        }
+                    }
+                    None => return Err(ParseError::ExpectedListSymbolHead(value.clone())),
+                },
+            }
+        };
+
+        visitor.visit_enum(variant)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, ParseError> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        <V: Visitor<'a>>
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char bytes byte_buf
+    }
+}
+
+impl<'a> Members<'a> {
+    /// Group the members of a list by field.
+    fn new(value: &'a Value, fields: &'static [&'static str]) -> Result<Self, ParseError> {
+        if !value.is_cons() {
+            return Err(ParseError::ExpectedList(value.clone()));
+        }
+
+        let mut grouped: Vec<(&'a str, Vec<&'a Value>)> = Vec::new();
+        let mut positional = Vec::new();
+
+        for member in members(value) {
+            let key = match member {
+                Value::Cons(cons) => cons.car().as_symbol(),
+                Value::Symbol(symbol) if fields.contains(&symbol.as_ref()) => Some(symbol.as_ref()),
+                _ => None,
+            };
+
+            match key {
+                Some(key) => match grouped.iter_mut().find(|(name, _)| *name == key) {
+                    Some((_, occurrences)) => occurrences.push(member),
+                    None => grouped.push((key, vec![member])),
+                },
+                None => positional.push(member),
+            }
+        }
+
+        let mut free: Vec<&'static str> =
+            fields.iter().copied().filter(|field| !grouped.iter().any(|(name, _)| name == field)).collect();
+        free.reverse();
+
+        for member in positional {
+            match free.pop() {
+                Some(field) => grouped.push((field, vec![member])),
+                None => return Err(ParseError::Unexpected(member.clone())),
+            }
+        }
+
+        Ok(Self {
+            fields: grouped.into_iter(),
+            pending: Vec::new(),
+        })
+    }
+}
+
+impl<'a> MapAccess<'a> for Members<'a> {
+    type Error = ParseError;
+
+    fn next_key_seed<K: DeserializeSeed<'a>>(&mut self, seed: K) -> Result<Option<K::Value>, ParseError> {
+        let Some((key, occurrences)) = self.fields.next() else {
+            return Ok(None);
+        };
+
+        self.pending = occurrences;
+        seed.deserialize(BorrowedStrDeserializer::new(key)).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'a>>(&mut self, seed: V) -> Result<V::Value, ParseError> {
+        let occurrences = std::mem::take(&mut self.pending);
+        let first = occurrences[0];
+
+        seed.deserialize(Deserializer {
+            input: Input::Field(occurrences),
+        })
+        .map_err(|e| match first.as_cons() {
+            Some(cons) => e.within_element(cons),
+            None => e,
+        })
+    }
+}
+
+impl<'a> SeqAccess<'a> for Items<'a> {
+    type Error = ParseError;
+
+    fn next_element_seed<T: DeserializeSeed<'a>>(&mut self, seed: T) -> Result<Option<T::Value>, ParseError> {
+        self.0.next().map(|value| seed.deserialize(Deserializer::new(value))).transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+impl<'a> SeqAccess<'a> for OneOrArguments<'a> {
+    type Error = ParseError;
+
+    fn next_element_seed<T: DeserializeSeed<'a>>(&mut self, seed: T) -> Result<Option<T::Value>, ParseError> {
+        if let Some(arguments) = &mut self.arguments {
+            return arguments.next().map(|value| seed.deserialize(Deserializer::new(value))).transpose();
+        }
+
+        if self.whole.get().is_some() || members(self.element).next().is_none() {
+            return Ok(None);
+        }
+
+        let item = seed.deserialize(Probe {
+            element: self.element,
+            whole: &self.whole,
+        })?;
+
+        if self.whole.get() == Some(false) {
+            self.arguments = Some(members(self.element).skip(1).collect::<Vec<_>>().into_iter());
+        }
+
+        Ok(Some(item))
+    }
+}
+
+/// Deserialize the whole element, as a sequence of one.
+macro_rules! probe_whole {
+    ($($method:ident($($arg:ident: $ty:ty),*)),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'a>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, ParseError> {
+                self.whole.set(Some(true));
+                Deserializer::new(self.element).$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+/// Deserialize the first argument of the element, as a sequence of its arguments.
+macro_rules! probe_arguments {
+    ($($method:ident($($arg:ident: $ty:ty),*)),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'a>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, ParseError> {
+                self.whole.set(Some(false));
+                let first = members(self.element).next().ok_or_else(|| ParseError::Unexpected(self.element.clone()))?;
+                Deserializer::new(first).$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'a> de::Deserializer<'a> for Probe<'a, '_> {
+    type Error = ParseError;
+
+    fn deserialize_option<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, ParseError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'a>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ParseError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    probe_whole! {
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+    }
+
+    probe_arguments! {
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(), deserialize_i16(), deserialize_i32(), deserialize_i64(), deserialize_i128(),
+        deserialize_u8(), deserialize_u16(), deserialize_u32(), deserialize_u64(), deserialize_u128(),
+        deserialize_f32(), deserialize_f64(),
+        deserialize_char(), deserialize_str(), deserialize_string(), deserialize_identifier(),
+        deserialize_bytes(), deserialize_byte_buf(),
+        deserialize_unit(), deserialize_unit_struct(name: &'static str),
+        deserialize_seq(), deserialize_tuple(len: usize),
+        deserialize_ignored_any(),
+    }
+}
+
+impl<'a> EnumAccess<'a> for Variant<'a> {
+    type Error = ParseError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'a>>(self, seed: V) -> Result<(V::Value, Self), ParseError> {
+        seed.deserialize(BorrowedStrDeserializer::new(self.name)).map(|variant| (variant, self))
+    }
+}
+
+impl<'a> VariantAccess<'a> for Variant<'a> {
+    type Error = ParseError;
+
+    fn unit_variant(self) -> Result<(), ParseError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'a>>(self, seed: T) -> Result<T::Value, ParseError> {
+        seed.deserialize(Deserializer::new(self.content))
+    }
+
+    fn tuple_variant<V: Visitor<'a>>(self, len: usize, visitor: V) -> Result<V::Value, ParseError> {
+        Deserializer::new(self.content).deserialize_tuple(len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'a>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ParseError> {
+        Deserializer::new(self.content).deserialize_struct("", fields, visitor)
+    }
+}
+
+/// Return the head symbol of a list.
+fn head(value: &Value) -> Option<&str> {
+    value.as_cons()?.car().as_symbol()
+}
+
+/// Return the members of a list after its head symbol, or all of them if it has none.
+fn members(value: &Value) -> impl Iterator<Item = &Value> {
+    let skip = usize::from(head(value).is_some());
+    value.list_iter().into_iter().flatten().skip(skip)
+}
+
+/// Return an atom, or the single argument of a list such as `(width 0.25)`.
+fn atom(value: &Value) -> Result<&Value, ParseError> {
+    if !value.is_cons() {
+        return Ok(value);
+    }
+
+    let mut arguments = members(value);
+    match (arguments.next(), arguments.next()) {
+        (Some(argument), None) if !argument.is_cons() => Ok(argument),
+        _ => Err(ParseError::Unexpected(value.clone())),
+    }
+}
+
+/// Read a flag: `yes` or `no`, a bare symbol, or a list such as `(hide)` or `(hide yes)`.
+fn flag(value: &Value) -> Result<bool, ParseError> {
+    match value {
+        Value::Bool(flag) => Ok(*flag),
+        Value::Symbol(symbol) => Ok(!matches!(symbol.as_ref(), "no" | "false")),
+        Value::Cons(_) if members(value).next().is_none() => Ok(true),
+        Value::Cons(_) => flag(atom(value)?),
+        _ => Err(ParseError::Unexpected(value.clone())),
+    }
+}
+
+/// Pass an atom to a visitor.
+fn visit_atom<'a, V: Visitor<'a>>(value: &'a Value, visitor: V) -> Result<V::Value, ParseError> {
+    match value {
+        Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+            (Some(n), _) => visitor.visit_u64(n),
+            (_, Some(n)) => visitor.visit_i64(n),
+            _ => visitor.visit_f64(number.as_f64().unwrap_or_default()),
+        },
+        // A decimal number kept as written by the reader.
+        Value::Symbol(_) if value.as_float().is_some() => visitor.visit_f64(value.as_float().unwrap_or_default()),
+        Value::String(text) | Value::Symbol(text) | Value::Keyword(text) => visitor.visit_borrowed_str(text),
+        Value::Bool(flag) => visitor.visit_bool(*flag),
+        Value::Char(c) => visitor.visit_char(*c),
+        Value::Bytes(bytes) => visitor.visit_borrowed_bytes(bytes),
+        Value::Null | Value::Nil => visitor.visit_unit(),
+        Value::Cons(_) | Value::Vector(_) => Err(ParseError::Unexpected(value.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::common::{Font, LineStyle, Nm, Position, Stroke},
+        lexpr::sexp,
+        serde::Deserialize,
+    };
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Net {
+        number: u32,
+        name: String,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Board {
+        version: i64,
+        #[serde(rename = "net")]
+        nets: Vec<Net>,
+        layers: Vec<String>,
+        #[serde(default)]
+        locked: bool,
+        #[serde(default)]
+        hide: bool,
+    }
+
+    #[test]
+    fn test_deserializer() {
+        // Model types read the same as with their hand-written conversions.
+        let font: Font =
+            from_value(&sexp!((font (face "KiCad Font") (size 1.27 1.27) (thickness 0.1524) bold))).unwrap();
+        assert_eq!(
+            (font.face.as_deref(), font.size.width, font.thickness),
+            (Some("KiCad Font"), Nm(1_270_000), Some(152_400))
+        );
+        assert!(font.bold && !font.italic);
+
+        let stroke: Stroke = from_str("(stroke (width 0.25) (type dash) (color 255 0 0 1))").unwrap();
+        assert!(matches!(stroke.line_style, Some(LineStyle::Dash)));
+        assert_eq!(stroke.color.map(|color| color.red), Some(255.0));
+
+        let position: Position = from_str("(at 10 -20)").unwrap();
+        assert_eq!((position.x, position.y, position.angle), (Nm(10_000_000), Nm(-20_000_000), None));
+
+        // Repeated keys become sequences; a key that appears once still reads as one item.
+        let board: Board =
+            from_str("(board (version 20240108) (net 0 \"\") (net 1 \"GND\") (layers \"F.Cu\" \"B.Cu\") locked)")
+                .unwrap();
+        assert_eq!(board.nets.len(), 2);
+        assert_eq!(board.layers, ["F.Cu", "B.Cu"]);
+        assert!(board.locked && !board.hide);
+
+        let board: Board = from_str("(board (version 1) (net 1 GND) (layers F.Cu) (hide yes))").unwrap();
+        assert_eq!(
+            board,
+            Board {
+                version: 1,
+                nets: vec![Net {
+                    number: 1,
+                    name: "GND".to_string()
+                }],
+                layers: vec!["F.Cu".to_string()],
+                locked: false,
+                hide: true,
+            }
+        );
+
+        // Errors are located like those of the hand-written conversions.
+        let text = "(stroke (width 0.25)\n  (color 255 0 zero 1))";
+        let err = from_str::<Stroke>(text).unwrap_err();
+        assert_eq!(err.location().map(|location| (location.line, location.column)), Some((2, 3)));
+        assert!(matches!(err.as_parse_error().unwrap().root_cause(), ParseError::Deserialize(_)), "{err}");
+    }
+}
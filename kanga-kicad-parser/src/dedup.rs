@@ -0,0 +1,217 @@
+use {
+    crate::{
+        common::{Point, Position},
+        sch::{Schematic, SchematicGlobalLabelShape},
+        Config,
+    },
+    std::{collections::HashMap, hash::Hash},
+    uuid::Uuid,
+};
+
+/// The kinds of schematic elements checked for duplicates.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DuplicateKind {
+    /// A wire with the same points as another, in either direction.
+    Wire,
+
+    /// A bus with the same points as another, in either direction.
+    Bus,
+
+    /// A local label with the same text, position, and rotation as another.
+    Label,
+
+    /// A global label with the same text, shape, position, and rotation as another.
+    GlobalLabel,
+
+    /// A no-connect flag at the same position as another.
+    NoConnect,
+
+    /// A junction at the same position as another.
+    Junction,
+}
+
+/// An element that duplicates an earlier one.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Duplicate {
+    /// The kind of element.
+    pub kind: DuplicateKind,
+
+    /// The UUID of the redundant element.
+    pub uuid: Uuid,
+
+    /// The UUID of the first element it duplicates, which is kept.
+    pub original: Uuid,
+}
+
+/// Find elements of a schematic that duplicate an earlier element of the same kind, as copying
+/// and pasting or merging sheets by hand often leaves behind.
+///
+/// Only duplicates are reported: removing one changes neither the drawing nor the connectivity.
+/// Coordinates within [`Config::tolerance`] of each other are taken to be the same. Wires that
+/// merely overlap are not duplicates, since KiCad connects only at wire ends and removing one
+/// could disconnect a pin. Duplicates are returned in document order by kind.
+pub fn find_duplicates(schematic: &Schematic, config: &Config) -> Vec<Duplicate> {
+    let tolerance = config.tolerance as i64;
+    let mut duplicates = Vec::new();
+
+    let same_path = |a: &Vec<(i64, i64)>, b: &Vec<(i64, i64)>| {
+        let near = |b: &mut dyn Iterator<Item = &(i64, i64)>| a.iter().zip(b).all(|(a, b)| within(*a, *b, tolerance));
+        a.len() == b.len() && (near(&mut b.iter()) || near(&mut b.iter().rev()))
+    };
+    let same_position = |a: &PositionKey, b: &PositionKey| a.2 == b.2 && within((a.0, a.1), (b.0, b.1), tolerance);
+
+    let wires = schematic.wires.iter().map(|wire| (path_key(&wire.points.points), wire.uuid));
+    collect(DuplicateKind::Wire, wires, tolerance, same_path, &mut duplicates);
+
+    let buses = schematic.buses.iter().map(|bus| (path_key(&bus.points.points), bus.uuid));
+    collect(DuplicateKind::Bus, buses, tolerance, same_path, &mut duplicates);
+
+    let labels =
+        schematic.labels.iter().map(|label| ((label.text.as_str(), position_key(&label.position)), label.uuid));
+    let same_label = |a: &(&str, PositionKey), b: &(&str, PositionKey)| a.0 == b.0 && same_position(&a.1, &b.1);
+    collect(DuplicateKind::Label, labels, tolerance, same_label, &mut duplicates);
+
+    let global_labels = schematic.global_labels.iter().map(|label| {
+        let key: (&str, SchematicGlobalLabelShape, _) = (&label.text, label.shape, position_key(&label.position));
+        (key, label.uuid)
+    });
+    let same_global_label = |a: &(&str, SchematicGlobalLabelShape, PositionKey),
+                             b: &(&str, SchematicGlobalLabelShape, PositionKey)| {
+        a.0 == b.0 && a.1 == b.1 && same_position(&a.2, &b.2)
+    };
+    collect(DuplicateKind::GlobalLabel, global_labels, tolerance, same_global_label, &mut duplicates);
+
+    let no_connects = schematic.no_connects.iter().map(|nc| (position_key(&nc.position), nc.uuid));
+    collect(DuplicateKind::NoConnect, no_connects, tolerance, same_position, &mut duplicates);
+
+    let junctions = schematic.junctions.iter().map(|junction| (position_key(&junction.position), junction.uuid));
+    collect(DuplicateKind::Junction, junctions, tolerance, same_position, &mut duplicates);
+
+    duplicates
+}
+
+/// Remove the elements reported by [`find_duplicates`], keeping the first of each, and return
+/// what was removed.
+pub fn remove_duplicates(schematic: &mut Schematic, config: &Config) -> Vec<Duplicate> {
+    let duplicates = find_duplicates(schematic, config);
+    let redundant = |kind: DuplicateKind, uuid: &Uuid| {
+        duplicates.iter().any(|duplicate| duplicate.kind == kind && duplicate.uuid == *uuid)
+    };
+
+    schematic.wires.retain(|wire| !redundant(DuplicateKind::Wire, &wire.uuid));
+    schematic.buses.retain(|bus| !redundant(DuplicateKind::Bus, &bus.uuid));
+    schematic.labels.retain(|label| !redundant(DuplicateKind::Label, &label.uuid));
+    schematic.global_labels.retain(|label| !redundant(DuplicateKind::GlobalLabel, &label.uuid));
+    schematic.no_connects.retain(|nc| !redundant(DuplicateKind::NoConnect, &nc.uuid));
+    schematic.junctions.retain(|junction| !redundant(DuplicateKind::Junction, &junction.uuid));
+
+    duplicates
+}
+
+/// Report each element whose key matches that of an earlier element. With a nonzero tolerance,
+/// keys that differ are compared with `same`.
+fn collect<K, I, F>(kind: DuplicateKind, elements: I, tolerance: i64, same: F, duplicates: &mut Vec<Duplicate>)
+where
+    K: Clone + Eq + Hash,
+    I: IntoIterator<Item = (K, Uuid)>,
+    F: Fn(&K, &K) -> bool,
+{
+    let mut first: HashMap<K, Uuid> = HashMap::new();
+    let mut firsts: Vec<(K, Uuid)> = Vec::new();
+
+    for (key, uuid) in elements {
+        let original = match first.get(&key) {
+            Some(&original) => Some(original),
+            None if tolerance > 0 => firsts.iter().find(|(first, _)| same(first, &key)).map(|&(_, original)| original),
+            None => None,
+        };
+
+        match original {
+            Some(original) => duplicates.push(Duplicate {
+                kind,
+                uuid,
+                original,
+            }),
+            None => {
+                first.insert(key.clone(), uuid);
+                firsts.push((key, uuid));
+            }
+        }
+    }
+}
+
+/// A key for a position and rotation: the coordinates and the bits of the normalized angle.
+type PositionKey = (i64, i64, u64);
+
+/// Return a key for a position and rotation. Rotations are compared after normalization to
+/// `[0, 360)`, so that 0 and 360 match.
+fn position_key(position: &Position) -> PositionKey {
+    let angle = position.angle.unwrap_or_default().degrees();
+    (position.x.0, position.y.0, angle.to_bits())
+}
+
+/// Return a key for a path of points that is the same whichever end it starts from.
+fn path_key(points: &[Point]) -> Vec<(i64, i64)> {
+    let forward: Vec<_> = points.iter().map(|point| (point.x.0, point.y.0)).collect();
+    let reverse: Vec<_> = forward.iter().rev().copied().collect();
+    forward.min(reverse)
+}
+
+/// Indicates whether two points are within `tolerance` of each other along both axes.
+fn within(a: (i64, i64), b: (i64, i64), tolerance: i64) -> bool {
+    (a.0 - b.0).abs() <= tolerance && (a.1 - b.1).abs() <= tolerance
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::parse_str};
+
+    #[test]
+    fn test_remove_duplicates() {
+        let text = r#"(kicad_sch (version 20231120) (generator "eeschema")
+            (no_connect (at 10 10) (uuid "00000000-0000-0000-0000-000000000001"))
+            (no_connect (at 10 10) (uuid "00000000-0000-0000-0000-000000000002"))
+            (wire (pts (xy 0 0) (xy 10 0)) (stroke (width 0) (type default)) (uuid "00000000-0000-0000-0000-000000000003"))
+            (wire (pts (xy 10 0) (xy 0 0)) (stroke (width 0) (type default)) (uuid "00000000-0000-0000-0000-000000000004"))
+            (wire (pts (xy 0 0) (xy 5 0)) (stroke (width 0) (type default)) (uuid "00000000-0000-0000-0000-000000000005"))
+            (label "CLK" (at 0 0 0) (effects (font (size 1.27 1.27))) (uuid "00000000-0000-0000-0000-000000000006"))
+            (label "CLK" (at 0 0 0) (effects (font (size 1.27 1.27))) (uuid "00000000-0000-0000-0000-000000000007"))
+            (label "CLK" (at 0 0 90) (effects (font (size 1.27 1.27))) (uuid "00000000-0000-0000-0000-000000000008")))"#;
+
+        let mut schematic: Schematic = parse_str(text).unwrap();
+        let removed = remove_duplicates(&mut schematic, &Config::default());
+
+        let found: Vec<(DuplicateKind, u128, u128)> =
+            removed.iter().map(|d| (d.kind, d.uuid.as_u128(), d.original.as_u128())).collect();
+        assert_eq!(
+            found,
+            [(DuplicateKind::Wire, 4, 3), (DuplicateKind::Label, 7, 6), (DuplicateKind::NoConnect, 2, 1)]
+        );
+
+        // The partially overlapping wire and the rotated label are kept.
+        assert_eq!((schematic.wires.len(), schematic.labels.len(), schematic.no_connects.len()), (2, 2, 1));
+        assert!(find_duplicates(&schematic, &Config::default()).is_empty());
+    }
+
+    #[test]
+    fn test_duplicates_within_tolerance() {
+        let text = r#"(kicad_sch (version 20231120) (generator "eeschema")
+            (junction (at 10 10) (diameter 0) (color 0 0 0 0) (uuid "00000000-0000-0000-0000-000000000001"))
+            (junction (at 10.001 10) (diameter 0) (color 0 0 0 0) (uuid "00000000-0000-0000-0000-000000000002"))
+            (wire (pts (xy 0 0) (xy 10 0)) (stroke (width 0) (type default)) (uuid "00000000-0000-0000-0000-000000000003"))
+            (wire (pts (xy 10.001 0) (xy 0 0.001)) (stroke (width 0) (type default)) (uuid "00000000-0000-0000-0000-000000000004")))"#;
+        let schematic: Schematic = parse_str(text).unwrap();
+
+        assert!(find_duplicates(&schematic, &Config::default()).is_empty());
+
+        let config = Config::builder().tolerance(1_000).build();
+        let found: Vec<(DuplicateKind, u128, u128)> = find_duplicates(&schematic, &config)
+            .iter()
+            .map(|d| (d.kind, d.uuid.as_u128(), d.original.as_u128()))
+            .collect();
+        assert_eq!(found, [(DuplicateKind::Wire, 4, 3), (DuplicateKind::Junction, 2, 1)]);
+    }
+}
@@ -0,0 +1,210 @@
+use {
+    crate::{common::Position, pcb::Pcb, Project},
+    serde::{Deserialize, Serialize},
+    std::path::PathBuf,
+};
+
+/// Counts of items per cell of a regular grid, for rendering as a heatmap.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct DensityGrid {
+    /// The X coordinate of the left edge of the first column, in millimeters.
+    pub origin_x: f64,
+
+    /// The Y coordinate of the top edge of the first row, in millimeters.
+    pub origin_y: f64,
+
+    /// The width and height of each cell, in millimeters.
+    pub cell_size: f64,
+
+    /// The number of columns.
+    pub columns: usize,
+
+    /// The number of rows.
+    pub rows: usize,
+
+    /// The count for each cell, in row-major order.
+    pub cells: Vec<u32>,
+}
+
+/// Density statistics for one schematic sheet.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SheetDensity {
+    /// The schematic file.
+    pub path: PathBuf,
+
+    /// The number of symbols placed on the sheet, including power symbols.
+    pub symbols: usize,
+
+    /// The number of wire segments.
+    pub wires: usize,
+
+    /// The number of places where two wire segments cross without connecting.
+    pub wire_crossings: usize,
+
+    /// Symbol positions binned into a grid.
+    pub grid: DensityGrid,
+}
+
+impl DensityGrid {
+    /// Bin points, in millimeters, into square cells of the given size. The grid covers the
+    /// bounding box of the points; with no points it is empty.
+    pub fn from_points(points: &[(f64, f64)], cell_size: f64) -> Self {
+        if points.is_empty() || cell_size <= 0.0 {
+            return Self {
+                cell_size,
+                ..Self::default()
+            };
+        }
+
+        let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+        let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for &(x, y) in points {
+            (min_x, min_y) = (min_x.min(x), min_y.min(y));
+            (max_x, max_y) = (max_x.max(x), max_y.max(y));
+        }
+
+        let origin_x = (min_x / cell_size).floor() * cell_size;
+        let origin_y = (min_y / cell_size).floor() * cell_size;
+        let columns = ((max_x - origin_x) / cell_size).floor() as usize + 1;
+        let rows = ((max_y - origin_y) / cell_size).floor() as usize + 1;
+
+        let mut cells = vec![0; columns * rows];
+        for &(x, y) in points {
+            let column = (((x - origin_x) / cell_size) as usize).min(columns - 1);
+            let row = (((y - origin_y) / cell_size) as usize).min(rows - 1);
+            cells[row * columns + column] += 1;
+        }
+
+        Self {
+            origin_x,
+            origin_y,
+            cell_size,
+            columns,
+            rows,
+            cells,
+        }
+    }
+
+    /// Return the count for a cell, or `None` if it is outside the grid.
+    pub fn get(&self, column: usize, row: usize) -> Option<u32> {
+        if column < self.columns {
+            self.cells.get(row * self.columns + column).copied()
+        } else {
+            None
+        }
+    }
+
+    /// Return the highest count of any cell.
+    pub fn max(&self) -> u32 {
+        self.cells.iter().copied().max().unwrap_or_default()
+    }
+}
+
+/// Bin the footprints on a board by position into cells of `cell_size` millimeters.
+pub fn board_density(board: &Pcb, cell_size: f64) -> DensityGrid {
+    let points: Vec<(f64, f64)> = board.footprints.iter().map(|footprint| mm(&footprint.position)).collect();
+
+    DensityGrid::from_points(&points, cell_size)
+}
+
+/// Compute density statistics for each schematic in a project, binning symbols into cells of
+/// `cell_size` millimeters.
+pub fn sheet_density(project: &Project, cell_size: f64) -> Vec<SheetDensity> {
+    let mut result = Vec::new();
+
+    for (path, schematic) in &project.schematics {
+        let symbols: Vec<(f64, f64)> = schematic.symbols.iter().map(|symbol| mm(&symbol.position)).collect();
+        let mut wires = Vec::new();
+
+        for wire in &schematic.wires {
+            if let [start, end] = wire.points.points[..] {
+                wires.push(((start.x.to_mm(), start.y.to_mm()), (end.x.to_mm(), end.y.to_mm())));
+            }
+        }
+
+        let mut wire_crossings = 0;
+        for (i, a) in wires.iter().enumerate() {
+            wire_crossings += wires[i + 1..].iter().filter(|b| segments_cross(*a, **b)).count();
+        }
+
+        result.push(SheetDensity {
+            path: path.clone(),
+            symbols: symbols.len(),
+            wires: wires.len(),
+            wire_crossings,
+            grid: DensityGrid::from_points(&symbols, cell_size),
+        });
+    }
+
+    result
+}
+
+/// Return the coordinates of a position in millimeters.
+fn mm(position: &Position) -> (f64, f64) {
+    (position.x.to_mm(), position.y.to_mm())
+}
+
+/// Indicates whether two segments cross at a point interior to both. Segments that touch at an
+/// endpoint are connected, not crossing.
+fn segments_cross(
+    ((ax, ay), (bx, by)): ((f64, f64), (f64, f64)),
+    ((cx, cy), (dx, dy)): ((f64, f64), (f64, f64)),
+) -> bool {
+    let cross = |(ox, oy): (f64, f64), (px, py): (f64, f64), (qx, qy): (f64, f64)| {
+        (px - ox) * (qy - oy) - (py - oy) * (qx - ox)
+    };
+
+    let d1 = cross((cx, cy), (dx, dy), (ax, ay));
+    let d2 = cross((cx, cy), (dx, dy), (bx, by));
+    let d3 = cross((ax, ay), (bx, by), (cx, cy));
+    let d4 = cross((ax, ay), (bx, by), (dx, dy));
+
+    d1 * d2 < 0.0 && d3 * d4 < 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::sch::Schematic, lexpr::sexp, std::collections::BTreeMap};
+
+    #[test]
+    fn test_density() {
+        let board = sexp!((kicad_pcb
+            (footprint "R" (layer "F.Cu") (at 1 1))
+            (footprint "R" (layer "F.Cu") (at 2 3))
+            (footprint "R" (layer "F.Cu") (at 12 1 90))));
+        let board = Pcb::try_from(&board).unwrap();
+
+        let grid = board_density(&board, 10.0);
+        assert_eq!((grid.columns, grid.rows), (2, 1));
+        assert_eq!(grid.get(0, 0), Some(2));
+        assert_eq!(grid.max(), 2);
+
+        let project = Project {
+            dir: PathBuf::from("/demo"),
+            name: "demo".to_string(),
+            settings: serde_json::Value::Null,
+            root_schematic: PathBuf::from("/demo/demo.kicad_sch"),
+            schematics: [(
+                PathBuf::from("/demo/demo.kicad_sch"),
+                Schematic::try_from(&sexp!((kicad_sch
+                    (symbol (lib_id "Device:R") (at 10 10 0) (uuid "0c6b985a-999e-45ae-b1a7-41cfbe8ba8fa"))
+                    (wire (pts (xy 0 5) (xy 10 5)) (stroke (width 0) (type default)) (uuid "1c6b985a-999e-45ae-b1a7-41cfbe8ba8fa"))
+                    (wire (pts (xy 5 0) (xy 5 10)) (stroke (width 0) (type default)) (uuid "2c6b985a-999e-45ae-b1a7-41cfbe8ba8fa"))
+                    (wire (pts (xy 10 5) (xy 10 0)) (stroke (width 0) (type default)) (uuid "3c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")))))
+                .unwrap(),
+            )]
+            .into_iter()
+            .collect(),
+            sheets: Vec::new(),
+            board: None,
+            symbol_lib_table: None,
+            footprint_lib_table: None,
+            worksheets: BTreeMap::new(),
+        };
+
+        let sheets = sheet_density(&project, 10.0);
+        assert_eq!(sheets[0].symbols, 1);
+        assert_eq!(sheets[0].wires, 3);
+        assert_eq!(sheets[0].wire_crossings, 1);
+    }
+}
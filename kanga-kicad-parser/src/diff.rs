@@ -0,0 +1,330 @@
+use {
+    crate::{
+        common::{Point, Points, Position},
+        sch::{
+            Schematic, SchematicGlobalLabel, SchematicGlobalLabelShape, SchematicLabel, SchematicSymbolInstance,
+            SchematicSymbolMirror, SchematicWire,
+        },
+    },
+    std::collections::{HashMap, HashSet},
+    uuid::Uuid,
+};
+
+/// The kinds of schematic elements compared by [`diff_schematics`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DiffElementKind {
+    /// A placed symbol.
+    Symbol,
+
+    /// A wire.
+    Wire,
+
+    /// A local label.
+    Label,
+
+    /// A global label.
+    GlobalLabel,
+}
+
+/// How an element differs between two schematics.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ChangeKind {
+    /// The element is only in the new schematic.
+    Added,
+
+    /// The element is only in the old schematic.
+    Removed,
+
+    /// The element is in both schematics, with different fields.
+    Modified,
+}
+
+/// A field of an element that differs between two schematics.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct FieldChange {
+    /// The field, named as in the file, e.g. `at`, `lib_id`, or `property "Value"`.
+    pub field: String,
+
+    /// The value in the old schematic, or `None` if the field is absent from it.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub old: Option<String>,
+
+    /// The value in the new schematic, or `None` if the field is absent from it.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub new: Option<String>,
+}
+
+/// An element added, removed, or modified between two schematics.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ElementChange {
+    /// The kind of element.
+    pub kind: DiffElementKind,
+
+    /// Whether the element was added, removed, or modified.
+    pub change: ChangeKind,
+
+    /// The UUID of the element.
+    pub uuid: Uuid,
+
+    /// A name for the element in messages: the reference of a symbol or the text of a label.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub name: Option<String>,
+
+    /// The fields that differ. Empty for added and removed elements.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub fields: Vec<FieldChange>,
+}
+
+/// The differences between two schematics.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct SchematicDiff {
+    /// The changed elements, grouped by kind in the order of [`DiffElementKind`]. Within a kind,
+    /// removed and modified elements come first in the old schematic's order, followed by added
+    /// elements in the new schematic's order.
+    pub changes: Vec<ElementChange>,
+}
+
+/// The comparable fields of an element, by name, in the order they are reported.
+type Fields = Vec<(String, String)>;
+
+impl SchematicDiff {
+    /// Indicates whether the schematics have no differences.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Return the changes of the given kind.
+    pub fn changes_of(&self, kind: DiffElementKind) -> impl Iterator<Item = &ElementChange> {
+        self.changes.iter().filter(move |change| change.kind == kind)
+    }
+}
+
+/// Compare two schematics, matching elements by UUID, and report the symbols, wires, and labels
+/// that were added, removed, or modified.
+///
+/// A symbol is compared by its library identifier, position, mirroring, unit, flags, and property
+/// values; a wire by its points; and a label by its text, position, and, for global labels, shape.
+/// Moving a wire end counts as modifying the wire, while redrawing it gives it a new UUID and so
+/// counts as removing it and adding another.
+pub fn diff_schematics(old: &Schematic, new: &Schematic) -> SchematicDiff {
+    let mut changes = Vec::new();
+
+    let symbol = |symbol: &SchematicSymbolInstance| {
+        let mut fields = vec![
+            ("lib_id".to_string(), symbol.lib_id.clone()),
+            ("at".to_string(), position(&symbol.position)),
+            ("unit".to_string(), symbol.unit.to_string()),
+        ];
+        if let Some(mirror) = symbol.mirror {
+            let axis = match mirror {
+                SchematicSymbolMirror::X => "x",
+                SchematicSymbolMirror::Y => "y",
+            };
+            fields.push(("mirror".to_string(), axis.to_string()));
+        }
+        for (flag, value) in [("in_bom", symbol.in_bom), ("on_board", symbol.on_board), ("dnp", symbol.dnp)] {
+            if let Some(value) = value {
+                fields.push((flag.to_string(), yes_no(value)));
+            }
+        }
+        for property in &symbol.properties {
+            fields.push((format!("property {:?}", property.key), property.value.clone()));
+        }
+        let name = symbol.property("Reference").map(String::from);
+        (symbol.uuid, name, fields)
+    };
+    compare(DiffElementKind::Symbol, &old.symbols, &new.symbols, symbol, &mut changes);
+
+    let wire = |wire: &SchematicWire| (wire.uuid, None, vec![("pts".to_string(), points(&wire.points))]);
+    compare(DiffElementKind::Wire, &old.wires, &new.wires, wire, &mut changes);
+
+    let label = |label: &SchematicLabel| {
+        let fields = vec![("text".to_string(), label.text.clone()), ("at".to_string(), position(&label.position))];
+        (label.uuid, Some(label.text.clone()), fields)
+    };
+    compare(DiffElementKind::Label, &old.labels, &new.labels, label, &mut changes);
+
+    let global_label = |label: &SchematicGlobalLabel| {
+        let fields = vec![
+            ("text".to_string(), label.text.clone()),
+            ("shape".to_string(), shape(label.shape).to_string()),
+            ("at".to_string(), position(&label.position)),
+        ];
+        (label.uuid, Some(label.text.clone()), fields)
+    };
+    compare(DiffElementKind::GlobalLabel, &old.global_labels, &new.global_labels, global_label, &mut changes);
+
+    SchematicDiff {
+        changes,
+    }
+}
+
+/// Compare the elements of one kind, appending their changes.
+fn compare<T, F>(kind: DiffElementKind, old: &[T], new: &[T], describe: F, changes: &mut Vec<ElementChange>)
+where
+    F: Fn(&T) -> (Uuid, Option<String>, Fields),
+{
+    let old: Vec<_> = old.iter().map(&describe).collect();
+    let new: Vec<_> = new.iter().map(&describe).collect();
+    let old_uuids: HashSet<Uuid> = old.iter().map(|(uuid, ..)| *uuid).collect();
+    let new_by_uuid: HashMap<Uuid, (&Option<String>, &Fields)> =
+        new.iter().map(|(uuid, name, fields)| (*uuid, (name, fields))).collect();
+
+    for (uuid, name, old_fields) in &old {
+        // A modified element is named as it is in the new schematic.
+        let (change, name, fields) = match new_by_uuid.get(uuid) {
+            None => (ChangeKind::Removed, name, Vec::new()),
+            Some((new_name, new_fields)) => match field_changes(old_fields, new_fields) {
+                fields if fields.is_empty() => continue,
+                fields => (ChangeKind::Modified, *new_name, fields),
+            },
+        };
+
+        changes.push(ElementChange {
+            kind,
+            change,
+            uuid: *uuid,
+            name: name.clone(),
+            fields,
+        });
+    }
+
+    for (uuid, name, _) in &new {
+        if !old_uuids.contains(uuid) {
+            changes.push(ElementChange {
+                kind,
+                change: ChangeKind::Added,
+                uuid: *uuid,
+                name: name.clone(),
+                fields: Vec::new(),
+            });
+        }
+    }
+}
+
+/// Return the fields that differ, in the old element's order followed by fields only the new
+/// element has.
+fn field_changes(old: &Fields, new: &Fields) -> Vec<FieldChange> {
+    let lookup =
+        |fields: &Fields, field: &str| fields.iter().find(|(name, _)| name == field).map(|(_, value)| value.clone());
+
+    let mut changes = Vec::new();
+    for (field, value) in old {
+        let new_value = lookup(new, field);
+        if new_value.as_ref() != Some(value) {
+            changes.push(FieldChange {
+                field: field.clone(),
+                old: Some(value.clone()),
+                new: new_value,
+            });
+        }
+    }
+
+    for (field, value) in new {
+        if lookup(old, field).is_none() {
+            changes.push(FieldChange {
+                field: field.clone(),
+                old: None,
+                new: Some(value.clone()),
+            });
+        }
+    }
+
+    changes
+}
+
+/// Format a position as in the file, e.g. `10.16 20.32 90`.
+fn position(position: &Position) -> String {
+    match position.angle {
+        Some(angle) => format!("{} {} {angle}", position.x, position.y),
+        None => format!("{} {}", position.x, position.y),
+    }
+}
+
+/// Format a point as in the file, e.g. `10.16 20.32`.
+fn point(point: &Point) -> String {
+    format!("{} {}", point.x, point.y)
+}
+
+/// Format a list of points, e.g. `(0 0) (10.16 0)`.
+fn points(points: &Points) -> String {
+    points.points.iter().map(point).map(|text| format!("({text})")).collect::<Vec<_>>().join(" ")
+}
+
+/// Format a flag as in the file.
+fn yes_no(value: bool) -> String {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+    .to_string()
+}
+
+/// Return the name KiCad uses for a global label shape.
+fn shape(shape: SchematicGlobalLabelShape) -> &'static str {
+    match shape {
+        SchematicGlobalLabelShape::Input => "input",
+        SchematicGlobalLabelShape::Output => "output",
+        SchematicGlobalLabelShape::Bidirectional => "bidirectional",
+        SchematicGlobalLabelShape::TriState => "tri_state",
+        SchematicGlobalLabelShape::Passive => "passive",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::parse_str};
+
+    #[test]
+    fn test_diff_schematics() {
+        let old = r#"(kicad_sch (version 20231120) (generator "eeschema")
+            (wire (pts (xy 0 0) (xy 10 0)) (stroke (width 0) (type default)) (uuid "00000000-0000-0000-0000-000000000001"))
+            (wire (pts (xy 10 0) (xy 20 0)) (stroke (width 0) (type default)) (uuid "00000000-0000-0000-0000-000000000002"))
+            (label "CLK" (at 0 0 0) (effects (font (size 1.27 1.27))) (uuid "00000000-0000-0000-0000-000000000003"))
+            (symbol (lib_id "Device:R") (at 10 10 0) (unit 1) (uuid "00000000-0000-0000-0000-000000000004")
+                (property "Reference" "R1" (at 0 0 0)) (property "Value" "10k" (at 0 0 0))))"#;
+        let new = r#"(kicad_sch (version 20231120) (generator "eeschema")
+            (wire (pts (xy 0 0) (xy 10 0)) (stroke (width 0) (type default)) (uuid "00000000-0000-0000-0000-000000000001"))
+            (wire (pts (xy 10 0) (xy 10 20)) (stroke (width 0) (type default)) (uuid "00000000-0000-0000-0000-000000000005"))
+            (label "CLK" (at 0 0 0) (effects (font (size 1.27 1.27))) (uuid "00000000-0000-0000-0000-000000000003"))
+            (symbol (lib_id "Device:R") (at 10 10 90) (unit 1) (dnp yes) (uuid "00000000-0000-0000-0000-000000000004")
+                (property "Reference" "R1" (at 0 0 0)) (property "Value" "4k7" (at 0 0 0))))"#;
+
+        let old: Schematic = parse_str(old).unwrap();
+        let new: Schematic = parse_str(new).unwrap();
+        let diff = diff_schematics(&old, &new);
+
+        let found: Vec<_> = diff.changes.iter().map(|c| (c.kind, c.change, c.uuid.as_u128())).collect();
+        assert_eq!(
+            found,
+            [
+                (DiffElementKind::Symbol, ChangeKind::Modified, 4),
+                (DiffElementKind::Wire, ChangeKind::Removed, 2),
+                (DiffElementKind::Wire, ChangeKind::Added, 5),
+            ]
+        );
+
+        let symbol = &diff.changes[0];
+        assert_eq!(symbol.name.as_deref(), Some("R1"));
+        let fields: Vec<_> =
+            symbol.fields.iter().map(|f| (f.field.as_str(), f.old.as_deref(), f.new.as_deref())).collect();
+        assert_eq!(
+            fields,
+            [
+                ("at", Some("10 10 0"), Some("10 10 90")),
+                ("property \"Value\"", Some("10k"), Some("4k7")),
+                ("dnp", None, Some("yes")),
+            ]
+        );
+
+        assert!(diff_schematics(&new, &new).is_empty());
+    }
+}
@@ -0,0 +1,434 @@
+use {
+    crate::{LexprExt, ParseError},
+    lexpr::Value,
+    serde::{Deserialize, Serialize},
+    std::collections::BTreeMap,
+};
+
+/// The schematic file format version written by KiCad 6.0.
+pub const KICAD6_SCHEMATIC_VERSION: i64 = 20211123;
+
+/// Elements introduced after KiCad 6 that have no KiCad 6 equivalent and are removed wherever they
+/// appear.
+const DROPPED_ELEMENTS: &[&str] =
+    &["dnp", "embedded_files", "embedded_fonts", "exclude_from_sim", "generator_version", "text_box"];
+
+/// Elements introduced after KiCad 6 that are only valid as top-level schematic items.
+const DROPPED_SCHEMATIC_ITEMS: &[&str] =
+    &["arc", "circle", "directive_label", "netclass_flag", "rectangle", "rule_area", "table"];
+
+/// The fixed field ids used by KiCad 6 for the mandatory symbol properties.
+const KICAD6_FIELD_IDS: &[(&str, i64)] = &[("Reference", 0), ("Value", 1), ("Footprint", 2), ("Datasheet", 3)];
+
+/// The result of downgrading a document to an older KiCad file format.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DowngradeReport {
+    /// The version of the original document, if it declared one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_version: Option<i64>,
+
+    /// The version written to the downgraded document.
+    pub to_version: i64,
+
+    /// The changes made, grouped by element path.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<DowngradeChange>,
+}
+
+/// A change made while downgrading a document.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DowngradeChange {
+    /// The path of the affected element, e.g. `kicad_sch > symbol > dnp`.
+    pub path: String,
+
+    /// What was done to the element.
+    pub action: DowngradeAction,
+
+    /// The number of elements at this path that were changed.
+    pub count: usize,
+}
+
+/// The kind of change made to an element while downgrading.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DowngradeAction {
+    /// The element has no equivalent in the older format and was removed.
+    Dropped,
+
+    /// The element was rewritten into its equivalent in the older format.
+    Translated,
+}
+
+/// Mutable state used while walking a schematic.
+struct Downgrader {
+    root_uuid: Option<String>,
+    changes: BTreeMap<(String, DowngradeAction), usize>,
+    symbol_instances: Vec<Value>,
+    sheet_instances: Vec<Value>,
+}
+
+impl DowngradeReport {
+    /// Indicates whether any element was removed from the document.
+    pub fn is_lossy(&self) -> bool {
+        self.changes.iter().any(|change| change.action == DowngradeAction::Dropped)
+    }
+}
+
+/// Downgrade a KiCad 7 or newer schematic to the KiCad 6 file format.
+///
+/// This is a best-effort conversion operating on the raw s-expression tree: elements that KiCad 6
+/// cannot read are dropped, and elements whose syntax changed (boolean flags, per-symbol
+/// `instances`, sheet property names) are translated. Every change is recorded in the returned
+/// [`DowngradeReport`]. The result can be written with [`lexpr::to_string`].
+pub fn downgrade_schematic_to_v6(value: &Value) -> Result<(Value, DowngradeReport), ParseError> {
+    let rest = value.expect_cons_with_symbol_head("kicad_sch")?;
+    let items = rest.to_vec().ok_or_else(|| ParseError::ExpectedList(rest.clone()))?;
+
+    let mut from_version = None;
+    let mut root_uuid = None;
+
+    for item in &items {
+        if let Ok((key, cdr)) = item.expect_cons_with_any_symbol_head() {
+            match key {
+                "version" => from_version = cdr.expect_cons_with_any_int_head().ok().map(|(version, _)| version),
+                "uuid" => root_uuid = uuid_str(cdr).map(str::to_string),
+                _ => (),
+            }
+        }
+    }
+
+    let mut downgrader = Downgrader {
+        root_uuid,
+        changes: BTreeMap::new(),
+        symbol_instances: Vec::new(),
+        sheet_instances: Vec::new(),
+    };
+
+    let path = vec!["kicad_sch".to_string()];
+    let mut result = vec![Value::symbol("kicad_sch")];
+    let mut existing_sheet_instances = None;
+
+    for item in &items {
+        let key = item.expect_cons_with_any_symbol_head().ok().map(|(key, _)| key);
+
+        match key {
+            Some("version") => {
+                result.push(Value::list(vec![Value::symbol("version"), Value::from(KICAD6_SCHEMATIC_VERSION)]));
+                downgrader.record(&path, "version", DowngradeAction::Translated);
+            }
+
+            Some("generator") => {
+                // KiCad 6 writes the generator as a bare symbol.
+                let (_, cdr) = item.expect_cons_with_any_symbol_head()?;
+                let (generator, _) = cdr.expect_cons_with_any_str_head()?;
+                result.push(Value::list(vec![Value::symbol("generator"), Value::symbol(generator)]));
+            }
+
+            Some("sheet_instances") => {
+                existing_sheet_instances = Some(item.clone());
+            }
+
+            Some(key) if DROPPED_SCHEMATIC_ITEMS.contains(&key) => {
+                downgrader.record(&path, key, DowngradeAction::Dropped);
+            }
+
+            _ => {
+                if let Some(item) = downgrader.downgrade(item, &path) {
+                    result.push(item);
+                }
+            }
+        }
+    }
+
+    // KiCad 6 keeps hierarchy instance data in top-level tables rather than on each item.
+    let mut sheet_instances = vec![Value::symbol("sheet_instances")];
+    if let Some(existing) = existing_sheet_instances.as_ref().and_then(Value::to_vec) {
+        sheet_instances.extend(existing.into_iter().skip(1));
+    }
+    sheet_instances.append(&mut downgrader.sheet_instances);
+    if sheet_instances.len() > 1 {
+        result.push(Value::list(sheet_instances));
+    }
+
+    if !downgrader.symbol_instances.is_empty() {
+        let mut symbol_instances = vec![Value::symbol("symbol_instances")];
+        symbol_instances.append(&mut downgrader.symbol_instances);
+        result.push(Value::list(symbol_instances));
+    }
+
+    let changes = downgrader
+        .changes
+        .into_iter()
+        .map(|((path, action), count)| DowngradeChange {
+            path,
+            action,
+            count,
+        })
+        .collect();
+
+    let report = DowngradeReport {
+        from_version,
+        to_version: KICAD6_SCHEMATIC_VERSION,
+        changes,
+    };
+
+    Ok((Value::list(result), report))
+}
+
+impl Downgrader {
+    /// Record a change to the element `key` beneath `path`.
+    fn record(&mut self, path: &[String], key: &str, action: DowngradeAction) {
+        let path = format!("{} > {key}", path.join(" > "));
+        *self.changes.entry((path, action)).or_default() += 1;
+    }
+
+    /// Downgrade a single element, returning `None` if it should be removed.
+    fn downgrade(&mut self, value: &Value, path: &[String]) -> Option<Value> {
+        let Ok((key, _)) = value.expect_cons_with_any_symbol_head() else {
+            return Some(value.clone());
+        };
+
+        if DROPPED_ELEMENTS.contains(&key) {
+            if value_flag(value) != Some(false) {
+                self.record(path, key, DowngradeAction::Dropped);
+            }
+            return None;
+        }
+
+        let mut item_path = path.to_vec();
+        item_path.push(key.to_string());
+
+        let elements = value.to_vec()?;
+        let mut result = Vec::with_capacity(elements.len());
+        let mut instances = None;
+        let mut property_count = 0;
+        let mut next_custom_id = KICAD6_FIELD_IDS.len() as i64;
+
+        for element in elements {
+            let Ok((child, _)) = element.expect_cons_with_any_symbol_head() else {
+                result.push(element);
+                continue;
+            };
+
+            match child {
+                // `(hide yes)` became the bare `hide` flag; `(hide no)` is the default.
+                "hide" | "fields_autoplaced" => match value_flag(&element) {
+                    Some(true) if child == "hide" => {
+                        result.push(Value::symbol("hide"));
+                        self.record(&item_path, child, DowngradeAction::Translated);
+                    }
+                    Some(true) => {
+                        result.push(Value::list(vec![Value::symbol(child)]));
+                        self.record(&item_path, child, DowngradeAction::Translated);
+                    }
+                    Some(false) => (),
+                    None => result.push(element),
+                },
+
+                "instances" if key == "symbol" || key == "sheet" => {
+                    instances = Some(element);
+                }
+
+                "property" if key == "symbol" || key == "sheet" => {
+                    if let Some(property) =
+                        self.downgrade_property(&element, &item_path, key, property_count, &mut next_custom_id)
+                    {
+                        property_count += 1;
+                        result.push(property);
+                    }
+                }
+
+                _ => {
+                    if let Some(element) = self.downgrade(&element, &item_path) {
+                        result.push(element);
+                    }
+                }
+            }
+        }
+
+        if let Some(instances) = instances {
+            self.collect_instances(&instances, value, key);
+            self.record(&item_path, "instances", DowngradeAction::Translated);
+        }
+
+        Some(Value::list(result))
+    }
+
+    /// Downgrade a symbol or sheet property, restoring the numeric field ids and sheet field names
+    /// used by KiCad 6. Symbol fields other than the mandatory ones are numbered from
+    /// `next_custom_id`, which is advanced past each id assigned.
+    fn downgrade_property(
+        &mut self,
+        value: &Value,
+        path: &[String],
+        parent: &str,
+        index: i64,
+        next_custom_id: &mut i64,
+    ) -> Option<Value> {
+        let mut property = self.downgrade(value, path)?.to_vec()?;
+        let name = property.get(1).and_then(Value::as_str).map(str::to_string);
+
+        if parent == "sheet" {
+            let renamed = match name.as_deref() {
+                Some("Sheetname") => Some("Sheet name"),
+                Some("Sheetfile") => Some("Sheet file"),
+                _ => None,
+            };
+
+            if let Some(renamed) = renamed {
+                property[1] = Value::string(renamed);
+                self.record(path, "property", DowngradeAction::Translated);
+            }
+        }
+
+        let has_id = property.iter().any(|element| element.expect_cons_with_symbol_head("id").is_ok());
+        if !has_id && property.len() >= 3 {
+            let id = if parent == "sheet" {
+                index
+            } else {
+                KICAD6_FIELD_IDS
+                    .iter()
+                    .find(|(field, _)| Some(*field) == name.as_deref())
+                    .map(|(_, id)| *id)
+                    .unwrap_or_else(|| {
+                        *next_custom_id += 1;
+                        *next_custom_id - 1
+                    })
+            };
+            property.insert(3, Value::list(vec![Value::symbol("id"), Value::from(id)]));
+        }
+
+        Some(Value::list(property))
+    }
+
+    /// Move an item's `(instances (project ... (path ...)))` block into the KiCad 6 top-level
+    /// `symbol_instances` or `sheet_instances` tables.
+    fn collect_instances(&mut self, instances: &Value, item: &Value, key: &str) {
+        let Some(item_uuid) = item.list_iter().and_then(|mut iter| {
+            iter.find_map(|element| element.expect_cons_with_symbol_head("uuid").ok().and_then(uuid_str))
+        }) else {
+            return;
+        };
+
+        let properties: BTreeMap<String, String> = item
+            .list_iter()
+            .into_iter()
+            .flatten()
+            .filter_map(|element| {
+                let rest = element.expect_cons_with_symbol_head("property").ok()?;
+                let (name, rest) = rest.expect_cons_with_any_str_head().ok()?;
+                let (value, _) = rest.expect_cons_with_any_str_head().ok()?;
+                Some((name.to_string(), value.to_string()))
+            })
+            .collect();
+
+        let projects = instances.list_iter().into_iter().flatten().skip(1);
+        let paths = projects.flat_map(|project| project.list_iter().into_iter().flatten().skip(2));
+
+        for path in paths {
+            let Ok(rest) = path.expect_cons_with_symbol_head("path") else {
+                continue;
+            };
+
+            let Ok((sheet_path, data)) = rest.expect_cons_with_any_str_head() else {
+                continue;
+            };
+
+            // KiCad 7+ paths begin with the root sheet UUID, which KiCad 6 omits.
+            let sheet_path = match &self.root_uuid {
+                Some(root) => sheet_path.strip_prefix(&format!("/{root}")).unwrap_or(sheet_path),
+                None => sheet_path,
+            };
+
+            let mut entry = vec![
+                Value::symbol("path"),
+                Value::string(format!("{}/{item_uuid}", sheet_path.trim_end_matches('/'))),
+            ];
+            entry.extend(data.list_iter().into_iter().flatten().cloned());
+
+            if key == "symbol" {
+                for field in ["Value", "Footprint"] {
+                    if let Some(value) = properties.get(field) {
+                        entry.push(Value::list(vec![
+                            Value::symbol(field.to_lowercase()),
+                            Value::string(value.as_str()),
+                        ]));
+                    }
+                }
+                self.symbol_instances.push(Value::list(entry));
+            } else {
+                self.sheet_instances.push(Value::list(entry));
+            }
+        }
+    }
+}
+
+/// Return the UUID string from the cdr of a `(uuid ...)` element, which may be quoted or bare.
+fn uuid_str(cdr: &Value) -> Option<&str> {
+    let car = cdr.as_cons()?.car();
+    car.as_str().or_else(|| car.as_symbol())
+}
+
+/// Interpret a KiCad 7+ boolean element like `(hide yes)` or `(dnp no)`.
+///
+/// A bare element without a value, such as `(fields_autoplaced)`, is treated as `true`. Returns
+/// `None` if the element's value is not a boolean.
+fn value_flag(value: &Value) -> Option<bool> {
+    let (_, cdr) = value.expect_cons_with_any_symbol_head().ok()?;
+    match cdr.as_cons() {
+        None => Some(true),
+        Some(cons) if cons.cdr().is_null() => cons.car().expect_bool().ok(),
+        Some(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, lexpr::sexp};
+
+    #[test]
+    fn test_downgrade_flags_and_instances() {
+        let sch = sexp!((kicad_sch
+            (version 20231120)
+            (generator "eeschema")
+            (generator_version "8.0")
+            (uuid "root")
+            (symbol
+                (lib_id "Device:R")
+                (exclude_from_sim no)
+                (dnp yes)
+                (uuid "r1")
+                (property "Reference" "R1" (effects (font (size 1.27 1.27)) (hide yes)))
+                (property "Value" "10k")
+                (property "MPN" "RC0603FR-0710KL")
+                (property "Tolerance" "1%")
+                (instances (project "demo" (path "/root" (reference "R1") (unit 1)))))
+            (netclass_flag "" (length 2.54))));
+
+        let (result, report) = downgrade_schematic_to_v6(&sch).unwrap();
+        let expected = sexp!((kicad_sch
+            (version 20211123)
+            (generator eeschema)
+            (uuid "root")
+            (symbol
+                (lib_id "Device:R")
+                (uuid "r1")
+                (property "Reference" "R1" (id 0) (effects (font (size 1.27 1.27)) hide))
+                (property "Value" "10k" (id 1))
+                (property "MPN" "RC0603FR-0710KL" (id 4))
+                (property "Tolerance" "1%" (id 5)))
+            (symbol_instances (path "/r1" (reference "R1") (unit 1) (value "10k")))));
+
+        assert_eq!(result, expected);
+        assert_eq!(report.from_version, Some(20231120));
+        assert!(report.is_lossy());
+
+        let dropped: Vec<&str> = report
+            .changes
+            .iter()
+            .filter(|change| change.action == DowngradeAction::Dropped)
+            .map(|change| change.path.as_str())
+            .collect();
+        assert_eq!(dropped, ["kicad_sch > generator_version", "kicad_sch > netclass_flag", "kicad_sch > symbol > dnp"]);
+    }
+}
@@ -0,0 +1,424 @@
+use {
+    crate::{
+        bus::on_path,
+        common::{ParentOrientation, Point, Position, Symbol, SymbolPin, SymbolPinElectricalType},
+        lint::Severity,
+        project::{ErcSettings, RuleSeverity},
+        sch::{Schematic, SchematicSymbolInstance, SchematicSymbolMirror},
+    },
+    serde::{Deserialize, Serialize},
+    std::collections::{BTreeMap, HashMap, HashSet},
+    uuid::Uuid,
+};
+
+/// The number of pin electrical types, which index the pin conflict matrix.
+const PIN_TYPES: usize = 12;
+
+/// KiCad's default pin conflict matrix, indexed by [`SymbolPinElectricalType`] in declaration
+/// order. Each entry is 0 (no error), 1 (warning), or 2 (error), as in a project's `pin_map`.
+#[rustfmt::skip]
+pub const DEFAULT_PIN_MAP: [[u8; PIN_TYPES]; PIN_TYPES] = [
+    //  I  O  Bi 3S Ps Fr Un PI PO OC OE NC
+    [0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 2], // Input
+    [0, 2, 0, 1, 0, 0, 1, 0, 2, 2, 2, 2], // Output
+    [0, 0, 0, 0, 0, 0, 1, 0, 1, 0, 1, 2], // Bidirectional
+    [0, 1, 0, 0, 0, 0, 1, 1, 2, 1, 1, 2], // Tri-state
+    [0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 2], // Passive
+    [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2], // Free
+    [1, 1, 1, 1, 1, 0, 1, 1, 1, 1, 1, 2], // Unspecified
+    [0, 0, 0, 1, 0, 0, 1, 0, 0, 0, 0, 2], // Power input
+    [0, 2, 1, 2, 0, 0, 1, 0, 2, 2, 2, 2], // Power output
+    [0, 2, 0, 1, 0, 0, 1, 0, 2, 0, 0, 2], // Open collector
+    [0, 2, 1, 1, 0, 0, 1, 0, 2, 0, 0, 2], // Open emitter
+    [2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2], // No connect
+];
+
+/// The electrical rules checked by [`check_erc`].
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErcRule {
+    /// A pin is connected to nothing and has no no-connect flag.
+    PinNotConnected,
+
+    /// Two symbols share a reference designator and unit.
+    DuplicateReference,
+
+    /// Two pins on the same net have electrical types that should not be connected, such as two
+    /// outputs.
+    PinToPin,
+
+    /// A symbol placed on the board has no footprint.
+    MissingFootprint,
+}
+
+/// A problem found by an electrical rules check.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ErcDiagnostic {
+    /// The rule that was violated.
+    pub rule: ErcRule,
+
+    /// How serious the problem is.
+    pub severity: Severity,
+
+    /// A human-readable description of the problem.
+    pub message: String,
+
+    /// The UUIDs of the offending elements: pins of placed symbols where a pin is at fault, and
+    /// placed symbols otherwise.
+    pub uuids: Vec<Uuid>,
+}
+
+/// A pin of a placed symbol, located on the sheet.
+struct PlacedPin<'a> {
+    /// The placed symbol.
+    instance: &'a SchematicSymbolInstance,
+
+    /// The reference designator of the symbol.
+    reference: &'a str,
+
+    /// The pin in the library symbol.
+    pin: &'a SymbolPin,
+
+    /// The connection point of the pin on the sheet.
+    point: (i64, i64),
+
+    /// The UUID of the placed pin, or of the symbol if the pin is not listed.
+    uuid: Uuid,
+}
+
+impl ErcRule {
+    /// Return the name KiCad uses for this rule in a project's `rule_severities`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PinNotConnected => "pin_not_connected",
+            Self::DuplicateReference => "duplicate_reference",
+            Self::PinToPin => "pin_to_pin",
+            Self::MissingFootprint => "missing_footprint",
+        }
+    }
+}
+
+impl PlacedPin<'_> {
+    /// Describe the pin for a message, e.g. `U1 pin 3 (output)`.
+    fn describe(&self) -> String {
+        format!("{} pin {} ({})", self.reference, self.pin.number.number, self.pin.electrical_type.as_str())
+    }
+}
+
+/// Run an electrical rules check on a single sheet of a schematic.
+///
+/// Pins are located from the schematic's embedded library symbols (`lib_symbols`); symbols whose
+/// library symbol is missing are checked for references and footprints only. Pins are joined into
+/// nets by wires, by touching one another, and by local and global labels with the same text.
+/// Power symbols, whose references start with `#`, join their pins to the net named by their
+/// value. Hierarchical connections to other sheets are not followed.
+///
+/// `settings` may come from [`Project::erc_settings`](crate::Project::erc_settings). Its
+/// `rule_severities` drop rules set to `ignore` and override the default severity of the others,
+/// except for `pin_to_pin`, whose severity comes from the pin conflict matrix. Its `pin_map`, if
+/// complete, replaces [`DEFAULT_PIN_MAP`]. Diagnostics are returned by rule, in document order.
+pub fn check_erc(schematic: &Schematic, settings: &ErcSettings) -> Vec<ErcDiagnostic> {
+    let pins = placed_pins(schematic);
+    let nets = nets(schematic, &pins);
+    let mut diagnostics = Vec::new();
+
+    let mut net_sizes: HashMap<usize, usize> = HashMap::new();
+    for &net in &nets {
+        *net_sizes.entry(net).or_default() += 1;
+    }
+
+    let no_connects: HashSet<(i64, i64)> = schematic.no_connects.iter().map(|nc| point(&nc.position)).collect();
+    for (i, pin) in pins.iter().enumerate() {
+        let connected = net_sizes[&nets[i]] > 1
+            || schematic.wires.iter().any(|wire| on_path(&wire.points.points, pin.point))
+            || no_connects.contains(&pin.point)
+            || pin.pin.electrical_type == SymbolPinElectricalType::NoConnect;
+        if !connected {
+            diagnostics.push(ErcDiagnostic {
+                rule: ErcRule::PinNotConnected,
+                severity: Severity::Error,
+                message: format!("{} is not connected", pin.describe()),
+                uuids: vec![pin.uuid],
+            });
+        }
+    }
+
+    let mut units: BTreeMap<(&str, i64), Vec<Uuid>> = BTreeMap::new();
+    for instance in &schematic.symbols {
+        if let Some(reference) = annotated_reference(instance) {
+            units.entry((reference, instance.unit)).or_default().push(instance.uuid);
+        }
+    }
+    for ((reference, unit), uuids) in units {
+        if uuids.len() > 1 {
+            diagnostics.push(ErcDiagnostic {
+                rule: ErcRule::DuplicateReference,
+                severity: Severity::Error,
+                message: format!("{reference} unit {unit} is used by {} symbols", uuids.len()),
+                uuids,
+            });
+        }
+    }
+
+    let pin_map = pin_map(settings);
+    for (i, a) in pins.iter().enumerate() {
+        for (j, b) in pins.iter().enumerate().skip(i + 1) {
+            if nets[i] != nets[j] {
+                continue;
+            }
+
+            let (ta, tb) = (a.pin.electrical_type as usize, b.pin.electrical_type as usize);
+            let severity = match pin_map[ta][tb].max(pin_map[tb][ta]) {
+                0 => continue,
+                1 => Severity::Warning,
+                _ => Severity::Error,
+            };
+            diagnostics.push(ErcDiagnostic {
+                rule: ErcRule::PinToPin,
+                severity,
+                message: format!("{} is connected to {}", a.describe(), b.describe()),
+                uuids: vec![a.uuid, b.uuid],
+            });
+        }
+    }
+
+    for instance in &schematic.symbols {
+        let Some(reference) = annotated_reference(instance) else {
+            continue;
+        };
+        let footprint = instance.property("Footprint").unwrap_or_default();
+        if instance.on_board != Some(false) && footprint.trim().is_empty() {
+            diagnostics.push(ErcDiagnostic {
+                rule: ErcRule::MissingFootprint,
+                severity: Severity::Warning,
+                message: format!("{reference} has no footprint"),
+                uuids: vec![instance.uuid],
+            });
+        }
+    }
+
+    diagnostics.retain_mut(|diagnostic| match settings.rule_severities.get(diagnostic.rule.as_str()) {
+        Some(RuleSeverity::Ignore) => false,
+        Some(RuleSeverity::Warning) if diagnostic.rule != ErcRule::PinToPin => {
+            diagnostic.severity = Severity::Warning;
+            true
+        }
+        Some(RuleSeverity::Error) if diagnostic.rule != ErcRule::PinToPin => {
+            diagnostic.severity = Severity::Error;
+            true
+        }
+        _ => true,
+    });
+
+    diagnostics
+}
+
+/// Return the project's pin conflict matrix if it is complete, or KiCad's default.
+fn pin_map(settings: &ErcSettings) -> [[u8; PIN_TYPES]; PIN_TYPES] {
+    let mut pin_map = DEFAULT_PIN_MAP;
+    if settings.pin_map.len() == PIN_TYPES && settings.pin_map.iter().all(|row| row.len() == PIN_TYPES) {
+        for (row, settings_row) in pin_map.iter_mut().zip(&settings.pin_map) {
+            row.copy_from_slice(settings_row);
+        }
+    }
+    pin_map
+}
+
+/// Return the reference designator of a placed symbol, unless it is a power symbol or has not
+/// been annotated.
+fn annotated_reference(instance: &SchematicSymbolInstance) -> Option<&str> {
+    let reference = instance.property("Reference")?;
+    (!reference.is_empty() && !reference.starts_with('#') && !reference.ends_with('?')).then_some(reference)
+}
+
+/// Locate the pins of each placed symbol on the sheet. Only the pins the placed symbol lists are
+/// included, which selects the pins of its unit, unless it lists none.
+fn placed_pins(schematic: &Schematic) -> Vec<PlacedPin<'_>> {
+    let Ok(lib_symbols) = schematic.lib_symbols.get() else {
+        return Vec::new();
+    };
+
+    let mut pins = Vec::new();
+    for instance in &schematic.symbols {
+        let name = instance.lib_name.as_deref().unwrap_or(&instance.lib_id);
+        let Some(symbol) = lib_symbols.iter().find(|symbol| symbol.id == name) else {
+            continue;
+        };
+
+        let reference = instance.property("Reference").unwrap_or(name);
+        for pin in placed_symbol_pins(symbol, instance) {
+            let placed = instance.pins.iter().find(|placed| placed.number == pin.number.number);
+            pins.push(PlacedPin {
+                instance,
+                reference,
+                pin,
+                point: pin_point(instance, &pin.position),
+                uuid: placed.map_or(instance.uuid, |placed| placed.uuid),
+            });
+        }
+    }
+
+    pins
+}
+
+/// Return the pins of a library symbol that belong to a placed symbol.
+fn placed_symbol_pins<'a>(
+    symbol: &'a Symbol,
+    instance: &'a SchematicSymbolInstance,
+) -> impl Iterator<Item = &'a SymbolPin> + 'a {
+    symbol.unit_pins(Some(instance.unit)).filter(move |pin| {
+        instance.pins.is_empty() || instance.pins.iter().any(|placed| placed.number == pin.number.number)
+    })
+}
+
+/// Map the connection point of a library pin, in the symbol's Y-up coordinates, onto the sheet.
+/// Rotation is applied before mirroring, as in KiCad.
+fn pin_point(instance: &SchematicSymbolInstance, position: &Position) -> (i64, i64) {
+    let orientation = ParentOrientation {
+        angle: instance.position.angle.unwrap_or_default(),
+        mirror_x: instance.mirror == Some(SchematicSymbolMirror::X),
+        mirror_y: instance.mirror == Some(SchematicSymbolMirror::Y),
+    };
+
+    let (mut x, mut y) = orientation.angle.rotate(position.x.0, -position.y.0);
+    if orientation.mirror_y {
+        x = -x;
+    }
+    if orientation.mirror_x {
+        y = -y;
+    }
+
+    (instance.position.x.0 + x, instance.position.y.0 + y)
+}
+
+/// Return the coordinates of a position.
+fn point(position: &Position) -> (i64, i64) {
+    (position.x.0, position.y.0)
+}
+
+/// Join pins into nets, returning an identifier of the net of each pin.
+fn nets(schematic: &Schematic, pins: &[PlacedPin]) -> Vec<usize> {
+    let paths: Vec<&[Point]> = schematic.wires.iter().map(|wire| wire.points.points.as_slice()).collect();
+
+    // Nodes are the wires followed by the pins.
+    let pin_node = |i: usize| paths.len() + i;
+    let mut group: Vec<usize> = (0..paths.len() + pins.len()).collect();
+
+    fn root(group: &mut [usize], mut i: usize) -> usize {
+        while group[i] != i {
+            group[i] = group[group[i]];
+            i = group[i];
+        }
+        i
+    }
+
+    fn join(group: &mut [usize], a: usize, b: usize) {
+        let (a, b) = (root(group, a), root(group, b));
+        group[a] = b;
+    }
+
+    for (i, path) in paths.iter().enumerate() {
+        let ends = [path.first(), path.last()];
+        for (j, other) in paths.iter().enumerate() {
+            if i != j && ends.iter().flatten().any(|end| on_path(other, (end.x.0, end.y.0))) {
+                join(&mut group, i, j);
+            }
+        }
+    }
+
+    let mut at_point: HashMap<(i64, i64), usize> = HashMap::new();
+    for (i, pin) in pins.iter().enumerate() {
+        if let Some(wire) = paths.iter().position(|path| on_path(path, pin.point)) {
+            join(&mut group, wire, pin_node(i));
+        }
+        match at_point.get(&pin.point) {
+            Some(&other) => join(&mut group, other, pin_node(i)),
+            None => {
+                at_point.insert(pin.point, pin_node(i));
+            }
+        }
+    }
+
+    // Labels, and the pins of power symbols, join everything with the same name.
+    let labels = schematic.labels.iter().map(|label| (label.text.as_str(), point(&label.position)));
+    let global_labels = schematic.global_labels.iter().map(|label| (label.text.as_str(), point(&label.position)));
+    let power_pins = pins
+        .iter()
+        .filter(|pin| pin.reference.starts_with('#'))
+        .filter_map(|pin| Some((pin.instance.property("Value")?, pin.point)));
+
+    let mut named: HashMap<&str, usize> = HashMap::new();
+    for (text, position) in labels.chain(global_labels).chain(power_pins) {
+        let node = paths.iter().position(|path| on_path(path, position)).or_else(|| at_point.get(&position).copied());
+        let Some(node) = node else {
+            continue;
+        };
+        match named.get(text) {
+            Some(&other) => join(&mut group, other, node),
+            None => {
+                named.insert(text, node);
+            }
+        }
+    }
+
+    (0..pins.len()).map(|i| root(&mut group, pin_node(i))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::parse_str};
+
+    #[test]
+    fn test_check_erc() {
+        let text = r#"(kicad_sch (version 20231120) (generator "eeschema")
+            (lib_symbols
+                (symbol "Logic:BUF"
+                    (pin input line (at -5.08 0 0) (length 2.54) (name "A" (effects (font (size 1.27 1.27))))
+                        (number "1" (effects (font (size 1.27 1.27)))))
+                    (pin output line (at 5.08 0 180) (length 2.54) (name "Y" (effects (font (size 1.27 1.27))))
+                        (number "2" (effects (font (size 1.27 1.27)))))))
+            (no_connect (at -5.08 0) (uuid "00000000-0000-0000-0000-000000000001"))
+            (wire (pts (xy 5.08 0) (xy 24.92 0)) (stroke (width 0) (type default))
+                (uuid "00000000-0000-0000-0000-000000000002"))
+            (label "X" (at 10 0 0) (effects (font (size 1.27 1.27))) (uuid "00000000-0000-0000-0000-000000000003"))
+            (label "X" (at 45.08 0 0) (effects (font (size 1.27 1.27))) (uuid "00000000-0000-0000-0000-000000000004"))
+            (symbol (lib_id "Logic:BUF") (at 0 0 0) (unit 1) (uuid "00000000-0000-0000-0000-000000000010")
+                (property "Reference" "U1" (at 0 0 0)) (property "Footprint" "Package_SO:SOIC-8" (at 0 0 0))
+                (pin "1" (uuid "00000000-0000-0000-0000-000000000011"))
+                (pin "2" (uuid "00000000-0000-0000-0000-000000000012")))
+            (symbol (lib_id "Logic:BUF") (at 30 0 0) (unit 1) (uuid "00000000-0000-0000-0000-000000000020")
+                (property "Reference" "U2" (at 0 0 0)) (property "Footprint" "Package_SO:SOIC-8" (at 0 0 0))
+                (pin "1" (uuid "00000000-0000-0000-0000-000000000021"))
+                (pin "2" (uuid "00000000-0000-0000-0000-000000000022")))
+            (symbol (lib_id "Logic:BUF") (at 40 0 0) (unit 1) (uuid "00000000-0000-0000-0000-000000000030")
+                (property "Reference" "U2" (at 0 0 0)) (property "Footprint" "" (at 0 0 0))
+                (pin "1" (uuid "00000000-0000-0000-0000-000000000031"))
+                (pin "2" (uuid "00000000-0000-0000-0000-000000000032"))))"#;
+
+        let schematic: Schematic = parse_str(text).unwrap();
+        let diagnostics = check_erc(&schematic, &ErcSettings::default());
+        let found: Vec<(ErcRule, Vec<u128>)> =
+            diagnostics.iter().map(|d| (d.rule, d.uuids.iter().map(Uuid::as_u128).collect())).collect();
+
+        // U1 drives U2's input over the wire, and the label joins the last symbol's output to the
+        // same net. U1's input has a no-connect flag.
+        assert_eq!(
+            found,
+            [
+                (ErcRule::PinNotConnected, vec![0x22]),
+                (ErcRule::PinNotConnected, vec![0x31]),
+                (ErcRule::DuplicateReference, vec![0x20, 0x30]),
+                (ErcRule::PinToPin, vec![0x12, 0x32]),
+                (ErcRule::MissingFootprint, vec![0x30]),
+            ]
+        );
+        assert_eq!(diagnostics[3].severity, Severity::Error);
+
+        let settings = ErcSettings {
+            rule_severities: [("pin_not_connected".to_string(), RuleSeverity::Ignore)].into(),
+            ..Default::default()
+        };
+        let diagnostics = check_erc(&schematic, &settings);
+        assert!(diagnostics.iter().all(|d| d.rule != ErcRule::PinNotConnected));
+    }
+}
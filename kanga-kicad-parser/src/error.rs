@@ -0,0 +1,213 @@
+use {
+    crate::{span::parse_str, ParseError, SourceLocation},
+    std::{
+        error::Error as StdError,
+        fmt::{Display, Formatter, Result as FmtResult},
+        fs::read_to_string,
+        io::Error as IoError,
+        path::Path,
+    },
+};
+
+/// Any error from loading a document: reading the file, reading its s-expressions, or converting
+/// them into a model.
+///
+/// Each variant converts from the underlying error, so application code can use `?` across all
+/// three steps, and [`Error::source`](StdError::source) returns the underlying error.
+#[derive(Debug)]
+pub enum Error {
+    /// The file could not be read.
+    Io(IoError),
+
+    /// The text is not a well-formed s-expression.
+    Syntax(SyntaxError),
+
+    /// The s-expression does not describe a valid model.
+    Parse(ParseError),
+
+    /// A JSON document could not be read or written.
+    #[cfg(feature = "json")]
+    Json(serde_json::Error),
+}
+
+impl Error {
+    /// Return the underlying parse error, if this is one.
+    pub fn as_parse_error(&self) -> Option<&ParseError> {
+        match self {
+            Self::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// Return the location in the source text at which this error occurred, if known.
+    pub fn location(&self) -> Option<SourceLocation> {
+        match self {
+            Self::Syntax(e) => e.location,
+            Self::Parse(e) => e.location(),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::Syntax(e) => write!(f, "Syntax error: {e}"),
+            Self::Parse(e) => write!(f, "{e}"),
+            #[cfg(feature = "json")]
+            Self::Json(e) => write!(f, "Invalid JSON: {e}"),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Syntax(e) => Some(e),
+            Self::Parse(e) => Some(e),
+            #[cfg(feature = "json")]
+            Self::Json(e) => Some(e),
+        }
+    }
+}
+
+impl From<IoError> for Error {
+    fn from(e: IoError) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<SyntaxError> for Error {
+    fn from(e: SyntaxError) -> Self {
+        Self::Syntax(e)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+/// Text that is not a well-formed s-expression.
+#[derive(Clone, Debug)]
+pub struct SyntaxError {
+    /// Where in the text the problem was found, if known.
+    pub location: Option<SourceLocation>,
+
+    /// What is wrong with the text.
+    pub message: String,
+}
+
+impl SyntaxError {
+    pub fn new<S>(message: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            location: None,
+            message: message.into(),
+        }
+    }
+
+    /// Record the location in the source text at which this error occurred.
+    pub fn at(self, location: SourceLocation) -> Self {
+        Self {
+            location: Some(location),
+            ..self
+        }
+    }
+}
+
+impl Display for SyntaxError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self.location {
+            Some(location) => write!(f, "{location}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl StdError for SyntaxError {}
+
+/// An element that could not be converted and was skipped by a lenient parse.
+///
+/// When [`ParseOptions::strict`](crate::ParseOptions::strict) is false, a top-level element that
+/// fails to convert does not abort the parse. It is kept raw, so it is written back unchanged, and
+/// the reason is reported as a warning.
+#[derive(Debug)]
+pub struct ParseWarning {
+    /// The element that was skipped.
+    pub element: lexpr::Value,
+
+    /// Why the element could not be converted.
+    pub error: ParseError,
+}
+
+impl Display for ParseWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self.element.as_cons().and_then(|cons| cons.car().as_symbol()) {
+            Some(key) => write!(f, "Skipped {key}: {}", self.error),
+            None => write!(f, "Skipped element: {}", self.error),
+        }
+    }
+}
+
+/// Read a document from a file and convert it into a model. Parse errors record where in the file
+/// they occurred; see [`parse_str`](crate::parse_str).
+pub fn parse_file<T, P>(path: P) -> Result<T, Error>
+where
+    T: for<'a> TryFrom<&'a lexpr::Value, Error = ParseError>,
+    P: AsRef<Path>,
+{
+    parse_str(&read_to_string(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::sch::Schematic};
+
+    #[test]
+    fn test_error_conversions() {
+        fn load(path: &Path) -> Result<usize, Error> {
+            let schematic: Schematic = parse_file(path)?;
+            Ok(schematic.wires.len())
+        }
+
+        let err = load(Path::new("tests/missing.kicad_sch")).unwrap_err();
+        assert!(matches!(err, Error::Io(_)));
+        assert!(err.source().unwrap().downcast_ref::<IoError>().is_some());
+
+        let dir = std::env::temp_dir().join(format!("kanga-error-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("truncated.kicad_sch"), "(kicad_sch (version 20231120)").unwrap();
+        std::fs::write(dir.join("invalid.kicad_sch"), "(kicad_sch (version 20231120) (junction (bogus)))").unwrap();
+        let truncated = load(&dir.join("truncated.kicad_sch")).unwrap_err();
+        let invalid = load(&dir.join("invalid.kicad_sch")).unwrap_err();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(truncated, Error::Syntax(_)));
+        assert_eq!(truncated.location().map(|location| location.line), Some(1));
+        let parse_error = invalid.as_parse_error().unwrap();
+        assert_eq!(parse_error.location().map(|location| location.column), Some(41));
+        assert!(matches!(parse_error.root_cause(), ParseError::Unexpected(_)));
+
+        // The parse error chains down to its root cause.
+        let mut source = invalid.source();
+        let mut depth = 0;
+        while let Some(e) = source {
+            source = e.source();
+            depth += 1;
+        }
+        assert!(depth >= 2);
+    }
+}
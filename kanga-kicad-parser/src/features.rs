@@ -0,0 +1,361 @@
+use {
+    crate::{FormatVersion, LexprExt},
+    lexpr::Value,
+    serde::{Deserialize, Serialize},
+    std::collections::BTreeMap,
+};
+
+/// A KiCad major release, used to describe when file format features appeared and disappeared.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum KicadVersion {
+    /// KiCad 5, which used s-expressions only for boards and footprints.
+    V5,
+
+    /// KiCad 6.
+    V6,
+
+    /// KiCad 7.
+    V7,
+
+    /// KiCad 8.
+    V8,
+
+    /// KiCad 9.
+    V9,
+}
+
+/// A syntax feature of the KiCad s-expression formats.
+#[derive(Clone, Debug)]
+pub struct FormatFeature {
+    /// The feature identifier, e.g. `text-box`.
+    pub id: &'static str,
+
+    /// A human-readable description.
+    pub description: &'static str,
+
+    /// The first release that can read the feature.
+    pub since: KicadVersion,
+
+    /// The last release that writes the feature, if newer releases replaced it.
+    pub until: Option<KicadVersion>,
+
+    pattern: Pattern,
+}
+
+/// How a feature appears in a document.
+#[derive(Clone, Copy, Debug)]
+enum Pattern {
+    /// An element with the given head, anywhere.
+    Element(&'static str),
+
+    /// An element with the given head directly inside an element with another head.
+    Child(&'static str, &'static str),
+
+    /// An element with the given head and no arguments.
+    EmptyElement(&'static str),
+
+    /// An element with the given head and a `yes` or `no` argument.
+    FlagElement(&'static str),
+
+    /// A bare symbol inside a list, other than in head position.
+    Atom(&'static str),
+
+    /// The head of the document root.
+    Root(&'static str),
+}
+
+/// The format features KiCad has added or retired since the s-expression formats were introduced.
+pub const FORMAT_FEATURES: &[FormatFeature] = &[
+    FormatFeature::new(
+        "legacy-module",
+        "uses (module ...) footprints",
+        KicadVersion::V5,
+        Some(KicadVersion::V5),
+        Pattern::Root("module"),
+    ),
+    FormatFeature::new(
+        "tstamp",
+        "uses tstamp identifiers",
+        KicadVersion::V5,
+        Some(KicadVersion::V7),
+        Pattern::Element("tstamp"),
+    ),
+    FormatFeature::new(
+        "fp-text-fields",
+        "uses fp_text reference/value fields",
+        KicadVersion::V5,
+        Some(KicadVersion::V7),
+        Pattern::Child("footprint", "fp_text"),
+    ),
+    FormatFeature::new("dnp", "uses the dnp flag", KicadVersion::V7, None, Pattern::Element("dnp")),
+    FormatFeature::new("text-box", "uses text_box", KicadVersion::V7, None, Pattern::Element("text_box")),
+    FormatFeature::new(
+        "directive-label",
+        "uses directive labels",
+        KicadVersion::V7,
+        None,
+        Pattern::Element("directive_label"),
+    ),
+    FormatFeature::new(
+        "netclass-flag",
+        "uses netclass flags",
+        KicadVersion::V7,
+        None,
+        Pattern::Element("netclass_flag"),
+    ),
+    FormatFeature::new(
+        "empty-fields-autoplaced",
+        "uses the bare fields_autoplaced flag",
+        KicadVersion::V6,
+        Some(KicadVersion::V7),
+        Pattern::EmptyElement("fields_autoplaced"),
+    ),
+    FormatFeature::new(
+        "generator-version",
+        "records generator_version",
+        KicadVersion::V8,
+        None,
+        Pattern::Element("generator_version"),
+    ),
+    FormatFeature::new(
+        "exclude-from-sim",
+        "uses the exclude_from_sim flag",
+        KicadVersion::V8,
+        None,
+        Pattern::Element("exclude_from_sim"),
+    ),
+    FormatFeature::new(
+        "footprint-properties",
+        "uses footprint properties for fields",
+        KicadVersion::V8,
+        None,
+        Pattern::Child("footprint", "property"),
+    ),
+    FormatFeature::new("table", "uses tables", KicadVersion::V8, None, Pattern::Element("table")),
+    FormatFeature::new(
+        "legacy-hide-flag",
+        "uses the legacy hide flag",
+        KicadVersion::V6,
+        Some(KicadVersion::V8),
+        Pattern::Atom("hide"),
+    ),
+    FormatFeature::new("hide-element", "uses (hide yes|no)", KicadVersion::V9, None, Pattern::FlagElement("hide")),
+    FormatFeature::new("embedded-files", "embeds files", KicadVersion::V9, None, Pattern::Element("embedded_files")),
+    FormatFeature::new(
+        "embedded-fonts",
+        "uses the embedded_fonts flag",
+        KicadVersion::V9,
+        None,
+        Pattern::Element("embedded_fonts"),
+    ),
+    FormatFeature::new(
+        "schematic-rule-area",
+        "uses schematic rule areas",
+        KicadVersion::V9,
+        None,
+        Pattern::Child("kicad_sch", "rule_area"),
+    ),
+];
+
+/// A format feature found in a document.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FeatureUse {
+    /// The feature identifier, from [`FORMAT_FEATURES`].
+    pub id: String,
+
+    /// A human-readable description, e.g. `uses text_box`.
+    pub description: String,
+
+    /// The first release that can read the feature.
+    pub since: KicadVersion,
+
+    /// The last release that writes the feature, if newer releases replaced it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<KicadVersion>,
+
+    /// The number of times the feature appears.
+    pub count: usize,
+
+    /// The path of the first occurrence, e.g. `kicad_sch > symbol > dnp`.
+    pub first_path: String,
+}
+
+/// The format features a document uses.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FormatReport {
+    /// The format version the document declares, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub declared_version: Option<i64>,
+
+    /// The features found, in [`FORMAT_FEATURES`] order.
+    pub features: Vec<FeatureUse>,
+}
+
+impl KicadVersion {
+    /// Return the release that writes a file format version. Each KiCad file type has its own
+    /// version sequence, but all are dates and fall within the same windows for each release.
+    pub fn from_format_version(version: i64) -> Self {
+        Self::from(FormatVersion::nearest(version))
+    }
+}
+
+impl From<FormatVersion> for KicadVersion {
+    fn from(version: FormatVersion) -> Self {
+        match version {
+            FormatVersion::Kicad5 => Self::V5,
+            FormatVersion::Kicad6 => Self::V6,
+            FormatVersion::Kicad7 => Self::V7,
+            FormatVersion::Kicad8 => Self::V8,
+            FormatVersion::Kicad9 => Self::V9,
+        }
+    }
+}
+
+impl FormatFeature {
+    const fn new(
+        id: &'static str,
+        description: &'static str,
+        since: KicadVersion,
+        until: Option<KicadVersion>,
+        pattern: Pattern,
+    ) -> Self {
+        Self {
+            id,
+            description,
+            since,
+            until,
+            pattern,
+        }
+    }
+
+    /// Indicates whether an element, given its head, its arguments, and its parent's head, is an
+    /// occurrence of this feature.
+    fn matches_element(&self, head: &str, args: &Value, parent: Option<&str>) -> bool {
+        match self.pattern {
+            Pattern::Element(name) => head == name,
+            Pattern::Child(parent_name, name) => head == name && parent == Some(parent_name),
+            Pattern::EmptyElement(name) => head == name && args.is_null(),
+            Pattern::FlagElement(name) => {
+                head == name && args.as_cons().is_some_and(|cons| matches!(cons.car().as_symbol(), Some("yes" | "no")))
+            }
+            Pattern::Root(name) => head == name && parent.is_none(),
+            Pattern::Atom(_) => false,
+        }
+    }
+}
+
+impl FormatReport {
+    /// Return the oldest release that can open the document: the newest of the release that
+    /// writes the declared version and the releases that introduced each feature.
+    pub fn minimum_version(&self) -> KicadVersion {
+        let declared = self.declared_version.map(KicadVersion::from_format_version);
+        self.features.iter().map(|feature| feature.since).chain(declared).max().unwrap_or(KicadVersion::V5)
+    }
+
+    /// Return the newest release that still writes every feature the document uses, or `None` if
+    /// no feature has been retired. A document from this era or older uses syntax that newer
+    /// releases read but rewrite on save.
+    pub fn latest_era(&self) -> Option<KicadVersion> {
+        self.features.iter().filter_map(|feature| feature.until).min()
+    }
+}
+
+/// Report the format features a document uses, to determine the KiCad release needed to open it.
+pub fn detect_format_features(document: &Value) -> FormatReport {
+    let mut found: BTreeMap<usize, (usize, String)> = BTreeMap::new();
+    let mut path = Vec::new();
+    visit(document, None, &mut path, &mut found);
+
+    let declared_version = document.expect_cons_with_any_symbol_head().ok().and_then(|(_, rest)| {
+        rest.list_iter()?
+            .find_map(|element| element.expect_cons_with_symbol_head("version").ok()?.as_cons()?.car().as_i64())
+    });
+
+    let features = found
+        .into_iter()
+        .map(|(i, (count, first_path))| {
+            let feature = &FORMAT_FEATURES[i];
+            FeatureUse {
+                id: feature.id.to_string(),
+                description: feature.description.to_string(),
+                since: feature.since,
+                until: feature.until,
+                count,
+                first_path,
+            }
+        })
+        .collect();
+
+    FormatReport {
+        declared_version,
+        features,
+    }
+}
+
+/// Record the features used by an element and its descendants.
+fn visit<'a>(
+    value: &'a Value,
+    parent: Option<&str>,
+    path: &mut Vec<&'a str>,
+    found: &mut BTreeMap<usize, (usize, String)>,
+) {
+    let Ok((head, args)) = value.expect_cons_with_any_symbol_head() else {
+        return;
+    };
+
+    path.push(head);
+    for (i, feature) in FORMAT_FEATURES.iter().enumerate() {
+        if feature.matches_element(head, args, parent) {
+            record(found, i, path);
+        }
+    }
+
+    for child in args.list_iter().into_iter().flatten() {
+        if let Some(atom) = child.as_symbol() {
+            for (i, feature) in FORMAT_FEATURES.iter().enumerate() {
+                if matches!(feature.pattern, Pattern::Atom(name) if name == atom) {
+                    path.push(atom);
+                    record(found, i, path);
+                    path.pop();
+                }
+            }
+        } else {
+            visit(child, Some(head), path, found);
+        }
+    }
+
+    path.pop();
+}
+
+/// Count an occurrence of a feature, remembering the path of the first.
+fn record(found: &mut BTreeMap<usize, (usize, String)>, i: usize, path: &[&str]) {
+    found.entry(i).or_insert_with(|| (0, path.join(" > "))).0 += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, lexpr::sexp};
+
+    #[test]
+    fn test_detect_format_features() {
+        let schematic = sexp!((kicad_sch (version 20230121) (generator eeschema)
+            (symbol (lib_id "Device:R") (dnp no) (property "Value" "10k" (effects (font (size 1.27 1.27)) hide)))
+            (text_box "note")
+            (symbol (dnp yes))));
+
+        let report = detect_format_features(&schematic);
+        assert_eq!(report.declared_version, Some(20230121));
+
+        let ids: Vec<&str> = report.features.iter().map(|feature| feature.id.as_str()).collect();
+        assert_eq!(ids, ["dnp", "text-box", "legacy-hide-flag"]);
+        assert_eq!(report.features[0].count, 2);
+        assert_eq!(report.features[2].first_path, "kicad_sch > symbol > property > effects > hide");
+        assert_eq!(report.minimum_version(), KicadVersion::V7);
+        assert_eq!(report.latest_era(), Some(KicadVersion::V8));
+
+        let footprint = sexp!((module "R_0603" (layer "F.Cu") (tstamp 1)));
+        let report = detect_format_features(&footprint);
+        assert_eq!(report.minimum_version(), KicadVersion::V5);
+        assert_eq!(report.latest_era(), Some(KicadVersion::V5));
+    }
+}
@@ -0,0 +1,470 @@
+use {
+    crate::common::{nm_to_mm_value, sexpr_list, Angle, Font, LineStyle, Nm, Position, Size, Stroke, TextEffects},
+    kanga_sexpr::ToSexpr,
+    lexpr::Value,
+    serde::{Deserialize, Serialize},
+};
+
+/// The grid that pad dimensions and courtyards are rounded to, in nanometers.
+const ROUNDING: i64 = 10_000;
+
+/// The clearance between silkscreen and copper, in nanometers.
+const SILK_CLEARANCE: i64 = 200_000;
+
+/// Row letters for ball grid arrays, skipping those JEDEC excludes.
+const BGA_ROWS: &str = "ABCDEFGHJKLMNPRTUVWY";
+
+/// An IPC-7351 land pattern density level.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DensityLevel {
+    /// Level A: large lands for hand soldering and rework.
+    Most,
+
+    /// Level B: the default for reflow soldering.
+    #[default]
+    Nominal,
+
+    /// Level C: small lands for high-density boards.
+    Least,
+}
+
+/// A chip component with a terminal at each end, such as a resistor or capacitor. Dimensions
+/// are nominal, in nanometers.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ChipSpec {
+    /// The length of the body, terminal to terminal.
+    pub body_length: i64,
+
+    /// The width of the body.
+    pub body_width: i64,
+
+    /// The length of each terminal along the body.
+    pub terminal_length: i64,
+}
+
+/// A quad flat package with gull-wing leads on four sides. Dimensions are nominal, in nanometers.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct QfpSpec {
+    /// The number of leads on each side.
+    pub pins_per_side: u32,
+
+    /// The distance between adjacent leads.
+    pub pitch: i64,
+
+    /// The distance between the tips of opposite leads.
+    pub lead_span: i64,
+
+    /// The length of the flat part of each lead.
+    pub lead_length: i64,
+
+    /// The width of each lead.
+    pub lead_width: i64,
+
+    /// The width and height of the square body.
+    pub body_size: i64,
+}
+
+/// A ball grid array. Dimensions are nominal, in nanometers.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BgaSpec {
+    /// The number of ball columns, numbered from 1.
+    pub columns: u32,
+
+    /// The number of ball rows, lettered from `A`.
+    pub rows: u32,
+
+    /// The distance between adjacent balls.
+    pub pitch: i64,
+
+    /// The diameter of each ball.
+    pub ball_diameter: i64,
+
+    /// The width and height of the square body.
+    pub body_size: i64,
+}
+
+/// The package to generate a footprint for.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FootprintSpec {
+    Chip(ChipSpec),
+    Qfp(QfpSpec),
+    Bga(BgaSpec),
+}
+
+/// Solder fillet goals and courtyard excess for a density level, in nanometers.
+struct Fillets {
+    toe: i64,
+    heel: i64,
+    side: i64,
+    courtyard: i64,
+}
+
+/// A generated pad, with its center and size in nanometers.
+struct Land {
+    number: String,
+    x: i64,
+    y: i64,
+    width: i64,
+    height: i64,
+}
+
+impl DensityLevel {
+    /// Return the fillet goals for chip components larger than 0603.
+    fn chip_fillets(self) -> Fillets {
+        match self {
+            Self::Most => Fillets::new(550_000, 0, 50_000, 500_000),
+            Self::Nominal => Fillets::new(350_000, 0, 0, 250_000),
+            Self::Least => Fillets::new(150_000, 0, -50_000, 100_000),
+        }
+    }
+
+    /// Return the fillet goals for gull-wing leads at the given pitch.
+    fn gull_wing_fillets(self, pitch: i64) -> Fillets {
+        // Fine-pitch leads get less side fillet so adjacent lands keep their clearance.
+        let (most, nominal, least) = if pitch <= 625_000 {
+            (10_000, -20_000, -40_000)
+        } else {
+            (50_000, 30_000, 10_000)
+        };
+
+        match self {
+            Self::Most => Fillets::new(550_000, 450_000, most, 500_000),
+            Self::Nominal => Fillets::new(350_000, 350_000, nominal, 250_000),
+            Self::Least => Fillets::new(150_000, 250_000, least, 100_000),
+        }
+    }
+
+    /// Return the BGA land diameter as a percentage of the ball diameter, and the courtyard excess.
+    fn bga_lands(self) -> (i64, i64) {
+        match self {
+            Self::Most => (85, 2_000_000),
+            Self::Nominal => (80, 1_000_000),
+            Self::Least => (75, 500_000),
+        }
+    }
+}
+
+impl Fillets {
+    fn new(toe: i64, heel: i64, side: i64, courtyard: i64) -> Self {
+        Self {
+            toe,
+            heel,
+            side,
+            courtyard,
+        }
+    }
+
+    /// Return the land length and the distance from the package center to the land center for
+    /// terminals whose outer ends are `span` apart.
+    fn land(&self, span: i64, terminal_length: i64) -> (i64, i64) {
+        let outer = span + 2 * self.toe;
+        let inner = span - 2 * terminal_length - 2 * self.heel;
+        (round(outer - inner) / 2, round(outer + inner) / 4)
+    }
+}
+
+/// Generate a footprint for a package.
+///
+/// The footprint is returned as a `(footprint ...)` element with copper pads, a fabrication
+/// outline, a courtyard, and silkscreen that keeps clear of the pads. Land sizes follow the
+/// IPC-7351 fillet goals for the density level, computed from nominal dimensions without
+/// tolerance stacking.
+pub fn generate_footprint(name: &str, spec: &FootprintSpec, density: DensityLevel) -> Value {
+    let (lands, round_pads, body, fillets) = match spec {
+        FootprintSpec::Chip(chip) => {
+            let fillets = density.chip_fillets();
+            (chip_lands(chip, &fillets), false, (chip.body_length, chip.body_width), fillets)
+        }
+        FootprintSpec::Qfp(qfp) => {
+            let fillets = density.gull_wing_fillets(qfp.pitch);
+            (qfp_lands(qfp, &fillets), false, (qfp.body_size, qfp.body_size), fillets)
+        }
+        FootprintSpec::Bga(bga) => {
+            let (percent, courtyard) = density.bga_lands();
+            (bga_lands(bga, percent), true, (bga.body_size, bga.body_size), Fillets::new(0, 0, 0, courtyard))
+        }
+    };
+
+    let (half_body_x, half_body_y) = (body.0 / 2, body.1 / 2);
+    let extent_x = lands.iter().map(|land| land.x.abs() + land.width / 2).max().unwrap_or_default().max(half_body_x);
+    let extent_y = lands.iter().map(|land| land.y.abs() + land.height / 2).max().unwrap_or_default().max(half_body_y);
+    let courtyard_x = round_up(extent_x + fillets.courtyard);
+    let courtyard_y = round_up(extent_y + fillets.courtyard);
+
+    let mut items = vec![
+        Value::string(name),
+        sexpr_list("layer", [Value::string("F.Cu")]),
+        sexpr_list("attr", [Value::symbol("smd")]),
+        field("Reference", "REF**", -courtyard_y - 1_000_000, "F.SilkS"),
+        field("Value", name, courtyard_y + 1_000_000, "F.Fab"),
+    ];
+
+    for (start, end) in silkscreen(spec, &lands, half_body_x, half_body_y) {
+        items.push(graphic("fp_line", start, end, 120_000, "F.SilkS"));
+    }
+    items.push(graphic("fp_rect", (-half_body_x, -half_body_y), (half_body_x, half_body_y), 100_000, "F.Fab"));
+    items.push(graphic("fp_rect", (-courtyard_x, -courtyard_y), (courtyard_x, courtyard_y), 50_000, "F.CrtYd"));
+
+    for land in &lands {
+        let mut pad = vec![
+            Value::string(land.number.as_str()),
+            Value::symbol("smd"),
+            Value::symbol(if round_pads {
+                "circle"
+            } else {
+                "roundrect"
+            }),
+            xy("at", land.x, land.y),
+            xy("size", land.width, land.height),
+            sexpr_list("layers", ["F.Cu", "F.Paste", "F.Mask"].map(Value::string)),
+        ];
+        if !round_pads {
+            pad.push(sexpr_list("roundrect_rratio", [Value::from(0.25)]));
+        }
+        items.push(sexpr_list("pad", pad));
+    }
+
+    sexpr_list("footprint", items)
+}
+
+/// Return the two lands of a chip component, pad 1 on the left.
+fn chip_lands(chip: &ChipSpec, fillets: &Fillets) -> Vec<Land> {
+    let (length, center) = fillets.land(chip.body_length, chip.terminal_length);
+    let width = round(chip.body_width + 2 * fillets.side);
+
+    [-center, center]
+        .into_iter()
+        .enumerate()
+        .map(|(i, x)| Land {
+            number: (i + 1).to_string(),
+            x,
+            y: 0,
+            width: length,
+            height: width,
+        })
+        .collect()
+}
+
+/// Return the lands of a quad flat package, numbered counterclockwise from the top of the left
+/// side.
+fn qfp_lands(qfp: &QfpSpec, fillets: &Fillets) -> Vec<Land> {
+    let (length, center) = fillets.land(qfp.lead_span, qfp.lead_length);
+    let width = round(qfp.lead_width + 2 * fillets.side);
+    let first = -(qfp.pins_per_side as i64 - 1) * qfp.pitch / 2;
+    let mut lands = Vec::new();
+
+    for side in 0..4 {
+        for i in 0..qfp.pins_per_side as i64 {
+            let along = first + i * qfp.pitch;
+            let (x, y) = match side {
+                0 => (-center, along),
+                1 => (along, center),
+                2 => (center, -along),
+                _ => (-along, -center),
+            };
+            let (w, h) = if side % 2 == 0 {
+                (length, width)
+            } else {
+                (width, length)
+            };
+
+            lands.push(Land {
+                number: (lands.len() + 1).to_string(),
+                x,
+                y,
+                width: w,
+                height: h,
+            });
+        }
+    }
+
+    lands
+}
+
+/// Return the lands of a ball grid array, with `A1` at the top left.
+fn bga_lands(bga: &BgaSpec, percent: i64) -> Vec<Land> {
+    let diameter = round(bga.ball_diameter * percent / 100);
+    let left = -(bga.columns as i64 - 1) * bga.pitch / 2;
+    let top = -(bga.rows as i64 - 1) * bga.pitch / 2;
+    let mut lands = Vec::new();
+
+    for row in 0..bga.rows as i64 {
+        for column in 0..bga.columns as i64 {
+            lands.push(Land {
+                number: format!("{}{}", bga_row_name(row as usize), column + 1),
+                x: left + column * bga.pitch,
+                y: top + row * bga.pitch,
+                width: diameter,
+                height: diameter,
+            });
+        }
+    }
+
+    lands
+}
+
+/// Return the JEDEC name of a BGA row: `A` through `Y`, then `AA`, `AB`, and so on.
+fn bga_row_name(row: usize) -> String {
+    let letters: Vec<char> = BGA_ROWS.chars().collect();
+    let n = letters.len();
+    if row < n {
+        letters[row].to_string()
+    } else {
+        format!("{}{}", letters[row / n - 1], letters[row % n])
+    }
+}
+
+/// Return the silkscreen line segments for a package. Chip components get lines along the body
+/// sides, and packages with pads on all sides get corner marks outside the pad rows.
+fn silkscreen(spec: &FootprintSpec, lands: &[Land], half_x: i64, half_y: i64) -> Vec<((i64, i64), (i64, i64))> {
+    let offset = 110_000;
+
+    match spec {
+        FootprintSpec::Chip(_) => {
+            let y = lands
+                .iter()
+                .map(|land| land.height / 2 + SILK_CLEARANCE)
+                .max()
+                .unwrap_or_default()
+                .max(half_y + offset);
+            let x = lands.iter().map(|land| land.x.abs() - land.width / 2 - SILK_CLEARANCE).min().unwrap_or_default();
+            if x <= 0 {
+                return Vec::new();
+            }
+            vec![((-x, -y), (x, -y)), ((-x, y), (x, y))]
+        }
+        FootprintSpec::Qfp(qfp) => {
+            let clear = (qfp.pins_per_side as i64 - 1) * qfp.pitch / 2 + qfp.lead_width / 2 + SILK_CLEARANCE;
+            let corner = half_x + offset;
+            if half_x <= clear {
+                return Vec::new();
+            }
+
+            let mut lines = Vec::new();
+            for (sx, sy) in [(-1, -1), (1, -1), (1, 1), (-1, 1)] {
+                lines.push(((sx * clear, sy * corner), (sx * corner, sy * corner)));
+                lines.push(((sx * corner, sy * clear), (sx * corner, sy * corner)));
+            }
+            lines
+        }
+        FootprintSpec::Bga(_) => {
+            let (x, y) = (half_x + offset, half_y + offset);
+            vec![((-x, -y), (x, -y)), ((x, -y), (x, y)), ((x, y), (-x, y)), ((-x, y), (-x, -y))]
+        }
+    }
+}
+
+/// Build a footprint property with the standard 1 mm text.
+fn field(key: &str, value: &str, y: i64, layer: &str) -> Value {
+    let effects = TextEffects {
+        font: Some(Font {
+            face: None,
+            size: Size::new(Nm(1_000_000), Nm(1_000_000)),
+            thickness: Some(150_000),
+            bold: false,
+            italic: false,
+            line_spacing: None,
+            color: None,
+        }),
+        justify: None,
+        hide: false,
+    };
+    let position = Position {
+        x: Nm::ZERO,
+        y: Nm(y),
+        angle: Some(Angle::ZERO),
+    };
+
+    sexpr_list(
+        "property",
+        [
+            Value::string(key),
+            Value::string(value),
+            position.to_value(),
+            sexpr_list("layer", [Value::string(layer)]),
+            effects.to_value(),
+        ],
+    )
+}
+
+/// Build an `fp_line` or `fp_rect` between two points.
+fn graphic(head: &str, start: (i64, i64), end: (i64, i64), width: i64, layer: &str) -> Value {
+    let stroke = Stroke {
+        width: Some(Nm(width)),
+        line_style: Some(LineStyle::Solid),
+        color: None,
+    };
+
+    sexpr_list(
+        head,
+        [
+            xy("start", start.0, start.1),
+            xy("end", end.0, end.1),
+            stroke.to_value(),
+            sexpr_list("layer", [Value::string(layer)]),
+        ],
+    )
+}
+
+/// Build a `(<head> x y)` element from nanometers.
+fn xy(head: &str, x: i64, y: i64) -> Value {
+    sexpr_list(head, [nm_to_mm_value(x), nm_to_mm_value(y)])
+}
+
+/// Round to the nearest multiple of [`ROUNDING`].
+fn round(value: i64) -> i64 {
+    (value as f64 / ROUNDING as f64).round() as i64 * ROUNDING
+}
+
+/// Round a non-negative value up to a multiple of [`ROUNDING`].
+fn round_up(value: i64) -> i64 {
+    (value + ROUNDING - 1) / ROUNDING * ROUNDING
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::lint::{lint_footprint, FootprintLintOptions},
+    };
+
+    #[test]
+    fn test_generate_footprint() {
+        let options = FootprintLintOptions::default();
+
+        let r0805 = FootprintSpec::Chip(ChipSpec {
+            body_length: 2_000_000,
+            body_width: 1_250_000,
+            terminal_length: 400_000,
+        });
+        let footprint = generate_footprint("R_0805", &r0805, DensityLevel::Nominal);
+        assert!(lint_footprint(&footprint, &options).unwrap().is_empty());
+        let text = footprint.to_string();
+        assert!(text.contains("(pad \"1\" smd roundrect (at -0.975 0.0) (size 0.75 1.25)"), "{text}");
+
+        let qfp = FootprintSpec::Qfp(QfpSpec {
+            pins_per_side: 12,
+            pitch: 500_000,
+            lead_span: 9_000_000,
+            lead_length: 600_000,
+            lead_width: 220_000,
+            body_size: 7_000_000,
+        });
+        let footprint = generate_footprint("LQFP-48", &qfp, DensityLevel::Most);
+        assert!(lint_footprint(&footprint, &options).unwrap().is_empty());
+        assert!(footprint.to_string().contains("(pad \"48\""));
+
+        let bga = FootprintSpec::Bga(BgaSpec {
+            columns: 22,
+            rows: 22,
+            pitch: 800_000,
+            ball_diameter: 400_000,
+            body_size: 19_000_000,
+        });
+        let footprint = generate_footprint("BGA-484", &bga, DensityLevel::Least);
+        assert!(lint_footprint(&footprint, &options).unwrap().is_empty());
+        assert!(footprint.to_string().contains("(pad \"AB22\" smd circle (at 8.4 8.4) (size 0.3 0.3)"));
+    }
+}
@@ -0,0 +1,196 @@
+use {
+    crate::{pcb::Pcb, LexprExt},
+    lexpr::Value,
+};
+
+/// The coordinate system an exported coordinate is expressed in.
+///
+/// KiCad stores coordinates relative to the top left corner of the page, with Y increasing
+/// downwards. Fabrication files and most CAD tools instead expect Y to increase upwards, often
+/// measured from the board's auxiliary axis origin. Every exporter takes a frame so the same
+/// conventions apply to all of its outputs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct CoordinateFrame {
+    /// The point that becomes (0, 0).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub origin: FrameOrigin,
+
+    /// The direction of increasing Y.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub y_axis: YAxis,
+}
+
+/// The point a [`CoordinateFrame`] measures from.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum FrameOrigin {
+    /// The top left corner of the page, as KiCad stores coordinates.
+    #[default]
+    Page,
+
+    /// The board's auxiliary axis (drill and place file) origin. Falls back to the page origin if
+    /// the board does not set one.
+    AuxAxis,
+
+    /// The board's grid origin. Falls back to the page origin if the board does not set one.
+    Grid,
+
+    /// A point in page coordinates, in nanometers.
+    Custom {
+        /// X coordinate in nanometers.
+        x: i64,
+
+        /// Y coordinate in nanometers.
+        y: i64,
+    },
+}
+
+/// The direction of increasing Y in a [`CoordinateFrame`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum YAxis {
+    /// Y increases down the page, as KiCad stores coordinates and as SVG expects.
+    #[default]
+    Down,
+
+    /// Y increases up the page, as fabrication files and PDF expect.
+    Up,
+}
+
+/// The origins a board defines in its `setup` section, in page coordinates in nanometers.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BoardOrigins {
+    /// The auxiliary axis origin, from `aux_axis_origin`.
+    pub aux_axis: Option<(i64, i64)>,
+
+    /// The grid origin, from `grid_origin`.
+    pub grid: Option<(i64, i64)>,
+}
+
+impl CoordinateFrame {
+    /// KiCad's own frame: the page origin with Y increasing downwards.
+    pub const KICAD: Self = Self {
+        origin: FrameOrigin::Page,
+        y_axis: YAxis::Down,
+    };
+
+    /// The page origin with Y increasing upwards, as KiCad writes position files by default.
+    pub const PAGE_Y_UP: Self = Self {
+        origin: FrameOrigin::Page,
+        y_axis: YAxis::Up,
+    };
+
+    /// Create a frame.
+    pub fn new(origin: FrameOrigin, y_axis: YAxis) -> Self {
+        Self {
+            origin,
+            y_axis,
+        }
+    }
+
+    /// Convert a point from page coordinates into this frame. Both are in nanometers.
+    pub fn apply(&self, origins: &BoardOrigins, x: i64, y: i64) -> (i64, i64) {
+        let (origin_x, origin_y) = match self.origin {
+            FrameOrigin::Page => (0, 0),
+            FrameOrigin::AuxAxis => origins.aux_axis.unwrap_or_default(),
+            FrameOrigin::Grid => origins.grid.unwrap_or_default(),
+            FrameOrigin::Custom {
+                x,
+                y,
+            } => (x, y),
+        };
+
+        let y = y - origin_y;
+        let y = match self.y_axis {
+            YAxis::Down => y,
+            YAxis::Up => -y,
+        };
+
+        (x - origin_x, y)
+    }
+}
+
+impl Default for CoordinateFrame {
+    fn default() -> Self {
+        Self::KICAD
+    }
+}
+
+impl BoardOrigins {
+    /// Read the origins from the top-level elements of a board, such as
+    /// [`Pcb::raw_sections`](crate::pcb::Pcb::raw_sections).
+    pub fn from_elements<'a, I>(elements: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Value>,
+    {
+        let mut origins = Self::default();
+
+        for element in elements {
+            let Ok(setup) = element.expect_cons_with_symbol_head("setup") else {
+                continue;
+            };
+
+            for item in setup.list_iter().into_iter().flatten() {
+                let Ok((key, cdr)) = item.expect_cons_with_any_symbol_head() else {
+                    continue;
+                };
+
+                let point = || {
+                    let (x, cdr) = cdr.expect_cons_with_any_length_head().ok()?;
+                    let (y, _) = cdr.expect_cons_with_any_length_head().ok()?;
+                    Some((x.0, y.0))
+                };
+
+                match key {
+                    "aux_axis_origin" => origins.aux_axis = point(),
+                    "grid_origin" => origins.grid = point(),
+                    _ => (),
+                }
+            }
+        }
+
+        origins
+    }
+
+    /// Read the origins from a parsed board, whose `setup` section is kept in
+    /// [`Pcb::raw_sections`](crate::pcb::Pcb::raw_sections).
+    pub fn from_pcb(pcb: &Pcb) -> Self {
+        Self::from_elements(&pcb.raw_sections)
+    }
+
+    /// Read the origins from a whole `(kicad_pcb ...)` document.
+    pub fn from_board(board: &Value) -> Self {
+        let elements = board.expect_cons_with_symbol_head("kicad_pcb").ok().and_then(Value::list_iter);
+        Self::from_elements(elements.into_iter().flatten())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, lexpr::sexp};
+
+    #[test]
+    fn test_coordinate_frame() {
+        let board = sexp!((kicad_pcb (version 20240108) (setup (aux_axis_origin 100 150) (grid_origin 50 50.5))));
+        let origins = BoardOrigins::from_board(&board);
+        assert_eq!(origins.aux_axis, Some((100_000_000, 150_000_000)));
+        assert_eq!(origins.grid, Some((50_000_000, 50_500_000)));
+
+        let point = (110_000_000, 140_000_000);
+        assert_eq!(CoordinateFrame::KICAD.apply(&origins, point.0, point.1), point);
+        assert_eq!(CoordinateFrame::PAGE_Y_UP.apply(&origins, point.0, point.1), (110_000_000, -140_000_000));
+
+        let aux = CoordinateFrame::new(FrameOrigin::AuxAxis, YAxis::Up);
+        assert_eq!(aux.apply(&origins, point.0, point.1), (10_000_000, 10_000_000));
+
+        // A board without a setup section measures from the page.
+        assert_eq!(aux.apply(&BoardOrigins::default(), point.0, point.1), (110_000_000, -140_000_000));
+    }
+}
@@ -0,0 +1,394 @@
+use {
+    crate::{
+        common::{
+            Angle, HorizJustify, Point, Stroke, Symbol, SymbolGraphic, SymbolGraphicArc, SymbolGraphicBezier,
+            SymbolGraphicCircle, SymbolGraphicPolyline, SymbolGraphicRectangle, SymbolGraphicText, SymbolPin,
+            VertJustify,
+        },
+        metrics::measure_text,
+        sch::{SchematicBus, SchematicWire},
+    },
+    std::f64::consts::{FRAC_PI_2, TAU},
+};
+
+/// An axis-aligned rectangle in nanometers, inclusive of its edges.
+///
+/// Extents are given in the coordinates of the file an element came from: library symbols use a
+/// Y-up coordinate system and schematics a Y-down one, so a box computed for a symbol must be
+/// flipped and placed before it is compared with schematic elements.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct BoundingBox {
+    /// The smallest X coordinate.
+    pub min_x: i64,
+
+    /// The smallest Y coordinate.
+    pub min_y: i64,
+
+    /// The largest X coordinate.
+    pub max_x: i64,
+
+    /// The largest Y coordinate.
+    pub max_y: i64,
+}
+
+impl BoundingBox {
+    /// Create an empty box at a point.
+    pub fn from_point(x: i64, y: i64) -> Self {
+        Self {
+            min_x: x,
+            min_y: y,
+            max_x: x,
+            max_y: y,
+        }
+    }
+
+    /// Return the smallest box containing all of the points, or `None` if there are none.
+    pub fn from_points<I>(points: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = (i64, i64)>,
+    {
+        let mut points = points.into_iter();
+        let (x, y) = points.next()?;
+        let mut bbox = Self::from_point(x, y);
+        for (x, y) in points {
+            bbox.include(x, y);
+        }
+        Some(bbox)
+    }
+
+    /// Return the width of the box.
+    pub fn width(&self) -> i64 {
+        self.max_x - self.min_x
+    }
+
+    /// Return the height of the box.
+    pub fn height(&self) -> i64 {
+        self.max_y - self.min_y
+    }
+
+    /// Return the center of the box.
+    pub fn center(&self) -> (i64, i64) {
+        ((self.min_x + self.max_x) / 2, (self.min_y + self.max_y) / 2)
+    }
+
+    /// Grow the box to contain a point.
+    pub fn include(&mut self, x: i64, y: i64) {
+        self.min_x = self.min_x.min(x);
+        self.min_y = self.min_y.min(y);
+        self.max_x = self.max_x.max(x);
+        self.max_y = self.max_y.max(y);
+    }
+
+    /// Return the smallest box containing both boxes.
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            min_x: self.min_x.min(other.min_x),
+            min_y: self.min_y.min(other.min_y),
+            max_x: self.max_x.max(other.max_x),
+            max_y: self.max_y.max(other.max_y),
+        }
+    }
+
+    /// Return the box grown by `margin` on every side.
+    pub fn inflated(self, margin: i64) -> Self {
+        Self {
+            min_x: self.min_x - margin,
+            min_y: self.min_y - margin,
+            max_x: self.max_x + margin,
+            max_y: self.max_y + margin,
+        }
+    }
+
+    /// Indicates whether the box contains a point.
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        (self.min_x..=self.max_x).contains(&x) && (self.min_y..=self.max_y).contains(&y)
+    }
+
+    /// Indicates whether the box overlaps or touches another.
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min_x <= other.max_x && other.min_x <= self.max_x && self.min_y <= other.max_y && other.min_y <= self.max_y
+    }
+
+    /// Return the box enclosing this one rotated about the origin, using the conventions of
+    /// [`Angle::rotate`].
+    pub fn rotated(self, angle: Angle) -> Self {
+        let corners =
+            [(self.min_x, self.min_y), (self.max_x, self.min_y), (self.min_x, self.max_y), (self.max_x, self.max_y)];
+        Self::from_points(corners.map(|(x, y)| angle.rotate(x, y))).unwrap_or(self)
+    }
+
+    /// Return the box moved by an offset.
+    pub fn translated(self, dx: i64, dy: i64) -> Self {
+        Self {
+            min_x: self.min_x + dx,
+            min_y: self.min_y + dy,
+            max_x: self.max_x + dx,
+            max_y: self.max_y + dy,
+        }
+    }
+}
+
+impl Symbol {
+    /// Return the box enclosing the graphics and pins of the symbol and its units, or `None` if it
+    /// has neither.
+    pub fn bbox(&self) -> Option<BoundingBox> {
+        let graphics = self.graphics.iter().filter_map(SymbolGraphic::bbox);
+        let pins = self.pins.iter().map(SymbolPin::bbox);
+        let units = self.units.iter().filter_map(Symbol::bbox);
+        graphics.chain(pins).chain(units).reduce(BoundingBox::union)
+    }
+}
+
+impl SymbolGraphic {
+    /// Return the box enclosing the graphic, including half its stroke width, or `None` if it has
+    /// no points.
+    pub fn bbox(&self) -> Option<BoundingBox> {
+        match self {
+            Self::Arc(arc) => Some(arc.bbox()),
+            Self::Bezier(bezier) => bezier.bbox(),
+            Self::Circle(circle) => Some(circle.bbox()),
+            Self::Polyline(polyline) => polyline.bbox(),
+            Self::Rectangle(rectangle) => Some(rectangle.bbox()),
+            Self::Text(text) => Some(text.bbox()),
+        }
+    }
+}
+
+impl SymbolGraphicArc {
+    /// Return the box enclosing the arc, including half its stroke width.
+    ///
+    /// The box reaches past the end points wherever the arc crosses an axis of its circle, and
+    /// degenerate arcs whose points are collinear are treated as lines through them.
+    pub fn bbox(&self) -> BoundingBox {
+        let points = [&self.start, &self.mid, &self.end].map(|point| (point.x.0 as f64, point.y.0 as f64));
+        let mut bbox = BoundingBox::from_points(points.map(round_point)).expect("an arc has points");
+
+        if let Some((cx, cy)) = circumcenter(points[0], points[1], points[2]) {
+            let radius = (points[0].0 - cx).hypot(points[0].1 - cy);
+            let angle = |(x, y): (f64, f64)| (y - cy).atan2(x - cx);
+            let (start, mid, end) = (angle(points[0]), angle(points[1]), angle(points[2]));
+
+            // Sweep counterclockwise from whichever end point reaches the midpoint first.
+            let (from, to) = if sweep(start, mid) <= sweep(start, end) {
+                (start, end)
+            } else {
+                (end, start)
+            };
+
+            for quarter in 0..4 {
+                let axis = quarter as f64 * FRAC_PI_2;
+                if sweep(from, axis) <= sweep(from, to) {
+                    let (sin, cos) = axis.sin_cos();
+                    bbox.include((cx + radius * cos).round() as i64, (cy + radius * sin).round() as i64);
+                }
+            }
+        }
+
+        bbox.inflated(half_width(&self.stroke))
+    }
+}
+
+impl SymbolGraphicBezier {
+    /// Return the box enclosing the curve, including half its stroke width, or `None` if it has no
+    /// points.
+    ///
+    /// Cubic curves are bounded by their end points and turning points rather than by their
+    /// control points; curves with any other number of points are bounded by their points.
+    pub fn bbox(&self) -> Option<BoundingBox> {
+        let points: Vec<(f64, f64)> =
+            self.points.points.iter().map(|point| (point.x.0 as f64, point.y.0 as f64)).collect();
+        let mut bbox = BoundingBox::from_points(points.iter().copied().map(round_point))?;
+
+        if let [p0, p1, p2, p3] = points[..] {
+            let axis = |p0: f64, p1: f64, p2: f64, p3: f64| {
+                // The roots of the derivative, scaled by 1/3: a t² + b t + c.
+                let (a, b, c) = (-p0 + 3.0 * p1 - 3.0 * p2 + p3, 2.0 * (p0 - 2.0 * p1 + p2), p1 - p0);
+                quadratic_roots(a, b, c)
+            };
+
+            let mut ts = axis(p0.0, p1.0, p2.0, p3.0);
+            ts.extend(axis(p0.1, p1.1, p2.1, p3.1));
+            for t in ts.into_iter().filter(|t| (0.0..=1.0).contains(t)) {
+                let u = 1.0 - t;
+                let at = |p0: f64, p1: f64, p2: f64, p3: f64| {
+                    u * u * u * p0 + 3.0 * u * u * t * p1 + 3.0 * u * t * t * p2 + t * t * t * p3
+                };
+                let (x, y) = (at(p0.0, p1.0, p2.0, p3.0), at(p0.1, p1.1, p2.1, p3.1));
+                bbox.include(x.round() as i64, y.round() as i64);
+            }
+        }
+
+        Some(bbox.inflated(half_width(&self.stroke)))
+    }
+}
+
+impl SymbolGraphicCircle {
+    /// Return the box enclosing the circle, including half its stroke width.
+    pub fn bbox(&self) -> BoundingBox {
+        BoundingBox::from_point(self.center.x.0, self.center.y.0).inflated(self.radius.0 + half_width(&self.stroke))
+    }
+}
+
+impl SymbolGraphicPolyline {
+    /// Return the box enclosing the polyline, including half its stroke width, or `None` if it has
+    /// no points.
+    pub fn bbox(&self) -> Option<BoundingBox> {
+        points_bbox(&self.points.points, &self.stroke)
+    }
+}
+
+impl SymbolGraphicRectangle {
+    /// Return the box enclosing the rectangle, including half its stroke width.
+    pub fn bbox(&self) -> BoundingBox {
+        let corners = [(self.start.x.0, self.start.y.0), (self.end.x.0, self.end.y.0)];
+        BoundingBox::from_points(corners).expect("a rectangle has corners").inflated(half_width(&self.stroke))
+    }
+}
+
+impl SymbolGraphicText {
+    /// Return the box enclosing the text as measured by [`measure_text`], placed by its
+    /// justification and rotated by the angle of its position.
+    pub fn bbox(&self) -> BoundingBox {
+        let size = measure_text(&self.text, &self.text_effects);
+        let (width, height) = (size.width.0, size.height.0);
+        let (horiz_justify, vert_justify) = match &self.text_effects.justify {
+            Some(justify) if justify.mirror => (justify.horiz_justify.flipped(), justify.vert_justify),
+            Some(justify) => (justify.horiz_justify, justify.vert_justify),
+            None => (HorizJustify::default(), VertJustify::default()),
+        };
+
+        // Library symbols are Y-up, so text justified to the top hangs below its anchor.
+        let (min_x, max_x) = match horiz_justify {
+            HorizJustify::Left => (0, width),
+            HorizJustify::Center => (-width / 2, width - width / 2),
+            HorizJustify::Right => (-width, 0),
+        };
+        let (min_y, max_y) = match vert_justify {
+            VertJustify::Top => (-height, 0),
+            VertJustify::Center => (-height / 2, height - height / 2),
+            VertJustify::Bottom => (0, height),
+        };
+
+        let angle = self.position.angle.unwrap_or_default();
+        let corners = [(min_x, min_y), (max_x, min_y), (min_x, max_y), (max_x, max_y)];
+        BoundingBox::from_points(corners.map(|(x, y)| rotate_y_up(angle, x, y)))
+            .expect("text has corners")
+            .translated(self.position.x.0, self.position.y.0)
+    }
+}
+
+impl SymbolPin {
+    /// Return the box enclosing the pin's line, from its connection point to where it meets the
+    /// body. Names and numbers are not included.
+    pub fn bbox(&self) -> BoundingBox {
+        let (dx, dy) = rotate_y_up(self.position.angle.unwrap_or_default(), self.length.0, 0);
+        let points = [(self.position.x.0, self.position.y.0), (self.position.x.0 + dx, self.position.y.0 + dy)];
+        BoundingBox::from_points(points).expect("a pin has end points")
+    }
+}
+
+impl SchematicWire {
+    /// Return the box enclosing the wire, including half its stroke width, or `None` if it has no
+    /// points.
+    pub fn bbox(&self) -> Option<BoundingBox> {
+        points_bbox(&self.points.points, &self.stroke)
+    }
+}
+
+impl SchematicBus {
+    /// Return the box enclosing the bus, including half its stroke width, or `None` if it has no
+    /// points.
+    pub fn bbox(&self) -> Option<BoundingBox> {
+        points_bbox(&self.points.points, &self.stroke)
+    }
+}
+
+/// Return the box enclosing a path of points, including half its stroke width.
+fn points_bbox(points: &[Point], stroke: &Stroke) -> Option<BoundingBox> {
+    let bbox = BoundingBox::from_points(points.iter().map(|point| (point.x.0, point.y.0)))?;
+    Some(bbox.inflated(half_width(stroke)))
+}
+
+/// Return half the width of a stroke, which extends the outline of what it draws.
+fn half_width(stroke: &Stroke) -> i64 {
+    stroke.width.unwrap_or_default().0.max(0) / 2
+}
+
+/// Rotate a point counterclockwise in a Y-up coordinate system, as used by library symbols.
+fn rotate_y_up(angle: Angle, x: i64, y: i64) -> (i64, i64) {
+    let (x, y) = angle.rotate(x, -y);
+    (x, -y)
+}
+
+/// Return the counterclockwise angle from `from` to `to`, in `[0, 2π)`.
+fn sweep(from: f64, to: f64) -> f64 {
+    (to - from).rem_euclid(TAU)
+}
+
+/// Return the center of the circle through three points, or `None` if they are collinear.
+pub(crate) fn circumcenter(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> Option<(f64, f64)> {
+    let d = 2.0 * (a.0 * (b.1 - c.1) + b.0 * (c.1 - a.1) + c.0 * (a.1 - b.1));
+    if d == 0.0 {
+        return None;
+    }
+
+    let (a2, b2, c2) = (a.0 * a.0 + a.1 * a.1, b.0 * b.0 + b.1 * b.1, c.0 * c.0 + c.1 * c.1);
+    let x = (a2 * (b.1 - c.1) + b2 * (c.1 - a.1) + c2 * (a.1 - b.1)) / d;
+    let y = (a2 * (c.0 - b.0) + b2 * (a.0 - c.0) + c2 * (b.0 - a.0)) / d;
+    Some((x, y))
+}
+
+/// Return the real roots of `a t² + b t + c`.
+fn quadratic_roots(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a.abs() < f64::EPSILON {
+        return if b == 0.0 {
+            Vec::new()
+        } else {
+            vec![-c / b]
+        };
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return Vec::new();
+    }
+
+    let root = discriminant.sqrt();
+    vec![(-b + root) / (2.0 * a), (-b - root) / (2.0 * a)]
+}
+
+/// Round a point to whole nanometers.
+fn round_point((x, y): (f64, f64)) -> (i64, i64) {
+    (x.round() as i64, y.round() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::parse_str};
+
+    #[test]
+    fn test_symbol_bbox() {
+        let symbol: Symbol = parse_str(
+            r#"(symbol "Demo"
+                (arc (start 0 -1) (mid -1 0) (end 0 1) (stroke (width 0) (type default)) (fill (type none)))
+                (rectangle (start 0 -1) (end 2 1) (stroke (width 0.2) (type default)) (fill (type none)))
+                (pin input line (at -3 0 0) (length 2) (name "A" (effects (font (size 1.27 1.27))))
+                    (number "1" (effects (font (size 1.27 1.27))))))"#,
+        )
+        .unwrap();
+
+        // The arc bulges left to -1 mm; the pin reaches from -3 mm to -1 mm; the rectangle's
+        // stroke adds 0.1 mm around it.
+        let arc = symbol.graphics[0].bbox().unwrap();
+        assert_eq!((arc.min_x, arc.max_x, arc.min_y, arc.max_y), (-1_000_000, 0, -1_000_000, 1_000_000));
+        assert_eq!(symbol.pins[0].bbox().max_x, -1_000_000);
+
+        let bbox = symbol.bbox().unwrap();
+        assert_eq!((bbox.min_x, bbox.min_y, bbox.max_x, bbox.max_y), (-3_000_000, -1_100_000, 2_100_000, 1_100_000));
+
+        let rotated = BoundingBox::from_points([(0, 0), (2, 1)]).unwrap().rotated(Angle::from_degrees(90.0));
+        assert_eq!(rotated, BoundingBox::from_points([(0, 0), (1, -2)]).unwrap());
+    }
+}
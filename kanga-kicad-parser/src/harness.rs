@@ -0,0 +1,202 @@
+use {
+    crate::{bundle::csv_field, impact::board_nets, pcb::Pcb},
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        fmt::Write as _,
+    },
+};
+
+/// Options for [`wiring_list`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct HarnessOptions {
+    /// Reference designator prefixes that identify connectors. A reference matches if it is one of
+    /// these prefixes followed by a digit, so `P` matches `P1` but not `PS1`.
+    pub connector_prefixes: Vec<String>,
+}
+
+/// One end of a wire: a connector pin.
+#[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct WireEnd {
+    /// The connector reference designator.
+    pub connector: String,
+
+    /// The connector pin number.
+    pub pin: String,
+}
+
+/// A wire between two connector pins.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Wire {
+    /// The net the wire carries.
+    pub net: String,
+
+    /// The end on the connector that sorts first.
+    pub from: WireEnd,
+
+    /// The other end.
+    pub to: WireEnd,
+}
+
+/// A connector-to-connector wiring list.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct WiringList {
+    /// The wires, ordered by net name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub wires: Vec<Wire>,
+}
+
+impl Default for HarnessOptions {
+    fn default() -> Self {
+        Self {
+            connector_prefixes: ["J", "P", "X"].map(String::from).to_vec(),
+        }
+    }
+}
+
+impl HarnessOptions {
+    /// Indicates whether a reference designator belongs to a connector.
+    pub fn is_connector(&self, reference: &str) -> bool {
+        self.connector_prefixes.iter().any(|prefix| {
+            reference.strip_prefix(prefix.as_str()).is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+        })
+    }
+}
+
+/// Build the wiring between the connectors on a board from the nets that span them.
+///
+/// Each net that reaches pins on two or more connectors becomes a star of wires from its first
+/// connector pin to the pins on every other connector. Additional pins of the first connector on the
+/// same net are assumed to be bridged at that connector and get no wires of their own.
+pub fn wiring_list(board: &Pcb, options: &HarnessOptions) -> WiringList {
+    let mut wires = Vec::new();
+
+    for (net, nodes) in board_nets(board) {
+        let mut ends = nodes.into_iter().filter(|(reference, _)| options.is_connector(reference));
+        let Some((connector, pin)) = ends.next() else {
+            continue;
+        };
+
+        let from = WireEnd {
+            connector,
+            pin,
+        };
+
+        for (connector, pin) in ends.filter(|(connector, _)| *connector != from.connector) {
+            wires.push(Wire {
+                net: net.clone(),
+                from: from.clone(),
+                to: WireEnd {
+                    connector,
+                    pin,
+                },
+            });
+        }
+    }
+
+    WiringList {
+        wires,
+    }
+}
+
+impl WiringList {
+    /// Render the wiring list as CSV with `Net,From,From Pin,To,To Pin` columns.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("Net,From,From Pin,To,To Pin\n");
+        for wire in &self.wires {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{}",
+                csv_field(wire.net.trim_start_matches('/')),
+                csv_field(&wire.from.connector),
+                csv_field(&wire.from.pin),
+                csv_field(&wire.to.connector),
+                csv_field(&wire.to.pin)
+            );
+        }
+        csv
+    }
+
+    /// Render the wiring list as a [WireViz](https://github.com/wireviz/WireViz) harness description.
+    ///
+    /// Each pair of connected connectors gets one cable, `W1`, `W2`, ..., with a wire per net.
+    pub fn to_wireviz(&self) -> String {
+        let mut pins: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        let mut cables: BTreeMap<(&str, &str), Vec<&Wire>> = BTreeMap::new();
+
+        for wire in &self.wires {
+            pins.entry(&wire.from.connector).or_default().insert(&wire.from.pin);
+            pins.entry(&wire.to.connector).or_default().insert(&wire.to.pin);
+            cables.entry((&wire.from.connector, &wire.to.connector)).or_default().push(wire);
+        }
+
+        let mut yaml = String::from("connectors:\n");
+        for (connector, pins) in &pins {
+            let _ = writeln!(yaml, "  {}:\n    pins: {}", quote(connector), quote_list(pins.iter().copied()));
+        }
+
+        yaml.push_str("cables:\n");
+        for (i, wires) in cables.values().enumerate() {
+            let labels = wires.iter().map(|wire| wire.net.trim_start_matches('/'));
+            let _ =
+                writeln!(yaml, "  W{}:\n    wirecount: {}\n    wirelabels: {}", i + 1, wires.len(), quote_list(labels));
+        }
+
+        yaml.push_str("connections:\n");
+        for (i, ((from, to), wires)) in cables.iter().enumerate() {
+            let numbers = (1..=wires.len()).map(|n| n.to_string()).collect::<Vec<_>>().join(", ");
+            let _ = writeln!(
+                yaml,
+                "  -\n    - {}: {}\n    - W{}: [{numbers}]\n    - {}: {}",
+                quote(from),
+                quote_list(wires.iter().map(|wire| wire.from.pin.as_str())),
+                i + 1,
+                quote(to),
+                quote_list(wires.iter().map(|wire| wire.to.pin.as_str())),
+            );
+        }
+
+        yaml
+    }
+}
+
+/// Quote a string for YAML. JSON strings are valid YAML flow scalars.
+fn quote(s: &str) -> String {
+    serde_json::Value::from(s).to_string()
+}
+
+/// Render a YAML flow sequence of quoted strings.
+fn quote_list<'a, I>(items: I) -> String
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    format!("[{}]", items.into_iter().map(quote).collect::<Vec<_>>().join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, lexpr::sexp};
+
+    #[test]
+    fn test_wiring_list() {
+        let board = sexp!((kicad_pcb
+            (footprint "Conn" (layer "F.Cu") (property "Reference" "J1")
+                (pad "1" thru_hole circle (at 0 0) (size 1.7 1.7) (net 1 "/CAN_H"))
+                (pad "2" thru_hole circle (at 2.54 0) (size 1.7 1.7) (net 2 "GND")))
+            (footprint "Conn" (layer "F.Cu") (property "Reference" "J2")
+                (pad "3" thru_hole circle (at 0 0) (size 1.7 1.7) (net 1 "/CAN_H"))
+                (pad "4" thru_hole circle (at 2.54 0) (size 1.7 1.7) (net 2 "GND")))
+            (footprint "R" (layer "F.Cu") (property "Reference" "R1") (pad "1" smd rect (at 0 0) (size 1 1) (net 1 "/CAN_H")))
+            (footprint "PSU" (layer "F.Cu") (property "Reference" "PS1") (pad "1" smd rect (at 0 0) (size 1 1) (net 2 "GND")))));
+        let board = Pcb::try_from(&board).unwrap();
+
+        let list = wiring_list(&board, &HarnessOptions::default());
+        assert_eq!(list.wires.len(), 2);
+        assert_eq!(list.to_csv(), "Net,From,From Pin,To,To Pin\nCAN_H,J1,1,J2,3\nGND,J1,2,J2,4\n");
+
+        let yaml = list.to_wireviz();
+        assert!(yaml.contains("  \"J2\":\n    pins: [\"3\", \"4\"]\n"));
+        assert!(yaml.contains("    wirecount: 2\n    wirelabels: [\"CAN_H\", \"GND\"]\n"));
+        assert!(yaml.contains("    - \"J1\": [\"1\", \"2\"]\n    - W1: [1, 2]\n    - \"J2\": [\"3\", \"4\"]\n"));
+    }
+}
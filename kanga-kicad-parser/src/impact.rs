@@ -0,0 +1,300 @@
+use {
+    crate::{
+        bom::{Bom, BomOptions},
+        diff::diff_schematics,
+        pcb::Pcb,
+        sch::Schematic,
+        LexprExt, Project,
+    },
+    lexpr::Value,
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        path::{Path, PathBuf},
+    },
+};
+
+/// The impact of the changes between two revisions of a project.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ImpactReport {
+    /// Schematics present only in the new revision, relative to the project directory.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sheets_added: Vec<PathBuf>,
+
+    /// Schematics present only in the old revision, relative to the project directory.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sheets_removed: Vec<PathBuf>,
+
+    /// Schematics present in both revisions whose contents differ, relative to the project directory.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sheets_modified: Vec<PathBuf>,
+
+    /// Components present in both revisions whose footprint changed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub footprint_changes: Vec<FootprintChange>,
+
+    /// Changes to the bill of materials.
+    pub bom: BomDelta,
+
+    /// Nets on the board whose set of connected pads changed, including added and removed nets.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub affected_nets: Vec<String>,
+}
+
+/// A component whose footprint assignment changed.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FootprintChange {
+    /// The component reference designator.
+    pub reference: String,
+
+    /// The footprint in the old revision.
+    pub old: String,
+
+    /// The footprint in the new revision.
+    pub new: String,
+}
+
+/// Changes to the bill of materials between two revisions.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BomDelta {
+    /// Components present only in the new revision.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added: Vec<BomLine>,
+
+    /// Components present only in the old revision.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<BomLine>,
+
+    /// Components present in both revisions whose value, footprint, or DNP flag changed, as
+    /// `(old, new)`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changed: Vec<(BomLine, BomLine)>,
+}
+
+/// A component as it appears in the bill of materials.
+#[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct BomLine {
+    /// The reference designator, e.g. `R1`.
+    pub reference: String,
+
+    /// The component value, e.g. `10k`.
+    pub value: String,
+
+    /// The footprint, e.g. `Resistor_SMD:R_0603_1608Metric`.
+    pub footprint: String,
+
+    /// Whether the component is marked "do not populate".
+    #[serde(default)]
+    pub dnp: bool,
+}
+
+impl ImpactReport {
+    /// Indicates whether the revisions differ in any way covered by the report.
+    pub fn is_empty(&self) -> bool {
+        self.sheets_added.is_empty()
+            && self.sheets_removed.is_empty()
+            && self.sheets_modified.is_empty()
+            && self.bom.added.is_empty()
+            && self.bom.removed.is_empty()
+            && self.bom.changed.is_empty()
+            && self.affected_nets.is_empty()
+    }
+}
+
+/// Report the impact of the changes from the `old` revision of a project to the `new` one.
+///
+/// Components are matched by reference designator. Nets are compared only if both revisions have a
+/// board.
+pub fn analyze_impact(old: &Project, new: &Project) -> ImpactReport {
+    let mut report = ImpactReport::default();
+
+    let old_sheets = relative_schematics(old);
+    let new_sheets = relative_schematics(new);
+
+    for (path, old_sheet) in &old_sheets {
+        match new_sheets.get(path) {
+            None => report.sheets_removed.push(path.to_path_buf()),
+            Some(new_sheet) if !diff_schematics(old_sheet, new_sheet).is_empty() => {
+                report.sheets_modified.push(path.to_path_buf())
+            }
+            Some(_) => (),
+        }
+    }
+
+    for path in new_sheets.keys().filter(|path| !old_sheets.contains_key(*path)) {
+        report.sheets_added.push(path.to_path_buf());
+    }
+
+    let old_bom = components(old);
+    let new_bom = components(new);
+
+    for (reference, old_line) in &old_bom {
+        match new_bom.get(reference) {
+            None => report.bom.removed.push(old_line.clone()),
+            Some(new_line) if new_line != old_line => {
+                if new_line.footprint != old_line.footprint {
+                    report.footprint_changes.push(FootprintChange {
+                        reference: reference.clone(),
+                        old: old_line.footprint.clone(),
+                        new: new_line.footprint.clone(),
+                    });
+                }
+                report.bom.changed.push((old_line.clone(), new_line.clone()));
+            }
+            Some(_) => (),
+        }
+    }
+
+    for (reference, new_line) in &new_bom {
+        if !old_bom.contains_key(reference) {
+            report.bom.added.push(new_line.clone());
+        }
+    }
+
+    if let (Some(old_board), Some(new_board)) = (&old.board, &new.board) {
+        let old_nets = board_nets(old_board);
+        let new_nets = board_nets(new_board);
+        let names: BTreeSet<&String> = old_nets.keys().chain(new_nets.keys()).collect();
+        report.affected_nets =
+            names.into_iter().filter(|name| old_nets.get(*name) != new_nets.get(*name)).cloned().collect();
+    }
+
+    report
+}
+
+/// Return a project's schematics keyed by their path relative to the project directory.
+fn relative_schematics(project: &Project) -> BTreeMap<&Path, &Schematic> {
+    project
+        .schematics
+        .iter()
+        .map(|(path, schematic)| (path.strip_prefix(&project.dir).unwrap_or(path), schematic))
+        .collect()
+}
+
+/// Return the components in a project's bill of materials, keyed by reference designator.
+///
+/// The components are those of [`Bom::from_project`], including ones marked "do not populate", so a
+/// symbol used on several sheet instances contributes each of its references, and symbols excluded
+/// from the BOM are skipped.
+pub(crate) fn components(project: &Project) -> BTreeMap<String, BomLine> {
+    let options = BomOptions {
+        include_dnp: true,
+        ..BomOptions::default()
+    };
+
+    Bom::from_project(project, &options)
+        .components
+        .into_iter()
+        .map(|component| {
+            let line = BomLine {
+                reference: component.reference.clone(),
+                value: component.property("Value").to_string(),
+                footprint: component.property("Footprint").to_string(),
+                dnp: component.dnp,
+            };
+            (component.reference, line)
+        })
+        .collect()
+}
+
+/// Return the pads connected to each named net on a board, as `(reference, pad)` pairs.
+pub(crate) fn board_nets(board: &Pcb) -> BTreeMap<String, BTreeSet<(String, String)>> {
+    let mut result: BTreeMap<String, BTreeSet<(String, String)>> = BTreeMap::new();
+
+    for footprint in &board.footprints {
+        let reference = footprint.properties.get("Reference").cloned().unwrap_or_default();
+
+        for pad in &footprint.pads {
+            if let Some(net) = pad.net.as_ref().filter(|net| !net.name.is_empty()) {
+                result.entry(net.name.clone()).or_default().insert((reference.clone(), pad.number.clone()));
+            }
+        }
+    }
+
+    result
+}
+
+/// Return the value of a `(property "<name>" "<value>" ...)` child.
+pub(crate) fn string_property<'a>(rest: &'a Value, name: &str) -> Option<&'a str> {
+    rest.list_iter()?.find_map(|element| {
+        let cdr = element.expect_cons_with_symbol_head("property").ok()?;
+        let (key, cdr) = cdr.expect_cons_with_any_str_head().ok()?;
+        let (value, _) = cdr.expect_cons_with_any_str_head().ok()?;
+        (key == name).then_some(value)
+    })
+}
+
+/// Return the reference from a KiCad 6/7 `(fp_text reference "<value>" ...)` child.
+pub(crate) fn fp_text_reference(rest: &Value) -> Option<&str> {
+    rest.list_iter()?.find_map(|element| {
+        let cdr = element.expect_cons_with_symbol_head("fp_text").ok()?;
+        cdr.expect_cons_with_symbol_head("reference").ok()?.as_cons()?.car().as_str()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, lexpr::sexp};
+
+    fn project(schematic: Value, board: Value) -> Project {
+        let schematic = Schematic::try_from(&schematic).unwrap();
+        let board = Pcb::try_from(&board).unwrap();
+        Project {
+            dir: PathBuf::from("/demo"),
+            name: "demo".to_string(),
+            settings: serde_json::Value::Null,
+            root_schematic: PathBuf::from("/demo/demo.kicad_sch"),
+            schematics: [(PathBuf::from("/demo/demo.kicad_sch"), schematic)].into_iter().collect(),
+            sheets: Vec::new(),
+            board: Some(board),
+            symbol_lib_table: None,
+            footprint_lib_table: None,
+            worksheets: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_impact() {
+        let old = project(
+            sexp!((kicad_sch
+                (symbol (lib_id "Device:R") (at 0 0 0) (uuid "0c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")
+                    (property "Reference" "R1") (property "Value" "10k") (property "Footprint" "R_0603"))
+                (symbol (lib_id "Device:C") (at 0 0 0) (uuid "1c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")
+                    (property "Reference" "C1") (property "Value" "1u") (property "Footprint" "C_0603"))
+                (symbol (lib_id "power:GND") (at 0 0 0) (uuid "2c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")
+                    (property "Reference" "#PWR01") (property "Value" "GND")))),
+            sexp!((kicad_pcb
+                (footprint "R_0603" (layer "F.Cu") (property "Reference" "R1")
+                    (pad "1" smd rect (at 0 0) (size 1 1) (net 1 "VCC")) (pad "2" smd rect (at 1 0) (size 1 1) (net 2 "A")))
+                (footprint "C_0603" (layer "F.Cu") (property "Reference" "C1")
+                    (pad "1" smd rect (at 0 0) (size 1 1) (net 2 "A")) (pad "2" smd rect (at 1 0) (size 1 1) (net 3 "GND"))))),
+        );
+
+        let new = project(
+            sexp!((kicad_sch
+                (symbol (lib_id "Device:R") (at 0 0 0) (dnp yes) (uuid "0c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")
+                    (property "Reference" "R1") (property "Value" "10k") (property "Footprint" "R_0402"))
+                (symbol (lib_id "Device:R") (at 0 0 0) (uuid "3c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")
+                    (property "Reference" "R2") (property "Value" "1k") (property "Footprint" "R_0402")
+                    (instances (project "demo"
+                        (path "/a" (reference "R2") (unit 1)) (path "/b" (reference "R3") (unit 1)))))
+                (symbol (lib_id "Mechanical:MountingHole") (at 0 0 0) (in_bom no)
+                    (uuid "4c6b985a-999e-45ae-b1a7-41cfbe8ba8fa") (property "Reference" "H1")))),
+            sexp!((kicad_pcb
+                (footprint "R_0402" (layer "F.Cu") (property "Reference" "R1")
+                    (pad "1" smd rect (at 0 0) (size 1 1) (net 1 "VCC")) (pad "2" smd rect (at 1 0) (size 1 1) (net 2 "A")))
+                (footprint "R_0402" (layer "F.Cu") (property "Reference" "R2")
+                    (pad "1" smd rect (at 0 0) (size 1 1) (net 2 "A")) (pad "2" smd rect (at 1 0) (size 1 1) (net 3 "GND"))))),
+        );
+
+        let report = analyze_impact(&old, &new);
+        assert_eq!(report.sheets_modified, vec![PathBuf::from("demo.kicad_sch")]);
+        assert_eq!(report.footprint_changes.len(), 1);
+        assert_eq!(report.footprint_changes[0].new, "R_0402");
+        let added: Vec<&str> = report.bom.added.iter().map(|line| line.reference.as_str()).collect();
+        assert_eq!(added, vec!["R2", "R3"]);
+        assert_eq!(report.bom.removed[0].reference, "C1");
+        assert!(report.bom.changed[0].1.dnp);
+        assert_eq!(report.affected_nets, vec!["A", "GND"]);
+    }
+}
@@ -0,0 +1,439 @@
+use crate::{Error, SourceLocation, SyntaxError};
+
+/// Top-level elements that are skipped, rather than ending the scan, when they appear among the
+/// header elements.
+const SKIPPED_ELEMENTS: &[&str] = &["general", "host"];
+
+/// The title block fields of a [`DocumentHeader`].
+///
+/// Text is borrowed from the document exactly as it appears between the quotes, so escape
+/// sequences such as `\"` are not processed.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct TitleBlockHeader<'a> {
+    /// The title.
+    pub title: Option<&'a str>,
+
+    /// The date.
+    pub date: Option<&'a str>,
+
+    /// The revision.
+    pub rev: Option<&'a str>,
+
+    /// The company name.
+    pub company: Option<&'a str>,
+
+    /// Comments 1 through 9, at indices 0 through 8.
+    pub comments: [Option<&'a str>; 9],
+}
+
+/// Metadata from the start of a KiCad document, read without parsing the rest of it.
+///
+/// Text is borrowed from the document exactly as it appears, so escape sequences are not
+/// processed.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DocumentHeader<'a> {
+    /// The head of the root element, e.g. `kicad_sch` or `footprint`.
+    pub kind: &'a str,
+
+    /// The file format version.
+    pub version: Option<i64>,
+
+    /// The program that wrote the file.
+    pub generator: Option<&'a str>,
+
+    /// The version of the program that wrote the file (KiCad 8+).
+    pub generator_version: Option<&'a str>,
+
+    /// The document UUID.
+    pub uuid: Option<&'a str>,
+
+    /// The paper size name, e.g. `A4` or `User`.
+    pub paper: Option<&'a str>,
+
+    /// Whether the paper is in portrait orientation.
+    pub portrait: bool,
+
+    /// The title block.
+    pub title_block: Option<TitleBlockHeader<'a>>,
+}
+
+/// The identifying fields of a symbol placed on a schematic, read by [`scan_symbols`].
+///
+/// Text is borrowed from the document exactly as it appears, so escape sequences are not
+/// processed.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SymbolSummary<'a> {
+    /// The library symbol, as `library:symbol`.
+    pub lib_id: Option<&'a str>,
+
+    /// The `Reference` property, e.g. `R1`.
+    pub reference: Option<&'a str>,
+
+    /// The `Value` property.
+    pub value: Option<&'a str>,
+
+    /// The `Footprint` property.
+    pub footprint: Option<&'a str>,
+
+    /// The unit of a multi-unit symbol, starting at 1.
+    pub unit: Option<i64>,
+
+    /// Whether the symbol is included in the bill of materials.
+    pub in_bom: Option<bool>,
+
+    /// Whether the symbol is marked do-not-populate.
+    pub dnp: Option<bool>,
+}
+
+/// A cursor over the text of a document.
+struct Scanner<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+/// A token read by a [`Scanner`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Token<'a> {
+    Open,
+    Close,
+    Str(&'a str),
+    Atom(&'a str),
+}
+
+/// Read the header of a KiCad s-expression document: the root element's version, generator,
+/// UUID, paper, and title block.
+///
+/// Scanning stops at the first top-level element that is not part of the header, such as
+/// `lib_symbols` or `layers`, so the cost does not depend on the size of the document and
+/// nothing is allocated. The text may be a prefix of a file; a header cut off by the end of the
+/// text holds the fields read up to that point.
+pub fn inspect_header(text: &str) -> Result<DocumentHeader<'_>, Error> {
+    let mut scanner = Scanner {
+        text,
+        pos: 0,
+    };
+
+    if scanner.next()? != Some(Token::Open) {
+        return Err(scanner.error("expected a list"));
+    }
+    let Some(Token::Atom(kind)) = scanner.next()? else {
+        return Err(scanner.error("expected the root element name"));
+    };
+
+    let mut header = DocumentHeader {
+        kind,
+        ..DocumentHeader::default()
+    };
+
+    read_header(&mut scanner, &mut header)?;
+    Ok(header)
+}
+
+/// Read the reference, value, and footprint of each symbol placed on a schematic, in document
+/// order.
+///
+/// This is much faster than converting the schematic for tools that only need an inventory of
+/// parts: nothing is allocated but the returned list, and `lib_symbols`, wires, and the graphics
+/// and pins of each symbol are skipped over without being read.
+pub fn scan_symbols(text: &str) -> Result<Vec<SymbolSummary<'_>>, Error> {
+    let mut scanner = Scanner {
+        text,
+        pos: 0,
+    };
+
+    if scanner.next()? != Some(Token::Open) || scanner.atom()? != Some("kicad_sch") {
+        return Err(scanner.error("expected a kicad_sch document"));
+    }
+
+    let mut symbols = Vec::new();
+    loop {
+        match scanner.next()? {
+            None | Some(Token::Close) => return Ok(symbols),
+            Some(Token::Open) => (),
+            Some(_) => continue,
+        }
+
+        if scanner.atom()? == Some("symbol") {
+            let mut symbol = SymbolSummary::default();
+            read_symbol(&mut scanner, &mut symbol)?;
+            symbols.push(symbol);
+        } else {
+            scanner.skip_rest()?;
+        }
+    }
+}
+
+/// Read header elements into `header`. The end of the text is treated as the end of the header.
+fn read_header<'a>(scanner: &mut Scanner<'a>, header: &mut DocumentHeader<'a>) -> Result<(), Error> {
+    loop {
+        match scanner.next()? {
+            None | Some(Token::Close) => return Ok(()),
+            Some(Token::Open) => (),
+            // Footprints have their name as the first argument.
+            Some(Token::Str(_) | Token::Atom(_)) => continue,
+        }
+
+        let Some(Token::Atom(head)) = scanner.next()? else {
+            return Err(scanner.error("expected an element name"));
+        };
+
+        match head {
+            "version" => {
+                if let Some(version) = scanner.atom()? {
+                    header.version = Some(version.parse().map_err(|_| scanner.error("expected an integer version"))?);
+                }
+            }
+            "generator" => header.generator = scanner.text()?,
+            "generator_version" => header.generator_version = scanner.text()?,
+            "uuid" => header.uuid = scanner.text()?,
+            "paper" | "page" => {
+                header.paper = scanner.text()?;
+                while let Some(token) = scanner.next()? {
+                    match token {
+                        Token::Close => break,
+                        Token::Atom("portrait") => header.portrait = true,
+                        _ => (),
+                    }
+                }
+                continue;
+            }
+            "title_block" => {
+                read_title_block(scanner, header.title_block.insert(TitleBlockHeader::default()))?;
+                continue;
+            }
+            _ if SKIPPED_ELEMENTS.contains(&head) => (),
+            _ => return Ok(()),
+        }
+
+        scanner.skip_rest()?;
+    }
+}
+
+/// Read the fields of a title block, through its closing parenthesis.
+fn read_title_block<'a>(scanner: &mut Scanner<'a>, title_block: &mut TitleBlockHeader<'a>) -> Result<(), Error> {
+    loop {
+        match scanner.next()? {
+            None | Some(Token::Close) => return Ok(()),
+            Some(Token::Open) => (),
+            Some(_) => continue,
+        }
+
+        let Some(Token::Atom(head)) = scanner.next()? else {
+            return Err(scanner.error("expected an element name"));
+        };
+
+        match head {
+            "title" => title_block.title = scanner.text()?,
+            "date" => title_block.date = scanner.text()?,
+            "rev" => title_block.rev = scanner.text()?,
+            "company" => title_block.company = scanner.text()?,
+            "comment" => {
+                let number = scanner.atom()?.and_then(|number| number.parse::<usize>().ok());
+                let text = scanner.text()?;
+                if let Some(slot) = number.and_then(|n| title_block.comments.get_mut(n.wrapping_sub(1))) {
+                    *slot = text;
+                }
+            }
+            _ => (),
+        }
+
+        scanner.skip_rest()?;
+    }
+}
+
+/// Read the fields of a placed symbol, through its closing parenthesis.
+fn read_symbol<'a>(scanner: &mut Scanner<'a>, symbol: &mut SymbolSummary<'a>) -> Result<(), Error> {
+    loop {
+        match scanner.next()? {
+            None | Some(Token::Close) => return Ok(()),
+            Some(Token::Open) => (),
+            Some(_) => continue,
+        }
+
+        let Some(Token::Atom(head)) = scanner.next()? else {
+            return Err(scanner.error("expected an element name"));
+        };
+
+        match head {
+            "lib_id" => symbol.lib_id = scanner.text()?,
+            "unit" => symbol.unit = scanner.atom()?.and_then(|unit| unit.parse().ok()),
+            "in_bom" => symbol.in_bom = scanner.flag()?,
+            "dnp" => symbol.dnp = scanner.flag()?,
+            "property" => {
+                let key = scanner.text()?;
+                let value = scanner.text()?;
+                match key {
+                    Some("Reference") => symbol.reference = value,
+                    Some("Value") => symbol.value = value,
+                    Some("Footprint") => symbol.footprint = value,
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+
+        scanner.skip_rest()?;
+    }
+}
+
+impl<'a> Scanner<'a> {
+    /// Read the next token, or `None` at the end of the text.
+    fn next(&mut self) -> Result<Option<Token<'a>>, Error> {
+        let bytes = self.text.as_bytes();
+        while self.pos < bytes.len() && bytes[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+
+        let Some(&c) = bytes.get(self.pos) else {
+            return Ok(None);
+        };
+
+        match c {
+            b'(' => {
+                self.pos += 1;
+                Ok(Some(Token::Open))
+            }
+            b')' => {
+                self.pos += 1;
+                Ok(Some(Token::Close))
+            }
+            b'"' => {
+                let start = self.pos + 1;
+                let mut end = start;
+                while end < bytes.len() && bytes[end] != b'"' {
+                    end += if bytes[end] == b'\\' {
+                        2
+                    } else {
+                        1
+                    };
+                }
+                if end >= bytes.len() {
+                    self.pos = bytes.len();
+                    return Ok(None);
+                }
+                self.pos = end + 1;
+                Ok(Some(Token::Str(&self.text[start..end])))
+            }
+            _ => {
+                let start = self.pos;
+                while self.pos < bytes.len()
+                    && !bytes[self.pos].is_ascii_whitespace()
+                    && !b"()\"".contains(&bytes[self.pos])
+                {
+                    self.pos += 1;
+                }
+                Ok(Some(Token::Atom(&self.text[start..self.pos])))
+            }
+        }
+    }
+
+    /// Read an unquoted atom.
+    fn atom(&mut self) -> Result<Option<&'a str>, Error> {
+        match self.next()? {
+            Some(Token::Atom(atom)) => Ok(Some(atom)),
+            None => Ok(None),
+            Some(_) => Err(self.error("expected an atom")),
+        }
+    }
+
+    /// Read a quoted string or an unquoted atom, which older formats use for some values.
+    fn text(&mut self) -> Result<Option<&'a str>, Error> {
+        match self.next()? {
+            Some(Token::Str(text) | Token::Atom(text)) => Ok(Some(text)),
+            None => Ok(None),
+            Some(_) => Err(self.error("expected a string")),
+        }
+    }
+
+    /// Read a `yes` or `no` flag. A flag written without a value, as older formats do, is `yes`.
+    fn flag(&mut self) -> Result<Option<bool>, Error> {
+        let start = self.pos;
+        match self.next()? {
+            Some(Token::Atom("yes")) => Ok(Some(true)),
+            Some(Token::Atom("no")) => Ok(Some(false)),
+            Some(Token::Close) => {
+                self.pos = start;
+                Ok(Some(true))
+            }
+            None => Ok(None),
+            Some(_) => Err(self.error("expected yes or no")),
+        }
+    }
+
+    /// Skip the remainder of the current list, through its closing parenthesis or the end of the
+    /// text.
+    fn skip_rest(&mut self) -> Result<(), Error> {
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next()? {
+                None => break,
+                Some(Token::Open) => depth += 1,
+                Some(Token::Close) => depth -= 1,
+                Some(_) => (),
+            }
+        }
+        Ok(())
+    }
+
+    /// Create a syntax error at the current position.
+    fn error(&self, message: &str) -> Error {
+        SyntaxError::new(message).at(SourceLocation::from_offset(self.text, self.pos)).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inspect_header() {
+        let text = r#"(kicad_sch (version 20231120) (generator "eeschema") (generator_version "8.0")
+            (uuid "c5a2ad3f-4c4e-4b1e-9b43-3f0a6b7c9a10")
+            (paper "A3" portrait)
+            (title_block (title "Power \"board\"") (rev "B") (comment 2 "Reviewed"))
+            (lib_symbols (symbol "Device:R" (pin passive line"#;
+
+        let header = inspect_header(text).unwrap();
+        assert_eq!(header.kind, "kicad_sch");
+        assert_eq!(header.version, Some(20231120));
+        assert_eq!(header.generator_version, Some("8.0"));
+        assert_eq!(header.uuid, Some("c5a2ad3f-4c4e-4b1e-9b43-3f0a6b7c9a10"));
+        assert_eq!((header.paper, header.portrait), (Some("A3"), true));
+
+        let title_block = header.title_block.unwrap();
+        assert_eq!(title_block.title, Some(r#"Power \"board\""#));
+        assert_eq!(title_block.comments[1], Some("Reviewed"));
+
+        let footprint = inspect_header("(footprint \"R_0603\" (version 20240108) (generator pcbnew) (layer").unwrap();
+        assert_eq!(
+            (footprint.kind, footprint.version, footprint.generator),
+            ("footprint", Some(20240108), Some("pcbnew"))
+        );
+
+        assert!(inspect_header("kicad_pcb").is_err());
+    }
+
+    #[test]
+    fn test_scan_symbols() {
+        let text = include_str!("../tests/ports.kicad_sch");
+        let symbols = scan_symbols(text).unwrap();
+
+        let value = lexpr::from_str(text).unwrap();
+        let options = crate::ParseOptions::default().sections(&["symbol"]);
+        let schematic = crate::sch::Schematic::parse(value.as_cons().unwrap(), &options).unwrap();
+        assert_eq!(symbols.len(), schematic.symbols.len());
+        for (summary, symbol) in symbols.iter().zip(&schematic.symbols) {
+            assert_eq!(summary.lib_id, Some(symbol.lib_id.as_str()));
+            assert_eq!(summary.reference, symbol.property("Reference"));
+            assert_eq!(summary.value, symbol.property("Value"));
+            assert_eq!(summary.footprint, symbol.property("Footprint"));
+            assert_eq!(summary.unit, Some(symbol.unit));
+            assert_eq!(summary.dnp, symbol.dnp);
+        }
+
+        let symbols =
+            scan_symbols("(kicad_sch (symbol (lib_id \"Device:C\") (dnp) (property \"Value\" \"1u\")))").unwrap();
+        assert_eq!((symbols[0].value, symbols[0].dnp, symbols[0].reference), (Some("1u"), Some(true), None));
+        assert!(scan_symbols("(kicad_pcb)").is_err());
+    }
+}
@@ -0,0 +1,454 @@
+use {
+    crate::{
+        parse_str,
+        pcb::{Pcb, PcbFootprint},
+        sch::Schematic,
+        symlib::SymbolLibrary,
+        wks::Worksheet,
+        Error,
+    },
+    std::io::{Error as IoError, ErrorKind},
+};
+
+/// The environment variable KiCad sets to the path of its API socket for plugins it launches.
+pub const API_SOCKET_VAR: &str = "KICAD_API_SOCKET";
+
+/// The API socket KiCad listens on when [`API_SOCKET_VAR`] is not set.
+pub const DEFAULT_API_SOCKET: &str = "/tmp/kicad/api.sock";
+
+/// The `Any` type URL prefix used by the KiCad API.
+const TYPE_URL_PREFIX: &str = "type.googleapis.com/";
+
+/// `kiapi.common.types.ApiStatusCode.AS_OK`.
+const STATUS_OK: u64 = 1;
+
+/// A connection that sends an encoded `ApiRequest` and returns the encoded `ApiResponse`.
+///
+/// [`NngSocket`] connects to a running KiCad; other implementations can stand in for it in tests.
+pub trait IpcTransport {
+    /// Send a request and wait for its response.
+    fn request(&mut self, message: &[u8]) -> Result<Vec<u8>, Error>;
+}
+
+/// The kinds of documents the KiCad API serves (`kiapi.common.types.DocumentType`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DocumentType {
+    Schematic = 1,
+    Symbol = 2,
+    Pcb = 3,
+    Footprint = 4,
+    DrawingSheet = 5,
+}
+
+/// A document fetched from KiCad, parsed into the model for its [`DocumentType`].
+#[derive(Clone, Debug)]
+pub enum Document {
+    Schematic(Box<Schematic>),
+    Symbol(SymbolLibrary),
+    Pcb(Box<Pcb>),
+    Footprint(Box<PcbFootprint>),
+    DrawingSheet(Worksheet),
+}
+
+/// A document open in KiCad, as returned by [`IpcClient::open_documents`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OpenDocument {
+    /// The kind of document.
+    pub kind: DocumentType,
+
+    /// The file name of a board, relative to its project.
+    pub board_filename: Option<String>,
+
+    /// The encoded `DocumentSpecifier`, passed back to KiCad to identify the document.
+    specifier: Vec<u8>,
+}
+
+/// A client for the KiCad IPC API (KiCad 9+), which lets tools work on the documents open in a
+/// running editor.
+///
+/// Only the requests needed to fetch documents are implemented. A fetched document is parsed from
+/// the same text KiCad would save, so it can be used anywhere a parsed file can.
+#[derive(Debug)]
+pub struct IpcClient<T> {
+    transport: T,
+    client_name: String,
+    token: String,
+}
+
+impl DocumentType {
+    /// Return the type with the given protobuf enum value.
+    fn from_proto(value: u64) -> Option<Self> {
+        match value {
+            1 => Some(Self::Schematic),
+            2 => Some(Self::Symbol),
+            3 => Some(Self::Pcb),
+            4 => Some(Self::Footprint),
+            5 => Some(Self::DrawingSheet),
+            _ => None,
+        }
+    }
+}
+
+impl<T: IpcTransport> IpcClient<T> {
+    /// Create a client that identifies itself to KiCad as `client_name`.
+    pub fn new<S>(transport: T, client_name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            transport,
+            client_name: client_name.into(),
+            token: String::new(),
+        }
+    }
+
+    /// Return the documents of a kind open in KiCad.
+    pub fn open_documents(&mut self, kind: DocumentType) -> Result<Vec<OpenDocument>, Error> {
+        let mut command = Vec::new();
+        put_varint_field(&mut command, 1, kind as u64);
+        let response = self.call("kiapi.common.commands.GetOpenDocuments", &command)?;
+
+        let mut documents = Vec::new();
+        for (field, value) in fields(&response)? {
+            if let (1, Field::Bytes(specifier)) = (field, value) {
+                documents.push(parse_specifier(specifier)?);
+            }
+        }
+        Ok(documents)
+    }
+
+    /// Return the text of an open document, as KiCad would save it.
+    pub fn document_text(&mut self, document: &OpenDocument) -> Result<String, Error> {
+        let mut command = Vec::new();
+        put_bytes_field(&mut command, 1, &document.specifier);
+        let response = self.call("kiapi.common.commands.SaveDocumentToString", &command)?;
+
+        fields(&response)?
+            .into_iter()
+            .find_map(|(field, value)| match (field, value) {
+                (2, Field::Bytes(contents)) => Some(String::from_utf8_lossy(contents).into_owned()),
+                _ => None,
+            })
+            .ok_or_else(|| protocol_error("KiCad API response has no document contents"))
+    }
+
+    /// Fetch an open document and parse it into the model for its kind.
+    pub fn fetch(&mut self, document: &OpenDocument) -> Result<Document, Error> {
+        let text = self.document_text(document)?;
+        Ok(match document.kind {
+            DocumentType::Schematic => Document::Schematic(Box::new(parse_str(&text)?)),
+            DocumentType::Symbol => Document::Symbol(parse_str(&text)?),
+            DocumentType::Pcb => Document::Pcb(Box::new(parse_str(&text)?)),
+            DocumentType::Footprint => Document::Footprint(Box::new(parse_str(&text)?)),
+            DocumentType::DrawingSheet => Document::DrawingSheet(parse_str(&text)?),
+        })
+    }
+
+    /// Send a command and return the encoded response message.
+    fn call(&mut self, type_name: &str, command: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut header = Vec::new();
+        put_bytes_field(&mut header, 1, self.token.as_bytes());
+        put_bytes_field(&mut header, 2, self.client_name.as_bytes());
+
+        let mut any = Vec::new();
+        put_bytes_field(&mut any, 1, format!("{TYPE_URL_PREFIX}{type_name}").as_bytes());
+        put_bytes_field(&mut any, 2, command);
+
+        let mut request = Vec::new();
+        put_bytes_field(&mut request, 1, &header);
+        put_bytes_field(&mut request, 2, &any);
+
+        let response = self.transport.request(&request)?;
+        let mut status = STATUS_OK;
+        let mut error_message = String::new();
+        let mut message = Vec::new();
+
+        for (field, value) in fields(&response)? {
+            match (field, value) {
+                (1, Field::Bytes(header)) => {
+                    for (field, value) in fields(header)? {
+                        if let (1, Field::Bytes(token)) = (field, value) {
+                            self.token = String::from_utf8_lossy(token).into_owned();
+                        }
+                    }
+                }
+                (2, Field::Bytes(response_status)) => {
+                    for (field, value) in fields(response_status)? {
+                        match (field, value) {
+                            (1, Field::Varint(code)) => status = code,
+                            (2, Field::Bytes(text)) => error_message = String::from_utf8_lossy(text).into_owned(),
+                            _ => (),
+                        }
+                    }
+                }
+                (3, Field::Bytes(any)) => {
+                    for (field, value) in fields(any)? {
+                        if let (2, Field::Bytes(value)) = (field, value) {
+                            message = value.to_vec();
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        if status != STATUS_OK {
+            return Err(IoError::other(format!("KiCad API error {status}: {error_message}")).into());
+        }
+
+        Ok(message)
+    }
+}
+
+/// Decode a `DocumentSpecifier`, keeping its encoding.
+fn parse_specifier(specifier: &[u8]) -> Result<OpenDocument, Error> {
+    let mut kind = None;
+    let mut board_filename = None;
+
+    for (field, value) in fields(specifier)? {
+        match (field, value) {
+            (1, Field::Varint(value)) => kind = DocumentType::from_proto(value),
+            (3, Field::Bytes(name)) => board_filename = Some(String::from_utf8_lossy(name).into_owned()),
+            _ => (),
+        }
+    }
+
+    Ok(OpenDocument {
+        kind: kind.ok_or_else(|| protocol_error("KiCad API document has an unknown type"))?,
+        board_filename,
+        specifier: specifier.to_vec(),
+    })
+}
+
+/// A decoded protobuf field value.
+#[derive(Clone, Copy, Debug)]
+enum Field<'a> {
+    Varint(u64),
+    Fixed,
+    Bytes(&'a [u8]),
+}
+
+/// Decode the fields of a protobuf message.
+fn fields(mut message: &[u8]) -> Result<Vec<(u64, Field<'_>)>, Error> {
+    let mut result = Vec::new();
+    while !message.is_empty() {
+        let key = take_varint(&mut message)?;
+        let value = match key & 7 {
+            0 => Field::Varint(take_varint(&mut message)?),
+            1 | 5 => {
+                let len = if key & 7 == 1 {
+                    8
+                } else {
+                    4
+                };
+                take(&mut message, len)?;
+                Field::Fixed
+            }
+            2 => {
+                let len = take_varint(&mut message)? as usize;
+                Field::Bytes(take(&mut message, len)?)
+            }
+            wire_type => return Err(protocol_error(format!("Unsupported protobuf wire type {wire_type}"))),
+        };
+        result.push((key >> 3, value));
+    }
+    Ok(result)
+}
+
+/// Remove `len` bytes from the front of `message`.
+fn take<'a>(message: &mut &'a [u8], len: usize) -> Result<&'a [u8], Error> {
+    if message.len() < len {
+        return Err(protocol_error("Truncated protobuf message"));
+    }
+    let (head, rest) = message.split_at(len);
+    *message = rest;
+    Ok(head)
+}
+
+/// Remove a varint from the front of `message`.
+fn take_varint(message: &mut &[u8]) -> Result<u64, Error> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = take(message, 1)?[0];
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(protocol_error("Invalid protobuf varint"))
+}
+
+/// Return the error for a message that does not follow the KiCad API protocol.
+fn protocol_error<S>(message: S) -> Error
+where
+    S: Into<String>,
+{
+    IoError::new(ErrorKind::InvalidData, message.into()).into()
+}
+
+/// Append a varint.
+fn put_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+/// Append a varint field.
+fn put_varint_field(buffer: &mut Vec<u8>, field: u64, value: u64) {
+    put_varint(buffer, field << 3);
+    put_varint(buffer, value);
+}
+
+/// Append a length-delimited field.
+fn put_bytes_field(buffer: &mut Vec<u8>, field: u64, value: &[u8]) {
+    put_varint(buffer, (field << 3) | 2);
+    put_varint(buffer, value.len() as u64);
+    buffer.extend_from_slice(value);
+}
+
+#[cfg(unix)]
+pub use self::nng::NngSocket;
+
+#[cfg(unix)]
+mod nng {
+    use {
+        super::{protocol_error, IpcTransport, API_SOCKET_VAR, DEFAULT_API_SOCKET},
+        crate::Error,
+        std::{
+            env,
+            io::{Read, Write},
+            os::unix::net::UnixStream,
+            path::{Path, PathBuf},
+        },
+    };
+
+    /// The scalability protocol header for a REQ socket talking to a REP socket.
+    const REQ_HANDSHAKE: [u8; 8] = [0, b'S', b'P', 0, 0, 0x30, 0, 0];
+
+    /// The nng IPC message type for user messages.
+    const USER_MESSAGE: u8 = 1;
+
+    /// An nng REQ socket connected to KiCad's API over IPC.
+    #[derive(Debug)]
+    pub struct NngSocket {
+        stream: UnixStream,
+        next_id: u32,
+    }
+
+    impl NngSocket {
+        /// Connect to the socket KiCad advertises in [`API_SOCKET_VAR`], or the default socket.
+        pub fn connect_default() -> Result<Self, Error> {
+            let path = env::var_os(API_SOCKET_VAR).map_or_else(|| PathBuf::from(DEFAULT_API_SOCKET), PathBuf::from);
+            Self::connect(path.strip_prefix("ipc://").unwrap_or(&path))
+        }
+
+        /// Connect to the API socket at `path`.
+        pub fn connect(path: &Path) -> Result<Self, Error> {
+            let mut stream = UnixStream::connect(path)?;
+            stream.write_all(&REQ_HANDSHAKE)?;
+
+            let mut peer = [0; 8];
+            stream.read_exact(&mut peer)?;
+            if peer[..4] != REQ_HANDSHAKE[..4] || peer[4..6] != [0, 0x31] {
+                return Err(protocol_error(format!("{} is not an nng REP socket", path.display())));
+            }
+
+            Ok(Self {
+                stream,
+                next_id: 1,
+            })
+        }
+    }
+
+    impl IpcTransport for NngSocket {
+        fn request(&mut self, message: &[u8]) -> Result<Vec<u8>, Error> {
+            let id = self.next_id | 0x8000_0000;
+            self.next_id = self.next_id.wrapping_add(1) & 0x7fff_ffff;
+
+            let mut frame = vec![USER_MESSAGE];
+            frame.extend_from_slice(&(message.len() as u64 + 4).to_be_bytes());
+            frame.extend_from_slice(&id.to_be_bytes());
+            frame.extend_from_slice(message);
+            self.stream.write_all(&frame)?;
+
+            loop {
+                let mut header = [0; 9];
+                self.stream.read_exact(&mut header)?;
+                let len = u64::from_be_bytes(header[1..].try_into().unwrap()) as usize;
+                let mut body = vec![0; len];
+                self.stream.read_exact(&mut body)?;
+
+                // Replies to earlier, abandoned requests are discarded.
+                if body.len() >= 4 && body[..4] == id.to_be_bytes() {
+                    return Ok(body.split_off(4));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A transport that replays queued responses and records the requests it is sent.
+    struct Canned {
+        responses: Vec<Vec<u8>>,
+        requests: Vec<Vec<u8>>,
+    }
+
+    impl IpcTransport for Canned {
+        fn request(&mut self, message: &[u8]) -> Result<Vec<u8>, Error> {
+            self.requests.push(message.to_vec());
+            Ok(self.responses.remove(0))
+        }
+    }
+
+    fn response(status: u64, type_name: &str, message: &[u8]) -> Vec<u8> {
+        let (mut header, mut status_message, mut any, mut response) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        put_bytes_field(&mut header, 1, b"token-1");
+        put_varint_field(&mut status_message, 1, status);
+        put_bytes_field(&mut any, 1, format!("{TYPE_URL_PREFIX}{type_name}").as_bytes());
+        put_bytes_field(&mut any, 2, message);
+        put_bytes_field(&mut response, 1, &header);
+        put_bytes_field(&mut response, 2, &status_message);
+        put_bytes_field(&mut response, 3, &any);
+        response
+    }
+
+    #[test]
+    fn test_fetch_open_document() {
+        let mut specifier = Vec::new();
+        put_varint_field(&mut specifier, 1, DocumentType::Pcb as u64);
+        put_bytes_field(&mut specifier, 3, b"demo.kicad_pcb");
+        let mut documents = Vec::new();
+        put_bytes_field(&mut documents, 1, &specifier);
+        let mut saved = Vec::new();
+        put_bytes_field(&mut saved, 2, b"(kicad_pcb (version 20240108) (generator \"pcbnew\"))");
+
+        let transport = Canned {
+            responses: vec![
+                response(STATUS_OK, "kiapi.common.commands.GetOpenDocumentsResponse", &documents),
+                response(STATUS_OK, "kiapi.common.commands.SavedDocumentResponse", &saved),
+                response(3, "", &[]),
+            ],
+            requests: Vec::new(),
+        };
+        let mut client = IpcClient::new(transport, "kanga");
+
+        let open = client.open_documents(DocumentType::Pcb).unwrap();
+        assert_eq!(open[0].board_filename.as_deref(), Some("demo.kicad_pcb"));
+        let Document::Pcb(board) = client.fetch(&open[0]).unwrap() else {
+            panic!("expected a board");
+        };
+        assert_eq!(board.version, Some(20240108));
+        let err = client.fetch(&open[0]).unwrap_err();
+        assert!(matches!(err, Error::Io(_)));
+
+        // The token from the first response is sent with later requests.
+        let sent = &client.transport.requests[1];
+        assert!(sent.windows(7).any(|window| window == b"token-1"));
+    }
+}
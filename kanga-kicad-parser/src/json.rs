@@ -0,0 +1,48 @@
+use {
+    crate::Error,
+    serde::{de::DeserializeOwned, Serialize},
+};
+
+/// Convert a model value to JSON.
+///
+/// Lengths are written in millimeters, as in KiCad files.
+pub fn to_json<T>(value: &T) -> Result<String, Error>
+where
+    T: Serialize,
+{
+    serde_json::to_string_pretty(value).map_err(Error::from)
+}
+
+/// Convert JSON produced by [`to_json`] (possibly edited) back into a model value.
+///
+/// Validation is strict: unknown fields, missing required fields, and values of the wrong type are
+/// all rejected, so a typo in an edited document is reported rather than silently dropped.
+pub fn from_json<T>(json: &str) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    serde_json::from_str(json).map_err(Error::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::common::{Font, Nm, Stroke},
+        lexpr::sexp,
+    };
+
+    #[test]
+    fn test_json_round_trip() {
+        let font = Font::try_from(&sexp!((font (size 1.27 1.27) (thickness 0.1524) bold))).unwrap();
+        let json = to_json(&font).unwrap();
+        let parsed: Font = from_json(&json).unwrap();
+        assert_eq!(parsed.size.height, Nm(1_270_000));
+        assert_eq!(parsed.thickness, Some(152_400));
+        assert!(parsed.bold);
+        assert!(parsed.line_spacing.is_none());
+
+        let err = from_json::<Stroke>(r#"{"width": 0.1, "colour": null}"#).unwrap_err();
+        assert!(matches!(err, Error::Json(e) if e.to_string().contains("colour")));
+    }
+}
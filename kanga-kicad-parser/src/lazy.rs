@@ -0,0 +1,148 @@
+use {
+    crate::ParseError,
+    lexpr::Value,
+    std::{fmt, sync::OnceLock},
+};
+
+#[cfg(feature = "serde")]
+use serde::{de::Deserializer, ser::Error as _, ser::Serializer, Deserialize, Serialize};
+
+/// Conversion from a raw s-expression element into a typed model, used by [`Lazy`].
+pub trait FromRaw: Sized {
+    /// Convert the raw element.
+    fn from_raw(raw: &Value) -> Result<Self, ParseError>;
+}
+
+/// A document section kept as a raw s-expression and converted into a typed model the first time
+/// it is accessed.
+///
+/// The conversion result, including any error, is cached, so later accesses are free. Sections
+/// that are never accessed are never converted, which lets tools that touch a small part of a
+/// large document skip most of the model construction.
+pub struct Lazy<T> {
+    raw: Value,
+    converted: OnceLock<Result<T, ParseError>>,
+}
+
+impl<T> Lazy<T> {
+    /// Create a section from a raw element, to be converted on first access.
+    pub fn new(raw: Value) -> Self {
+        Self {
+            raw,
+            converted: OnceLock::new(),
+        }
+    }
+
+    /// Create a section from an already converted value. Its raw element is nil.
+    pub fn from_value(value: T) -> Self {
+        Self {
+            raw: Value::Nil,
+            converted: OnceLock::from(Ok(value)),
+        }
+    }
+
+    /// Return the raw element, or nil if the section was created from a converted value.
+    pub fn raw(&self) -> &Value {
+        &self.raw
+    }
+
+    /// Indicates whether the section has been converted, successfully or not.
+    pub fn is_converted(&self) -> bool {
+        self.converted.get().is_some()
+    }
+}
+
+impl<T: FromRaw> Lazy<T> {
+    /// Return the typed model, converting the raw element if this is the first access.
+    pub fn get(&self) -> Result<&T, &ParseError> {
+        self.converted.get_or_init(|| T::from_raw(&self.raw)).as_ref()
+    }
+
+    /// Return the typed model, converting the raw element if needed.
+    pub fn into_inner(self) -> Result<T, ParseError> {
+        match self.converted.into_inner() {
+            Some(result) => result,
+            None => T::from_raw(&self.raw),
+        }
+    }
+}
+
+impl<T: Clone> Clone for Lazy<T> {
+    /// Clones the raw element and any successful conversion. A failed conversion is not cloned;
+    /// the clone repeats it on first access.
+    fn clone(&self) -> Self {
+        let converted = OnceLock::new();
+        if let Some(Ok(value)) = self.converted.get() {
+            let _ = converted.set(Ok(value.clone()));
+        }
+
+        Self {
+            raw: self.raw.clone(),
+            converted,
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Lazy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.converted.get() {
+            Some(Ok(value)) => value.fmt(f),
+            Some(Err(e)) => f.debug_tuple("Lazy").field(e).finish(),
+            None => f.debug_tuple("Lazy").field(&self.raw).finish(),
+        }
+    }
+}
+
+impl<T: Default> Default for Lazy<T> {
+    fn default() -> Self {
+        Self::from_value(T::default())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: FromRaw + Serialize> Serialize for Lazy<T> {
+    /// Serializes the typed model, converting it first if needed.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.get().map_err(|e| S::Error::custom(format!("{e:?}")))?.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Lazy<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::from_value)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl<T: schemars::JsonSchema> schemars::JsonSchema for Lazy<T> {
+    fn inline_schema() -> bool {
+        T::inline_schema()
+    }
+
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        T::schema_name()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        T::json_schema(generator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::common::TitleBlock, lexpr::sexp};
+
+    #[test]
+    fn test_lazy() {
+        let section: Lazy<TitleBlock> = Lazy::new(sexp!((title_block (title "Power") (rev "B"))));
+        assert!(!section.is_converted());
+        assert_eq!(section.get().unwrap().title, "Power");
+        assert!(section.is_converted());
+        assert_eq!(section.clone().into_inner().unwrap().rev, "B");
+
+        let broken: Lazy<TitleBlock> = Lazy::new(lexpr::from_str("(title_block (title))").unwrap());
+        assert!(broken.get().is_err());
+        assert!(broken.is_converted());
+    }
+}
@@ -0,0 +1,156 @@
+use {
+    crate::{
+        common::{Decimal, Nm},
+        ParseError,
+    },
+    lexpr::{Cons, Value},
+};
+
+/// Checked accessors for the shapes of s-expression that KiCad files are built from.
+pub trait LexprExt {
+    /// Return [`self`] as a float if it is a number, or a decimal number kept as written by
+    /// [`read_str`](crate::read_str).
+    fn as_float(&self) -> Option<f64>;
+
+    /// Assert that [`self`] is a boolean value. If it is, return it as a Rust `bool`.
+    ///
+    /// KiCad uses the symbols `yes` and `no` for booleans.
+    fn expect_bool(&self) -> Result<bool, ParseError>;
+
+    /// Assert that [`self`] is a cons cell. If it is, return it as a [`Cons`] cell.
+    fn expect_cons(&self) -> Result<&Cons, ParseError>;
+
+    /// Assert that [`self`] is a cons cell with a number head. If it is, return the head as a
+    /// float and the cdr.
+    fn expect_cons_with_any_float_head(&self) -> Result<(f64, &Value), ParseError>;
+
+    /// Assert that [`self`] is a cons cell with a number head. If it is, return the head, a length
+    /// in millimeters, converted to nanometers without going through a float, and the cdr.
+    fn expect_cons_with_any_length_head(&self) -> Result<(Nm, &Value), ParseError>;
+
+    /// Assert that [`self`] is a cons cell with an integer head. If it is, return the head and
+    /// the cdr.
+    fn expect_cons_with_any_int_head(&self) -> Result<(i64, &Value), ParseError>;
+
+    /// Assert that [`self`] is a cons cell with a string head. If it is, return the head and
+    /// the cdr.
+    fn expect_cons_with_any_str_head(&self) -> Result<(&str, &Value), ParseError>;
+
+    /// Assert that [`self`] is a cons cell with a symbol head. If it is, return the head and
+    /// the cdr.
+    fn expect_cons_with_any_symbol_head(&self) -> Result<(&str, &Value), ParseError>;
+
+    /// Assert that [`self`] is a cons cell with a particular symbol as its head. If it is, return
+    /// the cdr.
+    fn expect_cons_with_symbol_head(&self, symbol: &str) -> Result<&Value, ParseError>;
+
+    /// Assert that [`self`] is null (end-of-list).
+    fn expect_null(&self) -> Result<(), ParseError>;
+}
+
+impl LexprExt for Cons {
+    fn as_float(&self) -> Option<f64> {
+        None
+    }
+
+    fn expect_bool(&self) -> Result<bool, ParseError> {
+        Err(ParseError::Unexpected(Value::Cons(self.clone())))
+    }
+
+    fn expect_cons(&self) -> Result<&Cons, ParseError> {
+        Ok(self)
+    }
+
+    fn expect_cons_with_any_float_head(&self) -> Result<(f64, &Value), ParseError> {
+        let value =
+            self.car().as_float().ok_or_else(|| ParseError::ExpectedListFloatHead(Value::Cons(self.clone())))?;
+        Ok((value, self.cdr()))
+    }
+
+    fn expect_cons_with_any_length_head(&self) -> Result<(Nm, &Value), ParseError> {
+        let value =
+            Decimal::try_from(self.car()).map_err(|_| ParseError::ExpectedListFloatHead(Value::Cons(self.clone())))?;
+        Ok((value.to_length(), self.cdr()))
+    }
+
+    fn expect_cons_with_any_int_head(&self) -> Result<(i64, &Value), ParseError> {
+        let value = self.car().as_i64().ok_or_else(|| ParseError::ExpectedListIntHead(Value::Cons(self.clone())))?;
+        Ok((value, self.cdr()))
+    }
+
+    fn expect_cons_with_any_str_head(&self) -> Result<(&str, &Value), ParseError> {
+        let value = self.car().as_str().ok_or_else(|| ParseError::ExpectedListStrHead(Value::Cons(self.clone())))?;
+        Ok((value, self.cdr()))
+    }
+
+    fn expect_cons_with_any_symbol_head(&self) -> Result<(&str, &Value), ParseError> {
+        let value =
+            self.car().as_symbol().ok_or_else(|| ParseError::ExpectedListSymbolHead(Value::Cons(self.clone())))?;
+        Ok((value, self.cdr()))
+    }
+
+    fn expect_cons_with_symbol_head(&self, symbol: &str) -> Result<&Value, ParseError> {
+        match self.expect_cons_with_any_symbol_head()? {
+            (head, cdr) if head == symbol => Ok(cdr),
+            _ => Err(ParseError::ExpectedSymbol(Value::Cons(self.clone()), symbol.to_string())),
+        }
+    }
+
+    fn expect_null(&self) -> Result<(), ParseError> {
+        Err(ParseError::ExpectedNil(Value::Cons(self.clone())))
+    }
+}
+
+impl LexprExt for Value {
+    fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Number(number) => number.as_f64(),
+            Value::Symbol(text) if text.parse::<Decimal>().is_ok() => text.parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn expect_bool(&self) -> Result<bool, ParseError> {
+        match self {
+            Value::Symbol(symbol) if &**symbol == "yes" => Ok(true),
+            Value::Symbol(symbol) if &**symbol == "no" => Ok(false),
+            Value::Bool(value) => Ok(*value),
+            _ => Err(ParseError::Unexpected(self.clone())),
+        }
+    }
+
+    fn expect_cons(&self) -> Result<&Cons, ParseError> {
+        self.as_cons().ok_or_else(|| ParseError::ExpectedList(self.clone()))
+    }
+
+    fn expect_cons_with_any_float_head(&self) -> Result<(f64, &Value), ParseError> {
+        self.expect_cons()?.expect_cons_with_any_float_head()
+    }
+
+    fn expect_cons_with_any_length_head(&self) -> Result<(Nm, &Value), ParseError> {
+        self.expect_cons()?.expect_cons_with_any_length_head()
+    }
+
+    fn expect_cons_with_any_int_head(&self) -> Result<(i64, &Value), ParseError> {
+        self.expect_cons()?.expect_cons_with_any_int_head()
+    }
+
+    fn expect_cons_with_any_str_head(&self) -> Result<(&str, &Value), ParseError> {
+        self.expect_cons()?.expect_cons_with_any_str_head()
+    }
+
+    fn expect_cons_with_any_symbol_head(&self) -> Result<(&str, &Value), ParseError> {
+        self.expect_cons()?.expect_cons_with_any_symbol_head()
+    }
+
+    fn expect_cons_with_symbol_head(&self, symbol: &str) -> Result<&Value, ParseError> {
+        self.expect_cons()?.expect_cons_with_symbol_head(symbol)
+    }
+
+    fn expect_null(&self) -> Result<(), ParseError> {
+        match self {
+            Value::Null => Ok(()),
+            _ => Err(ParseError::ExpectedNil(self.clone())),
+        }
+    }
+}
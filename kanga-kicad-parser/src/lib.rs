@@ -1,19 +1,138 @@
+// The parsers `sexpr!` generates name their locals with Greek letters, such as `α`, so that they
+// cannot collide with field names; they are not confused with the crate's own identifiers.
+#![allow(confusable_idents)]
+
+#[cfg(feature = "tools")]
+pub mod batch;
+#[cfg(feature = "tools")]
+pub mod bom;
+#[cfg(feature = "tools")]
+pub mod builder;
+#[cfg(feature = "tools")]
+pub mod bundle;
+pub mod bus;
+pub mod cleanup;
+#[cfg(feature = "kicad-cli")]
+pub mod cli;
 pub mod common;
+#[cfg(feature = "tools")]
+pub mod compat;
+pub mod config;
+#[cfg(feature = "tools")]
+pub mod context;
+#[cfg(feature = "serde")]
+pub mod de;
+pub mod dedup;
+#[cfg(feature = "tools")]
+pub mod density;
+pub mod diff;
+#[cfg(feature = "tools")]
+pub mod downgrade;
+#[cfg(feature = "tools")]
+pub mod erc;
+pub mod error;
+#[cfg(feature = "tools")]
+pub mod features;
+#[cfg(feature = "tools")]
+pub mod fpgen;
+pub mod frame;
+pub mod geometry;
+#[cfg(feature = "tools")]
+pub mod harness;
+#[cfg(feature = "tools")]
+pub mod impact;
+#[cfg(feature = "tools")]
+pub mod inspect;
+#[cfg(feature = "kicad-ipc")]
+pub mod ipc;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod lazy;
+#[cfg(feature = "tools")]
+pub mod libindex;
+#[cfg(feature = "tools")]
+pub mod library;
+pub mod libtable;
+#[cfg(feature = "tools")]
+pub mod lint;
 pub mod lexpr_ext;
+#[cfg(feature = "tools")]
+pub mod merge;
+pub mod metrics;
+#[cfg(feature = "json")]
+pub mod netclass;
+pub mod pcb;
+#[cfg(feature = "tools")]
+pub mod pinmap;
+pub mod prelude;
+#[cfg(feature = "tools")]
+pub mod preview;
+#[cfg(feature = "json")]
+pub mod project;
+pub mod provenance;
+#[cfg(feature = "tools")]
+pub mod redact;
+#[cfg(feature = "tools")]
+pub mod repair;
+#[cfg(feature = "tools")]
+pub mod review;
 pub mod sch;
+#[cfg(feature = "schemars")]
+pub mod schema;
+#[cfg(feature = "tools")]
+pub mod spice;
+pub mod span;
+pub mod stream;
+#[cfg(feature = "svg")]
+pub mod svg;
+#[cfg(feature = "tools")]
+pub mod suggestion;
+#[cfg(feature = "tools")]
+pub mod symgen;
+pub mod symlib;
+pub mod uri;
+pub mod vars;
+pub mod version;
+pub mod visit;
+pub mod wks;
+pub mod writer;
 
 use {
-    lexpr::Value,
+    lexpr::{Cons, Value},
     std::{
-        error::Error,
+        error::Error as StdError,
         fmt::{Display, Formatter, Result as FmtResult},
     },
 };
 
 pub(crate) use lexpr_ext::*;
+pub use {
+    config::{Config, ConfigBuilder, ParseOptions, Theme},
+    error::{parse_file, Error, ParseWarning, SyntaxError},
+    lazy::{FromRaw, Lazy},
+    libtable::{LibTable, LibTableEntry, LibTableKind},
+    span::{parse_str, read_str, SourceLocation},
+    uri::UriResolver,
+    vars::VarProvider,
+    version::FormatVersion,
+};
+
+#[cfg(feature = "json")]
+pub use project::{DrcSettings, ErcSettings, NetClass, NetClassPattern, NetSettings, Project, ProjectSheet, RuleSeverity};
+
+#[cfg(feature = "tools")]
+pub use {
+    batch::{parse_many, BatchEntry, BatchResult},
+    context::OutputContext,
+    library::{LibraryCache, SymbolLibraryResolver},
+    suggestion::{Suggestion, SuggestionKind},
+};
 
 #[derive(Debug)]
 pub enum ParseError {
+    At(SourceLocation, Box<ParseError>),
+    Deserialize(String),
+    DuplicateField(String, String, Value),
     ExpectedList(Value),
     ExpectedListFloatHead(Value),
     ExpectedListIntHead(Value),
@@ -21,12 +140,19 @@ pub enum ParseError {
     ExpectedListSymbolHead(Value),
     ExpectedNil(Value),
     ExpectedSymbol(Value, String),
+    InElement(span::ElementId, Box<ParseError>),
+    InvalidElement(String, String),
+    InvalidEmbeddedFile(String, String),
     InvalidHeight(f64),
     InvalidPaperSize(String),
     InvalidUuid(String),
     InvalidWidth(f64),
     MissingField(String, String, Value),
+    UndefinedVariable(String),
     Unexpected(Value),
+    UnknownLibrary(String),
+    UnsupportedVersion(i64),
+    Within(String, Box<ParseError>),
 }
 
 impl ParseError {
@@ -38,11 +164,67 @@ impl ParseError {
     {
         Self::MissingField(struct_name.into(), field_name.into(), value.into())
     }
+
+    /// Record that this error occurred within the element `element`.
+    pub fn within<E>(self, element: E) -> Self
+    where
+        E: Into<String>,
+    {
+        Self::Within(element.into(), Box::new(self))
+    }
+
+    /// Record that this error occurred within the given list element. The element is labeled by its head
+    /// symbol and, if present, its first string argument (e.g. `symbol "Device:C"`), and identified so
+    /// that [`locate`](Self::locate) can find its position.
+    pub fn within_element(self, cons: &Cons) -> Self {
+        let mut label = match cons.car().as_symbol() {
+            Some(head) => head.to_string(),
+            None => return self,
+        };
+
+        if let Some(name) = cons.cdr().as_cons().and_then(|rest| rest.car().as_str()) {
+            label.push_str(&format!(" {name:?}"));
+        }
+
+        Self::InElement(span::ElementId::of(cons), Box::new(self.within(label)))
+    }
+
+    /// Return the path of elements, outermost first, in which this error occurred.
+    pub fn breadcrumb(&self) -> Vec<&str> {
+        let mut path = Vec::new();
+        let mut error = self;
+
+        loop {
+            match error {
+                Self::At(_, inner) | Self::InElement(_, inner) => error = inner,
+                Self::Within(element, inner) => {
+                    path.push(element.as_str());
+                    error = inner;
+                }
+                _ => break,
+            }
+        }
+
+        path
+    }
+
+    /// Return the underlying error without any element context.
+    pub fn root_cause(&self) -> &ParseError {
+        match self {
+            Self::At(_, inner) | Self::InElement(_, inner) | Self::Within(_, inner) => inner.root_cause(),
+            _ => self,
+        }
+    }
 }
 
 impl Display for ParseError {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         match self {
+            Self::At(location, inner) => write!(f, "{location}: {inner}"),
+            Self::Deserialize(message) => write!(f, "Cannot deserialize: {message}"),
+            Self::DuplicateField(struct_name, field_name, value) => {
+                write!(f, "Duplicate {struct_name} field {field_name}: {value}")
+            }
             Self::ExpectedList(value) => write!(f, "Expected list, got {value}"),
             Self::ExpectedListFloatHead(value) => {
                 write!(f, "Expected list with floating-point head, got {value}")
@@ -52,17 +234,74 @@ impl Display for ParseError {
             Self::ExpectedListSymbolHead(value) => write!(f, "Expected list with symbol head, got {value}"),
             Self::ExpectedNil(value) => write!(f, "Expected nil, got {value}"),
             Self::ExpectedSymbol(value, symbol) => write!(f, "Expected symbol {symbol}, got {value}"),
+            Self::InElement(_, inner) => write!(f, "{inner}"),
+            Self::InvalidElement(element, reason) => write!(f, "Invalid {element}: {reason}"),
+            Self::InvalidEmbeddedFile(name, reason) => write!(f, "Invalid embedded file {name}: {reason}"),
             Self::InvalidHeight(height) => write!(f, "Invalid height value {height}"),
             Self::InvalidPaperSize(paper_size) => write!(f, "Invalid paper size {paper_size}"),
             Self::InvalidUuid(value) => write!(f, "Invalid UUID {value}"),
             Self::InvalidWidth(width) => write!(f, "Invalid width value {width}"),
             Self::MissingField(struct_name, field_name, value) => write!(f, "Missing {struct_name} field {field_name}: {value}"),
+            Self::UndefinedVariable(name) => write!(f, "Undefined variable {name}"),
             Self::Unexpected(value) => write!(f, "Unexpected value {value}"),
+            Self::UnknownLibrary(name) => write!(f, "Unknown library {name}"),
+            Self::UnsupportedVersion(version) => write!(f, "Unsupported file format version {version}"),
+            Self::Within(..) => write!(f, "{}: {}", self.breadcrumb().join(" > "), self.root_cause()),
+        }
+    }
+}
+
+impl StdError for ParseError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::At(_, inner) | Self::Within(_, inner) => Some(inner.as_ref()),
+            Self::InElement(_, inner) => inner.source(),
+            _ => None,
+        }
+    }
+}
+
+/// Errors from the parsers declared with [`kanga_sexpr::sexpr!`] are reported as the errors the
+/// hand-written parsers give for the same faults.
+impl From<kanga_sexpr::ParseError> for ParseError {
+    fn from(error: kanga_sexpr::ParseError) -> Self {
+        use kanga_sexpr::ParseError as E;
+
+        // A list whose value is missing or of the wrong type, rather than the value itself.
+        let list_or = |value: Value, list_error: fn(Value) -> Self| {
+            if value.is_cons() {
+                list_error(value)
+            } else {
+                Self::Unexpected(value)
+            }
+        };
+
+        match error {
+            E::DuplicateField(struct_name, field_name, value) => Self::DuplicateField(struct_name, field_name, value),
+            E::ExpectedEnumSymbol(value, _) => Self::Unexpected(value),
+            E::ExpectedList(value) => Self::ExpectedList(value),
+            E::ExpectedFloat(value) => list_or(value, Self::ExpectedListFloatHead),
+            E::ExpectedInt(value) => list_or(value, Self::ExpectedListIntHead),
+            E::ExpectedStr(value) => list_or(value, Self::ExpectedListStrHead),
+            E::ExpectedSym(value) => list_or(value, Self::ExpectedListSymbolHead),
+            E::ExpectedNil(value) => Self::ExpectedNil(value),
+            E::ExpectedNamedSym(value, symbol) => Self::ExpectedSymbol(value, symbol),
+            E::InvalidHeight(height) => Self::InvalidHeight(height),
+            E::InvalidPaperSize(paper_size) => Self::InvalidPaperSize(paper_size),
+            E::InvalidUuid(value) => Self::InvalidUuid(value),
+            E::InvalidWidth(width) => Self::InvalidWidth(width),
+            E::MissingField(struct_name, field_name, value) => Self::MissingField(struct_name, field_name, value),
+            E::Unexpected(value) => Self::Unexpected(value),
+            E::Within(element, inner) => Self::Within(element, Box::new((*inner).into())),
         }
     }
 }
 
-impl Error for ParseError {}
+impl kanga_sexpr::SexprError for ParseError {
+    fn within_list(self, _name: &'static str, element: &Cons) -> Self {
+        self.within_element(element)
+    }
+}
 
 #[macro_export]
 macro_rules! impl_try_from_cons_value {
@@ -72,8 +311,172 @@ macro_rules! impl_try_from_cons_value {
 
             fn try_from(value: &::lexpr::Value) -> ::std::result::Result<Self, Self::Error> {
                 let cons = value.expect_cons()?;
-                Self::try_from(cons)
+                Self::try_from(cons).map_err(|e| e.within_element(cons))
             }
         }
     };
 }
+
+/// Implement [`Node`](kanga_sexpr::Node) and [`ToSexpr`](kanga_sexpr::ToSexpr) for a type with a
+/// hand-written parser, so that it can be a field of a type declared with `sexpr!`. The type is a
+/// leaf named `$sexpr_name`, written by its `From<&T> for Value` implementation.
+#[macro_export]
+macro_rules! impl_sexpr_leaf {
+    ($name:ty, $sexpr_name:literal) => {
+        impl ::kanga_sexpr::Node for $name {
+            fn sexpr_name(&self) -> &'static str {
+                $sexpr_name
+            }
+
+            fn children(&self) -> ::std::vec::Vec<&dyn ::kanga_sexpr::Node> {
+                ::std::vec::Vec::new()
+            }
+
+            fn children_mut(&mut self) -> ::std::vec::Vec<&mut dyn ::kanga_sexpr::Node> {
+                ::std::vec::Vec::new()
+            }
+
+            fn as_any(&self) -> &dyn ::std::any::Any {
+                self
+            }
+
+            fn as_any_mut(&mut self) -> &mut dyn ::std::any::Any {
+                self
+            }
+        }
+
+        impl ::kanga_sexpr::ToSexpr for $name {
+            fn to_value(&self) -> ::lexpr::Value {
+                ::lexpr::Value::from(self)
+            }
+        }
+    };
+}
+
+/// Implement [`Identified`](crate::common::Identified) for a type with a `uuid` field. Pass
+/// `optional` if the field is an `Option<Uuid>`.
+#[macro_export]
+macro_rules! impl_identified {
+    ($name:ty) => {
+        impl $crate::common::Identified for $name {
+            fn uuid(&self) -> ::std::option::Option<::uuid::Uuid> {
+                ::std::option::Option::Some(self.uuid)
+            }
+
+            fn set_uuid(&mut self, uuid: ::uuid::Uuid) {
+                self.uuid = uuid;
+            }
+        }
+    };
+
+    ($name:ty, optional) => {
+        impl $crate::common::Identified for $name {
+            fn uuid(&self) -> ::std::option::Option<::uuid::Uuid> {
+                self.uuid
+            }
+
+            fn set_uuid(&mut self, uuid: ::uuid::Uuid) {
+                self.uuid = ::std::option::Option::Some(uuid);
+            }
+        }
+    };
+}
+
+/// Implement [`Positioned`](crate::common::Positioned) for a type with a `position` field. Pass
+/// `optional` if the field is an `Option<Position>`.
+#[macro_export]
+macro_rules! impl_positioned {
+    ($name:ty) => {
+        impl $crate::common::Positioned for $name {
+            fn position(&self) -> ::std::option::Option<&$crate::common::Position> {
+                ::std::option::Option::Some(&self.position)
+            }
+
+            fn position_mut(&mut self) -> ::std::option::Option<&mut $crate::common::Position> {
+                ::std::option::Option::Some(&mut self.position)
+            }
+
+            fn set_position(&mut self, position: $crate::common::Position) {
+                self.position = position;
+            }
+        }
+    };
+
+    ($name:ty, optional) => {
+        impl $crate::common::Positioned for $name {
+            fn position(&self) -> ::std::option::Option<&$crate::common::Position> {
+                self.position.as_ref()
+            }
+
+            fn position_mut(&mut self) -> ::std::option::Option<&mut $crate::common::Position> {
+                self.position.as_mut()
+            }
+
+            fn set_position(&mut self, position: $crate::common::Position) {
+                self.position = ::std::option::Option::Some(position);
+            }
+        }
+    };
+}
+
+/// Parsed models are shared between threads by servers and parallel analyses. This fails to
+/// compile if a change, such as adding a cache or interner built on `Rc` or `Cell`, makes any of
+/// them `!Send` or `!Sync`.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+
+    use {common::*, pcb::*, sch::*, wks::*};
+
+    assert_send_sync::<Angle>();
+    assert_send_sync::<Color>();
+    assert_send_sync::<Decimal>();
+    assert_send_sync::<Fill>();
+    assert_send_sync::<Font>();
+    assert_send_sync::<Offset>();
+    assert_send_sync::<Paper>();
+    assert_send_sync::<Point>();
+    assert_send_sync::<Points>();
+    assert_send_sync::<Position>();
+    assert_send_sync::<Property>();
+    assert_send_sync::<Size>();
+    assert_send_sync::<Stroke>();
+    assert_send_sync::<Symbol>();
+    assert_send_sync::<SymbolGraphic>();
+    assert_send_sync::<SymbolPin>();
+    assert_send_sync::<SymbolProperty>();
+    assert_send_sync::<TextEffects>();
+    assert_send_sync::<TitleBlock>();
+
+    assert_send_sync::<Schematic>();
+    assert_send_sync::<SchematicBus>();
+    assert_send_sync::<SchematicBusEntry>();
+    assert_send_sync::<SchematicGlobalLabel>();
+    assert_send_sync::<SchematicGraphicPolyline>();
+    assert_send_sync::<SchematicGraphicText>();
+    assert_send_sync::<SchematicImage>();
+    assert_send_sync::<SchematicJunction>();
+    assert_send_sync::<SchematicLabel>();
+    assert_send_sync::<SchematicNoConnect>();
+    assert_send_sync::<SchematicSheet>();
+    assert_send_sync::<SchematicSymbolInstance>();
+    assert_send_sync::<SchematicWire>();
+
+    assert_send_sync::<Pcb>();
+    assert_send_sync::<PcbFootprint>();
+    assert_send_sync::<PcbPad>();
+    assert_send_sync::<PcbSegment>();
+    assert_send_sync::<PcbArc>();
+    assert_send_sync::<PcbVia>();
+    assert_send_sync::<PcbZone>();
+
+    assert_send_sync::<Worksheet>();
+    assert_send_sync::<symlib::SymbolLibrary>();
+    assert_send_sync::<LibTable>();
+    assert_send_sync::<Lazy<Vec<Symbol>>>();
+    assert_send_sync::<Config>();
+    assert_send_sync::<metrics::TextMetrics>();
+    assert_send_sync::<ParseError>();
+    assert_send_sync::<Error>();
+    #[cfg(feature = "json")]
+    assert_send_sync::<Project>();
+};
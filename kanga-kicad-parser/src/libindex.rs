@@ -0,0 +1,316 @@
+use {
+    crate::{impact::string_property, read_str, Error, LexprExt},
+    serde::{Deserialize, Serialize},
+    std::{
+        cmp::Ordering,
+        fs::{read_dir, read_to_string},
+        path::{Path, PathBuf},
+    },
+};
+
+/// The kinds of library items that are indexed.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LibraryItemKind {
+    /// A symbol in a `.kicad_sym` library.
+    Symbol,
+
+    /// A footprint in a `.kicad_mod` file, usually in a `.pretty` library directory.
+    Footprint,
+}
+
+/// A symbol or footprint found by a [`LibraryIndex`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LibraryIndexEntry {
+    /// Whether this is a symbol or a footprint.
+    pub kind: LibraryItemKind,
+
+    /// The library name: the file stem of a symbol library, or of the directory holding a
+    /// footprint.
+    pub library: String,
+
+    /// The name of the item within its library.
+    pub name: String,
+
+    /// The description, from a symbol's `Description` (or `ki_description`) property or a
+    /// footprint's `descr`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// The search keywords, from a symbol's `ki_keywords` property or a footprint's `tags`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+
+    /// The file the item was found in.
+    pub path: PathBuf,
+}
+
+/// An index entry matching a search, and how well it matched.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LibrarySearchHit<'a> {
+    /// The matching entry.
+    pub entry: &'a LibraryIndexEntry,
+
+    /// The relevance of the match; higher is better.
+    pub score: u32,
+}
+
+/// A searchable index of the symbols and footprints under one or more directory roots.
+///
+/// Files are read as raw S-expressions, so libraries that do not convert to the typed model are
+/// still indexed. Files that cannot be read or parsed are recorded in
+/// [`errors`](LibraryIndex::errors) rather than failing the scan.
+#[derive(Debug, Default)]
+pub struct LibraryIndex {
+    /// The indexed items, ordered by kind, library, and name.
+    pub entries: Vec<LibraryIndexEntry>,
+
+    /// The library files that could not be indexed, and why.
+    pub errors: Vec<(PathBuf, Error)>,
+}
+
+impl LibraryIndexEntry {
+    /// Return the library identifier of the item, `Library:Name`.
+    pub fn lib_id(&self) -> String {
+        format!("{}:{}", self.library, self.name)
+    }
+}
+
+impl LibraryIndex {
+    /// Create an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an index of the libraries under each of `roots`.
+    pub fn scan<P>(roots: &[P]) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let mut index = Self::new();
+        for root in roots {
+            index.add_root(root.as_ref())?;
+        }
+        Ok(index)
+    }
+
+    /// Add the `.kicad_sym` and `.kicad_mod` files under a directory, recursively. A directory that
+    /// cannot be listed is an error.
+    pub fn add_root(&mut self, root: &Path) -> Result<(), Error> {
+        let mut pending = vec![root.to_path_buf()];
+
+        while let Some(dir) = pending.pop() {
+            let entries = read_dir(&dir)?;
+            for entry in entries.filter_map(Result::ok) {
+                let path = entry.path();
+                if path.is_dir() {
+                    pending.push(path);
+                } else if let Err(e) = self.add_file(&path) {
+                    self.errors.push((path, e));
+                }
+            }
+        }
+
+        self.entries.sort_by(|a, b| (a.kind, &a.library, &a.name).cmp(&(b.kind, &b.library, &b.name)));
+        Ok(())
+    }
+
+    /// Add the items in a library file. Files that are not libraries are ignored.
+    fn add_file(&mut self, path: &Path) -> Result<(), Error> {
+        let kind = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("kicad_sym") => LibraryItemKind::Symbol,
+            Some("kicad_mod") => LibraryItemKind::Footprint,
+            _ => return Ok(()),
+        };
+
+        let text = read_to_string(path)?;
+        let value = read_str(&text)?;
+
+        match kind {
+            LibraryItemKind::Symbol => {
+                let library = stem(path);
+                let rest = value.expect_cons_with_symbol_head("kicad_symbol_lib")?;
+                for symbol in rest.list_iter().into_iter().flatten() {
+                    let Ok((name, rest)) = symbol
+                        .expect_cons_with_symbol_head("symbol")
+                        .and_then(|cdr| cdr.expect_cons_with_any_str_head())
+                    else {
+                        continue;
+                    };
+
+                    let description =
+                        string_property(rest, "Description").or_else(|| string_property(rest, "ki_description"));
+                    self.entries.push(LibraryIndexEntry {
+                        kind,
+                        library: library.clone(),
+                        name: name.to_string(),
+                        description: description.filter(|text| !text.is_empty()).map(String::from),
+                        keywords: words(string_property(rest, "ki_keywords")),
+                        path: path.to_path_buf(),
+                    });
+                }
+            }
+
+            LibraryItemKind::Footprint => {
+                // KiCad 5 footprints use `module` rather than `footprint`.
+                let (name, rest) = value
+                    .expect_cons_with_symbol_head("footprint")
+                    .or_else(|_| value.expect_cons_with_symbol_head("module"))?
+                    .expect_cons_with_any_str_head()?;
+                let library = path.parent().map(stem).unwrap_or_default();
+                let text_child = |head: &str| {
+                    rest.list_iter()?
+                        .find_map(|element| element.expect_cons_with_symbol_head(head).ok()?.as_cons()?.car().as_str())
+                };
+
+                self.entries.push(LibraryIndexEntry {
+                    kind,
+                    library,
+                    name: name.to_string(),
+                    description: text_child("descr").filter(|text| !text.is_empty()).map(String::from),
+                    keywords: words(text_child("tags")),
+                    path: path.to_path_buf(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Search the index, returning at most `limit` matches, best first.
+    ///
+    /// The query is split into words, each of which must match the name, library, a keyword, or
+    /// the description of an entry, ignoring case. Exact matches score above prefixes, which
+    /// score above substrings. A word also matches a name or keyword that contains its letters in
+    /// order, such as `r0603` for `R_0603_1608Metric`, with a low score, so omitted separators and
+    /// abbreviations still find the item. Matches on the name count most, then keywords, then the
+    /// library and description. Ties are broken by library and name.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<LibrarySearchHit<'_>> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        if words.is_empty() {
+            return Vec::new();
+        }
+
+        let mut hits: Vec<LibrarySearchHit> = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let score = words.iter().map(|word| word_score(word, entry)).sum::<Option<u32>>()?;
+                Some(LibrarySearchHit {
+                    entry,
+                    score,
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| match b.score.cmp(&a.score) {
+            Ordering::Equal => (&a.entry.library, &a.entry.name).cmp(&(&b.entry.library, &b.entry.name)),
+            ordering => ordering,
+        });
+        hits.truncate(limit);
+        hits
+    }
+}
+
+/// Score how well a lowercase query word matches an entry, or return `None` if it does not.
+fn word_score(word: &str, entry: &LibraryIndexEntry) -> Option<u32> {
+    let name = text_score(word, &entry.name, true).map(|score| score * 3);
+    let keywords =
+        entry.keywords.iter().filter_map(|keyword| text_score(word, keyword, true)).max().map(|score| score * 2);
+    let library = text_score(word, &entry.library, false);
+    let description = entry.description.as_deref().and_then(|description| text_score(word, description, false));
+
+    [name, keywords, library, description].into_iter().flatten().max()
+}
+
+/// Score how well a lowercase query word matches a piece of text, optionally allowing its letters
+/// to be spread out.
+fn text_score(word: &str, text: &str, fuzzy: bool) -> Option<u32> {
+    let text = text.to_lowercase();
+
+    if text == word {
+        Some(100)
+    } else if text.starts_with(word) {
+        Some(60)
+    } else if text.contains(word) {
+        Some(40)
+    } else if fuzzy && word.len() > 1 && is_subsequence(word, &text) {
+        Some(10)
+    } else {
+        None
+    }
+}
+
+/// Indicates whether the characters of `needle` appear in `haystack` in order.
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|c| haystack.any(|h| h == c))
+}
+
+/// Split a keyword list on whitespace and commas.
+fn words(text: Option<&str>) -> Vec<String> {
+    text.into_iter()
+        .flat_map(|text| text.split(|c: char| c.is_whitespace() || c == ','))
+        .filter(|word| !word.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Return the file stem of a path, e.g. `Device` for `Device.kicad_sym` or `Resistor_SMD` for
+/// `Resistor_SMD.pretty`.
+fn stem(path: &Path) -> String {
+    path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        std::{fs::create_dir_all, process},
+    };
+
+    #[test]
+    fn test_library_index() {
+        let dir = std::env::temp_dir().join(format!("kanga-libindex-{}", process::id()));
+        let (symbols, footprints) = (dir.join("symbols"), dir.join("footprints").join("Resistor_SMD.pretty"));
+        create_dir_all(&symbols).unwrap();
+        create_dir_all(&footprints).unwrap();
+
+        std::fs::write(
+            symbols.join("Device.kicad_sym"),
+            r#"(kicad_symbol_lib (version 20231120) (generator kanga)
+                (symbol "R" (property "Reference" "R" (at 0 0 0)) (property "Description" "Resistor" (at 0 0 0))
+                    (property "ki_keywords" "R res resistor" (at 0 0 0)))
+                (symbol "C" (property "Reference" "C" (at 0 0 0))
+                    (property "Description" "Unpolarized capacitor" (at 0 0 0)) (property "ki_keywords" "cap capacitor" (at 0 0 0))))"#,
+        )
+        .unwrap();
+        std::fs::write(
+            footprints.join("R_0603_1608Metric.kicad_mod"),
+            r#"(footprint "R_0603_1608Metric" (version 20240108) (generator kanga) (layer "F.Cu")
+                (descr "Resistor SMD 0603 (1608 Metric)") (tags "resistor"))"#,
+        )
+        .unwrap();
+        std::fs::write(footprints.join("Broken.kicad_mod"), "(footprint").unwrap();
+
+        let index = LibraryIndex::scan(&[&symbols, &dir.join("footprints")]).unwrap();
+        assert_eq!(index.entries.len(), 3);
+        assert_eq!(index.errors.len(), 1);
+        assert_eq!(index.entries[0].lib_id(), "Device:C");
+        assert_eq!(index.entries[2].lib_id(), "Resistor_SMD:R_0603_1608Metric");
+        assert_eq!(index.entries[2].keywords, ["resistor"]);
+
+        // Both match a keyword exactly, so they are ordered by library.
+        let found: Vec<String> = index.search("resistor", 10).iter().map(|hit| hit.entry.lib_id()).collect();
+        assert_eq!(found, ["Device:R", "Resistor_SMD:R_0603_1608Metric"]);
+
+        let found: Vec<String> = index.search("r0603", 10).iter().map(|hit| hit.entry.lib_id()).collect();
+        assert_eq!(found, ["Resistor_SMD:R_0603_1608Metric"]);
+
+        let found: Vec<String> = index.search("CAP unpolarized", 10).iter().map(|hit| hit.entry.lib_id()).collect();
+        assert_eq!(found, ["Device:C"]);
+        assert!(index.search("inductor", 10).is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
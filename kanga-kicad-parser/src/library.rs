@@ -0,0 +1,237 @@
+use {
+    crate::{
+        bundle::sha256_hex,
+        common::Symbol,
+        libtable::{LibTable, LibTableKind},
+        read_str,
+        symlib::SymbolLibrary,
+        uri::UriResolver,
+        vars::{EnvVars, ProjectVars, VarProvider},
+        Error, ParseError, Project,
+    },
+    lexpr::Value,
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::{BTreeMap, HashMap},
+        fs::{create_dir_all, read, read_to_string, rename, write},
+        path::{Path, PathBuf},
+        process,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+    },
+};
+
+/// The `format` of an on-disk symbol cache file.
+pub const SYMBOL_CACHE_FORMAT: &str = "kanga-symbol-cache";
+
+/// The version of the on-disk symbol cache format. Files with another version are ignored and
+/// rewritten.
+pub const SYMBOL_CACHE_VERSION: u32 = 1;
+
+/// An on-disk symbol cache file, written as JSON to `<sha256>.json` in the cache directory, where
+/// `<sha256>` is the checksum of the library file's contents.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SymbolCacheFile {
+    /// Always [`SYMBOL_CACHE_FORMAT`].
+    pub format: String,
+
+    /// The cache format version, [`SYMBOL_CACHE_VERSION`] when written by this crate.
+    pub version: u32,
+
+    /// The SHA-256 checksum of the library file, as lowercase hex.
+    pub source_sha256: String,
+
+    /// The parsed symbols, in library order.
+    pub symbols: Vec<Symbol>,
+}
+
+/// A parsed library and the checksum of the file it was parsed from.
+type CachedLibrary = (String, Arc<Vec<Symbol>>);
+
+/// A cache of parsed symbol libraries, shared between threads and, optionally, between processes
+/// through a cache directory.
+///
+/// Entries are keyed by the checksum of the library file, so an edited library is never served
+/// from a stale entry. Cache files are written to a temporary name and renamed into place, so
+/// processes sharing a directory never read a partially written file. Unreadable cache files,
+/// including those from other format versions, are treated as missing.
+#[derive(Debug, Default)]
+pub struct LibraryCache {
+    dir: Option<PathBuf>,
+    memory: Mutex<HashMap<PathBuf, CachedLibrary>>,
+}
+
+/// Resolves `Library:Symbol` identifiers through a symbol library table.
+#[derive(Debug)]
+pub struct SymbolLibraryResolver<V = EnvVars> {
+    uris: UriResolver<V>,
+    libraries: BTreeMap<String, String>,
+    cache: Arc<LibraryCache>,
+}
+
+impl LibraryCache {
+    /// Create a cache held in memory only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a cache that is also stored in `dir`, which is created if needed.
+    pub fn with_dir<P>(dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self {
+            dir: Some(dir.into()),
+            memory: Mutex::default(),
+        }
+    }
+
+    /// Return the symbols in a `.kicad_sym` library, parsing it only if it is not cached.
+    pub fn load(&self, path: &Path) -> Result<Arc<Vec<Symbol>>, Error> {
+        let bytes = read(path)?;
+        let sha256 = sha256_hex(&bytes);
+
+        if let Some((cached, symbols)) = self.memory.lock().unwrap().get(path) {
+            if *cached == sha256 {
+                return Ok(symbols.clone());
+            }
+        }
+
+        let symbols = match self.read_disk(&sha256) {
+            Some(symbols) => symbols,
+            None => {
+                let text = String::from_utf8_lossy(&bytes);
+                let library = read_str(&text)?;
+                let symbols = parse_symbol_library(&library)?;
+                self.write_disk(&sha256, &symbols);
+                symbols
+            }
+        };
+
+        let symbols = Arc::new(symbols);
+        self.memory.lock().unwrap().insert(path.to_path_buf(), (sha256, symbols.clone()));
+        Ok(symbols)
+    }
+
+    /// Read the symbols for a checksum from the cache directory.
+    fn read_disk(&self, sha256: &str) -> Option<Vec<Symbol>> {
+        let path = self.dir.as_ref()?.join(format!("{sha256}.json"));
+        let file: SymbolCacheFile = serde_json::from_str(&read_to_string(path).ok()?).ok()?;
+        let valid =
+            file.format == SYMBOL_CACHE_FORMAT && file.version == SYMBOL_CACHE_VERSION && file.source_sha256 == sha256;
+        valid.then_some(file.symbols)
+    }
+
+    /// Write the symbols for a checksum to the cache directory. Failures are ignored; the cache
+    /// is only an optimization.
+    fn write_disk(&self, sha256: &str, symbols: &[Symbol]) {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let Some(dir) = &self.dir else {
+            return;
+        };
+
+        let file = SymbolCacheFile {
+            format: SYMBOL_CACHE_FORMAT.to_string(),
+            version: SYMBOL_CACHE_VERSION,
+            source_sha256: sha256.to_string(),
+            symbols: symbols.to_vec(),
+        };
+
+        let Ok(json) = serde_json::to_string(&file) else {
+            return;
+        };
+
+        let temp = dir.join(format!(".{sha256}.{}.{}.tmp", process::id(), COUNTER.fetch_add(1, Ordering::Relaxed)));
+        if create_dir_all(dir).is_ok() && write(&temp, json).is_ok() {
+            let _ = rename(&temp, dir.join(format!("{sha256}.json")));
+        }
+    }
+}
+
+impl SymbolLibraryResolver {
+    /// Create a resolver for a project's `sym-lib-table`, resolving library URIs with the
+    /// project's variables.
+    pub fn for_project(project: &Project, cache: Arc<LibraryCache>) -> SymbolLibraryResolver<(ProjectVars, EnvVars)> {
+        let table = project.symbol_lib_table.clone().unwrap_or_else(|| LibTable::new(LibTableKind::Symbol));
+        SymbolLibraryResolver::new(UriResolver::for_project(project), &table, cache)
+    }
+}
+
+impl<V: VarProvider> SymbolLibraryResolver<V> {
+    /// Create a resolver for a symbol library table. Disabled libraries and libraries of types
+    /// other than `KiCad` are ignored.
+    pub fn new(uris: UriResolver<V>, table: &LibTable, cache: Arc<LibraryCache>) -> Self {
+        let libraries =
+            table.enabled().filter(|lib| lib.is_kicad()).map(|lib| (lib.name.clone(), lib.uri.clone())).collect();
+
+        Self {
+            uris,
+            libraries,
+            cache,
+        }
+    }
+
+    /// Return the nicknames of the libraries in the table.
+    pub fn nicknames(&self) -> impl Iterator<Item = &str> {
+        self.libraries.keys().map(String::as_str)
+    }
+
+    /// Return the symbols in the library with the given nickname.
+    pub fn library(&self, nickname: &str) -> Result<Arc<Vec<Symbol>>, Error> {
+        let uri = self.libraries.get(nickname).ok_or_else(|| ParseError::UnknownLibrary(nickname.to_string()))?;
+        self.cache.load(&self.uris.resolve(uri)?)
+    }
+
+    /// Return the symbol with a `Library:Symbol` identifier, or `None` if the library does not
+    /// contain it.
+    pub fn symbol(&self, lib_id: &str) -> Result<Option<Symbol>, Error> {
+        let (nickname, name) = lib_id.split_once(':').ok_or_else(|| ParseError::UnknownLibrary(lib_id.to_string()))?;
+        Ok(self.library(nickname)?.iter().find(|symbol| symbol.id == name).cloned())
+    }
+}
+
+/// Parse the symbols in a `(kicad_symbol_lib ...)` document.
+pub fn parse_symbol_library(library: &Value) -> Result<Vec<Symbol>, ParseError> {
+    SymbolLibrary::try_from(library).map(|library| library.symbols)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::vars::StaticVars, lexpr::sexp};
+
+    #[test]
+    fn test_library_cache() {
+        let dir = std::env::temp_dir().join(format!("kanga-library-{}", process::id()));
+        create_dir_all(&dir).unwrap();
+        write(
+            dir.join("Demo.kicad_sym"),
+            "(kicad_symbol_lib (version 20231120) (generator kanga)\n\
+             (symbol \"R\" (property \"Reference\" \"R\" (at 0 0 0) (effects (font (size 1.27 1.27))))))",
+        )
+        .unwrap();
+
+        let table = sexp!((sym_lib_table (lib (name "Demo") (type "KiCad") (uri "${LIBS}\\Demo.kicad_sym"))));
+        let table = LibTable::try_from(&table).unwrap();
+        let uris = UriResolver::new(&dir).with_vars(StaticVars::new().with("LIBS", dir.to_string_lossy()));
+        let cache_dir = dir.join("cache");
+        let resolver = SymbolLibraryResolver::new(uris.clone(), &table, Arc::new(LibraryCache::with_dir(&cache_dir)));
+
+        assert_eq!(resolver.symbol("Demo:R").unwrap().unwrap().id, "R");
+        assert!(resolver.symbol("Demo:C").unwrap().is_none());
+        assert!(
+            matches!(resolver.symbol("Other:R"), Err(Error::Parse(ParseError::UnknownLibrary(name))) if name == "Other")
+        );
+
+        // The cache file is shared with other caches, in this process or others, using the same directory.
+        let cached: Vec<PathBuf> = std::fs::read_dir(&cache_dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+        let file: SymbolCacheFile = serde_json::from_str(&read_to_string(&cached[0]).unwrap()).unwrap();
+        assert_eq!((cached.len(), file.version), (1, SYMBOL_CACHE_VERSION));
+        let fresh = SymbolLibraryResolver::new(uris, &table, Arc::new(LibraryCache::with_dir(&cache_dir)));
+        assert_eq!(fresh.library("Demo").unwrap().len(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
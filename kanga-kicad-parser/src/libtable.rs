@@ -0,0 +1,246 @@
+use {
+    crate::{common::sexpr_list, impl_try_from_cons_value, LexprExt, ParseError},
+    lexpr::{Cons, Value},
+};
+
+/// Which kind of library a table lists.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum LibTableKind {
+    /// A symbol library table, `(sym_lib_table ...)`, stored in `sym-lib-table` files.
+    Symbol,
+
+    /// A footprint library table, `(fp_lib_table ...)`, stored in `fp-lib-table` files.
+    Footprint,
+}
+
+/// A KiCad library table, which maps library nicknames to library files.
+///
+/// KiCad reads a global table from its configuration directory and a table from each project
+/// directory; a project's libraries take precedence.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct LibTable {
+    /// Whether this table lists symbol or footprint libraries.
+    pub kind: LibTableKind,
+
+    /// The table format version, written by KiCad 7 and later.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub version: Option<i64>,
+
+    /// The libraries, in table order.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub libs: Vec<LibTableEntry>,
+}
+
+/// A library in a library table, `(lib (name ...) (type ...) (uri ...) (options ...) (descr ...))`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "lib", deny_unknown_fields))]
+pub struct LibTableEntry {
+    /// The library nickname, which prefixes library identifiers such as `Device:R`.
+    pub name: String,
+
+    /// The library plugin, such as `KiCad`, `Legacy`, or, for a nested table, `Table`.
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub lib_type: String,
+
+    /// The location of the library, which may refer to variables such as `${KIPRJMOD}`.
+    pub uri: String,
+
+    /// Options passed to the library plugin.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "String::is_empty"))]
+    pub options: String,
+
+    /// A description of the library.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "String::is_empty"))]
+    pub descr: String,
+
+    /// Whether the library is disabled, in which case KiCad does not load it.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+    pub disabled: bool,
+
+    /// Whether the library is hidden from KiCad's library browsers.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+    pub hidden: bool,
+}
+
+impl LibTableKind {
+    /// Return the head symbol of a table of this kind.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Symbol => "sym_lib_table",
+            Self::Footprint => "fp_lib_table",
+        }
+    }
+}
+
+impl LibTable {
+    /// Create an empty table of the given kind.
+    pub fn new(kind: LibTableKind) -> Self {
+        Self {
+            kind,
+            version: None,
+            libs: Vec::new(),
+        }
+    }
+
+    /// Return the library with the given nickname.
+    pub fn lib(&self, name: &str) -> Option<&LibTableEntry> {
+        self.libs.iter().find(|lib| lib.name == name)
+    }
+
+    /// Return the libraries that KiCad loads: those that are not disabled.
+    pub fn enabled(&self) -> impl Iterator<Item = &LibTableEntry> {
+        self.libs.iter().filter(|lib| !lib.disabled)
+    }
+}
+
+impl LibTableEntry {
+    /// Indicates whether the library is in KiCad's native format, the only format this crate
+    /// reads.
+    pub fn is_kicad(&self) -> bool {
+        self.lib_type.eq_ignore_ascii_case("kicad")
+    }
+}
+
+impl TryFrom<&Cons> for LibTable {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let kind = match cons.car().as_symbol() {
+            Some("sym_lib_table") => LibTableKind::Symbol,
+            Some("fp_lib_table") => LibTableKind::Footprint,
+            _ => return Err(ParseError::ExpectedSymbol(Value::Cons(cons.clone()), "sym_lib_table".to_string())),
+        };
+
+        let mut table = Self::new(kind);
+        for element in cons.cdr().list_iter().into_iter().flatten() {
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "version" => table.version = Some(cdr.expect_cons_with_any_int_head()?.0),
+                "lib" => table.libs.push(LibTableEntry::try_from(element)?),
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        Ok(table)
+    }
+}
+
+impl_try_from_cons_value!(LibTable);
+
+impl TryFrom<&Cons> for LibTableEntry {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let rest = cons.expect_cons_with_symbol_head("lib")?;
+        let mut name = None;
+        let mut lib_type = None;
+        let mut uri = None;
+        let mut options = String::new();
+        let mut descr = String::new();
+        let mut disabled = false;
+        let mut hidden = false;
+
+        for element in rest.list_iter().into_iter().flatten() {
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "name" => name = Some(text_arg(cdr)?),
+                "type" => lib_type = Some(text_arg(cdr)?),
+                "uri" => uri = Some(text_arg(cdr)?),
+                "options" => options = text_arg(cdr)?,
+                "descr" => descr = text_arg(cdr)?,
+                "disabled" => disabled = true,
+                "hidden" => hidden = true,
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        let value = || Value::Cons(cons.clone());
+        Ok(Self {
+            name: name.ok_or_else(|| ParseError::missing_field("lib", "name", value()))?,
+            lib_type: lib_type.ok_or_else(|| ParseError::missing_field("lib", "type", value()))?,
+            uri: uri.ok_or_else(|| ParseError::missing_field("lib", "uri", value()))?,
+            options,
+            descr,
+            disabled,
+            hidden,
+        })
+    }
+}
+
+impl_try_from_cons_value!(LibTableEntry);
+
+impl From<&LibTable> for Value {
+    fn from(table: &LibTable) -> Self {
+        let version = table.version.map(|version| sexpr_list("version", [Value::from(version)]));
+        sexpr_list(table.kind.as_str(), version.into_iter().chain(table.libs.iter().map(Value::from)))
+    }
+}
+
+impl From<&LibTableEntry> for Value {
+    fn from(lib: &LibTableEntry) -> Self {
+        let mut items = vec![
+            sexpr_list("name", [Value::string(lib.name.as_str())]),
+            sexpr_list("type", [Value::string(lib.lib_type.as_str())]),
+            sexpr_list("uri", [Value::string(lib.uri.as_str())]),
+            sexpr_list("options", [Value::string(lib.options.as_str())]),
+            sexpr_list("descr", [Value::string(lib.descr.as_str())]),
+        ];
+
+        if lib.disabled {
+            items.push(sexpr_list("disabled", []));
+        }
+
+        if lib.hidden {
+            items.push(sexpr_list("hidden", []));
+        }
+
+        sexpr_list("lib", items)
+    }
+}
+
+/// Return the single argument of a table element, which KiCad writes as a string or, in older
+/// tables, a bare symbol.
+fn text_arg(args: &Value) -> Result<String, ParseError> {
+    let value = args.expect_cons()?.car();
+    value
+        .as_str()
+        .or_else(|| value.as_symbol())
+        .map(str::to_string)
+        .ok_or_else(|| ParseError::Unexpected(value.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, lexpr::sexp};
+
+    #[test]
+    fn test_lib_table() {
+        let table = sexp!((fp_lib_table (version 7)
+            (lib (name "Resistor_SMD") (type "KiCad") (uri "${KICAD8_FOOTPRINT_DIR}/Resistor_SMD.pretty")
+                (options "") (descr "Resistors, surface mount"))
+            (lib (name Legacy) (type Legacy) (uri "${KIPRJMOD}/old.mod") (options "") (descr "") (disabled))));
+
+        let table = LibTable::try_from(&table).unwrap();
+        assert_eq!((table.kind, table.version), (LibTableKind::Footprint, Some(7)));
+        assert_eq!(table.lib("Resistor_SMD").unwrap().descr, "Resistors, surface mount");
+        assert!(table.lib("Resistor_SMD").unwrap().is_kicad());
+        assert_eq!(table.lib("Legacy").unwrap().lib_type, "Legacy");
+        assert_eq!(table.enabled().count(), 1);
+
+        let written = Value::from(&table);
+        let reread = LibTable::try_from(&written).unwrap();
+        assert_eq!(reread.libs, table.libs);
+
+        let missing_uri = sexp!((sym_lib_table (lib (name "Device") (type "KiCad"))));
+        assert!(LibTable::try_from(&missing_uri).is_err());
+    }
+}
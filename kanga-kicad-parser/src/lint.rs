@@ -0,0 +1,55 @@
+mod cache;
+mod footprint;
+mod klc;
+mod symbol;
+
+pub use {cache::*, footprint::*, klc::*, symbol::*};
+
+use serde::{Deserialize, Serialize};
+
+/// How serious a lint finding is.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Informational; no action is required.
+    Info,
+
+    /// The item works but does not follow conventions.
+    Warning,
+
+    /// The item is likely to be wrong.
+    Error,
+}
+
+/// A problem found by a lint.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LintIssue {
+    /// The identifier of the rule that was violated, e.g. `duplicate-pin-number`.
+    pub rule: String,
+
+    /// How serious the problem is.
+    pub severity: Severity,
+
+    /// A human-readable description of the problem.
+    pub message: String,
+
+    /// The element the problem was found on, e.g. `pin 3`, if it is not the item as a whole.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+}
+
+impl LintIssue {
+    /// Create a new issue.
+    pub fn new<R, M>(rule: R, severity: Severity, message: M, location: Option<String>) -> Self
+    where
+        R: Into<String>,
+        M: Into<String>,
+    {
+        Self {
+            rule: rule.into(),
+            severity,
+            message: message.into(),
+            location,
+        }
+    }
+}
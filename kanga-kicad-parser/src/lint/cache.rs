@@ -0,0 +1,182 @@
+use {
+    super::{LintIssue, Severity},
+    crate::{impact::string_property, LexprExt, ParseError},
+    lexpr::Value,
+    std::collections::{BTreeMap, BTreeSet},
+};
+
+/// The units and pins of a symbol in a schematic's `lib_symbols` cache.
+#[derive(Debug, Default)]
+struct CachedSymbol<'a> {
+    /// The symbol this one extends, by name within its library.
+    extends: Option<&'a str>,
+
+    /// The pin numbers of each unit. Unit 0 holds pins common to all units.
+    pins: BTreeMap<u64, BTreeSet<&'a str>>,
+}
+
+/// Check that the symbols placed in a schematic agree with the schematic's embedded symbol cache
+/// (`lib_symbols`).
+///
+/// KiCad draws and netlists placed symbols from the cache, so a corrupted or stale cache causes
+/// missing or misnumbered connections. The rules checked are:
+/// * `missing-cached-symbol`: the placed symbol's `lib_id` (or `lib_name`) is not in the cache.
+/// * `unit-out-of-range`: the placed unit is not one the cached symbol defines.
+/// * `unknown-pin`: the placed symbol lists a pin the cached unit does not have.
+/// * `missing-pin`: the cached unit has a pin the placed symbol does not list.
+pub fn lint_symbol_cache(schematic: &Value) -> Result<Vec<LintIssue>, ParseError> {
+    let rest = schematic.expect_cons_with_symbol_head("kicad_sch")?;
+    let mut cache = BTreeMap::new();
+    let mut issues = Vec::new();
+
+    for element in rest.list_iter().into_iter().flatten() {
+        if let Ok(symbols) = element.expect_cons_with_symbol_head("lib_symbols") {
+            for symbol in symbols.list_iter().into_iter().flatten() {
+                if let Ok((id, cached)) = cached_symbol(symbol) {
+                    cache.insert(id, cached);
+                }
+            }
+        }
+    }
+
+    for element in rest.list_iter().into_iter().flatten() {
+        let Ok(cdr) = element.expect_cons_with_symbol_head("symbol") else {
+            continue;
+        };
+
+        let field = |name: &str| cdr.list_iter()?.find_map(|e| e.expect_cons_with_symbol_head(name).ok()?.as_cons());
+        let Some(lib_id) = field("lib_name").or_else(|| field("lib_id")).and_then(|cons| cons.car().as_str()) else {
+            continue;
+        };
+
+        let reference = string_property(cdr, "Reference").unwrap_or(lib_id);
+        let Some(cached) = cache.get(lib_id) else {
+            issues.push(LintIssue::new(
+                "missing-cached-symbol",
+                Severity::Error,
+                format!("{reference} uses {lib_id}, which is not in the symbol cache"),
+                None,
+            ));
+            continue;
+        };
+
+        // A derived symbol takes its units and pins from its parent, cached under the same library.
+        let parent_id = cached.extends.map(|parent| match lib_id.split_once(':') {
+            Some((library, _)) => format!("{library}:{parent}"),
+            None => parent.to_string(),
+        });
+        let definition = parent_id.as_deref().and_then(|id| cache.get(id)).unwrap_or(cached);
+
+        let unit = field("unit").and_then(|cons| cons.car().as_u64()).unwrap_or(1);
+        let units = definition.pins.keys().copied().max().unwrap_or_default().max(1);
+        if unit == 0 || unit > units {
+            issues.push(LintIssue::new(
+                "unit-out-of-range",
+                Severity::Error,
+                format!("{reference} is unit {unit}, but {lib_id} has {units} unit(s)"),
+                None,
+            ));
+            continue;
+        }
+
+        let expected: BTreeSet<&str> =
+            [0, unit].iter().filter_map(|unit| definition.pins.get(unit)).flatten().copied().collect();
+        let placed: BTreeSet<&str> = cdr
+            .list_iter()
+            .into_iter()
+            .flatten()
+            .filter_map(|e| e.expect_cons_with_symbol_head("pin").ok()?.expect_cons_with_any_str_head().ok())
+            .map(|(number, _)| number)
+            .collect();
+
+        for number in placed.difference(&expected) {
+            issues.push(LintIssue::new(
+                "unknown-pin",
+                Severity::Error,
+                format!("{reference} has pin {number}, which unit {unit} of {lib_id} does not define"),
+                Some(format!("{reference} pin {number}")),
+            ));
+        }
+
+        for number in expected.difference(&placed) {
+            issues.push(LintIssue::new(
+                "missing-pin",
+                Severity::Warning,
+                format!("{reference} does not list pin {number} of unit {unit} of {lib_id}"),
+                Some(format!("{reference} pin {number}")),
+            ));
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Collect the units and pins of a cached `(symbol "Lib:Name" ...)` definition.
+fn cached_symbol(symbol: &Value) -> Result<(&str, CachedSymbol<'_>), ParseError> {
+    let (id, rest) = symbol.expect_cons_with_symbol_head("symbol")?.expect_cons_with_any_str_head()?;
+    let name = id.rsplit(':').next().unwrap_or(id);
+    let mut cached = CachedSymbol::default();
+
+    for element in rest.list_iter().into_iter().flatten() {
+        let Ok((key, cdr)) = element.expect_cons_with_any_symbol_head() else {
+            continue;
+        };
+
+        match key {
+            "extends" => cached.extends = cdr.as_cons().and_then(|cons| cons.car().as_str()),
+            "pin" => cached.pins.entry(0).or_default().extend(pin_number(cdr)),
+            "symbol" => {
+                // Units are named `<name>_<unit>_<style>`.
+                let Ok((unit_name, unit_rest)) = cdr.expect_cons_with_any_str_head() else {
+                    continue;
+                };
+                let unit = unit_name
+                    .strip_prefix(name)
+                    .and_then(|suffix| suffix.strip_prefix('_'))
+                    .and_then(|suffix| suffix.split('_').next())
+                    .and_then(|unit| unit.parse().ok())
+                    .unwrap_or_default();
+                let pins = cached.pins.entry(unit).or_default();
+                for pin in unit_rest.list_iter().into_iter().flatten() {
+                    if let Ok(cdr) = pin.expect_cons_with_symbol_head("pin") {
+                        pins.extend(pin_number(cdr));
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    Ok((id, cached))
+}
+
+/// Return the number of a pin from the arguments of its `(pin ...)` element.
+fn pin_number(cdr: &Value) -> Option<&str> {
+    cdr.list_iter()?.find_map(|e| e.expect_cons_with_symbol_head("number").ok()?.as_cons()?.car().as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, lexpr::sexp};
+
+    #[test]
+    fn test_lint_symbol_cache() {
+        let schematic = sexp!((kicad_sch (version 20231120)
+            (lib_symbols
+                (symbol "Amp:Dual" (property "Reference" "U")
+                    (symbol "Dual_0_1" (pin power_in line (at 0 0 0) (length 2.54) (number "8")))
+                    (symbol "Dual_1_1" (pin input line (at 0 0 0) (length 2.54) (number "1")))
+                    (symbol "Dual_2_1" (pin input line (at 0 0 0) (length 2.54) (number "2"))))
+                (symbol "Amp:Dual_B" (extends "Dual")))
+            (symbol (lib_id "Amp:Dual") (unit 2) (property "Reference" "U1") (pin "2") (pin "8"))
+            (symbol (lib_id "Amp:Dual") (unit 3) (property "Reference" "U2"))
+            (symbol (lib_id "Amp:Dual_B") (unit 1) (property "Reference" "U3") (pin "1") (pin "5"))
+            (symbol (lib_id "Device:R") (property "Reference" "R1"))));
+
+        let issues = lint_symbol_cache(&schematic).unwrap();
+        let rules: Vec<&str> = issues.iter().map(|issue| issue.rule.as_str()).collect();
+        assert_eq!(rules, ["unit-out-of-range", "unknown-pin", "missing-pin", "missing-cached-symbol"]);
+        assert_eq!(issues[1].location.as_deref(), Some("U3 pin 5"));
+        assert_eq!(issues[2].message, "U3 does not list pin 8 of unit 1 of Amp:Dual_B");
+    }
+}
@@ -0,0 +1,334 @@
+use {
+    super::{LintIssue, Severity},
+    crate::{
+        pcb::{PcbFootprint, PcbPad},
+        LexprExt, ParseError,
+    },
+    lexpr::Value,
+    serde::{Deserialize, Serialize},
+};
+
+/// Graphic items that can carry a layer.
+const GRAPHIC_ITEMS: &[&str] = &["fp_arc", "fp_circle", "fp_curve", "fp_line", "fp_poly", "fp_rect", "fp_text"];
+
+/// Options for [`lint_footprint`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FootprintLintOptions {
+    /// The text the reference designator field must hold, or `None` to skip the check. Library
+    /// footprints use `REF**`; footprints placed on a board hold real designators.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_reference: Option<String>,
+
+    /// Whether the value field must match the footprint name.
+    pub value_matches_name: bool,
+}
+
+/// An axis-aligned rectangle in millimeters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Rect {
+    min_x: f64,
+    min_y: f64,
+    max_x: f64,
+    max_y: f64,
+}
+
+/// The parts of a pad needed for linting.
+struct Pad {
+    number: String,
+    bounds: Rect,
+    front: bool,
+    back: bool,
+}
+
+impl Default for FootprintLintOptions {
+    fn default() -> Self {
+        Self {
+            expected_reference: Some("REF**".to_string()),
+            value_matches_name: true,
+        }
+    }
+}
+
+/// Check a footprint for common quality problems.
+///
+/// The footprint is given as the raw `(footprint ...)` (or pre-KiCad 6 `(module ...)`) element.
+/// Pad shapes are approximated by their bounding boxes. The rules checked are:
+/// * `pad-short`: copper pads with different numbers on the same side overlap.
+/// * `missing-courtyard`: there are no graphics on a courtyard layer.
+/// * `missing-fab`: there are no graphics on a fabrication layer.
+/// * `silk-over-pad`: a silkscreen line crosses a pad on the same side.
+/// * `reference-field`: the reference is not the expected text or is not on the front silkscreen.
+/// * `value-field`: the value does not match the footprint name or is not on the front fabrication
+///   layer.
+pub fn lint_footprint(footprint: &Value, options: &FootprintLintOptions) -> Result<Vec<LintIssue>, ParseError> {
+    let (head, rest) = footprint.expect_cons_with_any_symbol_head()?;
+    if head != "footprint" && head != "module" {
+        return Err(ParseError::ExpectedSymbol(footprint.clone(), "footprint".to_string()));
+    }
+
+    let (name, _) = rest.expect_cons_with_any_str_head()?;
+    let elements: Vec<&Value> = rest.list_iter().into_iter().flatten().collect();
+    let pads = elements.iter().filter_map(|element| pad(element.expect_cons_with_symbol_head("pad").ok()?)).collect();
+
+    Ok(lint_parts(name, pads, &elements, options))
+}
+
+/// Check a footprint placed on a board for the problems [`lint_footprint`] checks. Pads are taken
+/// from the parsed model, and graphics and fields from [`PcbFootprint::raw_elements`].
+pub fn lint_board_footprint(footprint: &PcbFootprint, options: &FootprintLintOptions) -> Vec<LintIssue> {
+    let pads = footprint.pads.iter().map(Pad::from).collect();
+    let elements: Vec<&Value> = footprint.raw_elements.iter().collect();
+    lint_parts(&footprint.lib_id, pads, &elements, options)
+}
+
+/// Check a footprint given its library identifier, its pads, and its other child elements.
+fn lint_parts(lib_id: &str, pads: Vec<Pad>, elements: &[&Value], options: &FootprintLintOptions) -> Vec<LintIssue> {
+    let name = lib_id.rsplit(':').next().unwrap_or_default();
+    let mut issues = Vec::new();
+    let mut layers = Vec::new();
+    let mut silk_lines = Vec::new();
+
+    for element in elements {
+        let Ok((key, cdr)) = element.expect_cons_with_any_symbol_head() else {
+            continue;
+        };
+
+        if GRAPHIC_ITEMS.contains(&key) {
+            let Some(layer) = child_str(cdr, "layer") else {
+                continue;
+            };
+
+            if key == "fp_line" && layer.ends_with(".SilkS") {
+                if let (Some(start), Some(end)) = (child_xy(cdr, "start"), child_xy(cdr, "end")) {
+                    silk_lines.push((layer.starts_with("F."), start, end));
+                }
+            }
+            layers.push(layer.to_string());
+        }
+    }
+
+    for (i, a) in pads.iter().enumerate() {
+        for b in &pads[i + 1..] {
+            let same_side = (a.front && b.front) || (a.back && b.back);
+            if same_side
+                && !a.number.is_empty()
+                && !b.number.is_empty()
+                && a.number != b.number
+                && a.bounds.overlaps(&b.bounds)
+            {
+                issues.push(LintIssue::new(
+                    "pad-short",
+                    Severity::Error,
+                    format!("Pads {} and {} overlap", a.number, b.number),
+                    Some(format!("pad {}", a.number)),
+                ));
+            }
+        }
+    }
+
+    if !layers.iter().any(|layer| layer.ends_with(".CrtYd")) {
+        issues.push(LintIssue::new("missing-courtyard", Severity::Error, "Footprint has no courtyard", None));
+    }
+
+    if !layers.iter().any(|layer| layer.ends_with(".Fab")) {
+        issues.push(LintIssue::new(
+            "missing-fab",
+            Severity::Warning,
+            "Footprint has no fabrication layer graphics",
+            None,
+        ));
+    }
+
+    for (front, start, end) in silk_lines {
+        for pad in pads.iter().filter(|pad| (front && pad.front) || (!front && pad.back)) {
+            if pad.bounds.intersects_segment(start, end) {
+                issues.push(LintIssue::new(
+                    "silk-over-pad",
+                    Severity::Warning,
+                    format!("Silkscreen line from {start:?} to {end:?} mm crosses pad {}", pad.number),
+                    Some(format!("pad {}", pad.number)),
+                ));
+            }
+        }
+    }
+
+    let (reference, reference_layer) = field(elements, "Reference", "reference");
+    if let Some(expected) = &options.expected_reference {
+        if reference != Some(expected.as_str()) {
+            issues.push(LintIssue::new(
+                "reference-field",
+                Severity::Warning,
+                format!("Reference is {:?}; expected {expected:?}", reference.unwrap_or_default()),
+                None,
+            ));
+        }
+    }
+    if reference.is_some() && reference_layer != Some("F.SilkS") {
+        issues.push(LintIssue::new("reference-field", Severity::Warning, "Reference is not on F.SilkS", None));
+    }
+
+    let (value, value_layer) = field(elements, "Value", "value");
+    if options.value_matches_name && value != Some(name) {
+        issues.push(LintIssue::new(
+            "value-field",
+            Severity::Warning,
+            format!("Value is {:?}; expected the footprint name {name:?}", value.unwrap_or_default()),
+            None,
+        ));
+    }
+    if value.is_some() && value_layer != Some("F.Fab") {
+        issues.push(LintIssue::new("value-field", Severity::Warning, "Value is not on F.Fab", None));
+    }
+
+    issues
+}
+
+impl Rect {
+    /// Return the bounding box of a `w` by `h` rectangle centered on `(x, y)` and rotated by
+    /// `angle` radians.
+    fn around(x: f64, y: f64, w: f64, h: f64, angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        let half_w = (w * cos).abs() / 2.0 + (h * sin).abs() / 2.0;
+        let half_h = (w * sin).abs() / 2.0 + (h * cos).abs() / 2.0;
+
+        Self {
+            min_x: x - half_w,
+            min_y: y - half_h,
+            max_x: x + half_w,
+            max_y: y + half_h,
+        }
+    }
+
+    /// Indicates whether the interiors of two rectangles overlap.
+    fn overlaps(&self, other: &Rect) -> bool {
+        self.min_x < other.max_x && other.min_x < self.max_x && self.min_y < other.max_y && other.min_y < self.max_y
+    }
+
+    /// Indicates whether a line segment passes through the interior of this rectangle, using
+    /// Liang-Barsky clipping.
+    fn intersects_segment(&self, (x0, y0): (f64, f64), (x1, y1): (f64, f64)) -> bool {
+        let (dx, dy) = (x1 - x0, y1 - y0);
+        let mut t0: f64 = 0.0;
+        let mut t1: f64 = 1.0;
+
+        for (p, q) in [(-dx, x0 - self.min_x), (dx, self.max_x - x0), (-dy, y0 - self.min_y), (dy, self.max_y - y0)] {
+            if p == 0.0 {
+                if q <= 0.0 {
+                    return false;
+                }
+            } else {
+                let t = q / p;
+                if p < 0.0 {
+                    t0 = t0.max(t);
+                } else {
+                    t1 = t1.min(t);
+                }
+            }
+        }
+
+        t0 < t1
+    }
+}
+
+/// Extract a pad's number, bounding box, and copper sides from the arguments of a `(pad ...)`.
+fn pad(cdr: &Value) -> Option<Pad> {
+    let number = cdr.as_cons()?.car();
+    let number = number.as_str().map(str::to_string).unwrap_or_else(|| number.to_string());
+
+    let at = child_values(cdr, "at")?;
+    let (x, y) = (at.first()?.as_float()?, at.get(1)?.as_float()?);
+    let angle = at.get(2).and_then(Value::as_float).unwrap_or_default().to_radians();
+
+    let size = child_values(cdr, "size")?;
+    let (w, h) = (size.first()?.as_float()?, size.get(1)?.as_float()?);
+
+    let layers = child_values(cdr, "layers").unwrap_or_default();
+    let layers: Vec<&str> = layers.iter().filter_map(|layer| layer.as_str().or_else(|| layer.as_symbol())).collect();
+    let front = layers.iter().any(|layer| *layer == "F.Cu" || layer.starts_with("*.Cu"));
+    let back = layers.iter().any(|layer| *layer == "B.Cu" || layer.starts_with("*.Cu"));
+
+    Some(Pad {
+        number,
+        bounds: Rect::around(x, y, w, h, angle),
+        front,
+        back,
+    })
+}
+
+impl From<&PcbPad> for Pad {
+    fn from(pad: &PcbPad) -> Self {
+        let angle = pad.position.angle.map(|angle| angle.radians()).unwrap_or_default();
+        let (x, y) = (pad.position.x.to_mm(), pad.position.y.to_mm());
+        let (w, h) = (pad.size.width.to_mm(), pad.size.height.to_mm());
+        let layers = |side: &str| pad.layers.iter().any(|layer| layer == side || layer.starts_with("*.Cu"));
+
+        Self {
+            number: pad.number.clone(),
+            bounds: Rect::around(x, y, w, h, angle),
+            front: layers("F.Cu"),
+            back: layers("B.Cu"),
+        }
+    }
+}
+
+/// Return the text and layer of a footprint field, from either a KiCad 8+ `(property "<key>" ...)`
+/// or an older `(fp_text <kind> ...)`.
+fn field<'a>(elements: &[&'a Value], key: &str, kind: &str) -> (Option<&'a str>, Option<&'a str>) {
+    for element in elements {
+        if let Ok(cdr) = element.expect_cons_with_symbol_head("property") {
+            if let Ok((k, cdr)) = cdr.expect_cons_with_any_str_head() {
+                if k == key {
+                    let text = cdr.expect_cons_with_any_str_head().ok().map(|(text, _)| text);
+                    return (text, child_str(cdr, "layer"));
+                }
+            }
+        } else if let Ok(cdr) = element.expect_cons_with_symbol_head("fp_text") {
+            if let Ok(cdr) = cdr.expect_cons_with_symbol_head(kind) {
+                let text = cdr.expect_cons_with_any_str_head().ok().map(|(text, _)| text);
+                return (text, child_str(cdr, "layer"));
+            }
+        }
+    }
+
+    (None, None)
+}
+
+/// Return the arguments of the first child element with the given head.
+fn child_values(rest: &Value, head: &str) -> Option<Vec<Value>> {
+    rest.list_iter()?.find_map(|element| element.expect_cons_with_symbol_head(head).ok()?.to_vec())
+}
+
+/// Return the first argument of a child element, which may be quoted or bare.
+fn child_str<'a>(rest: &'a Value, head: &str) -> Option<&'a str> {
+    rest.list_iter()?.find_map(|element| {
+        let value = element.expect_cons_with_symbol_head(head).ok()?.as_cons()?.car();
+        value.as_str().or_else(|| value.as_symbol())
+    })
+}
+
+/// Return the coordinates of a child `(<head> <x> <y>)` element.
+fn child_xy(rest: &Value, head: &str) -> Option<(f64, f64)> {
+    let values = child_values(rest, head)?;
+    Some((values.first()?.as_float()?, values.get(1)?.as_float()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, lexpr::sexp};
+
+    #[test]
+    fn test_lint_footprint() {
+        let footprint = sexp!((footprint "Demo:R_0603"
+            (layer "F.Cu")
+            (property "Reference" "REF**" (at 0 -1.5 0) (layer "F.SilkS"))
+            (property "Value" "R_0603" (at 0 1.5 0) (layer "F.SilkS"))
+            (fp_line (start -1 -0.8) (end 1 -0.8) (layer "F.Fab"))
+            (fp_line (start -0.9 -0.2) (end -0.9 0.2) (layer "F.SilkS"))
+            (pad "1" smd rect (at -0.8 0) (size 0.9 0.95) (layers "F.Cu" "F.Mask"))
+            (pad "2" smd rect (at 0 0 90) (size 0.95 0.9) (layers "F.Cu" "F.Mask"))));
+
+        let issues = lint_footprint(&footprint, &FootprintLintOptions::default()).unwrap();
+        let rules: Vec<&str> = issues.iter().map(|issue| issue.rule.as_str()).collect();
+        assert_eq!(rules, ["pad-short", "missing-courtyard", "silk-over-pad", "value-field"]);
+    }
+}
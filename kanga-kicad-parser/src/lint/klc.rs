@@ -0,0 +1,222 @@
+use {
+    super::{lint_footprint, lint_symbol, FootprintLintOptions, LintIssue, Severity, SymbolLintOptions},
+    crate::{common::Symbol, read_str, Error, LexprExt, ParseError},
+    lexpr::Value,
+    serde::{Deserialize, Serialize},
+    std::collections::{BTreeMap, BTreeSet},
+};
+
+/// The marker that introduces a suppression comment, e.g. `klc:ignore S4.1 F5.2`.
+pub const SUPPRESSION_MARKER: &str = "klc:ignore";
+
+/// A KiCad Library Convention rule and the lints that check it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct KlcRule {
+    /// The KLC rule number, e.g. `S4.1`.
+    pub number: &'static str,
+
+    /// The rule title.
+    pub title: &'static str,
+
+    /// The default severity of violations.
+    pub severity: Severity,
+
+    /// The lint rules from [`lint_symbol`] and [`lint_footprint`] that check this rule.
+    pub lints: &'static [&'static str],
+}
+
+/// The KLC rules covered by this crate's lints.
+///
+/// [Reference](https://klc.kicad.org/)
+pub const KLC_RULES: &[KlcRule] = &[
+    KlcRule {
+        number: "S4.1",
+        title: "General pin requirements",
+        severity: Severity::Error,
+        lints: &["off-grid-pin", "zero-length-pin", "pin-text-size"],
+    },
+    KlcRule {
+        number: "S4.3",
+        title: "Pin stacking",
+        severity: Severity::Error,
+        lints: &["duplicate-pin-number", "pin-overlap"],
+    },
+    KlcRule {
+        number: "S6.2",
+        title: "Symbol fields are correctly populated",
+        severity: Severity::Error,
+        lints: &["missing-property"],
+    },
+    KlcRule {
+        number: "F5.1",
+        title: "Silkscreen layer requirements",
+        severity: Severity::Warning,
+        lints: &["silk-over-pad", "reference-field"],
+    },
+    KlcRule {
+        number: "F5.2",
+        title: "Fabrication layer requirements",
+        severity: Severity::Warning,
+        lints: &["missing-fab", "value-field"],
+    },
+    KlcRule {
+        number: "F5.3",
+        title: "Courtyard layer requirements",
+        severity: Severity::Error,
+        lints: &["missing-courtyard"],
+    },
+    KlcRule {
+        number: "F6.3",
+        title: "Pad requirements",
+        severity: Severity::Error,
+        lints: &["pad-short"],
+    },
+];
+
+/// Configuration for checking library items against the KLC rules.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct KlcConfig {
+    /// Severity overrides, keyed by KLC rule number.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub severity: BTreeMap<String, Severity>,
+
+    /// KLC rule numbers that are not checked. A section number such as `S4` disables every rule in
+    /// that section.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub disabled: BTreeSet<String>,
+
+    /// Options for the symbol lints.
+    #[serde(default)]
+    pub symbol: SymbolLintOptions,
+
+    /// Options for the footprint lints.
+    #[serde(default)]
+    pub footprint: FootprintLintOptions,
+}
+
+/// Return the KLC rule checked by a lint rule.
+pub fn klc_rule(lint: &str) -> Option<&'static KlcRule> {
+    KLC_RULES.iter().find(|rule| rule.lints.contains(&lint))
+}
+
+impl KlcConfig {
+    /// Check a library symbol against the KLC rules.
+    ///
+    /// Rules can be suppressed for a single symbol by adding a `klc:ignore <number>...` marker to
+    /// any of its property values, typically the description.
+    pub fn check_symbol(&self, symbol: &Symbol) -> Vec<LintIssue> {
+        let mut suppressed = BTreeSet::new();
+        for property in &symbol.properties {
+            suppressed.extend(suppressions(&property.value));
+        }
+
+        self.apply(lint_symbol(symbol, &self.symbol), &suppressed)
+    }
+
+    /// Check a footprint, given as the raw `(footprint ...)` element, against the KLC rules.
+    ///
+    /// Rules can be suppressed for a single footprint by adding a `klc:ignore <number>...` marker to
+    /// its description (`descr`) or tags.
+    pub fn check_footprint(&self, footprint: &Value) -> Result<Vec<LintIssue>, ParseError> {
+        let issues = lint_footprint(footprint, &self.footprint)?;
+        let (_, rest) = footprint.expect_cons_with_any_symbol_head()?;
+
+        let mut suppressed = BTreeSet::new();
+        for element in rest.list_iter().into_iter().flatten() {
+            for head in ["descr", "tags"] {
+                if let Ok(cdr) = element.expect_cons_with_symbol_head(head) {
+                    suppressed
+                        .extend(cdr.list_iter().into_iter().flatten().filter_map(Value::as_str).flat_map(suppressions));
+                }
+            }
+        }
+
+        Ok(self.apply(issues, &suppressed))
+    }
+
+    /// Check a footprint file (`.kicad_mod`) against the KLC rules.
+    ///
+    /// In addition to the markers recognized by [`KlcConfig::check_footprint`], suppression
+    /// markers may appear in `;` comments anywhere in the file.
+    pub fn check_footprint_str(&self, text: &str) -> Result<Vec<LintIssue>, Error> {
+        let footprint = read_str(text)?;
+        let issues = self.check_footprint(&footprint)?;
+        let suppressed = suppressions(text);
+        Ok(issues.into_iter().filter(|issue| !is_covered(&suppressed, &issue.rule)).collect())
+    }
+
+    /// Convert lint issues to KLC issues, applying severity overrides and dropping disabled and
+    /// suppressed rules.
+    fn apply(&self, issues: Vec<LintIssue>, suppressed: &BTreeSet<String>) -> Vec<LintIssue> {
+        issues
+            .into_iter()
+            .filter_map(|issue| {
+                let rule = klc_rule(&issue.rule)?;
+                if is_covered(&self.disabled, rule.number) || is_covered(suppressed, rule.number) {
+                    return None;
+                }
+
+                Some(LintIssue {
+                    rule: rule.number.to_string(),
+                    severity: self.severity.get(rule.number).copied().unwrap_or(rule.severity),
+                    message: format!("{} ({})", issue.message, issue.rule),
+                    location: issue.location,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Return the KLC rule numbers named by the suppression markers in a piece of text.
+pub fn suppressions(text: &str) -> BTreeSet<String> {
+    let mut result = BTreeSet::new();
+
+    for (start, _) in text.match_indices(SUPPRESSION_MARKER) {
+        let rest = &text[start + SUPPRESSION_MARKER.len()..];
+        let numbers = rest.split(|c: char| !c.is_ascii_alphanumeric() && c != '.').filter(|word| !word.is_empty());
+        result.extend(numbers.take_while(|word| is_rule_number(word)).map(str::to_string));
+    }
+
+    result
+}
+
+/// Indicates whether a word looks like a KLC rule or section number, e.g. `S4` or `F5.3`.
+fn is_rule_number(word: &str) -> bool {
+    matches!(word.chars().next(), Some('F' | 'G' | 'M' | 'S'))
+        && word[1..].split('.').all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Indicates whether a rule number is named, directly or by section, in a set of rule numbers.
+fn is_covered(numbers: &BTreeSet<String>, number: &str) -> bool {
+    numbers.iter().any(|n| number == n || number.strip_prefix(n.as_str()).is_some_and(|rest| rest.starts_with('.')))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_klc_footprint() {
+        let text = r#"; klc:ignore F5.2
+            (footprint "R_0603"
+                (descr "Resistor, klc:ignore F6")
+                (property "Reference" "REF**" (at 0 -1.5 0) (layer "F.SilkS"))
+                (property "Value" "R_0603" (at 0 1.5 0) (layer "F.Fab"))
+                (pad "1" smd rect (at -0.8 0) (size 0.9 0.95) (layers "F.Cu"))
+                (pad "2" smd rect (at 0 0) (size 0.9 0.95) (layers "F.Cu")))"#;
+
+        assert_eq!(suppressions(text), ["F5.2", "F6"].map(String::from).into_iter().collect());
+
+        let mut config = KlcConfig::default();
+        config.severity.insert("F5.3".to_string(), Severity::Warning);
+
+        let issues = config.check_footprint_str(text).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "F5.3");
+        assert_eq!(issues[0].severity, Severity::Warning);
+        assert_eq!(issues[0].message, "Footprint has no courtyard (missing-courtyard)");
+
+        config.disabled.insert("F5".to_string());
+        assert!(config.check_footprint_str(text).unwrap().is_empty());
+    }
+}
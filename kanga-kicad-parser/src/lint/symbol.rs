@@ -0,0 +1,234 @@
+use {
+    super::{LintIssue, Severity},
+    crate::common::{Nm, Symbol, SymbolPinElectricalType, TextEffects},
+    serde::{Deserialize, Serialize},
+    std::collections::{BTreeMap, HashMap},
+};
+
+/// Options for [`lint_symbol`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SymbolLintOptions {
+    /// The grid pin connection points must lie on, in nanometers. Zero disables the check.
+    pub pin_grid: u64,
+
+    /// Properties every symbol must have with a non-empty value.
+    pub required_properties: Vec<String>,
+
+    /// The expected height and width of pin name and number text, in nanometers. Zero disables the
+    /// check.
+    pub pin_text_size: u64,
+}
+
+/// Summary statistics for a library symbol, counting the items of all of its units in the normal
+/// body style.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SymbolStatistics {
+    /// The number of pins.
+    pub pins: usize,
+
+    /// The number of pins of each electrical type.
+    pub pins_by_type: BTreeMap<SymbolPinElectricalType, usize>,
+
+    /// The number of distinct pin numbers.
+    pub distinct_pin_numbers: usize,
+
+    /// The number of graphic items.
+    pub graphics: usize,
+
+    /// The number of properties.
+    pub properties: usize,
+}
+
+impl Default for SymbolLintOptions {
+    fn default() -> Self {
+        Self {
+            pin_grid: 1_270_000,
+            required_properties: ["Reference", "Value", "Footprint", "Datasheet"].map(String::from).to_vec(),
+            pin_text_size: 1_270_000,
+        }
+    }
+}
+
+impl SymbolStatistics {
+    /// Compute statistics for a symbol.
+    pub fn of(symbol: &Symbol) -> Self {
+        let mut pins_by_type = BTreeMap::new();
+        for pin in symbol.unit_pins(None) {
+            *pins_by_type.entry(pin.electrical_type).or_default() += 1;
+        }
+
+        let mut numbers: Vec<&str> = symbol.unit_pins(None).map(|pin| pin.number.number.as_str()).collect();
+        numbers.sort_unstable();
+        numbers.dedup();
+
+        Self {
+            pins: symbol.unit_pins(None).count(),
+            pins_by_type,
+            distinct_pin_numbers: numbers.len(),
+            graphics: symbol.graphics.len() + symbol.units_of(None).map(|unit| unit.graphics.len()).sum::<usize>(),
+            properties: symbol.properties.len(),
+        }
+    }
+}
+
+/// Check a library symbol for common quality problems.
+///
+/// The rules checked are:
+/// * `missing-property`: a required property is absent or empty. Footprint and datasheet may be
+///   empty only on symbols that extend another symbol, as they inherit them.
+/// * `duplicate-pin-number`: two pins share a number.
+/// * `pin-overlap`: two pins of the same unit with different names share a connection point.
+///   Stacked pins with the same name are allowed.
+/// * `off-grid-pin`: a pin's connection point is not on the pin grid.
+/// * `zero-length-pin`: a pin has no length.
+/// * `pin-text-size`: a pin name or number is not drawn at the expected size.
+pub fn lint_symbol(symbol: &Symbol, options: &SymbolLintOptions) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+
+    for name in &options.required_properties {
+        let value = symbol.properties.iter().find(|property| &property.key == name).map(|property| &property.value);
+        let inherited = symbol.extends.is_some() && name != "Reference" && name != "Value";
+
+        match value {
+            None if !inherited => issues.push(LintIssue::new(
+                "missing-property",
+                Severity::Error,
+                format!("Symbol {} has no {name} property", symbol.id),
+                None,
+            )),
+            Some(value) if value.is_empty() && (name == "Reference" || name == "Value") => issues.push(LintIssue::new(
+                "missing-property",
+                Severity::Error,
+                format!("Symbol {} has an empty {name} property", symbol.id),
+                None,
+            )),
+            _ => (),
+        }
+    }
+
+    let mut by_number: HashMap<&str, usize> = HashMap::new();
+    let mut by_position: HashMap<(i64, i64, i64), &str> = HashMap::new();
+
+    // Only the normal body style is checked; an alternate style repeats the same pins.
+    let units = symbol.units_of(None).map(|unit| (unit.unit_number().map_or(0, |(number, _)| number), unit));
+    let pins =
+        [(0, symbol)].into_iter().chain(units).flat_map(|(unit, owner)| owner.pins.iter().map(move |pin| (unit, pin)));
+
+    for (unit, pin) in pins {
+        let number = pin.number.number.as_str();
+        let name = pin.name.name.as_str();
+        let location = Some(format!("pin {number}"));
+
+        let count = by_number.entry(number).or_default();
+        *count += 1;
+        if *count == 2 {
+            issues.push(LintIssue::new(
+                "duplicate-pin-number",
+                Severity::Error,
+                format!("Pin number {number} is used more than once"),
+                location.clone(),
+            ));
+        }
+
+        let position = (unit, pin.position.x.0, pin.position.y.0);
+        match by_position.get(&position) {
+            Some(other) if *other != name => issues.push(LintIssue::new(
+                "pin-overlap",
+                Severity::Error,
+                format!("Pin {number} ({name}) overlaps a pin named {other}"),
+                location.clone(),
+            )),
+            Some(_) => (),
+            None => {
+                by_position.insert(position, name);
+            }
+        }
+
+        let grid = options.pin_grid as i64;
+        if grid != 0 && (pin.position.x.0 % grid != 0 || pin.position.y.0 % grid != 0) {
+            issues.push(LintIssue::new(
+                "off-grid-pin",
+                Severity::Warning,
+                format!(
+                    "Pin {number} at ({}, {}) mm is not on the {} mm grid",
+                    pin.position.x,
+                    pin.position.y,
+                    Nm(grid)
+                ),
+                location.clone(),
+            ));
+        }
+
+        if pin.length == Nm::ZERO {
+            issues.push(LintIssue::new(
+                "zero-length-pin",
+                Severity::Warning,
+                format!("Pin {number} has zero length"),
+                location.clone(),
+            ));
+        }
+
+        for (what, effects) in [("name", &pin.name.text_effects), ("number", &pin.number.text_effects)] {
+            if let Some((height, width)) = text_size(effects) {
+                let expected = Nm(options.pin_text_size as i64);
+                if expected != Nm::ZERO && (height != expected || width != expected) {
+                    issues.push(LintIssue::new(
+                        "pin-text-size",
+                        Severity::Warning,
+                        format!("Pin {number} {what} is {} x {} mm; expected {} mm", width, height, expected),
+                        location.clone(),
+                    ));
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+/// Return the font height and width of some text effects, if specified.
+fn text_size(effects: &TextEffects) -> Option<(Nm, Nm)> {
+    effects.font.as_ref().map(|font| (font.size.height, font.size.width))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, lexpr::sexp};
+
+    #[test]
+    fn test_lint_symbol() {
+        let symbol = Symbol::try_from(&sexp!((symbol "Demo"
+            (property "Reference" "U" (id 0) (at 0 0 0) (effects (font (size 1.27 1.27))))
+            (property "Value" "Demo" (id 1) (at 0 0 0) (effects (font (size 1.27 1.27))))
+            (pin input line (at 0 0 0) (length 2.54)
+                (name "A" (effects (font (size 1.27 1.27))))
+                (number "1" (effects (font (size 1.27 1.27)))))
+            (pin input line (at 0 0 0) (length 2.54)
+                (name "B" (effects (font (size 1.27 1.27))))
+                (number "1" (effects (font (size 1.0 1.0)))))
+            (pin passive line (at 0.5 2.54 0) (length 0)
+                (name "C" (effects (font (size 1.27 1.27))))
+                (number "3" (effects (font (size 1.27 1.27))))))))
+        .unwrap();
+
+        let issues = lint_symbol(&symbol, &SymbolLintOptions::default());
+        let rules: Vec<&str> = issues.iter().map(|issue| issue.rule.as_str()).collect();
+        assert_eq!(
+            rules,
+            [
+                "missing-property",
+                "missing-property",
+                "duplicate-pin-number",
+                "pin-overlap",
+                "pin-text-size",
+                "off-grid-pin",
+                "zero-length-pin"
+            ]
+        );
+
+        let stats = SymbolStatistics::of(&symbol);
+        assert_eq!(stats.pins, 3);
+        assert_eq!(stats.distinct_pin_numbers, 2);
+        assert_eq!(stats.pins_by_type[&SymbolPinElectricalType::Input], 2);
+    }
+}
@@ -0,0 +1,417 @@
+use {
+    crate::{LexprExt, ParseError},
+    lexpr::Value,
+    serde::{Deserialize, Serialize},
+    std::{collections::HashMap, time::SystemTime},
+};
+
+/// The result of a three-way merge.
+#[derive(Clone, Debug)]
+pub struct MergeResult {
+    /// The merged document. Where a conflict was found, our version of the element is used.
+    pub merged: Value,
+
+    /// The conflicts left unresolved, in document order. These can be serialized, resolved later, and
+    /// replayed with [`merge_with`] using a slice of [`ConflictResolution`]s as the resolver.
+    pub conflicts: Vec<MergeConflict>,
+
+    /// The conflicts settled by the resolver, in document order.
+    pub resolved: Vec<ConflictResolution>,
+}
+
+/// An element changed differently by both sides of a merge.
+///
+/// Each version is the element as written in the document, or `None` if it is absent from that
+/// version.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MergeConflict {
+    /// The path of the conflicting element, e.g. `kicad_sch > wire[0e1c...] > pts`.
+    pub path: String,
+
+    /// The element in the common ancestor.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+
+    /// The element in our version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ours: Option<String>,
+
+    /// The element in their version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theirs: Option<String>,
+}
+
+/// How a conflict was, or should be, settled.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Resolution {
+    /// Use the element from our version.
+    Ours,
+
+    /// Use the element from their version.
+    Theirs,
+
+    /// Use the element from the common ancestor, discarding both changes.
+    Base,
+
+    /// Leave the conflict for later resolution. Our version is used in the merged document.
+    Unresolved,
+}
+
+/// A conflict paired with its resolution.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ConflictResolution {
+    /// The conflict being resolved.
+    pub conflict: MergeConflict,
+
+    /// How it was resolved.
+    pub resolution: Resolution,
+}
+
+/// Decides how conflicts found during a merge are settled.
+///
+/// This is implemented by [`MergeStrategy`], by closures taking a [`MergeConflict`] (for interactive
+/// resolution), and by slices of previously recorded [`ConflictResolution`]s.
+pub trait ConflictResolver {
+    /// Choose how to settle a conflict.
+    fn resolve(&mut self, conflict: &MergeConflict) -> Resolution;
+}
+
+/// Built-in conflict resolution strategies.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum MergeStrategy {
+    /// Report every conflict without resolving it.
+    #[default]
+    Manual,
+
+    /// Resolve every conflict in favor of our version.
+    PreferOurs,
+
+    /// Resolve every conflict in favor of their version.
+    PreferTheirs,
+
+    /// Resolve every conflict in favor of whichever version was modified most recently. Ties favor our
+    /// version.
+    PreferNewest {
+        /// When our version was last modified.
+        ours: SystemTime,
+
+        /// When their version was last modified.
+        theirs: SystemTime,
+    },
+}
+
+/// Mutable state used while merging.
+struct Merger<'a> {
+    resolver: &'a mut dyn ConflictResolver,
+    conflicts: Vec<MergeConflict>,
+    resolved: Vec<ConflictResolution>,
+}
+
+/// How the children of a list are matched up between versions.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum ChildKey {
+    /// A non-list value, identified by its position among the non-list values of its parent.
+    Atom(usize),
+
+    /// An element carrying a `(uuid ...)` child.
+    Uuid(String),
+
+    /// Any other element, identified by its head symbol, its first string argument (if any), and its
+    /// position among elements with the same head and argument.
+    Named(String, String, usize),
+}
+
+impl MergeResult {
+    /// Indicates whether the merge completed without conflicts.
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+impl ConflictResolver for MergeStrategy {
+    fn resolve(&mut self, _conflict: &MergeConflict) -> Resolution {
+        match self {
+            Self::Manual => Resolution::Unresolved,
+            Self::PreferOurs => Resolution::Ours,
+            Self::PreferTheirs => Resolution::Theirs,
+            Self::PreferNewest {
+                ours,
+                theirs,
+            } => {
+                if theirs > ours {
+                    Resolution::Theirs
+                } else {
+                    Resolution::Ours
+                }
+            }
+        }
+    }
+}
+
+impl<F> ConflictResolver for F
+where
+    F: FnMut(&MergeConflict) -> Resolution,
+{
+    fn resolve(&mut self, conflict: &MergeConflict) -> Resolution {
+        self(conflict)
+    }
+}
+
+impl ConflictResolver for &[ConflictResolution] {
+    fn resolve(&mut self, conflict: &MergeConflict) -> Resolution {
+        self.iter()
+            .find(|recorded| recorded.conflict == *conflict)
+            .map_or(Resolution::Unresolved, |recorded| recorded.resolution)
+    }
+}
+
+/// Perform a three-way merge of two KiCad documents derived from a common ancestor.
+///
+/// Elements are matched between versions by their UUID where they have one, and otherwise by
+/// their head symbol and name (e.g. `(property "Reference" ...)` or `(symbol "Device:R" ...)`). A
+/// change made on only one side is taken; elements changed on both sides are merged recursively,
+/// and a [`MergeConflict`] is reported only where both sides changed the same value differently.
+/// Coordinates and other multi-part values, such as `(at x y angle)` and `(pts ...)`, are merged
+/// as a whole.
+///
+/// This is suitable for use as a git merge driver: write [`MergeResult::merged`] back to our file
+/// and exit with a non-zero status if [`MergeResult::is_clean`] is false.
+pub fn merge(base: &Value, ours: &Value, theirs: &Value) -> Result<MergeResult, ParseError> {
+    merge_with(base, ours, theirs, &mut MergeStrategy::Manual)
+}
+
+/// Perform a three-way merge as with [`merge`], settling conflicts with `resolver`.
+pub fn merge_with<R>(base: &Value, ours: &Value, theirs: &Value, resolver: &mut R) -> Result<MergeResult, ParseError>
+where
+    R: ConflictResolver,
+{
+    let (root, _) = base.expect_cons_with_any_symbol_head()?;
+    ours.expect_cons_with_symbol_head(root)?;
+    theirs.expect_cons_with_symbol_head(root)?;
+
+    let mut merger = Merger {
+        resolver,
+        conflicts: Vec::new(),
+        resolved: Vec::new(),
+    };
+    let merged = merger.merge_list(base, ours, theirs, root);
+
+    Ok(MergeResult {
+        merged,
+        conflicts: merger.conflicts,
+        resolved: merger.resolved,
+    })
+}
+
+impl Merger<'_> {
+    /// Merge three versions of a list element whose heads match.
+    fn merge_list(&mut self, base: &Value, ours: &Value, theirs: &Value, path: &str) -> Value {
+        let base = keyed_children(base);
+        let ours = keyed_children(ours);
+        let theirs = keyed_children(theirs);
+
+        let base_map: HashMap<&ChildKey, &Value> = base.iter().map(|(key, value)| (key, value)).collect();
+        let ours_map: HashMap<&ChildKey, &Value> = ours.iter().map(|(key, value)| (key, value)).collect();
+        let theirs_map: HashMap<&ChildKey, &Value> = theirs.iter().map(|(key, value)| (key, value)).collect();
+
+        // Our order is kept; elements only they have are placed after the element preceding them in
+        // their version.
+        let mut order: Vec<&ChildKey> = ours.iter().map(|(key, _)| key).collect();
+        let mut previous: Option<&ChildKey> = None;
+        for (key, _) in &theirs {
+            if !ours_map.contains_key(key) {
+                let index = previous.and_then(|p| order.iter().position(|k| *k == p)).map_or(0, |i| i + 1);
+                order.insert(index, key);
+            }
+            previous = Some(key);
+        }
+        for (key, _) in &base {
+            if !order.contains(&key) {
+                order.push(key);
+            }
+        }
+
+        let mut result = Vec::with_capacity(order.len());
+
+        for key in order {
+            let b = base_map.get(key).copied();
+            let o = ours_map.get(key).copied();
+            let t = theirs_map.get(key).copied();
+            let child_path = match key {
+                ChildKey::Atom(_) => path.to_string(),
+                ChildKey::Uuid(uuid) => format!("{path} > {}[{uuid}]", head(o.or(t).or(b))),
+                ChildKey::Named(head, name, _) if name.is_empty() => format!("{path} > {head}"),
+                ChildKey::Named(head, name, _) => format!("{path} > {head}[{name}]"),
+            };
+
+            let merged = if o == t || t == b {
+                o.cloned()
+            } else if o == b {
+                t.cloned()
+            } else {
+                let is_element = !matches!(key, ChildKey::Atom(_)) && !ATOMIC_ELEMENTS.contains(&head(o.or(t).or(b)));
+                match (b, o, t) {
+                    (Some(b), Some(o), Some(t)) if is_element && b.is_cons() && o.is_cons() && t.is_cons() => {
+                        Some(self.merge_list(b, o, t, &child_path))
+                    }
+                    _ => {
+                        let conflict = MergeConflict {
+                            path: child_path,
+                            base: b.map(Value::to_string),
+                            ours: o.map(Value::to_string),
+                            theirs: t.map(Value::to_string),
+                        };
+
+                        let resolution = self.resolver.resolve(&conflict);
+                        if resolution == Resolution::Unresolved {
+                            self.conflicts.push(conflict);
+                        } else {
+                            self.resolved.push(ConflictResolution {
+                                conflict,
+                                resolution,
+                            });
+                        }
+
+                        match resolution {
+                            Resolution::Ours | Resolution::Unresolved => o.cloned(),
+                            Resolution::Theirs => t.cloned(),
+                            Resolution::Base => b.cloned(),
+                        }
+                    }
+                }
+            };
+
+            result.extend(merged);
+        }
+
+        Value::list(result)
+    }
+}
+
+/// Elements whose arguments together form one value, such as a coordinate, and so are merged
+/// whole: changing `x` on one side and `y` on the other is a conflict, not a new point.
+const ATOMIC_ELEMENTS: &[&str] = &["at", "end", "mid", "pts", "size", "start", "xy"];
+
+/// Split a list into its children, each paired with the key used to match it across versions.
+fn keyed_children(value: &Value) -> Vec<(ChildKey, Value)> {
+    let mut atoms = 0;
+    let mut occurrences: HashMap<(String, String), usize> = HashMap::new();
+
+    value
+        .to_vec()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|child| {
+            let key = match child.expect_cons_with_any_symbol_head() {
+                Err(_) => {
+                    atoms += 1;
+                    ChildKey::Atom(atoms - 1)
+                }
+                Ok((head, rest)) => match uuid_of(rest) {
+                    Some(uuid) => ChildKey::Uuid(uuid.to_string()),
+                    None => {
+                        let name = rest.as_cons().and_then(|cons| cons.car().as_str()).unwrap_or_default();
+                        let id = (head.to_string(), name.to_string());
+                        let occurrence = occurrences.entry(id.clone()).or_default();
+                        *occurrence += 1;
+                        ChildKey::Named(id.0, id.1, *occurrence - 1)
+                    }
+                },
+            };
+            (key, child)
+        })
+        .collect()
+}
+
+/// Return the UUID of an element from its arguments, which may be quoted or bare.
+fn uuid_of(rest: &Value) -> Option<&str> {
+    rest.list_iter()?.find_map(|element| {
+        let cdr = element.expect_cons_with_symbol_head("uuid").ok()?;
+        let uuid = cdr.as_cons()?.car();
+        uuid.as_str().or_else(|| uuid.as_symbol())
+    })
+}
+
+/// Return the head symbol of an element, or an empty string.
+fn head(value: Option<&Value>) -> &str {
+    value.and_then(|value| value.expect_cons_with_any_symbol_head().ok()).map_or("", |(head, _)| head)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, lexpr::sexp};
+
+    #[test]
+    fn test_merge() {
+        let base = sexp!((kicad_sch
+            (version 20231120)
+            (wire (pts (xy 0 0) (xy 1 0)) (uuid "w1"))
+            (junction (at 1 0) (uuid "j1"))
+            (label "A" (at 0 0 0) (uuid "l1"))));
+
+        let ours = sexp!((kicad_sch
+            (version 20231120)
+            (wire (pts (xy 0 0) (xy 2 0)) (uuid "w1"))
+            (junction (at 1 0) (uuid "j1"))
+            (label "A" (at 5 0 0) (uuid "l1"))));
+
+        let theirs = sexp!((kicad_sch
+            (version 20231120)
+            (wire (pts (xy 0 0) (xy 1 0)) (uuid "w1"))
+            (label "A" (at 0 0 90) (uuid "l1"))
+            (no_connect (at 3 3) (uuid "n1"))));
+
+        let result = merge(&base, &ours, &theirs).unwrap();
+        assert_eq!(
+            result.merged,
+            sexp!((kicad_sch
+                (version 20231120)
+                (wire (pts (xy 0 0) (xy 2 0)) (uuid "w1"))
+                (label "A" (at 5 0 0) (uuid "l1"))
+                (no_connect (at 3 3) (uuid "n1"))))
+        );
+
+        // Both sides moved the label, so its position is a conflict rather than a mix of the two.
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].path, "kicad_sch > label[l1] > at");
+        assert_eq!(result.conflicts[0].ours.as_deref(), Some("(at 5 0 0)"));
+        assert_eq!(result.conflicts[0].theirs.as_deref(), Some("(at 0 0 90)"));
+
+        let theirs = sexp!((kicad_sch
+            (version 20231120)
+            (wire (pts (xy 0 0) (xy 3 0)) (uuid "w1"))
+            (junction (at 1 0) (uuid "j1"))
+            (label "A" (at 0 0 0) (uuid "l1"))));
+
+        let result = merge(&base, &ours, &theirs).unwrap();
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].path, "kicad_sch > wire[w1] > pts");
+        assert_eq!(result.conflicts[0].ours.as_deref(), Some("(pts (xy 0 0) (xy 2 0))"));
+        assert_eq!(result.conflicts[0].theirs.as_deref(), Some("(pts (xy 0 0) (xy 3 0))"));
+
+        let theirs_wire = sexp!((wire (pts (xy 0 0) (xy 3 0)) (uuid "w1")));
+        let preferred = merge_with(&base, &ours, &theirs, &mut MergeStrategy::PreferTheirs).unwrap();
+        assert!(preferred.is_clean());
+        assert_eq!(preferred.resolved.len(), 1);
+        assert_eq!(preferred.merged.to_vec().unwrap()[2], theirs_wire);
+
+        // Unresolved conflicts can be recorded and replayed.
+        let recorded = vec![ConflictResolution {
+            conflict: result.conflicts[0].clone(),
+            resolution: Resolution::Base,
+        }];
+        let replayed = merge_with(&base, &ours, &theirs, &mut recorded.as_slice()).unwrap();
+        assert!(replayed.is_clean());
+        assert_eq!(replayed.merged.to_vec().unwrap()[2], sexp!((wire (pts (xy 0 0) (xy 1 0)) (uuid "w1"))));
+
+        let mut asked = 0;
+        let interactive = merge_with(&base, &ours, &theirs, &mut |_: &MergeConflict| {
+            asked += 1;
+            Resolution::Unresolved
+        })
+        .unwrap();
+        assert_eq!(asked, 1);
+        assert_eq!(interactive.conflicts, result.conflicts);
+    }
+}
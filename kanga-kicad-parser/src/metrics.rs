@@ -0,0 +1,211 @@
+use {
+    crate::common::{Nm, Size, TextEffects},
+    std::{
+        collections::HashMap,
+        sync::{Mutex, OnceLock},
+    },
+};
+
+/// The number of glyph units in the height of the stroke font.
+const GLYPH_UNITS_PER_HEIGHT: f64 = 21.0;
+
+/// The horizontal shift of the top of an italic glyph, as a fraction of its height.
+const ITALIC_TILT: f64 = 1.0 / 8.0;
+
+/// The distance between baselines, as a multiple of the text height, at a line spacing of 1.
+const INTERLINE_PITCH_RATIO: f64 = 1.62;
+
+/// The size of subscript and superscript text relative to the surrounding text.
+const SUB_SUPER_SCALE: f64 = 0.8;
+
+/// The font size KiCad uses when text effects have no font, in nanometers.
+const DEFAULT_TEXT_SIZE: Nm = Nm(1_270_000);
+
+/// The number of distinct lines [`TextMetrics`] remembers before it starts over.
+const MAX_CACHED_LINES: usize = 4096;
+
+/// Measures text drawn in KiCad's stroke font.
+///
+/// The advance of each line of text, in glyph units, is cached, so measuring the same reference,
+/// value, or net name again at another size or style costs one lookup. The cache is shared between
+/// threads and is cleared when it grows past a few thousand lines.
+///
+/// Glyph advances approximate KiCad's built-in stroke font; text set in a TrueType face is
+/// measured as if it used the stroke font.
+#[derive(Debug, Default)]
+pub struct TextMetrics {
+    lines: Mutex<HashMap<String, f64>>,
+}
+
+impl TextMetrics {
+    /// Create an empty measurer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the size of the box enclosing `text` drawn with `effects`, including the pen width.
+    /// Lines are separated by `\n`, and KiCad's overbar, subscript, and superscript markup is
+    /// measured as drawn.
+    pub fn measure(&self, text: &str, effects: &TextEffects) -> Size {
+        let font = effects.font.as_ref();
+        let height = font.map(|font| font.size.height).unwrap_or(DEFAULT_TEXT_SIZE).0 as f64;
+        let width = font.map(|font| font.size.width).unwrap_or(DEFAULT_TEXT_SIZE).0 as f64;
+        let bold = font.is_some_and(|font| font.bold);
+        let italic = font.is_some_and(|font| font.italic);
+
+        let pen = match font.and_then(|font| font.thickness) {
+            Some(thickness) => thickness as f64,
+            None if bold => width / 5.0,
+            None => width / 8.0,
+        };
+
+        let line_spacing = font.and_then(|font| font.line_spacing).map(|spacing| spacing as f64 / 1e6).unwrap_or(1.0);
+
+        let mut lines = 0;
+        let mut advance: f64 = 0.0;
+        for line in text.split('\n') {
+            lines += 1;
+            advance = advance.max(self.line_advance(line));
+        }
+
+        let mut text_width = advance * width / GLYPH_UNITS_PER_HEIGHT;
+        if italic {
+            text_width += height * ITALIC_TILT;
+        }
+
+        let text_height = height + (lines - 1) as f64 * height * line_spacing * INTERLINE_PITCH_RATIO;
+
+        Size::new(Nm((text_width + pen).round() as i64), Nm((text_height + pen).round() as i64))
+    }
+
+    /// Return the advance of a single line of text in glyph units, where the font is 21 units
+    /// high.
+    pub fn line_advance(&self, line: &str) -> f64 {
+        if let Some(advance) = self.lines.lock().unwrap().get(line) {
+            return *advance;
+        }
+
+        let advance = markup_advance(line);
+
+        let mut lines = self.lines.lock().unwrap();
+        if lines.len() >= MAX_CACHED_LINES {
+            lines.clear();
+        }
+        lines.insert(line.to_string(), advance);
+        advance
+    }
+
+    /// Forget all cached lines.
+    pub fn clear(&self) {
+        self.lines.lock().unwrap().clear();
+    }
+}
+
+/// Return the size of the box enclosing `text` drawn with `effects`, using a cache shared by the
+/// whole process. See [`TextMetrics::measure`].
+pub fn measure_text(text: &str, effects: &TextEffects) -> Size {
+    static METRICS: OnceLock<TextMetrics> = OnceLock::new();
+    METRICS.get_or_init(TextMetrics::new).measure(text, effects)
+}
+
+/// Return the advance of a line of text, skipping markup delimiters and scaling subscripts and
+/// superscripts.
+fn markup_advance(line: &str) -> f64 {
+    let mut advance = 0.0;
+    // The scale of each open markup group; a `}` outside a group is drawn as is.
+    let mut groups: Vec<f64> = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let scale = groups.last().copied().unwrap_or(1.0);
+
+        match c {
+            '~' | '_' | '^' if chars.peek() == Some(&'{') => {
+                chars.next();
+                groups.push(if c == '~' {
+                    scale
+                } else {
+                    scale * SUB_SUPER_SCALE
+                });
+            }
+
+            '}' if !groups.is_empty() => {
+                groups.pop();
+            }
+
+            _ => advance += glyph_advance(c) * scale,
+        }
+    }
+
+    advance
+}
+
+/// Return the advance of a glyph in glyph units.
+fn glyph_advance(c: char) -> f64 {
+    match c {
+        ' ' => 16.0,
+        '\'' | '.' | ',' | ':' | ';' | '!' | '|' | '`' | 'i' | 'j' | 'l' => 8.0,
+        'I' => 10.0,
+        '"' | '(' | ')' | '[' | ']' | '{' | '}' => 12.0,
+        'f' | 'r' | 't' => 14.0,
+        '-' => 18.0,
+        'm' | 'w' | 'M' | 'W' => 26.0,
+        '@' => 28.0,
+        '0'..='9' => 20.0,
+        'a'..='z' => 19.0,
+        'A'..='Z' => 22.0,
+        // Wide scripts are drawn on a square cell; other characters get a typical capital width.
+        c if is_wide(c) => GLYPH_UNITS_PER_HEIGHT * 1.2,
+        c if c.is_control() => 0.0,
+        _ => 22.0,
+    }
+}
+
+/// Indicates whether a character is from a script KiCad draws at full width, such as CJK.
+fn is_wide(c: char) -> bool {
+    matches!(c as u32, 0x1100..=0x115F | 0x2E80..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF | 0xFF00..=0xFF60)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::common::{Font, Nm, TextEffects},
+    };
+
+    #[test]
+    fn test_measure_text() {
+        let effects = |bold, italic| TextEffects {
+            font: Some(Font {
+                face: None,
+                size: Size::new(Nm(1_270_000), Nm(1_270_000)),
+                thickness: None,
+                bold,
+                italic,
+                line_spacing: None,
+                color: None,
+            }),
+            justify: None,
+            hide: false,
+        };
+
+        let metrics = TextMetrics::new();
+        let plain = metrics.measure("GND", &effects(false, false));
+        assert_eq!(plain.height, Nm(1_270_000 + 158_750));
+        assert_eq!(plain.width, Nm((3.0 * 22.0 * 1_270_000.0 / 21.0 + 158_750.0_f64).round() as i64));
+
+        // Bold text uses a heavier pen, and italic text leans into a wider box.
+        assert!(metrics.measure("GND", &effects(true, false)).width > plain.width);
+        assert!(metrics.measure("GND", &effects(false, true)).width > plain.width);
+
+        // Markup delimiters are not drawn, and subscripts are smaller.
+        assert_eq!(metrics.line_advance("~{RESET}"), metrics.line_advance("RESET"));
+        assert!(metrics.line_advance("V_{CC}") < metrics.line_advance("VCC"));
+
+        let two_lines = metrics.measure("GND\nVCC", &effects(false, false));
+        assert_eq!(two_lines.width, plain.width);
+        assert!(two_lines.height > Nm(2 * 1_270_000));
+
+        assert_eq!(measure_text("GND", &effects(false, false)).width, plain.width);
+    }
+}
@@ -0,0 +1,246 @@
+use {
+    crate::{
+        bus::{bus_aliases, expand_bus, label_points, name_groups, on_path},
+        common::{Point, Position},
+        project::NetSettings,
+        sch::Schematic,
+        ParseError,
+    },
+    lexpr::Value,
+    serde::{Deserialize, Serialize},
+    std::collections::BTreeMap,
+    uuid::Uuid,
+};
+
+/// The name of the property of a directive label that gives its net class.
+pub const NETCLASS_PROPERTY: &str = "Netclass";
+
+/// The net class of nets with no other assignment.
+pub const DEFAULT_NETCLASS: &str = "Default";
+
+/// A net class directive label (`netclass_flag`) placed on a wire or bus of a schematic.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NetclassDirective {
+    /// The position of the directive's connection point.
+    pub position: Position,
+
+    /// The net class assigned to the nets the directive is attached to.
+    pub netclass: String,
+
+    /// A unique identifier for the directive, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<Uuid>,
+}
+
+/// Where a net's class came from.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetclassSource {
+    /// A directive label on the net, or on a bus carrying it.
+    Directive(Option<Uuid>),
+
+    /// A pattern in the project's net settings.
+    Pattern(String),
+
+    /// Neither; the net has the default class.
+    Default,
+}
+
+/// The net class assigned to a net.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct NetclassAssignment {
+    /// The net class name.
+    pub netclass: String,
+
+    /// Where the assignment came from.
+    pub source: NetclassSource,
+}
+
+impl TryFrom<&Value> for NetclassDirective {
+    type Error = ParseError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        let mut elements = value.list_iter().into_iter().flatten();
+        if elements.next().and_then(Value::as_symbol) != Some("netclass_flag") {
+            return Err(ParseError::Unexpected(value.clone()));
+        }
+
+        let mut position = None;
+        let mut netclass = None;
+        let mut uuid = None;
+
+        for element in elements {
+            let mut items = element.list_iter().into_iter().flatten();
+            match items.next().and_then(Value::as_symbol) {
+                Some("at") => position = Some(Position::try_from(element)?),
+                Some("uuid") => uuid = items.next().and_then(Value::as_str).and_then(|text| Uuid::parse_str(text).ok()),
+                Some("property") if items.next().and_then(Value::as_str) == Some(NETCLASS_PROPERTY) => {
+                    netclass = items.next().and_then(Value::as_str).map(String::from);
+                }
+                _ => (),
+            }
+        }
+
+        let Some(position) = position else {
+            return Err(ParseError::missing_field("netclass_flag", "at", value.clone()));
+        };
+        let Some(netclass) = netclass else {
+            return Err(ParseError::missing_field("netclass_flag", NETCLASS_PROPERTY, value.clone()));
+        };
+
+        Ok(Self {
+            position,
+            netclass,
+            uuid,
+        })
+    }
+}
+
+/// Return the net class directives placed on a schematic. Directives without a net class are
+/// skipped.
+pub fn netclass_directives(schematic: &Schematic) -> Vec<NetclassDirective> {
+    schematic.raw_sections.iter().filter_map(|element| NetclassDirective::try_from(element).ok()).collect()
+}
+
+/// Assign a net class to every named net of a schematic.
+///
+/// A directive label applies to the net of the wire it is attached to, or to every member of the
+/// bus it is attached to; either is named by a label elsewhere on it. Nets without a directive are
+/// assigned by the first matching pattern of the project's net settings, and the rest have the
+/// `Default` class. Directives take precedence over patterns, as they do in KiCad, and the first
+/// directive on a net wins over later ones.
+pub fn assign_netclasses(schematic: &Schematic, settings: &NetSettings) -> BTreeMap<String, NetclassAssignment> {
+    let aliases = bus_aliases(schematic);
+    let labels = label_points(schematic);
+    let wire_paths: Vec<&[Point]> = schematic.wires.iter().map(|wire| wire.points.points.as_slice()).collect();
+    let bus_paths: Vec<&[Point]> = schematic.buses.iter().map(|bus| bus.points.points.as_slice()).collect();
+    let wire_names = name_groups(&wire_paths, &labels);
+    let bus_names = name_groups(&bus_paths, &labels);
+
+    let mut assignments = BTreeMap::new();
+
+    for directive in netclass_directives(schematic) {
+        let point = (directive.position.x.0, directive.position.y.0);
+        let nets = match wire_paths.iter().position(|path| on_path(path, point)) {
+            Some(wire) => wire_names[wire].iter().cloned().collect(),
+            None => match bus_paths.iter().position(|path| on_path(path, point)) {
+                Some(bus) => bus_names[bus].as_deref().and_then(|bus| expand_bus(bus, &aliases)).unwrap_or_default(),
+                None => Vec::new(),
+            },
+        };
+
+        for net in nets {
+            assignments.entry(net).or_insert_with(|| NetclassAssignment {
+                netclass: directive.netclass.clone(),
+                source: NetclassSource::Directive(directive.uuid),
+            });
+        }
+    }
+
+    for (text, _) in labels {
+        let nets = expand_bus(text, &aliases).unwrap_or_else(|| vec![text.to_string()]);
+        for net in nets {
+            assignments.entry(net).or_insert_with_key(|net| pattern_assignment(net, settings));
+        }
+    }
+
+    assignments
+}
+
+/// Assign a net class to a net by the project's patterns alone.
+pub fn pattern_assignment(net: &str, settings: &NetSettings) -> NetclassAssignment {
+    match settings.netclass_patterns.iter().find(|pattern| wildcard_match(&pattern.pattern, net)) {
+        Some(pattern) => NetclassAssignment {
+            netclass: pattern.netclass.clone(),
+            source: NetclassSource::Pattern(pattern.pattern.clone()),
+        },
+        None => NetclassAssignment {
+            netclass: DEFAULT_NETCLASS.to_string(),
+            source: NetclassSource::Default,
+        },
+    }
+}
+
+/// Indicates whether `text` matches a pattern in which `*` matches any run of characters and `?`
+/// any one character, ignoring case.
+pub(crate) fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    // The position after the last `*` seen in the pattern, and the text position it matched to.
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p + 1, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{parse_str, project::NetClassPattern},
+    };
+
+    #[test]
+    fn test_assign_netclasses() {
+        let text = r#"(kicad_sch (version 20231120) (generator "eeschema")
+            (wire (pts (xy 0 0) (xy 20 0)) (stroke (width 0) (type default))
+                (uuid "00000000-0000-0000-0000-000000000001"))
+            (wire (pts (xy 0 10) (xy 20 10)) (stroke (width 0) (type default))
+                (uuid "00000000-0000-0000-0000-000000000002"))
+            (bus (pts (xy 0 20) (xy 20 20)) (stroke (width 0) (type default))
+                (uuid "00000000-0000-0000-0000-000000000003"))
+            (label "CLK" (at 5 0 0) (effects (font (size 1.27 1.27)))
+                (uuid "00000000-0000-0000-0000-000000000004"))
+            (label "+3V3" (at 5 10 0) (effects (font (size 1.27 1.27)))
+                (uuid "00000000-0000-0000-0000-000000000005"))
+            (label "D[0..1]" (at 5 20 0) (effects (font (size 1.27 1.27)))
+                (uuid "00000000-0000-0000-0000-000000000006"))
+            (global_label "+5V" (shape input) (at 40 40 0) (effects (font (size 1.27 1.27)))
+                (uuid "00000000-0000-0000-0000-000000000007"))
+            (netclass_flag "" (length 2.54) (shape round) (at 15 0 0) (effects (font (size 1.27 1.27)))
+                (uuid "00000000-0000-0000-0000-000000000008")
+                (property "Netclass" "HighSpeed" (at 15 -2.54 0) (effects (font (size 1.27 1.27)))))
+            (netclass_flag "" (length 2.54) (shape round) (at 15 20 0) (effects (font (size 1.27 1.27)))
+                (property "Netclass" "Data" (at 15 17.46 0) (effects (font (size 1.27 1.27))))))"#;
+
+        let schematic: Schematic = parse_str(text).unwrap();
+        let settings = NetSettings {
+            netclass_patterns: vec![NetClassPattern {
+                netclass: "Power".to_string(),
+                pattern: "+*".to_string(),
+            }],
+            ..NetSettings::default()
+        };
+
+        let assignments = assign_netclasses(&schematic, &settings);
+        let classes: Vec<_> = assignments.iter().map(|(net, a)| (net.as_str(), a.netclass.as_str())).collect();
+        assert_eq!(
+            classes,
+            [("+3V3", "Power"), ("+5V", "Power"), ("CLK", "HighSpeed"), ("D0", "Data"), ("D1", "Data")]
+        );
+        assert_eq!(assignments["CLK"].source, NetclassSource::Directive(Some(Uuid::from_u128(8))));
+        assert_eq!(pattern_assignment("GND", &settings).source, NetclassSource::Default);
+    }
+}
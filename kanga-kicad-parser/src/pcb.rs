@@ -0,0 +1,1031 @@
+use {
+    crate::{
+        common::{identifier_value, EmbeddedFile, Identified, Nm, Point, Points, Position, Size, TitleBlock},
+        config::HEADER_SECTIONS,
+        impl_identified, impl_positioned, impl_try_from_cons_value,
+        lazy::Lazy,
+        version::FormatVersion,
+        LexprExt, ParseError, ParseOptions, ParseWarning,
+    },
+    lexpr::{Cons, Value},
+    std::collections::BTreeMap,
+    uuid::Uuid,
+};
+
+#[cfg(feature = "serde")]
+use crate::common::{
+    deserialize_mm_to_opt_unsigned_nm, deserialize_mm_to_unsigned_nm, serialize_opt_unsigned_nm_to_mm,
+    serialize_unsigned_nm_to_mm,
+};
+
+/// KiCad board (PCB) file format.
+///
+/// Board items carry many settings this model does not cover (plot settings, graphics, teardrop
+/// parameters, and so on). Unmodeled elements inside an item are ignored; unmodeled top-level
+/// elements are kept in [`Pcb::raw_sections`].
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-pcb/index.html)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "kicad_pcb", deny_unknown_fields))]
+pub struct Pcb {
+    /// The board version, as a YYYYMMDD integer.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub version: Option<i64>,
+
+    /// The program used to generate this board (`pcbnew` for KiCad's board editor).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "String::is_empty"))]
+    pub generator: String,
+
+    /// The version of the program used to generate this board.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "String::is_empty"))]
+    pub generator_version: String,
+
+    /// The overall board thickness in nm, from the `general` section.
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<f64>"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "deserialize_mm_to_opt_unsigned_nm",
+            serialize_with = "serialize_opt_unsigned_nm_to_mm",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub thickness: Option<u64>,
+
+    /// The paper size name, e.g. `A4`.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "String::is_empty"))]
+    pub paper: String,
+
+    /// The title block, converted on first access if it was not selected for parsing.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub title_block: Option<Lazy<TitleBlock>>,
+
+    /// The layer stack, in ordinal order.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub layers: Vec<PcbLayer>,
+
+    /// The nets, in declaration order. Net 0 is the unconnected net.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub nets: Vec<PcbNet>,
+
+    /// The footprints placed on the board.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub footprints: Vec<PcbFootprint>,
+
+    /// Straight track segments.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub segments: Vec<PcbSegment>,
+
+    /// Arc track segments.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub arcs: Vec<PcbArc>,
+
+    /// Vias.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub vias: Vec<PcbVia>,
+
+    /// Copper and keepout zones.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub zones: Vec<PcbZone>,
+
+    /// Whether the fonts used by text are embedded in the document (version 9+).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub embedded_fonts: Option<bool>,
+
+    /// Files embedded in the document (version 9+).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub embedded_files: Vec<EmbeddedFile>,
+
+    /// Top-level elements that are not modeled or were left unconverted by
+    /// [`ParseOptions::sections`], in document order.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub raw_sections: Vec<Value>,
+}
+
+/// A layer in the board's layer stack.
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-pcb/index.html#_layers_section)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "layer", deny_unknown_fields))]
+pub struct PcbLayer {
+    /// The layer number.
+    pub ordinal: i64,
+
+    /// The canonical layer name, e.g. `F.Cu`.
+    pub name: String,
+
+    /// The layer type: `signal`, `power`, `mixed`, `jumper`, or `user`.
+    pub kind: String,
+
+    /// The name the user gave the layer, if any.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub user_name: Option<String>,
+}
+
+/// A net declaration.
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-pcb/index.html#_nets_section)
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "net", deny_unknown_fields))]
+pub struct PcbNet {
+    /// The net number.
+    pub number: i64,
+
+    /// The net name. The unconnected net has an empty name.
+    pub name: String,
+}
+
+/// A footprint placed on the board.
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_footprint)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "footprint", deny_unknown_fields))]
+pub struct PcbFootprint {
+    /// The library identifier, e.g. `Resistor_SMD:R_0603_1608Metric`.
+    pub lib_id: String,
+
+    /// The side of the board, `F.Cu` or `B.Cu`.
+    pub layer: String,
+
+    /// A unique identifier for the footprint.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub uuid: Option<Uuid>,
+
+    /// The position of the footprint.
+    #[cfg_attr(feature = "serde", serde(rename = "at"))]
+    pub position: Position,
+
+    /// The description, from `descr`.
+    #[cfg_attr(feature = "serde", serde(default, rename = "descr", skip_serializing_if = "Option::is_none"))]
+    pub description: Option<String>,
+
+    /// The search keywords, from the space-separated `tags`.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub tags: Vec<String>,
+
+    /// The footprint's fields, e.g. `Reference` and `Value`. Pre-KiCad 8 `fp_text` reference and
+    /// value fields are included.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "BTreeMap::is_empty"))]
+    pub properties: BTreeMap<String, String>,
+
+    /// The pads.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub pads: Vec<PcbPad>,
+
+    /// Child elements that are not fully modeled, in document order: graphics, 3D models, and
+    /// the fields with their placement.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub raw_elements: Vec<Value>,
+}
+
+/// A footprint pad.
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-intro/index.html#_footprint_pad)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "pad", deny_unknown_fields))]
+pub struct PcbPad {
+    /// The pad number. Mechanical pads have an empty number.
+    pub number: String,
+
+    /// The pad type, e.g. `smd` or `thru_hole`.
+    pub kind: PcbPadType,
+
+    /// The pad shape, e.g. `rect` or `roundrect`.
+    pub shape: String,
+
+    /// The position of the pad, relative to the footprint.
+    #[cfg_attr(feature = "serde", serde(rename = "at"))]
+    pub position: Position,
+
+    /// The size of the pad.
+    pub size: Size,
+
+    /// The drill diameter in nm, for through-hole pads. Oval drills report their width.
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<f64>"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "deserialize_mm_to_opt_unsigned_nm",
+            serialize_with = "serialize_opt_unsigned_nm_to_mm",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub drill: Option<u64>,
+
+    /// The layers the pad is on, e.g. `F.Cu` or `*.Cu`.
+    pub layers: Vec<String>,
+
+    /// The net the pad is connected to.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub net: Option<PcbNet>,
+
+    /// A unique identifier for the pad.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub uuid: Option<Uuid>,
+}
+
+/// Footprint pad type.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PcbPadType {
+    /// Plated through-hole.
+    ThruHole,
+
+    /// Surface mount.
+    Smd,
+
+    /// Edge connector.
+    Connect,
+
+    /// Non-plated through-hole.
+    NpThruHole,
+}
+
+/// A straight track segment.
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-pcb/index.html#_track_segment)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "segment", deny_unknown_fields))]
+pub struct PcbSegment {
+    /// The start of the segment.
+    pub start: Point,
+
+    /// The end of the segment.
+    pub end: Point,
+
+    /// The track width in nm.
+    #[cfg_attr(feature = "schemars", schemars(with = "f64"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "deserialize_mm_to_unsigned_nm", serialize_with = "serialize_unsigned_nm_to_mm")
+    )]
+    pub width: u64,
+
+    /// The copper layer.
+    pub layer: String,
+
+    /// The net number.
+    pub net: i64,
+
+    /// A unique identifier for the segment.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub uuid: Option<Uuid>,
+}
+
+/// An arc track segment.
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-pcb/index.html#_track_arc)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "arc", deny_unknown_fields))]
+pub struct PcbArc {
+    /// The start of the arc.
+    pub start: Point,
+
+    /// The midpoint of the arc.
+    pub mid: Point,
+
+    /// The end of the arc.
+    pub end: Point,
+
+    /// The track width in nm.
+    #[cfg_attr(feature = "schemars", schemars(with = "f64"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "deserialize_mm_to_unsigned_nm", serialize_with = "serialize_unsigned_nm_to_mm")
+    )]
+    pub width: u64,
+
+    /// The copper layer.
+    pub layer: String,
+
+    /// The net number.
+    pub net: i64,
+
+    /// A unique identifier for the arc.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub uuid: Option<Uuid>,
+}
+
+/// A via.
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-pcb/index.html#_track_via)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "via", deny_unknown_fields))]
+pub struct PcbVia {
+    /// The via type, `blind` or `micro`, or `None` for a through via.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub kind: Option<String>,
+
+    /// The position of the via.
+    #[cfg_attr(feature = "serde", serde(rename = "at"))]
+    pub position: Position,
+
+    /// The pad diameter in nm.
+    #[cfg_attr(feature = "schemars", schemars(with = "f64"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "deserialize_mm_to_unsigned_nm", serialize_with = "serialize_unsigned_nm_to_mm")
+    )]
+    pub size: u64,
+
+    /// The drill diameter in nm.
+    #[cfg_attr(feature = "schemars", schemars(with = "f64"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "deserialize_mm_to_unsigned_nm", serialize_with = "serialize_unsigned_nm_to_mm")
+    )]
+    pub drill: u64,
+
+    /// The first and last copper layers the via connects.
+    pub layers: Vec<String>,
+
+    /// The net number.
+    pub net: i64,
+
+    /// A unique identifier for the via.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub uuid: Option<Uuid>,
+}
+
+/// A copper or keepout zone.
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-pcb/index.html#_zone)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "zone", deny_unknown_fields))]
+pub struct PcbZone {
+    /// The net number, or 0 for an unconnected zone.
+    pub net: i64,
+
+    /// The net name.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "String::is_empty"))]
+    pub net_name: String,
+
+    /// The zone name, if any.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub name: Option<String>,
+
+    /// The layers the zone is on.
+    pub layers: Vec<String>,
+
+    /// Whether this is a rule area (keepout) rather than a copper fill.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+    pub keepout: bool,
+
+    /// The outlines of the zone.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub polygons: Vec<Points>,
+
+    /// A unique identifier for the zone.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub uuid: Option<Uuid>,
+}
+
+impl Pcb {
+    /// Parse a board, converting only the top-level sections selected by `options`. The title
+    /// block is converted on first access if it was not selected; other unselected sections are
+    /// kept in [`Pcb::raw_sections`].
+    pub fn parse(cons: &Cons, options: &ParseOptions) -> Result<Self, ParseError> {
+        Self::parse_with_warnings(cons, options).map(|(pcb, _)| pcb)
+    }
+
+    /// Parse a board as [`Pcb::parse`] does, also returning the elements that were skipped.
+    ///
+    /// Without [`ParseOptions::strict`], an element other than the header that fails to convert is
+    /// kept in [`Pcb::raw_sections`] and reported as a [`ParseWarning`] instead of failing the
+    /// parse.
+    pub fn parse_with_warnings(cons: &Cons, options: &ParseOptions) -> Result<(Self, Vec<ParseWarning>), ParseError> {
+        let mut pcb = Self {
+            version: None,
+            generator: String::new(),
+            generator_version: String::new(),
+            thickness: None,
+            paper: String::new(),
+            title_block: None,
+            layers: Vec::new(),
+            nets: Vec::new(),
+            footprints: Vec::new(),
+            segments: Vec::new(),
+            arcs: Vec::new(),
+            vias: Vec::new(),
+            zones: Vec::new(),
+            embedded_fonts: None,
+            embedded_files: Vec::new(),
+            raw_sections: Vec::new(),
+        };
+
+        let mut warnings = Vec::new();
+
+        let rest = cons.expect_cons_with_symbol_head("kicad_pcb")?;
+        for element in rest.list_iter().into_iter().flatten() {
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            if !options.includes_section(key) {
+                match key {
+                    "title_block" => pcb.title_block = Some(Lazy::new(element.clone())),
+                    _ => pcb.raw_sections.push(element.clone()),
+                }
+                continue;
+            }
+
+            let converted = (|| -> Result<(), ParseError> {
+                match key {
+                    "version" => {
+                        let version = cdr.expect_cons_with_any_int_head()?.0;
+                        FormatVersion::check(Some(version), FormatVersion::Kicad5, options)?;
+                        pcb.version = Some(version);
+                    }
+                    "generator" => pcb.generator = text_arg(cdr)?.to_string(),
+                    "generator_version" => pcb.generator_version = text_arg(cdr)?.to_string(),
+                    "general" => pcb.thickness = child(cdr, "thickness").map(mm_value).transpose()?,
+                    "paper" => pcb.paper = text_arg(cdr)?.to_string(),
+                    "title_block" => pcb.title_block = Some(Lazy::from_value(TitleBlock::try_from(element)?)),
+                    "layers" => {
+                        let layers = cdr
+                            .list_iter()
+                            .into_iter()
+                            .flatten()
+                            .map(PcbLayer::from_element)
+                            .collect::<Result<Vec<_>, _>>()?;
+                        pcb.layers.extend(layers);
+                    }
+                    "net" => pcb.nets.push(PcbNet::from_args(cdr)?),
+                    "footprint" | "module" => pcb.footprints.push(PcbFootprint::try_from(element)?),
+                    "segment" => pcb.segments.push(PcbSegment::try_from(element)?),
+                    "arc" => pcb.arcs.push(PcbArc::try_from(element)?),
+                    "via" => pcb.vias.push(PcbVia::try_from(element)?),
+                    "zone" => pcb.zones.push(PcbZone::try_from(element)?),
+                    "embedded_fonts" => pcb.embedded_fonts = Some(cdr.expect_cons()?.car().expect_bool()?),
+                    "embedded_files" => {
+                        let files = cdr
+                            .list_iter()
+                            .into_iter()
+                            .flatten()
+                            .map(EmbeddedFile::try_from)
+                            .collect::<Result<Vec<_>, _>>()?;
+                        pcb.embedded_files.extend(files);
+                    }
+                    _ => pcb.raw_sections.push(element.clone()),
+                }
+
+                Ok(())
+            })();
+
+            if let Err(error) = converted {
+                if options.strict || HEADER_SECTIONS.contains(&key) {
+                    return Err(error);
+                }
+
+                pcb.raw_sections.push(element.clone());
+                warnings.push(ParseWarning {
+                    element: element.clone(),
+                    error,
+                });
+            }
+        }
+
+        Ok((pcb, warnings))
+    }
+
+    /// Return the KiCad release whose format the board declares, if it declares a version.
+    pub fn format_version(&self) -> Option<FormatVersion> {
+        self.version.map(FormatVersion::nearest)
+    }
+
+    /// Return the net with the given number.
+    pub fn net(&self, number: i64) -> Option<&PcbNet> {
+        self.nets.iter().find(|net| net.number == number)
+    }
+
+    /// Call `f` with every element of the board that has a UUID slot. Pads follow the footprint
+    /// they belong to.
+    pub fn for_each_identified<F>(&self, mut f: F)
+    where
+        F: FnMut(&dyn Identified),
+    {
+        for footprint in &self.footprints {
+            f(footprint);
+            footprint.pads.iter().for_each(|e| f(e));
+        }
+
+        self.segments.iter().for_each(|e| f(e));
+        self.arcs.iter().for_each(|e| f(e));
+        self.vias.iter().for_each(|e| f(e));
+        self.zones.iter().for_each(|e| f(e));
+    }
+
+    /// Call `f` with every element of the board that has a UUID slot, mutably, in the same order as
+    /// [`Pcb::for_each_identified`].
+    pub fn for_each_identified_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut dyn Identified),
+    {
+        for footprint in &mut self.footprints {
+            f(footprint);
+            footprint.pads.iter_mut().for_each(|e| f(e));
+        }
+
+        self.segments.iter_mut().for_each(|e| f(e));
+        self.arcs.iter_mut().for_each(|e| f(e));
+        self.vias.iter_mut().for_each(|e| f(e));
+        self.zones.iter_mut().for_each(|e| f(e));
+    }
+}
+
+impl TryFrom<&Cons> for Pcb {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, ParseError> {
+        Self::parse(cons, &ParseOptions::default())
+    }
+}
+
+impl_try_from_cons_value!(Pcb);
+
+impl PcbLayer {
+    /// Parse a `(ordinal "name" type ["user name"])` entry of the `layers` section.
+    fn from_element(element: &Value) -> Result<Self, ParseError> {
+        let (ordinal, rest) = element.expect_cons_with_any_int_head()?;
+        let name = text_arg(rest)?.to_string();
+        let rest = rest.expect_cons()?.cdr();
+        let kind = text_arg(rest)?.to_string();
+        let user_name = rest.expect_cons()?.cdr().as_cons().and_then(|cons| cons.car().as_str()).map(str::to_string);
+
+        Ok(Self {
+            ordinal,
+            name,
+            kind,
+            user_name,
+        })
+    }
+}
+
+impl PcbNet {
+    /// Parse the `number "name"` arguments of a `net` element.
+    fn from_args(args: &Value) -> Result<Self, ParseError> {
+        let (number, rest) = args.expect_cons_with_any_int_head()?;
+        let name = rest.as_cons().map(|cons| text_value(cons.car())).transpose()?.unwrap_or_default();
+
+        Ok(Self {
+            number,
+            name: name.to_string(),
+        })
+    }
+}
+
+impl TryFrom<&Cons> for PcbFootprint {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let rest = cons.cdr();
+        if !matches!(cons.car().as_symbol(), Some("footprint" | "module")) {
+            return Err(ParseError::ExpectedSymbol(Value::Cons(cons.clone()), "footprint".to_string()));
+        }
+
+        let lib_id = text_arg(rest)?.to_string();
+        let mut layer = None;
+        let mut uuid = None;
+        let mut position = None;
+        let mut description = None;
+        let mut tags = Vec::new();
+        let mut properties = BTreeMap::new();
+        let mut pads = Vec::new();
+        let mut raw_elements = Vec::new();
+
+        for element in rest.expect_cons()?.cdr().list_iter().into_iter().flatten() {
+            let Ok((key, cdr)) = element.expect_cons_with_any_symbol_head() else {
+                continue;
+            };
+
+            match key {
+                "layer" => layer = Some(text_arg(cdr)?.to_string()),
+                "uuid" | "tstamp" => uuid = Some(uuid_arg(cdr)?),
+                "at" => position = Some(Position::try_from(element)?),
+                "descr" => description = Some(text_arg(cdr)?.to_string()),
+                "tags" => tags = text_arg(cdr)?.split_whitespace().map(String::from).collect(),
+                // Older boards write some property names as bare symbols, e.g. `ki_fp_filters`.
+                "property" => {
+                    let name = text_arg(cdr)?;
+                    properties.insert(name.to_string(), text_arg(cdr.expect_cons()?.cdr())?.to_string());
+                    raw_elements.push(element.clone());
+                }
+                "fp_text" => {
+                    raw_elements.push(element.clone());
+                    let (kind, cdr) = cdr.expect_cons_with_any_symbol_head()?;
+                    let name = match kind {
+                        "reference" => "Reference",
+                        "value" => "Value",
+                        _ => continue,
+                    };
+                    properties.entry(name.to_string()).or_insert(text_arg(cdr)?.to_string());
+                }
+                "pad" => pads.push(PcbPad::try_from(element)?),
+                _ => raw_elements.push(element.clone()),
+            }
+        }
+
+        let Some(layer) = layer else {
+            return Err(ParseError::missing_field("footprint", "layer", cons.clone()));
+        };
+
+        Ok(Self {
+            lib_id,
+            layer,
+            uuid,
+            position: position.unwrap_or(Position {
+                x: Nm::ZERO,
+                y: Nm::ZERO,
+                angle: None,
+            }),
+            description,
+            tags,
+            properties,
+            pads,
+            raw_elements,
+        })
+    }
+}
+
+impl_try_from_cons_value!(PcbFootprint);
+impl_identified!(PcbFootprint, optional);
+impl_positioned!(PcbFootprint);
+
+impl TryFrom<&Cons> for PcbPad {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let rest = cons.expect_cons_with_symbol_head("pad")?;
+        let number = text_arg(rest)?.to_string();
+        let rest = rest.expect_cons()?.cdr();
+        let kind = match text_arg(rest)? {
+            "thru_hole" => PcbPadType::ThruHole,
+            "smd" => PcbPadType::Smd,
+            "connect" => PcbPadType::Connect,
+            "np_thru_hole" => PcbPadType::NpThruHole,
+            _ => return Err(ParseError::Unexpected(rest.expect_cons()?.car().clone())),
+        };
+        let rest = rest.expect_cons()?.cdr();
+        let shape = text_arg(rest)?.to_string();
+
+        let mut position = None;
+        let mut size = None;
+        let mut drill = None;
+        let mut layers = Vec::new();
+        let mut net = None;
+        let mut uuid = None;
+
+        for element in rest.expect_cons()?.cdr().list_iter().into_iter().flatten() {
+            let Ok((key, cdr)) = element.expect_cons_with_any_symbol_head() else {
+                continue;
+            };
+
+            match key {
+                "at" => position = Some(Position::try_from(element)?),
+                "size" => {
+                    let (width, rest) = cdr.expect_cons_with_any_length_head()?;
+                    let (height, _) = rest.expect_cons_with_any_length_head()?;
+                    size = Some(Size::try_new(width, height)?);
+                }
+                "drill" => {
+                    // Oval drills are written `(drill oval <width> <height>)`.
+                    let args = match cdr.as_cons() {
+                        Some(cons) if cons.car().as_symbol() == Some("oval") => cons.cdr(),
+                        _ => cdr,
+                    };
+                    drill = args.as_cons().map(|_| mm_value(args)).transpose()?;
+                }
+                "layers" => layers = text_args(cdr)?,
+                "net" => net = Some(PcbNet::from_args(cdr)?),
+                "uuid" | "tstamp" => uuid = Some(uuid_arg(cdr)?),
+                _ => (),
+            }
+        }
+
+        let Some(position) = position else {
+            return Err(ParseError::missing_field("pad", "at", cons.clone()));
+        };
+
+        let Some(size) = size else {
+            return Err(ParseError::missing_field("pad", "size", cons.clone()));
+        };
+
+        Ok(Self {
+            number,
+            kind,
+            shape,
+            position,
+            size,
+            drill,
+            layers,
+            net,
+            uuid,
+        })
+    }
+}
+
+impl_try_from_cons_value!(PcbPad);
+impl_identified!(PcbPad, optional);
+impl_positioned!(PcbPad);
+
+/// The elements shared by straight and arc track segments.
+#[derive(Default)]
+struct Track {
+    start: Option<Point>,
+    mid: Option<Point>,
+    end: Option<Point>,
+    width: Option<u64>,
+    layer: Option<String>,
+    net: i64,
+    uuid: Option<Uuid>,
+}
+
+impl Track {
+    /// Collect the elements of a `segment` or `arc`.
+    fn parse(rest: &Value) -> Result<Self, ParseError> {
+        let mut track = Self::default();
+        for element in rest.list_iter().into_iter().flatten() {
+            let Ok((key, cdr)) = element.expect_cons_with_any_symbol_head() else {
+                continue;
+            };
+
+            match key {
+                "start" => track.start = Some(Point::try_from_xy_cons(cdr)?),
+                "mid" => track.mid = Some(Point::try_from_xy_cons(cdr)?),
+                "end" => track.end = Some(Point::try_from_xy_cons(cdr)?),
+                "width" => track.width = Some(mm_value(cdr)?),
+                "layer" => track.layer = Some(text_arg(cdr)?.to_string()),
+                "net" => track.net = cdr.expect_cons_with_any_int_head()?.0,
+                "uuid" | "tstamp" => track.uuid = Some(uuid_arg(cdr)?),
+                _ => (),
+            }
+        }
+        Ok(track)
+    }
+}
+
+impl TryFrom<&Cons> for PcbSegment {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let track = Track::parse(cons.expect_cons_with_symbol_head("segment")?)?;
+        let missing = |field| ParseError::missing_field("segment", field, cons.clone());
+
+        Ok(Self {
+            start: track.start.ok_or_else(|| missing("start"))?,
+            end: track.end.ok_or_else(|| missing("end"))?,
+            width: track.width.ok_or_else(|| missing("width"))?,
+            layer: track.layer.ok_or_else(|| missing("layer"))?,
+            net: track.net,
+            uuid: track.uuid,
+        })
+    }
+}
+
+impl_try_from_cons_value!(PcbSegment);
+impl_identified!(PcbSegment, optional);
+
+impl TryFrom<&Cons> for PcbArc {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let track = Track::parse(cons.expect_cons_with_symbol_head("arc")?)?;
+        let missing = |field| ParseError::missing_field("arc", field, cons.clone());
+
+        Ok(Self {
+            start: track.start.ok_or_else(|| missing("start"))?,
+            mid: track.mid.ok_or_else(|| missing("mid"))?,
+            end: track.end.ok_or_else(|| missing("end"))?,
+            width: track.width.ok_or_else(|| missing("width"))?,
+            layer: track.layer.ok_or_else(|| missing("layer"))?,
+            net: track.net,
+            uuid: track.uuid,
+        })
+    }
+}
+
+impl_try_from_cons_value!(PcbArc);
+impl_identified!(PcbArc, optional);
+
+impl TryFrom<&Cons> for PcbVia {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let mut kind = None;
+        let mut position = None;
+        let mut size = None;
+        let mut drill = None;
+        let mut layers = Vec::new();
+        let mut net = 0;
+        let mut uuid = None;
+
+        for element in cons.expect_cons_with_symbol_head("via")?.list_iter().into_iter().flatten() {
+            if let Some(flag) = element.as_symbol() {
+                if flag == "blind" || flag == "micro" {
+                    kind = Some(flag.to_string());
+                }
+                continue;
+            }
+
+            let Ok((key, cdr)) = element.expect_cons_with_any_symbol_head() else {
+                continue;
+            };
+
+            match key {
+                "at" => position = Some(Position::try_from(element)?),
+                "size" => size = Some(mm_value(cdr)?),
+                "drill" => drill = Some(mm_value(cdr)?),
+                "layers" => layers = text_args(cdr)?,
+                "net" => net = cdr.expect_cons_with_any_int_head()?.0,
+                "uuid" | "tstamp" => uuid = Some(uuid_arg(cdr)?),
+                _ => (),
+            }
+        }
+
+        let missing = |field| ParseError::missing_field("via", field, cons.clone());
+
+        Ok(Self {
+            kind,
+            position: position.ok_or_else(|| missing("at"))?,
+            size: size.ok_or_else(|| missing("size"))?,
+            drill: drill.ok_or_else(|| missing("drill"))?,
+            layers,
+            net,
+            uuid,
+        })
+    }
+}
+
+impl_try_from_cons_value!(PcbVia);
+impl_identified!(PcbVia, optional);
+impl_positioned!(PcbVia);
+
+impl TryFrom<&Cons> for PcbZone {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let mut zone = Self {
+            net: 0,
+            net_name: String::new(),
+            name: None,
+            layers: Vec::new(),
+            keepout: false,
+            polygons: Vec::new(),
+            uuid: None,
+        };
+
+        for element in cons.expect_cons_with_symbol_head("zone")?.list_iter().into_iter().flatten() {
+            let Ok((key, cdr)) = element.expect_cons_with_any_symbol_head() else {
+                continue;
+            };
+
+            match key {
+                "net" => zone.net = cdr.expect_cons_with_any_int_head()?.0,
+                "net_name" => zone.net_name = text_arg(cdr)?.to_string(),
+                "name" => zone.name = Some(text_arg(cdr)?.to_string()),
+                "layer" | "layers" => zone.layers = text_args(cdr)?,
+                "keepout" => zone.keepout = true,
+                "polygon" => {
+                    let pts =
+                        child(cdr, "pts").ok_or_else(|| ParseError::missing_field("polygon", "pts", cons.clone()))?;
+                    let points = pts
+                        .list_iter()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|point| point.expect_cons_with_symbol_head("xy").ok())
+                        .map(Point::try_from_xy_cons)
+                        .collect::<Result<_, _>>()?;
+                    zone.polygons.push(Points {
+                        points,
+                    });
+                }
+                "uuid" | "tstamp" => zone.uuid = Some(uuid_arg(cdr)?),
+                _ => (),
+            }
+        }
+
+        Ok(zone)
+    }
+}
+
+impl_try_from_cons_value!(PcbZone);
+impl_identified!(PcbZone, optional);
+
+/// Return the arguments of the first child element with the given head.
+fn child<'a>(rest: &'a Value, head: &str) -> Option<&'a Value> {
+    rest.list_iter()?.find_map(|element| element.expect_cons_with_symbol_head(head).ok())
+}
+
+/// Return a string or symbol value.
+fn text_value(value: &Value) -> Result<&str, ParseError> {
+    value.as_str().or_else(|| value.as_symbol()).ok_or_else(|| ParseError::ExpectedListStrHead(value.clone()))
+}
+
+/// Return the first argument of an element as text.
+fn text_arg(args: &Value) -> Result<&str, ParseError> {
+    text_value(args.expect_cons()?.car())
+}
+
+/// Return every argument of an element as text.
+fn text_args(args: &Value) -> Result<Vec<String>, ParseError> {
+    args.list_iter().into_iter().flatten().map(|value| text_value(value).map(str::to_string)).collect()
+}
+
+/// Return the first argument of an element, in millimeters, as nanometers.
+fn mm_value(args: &Value) -> Result<u64, ParseError> {
+    let (value, _) = args.expect_cons_with_any_length_head()?;
+    if value < Nm::ZERO {
+        return Err(ParseError::InvalidWidth(value.to_mm()));
+    }
+    Ok(value.0 as u64)
+}
+
+/// Return the first argument of an element as a UUID, converting KiCad 5 timestamps.
+fn uuid_arg(args: &Value) -> Result<Uuid, ParseError> {
+    identifier_value(args.expect_cons()?.car())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::parse_str, lexpr::sexp};
+
+    #[test]
+    fn test_pcb() {
+        let board = sexp!((kicad_pcb (version 20240108) (generator "pcbnew") (generator_version "8.0")
+            (general (thickness 1.6) (legacy_teardrops no))
+            (paper "A4")
+            (layers (0 "F.Cu" signal) (31 "B.Cu" signal) (44 "Edge.Cuts" user "Board Outline"))
+            (setup (pad_to_mask_clearance 0))
+            (net 0 "") (net 1 "VCC") (net 2 "GND")
+            (footprint "Resistor_SMD:R_0603" (layer "F.Cu") (uuid "c5a2ad3f-4c4e-4b1e-9b43-3f0a6b7c9a10") (at 10 20 90)
+                (descr "Resistor SMD 0603") (tags "resistor  0603")
+                (property "Reference" "R1" (at 0 -1.4 90) (layer "F.SilkS"))
+                (fp_text value "10k" (at 0 1.4 90) (layer "F.Fab"))
+                (pad "1" smd roundrect (at -0.8 0 90) (size 0.8 0.95) (layers "F.Cu" "F.Paste" "F.Mask")
+                    (roundrect_rratio 0.25) (net 1 "VCC"))
+                (pad "" np_thru_hole circle (at 0 0) (size 1 1) (drill 1) (layers "*.Cu" "*.Mask")))
+            (segment (start 10 20) (end 15 20) (width 0.25) (layer "F.Cu") (net 1) (tstamp "0a1b2c3d-0000-4000-8000-000000000001"))
+            (arc (start 15 20) (mid 16 21) (end 17 20) (width 0.25) (layer "F.Cu") (net 1))
+            (via micro (at 17 20) (size 0.6) (drill 0.3) (layers "F.Cu" "In1.Cu") (net 1))
+            (zone (net 2) (net_name "GND") (layers "F.Cu" "B.Cu") (name "ground")
+                (polygon (pts (xy 0 0) (xy 50 0) (xy 50 50) (xy 0 50))))));
+
+        let pcb = Pcb::try_from(&board).unwrap();
+        assert_eq!((pcb.version, pcb.generator.as_str(), pcb.thickness), (Some(20240108), "pcbnew", Some(1_600_000)));
+        assert_eq!(pcb.layers[2].user_name.as_deref(), Some("Board Outline"));
+        assert_eq!(pcb.net(2).map(|net| net.name.as_str()), Some("GND"));
+
+        let footprint = &pcb.footprints[0];
+        assert_eq!(footprint.properties["Reference"], "R1");
+        assert_eq!(footprint.properties["Value"], "10k");
+        assert_eq!(footprint.description.as_deref(), Some("Resistor SMD 0603"));
+        assert_eq!(footprint.tags, ["resistor", "0603"]);
+        assert_eq!(footprint.pads[0].size.width, Nm(800_000));
+        assert_eq!(footprint.pads[0].net.as_ref().map(|net| net.number), Some(1));
+        assert_eq!((footprint.pads[1].kind, footprint.pads[1].drill), (PcbPadType::NpThruHole, Some(1_000_000)));
+
+        assert_eq!(pcb.segments[0].width, 250_000);
+        assert!(pcb.segments[0].uuid.is_some());
+        assert_eq!(pcb.arcs[0].mid.x, Nm(16_000_000));
+        assert_eq!((pcb.vias[0].kind.as_deref(), pcb.vias[0].drill), (Some("micro"), 300_000));
+        assert_eq!(pcb.zones[0].polygons[0].points.len(), 4);
+        assert_eq!(pcb.raw_sections.len(), 1);
+
+        let header_only = Pcb::parse(board.as_cons().unwrap(), &ParseOptions::default().sections(&["layers"])).unwrap();
+        assert!(header_only.footprints.is_empty());
+        assert_eq!(header_only.layers.len(), 3);
+    }
+    #[test]
+    fn test_pcb_fixture() {
+        // lexpr reads and drops lists recursively, which overflows a test thread's stack on a board this size.
+        let parse = || parse_str::<Pcb>(include_str!("../tests/MoonSnailKeyboardLeft.kicad_pcb")).unwrap();
+        let pcb = std::thread::Builder::new().stack_size(64 << 20).spawn(parse).unwrap().join().unwrap();
+        assert_eq!(pcb.generator, "pcbnew");
+        assert!(!pcb.footprints.is_empty() && !pcb.segments.is_empty());
+        assert!(pcb.footprints.iter().all(|footprint| footprint.properties.contains_key("Reference")));
+
+        // KiCad wrote this property name as a symbol.
+        let filters = pcb.footprints.iter().find_map(|footprint| footprint.properties.get("ki_fp_filters"));
+        assert!(filters.is_some());
+    }
+}
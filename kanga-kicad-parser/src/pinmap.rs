@@ -0,0 +1,182 @@
+use {
+    crate::{
+        bundle::csv_field,
+        common::Symbol,
+        impact::{fp_text_reference, string_property},
+        LexprExt,
+    },
+    lexpr::Value,
+    serde::{Deserialize, Serialize},
+    std::fmt::Write as _,
+};
+
+/// A pin of a component and the net attached to it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PinAssignment {
+    /// The pin (pad) number, e.g. `A1` or `12`.
+    pub pin: String,
+
+    /// The pin name from the symbol, e.g. `PA0`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+
+    /// The electrical type of the pin in KiCad's spelling, e.g. `bidirectional`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pin_type: Option<String>,
+
+    /// The net attached to the pin, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub net: Option<String>,
+}
+
+/// Pin mapping formats understood by vendor tools.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PinMapFormat {
+    /// CSV with `Pin,Function,Type,Net` columns, suitable for generating constraints.
+    Csv,
+
+    /// Xilinx Vivado design constraints (XDC), one `PACKAGE_PIN` constraint per connected pin.
+    Xdc,
+
+    /// STM32CubeMX-style pinout CSV with `Position,Name,Type,Signal,Label` columns.
+    CubeMx,
+}
+
+/// Return the pin assignments of the component with the given reference designator on a board.
+///
+/// Pin names and types come from the `pinfunction` and `pintype` attributes KiCad copies from the
+/// symbol to each pad. Returns `None` if no footprint has the reference.
+pub fn board_pin_assignments(board: &Value, reference: &str) -> Option<Vec<PinAssignment>> {
+    let footprint = board.list_iter()?.find_map(|item| {
+        let rest = item.expect_cons_with_symbol_head("footprint").ok()?;
+        let found = string_property(rest, "Reference").or_else(|| fp_text_reference(rest));
+        (found == Some(reference)).then_some(rest)
+    })?;
+
+    let mut result: Vec<PinAssignment> = Vec::new();
+    for element in footprint.list_iter().into_iter().flatten() {
+        let Ok(pad) = element.expect_cons_with_symbol_head("pad") else {
+            continue;
+        };
+
+        let pin = pad.as_cons().and_then(|cons| cons.car().as_str()).unwrap_or_default();
+
+        // Mounting holes have no number, and pads repeated for thermal or mechanical reasons share one.
+        if pin.is_empty() || result.iter().any(|assignment| assignment.pin == pin) {
+            continue;
+        }
+
+        let attribute = |head| {
+            pad.list_iter()?.find_map(|e| {
+                let cdr = e.expect_cons_with_symbol_head(head).ok()?;
+                cdr.list_iter()?.filter_map(Value::as_str).last().map(str::to_string)
+            })
+        };
+
+        result.push(PinAssignment {
+            pin: pin.to_string(),
+            function: attribute("pinfunction"),
+            pin_type: attribute("pintype"),
+            net: attribute("net").filter(|net| !net.is_empty()),
+        });
+    }
+
+    Some(result)
+}
+
+/// Return the pins of a library symbol without net assignments.
+pub fn symbol_pin_assignments(symbol: &Symbol) -> Vec<PinAssignment> {
+    symbol
+        .unit_pins(None)
+        .map(|pin| PinAssignment {
+            pin: pin.number.number.clone(),
+            function: Some(pin.name.name.clone()).filter(|name| !name.is_empty() && name != "~"),
+            pin_type: serde_json::to_value(pin.electrical_type).ok().and_then(|v| v.as_str().map(str::to_string)),
+            net: None,
+        })
+        .collect()
+}
+
+/// Render pin assignments in the given format.
+///
+/// Net names are written without KiCad's leading `/` sheet separator. The XDC format omits pins
+/// without a net and pins on KiCad's auto-generated `unconnected-` nets, as they have no port.
+pub fn export_pin_map(assignments: &[PinAssignment], format: PinMapFormat) -> String {
+    let mut result = match format {
+        PinMapFormat::Csv => String::from("Pin,Function,Type,Net\n"),
+        PinMapFormat::Xdc => String::new(),
+        PinMapFormat::CubeMx => String::from("Position,Name,Type,Signal,Label\n"),
+    };
+
+    for assignment in assignments {
+        let function = assignment.function.as_deref().unwrap_or_default();
+        let pin_type = assignment.pin_type.as_deref().unwrap_or_default();
+        let net = assignment.net.as_deref().map(|net| net.trim_start_matches('/')).unwrap_or_default();
+
+        let _ = match format {
+            PinMapFormat::Csv => writeln!(
+                result,
+                "{},{},{},{}",
+                csv_field(&assignment.pin),
+                csv_field(function),
+                csv_field(pin_type),
+                csv_field(net)
+            ),
+            PinMapFormat::Xdc if net.is_empty() || net.starts_with("unconnected-") => continue,
+            PinMapFormat::Xdc => writeln!(result, "set_property PACKAGE_PIN {} [get_ports {{{net}}}]", assignment.pin),
+            PinMapFormat::CubeMx => writeln!(
+                result,
+                "{},{},{},{},{}",
+                csv_field(&assignment.pin),
+                csv_field(function),
+                csv_field(cube_mx_type(pin_type)),
+                csv_field(function),
+                csv_field(net)
+            ),
+        };
+    }
+
+    result
+}
+
+/// Map a KiCad pin type to the pin types used in CubeMX pinout exports.
+fn cube_mx_type(pin_type: &str) -> &'static str {
+    match pin_type.split('+').next().unwrap_or_default() {
+        "power_in" | "power_out" => "Power",
+        "input" => "Input",
+        "output" | "open_collector" | "open_emitter" | "tri_state" => "Output",
+        "bidirectional" => "I/O",
+        "passive" | "free" | "unspecified" => "Passive",
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pin_map() {
+        let board = lexpr::from_str(
+            r#"(kicad_pcb
+                (footprint "Package_QFP:LQFP-48" (property "Reference" "U1")
+                    (pad "1" smd rect (net 1 "/LED0") (pinfunction "PA0") (pintype "bidirectional"))
+                    (pad "2" smd rect (net 2 "GND") (pinfunction "VSS") (pintype "power_in"))
+                    (pad "3" smd rect (net 3 "unconnected-(U1-PA1-Pad3)") (pinfunction "PA1") (pintype "bidirectional"))
+                    (pad "" np_thru_hole circle)))"#,
+        )
+        .unwrap();
+
+        assert!(board_pin_assignments(&board, "U2").is_none());
+        let pins = board_pin_assignments(&board, "U1").unwrap();
+        assert_eq!(pins.len(), 3);
+        assert_eq!(pins[0].function.as_deref(), Some("PA0"));
+
+        assert_eq!(
+            export_pin_map(&pins, PinMapFormat::Xdc),
+            "set_property PACKAGE_PIN 1 [get_ports {LED0}]\nset_property PACKAGE_PIN 2 [get_ports {GND}]\n"
+        );
+        assert_eq!(export_pin_map(&pins, PinMapFormat::CubeMx).lines().nth(2), Some("2,VSS,Power,VSS,GND"));
+    }
+}
@@ -0,0 +1,35 @@
+//! The traits, entry points, and options most programs need, for import with
+//! `use kanga_kicad_parser::prelude::*;`.
+
+pub use crate::{
+    cleanup::CleanupOptions,
+    common::{Identified, Nm, Point, Position, Positioned},
+    config::{Config, ConfigBuilder, ParseOptions, Theme},
+    error::{parse_file, Error, ParseWarning, SyntaxError},
+    lazy::{FromRaw, Lazy},
+    lexpr_ext::LexprExt,
+    libtable::{LibTable, LibTableKind},
+    pcb::Pcb,
+    sch::Schematic,
+    span::{parse_str, read_str},
+    symlib::SymbolLibrary,
+    wks::Worksheet,
+    writer::to_kicad_string,
+    ParseError,
+};
+
+#[cfg(feature = "json")]
+pub use crate::project::Project;
+
+#[cfg(feature = "svg")]
+pub use crate::svg::SvgOptions;
+
+#[cfg(feature = "tools")]
+pub use crate::{
+    bom::BomOptions,
+    lint::{FootprintLintOptions, SymbolLintOptions},
+    redact::RedactOptions,
+    review::ReviewOptions,
+    spice::SpiceOptions,
+    symgen::SymbolGeneratorOptions,
+};
@@ -0,0 +1,316 @@
+use {
+    crate::{bundle::sha256_hex, Error},
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::BTreeMap,
+        fs::{create_dir_all, read, read_to_string, remove_file, rename, write},
+        path::{Path, PathBuf},
+    },
+};
+
+#[cfg(feature = "kicad-cli")]
+use crate::cli::{KicadCli, Render3d, RenderSide};
+
+/// The name of the index file within a preview cache directory.
+pub const INDEX_FILE: &str = "index.json";
+
+/// The image format of a preview.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreviewFormat {
+    Png,
+    Svg,
+}
+
+/// A way of rendering a document to a preview image.
+///
+/// Implementations that shell out to KiCad or draw natively both fit here; the cache only needs to
+/// know what a renderer produces and how its output differs from other renderers'.
+pub trait PreviewRenderer {
+    /// Return a string identifying the renderer and any settings that change its output, e.g.
+    /// `kicad-cli pcb render 800x600 top`. Previews are cached separately for each.
+    fn id(&self) -> String;
+
+    /// Return the format of the images rendered.
+    fn format(&self) -> PreviewFormat;
+
+    /// Render the document at `source` to an image at `output`.
+    fn render(&self, source: &Path, output: &Path) -> Result<(), Error>;
+}
+
+/// A board rendered by `kicad-cli pcb render`.
+#[cfg(feature = "kicad-cli")]
+#[derive(Clone, Debug)]
+pub struct CliBoardRenderer {
+    /// The KiCad installation to run.
+    pub cli: KicadCli,
+
+    /// The image width in pixels.
+    pub width: u32,
+
+    /// The image height in pixels.
+    pub height: u32,
+
+    /// The side the board is viewed from.
+    pub side: RenderSide,
+}
+
+/// A previously rendered preview, as recorded in a cache's index.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PreviewEntry {
+    /// The document the preview was rendered from, as given when it was rendered.
+    pub source: PathBuf,
+
+    /// The [`PreviewRenderer::id`] of the renderer used.
+    pub renderer: String,
+
+    /// The image format.
+    pub format: PreviewFormat,
+}
+
+/// A preview returned by [`PreviewCache::preview`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Preview {
+    /// The cache key: a hash of the renderer and the document contents.
+    pub key: String,
+
+    /// The path of the image.
+    pub path: PathBuf,
+
+    /// Whether the image was already in the cache rather than rendered.
+    pub cached: bool,
+}
+
+/// A directory of rendered previews, keyed by the contents of the documents they show.
+///
+/// The directory holds one image per key, named `<key>.<png|svg>`, and an `index.json` mapping
+/// each key to a [`PreviewEntry`] so that browsers and dashboards can list what is cached. Keys
+/// hash the document contents rather than their paths or timestamps, so a preview is reused for
+/// as long as the document is unchanged, wherever it is, and re-rendered as soon as it changes.
+#[derive(Clone, Debug)]
+pub struct PreviewCache {
+    dir: PathBuf,
+    index: BTreeMap<String, PreviewEntry>,
+}
+
+impl PreviewFormat {
+    /// Return the file extension for the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Svg => "svg",
+        }
+    }
+}
+
+#[cfg(feature = "kicad-cli")]
+impl CliBoardRenderer {
+    /// Render boards from the top with `cli`, at 800x600 pixels.
+    pub fn new(cli: KicadCli) -> Self {
+        Self {
+            cli,
+            width: 800,
+            height: 600,
+            side: RenderSide::Top,
+        }
+    }
+}
+
+#[cfg(feature = "kicad-cli")]
+impl PreviewRenderer for CliBoardRenderer {
+    fn id(&self) -> String {
+        format!("kicad-cli pcb render {}x{} {:?}", self.width, self.height, self.side).to_lowercase()
+    }
+
+    fn format(&self) -> PreviewFormat {
+        PreviewFormat::Png
+    }
+
+    fn render(&self, source: &Path, output: &Path) -> Result<(), Error> {
+        let render = Render3d::new(source, output).size(self.width, self.height).side(self.side);
+        self.cli.run(&render).map(drop)
+    }
+}
+
+impl PreviewCache {
+    /// Open the cache in `dir`, creating the directory if needed and reading its index if present.
+    pub fn open<P>(dir: P) -> Result<Self, Error>
+    where
+        P: Into<PathBuf>,
+    {
+        let dir = dir.into();
+        create_dir_all(&dir)?;
+
+        let index_path = dir.join(INDEX_FILE);
+        let index = if index_path.exists() {
+            let json = read_to_string(&index_path)?;
+            serde_json::from_str(&json)?
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(Self {
+            dir,
+            index,
+        })
+    }
+
+    /// Return the cache directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Return the cached previews, by key.
+    pub fn entries(&self) -> &BTreeMap<String, PreviewEntry> {
+        &self.index
+    }
+
+    /// Return the cache key for a document's contents rendered by a renderer.
+    pub fn key(contents: &[u8], renderer: &dyn PreviewRenderer) -> String {
+        content_key(&renderer.id(), contents)
+    }
+
+    /// Return a preview of the document at `source`, rendering it only if the cache has no image
+    /// for its current contents.
+    ///
+    /// Images are rendered to a temporary name and moved into place, so an interrupted render never
+    /// leaves a partial image that later calls would reuse.
+    pub fn preview(&mut self, source: &Path, renderer: &dyn PreviewRenderer) -> Result<Preview, Error> {
+        let contents = read(source)?;
+        let key = Self::key(&contents, renderer);
+        let format = renderer.format();
+        let path = self.dir.join(format!("{key}.{}", format.extension()));
+
+        let cached = path.exists();
+        if !cached {
+            let partial = self.dir.join(format!("{key}.partial.{}", format.extension()));
+            renderer.render(source, &partial)?;
+            rename(&partial, &path)?;
+        }
+
+        let entry = PreviewEntry {
+            source: source.to_path_buf(),
+            renderer: renderer.id(),
+            format,
+        };
+        if self.index.get(&key) != Some(&entry) {
+            self.index.insert(key.clone(), entry);
+            self.save_index()?;
+        }
+
+        Ok(Preview {
+            key,
+            path,
+            cached,
+        })
+    }
+
+    /// Return previews of several documents, rendering those that are missing. A document that
+    /// fails to render does not stop the others.
+    pub fn previews(
+        &mut self,
+        sources: &[PathBuf],
+        renderer: &dyn PreviewRenderer,
+    ) -> Vec<(PathBuf, Result<Preview, Error>)> {
+        sources.iter().map(|source| (source.clone(), self.preview(source, renderer))).collect()
+    }
+
+    /// Remove the previews of documents that have changed since they were rendered, or that no
+    /// longer exist, returning the number removed. Renderers are not needed, since the index
+    /// records the id each key was computed with.
+    pub fn prune(&mut self) -> Result<usize, Error> {
+        let mut stale = Vec::new();
+        let mut current: BTreeMap<&Path, Option<Vec<u8>>> = BTreeMap::new();
+
+        for (key, entry) in &self.index {
+            let contents = current.entry(&entry.source).or_insert_with(|| read(&entry.source).ok());
+            let fresh = contents.as_ref().is_some_and(|contents| content_key(&entry.renderer, contents) == *key);
+
+            if !fresh {
+                stale.push((key.clone(), entry.format));
+            }
+        }
+
+        for (key, format) in &stale {
+            let path = self.dir.join(format!("{key}.{}", format.extension()));
+            if path.exists() {
+                remove_file(&path)?;
+            }
+            self.index.remove(key);
+        }
+
+        if !stale.is_empty() {
+            self.save_index()?;
+        }
+
+        Ok(stale.len())
+    }
+
+    /// Write the index to the cache directory.
+    fn save_index(&self) -> Result<(), Error> {
+        let json = serde_json::to_string_pretty(&self.index)?;
+        Ok(write(self.dir.join(INDEX_FILE), json)?)
+    }
+}
+
+/// Return the cache key for document contents rendered by the renderer with id `renderer`.
+fn content_key(renderer: &str, contents: &[u8]) -> String {
+    let mut keyed = renderer.as_bytes().to_vec();
+    keyed.push(0);
+    keyed.extend_from_slice(contents);
+    sha256_hex(&keyed)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::cell::Cell};
+
+    /// A renderer that writes the document's length as an SVG comment, counting its renders.
+    struct CountingRenderer(Cell<usize>);
+
+    impl PreviewRenderer for CountingRenderer {
+        fn id(&self) -> String {
+            "counting".to_string()
+        }
+
+        fn format(&self) -> PreviewFormat {
+            PreviewFormat::Svg
+        }
+
+        fn render(&self, source: &Path, output: &Path) -> Result<(), Error> {
+            self.0.set(self.0.get() + 1);
+            let len = read(source)?.len();
+            Ok(write(output, format!("<svg><!-- {len} --></svg>"))?)
+        }
+    }
+
+    #[test]
+    fn test_preview_cache() {
+        let dir = std::env::temp_dir().join(format!("kanga-preview-{}", std::process::id()));
+        let source = dir.join("demo.kicad_sch");
+        create_dir_all(&dir).unwrap();
+        write(&source, "(kicad_sch (version 20231120))").unwrap();
+
+        let renderer = CountingRenderer(Cell::new(0));
+        let mut cache = PreviewCache::open(dir.join("previews")).unwrap();
+        let first = cache.preview(&source, &renderer).unwrap();
+        let second = cache.preview(&source, &renderer).unwrap();
+        assert!(!first.cached && second.cached);
+        assert_eq!(first.path, second.path);
+        assert_eq!(renderer.0.get(), 1);
+
+        // A changed document is rendered again, and the old preview is pruned.
+        write(&source, "(kicad_sch (version 20250114))").unwrap();
+        let third = cache.preview(&source, &renderer).unwrap();
+        assert!(!third.cached);
+        assert_eq!(renderer.0.get(), 2);
+
+        let mut reopened = PreviewCache::open(dir.join("previews")).unwrap();
+        assert_eq!(reopened.entries().len(), 2);
+        assert_eq!(reopened.prune().unwrap(), 1);
+        assert!(!first.path.exists() && third.path.exists());
+        assert_eq!(reopened.entries().keys().collect::<Vec<_>>(), [&third.key]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
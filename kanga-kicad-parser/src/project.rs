@@ -0,0 +1,506 @@
+use {
+    crate::{
+        libtable::LibTable,
+        parse_str,
+        pcb::{Pcb, PcbFootprint},
+        sch::{Schematic, SchematicSymbolInstance},
+        wks::Worksheet,
+        Error, ParseError, UriResolver,
+    },
+    serde::{de::DeserializeOwned, Deserialize, Serialize},
+    std::{
+        collections::BTreeMap,
+        fs::{read_dir, read_to_string},
+        io::{Error as IoError, ErrorKind},
+        path::{Path, PathBuf},
+    },
+};
+
+/// A KiCad project: the `.kicad_pro` settings file and the documents it owns.
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/)
+#[derive(Clone, Debug)]
+pub struct Project {
+    /// The directory containing the project.
+    pub dir: PathBuf,
+
+    /// The project name; this is the file stem shared by the project's files.
+    pub name: String,
+
+    /// The contents of the `.kicad_pro` file.
+    pub settings: serde_json::Value,
+
+    /// The root schematic's path.
+    pub root_schematic: PathBuf,
+
+    /// Every schematic in the hierarchy, keyed by path. Each file appears once no matter how many
+    /// times it is instantiated.
+    pub schematics: BTreeMap<PathBuf, Schematic>,
+
+    /// The sheets instantiated by each schematic, in document order.
+    pub sheets: Vec<ProjectSheet>,
+
+    /// The board, if the project has one.
+    pub board: Option<Pcb>,
+
+    /// The project-specific symbol library table (`sym-lib-table`), if present.
+    pub symbol_lib_table: Option<LibTable>,
+
+    /// The project-specific footprint library table (`fp-lib-table`), if present.
+    pub footprint_lib_table: Option<LibTable>,
+
+    /// Drawing sheet (`.kicad_wks`) files referenced by the project settings, keyed by path.
+    pub worksheets: BTreeMap<PathBuf, Worksheet>,
+}
+
+/// A hierarchical sheet placed in a schematic.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProjectSheet {
+    /// The schematic containing the sheet.
+    pub parent: PathBuf,
+
+    /// The sheet name.
+    pub name: String,
+
+    /// The schematic instantiated by the sheet.
+    pub file: PathBuf,
+}
+
+/// The net classes and net class assignments of a project, from `net_settings`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct NetSettings {
+    /// The net classes, including `Default`.
+    #[serde(default)]
+    pub classes: Vec<NetClass>,
+
+    /// Rules assigning nets whose names match a wildcard pattern to a net class.
+    #[serde(default)]
+    pub netclass_patterns: Vec<NetClassPattern>,
+}
+
+/// A net class: the default track, via, and wire dimensions for the nets assigned to it.
+///
+/// Board dimensions are in millimeters; schematic wire and bus widths are in mils. Settings not
+/// present in the file are `None`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct NetClass {
+    /// The net class name.
+    pub name: String,
+
+    /// The minimum clearance to other copper.
+    pub clearance: Option<f64>,
+
+    /// The track width.
+    pub track_width: Option<f64>,
+
+    /// The via pad diameter.
+    pub via_diameter: Option<f64>,
+
+    /// The via drill diameter.
+    pub via_drill: Option<f64>,
+
+    /// The microvia pad diameter.
+    pub microvia_diameter: Option<f64>,
+
+    /// The microvia drill diameter.
+    pub microvia_drill: Option<f64>,
+
+    /// The width of each track of a differential pair.
+    pub diff_pair_width: Option<f64>,
+
+    /// The gap between the tracks of a differential pair.
+    pub diff_pair_gap: Option<f64>,
+
+    /// The schematic wire width.
+    pub wire_width: Option<f64>,
+
+    /// The schematic bus width.
+    pub bus_width: Option<f64>,
+
+    /// The schematic line style, as KiCad's numeric line style.
+    pub line_style: Option<i64>,
+
+    /// The schematic color, as a CSS `rgba(...)` string.
+    pub schematic_color: Option<String>,
+
+    /// The board color, as a CSS `rgba(...)` string.
+    pub pcb_color: Option<String>,
+}
+
+/// A rule assigning nets to a net class by name.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct NetClassPattern {
+    /// The net class assigned.
+    pub netclass: String,
+
+    /// The wildcard pattern matched against net names.
+    pub pattern: String,
+}
+
+/// The severity KiCad reports a rule violation at.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleSeverity {
+    Error,
+    Warning,
+    Ignore,
+}
+
+/// The electrical rules check settings of a project, from `erc`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ErcSettings {
+    /// The severity of each check, keyed by KiCad's rule name, such as `pin_not_connected`.
+    #[serde(default)]
+    pub rule_severities: BTreeMap<String, RuleSeverity>,
+
+    /// The pin conflict matrix, indexed by pin electrical type. Each entry is 0 (no error),
+    /// 1 (warning), or 2 (error).
+    #[serde(default)]
+    pub pin_map: Vec<Vec<u8>>,
+
+    /// Violations the designer has excluded, in KiCad's serialized form.
+    #[serde(default)]
+    pub erc_exclusions: Vec<serde_json::Value>,
+}
+
+/// The design rules check settings of a project, from `board.design_settings`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct DrcSettings {
+    /// The severity of each check, keyed by KiCad's rule name, such as `clearance`.
+    #[serde(default)]
+    pub rule_severities: BTreeMap<String, RuleSeverity>,
+
+    /// The board-wide design rule constraints, such as `min_clearance`, keyed by name. Dimensions
+    /// are in millimeters.
+    #[serde(default)]
+    pub rules: BTreeMap<String, serde_json::Value>,
+
+    /// Violations the designer has excluded, in KiCad's serialized form.
+    #[serde(default)]
+    pub drc_exclusions: Vec<serde_json::Value>,
+}
+
+impl Project {
+    /// Load a project from its `.kicad_pro` file or from the directory containing it.
+    pub fn load<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let pro_path = if path.is_dir() {
+            find_project_file(path)?
+        } else {
+            path.to_path_buf()
+        };
+
+        let dir = pro_path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let name = pro_path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+        let settings: serde_json::Value = serde_json::from_str(&read_to_string(&pro_path)?)?;
+
+        let root_schematic = dir.join(format!("{name}.kicad_sch"));
+        let mut project = Self {
+            dir,
+            name,
+            settings,
+            root_schematic: root_schematic.clone(),
+            schematics: BTreeMap::new(),
+            sheets: Vec::new(),
+            board: None,
+            symbol_lib_table: None,
+            footprint_lib_table: None,
+            worksheets: BTreeMap::new(),
+        };
+
+        if root_schematic.is_file() {
+            project.load_schematic(root_schematic)?;
+        }
+
+        project.board = project.load_optional(&format!("{}.kicad_pcb", project.name))?;
+        project.symbol_lib_table = project.load_optional("sym-lib-table")?;
+        project.footprint_lib_table = project.load_optional("fp-lib-table")?;
+
+        for section in ["schematic", "pcbnew"] {
+            let file = project.settings.pointer(&format!("/{section}/page_layout_descr_file")).and_then(|v| v.as_str());
+            if let Some(file) = file.filter(|file| !file.is_empty()) {
+                let path = UriResolver::for_project(&project).resolve(file)?;
+                if path.is_file() && !project.worksheets.contains_key(&path) {
+                    let worksheet = parse_file(&path)?;
+                    project.worksheets.insert(path, worksheet);
+                }
+            }
+        }
+
+        Ok(project)
+    }
+
+    /// Return the project's net classes and net class assignments.
+    pub fn net_settings(&self) -> Result<NetSettings, Error> {
+        self.settings_section("/net_settings")
+    }
+
+    /// Return the project's electrical rules check settings.
+    pub fn erc_settings(&self) -> Result<ErcSettings, Error> {
+        self.settings_section("/erc")
+    }
+
+    /// Return the project's design rules check settings.
+    pub fn drc_settings(&self) -> Result<DrcSettings, Error> {
+        self.settings_section("/board/design_settings")
+    }
+
+    /// Return the root schematic, if the project has one.
+    pub fn root(&self) -> Option<&Schematic> {
+        self.schematics.get(&self.root_schematic)
+    }
+
+    /// Return the schematic instantiated by a sheet.
+    pub fn sheet_schematic(&self, sheet: &ProjectSheet) -> Option<&Schematic> {
+        self.schematics.get(&sheet.file)
+    }
+
+    /// Return the schematic symbols with the given reference designator: one per unit of the
+    /// component. A symbol matches if any of its instances, or its `Reference` property, has the
+    /// reference.
+    pub fn symbols<'a>(&'a self, reference: &'a str) -> impl Iterator<Item = &'a SchematicSymbolInstance> {
+        self.schematics.values().flat_map(|schematic| &schematic.symbols).filter(move |symbol| {
+            let mut paths = symbol.instances.iter().flat_map(|project| &project.paths);
+            paths.any(|path| path.reference == reference) || symbol.property("Reference") == Some(reference)
+        })
+    }
+
+    /// Return the footprint on the board with the given reference designator.
+    pub fn footprint(&self, reference: &str) -> Option<&PcbFootprint> {
+        let footprints = self.board.iter().flat_map(|board| &board.footprints);
+        footprints.into_iter().find(|footprint| footprint.properties.get("Reference").is_some_and(|r| r == reference))
+    }
+
+    /// Return the footprint on the board for a schematic symbol, matched by reference designator.
+    pub fn symbol_footprint(&self, symbol: &SchematicSymbolInstance) -> Option<&PcbFootprint> {
+        let mut references =
+            symbol.instances.iter().flat_map(|project| &project.paths).map(|path| path.reference.as_str());
+        references
+            .find_map(|reference| self.footprint(reference))
+            .or_else(|| self.footprint(symbol.property("Reference")?))
+    }
+
+    /// Return the drawing sheet used by the schematic editor (`schematic`) or the board editor
+    /// (`pcbnew`), if the project settings name one that was loaded.
+    pub fn worksheet(&self, editor: &str) -> Option<&Worksheet> {
+        let file = self.settings.pointer(&format!("/{editor}/page_layout_descr_file"))?.as_str()?;
+        let path = UriResolver::for_project(self).resolve(file).ok()?;
+        self.worksheets.get(&path)
+    }
+
+    /// Return the text variables defined by the project, such as `${REVISION}`. Values that are
+    /// not strings are ignored.
+    pub fn text_variables(&self) -> BTreeMap<String, String> {
+        let Some(vars) = self.settings.get("text_variables").and_then(|v| v.as_object()) else {
+            return BTreeMap::new();
+        };
+
+        vars.iter().filter_map(|(name, value)| Some((name.clone(), value.as_str()?.to_string()))).collect()
+    }
+
+    /// Return the sheets placed directly in the given schematic.
+    pub fn child_sheets<'a>(&'a self, schematic: &'a Path) -> impl Iterator<Item = &'a ProjectSheet> {
+        self.sheets.iter().filter(move |sheet| sheet.parent == schematic)
+    }
+
+    /// Deserialize the settings at a JSON pointer, or return the defaults if they are absent.
+    fn settings_section<T>(&self, pointer: &str) -> Result<T, Error>
+    where
+        T: DeserializeOwned + Default,
+    {
+        match self.settings.pointer(pointer) {
+            None | Some(serde_json::Value::Null) => Ok(T::default()),
+            Some(section) => Ok(T::deserialize(section)?),
+        }
+    }
+
+    /// Load a schematic and, recursively, the schematics of the sheets it places.
+    fn load_schematic(&mut self, path: PathBuf) -> Result<(), Error> {
+        if self.schematics.contains_key(&path) {
+            return Ok(());
+        }
+
+        let schematic: Schematic = parse_file(&path)?;
+        let base = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let resolver = UriResolver::for_project(self);
+        let mut children = Vec::new();
+
+        for sheet in &schematic.sheets {
+            // KiCad 6 names the sheet properties `Sheet name` and `Sheet file`.
+            let name = sheet.name().or_else(|| sheet.property("Sheet name")).unwrap_or_default();
+            if let Some(file) = sheet.file().or_else(|| sheet.property("Sheet file")) {
+                children.push(ProjectSheet {
+                    parent: path.clone(),
+                    name: name.to_string(),
+                    file: resolver.resolve_from(file, &base)?,
+                });
+            }
+        }
+
+        self.schematics.insert(path, schematic);
+
+        for child in children {
+            let file = child.file.clone();
+            self.sheets.push(child);
+            self.load_schematic(file)?;
+        }
+
+        Ok(())
+    }
+
+    /// Parse a file in the project directory if it exists.
+    fn load_optional<T>(&self, file: &str) -> Result<Option<T>, Error>
+    where
+        T: for<'a> TryFrom<&'a lexpr::Value, Error = ParseError>,
+    {
+        let path = self.dir.join(file);
+        if path.is_file() {
+            parse_file(&path).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Find the single `.kicad_pro` file in a directory.
+fn find_project_file(dir: &Path) -> Result<PathBuf, Error> {
+    let entries = read_dir(dir)?;
+    let mut found = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "kicad_pro"));
+
+    match (found.next(), found.next()) {
+        (Some(path), None) => Ok(path),
+        _ => {
+            Err(IoError::new(ErrorKind::NotFound, format!("Expected exactly one .kicad_pro file in {}", dir.display()))
+                .into())
+        }
+    }
+}
+
+/// Read and parse an s-expression file, recording where in the file any error occurred.
+fn parse_file<T>(path: &Path) -> Result<T, Error>
+where
+    T: for<'a> TryFrom<&'a lexpr::Value, Error = ParseError>,
+{
+    parse_str(&read_to_string(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::fs::write};
+
+    #[test]
+    fn test_project_settings() {
+        let dir = std::env::temp_dir().join(format!("kanga-project-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        write(
+            dir.join("board.kicad_pro"),
+            r#"{
+                "board": {"design_settings": {"rule_severities": {"clearance": "error"}, "rules": {"min_clearance": 0.2}}},
+                "erc": {"pin_map": [[0, 1], [1, 2]], "rule_severities": {"pin_not_connected": "warning"}},
+                "net_settings": {
+                    "classes": [{"name": "Default", "clearance": 0.2, "track_width": 0.25, "wire_width": 6}],
+                    "netclass_patterns": [{"netclass": "Power", "pattern": "+*"}]
+                },
+                "text_variables": {"REVISION": "B", "COUNT": 3}
+            }"#,
+        )
+        .unwrap();
+
+        let project = Project::load(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        let project = project.unwrap();
+
+        let net_settings = project.net_settings().unwrap();
+        assert_eq!(net_settings.classes[0].name, "Default");
+        assert_eq!(net_settings.classes[0].track_width, Some(0.25));
+        assert_eq!(net_settings.classes[0].via_drill, None);
+        assert_eq!(net_settings.netclass_patterns[0].pattern, "+*");
+
+        let erc = project.erc_settings().unwrap();
+        assert_eq!(erc.rule_severities["pin_not_connected"], RuleSeverity::Warning);
+        assert_eq!(erc.pin_map[1], vec![1, 2]);
+
+        let drc = project.drc_settings().unwrap();
+        assert_eq!(drc.rule_severities["clearance"], RuleSeverity::Error);
+        assert_eq!(drc.rules["min_clearance"], 0.2);
+
+        assert_eq!(project.text_variables(), BTreeMap::from([("REVISION".to_string(), "B".to_string())]));
+    }
+
+    #[test]
+    fn test_project_documents() {
+        let dir = std::env::temp_dir().join(format!("kanga-project-documents-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        write(dir.join("demo.kicad_pro"), r#"{"schematic": {"page_layout_descr_file": "${KIPRJMOD}/demo.kicad_wks"}}"#)
+            .unwrap();
+        write(
+            dir.join("demo.kicad_sch"),
+            r#"(kicad_sch (version 20231120) (generator "eeschema")
+                (symbol (lib_id "Device:R") (at 100 50 0) (unit 1) (uuid "0c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")
+                    (property "Reference" "R1") (property "Value" "10k"))
+                (sheet (at 20 20) (size 30 20) (stroke (width 0) (type solid)) (fill (color 0 0 0 0))
+                    (uuid "1c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")
+                    (property "Sheetname" "Power") (property "Sheetfile" "power.kicad_sch")))"#,
+        )
+        .unwrap();
+        write(dir.join("power.kicad_sch"), "(kicad_sch (version 20231120) (generator \"eeschema\"))").unwrap();
+        write(
+            dir.join("demo.kicad_pcb"),
+            r#"(kicad_pcb (version 20240108) (generator "pcbnew")
+                (footprint "Resistor_SMD:R_0603" (layer "F.Cu") (at 10 20) (property "Reference" "R1")
+                    (pad "1" smd rect (at -0.8 0) (size 0.9 0.95) (layers "F.Cu") (net 1 "VCC"))))"#,
+        )
+        .unwrap();
+        write(
+            dir.join("sym-lib-table"),
+            r#"(sym_lib_table (lib (name "Demo") (type "KiCad") (uri "demo.kicad_sym")))"#,
+        )
+        .unwrap();
+        write(dir.join("demo.kicad_wks"), Worksheet::default().to_kicad_wks()).unwrap();
+
+        let project = Project::load(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        let project = project.unwrap();
+
+        assert_eq!(project.schematics.len(), 2);
+        assert_eq!(project.root().map(|root| root.sheets.len()), Some(1));
+        assert_eq!(project.sheets[0].name, "Power");
+        assert!(project.sheet_schematic(&project.sheets[0]).is_some_and(|schematic| schematic.symbols.is_empty()));
+        assert_eq!(
+            project.symbol_lib_table.as_ref().and_then(|table| table.lib("Demo")).map(|lib| lib.uri.as_str()),
+            Some("demo.kicad_sym")
+        );
+        assert!(project.footprint_lib_table.is_none());
+        assert!(project.worksheet("schematic").is_some());
+        assert!(project.worksheet("pcbnew").is_none());
+
+        let symbol = project.symbols("R1").next().unwrap();
+        assert_eq!(symbol.property("Value"), Some("10k"));
+        let footprint = project.symbol_footprint(symbol).unwrap();
+        assert_eq!(footprint.lib_id, "Resistor_SMD:R_0603");
+        assert_eq!(footprint.pads[0].net.as_ref().map(|net| net.name.as_str()), Some("VCC"));
+        assert!(project.footprint("R2").is_none());
+    }
+
+    #[test]
+    fn test_project_errors() {
+        let dir = std::env::temp_dir().join(format!("kanga-project-errors-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        write(dir.join("demo.kicad_pro"), "{").unwrap();
+        let invalid_json = Project::load(&dir);
+        write(dir.join("demo.kicad_pro"), "{}").unwrap();
+        write(dir.join("demo.kicad_sch"), "(kicad_sch (version 20231120)\n  (junction (bogus)))").unwrap();
+        let invalid_schematic = Project::load(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(invalid_json, Err(Error::Json(_))));
+        let error = invalid_schematic.unwrap_err();
+        assert_eq!(error.as_parse_error().and_then(ParseError::location).map(|location| location.line), Some(2));
+    }
+}
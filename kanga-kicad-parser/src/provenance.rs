@@ -0,0 +1,153 @@
+use {
+    crate::{
+        error::Error,
+        span::{read_str, scan, Node, SourceLocation},
+        ParseOptions,
+    },
+    lexpr::Value,
+    std::{
+        collections::BTreeMap,
+        fs::read_to_string,
+        ops::Range,
+        path::{Path, PathBuf},
+    },
+};
+
+/// Where in the source each list of a document came from.
+///
+/// Elements are identified by their path from the root list: the index of each list within its
+/// parent, counting the head as index 0. In `(kicad_sch (version 20231120) (wire ...))`, the root
+/// is `[]`, the `version` list is `[1]`, and the `wire` list is `[2]`. Paths index the values read
+/// from the same text, so [`element`] finds the value an entry describes.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Provenance {
+    /// The file the document was read from, if any.
+    pub file: Option<PathBuf>,
+
+    /// The byte range of each list in the text, by path.
+    ranges: BTreeMap<Vec<usize>, Range<usize>>,
+}
+
+impl Provenance {
+    /// Record the position of every list in the first document in `text`.
+    pub fn from_text(text: &str, file: Option<PathBuf>) -> Self {
+        let mut ranges = BTreeMap::new();
+        if let Some(root) = scan(text).iter().find(|node| node.children.is_some()) {
+            record(root, &mut Vec::new(), &mut ranges);
+        }
+
+        Self {
+            file,
+            ranges,
+        }
+    }
+
+    /// Return the number of elements recorded.
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Indicates whether no elements were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Return the byte range of the element at `path`.
+    pub fn range(&self, path: &[usize]) -> Option<Range<usize>> {
+        self.ranges.get(path).cloned()
+    }
+
+    /// Return the line and column at which the element at `path` starts in `text`, the text the
+    /// provenance was recorded from.
+    pub fn location(&self, path: &[usize], text: &str) -> Option<SourceLocation> {
+        self.ranges.get(path).map(|range| SourceLocation::from_offset(text, range.start))
+    }
+
+    /// Return the path of the innermost element whose text contains the byte `offset`.
+    pub fn element_at(&self, offset: usize) -> Option<&[usize]> {
+        self.ranges
+            .iter()
+            .filter(|(_, range)| range.contains(&offset))
+            .max_by_key(|(path, _)| path.len())
+            .map(|(path, _)| path.as_slice())
+    }
+
+    /// Iterate over the recorded elements and their byte ranges, in document order.
+    pub fn iter(&self) -> impl Iterator<Item = (&[usize], Range<usize>)> {
+        let mut entries: Vec<_> = self.ranges.iter().map(|(path, range)| (path.as_slice(), range.clone())).collect();
+        entries.sort_by_key(|(path, range)| (range.start, path.len()));
+        entries.into_iter()
+    }
+}
+
+/// Read the s-expressions of a document, also recording where each element came from if
+/// [`ParseOptions::track_provenance`] is set.
+pub fn read_document(text: &str, options: &ParseOptions) -> Result<(Value, Option<Provenance>), Error> {
+    let value = read_str(text)?;
+    let provenance = options.track_provenance.then(|| Provenance::from_text(text, None));
+    Ok((value, provenance))
+}
+
+/// Read the s-expressions of a document from a file, also recording where each element came from
+/// if [`ParseOptions::track_provenance`] is set.
+pub fn read_document_file<P>(path: P, options: &ParseOptions) -> Result<(Value, Option<Provenance>), Error>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let text = read_to_string(path)?;
+    let (value, provenance) = read_document(&text, options)?;
+    let provenance = provenance.map(|provenance| Provenance {
+        file: Some(path.to_path_buf()),
+        ..provenance
+    });
+    Ok((value, provenance))
+}
+
+/// Return the element of `root` at `path`, as recorded in a [`Provenance`].
+pub fn element<'a>(root: &'a Value, path: &[usize]) -> Option<&'a Value> {
+    path.iter().try_fold(root, |value, &index| value.list_iter()?.nth(index))
+}
+
+/// Record the range of a list and of the lists within it.
+fn record(node: &Node, path: &mut Vec<usize>, ranges: &mut BTreeMap<Vec<usize>, Range<usize>>) {
+    ranges.insert(path.clone(), node.start..node.end);
+
+    for (index, child) in node.children.iter().flatten().enumerate() {
+        if child.children.is_some() {
+            path.push(index);
+            record(child, path, ranges);
+            path.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::sch::Schematic};
+
+    #[test]
+    fn test_provenance() {
+        let text = "(kicad_sch (version 20231120) (generator \"eeschema\")\n  \
+            (embedded_files (file (name \"a.txt\") (data |KLUv\n  aGVs|)))\n  \
+            (wire (pts (xy 0 0) (xy 10 0)) (stroke (width 0) (type default)) (uuid \"c290e417-b246-4481-96d9-22242c5e4431\")))";
+
+        let (value, provenance) = read_document(text, &ParseOptions::default()).unwrap();
+        assert!(provenance.is_none());
+        Schematic::try_from(&value).unwrap();
+
+        let options = ParseOptions::default().track_provenance(true);
+        let (value, provenance) = read_document(text, &options).unwrap();
+        let provenance = provenance.unwrap();
+
+        // Bar-delimited data spanning lines does not shift the elements that follow it.
+        let wire = provenance.range(&[4]).unwrap();
+        assert!(text[wire.clone()].starts_with("(wire (pts"));
+        assert_eq!(element(&value, &[4, 1, 2]).unwrap().to_string(), "(xy 10 0)");
+        assert_eq!(provenance.location(&[4], text).map(|location| location.line), Some(4));
+
+        let offset = text.find("10 0").unwrap();
+        assert_eq!(provenance.element_at(offset), Some(&[4, 1, 2][..]));
+        assert_eq!(provenance.iter().next().map(|(path, _)| path.len()), Some(0));
+    }
+}
@@ -0,0 +1,217 @@
+use {
+    crate::{
+        bundle::sha256_hex,
+        netclass::wildcard_match,
+        visit::{walk_mut, Edit},
+    },
+    lexpr::Value,
+    serde::{Deserialize, Serialize},
+    std::collections::BTreeMap,
+};
+
+/// Title block fields redacted by [`RedactOptions::title_block`].
+const TITLE_BLOCK_FIELDS: &[&str] = &["title", "date", "rev", "company"];
+
+/// Elements whose first argument is free text, redacted by [`RedactOptions::text`].
+const TEXT_ELEMENTS: &[&str] = &["text", "text_box", "gr_text", "gr_text_box", "fp_text_box"];
+
+/// How redacted text is replaced.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactMode {
+    /// Replace text with a short hash of it, so that equal strings stay equal and a bug that
+    /// depends on two fields matching can still be reproduced.
+    #[default]
+    Hash,
+
+    /// Replace text with an empty string.
+    Clear,
+}
+
+/// Options controlling what [`redact`] removes from a document.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RedactOptions {
+    /// How redacted text is replaced.
+    #[serde(default)]
+    pub mode: RedactMode,
+
+    /// Mixed into each hash, so that short strings cannot be recovered by hashing guesses.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub salt: String,
+
+    /// Redact the title, date, revision, and company of the title block.
+    pub title_block: bool,
+
+    /// Redact the comments of the title block.
+    pub comments: bool,
+
+    /// Redact free text placed on schematics and boards, such as notes and text boxes.
+    #[serde(default)]
+    pub text: bool,
+
+    /// Redact the values of properties whose names match any of these patterns, where `*`
+    /// matches any run of characters and `?` any one character, ignoring case, e.g. `Supplier*`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub properties: Vec<String>,
+
+    /// Remove files embedded in the document.
+    #[serde(default)]
+    pub embedded_files: bool,
+}
+
+/// What [`redact`] changed, for checking that a document was sanitized as intended.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RedactReport {
+    /// The number of elements redacted or removed, by element path, e.g.
+    /// `kicad_sch > title_block > company`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub redacted: BTreeMap<String, usize>,
+}
+
+impl Default for RedactOptions {
+    fn default() -> Self {
+        Self {
+            mode: RedactMode::default(),
+            salt: String::new(),
+            title_block: true,
+            comments: true,
+            text: false,
+            properties: Vec::new(),
+            embedded_files: false,
+        }
+    }
+}
+
+impl RedactOptions {
+    /// Return the replacement for redacted text. Empty text is left empty.
+    pub fn replacement(&self, text: &str) -> String {
+        match self.mode {
+            _ if text.is_empty() => String::new(),
+            RedactMode::Hash => format!("redacted-{}", &sha256_hex(format!("{}{text}", self.salt).as_bytes())[..8]),
+            RedactMode::Clear => String::new(),
+        }
+    }
+
+    /// Return the index of the argument of an element holding the text to redact, if the element
+    /// is redacted.
+    fn text_argument(&self, head: &str, element: &Value) -> Option<usize> {
+        match head {
+            "comment" if self.comments => Some(2),
+            _ if self.title_block && TITLE_BLOCK_FIELDS.contains(&head) => Some(1),
+            _ if self.text && TEXT_ELEMENTS.contains(&head) => Some(1),
+            "fp_text" if self.text => {
+                let kind = element.as_cons()?.cdr().as_cons()?.car().as_symbol()?;
+                (kind == "user").then_some(2)
+            }
+            "property" => {
+                let name = element.as_cons()?.cdr().as_cons()?.car().as_str()?;
+                self.properties.iter().any(|pattern| wildcard_match(pattern, name)).then_some(2)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Strip sensitive content from a document in place, so that it can be shared in a bug report.
+///
+/// Works on the raw s-expressions of any KiCad document. Only text is replaced, so the structure
+/// of the document, and any bug that depends on it, is kept. Title block fields are matched only
+/// within `title_block` elements, and free text only where KiCad places notes, so that names
+/// that carry connectivity, such as labels and references, are unchanged.
+pub fn redact(value: &mut Value, options: &RedactOptions) -> RedactReport {
+    let mut report = RedactReport::default();
+
+    walk_mut(value, |path, element| {
+        let head = path.last().map(String::as_str).unwrap_or_default();
+        let parent = path.len().checked_sub(2).map(|i| path[i].as_str());
+
+        if head == "embedded_files" && options.embedded_files {
+            *report.redacted.entry(path.join(" > ")).or_default() += 1;
+            return Edit::Remove;
+        }
+
+        let is_title_block_field = TITLE_BLOCK_FIELDS.contains(&head) || head == "comment";
+        if is_title_block_field && parent != Some("title_block") {
+            return Edit::Keep;
+        }
+
+        let Some(index) = options.text_argument(head, element) else {
+            return Edit::Keep;
+        };
+
+        if let Some(cons) = nth_cons_mut(element, index) {
+            if let Some(text) = cons.car().as_str() {
+                let replacement = options.replacement(text);
+                if replacement != text {
+                    cons.set_car(Value::string(replacement));
+                    *report.redacted.entry(path.join(" > ")).or_default() += 1;
+                }
+            }
+        }
+
+        Edit::Keep
+    });
+
+    report
+}
+
+/// Return the cons cell holding the argument at `index` of a list, where the head is index 0.
+fn nth_cons_mut(value: &mut Value, index: usize) -> Option<&mut lexpr::Cons> {
+    let mut cons = value.as_cons_mut()?;
+    for _ in 0..index {
+        cons = cons.cdr_mut().as_cons_mut()?;
+    }
+    Some(cons)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, lexpr::sexp};
+
+    #[test]
+    fn test_redact() {
+        let mut schematic = sexp!((kicad_sch (version 20231120)
+            (title_block (title "Secret Widget") (company "Acme Corp") (rev "B") (comment 1 "Acme Corp"))
+            (text "Call Bob at 555-0100" (at 10 20 0))
+            (label "CLK" (at 10 20 0))
+            (symbol (lib_id "Device:R")
+                (property "Reference" "R1") (property "Supplier PN" "ACME-123") (property "Cost" "0.10"))
+            (embedded_files (file (name "notes.txt")))));
+
+        let options = RedactOptions {
+            text: true,
+            properties: vec!["supplier*".to_string(), "C?st".to_string()],
+            embedded_files: true,
+            ..RedactOptions::default()
+        };
+        let report = redact(&mut schematic, &options);
+
+        // Equal strings are replaced by equal hashes.
+        let company = options.replacement("Acme Corp");
+        assert!(company.starts_with("redacted-"));
+        assert_eq!(report.redacted["kicad_sch > title_block > company"], 1);
+        assert_eq!(report.redacted["kicad_sch > symbol > property"], 2);
+        let r = |text| options.replacement(text);
+        let expected = format!(
+            "(kicad_sch (version 20231120)
+                (title_block (title \"{}\") (company \"{company}\") (rev \"{}\") (comment 1 \"{company}\"))
+                (text \"{}\" (at 10 20 0))
+                (label \"CLK\" (at 10 20 0))
+                (symbol (lib_id \"Device:R\")
+                    (property \"Reference\" \"R1\") (property \"Supplier PN\" \"{}\") (property \"Cost\" \"{}\")))",
+            r("Secret Widget"),
+            r("B"),
+            r("Call Bob at 555-0100"),
+            r("ACME-123"),
+            r("0.10"),
+        );
+        assert_eq!(schematic, lexpr::from_str(&expected).unwrap());
+
+        let cleared = RedactOptions {
+            mode: RedactMode::Clear,
+            ..RedactOptions::default()
+        };
+        assert_eq!(cleared.replacement("Acme Corp"), "");
+        assert!(!wildcard_match("supplier*", "Manufacturer"));
+    }
+}
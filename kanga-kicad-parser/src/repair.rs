@@ -0,0 +1,269 @@
+use {
+    crate::{context::OutputContext, read_str, Error, LexprExt},
+    lexpr::Value,
+    serde::{Deserialize, Serialize},
+    std::collections::HashSet,
+};
+
+/// Top-level items that KiCad requires to carry a `uuid` element.
+const UUID_ITEMS: &[&str] = &[
+    "bus",
+    "bus_entry",
+    "global_label",
+    "hierarchical_label",
+    "junction",
+    "label",
+    "no_connect",
+    "polyline",
+    "sheet",
+    "symbol",
+    "text",
+    "wire",
+];
+
+/// The result of repairing a document.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct RepairReport {
+    /// The changes made, in document order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<RepairChange>,
+}
+
+/// A change made while repairing a document.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RepairChange {
+    /// The path of the affected element, e.g. `kicad_sch > wire`.
+    pub path: String,
+
+    /// What was done to the element.
+    pub action: RepairAction,
+}
+
+/// The kind of change made while repairing a document.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepairAction {
+    /// The document ended inside a string, which was terminated.
+    ClosedString,
+
+    /// The document ended before all lists were closed; this many closing parentheses were added.
+    ClosedParentheses(usize),
+
+    /// Text following the end of the document was removed.
+    RemovedTrailingData(String),
+
+    /// A UUID already used by an earlier element was replaced.
+    ReplacedDuplicateUuid {
+        old: String,
+        new: String,
+    },
+
+    /// An element missing its required UUID was given one.
+    AddedUuid(String),
+}
+
+impl RepairReport {
+    /// Indicates whether the document was changed.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Record a change to the element at `path`.
+    fn push(&mut self, path: String, action: RepairAction) {
+        self.changes.push(RepairChange {
+            path,
+            action,
+        });
+    }
+}
+
+/// Repair common corruptions in the text of a KiCad document and parse it.
+///
+/// The following problems are fixed, with each fix recorded in the returned [`RepairReport`]:
+/// * A document truncated inside a string or list is closed.
+/// * Text following the first top-level expression (often left by a bad merge) is removed.
+/// * Duplicated UUIDs are replaced with fresh ones; the first use of each UUID is kept.
+/// * Top-level items that require a UUID but lack one are given a fresh one.
+///
+/// The repaired document can be written with [`lexpr::to_string`].
+pub fn repair(text: &str) -> Result<(Value, RepairReport), Error> {
+    repair_with(text, &mut OutputContext::new())
+}
+
+/// Repair a document as [`repair`] does, generating UUIDs from `context`.
+pub fn repair_with(text: &str, context: &mut OutputContext) -> Result<(Value, RepairReport), Error> {
+    let mut report = RepairReport::default();
+    let text = repair_text(text, &mut report);
+    let value = read_str(&text)?;
+    value.expect_cons_with_any_symbol_head()?;
+
+    let mut repairer = Repairer {
+        seen: HashSet::new(),
+        report,
+        context,
+    };
+
+    let value = repairer.repair(&value, &[]);
+    Ok((value, repairer.report))
+}
+
+/// Balance the first top-level expression in `text` and drop anything after it.
+fn repair_text(text: &str, report: &mut RepairReport) -> String {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut end = None;
+
+    for (i, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => (),
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '(' => depth += 1,
+            ')' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    end = Some(i + 1);
+                    break;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    let Some(end) = end else {
+        let mut result = text.trim_end().to_string();
+
+        if in_string {
+            result.push('"');
+            report.push(String::new(), RepairAction::ClosedString);
+        }
+
+        if depth > 0 {
+            result.push_str(&")".repeat(depth));
+            report.push(String::new(), RepairAction::ClosedParentheses(depth));
+        }
+
+        return result;
+    };
+
+    let trailing = text[end..].trim();
+    if !trailing.is_empty() {
+        report.push(String::new(), RepairAction::RemovedTrailingData(trailing.to_string()));
+    }
+
+    text[..end].to_string()
+}
+
+/// Mutable state used while walking a document.
+struct Repairer<'a> {
+    seen: HashSet<String>,
+    report: RepairReport,
+    context: &'a mut OutputContext,
+}
+
+impl Repairer<'_> {
+    /// Repair the UUIDs of a single element and its children.
+    fn repair(&mut self, value: &Value, path: &[String]) -> Value {
+        let Ok((key, _)) = value.expect_cons_with_any_symbol_head() else {
+            return value.clone();
+        };
+
+        let Some(elements) = value.to_vec() else {
+            return value.clone();
+        };
+
+        let mut item_path = path.to_vec();
+        item_path.push(key.to_string());
+        let path_str = item_path.join(" > ");
+
+        if key == "uuid" {
+            let Some(old) = elements.get(1).and_then(|uuid| uuid.as_str().or_else(|| uuid.as_symbol())) else {
+                return value.clone();
+            };
+
+            if self.seen.insert(old.to_string()) {
+                return value.clone();
+            }
+
+            let new = self.fresh_uuid();
+            self.report.push(
+                path_str,
+                RepairAction::ReplacedDuplicateUuid {
+                    old: old.to_string(),
+                    new: new.clone(),
+                },
+            );
+            return uuid_element(new);
+        }
+
+        let mut result: Vec<Value> = elements.iter().map(|element| self.repair(element, &item_path)).collect();
+
+        // Only items directly beneath the root are required to have a UUID.
+        let is_item = path.len() == 1 && UUID_ITEMS.contains(&key);
+        let has_uuid = elements.iter().any(|element| element.expect_cons_with_symbol_head("uuid").is_ok());
+
+        if is_item && !has_uuid {
+            let new = self.fresh_uuid();
+            self.report.push(path_str, RepairAction::AddedUuid(new.clone()));
+            result.push(uuid_element(new));
+        }
+
+        Value::list(result)
+    }
+
+    /// Generate a UUID not used anywhere in the document.
+    fn fresh_uuid(&mut self) -> String {
+        loop {
+            let uuid = self.context.new_uuid().to_string();
+            if self.seen.insert(uuid.clone()) {
+                return uuid;
+            }
+        }
+    }
+}
+
+/// Create a `(uuid "...")` element.
+fn uuid_element(uuid: String) -> Value {
+    Value::list(vec![Value::symbol("uuid"), Value::string(uuid)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repair() {
+        let text = r#"(kicad_sch (version 20231120) (uuid "a")
+            (junction (at 1 2) (uuid "b"))
+            (wire (pts (xy 0 0) (xy 1 0)) (uuid "b"))
+            (no_connect (at 3 4))
+            (text "unbalanced ( in a string" (uuid "c"))"#;
+
+        let (value, report) = repair(text).unwrap();
+        let actions: Vec<&RepairAction> = report.changes.iter().map(|change| &change.action).collect();
+        assert_eq!(actions[0], &RepairAction::ClosedParentheses(1));
+        assert!(matches!(actions[1], RepairAction::ReplacedDuplicateUuid { old, .. } if old == "b"));
+        assert!(matches!(actions[2], RepairAction::AddedUuid(_)));
+        assert_eq!(report.changes[2].path, "kicad_sch > no_connect");
+        assert_eq!(actions.len(), 3);
+
+        let items = value.to_vec().unwrap();
+        assert_eq!(items.len(), 7);
+
+        let mut a = OutputContext::deterministic(1);
+        let mut b = OutputContext::deterministic(1);
+        assert_eq!(repair_with(text, &mut a).unwrap().0, repair_with(text, &mut b).unwrap().0);
+
+        let (_, report) = repair("(kicad_sch (uuid \"a\")))\n<<<<<<< HEAD\n").unwrap();
+        assert_eq!(report.changes[0].action, RepairAction::RemovedTrailingData(")\n<<<<<<< HEAD".to_string()));
+    }
+}
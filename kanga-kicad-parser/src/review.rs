@@ -0,0 +1,505 @@
+use {
+    crate::{
+        bom::{Bom, BomOptions},
+        common::Symbol,
+        impact::board_nets,
+        lint::{lint_board_footprint, lint_symbol, FootprintLintOptions, LintIssue, Severity, SymbolLintOptions},
+        Project,
+    },
+    serde::{Deserialize, Serialize},
+    std::{
+        cmp::Reverse,
+        collections::{BTreeMap, BTreeSet},
+        fmt::Write as _,
+    },
+};
+
+/// The identifiers of the sections of a design review, in report order.
+pub const REVIEW_SECTIONS: &[&str] = &["annotation", "symbols", "footprints", "bom"];
+
+/// Options controlling a design review.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ReviewOptions {
+    /// Sections to leave out of the review, by identifier from [`REVIEW_SECTIONS`].
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub skip: BTreeSet<String>,
+
+    /// Options for linting the library symbols embedded in the schematics.
+    #[serde(default)]
+    pub symbol_lint: SymbolLintOptions,
+
+    /// Options for linting the footprints placed on the board.
+    pub footprint_lint: FootprintLintOptions,
+
+    /// How to build the bill of materials that the BOM checks run against.
+    #[serde(default)]
+    pub bom: BomOptions,
+
+    /// Properties every fitted component must have with a non-empty value, e.g. `MPN`.
+    pub required_properties: Vec<String>,
+
+    /// The number of errors allowed before the review fails.
+    #[serde(default)]
+    pub max_errors: usize,
+
+    /// The number of warnings allowed before the review fails, or `None` for no limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_warnings: Option<usize>,
+}
+
+/// A group of related findings in a design review.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ReviewSection {
+    /// The section identifier, from [`REVIEW_SECTIONS`].
+    pub id: String,
+
+    /// The section heading.
+    pub title: String,
+
+    /// The problems found.
+    pub issues: Vec<LintIssue>,
+}
+
+/// A pass/fail threshold on the number of findings.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ReviewGate {
+    /// What the gate counts, e.g. `Errors`.
+    pub name: String,
+
+    /// The most findings allowed.
+    pub limit: usize,
+
+    /// The number of findings.
+    pub actual: usize,
+}
+
+/// The result of a design review.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ReviewReport {
+    /// The project name.
+    pub project: String,
+
+    /// Counts describing the size of the design, in display order.
+    pub statistics: Vec<(String, usize)>,
+
+    /// The findings, by section.
+    pub sections: Vec<ReviewSection>,
+
+    /// The thresholds the review is judged against.
+    pub gates: Vec<ReviewGate>,
+}
+
+impl Default for ReviewOptions {
+    fn default() -> Self {
+        Self {
+            skip: BTreeSet::new(),
+            symbol_lint: SymbolLintOptions::default(),
+            footprint_lint: FootprintLintOptions {
+                expected_reference: None,
+                value_matches_name: false,
+            },
+            bom: BomOptions::default(),
+            required_properties: ["Value", "Footprint"].map(String::from).to_vec(),
+            max_errors: 0,
+            max_warnings: None,
+        }
+    }
+}
+
+impl ReviewGate {
+    /// Indicates whether the number of findings is within the limit.
+    pub fn passed(&self) -> bool {
+        self.actual <= self.limit
+    }
+}
+
+impl ReviewReport {
+    /// Indicates whether every gate passed.
+    pub fn passed(&self) -> bool {
+        self.gates.iter().all(ReviewGate::passed)
+    }
+
+    /// Return the number of findings with the given severity across all sections.
+    pub fn count(&self, severity: Severity) -> usize {
+        self.sections.iter().flat_map(|section| &section.issues).filter(|issue| issue.severity == severity).count()
+    }
+
+    /// Render the report as Markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("# Design review: {}\n\n**Result: {}**\n\n", self.project, self.result());
+
+        md.push_str("## Statistics\n\n| Item | Count |\n| --- | ---: |\n");
+        for (name, count) in &self.statistics {
+            let _ = writeln!(md, "| {name} | {count} |");
+        }
+
+        md.push_str("\n## Gates\n\n| Gate | Limit | Actual | Result |\n| --- | ---: | ---: | --- |\n");
+        for gate in &self.gates {
+            let result = if gate.passed() {
+                "pass"
+            } else {
+                "FAIL"
+            };
+            let _ = writeln!(md, "| {} | {} | {} | {result} |", gate.name, gate.limit, gate.actual);
+        }
+
+        for section in &self.sections {
+            let _ = write!(md, "\n## {}\n\n", section.title);
+            if section.issues.is_empty() {
+                md.push_str("No issues found.\n");
+                continue;
+            }
+
+            md.push_str("| Severity | Rule | Location | Message |\n| --- | --- | --- | --- |\n");
+            for issue in &section.issues {
+                let _ = writeln!(
+                    md,
+                    "| {} | `{}` | {} | {} |",
+                    severity_name(issue.severity),
+                    issue.rule,
+                    markdown_escape(issue.location.as_deref().unwrap_or_default()),
+                    markdown_escape(&issue.message),
+                );
+            }
+        }
+
+        md
+    }
+
+    /// Render the report as a standalone HTML page.
+    pub fn to_html(&self) -> String {
+        let title = format!("Design review: {}", html_escape(&self.project));
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n<body>\n\
+             <h1>{title}</h1>\n<p><strong>Result: {}</strong></p>\n",
+            self.result()
+        );
+
+        html.push_str("<h2>Statistics</h2>\n<table>\n<tr><th>Item</th><th>Count</th></tr>\n");
+        for (name, count) in &self.statistics {
+            let _ = writeln!(html, "<tr><td>{}</td><td>{count}</td></tr>", html_escape(name));
+        }
+        html.push_str("</table>\n");
+
+        html.push_str("<h2>Gates</h2>\n<table>\n<tr><th>Gate</th><th>Limit</th><th>Actual</th><th>Result</th></tr>\n");
+        for gate in &self.gates {
+            let result = if gate.passed() {
+                "pass"
+            } else {
+                "FAIL"
+            };
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{result}</td></tr>",
+                html_escape(&gate.name),
+                gate.limit,
+                gate.actual
+            );
+        }
+        html.push_str("</table>\n");
+
+        for section in &self.sections {
+            let _ = writeln!(html, "<h2>{}</h2>", html_escape(&section.title));
+            if section.issues.is_empty() {
+                html.push_str("<p>No issues found.</p>\n");
+                continue;
+            }
+
+            html.push_str("<table>\n<tr><th>Severity</th><th>Rule</th><th>Location</th><th>Message</th></tr>\n");
+            for issue in &section.issues {
+                let _ = writeln!(
+                    html,
+                    "<tr><td>{}</td><td><code>{}</code></td><td>{}</td><td>{}</td></tr>",
+                    severity_name(issue.severity),
+                    html_escape(&issue.rule),
+                    html_escape(issue.location.as_deref().unwrap_or_default()),
+                    html_escape(&issue.message),
+                );
+            }
+            html.push_str("</table>\n");
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    /// Return `PASS` or `FAIL`.
+    fn result(&self) -> &'static str {
+        if self.passed() {
+            "PASS"
+        } else {
+            "FAIL"
+        }
+    }
+}
+
+/// Review a project, combining annotation checks, symbol and footprint lints, statistics, and
+/// BOM checks into one report.
+///
+/// The annotation section checks that every component is annotated, that no reference is used
+/// twice for the same unit, and that the schematics and board contain the same components.
+/// Symbols marked `(on_board no)` need not be on the board.
+/// Embedded library symbols of a schematic that cannot be parsed are reported as an informational
+/// finding rather than being linted.
+pub fn design_review(project: &Project, options: &ReviewOptions) -> ReviewReport {
+    let bom = Bom::from_project(project, &options.bom);
+    let enabled = |id: &str| !options.skip.contains(id);
+    let mut sections = Vec::new();
+
+    let mut schematic_references = BTreeSet::new();
+    let mut placed = BTreeSet::new();
+    let mut units = BTreeSet::new();
+    let mut annotation = Vec::new();
+    let mut symbols: BTreeMap<&str, &Symbol> = BTreeMap::new();
+    let mut unchecked_symbols = Vec::new();
+
+    for (path, schematic) in &project.schematics {
+        match schematic.lib_symbols.get() {
+            Ok(lib_symbols) => {
+                for symbol in lib_symbols {
+                    symbols.entry(symbol.id.as_str()).or_insert(symbol);
+                }
+            }
+            Err(e) => unchecked_symbols.push(LintIssue::new(
+                "symbol-not-checked",
+                Severity::Info,
+                format!("Library symbols could not be checked: {e}"),
+                Some(path.display().to_string()),
+            )),
+        }
+
+        for symbol in &schematic.symbols {
+            let reference = symbol.property("Reference").unwrap_or_default();
+            if reference.starts_with('#') {
+                continue;
+            }
+
+            if reference.is_empty() || reference.ends_with('?') {
+                annotation.push(LintIssue::new(
+                    "unannotated",
+                    Severity::Error,
+                    format!("Symbol {reference:?} is not annotated"),
+                    None,
+                ));
+                continue;
+            }
+
+            if !units.insert((reference.to_string(), symbol.unit)) {
+                annotation.push(LintIssue::new(
+                    "duplicate-reference",
+                    Severity::Error,
+                    format!("Reference {reference} is used more than once"),
+                    Some(reference.to_string()),
+                ));
+            }
+
+            schematic_references.insert(reference.to_string());
+            if symbol.on_board != Some(false) {
+                placed.insert(reference.to_string());
+            }
+        }
+    }
+
+    let mut board_references = BTreeSet::new();
+    let mut footprints = Vec::new();
+    for footprint in project.board.iter().flat_map(|board| &board.footprints) {
+        let reference = footprint.properties.get("Reference").map(String::as_str).unwrap_or_default();
+        board_references.insert(reference.to_string());
+        footprints.push((reference, footprint));
+    }
+
+    if project.board.is_some() {
+        for reference in placed.difference(&board_references) {
+            annotation.push(LintIssue::new(
+                "not-on-board",
+                Severity::Error,
+                format!("{reference} is in the schematic but not on the board"),
+                Some(reference.clone()),
+            ));
+        }
+
+        for reference in &board_references {
+            if !reference.is_empty() && !schematic_references.contains(reference) {
+                annotation.push(LintIssue::new(
+                    "not-in-schematic",
+                    Severity::Warning,
+                    format!("{reference} is on the board but not in the schematic"),
+                    Some(reference.clone()),
+                ));
+            }
+        }
+    }
+
+    if enabled("annotation") {
+        sections.push(section("annotation", "Annotation", annotation));
+    }
+
+    if enabled("symbols") {
+        let mut issues = unchecked_symbols;
+        for (id, symbol) in &symbols {
+            issues.extend(lint_symbol(symbol, &options.symbol_lint).into_iter().map(|mut issue| {
+                issue.location = Some(located(id, issue.location.as_deref()));
+                issue
+            }));
+        }
+        sections.push(section("symbols", "Library symbols", issues));
+    }
+
+    if enabled("footprints") {
+        let mut issues = Vec::new();
+        for (reference, footprint) in &footprints {
+            issues.extend(lint_board_footprint(footprint, &options.footprint_lint).into_iter().map(|mut issue| {
+                issue.location = Some(located(reference, issue.location.as_deref()));
+                issue
+            }));
+        }
+        sections.push(section("footprints", "Footprints", issues));
+    }
+
+    if enabled("bom") {
+        let mut issues = Vec::new();
+        for component in &bom.components {
+            for name in &options.required_properties {
+                if component.property(name).is_empty() {
+                    issues.push(LintIssue::new(
+                        "missing-bom-field",
+                        Severity::Warning,
+                        format!("{} has no {name}", component.reference),
+                        Some(component.reference.clone()),
+                    ));
+                }
+            }
+        }
+        sections.push(section("bom", "Bill of materials", issues));
+    }
+
+    let statistics = vec![
+        ("Schematic files".to_string(), project.schematics.len()),
+        ("Components".to_string(), bom.components.len()),
+        ("Unique parts".to_string(), bom.groups.len()),
+        ("Footprints".to_string(), footprints.len()),
+        ("Nets".to_string(), project.board.as_ref().map(|board| board_nets(board).len()).unwrap_or_default()),
+    ];
+
+    let mut report = ReviewReport {
+        project: project.name.clone(),
+        statistics,
+        sections,
+        gates: Vec::new(),
+    };
+
+    report.gates.push(ReviewGate {
+        name: "Errors".to_string(),
+        limit: options.max_errors,
+        actual: report.count(Severity::Error),
+    });
+    if let Some(limit) = options.max_warnings {
+        report.gates.push(ReviewGate {
+            name: "Warnings".to_string(),
+            limit,
+            actual: report.count(Severity::Warning),
+        });
+    }
+
+    report
+}
+
+/// Build a section with its findings sorted by decreasing severity.
+fn section(id: &str, title: &str, mut issues: Vec<LintIssue>) -> ReviewSection {
+    issues.sort_by_key(|issue| Reverse(issue.severity));
+
+    ReviewSection {
+        id: id.to_string(),
+        title: title.to_string(),
+        issues,
+    }
+}
+
+/// Qualify a lint location with the item it was found on, e.g. `U1 pad 3`.
+fn located(item: &str, location: Option<&str>) -> String {
+    match location {
+        Some(location) => format!("{item} {location}"),
+        None => item.to_string(),
+    }
+}
+
+/// Return the display name of a severity.
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "Info",
+        Severity::Warning => "Warning",
+        Severity::Error => "Error",
+    }
+}
+
+/// Escape text for use in a Markdown table cell.
+fn markdown_escape(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Escape text for use in HTML content.
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{pcb::Pcb, sch::Schematic},
+        lexpr::sexp,
+        std::path::PathBuf,
+    };
+
+    #[test]
+    fn test_design_review() {
+        let project = Project {
+            dir: PathBuf::from("/demo"),
+            name: "demo".to_string(),
+            settings: serde_json::Value::Null,
+            root_schematic: PathBuf::from("/demo/demo.kicad_sch"),
+            schematics: [(
+                PathBuf::from("/demo/demo.kicad_sch"),
+                Schematic::try_from(&sexp!((kicad_sch
+                    (symbol (lib_id "Device:R") (at 0 0 0) (uuid "0c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")
+                        (property "Reference" "R1") (property "Value" "10k") (property "Footprint" "R_0603"))
+                    (symbol (lib_id "Device:R") (at 0 0 0) (uuid "1c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")
+                        (property "Reference" "R2") (property "Value" "10k"))
+                    (symbol (lib_id "Device:R") (at 0 0 0) (uuid "2c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")
+                        (property "Reference" "R?") (property "Value" "1k")))))
+                .unwrap(),
+            )]
+            .into_iter()
+            .collect(),
+            sheets: Vec::new(),
+            board: Some(
+                Pcb::try_from(&sexp!((kicad_pcb
+                    (footprint "R_0603" (property "Reference" "R1" (layer "F.SilkS")) (layer "F.Cu")
+                        (fp_rect (start -1 -1) (end 1 1) (layer "F.CrtYd"))
+                        (fp_rect (start -1 -1) (end 1 1) (layer "F.Fab")))
+                    (footprint "R_0603" (property "Reference" "R3" (layer "F.SilkS")) (layer "F.Cu")))))
+                .unwrap(),
+            ),
+            symbol_lib_table: None,
+            footprint_lib_table: None,
+            worksheets: BTreeMap::new(),
+        };
+
+        let options = ReviewOptions {
+            max_warnings: Some(10),
+            ..ReviewOptions::default()
+        };
+        let report = design_review(&project, &options);
+        let rules: Vec<&str> = report.sections[0].issues.iter().map(|issue| issue.rule.as_str()).collect();
+        assert_eq!(rules, ["unannotated", "not-on-board", "not-in-schematic"]);
+        assert_eq!(report.sections[2].issues[0].location.as_deref(), Some("R3"));
+        assert_eq!(report.sections[3].issues[0].message, "R2 has no Footprint");
+        assert_eq!(report.count(Severity::Error), 3);
+        assert!(!report.passed());
+
+        let md = report.to_markdown();
+        assert!(md.starts_with("# Design review: demo\n\n**Result: FAIL**\n"));
+        assert!(md.contains("| Errors | 0 | 3 | FAIL |\n| Warnings | 10 | 4 | pass |\n"));
+        assert!(report.to_html().contains("<tr><td>Footprints</td><td>2</td></tr>"));
+    }
+}
@@ -1,92 +1,132 @@
 use {
     crate::{
         common::{
-            deserialize_mm_to_unsigned_nm, serialize_unsigned_nm_to_mm, Color, Paper, Points, Position, Size, Stroke,
-            Symbol, SymbolProperty, TextEffects, TitleBlock,
+            Color, EmbeddedFile, Fill, Identified, Offset, Paper, Points, Position, Positioned, Size, Stroke, Symbol,
+            SymbolProperty, TextEffects, TitleBlock,
         },
-        impl_try_from_cons_value, LexprExt, ParseError,
+        impl_identified, impl_positioned, impl_try_from_cons_value,
+        lazy::{FromRaw, Lazy},
+        version::FormatVersion,
+        config::HEADER_SECTIONS,
+        LexprExt, ParseError, ParseOptions, ParseWarning,
     },
     lexpr::{Cons, Value},
-    serde::{Deserialize, Serialize},
     uuid::Uuid,
 };
 
+#[cfg(feature = "serde")]
+use crate::common::{deserialize_mm_to_unsigned_nm, serialize_unsigned_nm_to_mm};
+
 /// KiCad schematic file format.
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-schematic/index.html)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "kicad_sch")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "kicad_sch", deny_unknown_fields))]
 pub struct Schematic {
     /// The schematic version, as a YYYYMMDD integer.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
     pub version: Option<i64>,
 
     /// The program used to generate this schematic (`eeschema` for KiCad's schematic editor).
-    #[serde(default, skip_serializing_if = "String::is_empty")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "String::is_empty"))]
     pub generator: String,
 
     /// The version of the program used to generate this schematic.
-    #[serde(default, skip_serializing_if = "String::is_empty")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "String::is_empty"))]
     pub generator_version: String,
 
     /// The UUID of the schematic.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
     pub uuid: Option<Uuid>,
 
     /// The paper size.
-    #[serde(default, skip_serializing_if = "String::is_empty")]
-    pub paper: String,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub paper: Option<Paper>,
 
-    /// The title block.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub title_block: Option<TitleBlock>,
+    /// The title block, converted on first access if it was not selected for parsing.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub title_block: Option<Lazy<TitleBlock>>,
 
-    /// All of the symbols used
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    pub lib_symbols: Vec<Symbol>,
+    /// All of the symbols used, converted on first access if they were not selected for parsing.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub lib_symbols: Lazy<Vec<Symbol>>,
 
     /// Junctions
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
     pub junctions: Vec<SchematicJunction>,
 
     /// Unused pins
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
     pub no_connects: Vec<SchematicNoConnect>,
 
     /// Bus entries
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
     pub bus_entries: Vec<SchematicBusEntry>,
 
     /// Wires
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
     pub wires: Vec<SchematicWire>,
 
     /// Buses
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
     pub buses: Vec<SchematicBus>,
 
     /// Graphical polylines
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
     pub polylines: Vec<SchematicGraphicPolyline>,
 
     /// Graphical text elements
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
     pub texts: Vec<SchematicGraphicText>,
 
+    /// Bitmap images
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub images: Vec<SchematicImage>,
+
     /// Net labels
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
     pub labels: Vec<SchematicLabel>,
 
     /// Global labels (sheet pins)
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
     pub global_labels: Vec<SchematicGlobalLabel>,
+
+    /// Symbols placed on the sheet
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub symbols: Vec<SchematicSymbolInstance>,
+
+    /// Hierarchical sheets placed on the sheet
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub sheets: Vec<SchematicSheet>,
+
+    /// The page number of each sheet path in the hierarchy. Only the root schematic has these.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub sheet_instances: Vec<SchematicSheetPath>,
+
+    /// Whether the fonts used by text are embedded in the document (version 9+).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub embedded_fonts: Option<bool>,
+
+    /// Files embedded in the document (version 9+).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub embedded_files: Vec<EmbeddedFile>,
+
+    /// Top-level elements left unconverted by [`ParseOptions::sections`] that have no lazily
+    /// converted field, and elements of kinds not modeled here (such as shapes), in
+    /// document order.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub raw_sections: Vec<Value>,
 }
 
 /// Schematic Bus
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-schematic/index.html#_wire_and_bus_section)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "bus")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "bus", deny_unknown_fields))]
 pub struct SchematicBus {
     /// The coordinates of the bus.
     pub points: Points,
@@ -101,15 +141,18 @@ pub struct SchematicBus {
 /// Schematic bus entry
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-schematic/index.html#_bus_entry_section)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "bus_entry")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "bus_entry", deny_unknown_fields))]
 pub struct SchematicBusEntry {
     /// The position of the bus entry
-    #[serde(rename = "at")]
+    #[cfg_attr(feature = "serde", serde(rename = "at"))]
     pub position: Position,
 
-    /// The size of the bus entry. This defines the end point.
-    pub size: Size,
+    /// The offset of the end point from the position. Unlike a [`Size`](crate::common::Size), either
+    /// component may be negative.
+    pub size: Offset,
 
     /// The stroke to use for the bus entry.
     pub stroke: Stroke,
@@ -118,12 +161,13 @@ pub struct SchematicBusEntry {
     pub uuid: Uuid,
 }
 
-
 /// Global schematic label (sheet pin), visible across all schematics in a design.
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-schematic/index.html#_global_label_section)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "global_label")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "global_label", deny_unknown_fields))]
 pub struct SchematicGlobalLabel {
     /// The net name.
     pub text: String,
@@ -132,15 +176,15 @@ pub struct SchematicGlobalLabel {
     pub shape: SchematicGlobalLabelShape,
 
     /// Whether fields have been automatically placed
-    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
     pub fields_autoplaced: bool,
 
     /// The position of the label.
-    #[serde(rename = "at")]
+    #[cfg_attr(feature = "serde", serde(rename = "at"))]
     pub position: Position,
 
     /// Effects to apply to the label.
-    #[serde(rename = "effects")]
+    #[cfg_attr(feature = "serde", serde(rename = "effects"))]
     pub text_effects: TextEffects,
 
     /// A unique identifier for the label
@@ -153,8 +197,10 @@ pub struct SchematicGlobalLabel {
 /// Global schematic label shape
 ///
 /// [Reference](https://gitlab.com/kicad/code/kicad/-/blob/cbccf6f027002577b1268371cf031a490a6f38f1/eeschema/sch_io/kicad_sexpr/sch_io_kicad_sexpr_parser.cpp#L2358)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "shape", rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "shape", rename_all = "snake_case"))]
 pub enum SchematicGlobalLabelShape {
     /// Input
     Input,
@@ -175,11 +221,13 @@ pub enum SchematicGlobalLabelShape {
 /// Schematic graphical lines, which may not necessarily represnt a closed polygon.
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-schematic/index.html#_graphical_line_section)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "polyline")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "polyline", deny_unknown_fields))]
 pub struct SchematicGraphicPolyline {
     /// The coordinates of the polyline.
-    #[serde(rename = "pts")]
+    #[cfg_attr(feature = "serde", serde(rename = "pts"))]
     pub points: Points,
 
     /// The stroke to use for the polyline.
@@ -192,37 +240,78 @@ pub struct SchematicGraphicPolyline {
 /// Schematic text
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-schematic/index.html#_graphical_text_section)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "text")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "text", deny_unknown_fields))]
 pub struct SchematicGraphicText {
     /// The text to display.
     pub text: String,
 
+    /// Whether the text is excluded from simulation.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+    pub exclude_from_sim: bool,
+
     /// The position of the text.
-    #[serde(rename = "at")]
+    #[cfg_attr(feature = "serde", serde(rename = "at"))]
     pub position: Position,
 
     /// Effects to apply to the text.
-    #[serde(rename = "effects")]
+    #[cfg_attr(feature = "serde", serde(rename = "effects"))]
     pub text_effects: TextEffects,
 
     /// A unique identifier for the text
     pub uuid: Uuid,
 }
 
+/// Schematic image, a bitmap placed on the sheet.
+///
+/// The data is kept as KiCad stores it, base64-encoded, so that documents round-trip without
+/// re-encoding their images. With the `tools` feature, [`SchematicImage::decode`] returns the
+/// image file, which KiCad writes as a PNG.
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-schematic/index.html#_image_section)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "image", deny_unknown_fields))]
+pub struct SchematicImage {
+    /// The position of the center of the image.
+    #[cfg_attr(feature = "serde", serde(rename = "at"))]
+    pub position: Position,
+
+    /// The scale factor applied to the image; KiCad omits it when it is 1.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub scale: Option<f64>,
+
+    /// A unique identifier for the image
+    pub uuid: Uuid,
+
+    /// The base64-encoded image file, without whitespace.
+    pub data: String,
+}
 
 /// Schematic wire junction
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-schematic/index.html#_no_connect_section)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "junction")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "junction", deny_unknown_fields))]
 pub struct SchematicJunction {
     /// The position of the junction
-    #[serde(rename = "at")]
+    #[cfg_attr(feature = "serde", serde(rename = "at"))]
     pub position: Position,
 
     /// The diameter in nm
-    #[serde(deserialize_with = "deserialize_mm_to_unsigned_nm", serialize_with = "serialize_unsigned_nm_to_mm")]
+    #[cfg_attr(feature = "schemars", schemars(with = "f64"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            deserialize_with = "deserialize_mm_to_unsigned_nm",
+            serialize_with = "serialize_unsigned_nm_to_mm"
+        )
+    )]
     pub diameter: u64,
 
     /// The color of the junction.
@@ -235,11 +324,13 @@ pub struct SchematicJunction {
 /// Unused schematic pin.
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-schematic/index.html#_no_connect_section)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "no_connect")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "no_connect", deny_unknown_fields))]
 pub struct SchematicNoConnect {
     /// The position of the no connect
-    #[serde(rename = "at")]
+    #[cfg_attr(feature = "serde", serde(rename = "at"))]
     pub position: Position,
 
     /// A unique identifier for the no connect
@@ -249,29 +340,271 @@ pub struct SchematicNoConnect {
 /// Schematic wire or bus label
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-schematic/index.html#_local_label_section)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "label")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "label", deny_unknown_fields))]
 pub struct SchematicLabel {
     /// The net name.
     pub text: String,
 
+    /// Whether fields have been automatically placed
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+    pub fields_autoplaced: bool,
+
     /// The position of the label.
-    #[serde(rename = "at")]
+    #[cfg_attr(feature = "serde", serde(rename = "at"))]
     pub position: Position,
 
     /// Effects to apply to the label.
-    #[serde(rename = "effects")]
+    #[cfg_attr(feature = "serde", serde(rename = "effects"))]
     pub text_effects: TextEffects,
 
     /// A unique identifier for the label
     pub uuid: Uuid,
 }
 
+/// Hierarchical sheet placed on a schematic, referring to another schematic file.
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-schematic/index.html#_hierarchical_sheet_section)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "sheet", deny_unknown_fields))]
+pub struct SchematicSheet {
+    /// The position of the top left corner of the sheet.
+    #[cfg_attr(feature = "serde", serde(rename = "at"))]
+    pub position: Position,
+
+    /// The size of the sheet outline.
+    pub size: Size,
+
+    /// Whether the sheet is excluded from simulation.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub exclude_from_sim: Option<bool>,
+
+    /// Whether the sheet's symbols are included in the bill of materials.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub in_bom: Option<bool>,
+
+    /// Whether the sheet's symbols are exported to the board.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub on_board: Option<bool>,
+
+    /// Whether the sheet's symbols are marked do-not-populate.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub dnp: Option<bool>,
+
+    /// Whether fields have been automatically placed
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+    pub fields_autoplaced: bool,
+
+    /// The stroke to use for the sheet outline.
+    pub stroke: Stroke,
+
+    /// The fill of the sheet background.
+    pub fill: Fill,
+
+    /// A unique identifier for the sheet
+    pub uuid: Uuid,
+
+    /// Properties of the sheet, including its `Sheetname` and `Sheetfile`.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub properties: Vec<SymbolProperty>,
+
+    /// The pins connecting the sheet to hierarchical labels in its schematic.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub pins: Vec<SheetPin>,
+
+    /// The page numbers of the sheet in each project and sheet path that uses it.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub instances: Vec<SchematicSheetProject>,
+}
+
+/// The page number of a hierarchical sheet on one sheet path.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "path", deny_unknown_fields))]
+pub struct SchematicSheetPath {
+    /// The sheet path, as a `/`-separated list of sheet UUIDs starting with the root schematic's.
+    pub path: String,
+
+    /// The page number, which need not be numeric.
+    pub page: String,
+}
+
+/// The instances of a hierarchical sheet within one project.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "project", deny_unknown_fields))]
+pub struct SchematicSheetProject {
+    /// The project name.
+    pub name: String,
+
+    /// The sheet's page number on each sheet path.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub paths: Vec<SchematicSheetPath>,
+}
+
+/// Pin on a hierarchical sheet, connected to the hierarchical label of the same name in the
+/// sheet's schematic.
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-schematic/index.html#_hierarchical_sheet_pin_definition)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "pin", deny_unknown_fields))]
+pub struct SheetPin {
+    /// The pin name.
+    pub name: String,
+
+    /// The electrical type of the pin, which KiCad draws with the same shapes as global labels.
+    pub shape: SchematicGlobalLabelShape,
+
+    /// The position of the pin on the sheet edge.
+    #[cfg_attr(feature = "serde", serde(rename = "at"))]
+    pub position: Position,
+
+    /// Effects to apply to the pin name.
+    #[cfg_attr(feature = "serde", serde(rename = "effects"))]
+    pub text_effects: TextEffects,
+
+    /// A unique identifier for the pin
+    pub uuid: Uuid,
+}
+
+/// Symbol placed on a schematic sheet.
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-schematic/index.html#_symbol_section)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "symbol", deny_unknown_fields))]
+pub struct SchematicSymbolInstance {
+    /// The library symbol this is an instance of, as `library:symbol`.
+    pub lib_id: String,
+
+    /// The name of the symbol in `lib_symbols`, if it differs from `lib_id` because the symbol was
+    /// edited in the schematic.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub lib_name: Option<String>,
+
+    /// The position and rotation of the symbol.
+    #[cfg_attr(feature = "serde", serde(rename = "at"))]
+    pub position: Position,
+
+    /// The axis the symbol is mirrored about, if any.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub mirror: Option<SchematicSymbolMirror>,
+
+    /// The unit of a multi-unit symbol, starting at 1.
+    pub unit: i64,
+
+    /// Whether the symbol is excluded from simulation.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub exclude_from_sim: Option<bool>,
+
+    /// Whether the symbol is included in the bill of materials.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub in_bom: Option<bool>,
+
+    /// Whether the symbol is exported to the board.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub on_board: Option<bool>,
+
+    /// Whether the symbol is marked do-not-populate.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub dnp: Option<bool>,
+
+    /// Whether fields have been automatically placed
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+    pub fields_autoplaced: bool,
+
+    /// A unique identifier for the symbol
+    pub uuid: Uuid,
+
+    /// Properties of the symbol, such as its reference and value.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub properties: Vec<SymbolProperty>,
+
+    /// The pins of the symbol.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub pins: Vec<SchematicSymbolPin>,
+
+    /// The references and units of the symbol in each project and sheet path that uses it.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub instances: Vec<SchematicSymbolProject>,
+}
+
+/// Axis a placed schematic symbol is mirrored about.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "mirror", rename_all = "snake_case"))]
+pub enum SchematicSymbolMirror {
+    /// Mirrored about the X axis (flipped vertically).
+    X,
+
+    /// Mirrored about the Y axis (flipped horizontally).
+    Y,
+}
+
+/// Pin of a placed schematic symbol.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "pin", deny_unknown_fields))]
+pub struct SchematicSymbolPin {
+    /// The pin number.
+    pub number: String,
+
+    /// A unique identifier for the pin
+    pub uuid: Uuid,
+
+    /// The alternate pin function selected, if any.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub alternate: Option<String>,
+}
+
+/// The instances of a placed schematic symbol within one project.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "project", deny_unknown_fields))]
+pub struct SchematicSymbolProject {
+    /// The project name.
+    pub name: String,
+
+    /// The symbol's reference and unit on each sheet path.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub paths: Vec<SchematicSymbolPath>,
+}
+
+/// The reference and unit of a placed schematic symbol on one sheet path.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "path", deny_unknown_fields))]
+pub struct SchematicSymbolPath {
+    /// The sheet path, as a `/`-separated list of sheet UUIDs starting with the root schematic's.
+    pub path: String,
+
+    /// The reference designator, such as `R1`.
+    pub reference: String,
+
+    /// The unit used on this path.
+    pub unit: i64,
+}
+
 /// Schematic Wire
 ///
 /// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-schematic/index.html#_wire_and_bus_section)
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename = "wire")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "wire", deny_unknown_fields))]
 pub struct SchematicWire {
     /// The coordinates of the wire.
     pub points: Points,
@@ -283,17 +616,28 @@ pub struct SchematicWire {
     pub uuid: Uuid,
 }
 
-impl TryFrom<&Cons> for Schematic {
-    type Error = ParseError;
+impl Schematic {
+    /// Parse a schematic, converting only the top-level sections selected by `options`. Other
+    /// sections are kept raw: the title block and library symbols are converted on first access,
+    /// and the rest are kept in [`Schematic::raw_sections`].
+    pub fn parse(cons: &Cons, options: &ParseOptions) -> Result<Self, ParseError> {
+        Self::parse_with_warnings(cons, options).map(|(schematic, _)| schematic)
+    }
 
-    fn try_from(cons: &Cons) -> Result<Self, ParseError> {
+    /// Parse a schematic as [`Schematic::parse`] does, also returning the elements that were
+    /// skipped.
+    ///
+    /// Without [`ParseOptions::strict`], an element other than the header that fails to convert is
+    /// kept in [`Schematic::raw_sections`] and reported as a [`ParseWarning`] instead of failing
+    /// the parse.
+    pub fn parse_with_warnings(cons: &Cons, options: &ParseOptions) -> Result<(Self, Vec<ParseWarning>), ParseError> {
         let mut version = None;
         let mut generator = None;
         let mut generator_version = None;
         let mut uuid = None;
         let mut paper = None;
         let mut title_block = None;
-        let mut lib_symbols = Vec::new();
+        let mut lib_symbols = Lazy::default();
         let mut junctions = Vec::new();
         let mut no_connects = Vec::new();
         let mut bus_entries = Vec::new();
@@ -301,6 +645,16 @@ impl TryFrom<&Cons> for Schematic {
         let mut buses = Vec::new();
         let mut polylines = Vec::new();
         let mut texts = Vec::new();
+        let mut images = Vec::new();
+        let mut labels = Vec::new();
+        let mut global_labels = Vec::new();
+        let mut symbols = Vec::new();
+        let mut sheets = Vec::new();
+        let mut sheet_instances = Vec::new();
+        let mut embedded_fonts = None;
+        let mut embedded_files = Vec::new();
+        let mut raw_sections = Vec::new();
+        let mut warnings = Vec::new();
 
         let mut rest = cons.expect_cons_with_symbol_head("kicad_sch")?;
 
@@ -308,84 +662,310 @@ impl TryFrom<&Cons> for Schematic {
             let r_cons = rest.expect_cons()?;
             let element = r_cons.car();
             rest = r_cons.cdr();
-            let (key, mut cdr) = element.expect_cons_with_any_symbol_head()?;
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
 
-            match key {
-                "version" => {
-                    let (value, cdr) = cdr.expect_cons_with_any_int_head()?;
-                    cdr.expect_null();
-                    version = Some(value);
+            if !options.includes_section(key) {
+                match key {
+                    "title_block" => title_block = Some(Lazy::new(element.clone())),
+                    "lib_symbols" => lib_symbols = Lazy::new(element.clone()),
+                    _ => raw_sections.push(element.clone()),
                 }
+                continue;
+            }
 
-                "generator" => {
-                    let (value, cdr) = cdr.expect_cons_with_any_symbol_head()?;
-                    cdr.expect_null()?;
-                    generator = Some(value.to_string());
-                }
+            let converted = (|| -> Result<(), ParseError> {
+                match key {
+                    "version" => {
+                        let (value, cdr) = cdr.expect_cons_with_any_int_head()?;
+                        cdr.expect_null()?;
+                        FormatVersion::check(Some(value), FormatVersion::Kicad6, options)?;
+                        version = Some(value);
+                    }
 
-                "generator_version" => {
-                    let (value, cdr) = cdr.expect_cons_with_any_symbol_head()?;
-                    cdr.expect_null();
-                    generator_version = Some(value.to_string());
-                }
+                    "generator" => {
+                        generator = Some(text_arg(cdr)?.to_string());
+                    }
 
-                "uuid" => {
-                    let (value, cdr) = cdr.expect_cons_with_any_symbol_head()?;
-                    cdr.expect_null();
-                    uuid = Some(Uuid::parse_str(value).map_err(|_| ParseError::InvalidUuid(value.to_string()))?);
-                }
+                    "generator_version" => {
+                        generator_version = Some(text_arg(cdr)?.to_string());
+                    }
 
-                "paper" => {
-                    paper = Some(Paper::try_from(r_cons));
-                }
+                    "uuid" => {
+                        uuid = Some(uuid_arg(cdr)?);
+                    }
 
-                "title_block" => {
-                    title_block = Some(TitleBlock::try_from(r_cons));
-                }
+                    "paper" => {
+                        paper = Some(Paper::try_from(element)?);
+                    }
 
-                "lib_symbols" => {
-                    while !cdr.is_null() {
-                        let r_cons = cdr.expect_cons()?;
-                        let element = r_cons.car();
-                        cdr = r_cons.cdr();
-                        lib_symbols.push(Symbol::try_from(element)?);
+                    "title_block" => {
+                        title_block = Some(Lazy::from_value(TitleBlock::try_from(element)?));
+                    }
+
+                    "lib_symbols" => {
+                        lib_symbols = Lazy::from_value(Vec::from_raw(element)?);
+                    }
+
+                    "junction" => {
+                        junctions.push(SchematicJunction::try_from(element)?);
+                    }
+
+                    "no_connect" => {
+                        no_connects.push(SchematicNoConnect::try_from(element)?);
+                    }
+
+                    "bus_entry" => {
+                        bus_entries.push(SchematicBusEntry::try_from(element)?);
+                    }
+
+                    "wire" => {
+                        wires.push(SchematicWire::try_from(element)?);
+                    }
+
+                    "bus" => {
+                        buses.push(SchematicBus::try_from(element)?);
+                    }
+
+                    "polyline" => {
+                        polylines.push(SchematicGraphicPolyline::try_from(element)?);
+                    }
+
+                    "text" => {
+                        texts.push(SchematicGraphicText::try_from(element)?);
+                    }
+
+                    "image" => {
+                        images.push(SchematicImage::try_from(element)?);
+                    }
+
+                    "label" => {
+                        labels.push(SchematicLabel::try_from(element)?);
+                    }
+
+                    "global_label" => {
+                        global_labels.push(SchematicGlobalLabel::try_from(element)?);
+                    }
+
+                    "symbol" => {
+                        symbols.push(SchematicSymbolInstance::try_from(element)?);
+                    }
+
+                    "sheet" => {
+                        sheets.push(SchematicSheet::try_from(element)?);
+                    }
+
+                    "sheet_instances" => {
+                        let paths = cdr
+                            .list_iter()
+                            .into_iter()
+                            .flatten()
+                            .map(SchematicSheetPath::try_from)
+                            .collect::<Result<Vec<_>, _>>()?;
+                        sheet_instances.extend(paths);
+                    }
+
+                    "embedded_fonts" => {
+                        embedded_fonts = Some(bool_arg(cdr)?);
+                    }
+
+                    "embedded_files" => {
+                        let files = cdr
+                            .list_iter()
+                            .into_iter()
+                            .flatten()
+                            .map(EmbeddedFile::try_from)
+                            .collect::<Result<Vec<_>, _>>()?;
+                        embedded_files.extend(files);
                     }
-                }
 
-                "junction" => {
-                    junctions.push(SchematicJunction::try_from(element)?);
+                    _ => raw_sections.push(element.clone()),
                 }
 
-                "no_connect" => {
-                    no_connects.push(SchematicNoConnect::try_from(element)?);
+                Ok(())
+            })();
+
+            if let Err(error) = converted {
+                if options.strict || HEADER_SECTIONS.contains(&key) {
+                    return Err(error);
                 }
+
+                raw_sections.push(element.clone());
+                warnings.push(ParseWarning {
+                    element: element.clone(),
+                    error,
+                });
             }
         }
 
-        todo!()
+        let schematic = Self {
+            version,
+            generator: generator.unwrap_or_default(),
+            generator_version: generator_version.unwrap_or_default(),
+            uuid,
+            paper,
+            title_block,
+            lib_symbols,
+            junctions,
+            no_connects,
+            bus_entries,
+            wires,
+            buses,
+            polylines,
+            texts,
+            images,
+            labels,
+            global_labels,
+            symbols,
+            sheets,
+            sheet_instances,
+            embedded_fonts,
+            embedded_files,
+            raw_sections,
+        };
+
+        Ok((schematic, warnings))
+    }
+
+    /// Return the KiCad release whose format the schematic declares, if it declares a version.
+    pub fn format_version(&self) -> Option<FormatVersion> {
+        self.version.map(FormatVersion::nearest)
+    }
+
+    /// Call `f` with every element of the schematic that has a UUID, starting with the schematic
+    /// itself. Sheet pins and symbol pins follow the sheet or symbol they belong to.
+    pub fn for_each_identified<F>(&self, mut f: F)
+    where
+        F: FnMut(&dyn Identified),
+    {
+        f(self);
+        self.junctions.iter().for_each(|e| f(e));
+        self.no_connects.iter().for_each(|e| f(e));
+        self.bus_entries.iter().for_each(|e| f(e));
+        self.wires.iter().for_each(|e| f(e));
+        self.buses.iter().for_each(|e| f(e));
+        self.polylines.iter().for_each(|e| f(e));
+        self.texts.iter().for_each(|e| f(e));
+        self.images.iter().for_each(|e| f(e));
+        self.labels.iter().for_each(|e| f(e));
+        self.global_labels.iter().for_each(|e| f(e));
+
+        for symbol in &self.symbols {
+            f(symbol);
+            symbol.pins.iter().for_each(|e| f(e));
+        }
+
+        for sheet in &self.sheets {
+            f(sheet);
+            sheet.pins.iter().for_each(|e| f(e));
+        }
+    }
+
+    /// Call `f` with every element of the schematic that has a UUID, mutably, in the same order as
+    /// [`Schematic::for_each_identified`].
+    pub fn for_each_identified_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut dyn Identified),
+    {
+        f(self);
+        self.junctions.iter_mut().for_each(|e| f(e));
+        self.no_connects.iter_mut().for_each(|e| f(e));
+        self.bus_entries.iter_mut().for_each(|e| f(e));
+        self.wires.iter_mut().for_each(|e| f(e));
+        self.buses.iter_mut().for_each(|e| f(e));
+        self.polylines.iter_mut().for_each(|e| f(e));
+        self.texts.iter_mut().for_each(|e| f(e));
+        self.images.iter_mut().for_each(|e| f(e));
+        self.labels.iter_mut().for_each(|e| f(e));
+        self.global_labels.iter_mut().for_each(|e| f(e));
+
+        for symbol in &mut self.symbols {
+            f(symbol);
+            symbol.pins.iter_mut().for_each(|e| f(e));
+        }
+
+        for sheet in &mut self.sheets {
+            f(sheet);
+            sheet.pins.iter_mut().for_each(|e| f(e));
+        }
+    }
+
+    /// Call `f` with every placed element of the schematic, mutably. Properties and pins follow the
+    /// label, sheet, or symbol they belong to; their positions are absolute, like the rest.
+    pub fn for_each_positioned_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut dyn Positioned),
+    {
+        self.junctions.iter_mut().for_each(|e| f(e));
+        self.no_connects.iter_mut().for_each(|e| f(e));
+        self.bus_entries.iter_mut().for_each(|e| f(e));
+        self.texts.iter_mut().for_each(|e| f(e));
+        self.images.iter_mut().for_each(|e| f(e));
+        self.labels.iter_mut().for_each(|e| f(e));
+
+        for label in &mut self.global_labels {
+            f(label);
+            label.properties.iter_mut().for_each(|e| f(e));
+        }
+
+        for symbol in &mut self.symbols {
+            f(symbol);
+            symbol.properties.iter_mut().for_each(|e| f(e));
+        }
+
+        for sheet in &mut self.sheets {
+            f(sheet);
+            sheet.properties.iter_mut().for_each(|e| f(e));
+            sheet.pins.iter_mut().for_each(|e| f(e));
+        }
+    }
+}
+
+impl TryFrom<&Cons> for Schematic {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, ParseError> {
+        Self::parse(cons, &ParseOptions::default())
+    }
+}
+
+impl FromRaw for Vec<Symbol> {
+    /// Convert a `(lib_symbols ...)` element.
+    fn from_raw(raw: &Value) -> Result<Self, ParseError> {
+        let rest = raw.expect_cons_with_symbol_head("lib_symbols")?;
+        rest.list_iter().into_iter().flatten().map(Symbol::try_from).collect()
     }
 }
 
 impl_try_from_cons_value!(Schematic);
+impl_identified!(Schematic, optional);
 
-impl TryFrom<&Cons> for SchematicBusEntry {
+impl TryFrom<&Cons> for SchematicBus {
     type Error = ParseError;
 
     fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
-        
+        let (points, stroke, uuid) = parse_line(cons, "bus")?;
+
+        Ok(Self {
+            points,
+            stroke,
+            uuid,
+        })
     }
 }
 
-impl TryFrom<&Cons> for SchematicJunction {
+impl_try_from_cons_value!(SchematicBus);
+impl_identified!(SchematicBus);
+
+impl TryFrom<&Cons> for SchematicBusEntry {
     type Error = ParseError;
 
     fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
         let mut position = None;
-        let mut diameter = None;
-        let mut color = None;
+        let mut size = None;
+        let mut stroke = None;
         let mut uuid = None;
 
-        let mut rest = cons.expect_cons_with_symbol_head("junction")?;
+        let mut rest = cons.expect_cons_with_symbol_head("bus_entry")?;
         while !rest.is_null() {
             let r_cons = rest.expect_cons()?;
             let element = r_cons.car();
@@ -397,20 +977,22 @@ impl TryFrom<&Cons> for SchematicJunction {
                     position = Some(Position::try_from(element)?);
                 }
 
-                "diameter" => {
-                    let (value, cdr) = cdr.expect_cons_with_any_float_head()?;
+                "size" => {
+                    let (x, cdr) = cdr.expect_cons_with_any_length_head()?;
+                    let (y, cdr) = cdr.expect_cons_with_any_length_head()?;
                     cdr.expect_null()?;
-                    diameter = Some((value * 1e6) as u64);
+                    size = Some(Offset {
+                        x,
+                        y,
+                    });
                 }
 
-                "color" => {
-                    color = Some(Color::try_from(element)?);
+                "stroke" => {
+                    stroke = Some(Stroke::try_from(element)?);
                 }
 
                 "uuid" => {
-                    let (value, cdr) = cdr.expect_cons_with_any_symbol_head()?;
-                    cdr.expect_null()?;
-                    uuid = Some(Uuid::parse_str(value).map_err(|_| ParseError::InvalidUuid(value.to_string()))?);
+                    uuid = Some(uuid_arg(cdr)?);
                 }
 
                 _ => return Err(ParseError::Unexpected(element.clone())),
@@ -418,40 +1000,49 @@ impl TryFrom<&Cons> for SchematicJunction {
         }
 
         let Some(position) = position else {
-            return Err(ParseError::missing_field("junction", "at", cons.clone()));
+            return Err(ParseError::missing_field("bus_entry", "at", cons.clone()));
         };
 
-        let Some(diameter) = diameter else {
-            return Err(ParseError::missing_field("junction", "diameter", cons.clone()));
+        let Some(size) = size else {
+            return Err(ParseError::missing_field("bus_entry", "size", cons.clone()));
         };
 
-        let Some(color) = color else {
-            return Err(ParseError::missing_field("junction", "color", cons.clone()));
+        let Some(stroke) = stroke else {
+            return Err(ParseError::missing_field("bus_entry", "stroke", cons.clone()));
         };
 
         let Some(uuid) = uuid else {
-            return Err(ParseError::missing_field("junction", "uuid", cons.clone()));
+            return Err(ParseError::missing_field("bus_entry", "uuid", cons.clone()));
         };
 
         Ok(Self {
             position,
-            diameter,
-            color,
+            size,
+            stroke,
             uuid,
         })
     }
 }
 
-impl_try_from_cons_value!(SchematicJunction);
+impl_try_from_cons_value!(SchematicBusEntry);
+impl_identified!(SchematicBusEntry);
+impl_positioned!(SchematicBusEntry);
 
-impl TryFrom<&Cons> for SchematicNoConnect {
+impl TryFrom<&Cons> for SchematicGlobalLabel {
     type Error = ParseError;
 
     fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let rest = cons.expect_cons_with_symbol_head("global_label")?;
+        let (text, mut rest) = rest.expect_cons_with_any_str_head()?;
+        let text = text.to_string();
+
+        let mut shape = None;
+        let mut fields_autoplaced = false;
         let mut position = None;
+        let mut text_effects = None;
         let mut uuid = None;
+        let mut properties = Vec::new();
 
-        let mut rest = cons.expect_cons_with_symbol_head("no_connect")?;
         while !rest.is_null() {
             let r_cons = rest.expect_cons()?;
             let element = r_cons.car();
@@ -459,30 +1050,1185 @@ impl TryFrom<&Cons> for SchematicNoConnect {
             let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
 
             match key {
+                "shape" => {
+                    shape = Some(SchematicGlobalLabelShape::try_from(element)?);
+                }
+
+                "fields_autoplaced" => {
+                    fields_autoplaced = bool_arg(cdr)?;
+                }
+
                 "at" => {
                     position = Some(Position::try_from(element)?);
                 }
 
+                "effects" => {
+                    text_effects = Some(TextEffects::try_from(element)?);
+                }
+
                 "uuid" => {
-                    let (value, cdr) = cdr.expect_cons_with_any_symbol_head()?;
-                    cdr.expect_null()?;
-                    uuid = Some(Uuid::parse_str(value).map_err(|_| ParseError::InvalidUuid(value.to_string()))?);
+                    uuid = Some(uuid_arg(cdr)?);
+                }
+
+                "property" => {
+                    properties.push(SymbolProperty::try_from(element)?);
                 }
 
                 _ => return Err(ParseError::Unexpected(element.clone())),
             }
         }
 
+        let Some(shape) = shape else {
+            return Err(ParseError::missing_field("global_label", "shape", cons.clone()));
+        };
+
         let Some(position) = position else {
-            return Err(ParseError::missing_field("no_connect", "at", cons.clone()));
+            return Err(ParseError::missing_field("global_label", "at", cons.clone()));
+        };
+
+        let Some(text_effects) = text_effects else {
+            return Err(ParseError::missing_field("global_label", "effects", cons.clone()));
         };
 
         let Some(uuid) = uuid else {
-            return Err(ParseError::missing_field("no_connect", "uuid", cons.clone()));
+            return Err(ParseError::missing_field("global_label", "uuid", cons.clone()));
         };
 
-        Ok(Self { position, uuid })
+        Ok(Self {
+            text,
+            shape,
+            fields_autoplaced,
+            position,
+            text_effects,
+            uuid,
+            properties,
+        })
+    }
+}
+
+impl_try_from_cons_value!(SchematicGlobalLabel);
+impl_identified!(SchematicGlobalLabel);
+impl_positioned!(SchematicGlobalLabel);
+
+impl TryFrom<&Cons> for SchematicGlobalLabelShape {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let rest = cons.expect_cons_with_symbol_head("shape")?;
+        let (value, rest) = rest.expect_cons_with_any_symbol_head()?;
+        rest.expect_null()?;
+
+        match value {
+            "input" => Ok(Self::Input),
+            "output" => Ok(Self::Output),
+            "bidirectional" => Ok(Self::Bidirectional),
+            "tri_state" => Ok(Self::TriState),
+            "passive" => Ok(Self::Passive),
+            _ => Err(ParseError::Unexpected(Value::Cons(cons.clone()))),
+        }
+    }
+}
+
+impl_try_from_cons_value!(SchematicGlobalLabelShape);
+
+impl TryFrom<&Cons> for SchematicGraphicPolyline {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let (points, stroke, uuid) = parse_line(cons, "polyline")?;
+
+        Ok(Self {
+            points,
+            stroke,
+            uuid,
+        })
     }
 }
 
-impl_try_from_cons_value!(SchematicNoConnect);
\ No newline at end of file
+impl_try_from_cons_value!(SchematicGraphicPolyline);
+impl_identified!(SchematicGraphicPolyline);
+
+impl TryFrom<&Cons> for SchematicGraphicText {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let rest = cons.expect_cons_with_symbol_head("text")?;
+        let (text, mut rest) = rest.expect_cons_with_any_str_head()?;
+        let text = text.to_string();
+
+        let mut exclude_from_sim = false;
+        let mut position = None;
+        let mut text_effects = None;
+        let mut uuid = None;
+
+        while !rest.is_null() {
+            let r_cons = rest.expect_cons()?;
+            let element = r_cons.car();
+            rest = r_cons.cdr();
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "exclude_from_sim" => {
+                    exclude_from_sim = bool_arg(cdr)?;
+                }
+
+                "at" => {
+                    position = Some(Position::try_from(element)?);
+                }
+
+                "effects" => {
+                    text_effects = Some(TextEffects::try_from(element)?);
+                }
+
+                "uuid" => {
+                    uuid = Some(uuid_arg(cdr)?);
+                }
+
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        let Some(position) = position else {
+            return Err(ParseError::missing_field("text", "at", cons.clone()));
+        };
+
+        let Some(text_effects) = text_effects else {
+            return Err(ParseError::missing_field("text", "effects", cons.clone()));
+        };
+
+        let Some(uuid) = uuid else {
+            return Err(ParseError::missing_field("text", "uuid", cons.clone()));
+        };
+
+        Ok(Self {
+            text,
+            exclude_from_sim,
+            position,
+            text_effects,
+            uuid,
+        })
+    }
+}
+
+impl_try_from_cons_value!(SchematicGraphicText);
+impl_identified!(SchematicGraphicText);
+impl_positioned!(SchematicGraphicText);
+
+impl TryFrom<&Cons> for SchematicImage {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let mut position = None;
+        let mut scale = None;
+        let mut uuid = None;
+        let mut data = None;
+
+        let mut rest = cons.expect_cons_with_symbol_head("image")?;
+        while !rest.is_null() {
+            let r_cons = rest.expect_cons()?;
+            let element = r_cons.car();
+            rest = r_cons.cdr();
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "at" => {
+                    position = Some(Position::try_from(element)?);
+                }
+
+                "scale" => {
+                    let (value, cdr) = cdr.expect_cons_with_any_float_head()?;
+                    cdr.expect_null()?;
+                    scale = Some(value);
+                }
+
+                "uuid" => {
+                    uuid = Some(uuid_arg(cdr)?);
+                }
+
+                // KiCad splits the data into several strings, or writes one bar-delimited symbol.
+                "data" => {
+                    let mut text = String::new();
+                    for chunk in cdr.list_iter().into_iter().flatten() {
+                        let chunk = chunk.as_str().or_else(|| chunk.as_symbol());
+                        let chunk = chunk.ok_or_else(|| ParseError::Unexpected(element.clone()))?;
+                        text.extend(chunk.chars().filter(|c| !c.is_ascii_whitespace() && *c != '|'));
+                    }
+                    data = Some(text);
+                }
+
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        let Some(position) = position else {
+            return Err(ParseError::missing_field("image", "at", cons.clone()));
+        };
+
+        let Some(uuid) = uuid else {
+            return Err(ParseError::missing_field("image", "uuid", cons.clone()));
+        };
+
+        let Some(data) = data else {
+            return Err(ParseError::missing_field("image", "data", cons.clone()));
+        };
+
+        Ok(Self {
+            position,
+            scale,
+            uuid,
+            data,
+        })
+    }
+}
+
+impl_try_from_cons_value!(SchematicImage);
+impl_identified!(SchematicImage);
+impl_positioned!(SchematicImage);
+
+#[cfg(feature = "tools")]
+impl SchematicImage {
+    /// Decode the image file, which KiCad writes as a PNG.
+    pub fn decode(&self) -> Result<Vec<u8>, ParseError> {
+        use base64::{engine::general_purpose::STANDARD, Engine};
+
+        STANDARD.decode(&self.data).map_err(|e| ParseError::InvalidElement("image".to_string(), e.to_string()))
+    }
+}
+
+impl TryFrom<&Cons> for SchematicJunction {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let mut position = None;
+        let mut diameter = None;
+        let mut color = None;
+        let mut uuid = None;
+
+        let mut rest = cons.expect_cons_with_symbol_head("junction")?;
+        while !rest.is_null() {
+            let r_cons = rest.expect_cons()?;
+            let element = r_cons.car();
+            rest = r_cons.cdr();
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "at" => {
+                    position = Some(Position::try_from(element)?);
+                }
+
+                "diameter" => {
+                    let (value, cdr) = cdr.expect_cons_with_any_length_head()?;
+                    cdr.expect_null()?;
+                    diameter = Some(value.0 as u64);
+                }
+
+                "color" => {
+                    color = Some(Color::try_from(element)?);
+                }
+
+                "uuid" => {
+                    uuid = Some(uuid_arg(cdr)?);
+                }
+
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        let Some(position) = position else {
+            return Err(ParseError::missing_field("junction", "at", cons.clone()));
+        };
+
+        let Some(diameter) = diameter else {
+            return Err(ParseError::missing_field("junction", "diameter", cons.clone()));
+        };
+
+        let Some(color) = color else {
+            return Err(ParseError::missing_field("junction", "color", cons.clone()));
+        };
+
+        let Some(uuid) = uuid else {
+            return Err(ParseError::missing_field("junction", "uuid", cons.clone()));
+        };
+
+        Ok(Self {
+            position,
+            diameter,
+            color,
+            uuid,
+        })
+    }
+}
+
+impl_try_from_cons_value!(SchematicJunction);
+impl_identified!(SchematicJunction);
+impl_positioned!(SchematicJunction);
+
+impl TryFrom<&Cons> for SchematicNoConnect {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let mut position = None;
+        let mut uuid = None;
+
+        let mut rest = cons.expect_cons_with_symbol_head("no_connect")?;
+        while !rest.is_null() {
+            let r_cons = rest.expect_cons()?;
+            let element = r_cons.car();
+            rest = r_cons.cdr();
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "at" => {
+                    position = Some(Position::try_from(element)?);
+                }
+
+                "uuid" => {
+                    uuid = Some(uuid_arg(cdr)?);
+                }
+
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        let Some(position) = position else {
+            return Err(ParseError::missing_field("no_connect", "at", cons.clone()));
+        };
+
+        let Some(uuid) = uuid else {
+            return Err(ParseError::missing_field("no_connect", "uuid", cons.clone()));
+        };
+
+        Ok(Self { position, uuid })
+    }
+}
+
+impl_try_from_cons_value!(SchematicNoConnect);
+impl_identified!(SchematicNoConnect);
+impl_positioned!(SchematicNoConnect);
+
+impl TryFrom<&Cons> for SchematicLabel {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let rest = cons.expect_cons_with_symbol_head("label")?;
+        let (text, mut rest) = rest.expect_cons_with_any_str_head()?;
+        let text = text.to_string();
+
+        let mut fields_autoplaced = false;
+        let mut position = None;
+        let mut text_effects = None;
+        let mut uuid = None;
+
+        while !rest.is_null() {
+            let r_cons = rest.expect_cons()?;
+            let element = r_cons.car();
+            rest = r_cons.cdr();
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "fields_autoplaced" => {
+                    fields_autoplaced = bool_arg(cdr)?;
+                }
+
+                "at" => {
+                    position = Some(Position::try_from(element)?);
+                }
+
+                "effects" => {
+                    text_effects = Some(TextEffects::try_from(element)?);
+                }
+
+                "uuid" => {
+                    uuid = Some(uuid_arg(cdr)?);
+                }
+
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        let Some(position) = position else {
+            return Err(ParseError::missing_field("label", "at", cons.clone()));
+        };
+
+        let Some(text_effects) = text_effects else {
+            return Err(ParseError::missing_field("label", "effects", cons.clone()));
+        };
+
+        let Some(uuid) = uuid else {
+            return Err(ParseError::missing_field("label", "uuid", cons.clone()));
+        };
+
+        Ok(Self {
+            text,
+            fields_autoplaced,
+            position,
+            text_effects,
+            uuid,
+        })
+    }
+}
+
+impl_try_from_cons_value!(SchematicLabel);
+impl_identified!(SchematicLabel);
+impl_positioned!(SchematicLabel);
+
+impl SchematicSheet {
+    /// Return the name of the sheet, from its `Sheetname` property.
+    pub fn name(&self) -> Option<&str> {
+        self.property("Sheetname")
+    }
+
+    /// Return the schematic file of the sheet, relative to this schematic, from its `Sheetfile`
+    /// property.
+    pub fn file(&self) -> Option<&str> {
+        self.property("Sheetfile")
+    }
+
+    /// Return the value of the property with the given name.
+    pub fn property(&self, key: &str) -> Option<&str> {
+        self.properties.iter().find(|property| property.key == key).map(|property| property.value.as_str())
+    }
+}
+
+impl TryFrom<&Cons> for SchematicSheet {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let mut position = None;
+        let mut size = None;
+        let mut exclude_from_sim = None;
+        let mut in_bom = None;
+        let mut on_board = None;
+        let mut dnp = None;
+        let mut fields_autoplaced = false;
+        let mut stroke = None;
+        let mut fill = None;
+        let mut uuid = None;
+        let mut properties = Vec::new();
+        let mut pins = Vec::new();
+        let mut instances = Vec::new();
+
+        let mut rest = cons.expect_cons_with_symbol_head("sheet")?;
+        while !rest.is_null() {
+            let r_cons = rest.expect_cons()?;
+            let element = r_cons.car();
+            rest = r_cons.cdr();
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "at" => {
+                    position = Some(Position::try_from(element)?);
+                }
+
+                // Unlike font sizes, sheet sizes are written width first.
+                "size" => {
+                    let (width, cdr) = cdr.expect_cons_with_any_length_head()?;
+                    let (height, cdr) = cdr.expect_cons_with_any_length_head()?;
+                    cdr.expect_null()?;
+                    size = Some(Size::try_new(width, height)?);
+                }
+
+                "exclude_from_sim" | "in_bom" | "on_board" | "dnp" => {
+                    let value = Some(bool_arg(cdr)?);
+                    match key {
+                        "exclude_from_sim" => exclude_from_sim = value,
+                        "in_bom" => in_bom = value,
+                        "on_board" => on_board = value,
+                        "dnp" => dnp = value,
+                        _ => unreachable!(),
+                    }
+                }
+
+                "fields_autoplaced" => {
+                    fields_autoplaced = bool_arg(cdr)?;
+                }
+
+                "stroke" => {
+                    stroke = Some(Stroke::try_from(element)?);
+                }
+
+                "fill" => {
+                    fill = Some(Fill::try_from(element)?);
+                }
+
+                "uuid" => {
+                    uuid = Some(uuid_arg(cdr)?);
+                }
+
+                "property" => {
+                    properties.push(SymbolProperty::try_from(element)?);
+                }
+
+                "pin" => {
+                    pins.push(SheetPin::try_from(element)?);
+                }
+
+                "instances" => {
+                    for project in cdr.list_iter().into_iter().flatten() {
+                        instances.push(SchematicSheetProject::try_from(project)?);
+                    }
+                }
+
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        let Some(position) = position else {
+            return Err(ParseError::missing_field("sheet", "at", cons.clone()));
+        };
+
+        let Some(size) = size else {
+            return Err(ParseError::missing_field("sheet", "size", cons.clone()));
+        };
+
+        let Some(stroke) = stroke else {
+            return Err(ParseError::missing_field("sheet", "stroke", cons.clone()));
+        };
+
+        let Some(fill) = fill else {
+            return Err(ParseError::missing_field("sheet", "fill", cons.clone()));
+        };
+
+        let Some(uuid) = uuid else {
+            return Err(ParseError::missing_field("sheet", "uuid", cons.clone()));
+        };
+
+        Ok(Self {
+            position,
+            size,
+            exclude_from_sim,
+            in_bom,
+            on_board,
+            dnp,
+            fields_autoplaced,
+            stroke,
+            fill,
+            uuid,
+            properties,
+            pins,
+            instances,
+        })
+    }
+}
+
+impl_try_from_cons_value!(SchematicSheet);
+impl_identified!(SchematicSheet);
+impl_positioned!(SchematicSheet);
+
+impl TryFrom<&Cons> for SchematicSheetPath {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let rest = cons.expect_cons_with_symbol_head("path")?;
+        let (path, mut rest) = rest.expect_cons_with_any_str_head()?;
+        let path = path.to_string();
+
+        let mut page = None;
+
+        while !rest.is_null() {
+            let r_cons = rest.expect_cons()?;
+            let element = r_cons.car();
+            rest = r_cons.cdr();
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "page" => {
+                    page = Some(text_arg(cdr)?.to_string());
+                }
+
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        let Some(page) = page else {
+            return Err(ParseError::missing_field("path", "page", cons.clone()));
+        };
+
+        Ok(Self { path, page })
+    }
+}
+
+impl_try_from_cons_value!(SchematicSheetPath);
+
+impl TryFrom<&Cons> for SchematicSheetProject {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let rest = cons.expect_cons_with_symbol_head("project")?;
+        let (name, rest) = rest.expect_cons_with_any_str_head()?;
+        let name = name.to_string();
+        let paths =
+            rest.list_iter().into_iter().flatten().map(SchematicSheetPath::try_from).collect::<Result<_, _>>()?;
+
+        Ok(Self { name, paths })
+    }
+}
+
+impl_try_from_cons_value!(SchematicSheetProject);
+
+impl TryFrom<&Cons> for SheetPin {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let rest = cons.expect_cons_with_symbol_head("pin")?;
+        let (name, rest) = rest.expect_cons_with_any_str_head()?;
+        let name = name.to_string();
+        let (shape, mut rest) = rest.expect_cons_with_any_symbol_head()?;
+        let shape = match shape {
+            "input" => SchematicGlobalLabelShape::Input,
+            "output" => SchematicGlobalLabelShape::Output,
+            "bidirectional" => SchematicGlobalLabelShape::Bidirectional,
+            "tri_state" => SchematicGlobalLabelShape::TriState,
+            "passive" => SchematicGlobalLabelShape::Passive,
+            _ => return Err(ParseError::Unexpected(Value::Cons(cons.clone()))),
+        };
+
+        let mut position = None;
+        let mut text_effects = None;
+        let mut uuid = None;
+
+        while !rest.is_null() {
+            let r_cons = rest.expect_cons()?;
+            let element = r_cons.car();
+            rest = r_cons.cdr();
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "at" => {
+                    position = Some(Position::try_from(element)?);
+                }
+
+                "effects" => {
+                    text_effects = Some(TextEffects::try_from(element)?);
+                }
+
+                "uuid" => {
+                    uuid = Some(uuid_arg(cdr)?);
+                }
+
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        let Some(position) = position else {
+            return Err(ParseError::missing_field("pin", "at", cons.clone()));
+        };
+
+        let Some(text_effects) = text_effects else {
+            return Err(ParseError::missing_field("pin", "effects", cons.clone()));
+        };
+
+        let Some(uuid) = uuid else {
+            return Err(ParseError::missing_field("pin", "uuid", cons.clone()));
+        };
+
+        Ok(Self {
+            name,
+            shape,
+            position,
+            text_effects,
+            uuid,
+        })
+    }
+}
+
+impl_try_from_cons_value!(SheetPin);
+impl_identified!(SheetPin);
+impl_positioned!(SheetPin);
+
+impl SchematicSymbolInstance {
+    /// Return the value of the property with the given name, such as `Reference` or `Value`.
+    pub fn property(&self, key: &str) -> Option<&str> {
+        self.properties.iter().find(|property| property.key == key).map(|property| property.value.as_str())
+    }
+}
+
+impl TryFrom<&Cons> for SchematicSymbolInstance {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let mut lib_id = None;
+        let mut lib_name = None;
+        let mut position = None;
+        let mut mirror = None;
+        let mut unit = 1;
+        let mut exclude_from_sim = None;
+        let mut in_bom = None;
+        let mut on_board = None;
+        let mut dnp = None;
+        let mut fields_autoplaced = false;
+        let mut uuid = None;
+        let mut properties = Vec::new();
+        let mut pins = Vec::new();
+        let mut instances = Vec::new();
+
+        let mut rest = cons.expect_cons_with_symbol_head("symbol")?;
+        while !rest.is_null() {
+            let r_cons = rest.expect_cons()?;
+            let element = r_cons.car();
+            rest = r_cons.cdr();
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "lib_id" => {
+                    lib_id = Some(text_arg(cdr)?.to_string());
+                }
+
+                "lib_name" => {
+                    lib_name = Some(text_arg(cdr)?.to_string());
+                }
+
+                "at" => {
+                    position = Some(Position::try_from(element)?);
+                }
+
+                "mirror" => {
+                    mirror = Some(match text_arg(cdr)? {
+                        "x" => SchematicSymbolMirror::X,
+                        "y" => SchematicSymbolMirror::Y,
+                        _ => return Err(ParseError::Unexpected(element.clone())),
+                    });
+                }
+
+                "unit" => {
+                    let (value, cdr) = cdr.expect_cons_with_any_int_head()?;
+                    cdr.expect_null()?;
+                    unit = value;
+                }
+
+                "exclude_from_sim" | "in_bom" | "on_board" | "dnp" => {
+                    let value = Some(bool_arg(cdr)?);
+                    match key {
+                        "exclude_from_sim" => exclude_from_sim = value,
+                        "in_bom" => in_bom = value,
+                        "on_board" => on_board = value,
+                        "dnp" => dnp = value,
+                        _ => unreachable!(),
+                    }
+                }
+
+                "fields_autoplaced" => {
+                    fields_autoplaced = bool_arg(cdr)?;
+                }
+
+                "uuid" => {
+                    uuid = Some(uuid_arg(cdr)?);
+                }
+
+                "property" => {
+                    properties.push(SymbolProperty::try_from(element)?);
+                }
+
+                "pin" => {
+                    pins.push(SchematicSymbolPin::try_from(element)?);
+                }
+
+                "instances" => {
+                    for project in cdr.list_iter().into_iter().flatten() {
+                        instances.push(SchematicSymbolProject::try_from(project)?);
+                    }
+                }
+
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        let Some(lib_id) = lib_id else {
+            return Err(ParseError::missing_field("symbol", "lib_id", cons.clone()));
+        };
+
+        let Some(position) = position else {
+            return Err(ParseError::missing_field("symbol", "at", cons.clone()));
+        };
+
+        let Some(uuid) = uuid else {
+            return Err(ParseError::missing_field("symbol", "uuid", cons.clone()));
+        };
+
+        Ok(Self {
+            lib_id,
+            lib_name,
+            position,
+            mirror,
+            unit,
+            exclude_from_sim,
+            in_bom,
+            on_board,
+            dnp,
+            fields_autoplaced,
+            uuid,
+            properties,
+            pins,
+            instances,
+        })
+    }
+}
+
+impl_try_from_cons_value!(SchematicSymbolInstance);
+impl_identified!(SchematicSymbolInstance);
+impl_positioned!(SchematicSymbolInstance);
+
+impl TryFrom<&Cons> for SchematicSymbolPin {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let rest = cons.expect_cons_with_symbol_head("pin")?;
+        let (number, mut rest) = rest.expect_cons_with_any_str_head()?;
+        let number = number.to_string();
+
+        let mut uuid = None;
+        let mut alternate = None;
+
+        while !rest.is_null() {
+            let r_cons = rest.expect_cons()?;
+            let element = r_cons.car();
+            rest = r_cons.cdr();
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "uuid" => {
+                    uuid = Some(uuid_arg(cdr)?);
+                }
+
+                "alternate" => {
+                    alternate = Some(text_arg(cdr)?.to_string());
+                }
+
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        let Some(uuid) = uuid else {
+            return Err(ParseError::missing_field("pin", "uuid", cons.clone()));
+        };
+
+        Ok(Self {
+            number,
+            uuid,
+            alternate,
+        })
+    }
+}
+
+impl_try_from_cons_value!(SchematicSymbolPin);
+impl_identified!(SchematicSymbolPin);
+
+impl TryFrom<&Cons> for SchematicSymbolProject {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let rest = cons.expect_cons_with_symbol_head("project")?;
+        let (name, rest) = rest.expect_cons_with_any_str_head()?;
+        let name = name.to_string();
+        let paths =
+            rest.list_iter().into_iter().flatten().map(SchematicSymbolPath::try_from).collect::<Result<_, _>>()?;
+
+        Ok(Self { name, paths })
+    }
+}
+
+impl_try_from_cons_value!(SchematicSymbolProject);
+
+impl TryFrom<&Cons> for SchematicSymbolPath {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let rest = cons.expect_cons_with_symbol_head("path")?;
+        let (path, mut rest) = rest.expect_cons_with_any_str_head()?;
+        let path = path.to_string();
+
+        let mut reference = None;
+        let mut unit = None;
+
+        while !rest.is_null() {
+            let r_cons = rest.expect_cons()?;
+            let element = r_cons.car();
+            rest = r_cons.cdr();
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "reference" => {
+                    reference = Some(text_arg(cdr)?.to_string());
+                }
+
+                "unit" => {
+                    let (value, cdr) = cdr.expect_cons_with_any_int_head()?;
+                    cdr.expect_null()?;
+                    unit = Some(value);
+                }
+
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        let Some(reference) = reference else {
+            return Err(ParseError::missing_field("path", "reference", cons.clone()));
+        };
+
+        let Some(unit) = unit else {
+            return Err(ParseError::missing_field("path", "unit", cons.clone()));
+        };
+
+        Ok(Self {
+            path,
+            reference,
+            unit,
+        })
+    }
+}
+
+impl_try_from_cons_value!(SchematicSymbolPath);
+
+impl TryFrom<&Cons> for SchematicWire {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let (points, stroke, uuid) = parse_line(cons, "wire")?;
+
+        Ok(Self {
+            points,
+            stroke,
+            uuid,
+        })
+    }
+}
+
+impl_try_from_cons_value!(SchematicWire);
+impl_identified!(SchematicWire);
+
+/// Parse the points, stroke, and UUID of a wire, bus, or polyline.
+fn parse_line(cons: &Cons, head: &str) -> Result<(Points, Stroke, Uuid), ParseError> {
+    let mut points = None;
+    let mut stroke = None;
+    let mut uuid = None;
+
+    let mut rest = cons.expect_cons_with_symbol_head(head)?;
+    while !rest.is_null() {
+        let r_cons = rest.expect_cons()?;
+        let element = r_cons.car();
+        rest = r_cons.cdr();
+        let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+        match key {
+            "pts" => {
+                points = Some(Points::try_from(element)?);
+            }
+
+            "stroke" => {
+                stroke = Some(Stroke::try_from(element)?);
+            }
+
+            "uuid" => {
+                uuid = Some(uuid_arg(cdr)?);
+            }
+
+            _ => return Err(ParseError::Unexpected(element.clone())),
+        }
+    }
+
+    let Some(points) = points else {
+        return Err(ParseError::missing_field(head, "pts", cons.clone()));
+    };
+
+    let Some(stroke) = stroke else {
+        return Err(ParseError::missing_field(head, "stroke", cons.clone()));
+    };
+
+    let Some(uuid) = uuid else {
+        return Err(ParseError::missing_field(head, "uuid", cons.clone()));
+    };
+
+    Ok((points, stroke, uuid))
+}
+
+/// Return the single argument of an element as a boolean. Before KiCad 8, flags are written
+/// without an argument and are true when present.
+fn bool_arg(args: &Value) -> Result<bool, ParseError> {
+    if args.is_null() {
+        return Ok(true);
+    }
+
+    let value = args.expect_cons()?;
+    value.cdr().expect_null()?;
+    value.car().expect_bool()
+}
+
+/// Return the single argument of an element as text. KiCad writes these as strings, or before
+/// KiCad 8 as bare symbols.
+fn text_arg(args: &Value) -> Result<&str, ParseError> {
+    let value = args.expect_cons()?;
+    value.cdr().expect_null()?;
+    value.car().as_str().or_else(|| value.car().as_symbol()).ok_or_else(|| ParseError::Unexpected(value.car().clone()))
+}
+
+/// Return the single argument of an element as a UUID.
+fn uuid_arg(args: &Value) -> Result<Uuid, ParseError> {
+    let value = text_arg(args)?;
+    Uuid::parse_str(value).map_err(|_| ParseError::InvalidUuid(value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{common::Nm, read_str},
+        lexpr::sexp,
+    };
+
+    #[test]
+    fn test_schematic_fixtures() {
+        // Kinds of element that Schematic does not model, which are kept in raw_sections.
+        const UNMODELED: &[&str] =
+            &["arc", "bezier", "circle", "hierarchical_label", "netclass_flag", "rectangle", "table", "text_box"];
+
+        let fixtures = [
+            ("HDMI Breakout.kicad_sch", include_str!("../tests/HDMI Breakout.kicad_sch")),
+            ("KiCadSchemaSample.kicad_sch", include_str!("../tests/KiCadSchemaSample.kicad_sch")),
+            ("ports.kicad_sch", include_str!("../tests/ports.kicad_sch")),
+        ];
+
+        for (name, text) in fixtures {
+            let value = read_str(text).unwrap();
+            let schematic = Schematic::parse(value.expect_cons().unwrap(), &ParseOptions::default())
+                .unwrap_or_else(|e| panic!("{name}: {e}"));
+            assert_eq!(schematic.generator, "eeschema", "{name}");
+            assert!(schematic.paper.is_some(), "{name}");
+            for element in &schematic.raw_sections {
+                let head = element.as_cons().and_then(|cons| cons.car().as_symbol());
+                assert!(head.is_some_and(|head| UNMODELED.contains(&head)), "{name}: {element}");
+            }
+            assert!(schematic.symbols.iter().all(|symbol| symbol.property("Reference").is_some()), "{name}");
+
+            let lib_symbols = schematic.lib_symbols.get().unwrap_or_else(|e| panic!("{name}: {e}"));
+            assert!(lib_symbols.iter().all(|symbol| !symbol.units.is_empty()), "{name}");
+        }
+
+        let schematic = sexp!((kicad_sch (version 20231120) (generator "eeschema") (paper "A4")
+            (bus_entry (at 49.53 96.52) (size -2.54 2.54) (stroke (width 0) (type default))
+                (uuid "e263b932-c702-421c-a015-de518b1ee54d"))
+            (bus (pts (xy 49.53 96.52) (xy 49.53 111.76)) (stroke (width 0) (type default))
+                (uuid "70c61c93-461b-4c4f-b27f-ea416ca6dd6a"))
+            (global_label "CLK" (shape tri_state) (at 52.07 138.43 180) (fields_autoplaced yes)
+                (effects (font (size 1.27 1.27)) (justify right))
+                (uuid "c290e417-b246-4481-96d9-22242c5e4431")
+                (property "Intersheetrefs" "${INTERSHEET_REFS}" (at 32.885 138.43 0)
+                    (effects (font (size 1.27 1.27)) (justify right) (hide yes))))
+            (text "Note" (exclude_from_sim no) (at 151.13 152.908 90) (effects (font (size 2 2)))
+                (uuid "ca81037f-ceb2-46ec-819c-e386c1f2833e"))
+            (symbol (lib_id "Device:R") (at 10.16 20.32 90) (mirror y) (unit 1) (in_bom yes) (on_board yes) (dnp no)
+                (uuid "0c6b985a-999e-45ae-b1a7-41cfbe8ba8fa")
+                (property "Reference" "R1" (at 12.7 20.32 0) (show_name yes) (effects (font (size 1.27 1.27))))
+                (property "Value" "10k" (at 15.24 20.32 0) (effects (font (size 1.27 1.27))))
+                (pin "1" (uuid "c9db87a8-b50c-4611-9342-9c97de52e1bc"))
+                (pin "2" (uuid "78c0e21c-b98d-42e1-a0e2-fc8f447d3922"))
+                (instances (project "demo" (path "/790646b3-3470-4d14-ac83-7abeaa704d04" (reference "R1") (unit 1)))))
+            (sheet_instances (path "/" (page "1")))));
+
+        let schematic = Schematic::try_from(&schematic).unwrap();
+        assert_eq!(schematic.bus_entries[0].size.x, Nm(-2_540_000));
+        assert_eq!(schematic.buses[0].points.points.len(), 2);
+        assert!(matches!(schematic.global_labels[0].shape, SchematicGlobalLabelShape::TriState));
+        assert!(schematic.global_labels[0].properties[0].text_effects.as_ref().unwrap().hide);
+        assert_eq!(schematic.texts[0].text, "Note");
+        assert_eq!(schematic.sheet_instances[0].path, "/");
+        assert!(schematic.raw_sections.is_empty());
+
+        let symbol = &schematic.symbols[0];
+        assert_eq!(symbol.lib_id, "Device:R");
+        assert_eq!(symbol.mirror, Some(SchematicSymbolMirror::Y));
+        assert_eq!(symbol.property("Value"), Some("10k"));
+        assert!(symbol.properties[0].show_name);
+        assert_eq!(symbol.pins.len(), 2);
+        assert_eq!(symbol.instances[0].paths[0].reference, "R1");
+
+        let mut schematic = schematic;
+        let mut uuids = Vec::new();
+        schematic.for_each_identified(|element| uuids.push(element.uuid()));
+        assert_eq!(uuids.len(), 8);
+        assert_eq!(uuids[0], None);
+        assert_eq!(uuids[7], Some(Uuid::parse_str("78c0e21c-b98d-42e1-a0e2-fc8f447d3922").unwrap()));
+
+        schematic.for_each_identified_mut(|element| element.set_uuid(Uuid::nil()));
+        assert_eq!(schematic.uuid, Some(Uuid::nil()));
+        assert_eq!(schematic.symbols[0].pins[1].uuid, Uuid::nil());
+
+        schematic.for_each_positioned_mut(|element| element.translate(Nm(1_000_000), Nm::ZERO));
+        assert_eq!(schematic.bus_entries[0].position.x, Nm(50_530_000));
+        assert_eq!(schematic.symbols[0].properties[1].position().map(|at| at.x), Some(Nm(16_240_000)));
+        assert_eq!(schematic.symbols[0].angle().degrees(), 90.0);
+
+        let value = read_str(include_str!("../tests/HDMI Breakout.kicad_sch")).unwrap();
+        let schematic = Schematic::parse(value.expect_cons().unwrap(), &ParseOptions::default()).unwrap();
+        assert_eq!(schematic.sheets.len(), 2);
+        assert_eq!(schematic.sheet_instances[0].page, "1");
+        let sheet = &schematic.sheets[1];
+        assert_eq!((sheet.name(), sheet.file()), (Some("ESP32"), Some("esp32.kicad_sch")));
+        assert_eq!((sheet.size.width, sheet.size.height), (Nm(34_290_000), Nm(85_090_000)));
+        assert!(sheet.fill.color.is_some());
+        assert_eq!(sheet.pins[0].name, "SCK_CTL");
+        assert!(matches!(sheet.pins[0].shape, SchematicGlobalLabelShape::Output));
+        assert_eq!(sheet.instances[0].paths[0].page, "2");
+    }
+
+    #[test]
+    fn test_lenient_parse() {
+        let schematic = sexp!((kicad_sch (version 20231120) (generator "eeschema")
+            (junction (bogus))
+            (wire (pts (xy 0 0) (xy 2.54 0)) (stroke (width 0) (type default))
+                (uuid "70c61c93-461b-4c4f-b27f-ea416ca6dd6a"))));
+        let cons = schematic.expect_cons().unwrap();
+
+        assert!(Schematic::parse(cons, &ParseOptions::default()).is_err());
+
+        let options = ParseOptions::default().strict(false);
+        let (schematic, warnings) = Schematic::parse_with_warnings(cons, &options).unwrap();
+        assert_eq!(schematic.wires.len(), 1);
+        assert!(schematic.junctions.is_empty());
+        assert_eq!(schematic.raw_sections.len(), 1);
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(warnings[0].error.root_cause(), ParseError::Unexpected(_)));
+        assert!(warnings[0].to_string().starts_with("Skipped junction: "));
+
+        // The header is never skipped.
+        let unreadable = sexp!((kicad_sch (version "20231120")));
+        assert!(Schematic::parse_with_warnings(unreadable.expect_cons().unwrap(), &options).is_err());
+    }
+
+    #[test]
+    fn test_bus_entry() {
+        let entry = sexp!((bus_entry (at 49.53 96.52) (size 2.54 -2.54) (stroke (width 0) (type default))
+            (uuid "e263b932-c702-421c-a015-de518b1ee54d")));
+        let entry = SchematicBusEntry::try_from(&entry).unwrap();
+        assert_eq!((entry.size.x, entry.size.y), (Nm(2_540_000), Nm(-2_540_000)));
+        assert_eq!(entry.position.x, Nm(49_530_000));
+
+        let missing_size = sexp!((bus_entry (at 49.53 96.52) (stroke (width 0) (type default))
+            (uuid "e263b932-c702-421c-a015-de518b1ee54d")));
+        let err = SchematicBusEntry::try_from(&missing_size).unwrap_err();
+        assert!(matches!(err.root_cause(), ParseError::MissingField(_, field, _) if field == "size"));
+
+        let unknown = sexp!((bus_entry (at 49.53 96.52) (size 2.54 2.54) (length 1)));
+        let err = SchematicBusEntry::try_from(&unknown).unwrap_err();
+        assert!(matches!(err.root_cause(), ParseError::Unexpected(_)));
+    }
+
+    #[test]
+    fn test_image() {
+        let schematic = sexp!((kicad_sch (version 20231120) (generator "eeschema")
+            (image (at 63.5 38.1) (scale 0.5) (uuid "5b8a4c1e-2f3d-4e6a-9b7c-8d9e0f1a2b3c")
+                (data "iVBORw0K" "Ggo="))));
+        let schematic = Schematic::try_from(&schematic).unwrap();
+        assert!(schematic.raw_sections.is_empty());
+
+        let image = &schematic.images[0];
+        assert_eq!((image.position.x, image.scale), (Nm(63_500_000), Some(0.5)));
+        assert_eq!(image.data, "iVBORw0KGgo=");
+
+        #[cfg(feature = "tools")]
+        assert_eq!(image.decode().unwrap(), b"\x89PNG\r\n\x1a\n");
+
+        let missing_data = sexp!((image (at 0 0) (uuid "5b8a4c1e-2f3d-4e6a-9b7c-8d9e0f1a2b3c")));
+        let err = SchematicImage::try_from(&missing_data).unwrap_err();
+        assert!(matches!(err.root_cause(), ParseError::MissingField(_, field, _) if field == "data"));
+    }
+}
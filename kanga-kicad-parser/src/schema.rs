@@ -0,0 +1,44 @@
+use {
+    crate::{common::Symbol, pcb::Pcb, sch::Schematic, symlib::SymbolLibrary},
+    schemars::{schema_for, JsonSchema, Schema},
+};
+
+/// Return the JSON Schema for the JSON representation of a model type.
+pub fn schema<T>() -> Schema
+where
+    T: JsonSchema,
+{
+    schema_for!(T)
+}
+
+/// Return the JSON Schema for a [`Pcb`].
+pub fn pcb_schema() -> Schema {
+    schema::<Pcb>()
+}
+
+/// Return the JSON Schema for a [`Schematic`].
+pub fn schematic_schema() -> Schema {
+    schema::<Schematic>()
+}
+
+/// Return the JSON Schema for a library [`Symbol`].
+pub fn symbol_schema() -> Schema {
+    schema::<Symbol>()
+}
+
+/// Return the JSON Schema for a [`SymbolLibrary`].
+pub fn symbol_library_schema() -> Schema {
+    schema::<SymbolLibrary>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schematic_schema() {
+        let schema = serde_json::to_value(schematic_schema()).unwrap();
+        assert_eq!(schema["title"], "kicad_sch");
+        assert_eq!(schema["additionalProperties"], false);
+    }
+}
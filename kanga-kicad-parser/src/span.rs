@@ -0,0 +1,492 @@
+use {
+    crate::{
+        error::SyntaxError,
+        provenance::{self, Provenance},
+        Error, ParseError,
+    },
+    lexpr::{Cons, Value},
+    std::{
+        borrow::Cow,
+        fmt::{Display, Formatter, Result as FmtResult},
+    },
+};
+
+/// A position in the text of a document.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SourceLocation {
+    /// The one-based line number.
+    pub line: usize,
+
+    /// The one-based column number, in characters.
+    pub column: usize,
+
+    /// The byte offset from the start of the text.
+    pub offset: usize,
+}
+
+/// Identifies the list element an error occurred in, so that [`ParseError::locate`] can find it in
+/// the value being converted. An identifier is only meaningful while that value is alive.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ElementId(usize);
+
+/// A list or atom in the text of a document, as found by [`scan`].
+#[derive(Debug)]
+pub(crate) struct Node {
+    /// The byte range of the node's text.
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+
+    /// The node's elements, if it is a list.
+    pub(crate) children: Option<Vec<Node>>,
+}
+
+impl SourceLocation {
+    /// Return the location of a byte offset in `text`. Offsets past the end of the text are
+    /// clamped to the end.
+    pub fn from_offset(text: &str, offset: usize) -> Self {
+        let mut offset = offset.min(text.len());
+        while !text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+
+        let before = &text[..offset];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            offset,
+        }
+    }
+
+    /// Return the location of a one-based line and column in `text`.
+    pub fn from_line_column(text: &str, line: usize, column: usize) -> Self {
+        let line_start = match line {
+            0 | 1 => 0,
+            _ => text.match_indices('\n').nth(line - 2).map(|(i, _)| i + 1).unwrap_or(text.len()),
+        };
+
+        let offset = text[line_start..]
+            .char_indices()
+            .nth(column.saturating_sub(1))
+            .map(|(i, _)| line_start + i)
+            .unwrap_or(text.len());
+
+        Self {
+            line: line.max(1),
+            column: column.max(1),
+            offset,
+        }
+    }
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+impl ElementId {
+    /// Identify a list by its address.
+    pub(crate) fn of(cons: &Cons) -> Self {
+        Self(cons as *const Cons as usize)
+    }
+}
+
+impl ParseError {
+    /// Record that this error occurred at the given location in the source text.
+    pub fn at(self, location: SourceLocation) -> Self {
+        Self::At(location, Box::new(self))
+    }
+
+    /// Return the location in the source text at which this error occurred, if known.
+    pub fn location(&self) -> Option<SourceLocation> {
+        match self {
+            Self::At(location, _) => Some(*location),
+            Self::InElement(_, inner) | Self::Within(_, inner) => inner.location(),
+            _ => None,
+        }
+    }
+
+    /// Find where this error occurred in `text`, the text `root` was read from, and record it.
+    ///
+    /// The error identifies the elements it occurred within, and the positions of those elements
+    /// were recorded in `provenance` when the text was read. The innermost element that is part of
+    /// `root` is looked up there, along with the value the error complains about if it is a list
+    /// within that element. Errors that already have a location are returned unchanged.
+    pub fn locate(self, root: &Value, provenance: &Provenance, text: &str) -> Self {
+        if self.location().is_some() {
+            return self;
+        }
+
+        let mut elements = Vec::new();
+        let mut error = &self;
+        loop {
+            match error {
+                Self::InElement(id, inner) => {
+                    elements.push(*id);
+                    error = inner;
+                }
+                Self::At(_, inner) | Self::Within(_, inner) => error = inner,
+                _ => break,
+            }
+        }
+
+        let Some(mut path) = elements.iter().rev().find_map(|id| element_path(root, *id)) else {
+            return self;
+        };
+
+        if let Some(offending) = offending_value(self.root_cause()).filter(|value| value.is_cons()) {
+            let element = provenance::element(root, &path);
+            if let Some(subpath) = element.and_then(|element| value_path(element, offending)) {
+                path.extend(subpath);
+            }
+        }
+
+        match provenance.location(&path, text) {
+            Some(location) => self.at(location),
+            None => self,
+        }
+    }
+}
+
+/// Read a document from text and convert it into a model, recording where in the text any error
+/// occurred.
+pub fn parse_str<T>(text: &str) -> Result<T, Error>
+where
+    T: for<'a> TryFrom<&'a Value, Error = ParseError>,
+{
+    let (value, provenance) = read_tracked(text)?;
+    T::try_from(&value).map_err(|e| e.locate(&value, &provenance, text).into())
+}
+
+/// Read the s-expressions of a document as [`read_str`] does, recording the position of each list
+/// for [`ParseError::locate`].
+pub(crate) fn read_tracked(text: &str) -> Result<(Value, Provenance), Error> {
+    let value = read_str(text)?;
+    Ok((value, Provenance::from_text(text, None)))
+}
+
+/// Read the s-expressions of a document, recording where in the text any syntax error occurred.
+/// See [`lexpr_text`] for the KiCad syntax this accepts beyond what lexpr reads.
+///
+/// Numbers with a fractional part are kept as written, as symbols holding the text of a
+/// [`Decimal`](crate::common::Decimal), so that they convert to nanometers exactly and are written back with the same
+/// digits. Integers are read as numbers.
+pub fn read_str(text: &str) -> Result<Value, Error> {
+    let (rewritten, inserted, decimals) = rewrite(text);
+    let mut value = lexpr::from_str(&rewritten).map_err(|e| {
+        let message = e.to_string();
+        let Some(location) = e.location() else {
+            return SyntaxError::new(message);
+        };
+
+        // lexpr reports the position in the rewritten text; map it back to the original.
+        let suffix = format!(" at line {} column {}", location.line(), location.column());
+        let offset = SourceLocation::from_line_column(&rewritten, location.line(), location.column()).offset;
+        let shift = inserted.iter().take_while(|&&inserted| inserted < offset).count();
+        SyntaxError::new(message.strip_suffix(&suffix).unwrap_or(&message))
+            .at(SourceLocation::from_offset(text, offset - shift))
+    })?;
+
+    keep_decimals(&mut value, &mut decimals.into_iter());
+    Ok(value)
+}
+
+/// Rewrite the parts of a document that KiCad writes but lexpr cannot read, so that the text can
+/// be passed to [`lexpr::from_str`]. Text that needs no rewriting is returned as is.
+///
+/// Embedded file data delimited by bars, `(data |...|)`, becomes a string. Atoms that begin with
+/// a digit but are not numbers, such as the unquoted UUIDs of KiCad 9 and the hexadecimal
+/// timestamps of KiCad 5, are quoted. Numbers and quoted strings are unchanged.
+pub fn lexpr_text(text: &str) -> Cow<'_, str> {
+    rewrite(text).0
+}
+
+/// Rewrite a document as described by [`lexpr_text`], also returning the offsets in the
+/// rewritten text of the characters inserted, in order, and the text of each decimal number, in
+/// order.
+fn rewrite(text: &str) -> (Cow<'_, str>, Vec<usize>, Vec<&str>) {
+    let bytes = text.as_bytes();
+    let is_delimiter = |b: u8| b.is_ascii_whitespace() || b"()\";".contains(&b);
+
+    let mut rewritten: Option<String> = None;
+    let mut inserted = Vec::new();
+    let mut decimals = Vec::new();
+    let mut copied = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' {
+                        2
+                    } else {
+                        1
+                    };
+                }
+                i += 1;
+            }
+            b';' => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'|' => {
+                // Base64 data has no quotes or escapes, so swapping the bars keeps every offset.
+                let out = rewritten.get_or_insert_with(|| String::with_capacity(text.len()));
+                let end = text[i + 1..].find('|').map(|end| i + 1 + end).unwrap_or(bytes.len());
+                out.push_str(&text[copied..i]);
+                out.push('"');
+                out.push_str(&text[i + 1..end]);
+                if end < bytes.len() {
+                    out.push('"');
+                }
+                i = end + 1;
+                copied = i.min(bytes.len());
+            }
+            b if !is_delimiter(b) && (i == 0 || is_delimiter(bytes[i - 1])) => {
+                let start = i;
+                while i < bytes.len() && !is_delimiter(bytes[i]) {
+                    i += 1;
+                }
+
+                let atom = &text[start..i];
+                let digits = atom.strip_prefix(['-', '+']).unwrap_or(atom);
+                let numeric = digits.bytes().all(|b| b.is_ascii_digit() || b == b'.');
+                if numeric && digits.contains('.') && digits.parse::<f64>().is_ok() {
+                    decimals.push(atom);
+                } else if b.is_ascii_digit() && !numeric {
+                    let out = rewritten.get_or_insert_with(|| String::with_capacity(text.len()));
+                    out.push_str(&text[copied..start]);
+                    inserted.push(out.len());
+                    out.push('"');
+                    out.push_str(&text[start..i]);
+                    inserted.push(out.len());
+                    out.push('"');
+                    copied = i;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    match rewritten {
+        Some(mut out) => {
+            out.push_str(&text[copied..]);
+            (Cow::Owned(out), inserted, decimals)
+        }
+        None => (Cow::Borrowed(text), inserted, decimals),
+    }
+}
+
+/// Replace the non-integer numbers of a value, in document order, with the text they were read
+/// from. Texts that do not read as the next number are skipped.
+fn keep_decimals<'a, I>(mut value: &mut Value, decimals: &mut I)
+where
+    I: Iterator<Item = &'a str>,
+{
+    loop {
+        if let Value::Number(number) = value {
+            let read = number.as_f64().filter(|_| number.is_f64());
+            if let Some(decimal) = read.and_then(|read| decimals.find(|decimal| decimal.parse() == Ok(read))) {
+                *value = Value::symbol(decimal);
+            }
+            return;
+        }
+
+        // Lists are followed along their tails, since a document can have many elements.
+        let Value::Cons(cons) = value else {
+            return;
+        };
+        keep_decimals(cons.car_mut(), decimals);
+        value = cons.cdr_mut();
+    }
+}
+
+/// Return the value an error complains about, if it names one that appears in the document.
+fn offending_value(error: &ParseError) -> Option<&Value> {
+    match error {
+        ParseError::DuplicateField(_, _, value)
+        | ParseError::ExpectedList(value)
+        | ParseError::ExpectedListFloatHead(value)
+        | ParseError::ExpectedListIntHead(value)
+        | ParseError::ExpectedListStrHead(value)
+        | ParseError::ExpectedListSymbolHead(value)
+        | ParseError::ExpectedNil(value)
+        | ParseError::ExpectedSymbol(value, _)
+        | ParseError::MissingField(_, _, value)
+        | ParseError::Unexpected(value) => Some(value),
+        _ => None,
+    }
+}
+
+/// Return the path, as recorded in a [`Provenance`], of the list within `root` identified by `id`.
+fn element_path(root: &Value, id: ElementId) -> Option<Vec<usize>> {
+    let cons = root.as_cons()?;
+    if ElementId::of(cons) == id {
+        return Some(Vec::new());
+    }
+
+    root.list_iter()?.enumerate().find_map(|(index, child)| {
+        let mut path = element_path(child, id)?;
+        path.insert(0, index);
+        Some(path)
+    })
+}
+
+/// Return the path, relative to `element`, of the first list within it equal to `value`.
+fn value_path(element: &Value, value: &Value) -> Option<Vec<usize>> {
+    element.list_iter()?.enumerate().find_map(|(index, child)| {
+        if child == value {
+            return Some(vec![index]);
+        }
+
+        let mut path = value_path(child, value)?;
+        path.insert(0, index);
+        Some(path)
+    })
+}
+
+/// Split text into its top-level lists and atoms. Unbalanced text yields the nodes that could be
+/// delimited.
+pub(crate) fn scan(text: &str) -> Vec<Node> {
+    let bytes = text.as_bytes();
+    let mut stack: Vec<Node> = vec![Node {
+        start: 0,
+        end: bytes.len(),
+        children: Some(Vec::new()),
+    }];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b if b.is_ascii_whitespace() => i += 1,
+            b';' => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'(' => {
+                stack.push(Node {
+                    start: i,
+                    end: i,
+                    children: Some(Vec::new()),
+                });
+                i += 1;
+            }
+            b')' => {
+                i += 1;
+                if stack.len() > 1 {
+                    let mut list = stack.pop().unwrap();
+                    list.end = i;
+                    push_child(&mut stack, list);
+                }
+            }
+            delimiter @ (b'"' | b'|') => {
+                // Embedded file data is delimited by bars and may span lines.
+                let start = i;
+                i += 1;
+                while i < bytes.len() && bytes[i] != delimiter {
+                    i += if bytes[i] == b'\\' {
+                        2
+                    } else {
+                        1
+                    };
+                }
+                i = (i + 1).min(bytes.len());
+                push_child(
+                    &mut stack,
+                    Node {
+                        start,
+                        end: i,
+                        children: None,
+                    },
+                );
+            }
+            _ => {
+                let start = i;
+                while i < bytes.len() && !bytes[i].is_ascii_whitespace() && !b"()\";".contains(&bytes[i]) {
+                    i += 1;
+                }
+                push_child(
+                    &mut stack,
+                    Node {
+                        start,
+                        end: i,
+                        children: None,
+                    },
+                );
+            }
+        }
+    }
+
+    stack.swap_remove(0).children.unwrap_or_default()
+}
+
+/// Append a node to the innermost open list.
+fn push_child(stack: &mut [Node], node: Node) {
+    if let Some(children) = stack.last_mut().and_then(|list| list.children.as_mut()) {
+        children.push(node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            common::{Nm, Position},
+            sch::Schematic,
+            writer::to_kicad_string,
+            LexprExt,
+        },
+    };
+
+    #[test]
+    fn test_error_location() {
+        let text = "(kicad_sch (version 20231120) (generator \"eeschema\")\n  (junction (at 10 20) (diameter 0) (color 0 0 0 0)\n    (uuid \"not-a-uuid\")))\n";
+
+        let err = parse_str::<Schematic>(text).unwrap_err();
+        let location = err.location().unwrap();
+        assert_eq!((location.line, location.column), (2, 3));
+        assert!(err.to_string().starts_with("line 2, column 3: "), "{err}");
+
+        let text = "(kicad_sch (version 20231120)\n  (junction (at 10 20) (bogus 1)))";
+        let err = parse_str::<Schematic>(text).unwrap_err();
+        let location = err.location().unwrap();
+        assert_eq!((location.line, location.column), (2, 24));
+        assert_eq!(&text[location.offset..location.offset + 6], "(bogus");
+
+        // Of several elements with the same label, the one that failed is found.
+        let text = "(kicad_sch (version 20231120)\n  (junction (at 10 20) (diameter 0) (color 0 0 0 0)\n    (uuid \"c290e417-b246-4481-96d9-22242c5e4431\"))\n  (junction (at 30 40) (bogus 1)))";
+        let err = parse_str::<Schematic>(text).unwrap_err();
+        let location = err.location().unwrap();
+        assert_eq!((location.line, location.column), (4, 24));
+        assert_eq!(err.as_parse_error().unwrap().breadcrumb(), ["kicad_sch", "junction"]);
+
+        let err = parse_str::<Schematic>("(kicad_sch (version 20231120)\n  (wire (pts)").unwrap_err();
+        assert!(matches!(err, Error::Syntax(_)));
+        assert_eq!(err.location().map(|location| location.line), Some(2));
+    }
+
+    #[test]
+    fn test_read_decimals() {
+        let value = read_str("(pad (at 1.001 -2.5400 90) (size 0.0000005 2))").unwrap();
+        let (_, args) = value.expect_cons_with_any_symbol_head().unwrap();
+        let at = args.as_cons().unwrap().car();
+        assert_eq!(at.to_vec().unwrap()[2], Value::symbol("-2.5400"));
+        assert_eq!(at.to_vec().unwrap()[3], Value::from(90));
+        assert_eq!(to_kicad_string(at), "(at 1.001 -2.5400 90)\n");
+
+        let position = Position::try_from(at).unwrap();
+        assert_eq!((position.x, position.y), (Nm(1_001_000), Nm(-2_540_000)));
+
+        // Digits past a nanometer are rounded.
+        let size = args.as_cons().unwrap().cdr().as_cons().unwrap().car();
+        let (width, _) = size.expect_cons_with_symbol_head("size").unwrap().expect_cons_with_any_length_head().unwrap();
+        assert_eq!(width, Nm(1));
+    }
+}
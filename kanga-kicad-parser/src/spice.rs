@@ -0,0 +1,230 @@
+use {
+    crate::{
+        sch::Schematic,
+        vars::{expand_text, VarProvider},
+        Error,
+    },
+    std::{
+        collections::BTreeSet,
+        fs::read_to_string,
+        path::{Path, PathBuf},
+    },
+};
+
+/// The symbol property naming the file a simulation model is read from.
+pub const SIM_LIBRARY_PROPERTY: &str = "Sim.Library";
+
+/// The symbol property naming the model or subcircuit within the library file.
+pub const SIM_NAME_PROPERTY: &str = "Sim.Name";
+
+/// How deeply inlined model files may include other files.
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// A placed symbol's reference to a model in an external SPICE library file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct SpiceModelRef {
+    /// The reference designator of the symbol.
+    pub reference: String,
+
+    /// The library file, as written in the `Sim.Library` property.
+    pub library: String,
+
+    /// The model or subcircuit name, from the `Sim.Name` property, if any.
+    pub name: Option<String>,
+}
+
+/// Where to look for model libraries and whether to inline them.
+#[derive(Clone, Debug, Default)]
+pub struct SpiceOptions {
+    /// Directories searched, in order, for libraries given by a relative path that is not found
+    /// relative to the schematic.
+    pub include_paths: Vec<PathBuf>,
+
+    /// Copy the content of each library into the netlist instead of writing an `.include` line,
+    /// so that the netlist can be simulated on another machine.
+    pub inline: bool,
+}
+
+/// The model library section of a SPICE netlist.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SpiceIncludes {
+    /// The netlist text: an `.include` line or the inlined content of each library.
+    pub text: String,
+
+    /// The library files used, in the order they first appear.
+    pub files: Vec<PathBuf>,
+
+    /// The libraries that could not be found, as written in the `Sim.Library` property.
+    pub missing: Vec<String>,
+}
+
+/// Return the model library references of the symbols in a schematic, in document order. Symbols
+/// excluded from simulation are skipped.
+pub fn spice_model_refs(schematic: &Schematic) -> Vec<SpiceModelRef> {
+    schematic
+        .symbols
+        .iter()
+        .filter(|symbol| symbol.exclude_from_sim != Some(true))
+        .filter_map(|symbol| {
+            let property = |key: &str| {
+                symbol.properties.iter().find(|property| property.key == key).map(|property| property.value.as_str())
+            };
+            let library = property(SIM_LIBRARY_PROPERTY).filter(|library| !library.trim().is_empty())?;
+
+            Some(SpiceModelRef {
+                reference: property("Reference").unwrap_or_default().to_string(),
+                library: library.to_string(),
+                name: property(SIM_NAME_PROPERTY).map(String::from),
+            })
+        })
+        .collect()
+}
+
+/// Find the file a `Sim.Library` property refers to.
+///
+/// Variables such as `${KIPRJMOD}` are expanded first. A relative path is looked up relative to
+/// `base_dir`, the directory of the schematic, and then in each of the include paths, as KiCad
+/// does with its `SPICE_LIB_DIR` setting.
+pub fn resolve_model_library(
+    library: &str,
+    base_dir: &Path,
+    options: &SpiceOptions,
+    vars: &dyn VarProvider,
+) -> Option<PathBuf> {
+    let path = PathBuf::from(expand_text(library.trim(), vars));
+    if path.is_absolute() {
+        return path.is_file().then_some(path);
+    }
+
+    std::iter::once(base_dir)
+        .chain(options.include_paths.iter().map(PathBuf::as_path))
+        .map(|dir| dir.join(&path))
+        .find(|path| path.is_file())
+}
+
+/// Produce the model library section of a netlist for a set of model references.
+///
+/// Each library is included once, however many symbols use it. With [`SpiceOptions::inline`] set,
+/// the content of each library is copied in, along with any files it includes in turn with
+/// `.include` or `.inc`; otherwise an `.include` line naming the resolved file is written.
+/// Libraries that cannot be found are listed in [`SpiceIncludes::missing`] rather than failing, so
+/// that a partial netlist can still be produced.
+pub fn model_includes(
+    refs: &[SpiceModelRef],
+    base_dir: &Path,
+    options: &SpiceOptions,
+    vars: &dyn VarProvider,
+) -> Result<SpiceIncludes, Error> {
+    let mut includes = SpiceIncludes::default();
+    let mut seen = BTreeSet::new();
+
+    for library in refs.iter().map(|model| model.library.as_str()) {
+        if !seen.insert(library) {
+            continue;
+        }
+
+        let Some(path) = resolve_model_library(library, base_dir, options, vars) else {
+            includes.missing.push(library.to_string());
+            continue;
+        };
+
+        if options.inline {
+            inline_file(&path, 0, &mut includes)?;
+        } else if !includes.files.contains(&path) {
+            includes.text.push_str(&format!(".include \"{}\"\n", path.display()));
+            includes.files.push(path);
+        }
+    }
+
+    Ok(includes)
+}
+
+/// Append the content of a library file, replacing its `.include` lines with the files they name.
+fn inline_file(path: &Path, depth: usize, includes: &mut SpiceIncludes) -> Result<(), Error> {
+    if includes.files.iter().any(|file| file == path) {
+        return Ok(());
+    }
+
+    let text = read_to_string(path)?;
+    includes.files.push(path.to_path_buf());
+    includes.text.push_str(&format!("* Begin {}\n", path.display()));
+
+    for line in text.lines() {
+        match include_target(line) {
+            Some(target) if depth < MAX_INCLUDE_DEPTH => {
+                let target = path.parent().unwrap_or(Path::new("")).join(target);
+                inline_file(&target, depth + 1, includes)?;
+            }
+            _ => {
+                includes.text.push_str(line);
+                includes.text.push('\n');
+            }
+        }
+    }
+
+    includes.text.push_str(&format!("* End {}\n", path.display()));
+    Ok(())
+}
+
+/// Return the file named by an `.include` or `.inc` line, unquoted.
+fn include_target(line: &str) -> Option<&str> {
+    let (command, target) = line.trim().split_once(char::is_whitespace)?;
+    if !command.eq_ignore_ascii_case(".include") && !command.eq_ignore_ascii_case(".inc") {
+        return None;
+    }
+
+    let target = target.trim();
+    Some(target.strip_prefix('"').and_then(|target| target.strip_suffix('"')).unwrap_or(target))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{parse_str, vars::StaticVars},
+        std::fs::{create_dir_all, remove_dir_all, write},
+    };
+
+    #[test]
+    fn test_model_includes() {
+        let dir = std::env::temp_dir().join(format!("kanga-spice-{}", std::process::id()));
+        let models = dir.join("models");
+        create_dir_all(&models).unwrap();
+        write(models.join("opamp.lib"), ".include \"common.inc\"\n.subckt OPA 1 2 3 4 5\n.ends\n").unwrap();
+        write(models.join("common.inc"), ".model D1N4148 D(Is=2.52n)\n").unwrap();
+
+        let text = r#"(kicad_sch (version 20231120) (generator "eeschema")
+            (symbol (lib_id "Amplifier:OPA") (at 0 0 0) (unit 1) (uuid "00000000-0000-0000-0000-000000000001")
+                (property "Reference" "U1" (at 0 0 0)) (property "Sim.Library" "${MODELS}/opamp.lib" (at 0 0 0))
+                (property "Sim.Name" "OPA" (at 0 0 0)))
+            (symbol (lib_id "Amplifier:OPA") (at 10 0 0) (unit 1) (uuid "00000000-0000-0000-0000-000000000002")
+                (property "Reference" "U2" (at 0 0 0)) (property "Sim.Library" "opamp.lib" (at 0 0 0)))
+            (symbol (lib_id "Device:Q") (at 20 0 0) (unit 1) (uuid "00000000-0000-0000-0000-000000000003")
+                (property "Reference" "Q1" (at 0 0 0)) (property "Sim.Library" "missing.lib" (at 0 0 0))))"#;
+
+        let schematic: Schematic = parse_str(text).unwrap();
+        let refs = spice_model_refs(&schematic);
+        assert_eq!(refs.len(), 3);
+        assert_eq!(refs[0].name.as_deref(), Some("OPA"));
+
+        // The second symbol's relative library is found in the include path, and is the same file.
+        let vars = StaticVars::new().with("MODELS", models.to_string_lossy());
+        let mut options = SpiceOptions {
+            include_paths: vec![models.clone()],
+            inline: false,
+        };
+        let includes = model_includes(&refs, &dir, &options, &vars).unwrap();
+        assert_eq!(includes.files, [models.join("opamp.lib")]);
+        assert_eq!(includes.missing, ["missing.lib"]);
+
+        options.inline = true;
+        let inlined = model_includes(&refs, &dir, &options, &vars).unwrap();
+        remove_dir_all(&dir).unwrap();
+
+        assert_eq!(inlined.files, [models.join("opamp.lib"), models.join("common.inc")]);
+        assert!(inlined.text.contains(".model D1N4148"));
+        assert!(inlined.text.find(".model").unwrap() < inlined.text.find(".subckt").unwrap());
+        assert!(!inlined.text.contains(".include"));
+    }
+}
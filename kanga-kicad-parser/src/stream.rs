@@ -0,0 +1,319 @@
+use {
+    crate::{span::read_str, Error, LexprExt, ParseError, SyntaxError},
+    lexpr::Value,
+    std::io::{BufRead, ErrorKind},
+};
+
+/// The kinds of board items [`for_each_board_item`] can stream.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum BoardItemKind {
+    /// A `segment` or `arc` track.
+    Track,
+
+    /// A `via`.
+    Via,
+
+    /// A footprint `pad`.
+    Pad,
+
+    /// A copper or keepout `zone`.
+    Zone,
+}
+
+/// Reads the top-level elements of a document one at a time, without building the whole tree.
+///
+/// Each element is read into a buffer that is reused for the next one, so [`next_element`]
+/// lends a [`RawElement`] that must be dropped before the stream can advance. Memory use is
+/// bounded by the largest top-level element, not the size of the document.
+///
+/// [`next_element`]: ElementStream::next_element
+#[derive(Debug)]
+pub struct ElementStream<R> {
+    reader: R,
+    root: String,
+    buffer: Vec<u8>,
+    head_len: usize,
+    finished: bool,
+}
+
+/// A top-level element lent by an [`ElementStream`].
+#[derive(Clone, Copy, Debug)]
+pub struct RawElement<'a> {
+    head: &'a str,
+    text: &'a str,
+}
+
+impl<R: BufRead> ElementStream<R> {
+    /// Start reading a document, consuming its opening parenthesis and root element name.
+    pub fn new(mut reader: R) -> Result<Self, Error> {
+        skip_whitespace(&mut reader)?;
+        if next_byte(&mut reader)? != Some(b'(') {
+            return Err(SyntaxError::new("Expected a list at the start of the document").into());
+        }
+
+        let mut root = Vec::new();
+        read_atom(&mut reader, &mut root)?;
+        let root = String::from_utf8(root).map_err(|e| SyntaxError::new(e.to_string()))?;
+
+        Ok(Self {
+            reader,
+            root,
+            buffer: Vec::new(),
+            head_len: 0,
+            finished: false,
+        })
+    }
+
+    /// Return the name of the root element, e.g. `kicad_pcb`.
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    /// Read the next top-level element, or return `None` at the end of the root element. Atoms
+    /// and strings among the top-level elements, such as a footprint's name, are skipped.
+    pub fn next_element(&mut self) -> Option<Result<RawElement<'_>, Error>> {
+        if self.finished {
+            return None;
+        }
+
+        match self.read_element() {
+            Ok(true) => (),
+            Ok(false) => {
+                self.finished = true;
+                return None;
+            }
+            Err(e) => {
+                self.finished = true;
+                return Some(Err(e));
+            }
+        }
+
+        match std::str::from_utf8(&self.buffer) {
+            Ok(text) => Some(Ok(RawElement {
+                head: &text[1..1 + self.head_len],
+                text,
+            })),
+            Err(e) => Some(Err(SyntaxError::new(e.to_string()).into())),
+        }
+    }
+
+    /// Call `f` with each top-level element whose head is in `heads`, or with every element if
+    /// `heads` is empty. Other elements are read but not parsed.
+    pub fn for_each_element<F>(mut self, heads: &[&str], mut f: F) -> Result<(), Error>
+    where
+        F: FnMut(RawElement<'_>) -> Result<(), Error>,
+    {
+        while let Some(element) = self.next_element() {
+            let element = element?;
+            if heads.is_empty() || heads.contains(&element.head) {
+                f(element)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the next top-level list into the buffer. Returns `false` at the end of the root.
+    fn read_element(&mut self) -> Result<bool, Error> {
+        self.buffer.clear();
+        loop {
+            skip_whitespace(&mut self.reader)?;
+            match peek_byte(&mut self.reader)? {
+                None | Some(b')') => return Ok(false),
+                Some(b'(') => break,
+                Some(b'"') => {
+                    next_byte(&mut self.reader)?;
+                    read_string(&mut self.reader, &mut self.buffer)?;
+                    self.buffer.clear();
+                }
+                Some(_) => {
+                    read_atom(&mut self.reader, &mut self.buffer)?;
+                    self.buffer.clear();
+                }
+            }
+        }
+
+        next_byte(&mut self.reader)?;
+        self.buffer.push(b'(');
+        read_atom(&mut self.reader, &mut self.buffer)?;
+        self.head_len = self.buffer.len() - 1;
+
+        let mut depth = 1;
+        while depth > 0 {
+            let Some(byte) = next_byte(&mut self.reader)? else {
+                return Err(SyntaxError::new(format!("Unterminated {} element", self.root)).into());
+            };
+            self.buffer.push(byte);
+            match byte {
+                b'(' => depth += 1,
+                b')' => depth -= 1,
+                b'"' => read_string(&mut self.reader, &mut self.buffer)?,
+                _ => (),
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl<'a> RawElement<'a> {
+    /// Return the element name, e.g. `segment`.
+    pub fn head(&self) -> &'a str {
+        self.head
+    }
+
+    /// Return the text of the element.
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Parse the element, accepting the same KiCad syntax as [`read_str`].
+    pub fn parse(&self) -> Result<Value, Error> {
+        read_str(self.text).map_err(|e| match e {
+            Error::Syntax(e) => SyntaxError {
+                message: format!("{}: {}", self.head, e.message),
+                ..e
+            }
+            .into(),
+            e => e,
+        })
+    }
+}
+
+/// Stream the tracks, vias, pads, and zones of a board, calling `f` with each item of the
+/// requested kinds. Only one top-level element is held in memory at a time; pads are visited
+/// one footprint at a time.
+pub fn for_each_board_item<R, F>(reader: R, kinds: &[BoardItemKind], mut f: F) -> Result<(), Error>
+where
+    R: BufRead,
+    F: FnMut(BoardItemKind, &Value) -> Result<(), Error>,
+{
+    let stream = ElementStream::new(reader)?;
+    if stream.root() != "kicad_pcb" {
+        return Err(ParseError::ExpectedSymbol(Value::symbol(stream.root()), "kicad_pcb".to_string()).into());
+    }
+
+    stream.for_each_element(&[], |element| {
+        let kind = match element.head() {
+            "segment" | "arc" => BoardItemKind::Track,
+            "via" => BoardItemKind::Via,
+            "zone" => BoardItemKind::Zone,
+            "footprint" | "module" => BoardItemKind::Pad,
+            _ => return Ok(()),
+        };
+
+        if !kinds.contains(&kind) {
+            return Ok(());
+        }
+
+        let value = element.parse()?;
+        if kind != BoardItemKind::Pad {
+            return f(kind, &value);
+        }
+
+        let (_, rest) = value.expect_cons_with_any_symbol_head()?;
+        for pad in rest.list_iter().into_iter().flatten() {
+            if pad.expect_cons_with_symbol_head("pad").is_ok() {
+                f(kind, pad)?;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Read a byte, or `None` at the end of the input.
+fn next_byte<R: BufRead>(reader: &mut R) -> Result<Option<u8>, Error> {
+    let byte = peek_byte(reader)?;
+    if byte.is_some() {
+        reader.consume(1);
+    }
+    Ok(byte)
+}
+
+/// Return the next byte without consuming it, or `None` at the end of the input.
+fn peek_byte<R: BufRead>(reader: &mut R) -> Result<Option<u8>, Error> {
+    loop {
+        match reader.fill_buf() {
+            Ok(buffer) => return Ok(buffer.first().copied()),
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Consume whitespace.
+fn skip_whitespace<R: BufRead>(reader: &mut R) -> Result<(), Error> {
+    while peek_byte(reader)?.is_some_and(|byte| byte.is_ascii_whitespace()) {
+        reader.consume(1);
+    }
+    Ok(())
+}
+
+/// Read an unquoted atom into `buffer`.
+fn read_atom<R: BufRead>(reader: &mut R, buffer: &mut Vec<u8>) -> Result<(), Error> {
+    while let Some(byte) = peek_byte(reader)? {
+        if byte.is_ascii_whitespace() || b"()\"".contains(&byte) {
+            break;
+        }
+        buffer.push(byte);
+        reader.consume(1);
+    }
+    Ok(())
+}
+
+/// Read the rest of a string, whose opening quote has been consumed, into `buffer`.
+fn read_string<R: BufRead>(reader: &mut R, buffer: &mut Vec<u8>) -> Result<(), Error> {
+    let mut escaped = false;
+    loop {
+        let Some(byte) = next_byte(reader)? else {
+            return Err(SyntaxError::new("Unterminated string").into());
+        };
+        buffer.push(byte);
+        match byte {
+            _ if escaped => escaped = false,
+            b'\\' => escaped = true,
+            b'"' => return Ok(()),
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::io::BufReader};
+
+    #[test]
+    fn test_stream_board() {
+        let board = r#"(kicad_pcb (version 20240108) (generator "pcbnew")
+            (footprint "R:R_0603" (layer "F.Cu") (property "Reference" "R1 (\"top\")")
+                (pad "1" smd rect (at -0.8 0) (size 0.8 0.9) (net 1 "VCC"))
+                (pad "2" smd rect (at 0.8 0) (size 0.8 0.9) (net 2 "GND")))
+            (segment (start 0 0) (end 1 0) (width 0.25) (layer "F.Cu") (net 1)
+                (uuid 0c6b985a-999e-45ae-b1a7-41cfbe8ba8fa))
+            (via (at 1 0) (size 0.6) (drill 0.3) (layers "F.Cu" "B.Cu") (net 1))
+            (zone (net 2) (net_name "GND") (layer "B.Cu")))"#;
+
+        // A tiny buffer exercises elements and strings split across reads.
+        let reader = BufReader::with_capacity(7, board.as_bytes());
+        let mut seen = Vec::new();
+        for_each_board_item(reader, &[BoardItemKind::Pad, BoardItemKind::Track, BoardItemKind::Via], |kind, item| {
+            seen.push((kind, item.to_vec().unwrap()[1].to_string()));
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            seen,
+            [
+                (BoardItemKind::Pad, "\"1\"".to_string()),
+                (BoardItemKind::Pad, "\"2\"".to_string()),
+                (BoardItemKind::Track, "(start 0 0)".to_string()),
+                (BoardItemKind::Via, "(at 1 0)".to_string()),
+            ]
+        );
+
+        let mut stream = ElementStream::new(board.as_bytes()).unwrap();
+        assert_eq!(stream.root(), "kicad_pcb");
+        let heads: Vec<String> =
+            std::iter::from_fn(|| stream.next_element().map(|e| e.unwrap().head().to_string())).collect();
+        assert_eq!(heads, ["version", "generator", "footprint", "segment", "via", "zone"]);
+    }
+}
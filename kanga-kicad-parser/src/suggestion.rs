@@ -0,0 +1,172 @@
+use {
+    crate::ParseError,
+    lexpr::{Cons, Value},
+    serde::{Deserialize, Serialize},
+};
+
+/// Boolean flags that KiCad 6 writes as a bare symbol (`hide`) and KiCad 7+ writes as an element
+/// with a value (`(hide yes)`).
+const BOOLEAN_FLAGS: &[&str] = &["bold", "fields_autoplaced", "hide", "italic", "mirror"];
+
+/// A machine-readable hint describing how a document that failed to parse might be repaired.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Suggestion {
+    /// The kind of problem detected.
+    pub kind: SuggestionKind,
+
+    /// The offending element, as written in the document.
+    pub found: String,
+
+    /// The forms that would have been accepted at this location.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub expected: Vec<String>,
+
+    /// The text to substitute for the offending element. An empty string means the element should be
+    /// removed. This is only present if the repair can be made automatically.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<String>,
+}
+
+/// The kinds of common failures for which a [`Suggestion`] can be made.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionKind {
+    /// An element has too few or too many arguments.
+    WrongArity,
+
+    /// A value that must be a quoted string was written as a bare symbol or number, or vice versa.
+    MisquotedString,
+
+    /// A boolean flag was written in the form used by a different KiCad version.
+    LegacyBoolean,
+}
+
+impl Suggestion {
+    /// Indicates whether the repair can be applied without user input.
+    pub fn is_auto_fixable(&self) -> bool {
+        self.replacement.is_some()
+    }
+}
+
+impl ParseError {
+    /// Return a suggestion for repairing the document, if this is a common failure mode.
+    pub fn suggestion(&self) -> Option<Suggestion> {
+        match self.root_cause() {
+            Self::ExpectedListStrHead(value) => {
+                let cons = value.as_cons()?;
+                let unquoted = match cons.car() {
+                    Value::Symbol(s) => s.to_string(),
+                    Value::Number(n) => n.to_string(),
+                    _ => return None,
+                };
+
+                Some(Suggestion {
+                    kind: SuggestionKind::MisquotedString,
+                    found: value.to_string(),
+                    expected: vec![format!("{unquoted:?}")],
+                    replacement: Some(with_car(cons, Value::string(unquoted)).to_string()),
+                })
+            }
+
+            Self::ExpectedSymbol(value, symbol) => {
+                let cons = value.as_cons()?;
+                if cons.car().as_str() != Some(symbol.as_str()) {
+                    return None;
+                }
+
+                Some(Suggestion {
+                    kind: SuggestionKind::MisquotedString,
+                    found: value.to_string(),
+                    expected: vec![format!("({symbol} ...)")],
+                    replacement: Some(with_car(cons, Value::symbol(symbol.as_str())).to_string()),
+                })
+            }
+
+            Self::MissingField(struct_name, field_name, value) => Some(Suggestion {
+                kind: SuggestionKind::WrongArity,
+                found: value.to_string(),
+                expected: vec![format!("({struct_name} ... ({field_name} ...))")],
+                replacement: None,
+            }),
+
+            Self::ExpectedNil(value) => Some(Suggestion {
+                kind: SuggestionKind::WrongArity,
+                found: value.to_string(),
+                expected: vec!["()".to_string()],
+                replacement: None,
+            }),
+
+            Self::Unexpected(value) => legacy_boolean(value),
+
+            _ => None,
+        }
+    }
+}
+
+/// Suggest the other version's form of a boolean flag, if `value` is one.
+fn legacy_boolean(value: &Value) -> Option<Suggestion> {
+    if let Some(flag) = value.as_symbol().filter(|flag| BOOLEAN_FLAGS.contains(flag)) {
+        // KiCad 6 form where KiCad 7+ syntax was expected.
+        return Some(Suggestion {
+            kind: SuggestionKind::LegacyBoolean,
+            found: value.to_string(),
+            expected: vec![format!("({flag} yes)"), format!("({flag} no)")],
+            replacement: Some(format!("({flag} yes)")),
+        });
+    }
+
+    // KiCad 7+ form where KiCad 6 syntax was expected.
+    let cons = value.as_cons()?;
+    let flag = cons.car().as_symbol().filter(|flag| BOOLEAN_FLAGS.contains(flag))?;
+    let set = match cons.cdr() {
+        Value::Null => true,
+        Value::Cons(rest) if rest.cdr().is_null() => match rest.car().as_symbol()? {
+            "yes" => true,
+            "no" => false,
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    Some(Suggestion {
+        kind: SuggestionKind::LegacyBoolean,
+        found: value.to_string(),
+        expected: vec![flag.to_string()],
+        replacement: Some(if set {
+            flag.to_string()
+        } else {
+            String::new()
+        }),
+    })
+}
+
+/// Return a copy of `cons` as a list value with its head replaced.
+fn with_car(cons: &Cons, car: Value) -> Value {
+    Value::cons(car, cons.cdr().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, lexpr::sexp};
+
+    #[test]
+    fn test_suggestions() {
+        let err = ParseError::ExpectedListStrHead(lexpr::from_str("(Device:R (at 0 0))").unwrap()).within("lib_id");
+        let suggestion = err.suggestion().unwrap();
+        assert_eq!(suggestion.kind, SuggestionKind::MisquotedString);
+        assert_eq!(suggestion.replacement.as_deref(), Some("(\"Device:R\" (at 0 0))"));
+
+        let suggestion = ParseError::Unexpected(sexp!((hide yes))).suggestion().unwrap();
+        assert_eq!(suggestion.kind, SuggestionKind::LegacyBoolean);
+        assert_eq!(suggestion.replacement.as_deref(), Some("hide"));
+
+        let suggestion = ParseError::Unexpected(sexp!((hide no))).suggestion().unwrap();
+        assert_eq!(suggestion.replacement.as_deref(), Some(""));
+
+        let suggestion = ParseError::missing_field("pin", "length", sexp!((pin input line))).suggestion().unwrap();
+        assert_eq!(suggestion.kind, SuggestionKind::WrongArity);
+        assert!(!suggestion.is_auto_fixable());
+
+        assert!(ParseError::InvalidUuid("x".to_string()).suggestion().is_none());
+    }
+}
@@ -0,0 +1,693 @@
+use {
+    crate::{
+        common::{
+            Angle, Color, Decimal, Fill, FillType, HorizJustify, LineStyle, Nm, ParentOrientation, Point, Position,
+            Stroke, Symbol, SymbolGraphic, SymbolPin, TextEffects, TextJustify, VertJustify,
+        },
+        geometry::{circumcenter, BoundingBox},
+        metrics::measure_text,
+        sch::{Schematic, SchematicSymbolInstance, SchematicSymbolMirror},
+        wks::{WorksheetFrame, WorksheetMargins},
+        Config, Theme,
+    },
+    std::fmt::Write,
+};
+
+/// The font size KiCad uses when text effects have no font, in nanometers.
+const DEFAULT_TEXT_SIZE: i64 = 1_270_000;
+
+/// Sizes used by [`symbol_to_svg`] and [`schematic_to_svg`]. The colors are taken from the
+/// [`Theme`] of the [`Config`].
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SvgOptions {
+    /// The space left around the drawing, in nanometers.
+    pub margin: i64,
+
+    /// The width of strokes whose width is zero, which KiCad draws at its default line width, in
+    /// nanometers.
+    pub default_line_width: i64,
+
+    /// The drawing sheet margins. When set, a schematic with page settings is drawn on its whole
+    /// page with the sheet frame at these margins, rather than cropped to its contents.
+    pub frame: Option<WorksheetMargins>,
+}
+
+/// A mapping from the coordinates of an element to the Y-down coordinates of the drawing.
+#[derive(Clone, Copy, Debug, Default)]
+struct Transform {
+    /// The position of the element's origin in the drawing.
+    origin: (i64, i64),
+
+    /// Whether the element uses Y-up coordinates, as library symbols do.
+    flip_y: bool,
+
+    /// The rotation and mirroring of the element.
+    orientation: ParentOrientation,
+}
+
+/// Accumulates SVG elements and the extent of what they draw.
+struct SvgWriter<'a> {
+    options: &'a SvgOptions,
+    theme: &'a Theme,
+    body: String,
+    bbox: Option<BoundingBox>,
+    page: Option<BoundingBox>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            margin: 2_540_000,
+            default_line_width: 152_400,
+            frame: None,
+        }
+    }
+}
+
+impl Transform {
+    /// The transform of a library symbol drawn on its own.
+    fn library() -> Self {
+        Self {
+            flip_y: true,
+            ..Self::default()
+        }
+    }
+
+    /// The transform of a library symbol placed in a schematic.
+    fn placed(instance: &SchematicSymbolInstance) -> Self {
+        Self {
+            origin: (instance.position.x.0, instance.position.y.0),
+            flip_y: true,
+            orientation: ParentOrientation {
+                angle: instance.position.angle.unwrap_or_default(),
+                mirror_x: instance.mirror == Some(SchematicSymbolMirror::X),
+                mirror_y: instance.mirror == Some(SchematicSymbolMirror::Y),
+            },
+        }
+    }
+
+    /// Map a point into the drawing. Rotation is applied before mirroring, as in KiCad.
+    fn apply(&self, x: i64, y: i64) -> (i64, i64) {
+        let y = if self.flip_y {
+            -y
+        } else {
+            y
+        };
+        let (mut x, mut y) = self.orientation.angle.rotate(x, y);
+
+        if self.orientation.mirror_y {
+            x = -x;
+        }
+
+        if self.orientation.mirror_x {
+            y = -y;
+        }
+
+        (self.origin.0 + x, self.origin.1 + y)
+    }
+}
+
+impl<'a> SvgWriter<'a> {
+    fn new(config: &'a Config) -> Self {
+        Self {
+            options: &config.svg,
+            theme: &config.theme,
+            body: String::new(),
+            bbox: None,
+            page: None,
+        }
+    }
+
+    /// Grow the extent of the drawing to include a point.
+    fn include(&mut self, (x, y): (i64, i64), margin: i64) {
+        let point = BoundingBox::from_point(x, y).inflated(margin);
+        self.bbox = Some(self.bbox.map_or(point, |bbox| bbox.union(point)));
+    }
+
+    /// Write a path through points, closing it if it is filled.
+    fn path(&mut self, points: &[(i64, i64)], stroke: &Stroke, fill: Option<&Fill>, color: &str) {
+        if points.is_empty() {
+            return;
+        }
+
+        let mut d = String::new();
+        for (i, &(x, y)) in points.iter().enumerate() {
+            let _ = write!(
+                d,
+                "{}{} {} ",
+                if i == 0 {
+                    "M"
+                } else {
+                    "L"
+                },
+                mm(x),
+                mm(y)
+            );
+        }
+        if fill.is_some_and(|fill| !matches!(fill.fill_type, FillType::None)) {
+            d.push('Z');
+        }
+
+        self.shape(&d, points, stroke, fill, color);
+    }
+
+    /// Write a `<path>` element with data `d` passing through or bounded by `extent`.
+    fn shape(&mut self, d: &str, extent: &[(i64, i64)], stroke: &Stroke, fill: Option<&Fill>, color: &str) {
+        let width = self.stroke_width(stroke);
+        for &point in extent {
+            self.include(point, width / 2);
+        }
+
+        let _ = writeln!(
+            self.body,
+            r#"<path d="{}" {} {}/>"#,
+            d.trim_end(),
+            self.stroke_attrs(stroke, color),
+            self.fill_attrs(fill, stroke, color)
+        );
+    }
+
+    /// Write a circle.
+    fn circle(&mut self, center: (i64, i64), radius: i64, stroke: &Stroke, fill: Option<&Fill>, color: &str) {
+        let width = self.stroke_width(stroke);
+        self.include(center, radius + width / 2);
+
+        let _ = writeln!(
+            self.body,
+            r#"<circle cx="{}" cy="{}" r="{}" {} {}/>"#,
+            mm(center.0),
+            mm(center.1),
+            mm(radius),
+            self.stroke_attrs(stroke, color),
+            self.fill_attrs(fill, stroke, color)
+        );
+    }
+
+    /// Write an arc through three points.
+    fn arc(&mut self, points: [(i64, i64); 3], stroke: &Stroke, fill: Option<&Fill>, color: &str) {
+        let [start, mid, end] = points;
+        let as_f64 = |(x, y): (i64, i64)| (x as f64, y as f64);
+
+        let Some(center) = circumcenter(as_f64(start), as_f64(mid), as_f64(end)) else {
+            self.path(&points, stroke, fill, color);
+            return;
+        };
+
+        let radius = (start.0 as f64 - center.0).hypot(start.1 as f64 - center.1).round() as i64;
+        let cross = |o: (f64, f64), a: (f64, f64), b: (f64, f64)| (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0);
+
+        // In Y-down coordinates, a positive turn through the midpoint is clockwise on screen, which
+        // is SVG's positive sweep. The arc is the larger one when the center lies on the same side
+        // of the chord as the midpoint.
+        let sweep = cross(as_f64(start), as_f64(mid), as_f64(end)) > 0.0;
+        let large = cross(as_f64(start), as_f64(end), as_f64(mid)) * cross(as_f64(start), as_f64(end), center) > 0.0;
+
+        let d = format!(
+            "M{} {} A{} {} 0 {} {} {} {}",
+            mm(start.0),
+            mm(start.1),
+            mm(radius),
+            mm(radius),
+            large as u8,
+            sweep as u8,
+            mm(end.0),
+            mm(end.1)
+        );
+        let extent = BoundingBox::from_points(points).map(|bbox| bbox.union(arc_extent(center, radius)));
+        let extent: Vec<_> =
+            extent.map(|bbox| vec![(bbox.min_x, bbox.min_y), (bbox.max_x, bbox.max_y)]).unwrap_or_default();
+        self.shape(&d, &extent, stroke, fill, color);
+    }
+
+    /// Write text anchored at `at`, drawn at `angle` relative to a parent with `orientation`.
+    fn text(
+        &mut self,
+        text: &str,
+        at: (i64, i64),
+        angle: Angle,
+        effects: &TextEffects,
+        orientation: ParentOrientation,
+    ) {
+        if effects.hide || text.is_empty() {
+            return;
+        }
+
+        let justify = effects.justify.clone().unwrap_or(TextJustify {
+            horiz_justify: HorizJustify::default(),
+            vert_justify: VertJustify::default(),
+            mirror: false,
+        });
+        let effective = justify.effective_justification(angle, orientation);
+        let font = effects.font.as_ref();
+        let size = font.map_or(DEFAULT_TEXT_SIZE, |font| font.size.height.0);
+
+        let anchor = match effective.horiz_justify {
+            HorizJustify::Left => "start",
+            HorizJustify::Center => "middle",
+            HorizJustify::Right => "end",
+        };
+        let baseline = match effective.vert_justify {
+            VertJustify::Top => "hanging",
+            VertJustify::Center => "central",
+            VertJustify::Bottom => "alphabetic",
+        };
+
+        let measured = measure_text(text, effects);
+        self.include(at, measured.width.max(measured.height).0);
+
+        let mut attrs = format!(r#"font-family="sans-serif" font-size="{}""#, mm(size));
+        if font.is_some_and(|font| font.bold) {
+            attrs.push_str(r#" font-weight="bold""#);
+        }
+        if font.is_some_and(|font| font.italic) {
+            attrs.push_str(r#" font-style="italic""#);
+        }
+
+        // KiCad angles turn counterclockwise on screen; SVG rotations turn clockwise.
+        let _ = writeln!(
+            self.body,
+            r#"<text x="{x}" y="{y}" {attrs} text-anchor="{anchor}" dominant-baseline="{baseline}" fill="{}" transform="rotate({} {x} {y})">{}</text>"#,
+            self.theme.color,
+            -effective.angle.degrees(),
+            escape(text),
+            x = mm(at.0),
+            y = mm(at.1),
+        );
+    }
+
+    /// Write the graphics, pins, and visible properties of unit `unit` of a library symbol.
+    fn symbol(&mut self, symbol: &Symbol, unit: i64, transform: Transform, draw_properties: bool) {
+        let color = self.theme.color.clone();
+        let map = |point: &Point| transform.apply(point.x.0, point.y.0);
+
+        let graphics = symbol.graphics.iter().chain(symbol.units_of(Some(unit)).flat_map(|unit| &unit.graphics));
+        for graphic in graphics {
+            match graphic {
+                SymbolGraphic::Arc(arc) => {
+                    self.arc([map(&arc.start), map(&arc.mid), map(&arc.end)], &arc.stroke, Some(&arc.fill), &color)
+                }
+                SymbolGraphic::Bezier(bezier) => {
+                    let points: Vec<_> = bezier.points.points.iter().map(map).collect();
+                    if let [p0, p1, p2, p3] = points[..] {
+                        let d = format!(
+                            "M{} {} C{} {} {} {} {} {}",
+                            mm(p0.0),
+                            mm(p0.1),
+                            mm(p1.0),
+                            mm(p1.1),
+                            mm(p2.0),
+                            mm(p2.1),
+                            mm(p3.0),
+                            mm(p3.1)
+                        );
+                        self.shape(&d, &points, &bezier.stroke, Some(&bezier.fill), &color);
+                    } else {
+                        self.path(&points, &bezier.stroke, Some(&bezier.fill), &color);
+                    }
+                }
+                SymbolGraphic::Circle(circle) => {
+                    self.circle(map(&circle.center), circle.radius.0, &circle.stroke, Some(&circle.fill), &color)
+                }
+                SymbolGraphic::Polyline(polyline) => {
+                    let points: Vec<_> = polyline.points.points.iter().map(map).collect();
+                    self.path(&points, &polyline.stroke, Some(&polyline.fill), &color);
+                }
+                SymbolGraphic::Rectangle(rectangle) => {
+                    let (start, end) = (&rectangle.start, &rectangle.end);
+                    let corners =
+                        [(start.x.0, start.y.0), (end.x.0, start.y.0), (end.x.0, end.y.0), (start.x.0, end.y.0)];
+                    let points: Vec<_> = corners.iter().map(|&(x, y)| transform.apply(x, y)).collect();
+                    let mut d = String::new();
+                    for (i, &(x, y)) in points.iter().enumerate() {
+                        let _ = write!(
+                            d,
+                            "{}{} {} ",
+                            if i == 0 {
+                                "M"
+                            } else {
+                                "L"
+                            },
+                            mm(x),
+                            mm(y)
+                        );
+                    }
+                    d.push('Z');
+                    self.shape(&d, &points, &rectangle.stroke, Some(&rectangle.fill), &color);
+                }
+                SymbolGraphic::Text(text) => {
+                    let angle = text.position.angle.unwrap_or_default();
+                    self.text(
+                        &text.text,
+                        map(&text.position.point()),
+                        angle,
+                        &text.text_effects,
+                        transform.orientation,
+                    );
+                }
+            }
+        }
+
+        for pin in symbol.unit_pins(Some(unit)) {
+            self.pin(symbol, pin, transform);
+        }
+
+        if draw_properties {
+            for property in &symbol.properties {
+                let (Some(position), Some(effects)) = (&property.position, &property.text_effects) else {
+                    continue;
+                };
+                let angle = position.angle.unwrap_or_default();
+                self.text(&property.value, map(&position.point()), angle, effects, transform.orientation);
+            }
+        }
+    }
+
+    /// Write a pin's line, with its number beside the line and its name past the end inside the body.
+    fn pin(&mut self, symbol: &Symbol, pin: &SymbolPin, transform: Transform) {
+        let color = self.theme.color.clone();
+        let angle = pin.position.angle.unwrap_or_default();
+        let (x, y) = (pin.position.x.0, pin.position.y.0);
+        let along = |distance: i64| {
+            let (dx, dy) = angle.rotate(distance, 0);
+            // Library symbols are Y-up, so the Y-down rotation turns the wrong way.
+            transform.apply(x + dx, y - dy)
+        };
+
+        let stroke = Stroke {
+            width: None,
+            line_style: None,
+            color: None,
+        };
+        self.path(&[along(0), along(pin.length.0)], &stroke, None, &color);
+
+        let orientation = ParentOrientation {
+            angle: transform.orientation.angle + angle,
+            ..transform.orientation
+        };
+
+        if !symbol.pin_numbers.hide {
+            let mut effects = pin.number.text_effects.clone();
+            effects.justify = Some(TextJustify {
+                horiz_justify: HorizJustify::Center,
+                vert_justify: VertJustify::Bottom,
+                mirror: false,
+            });
+            self.text(&pin.number.number, along(pin.length.0 / 2), Angle::default(), &effects, orientation);
+        }
+
+        if !symbol.pin_names.hide && pin.name.name != "~" {
+            let mut effects = pin.name.text_effects.clone();
+            effects.justify = Some(TextJustify {
+                horiz_justify: HorizJustify::Left,
+                vert_justify: VertJustify::Center,
+                mirror: false,
+            });
+            let at = along(pin.length.0 + symbol.pin_names.offset);
+            self.text(&pin.name.name, at, Angle::default(), &effects, orientation);
+        }
+    }
+
+    /// Return the width to draw a stroke at.
+    fn stroke_width(&self, stroke: &Stroke) -> i64 {
+        match stroke.width {
+            Some(width) if width > Nm::ZERO => width.0,
+            _ => self.options.default_line_width,
+        }
+    }
+
+    /// Return the `stroke` attributes for a stroke, drawn in `color` unless it has its own.
+    fn stroke_attrs(&self, stroke: &Stroke, color: &str) -> String {
+        let width = self.stroke_width(stroke);
+        let color = stroke.color.as_ref().filter(|color| color.alpha != Some(0.0)).map_or(color.to_string(), css_color);
+        let mut attrs = format!(r#"stroke="{color}" stroke-width="{}" stroke-linecap="round""#, mm(width));
+
+        let dash = match stroke.line_style.as_ref().unwrap_or(&LineStyle::Default) {
+            LineStyle::Dash => Some(vec![5, 3]),
+            LineStyle::DashDot => Some(vec![5, 3, 1, 3]),
+            LineStyle::DashDotDot => Some(vec![5, 3, 1, 3, 1, 3]),
+            LineStyle::Dot => Some(vec![1, 3]),
+            LineStyle::Default | LineStyle::Solid => None,
+        };
+        if let Some(dash) = dash {
+            let dash: Vec<String> = dash.into_iter().map(|length| mm(length * width)).collect();
+            let _ = write!(attrs, r#" stroke-dasharray="{}""#, dash.join(" "));
+        }
+
+        attrs
+    }
+
+    /// Return the `fill` attributes for a fill. Hatched fills are drawn unfilled.
+    fn fill_attrs(&self, fill: Option<&Fill>, stroke: &Stroke, color: &str) -> String {
+        let fill_color = match fill.map(|fill| (&fill.fill_type, fill.color.as_ref())) {
+            Some((FillType::Outline, _)) => stroke.color.as_ref().map_or(color.to_string(), css_color),
+            Some((FillType::Background, _)) => self.theme.background_color.clone(),
+            Some((FillType::Color, Some(fill_color))) => css_color(fill_color),
+            _ => "none".to_string(),
+        };
+        format!(r#"fill="{fill_color}""#)
+    }
+
+    /// Draw the border of a drawing sheet frame and extend the document to its whole page.
+    fn frame(&mut self, frame: &WorksheetFrame) {
+        let origin = frame.origin();
+        let size = frame.drawing_size();
+        let (left, top) = (origin.x.0, origin.y.0);
+        let (right, bottom) = (left + size.width.0, top + size.height.0);
+        let stroke = Stroke {
+            width: None,
+            line_style: None,
+            color: None,
+        };
+
+        let color = self.theme.color.clone();
+        self.path(&[(left, top), (right, top), (right, bottom), (left, bottom), (left, top)], &stroke, None, &color);
+        self.page = Some(BoundingBox {
+            min_x: 0,
+            min_y: 0,
+            max_x: frame.page.width.0,
+            max_y: frame.page.height.0,
+        });
+    }
+
+    /// Return the finished document. A document with a page spans the page, growing only to
+    /// include anything drawn off it; otherwise it is cropped to its contents and the margin.
+    fn finish(self) -> String {
+        let bbox = match (self.page, self.bbox) {
+            (Some(page), Some(bbox)) => page.union(bbox),
+            (Some(page), None) => page,
+            (None, bbox) => bbox.unwrap_or(BoundingBox::from_point(0, 0)).inflated(self.options.margin),
+        };
+        let (width, height) = (mm(bbox.width()), mm(bbox.height()));
+
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}mm\" height=\"{height}mm\" viewBox=\"{} {} {width} {height}\">\n{}</svg>\n",
+            mm(bbox.min_x),
+            mm(bbox.min_y),
+            self.body
+        )
+    }
+}
+
+/// Draw a library symbol: its graphics, pins, and visible properties.
+///
+/// The symbol is drawn as KiCad's symbol editor shows it, with its Y-up coordinates flipped so that
+/// up is toward the top of the image. A multi-unit symbol is drawn as its first unit.
+pub fn symbol_to_svg(symbol: &Symbol, config: &Config) -> String {
+    let mut writer = SvgWriter::new(config);
+    writer.symbol(symbol, 1, Transform::library(), true);
+    writer.finish()
+}
+
+/// Draw a schematic: its wires, buses, bus entries, junctions, no-connect flags, labels, text, and
+/// placed symbols with their visible properties.
+///
+/// Symbols are drawn from the schematic's embedded library symbols; a placed symbol whose library
+/// symbol is missing or cannot be converted is drawn as its properties alone.
+pub fn schematic_to_svg(schematic: &Schematic, config: &Config) -> String {
+    let (options, theme) = (&config.svg, &config.theme);
+    let mut writer = SvgWriter::new(config);
+    let screen = Transform::default();
+    let point = |position: &Position| (position.x.0, position.y.0);
+    let xy = |point: &Point| (point.x.0, point.y.0);
+
+    for polyline in &schematic.polylines {
+        let points: Vec<_> = polyline.points.points.iter().map(xy).collect();
+        writer.path(&points, &polyline.stroke, None, &theme.color);
+    }
+
+    for wire in &schematic.wires {
+        let points: Vec<_> = wire.points.points.iter().map(xy).collect();
+        writer.path(&points, &wire.stroke, None, &theme.wire_color);
+    }
+
+    for bus in &schematic.buses {
+        let mut stroke = bus.stroke.clone();
+        stroke.width =
+            Some(stroke.width.filter(|&width| width > Nm::ZERO).unwrap_or(Nm(options.default_line_width * 3)));
+        let points: Vec<_> = bus.points.points.iter().map(xy).collect();
+        writer.path(&points, &stroke, None, &theme.bus_color);
+    }
+
+    for entry in &schematic.bus_entries {
+        let start = point(&entry.position);
+        let end = (start.0 + entry.size.x.0, start.1 + entry.size.y.0);
+        writer.path(&[start, end], &entry.stroke, None, &theme.wire_color);
+    }
+
+    for junction in &schematic.junctions {
+        let diameter = if junction.diameter > 0 {
+            junction.diameter as i64
+        } else {
+            options.default_line_width * 6
+        };
+        let color = if junction.color.alpha == Some(0.0) {
+            theme.wire_color.clone()
+        } else {
+            css_color(&junction.color)
+        };
+        let fill = Fill {
+            fill_type: FillType::Outline,
+            color: None,
+        };
+        let stroke = Stroke {
+            width: Some(Nm::ZERO),
+            line_style: None,
+            color: None,
+        };
+        writer.circle(point(&junction.position), diameter / 2, &stroke, Some(&fill), &color);
+    }
+
+    for no_connect in &schematic.no_connects {
+        let (x, y) = point(&no_connect.position);
+        let arm = 635_000;
+        let stroke = Stroke {
+            width: None,
+            line_style: None,
+            color: None,
+        };
+        writer.path(&[(x - arm, y - arm), (x + arm, y + arm)], &stroke, None, &theme.no_connect_color);
+        writer.path(&[(x - arm, y + arm), (x + arm, y - arm)], &stroke, None, &theme.no_connect_color);
+    }
+
+    for label in &schematic.labels {
+        let angle = label.position.angle.unwrap_or_default();
+        writer.text(&label.text, point(&label.position), angle, &label.text_effects, screen.orientation);
+    }
+
+    for label in &schematic.global_labels {
+        let angle = label.position.angle.unwrap_or_default();
+        writer.text(&label.text, point(&label.position), angle, &label.text_effects, screen.orientation);
+    }
+
+    for text in &schematic.texts {
+        let angle = text.position.angle.unwrap_or_default();
+        writer.text(&text.text, point(&text.position), angle, &text.text_effects, screen.orientation);
+    }
+
+    if let (Some(margins), Some(paper)) = (&options.frame, &schematic.paper) {
+        writer.frame(&WorksheetFrame::new(paper, margins.clone()));
+    }
+
+    let lib_symbols = schematic.lib_symbols.get().ok();
+    for instance in &schematic.symbols {
+        let name = instance.lib_name.as_deref().unwrap_or(&instance.lib_id);
+        if let Some(symbol) = lib_symbols.and_then(|symbols| symbols.iter().find(|symbol| symbol.id == name)) {
+            writer.symbol(symbol, instance.unit, Transform::placed(instance), false);
+        }
+
+        for property in &instance.properties {
+            let (Some(position), Some(effects)) = (&property.position, &property.text_effects) else {
+                continue;
+            };
+            let angle = position.angle.unwrap_or_default();
+            writer.text(&property.value, point(position), angle, effects, screen.orientation);
+        }
+    }
+
+    writer.finish()
+}
+
+/// Return the extreme points of a circle, used to bound the arcs drawn on it.
+fn arc_extent(center: (f64, f64), radius: i64) -> BoundingBox {
+    let (x, y) = (center.0.round() as i64, center.1.round() as i64);
+    BoundingBox::from_point(x, y).inflated(radius)
+}
+
+/// Format nanometers as millimeters, the drawing's user unit.
+fn mm(nm: i64) -> String {
+    Decimal::from_nm(nm).normalized().to_string()
+}
+
+/// Return a color as a CSS color.
+fn css_color(color: &Color) -> String {
+    let channel = |value: f64| value.round().clamp(0.0, 255.0) as u8;
+    format!(
+        "rgba({}, {}, {}, {})",
+        channel(color.red),
+        channel(color.green),
+        channel(color.blue),
+        color.alpha.unwrap_or(1.0)
+    )
+}
+
+/// Escape text for use in an SVG document.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::parse_str};
+
+    #[test]
+    fn test_symbol_to_svg() {
+        let symbol: Symbol = parse_str(
+            r#"(symbol "Device:R"
+                (property "Reference" "R" (at 2.032 0 90) (effects (font (size 1.27 1.27))))
+                (rectangle (start -1.016 -2.54) (end 1.016 2.54) (stroke (width 0.254) (type default))
+                    (fill (type background)))
+                (pin passive line (at 0 3.81 270) (length 1.27) (name "~" (effects (font (size 1.27 1.27))))
+                    (number "1" (effects (font (size 1.27 1.27))))))"#,
+        )
+        .unwrap();
+
+        let svg = symbol_to_svg(&symbol, &Config::default());
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+
+        // The body is flipped to Y-down and filled with the background color.
+        assert!(svg.contains(r##"<path d="M-1.016 2.54 L1.016 2.54 L1.016 -2.54 L-1.016 -2.54 Z" stroke="#840000" stroke-width="0.254" stroke-linecap="round" fill="#ffffc2"/>"##));
+
+        // The pin runs down from its connection point at the top toward the body.
+        assert!(svg.contains(r#"<path d="M0 -3.81 L0 -2.54""#));
+        assert!(svg.contains(">1</text>"));
+        assert!(svg.contains(r#"transform="rotate(-90 2.032 0)">R</text>"#));
+        assert!(!svg.contains(">~</text>"));
+    }
+
+    #[test]
+    fn test_schematic_frame() {
+        let schematic: Schematic = parse_str(
+            r#"(kicad_sch (version 20231120) (generator "eeschema") (uuid "0f6f6d70-9b4c-4d5e-8a8a-1c2d3e4f5a6b") (paper "A4")
+                (wire (pts (xy 50.8 50.8) (xy 76.2 50.8)) (stroke (width 0) (type default))
+                    (uuid "5b8f2f2e-2a8e-4d4b-9a57-0d6a1c3e9f10")))"#,
+        )
+        .unwrap();
+
+        // Without a frame, the drawing is cropped to the wire and the margin.
+        let svg = schematic_to_svg(&schematic, &Config::default());
+        assert!(svg.contains(r#"viewBox="48.1838 48.1838 30.6324 5.2324""#), "{svg}");
+
+        // With a frame, the drawing spans the page and the frame is drawn inside the margins.
+        let config = Config::builder()
+            .svg_options(SvgOptions {
+                frame: Some(WorksheetMargins::default()),
+                ..SvgOptions::default()
+            })
+            .build();
+        let svg = schematic_to_svg(&schematic, &config);
+        assert!(svg.contains(r#"width="297mm" height="210mm" viewBox="0 0 297 210""#), "{svg}");
+        assert!(svg.contains(r##"<path d="M10 10 L287 10 L287 200 L10 200 L10 10" stroke="#840000""##), "{svg}");
+    }
+}
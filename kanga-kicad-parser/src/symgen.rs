@@ -0,0 +1,311 @@
+use {
+    crate::{
+        common::{
+            sexpr_list, Angle, Fill, FillType, Font, Nm, Point, Position, Size, Stroke, Symbol, SymbolGraphic,
+            SymbolGraphicRectangle, SymbolPin, SymbolPinElectricalType, SymbolPinGraphicalStyle, SymbolPinName,
+            SymbolPinNameDefaults, SymbolPinNumber, SymbolPinNumberDefaults, SymbolProperty, TextEffects,
+        },
+        writer::to_kicad_string,
+        ParseError,
+    },
+    lexpr::Value,
+    serde::{Deserialize, Serialize},
+    std::str::FromStr,
+};
+
+/// The file format version written to generated symbol libraries.
+pub const SYMBOL_LIB_VERSION: i64 = 20231120;
+
+/// The side of the symbol body a pin is placed on.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PinSide {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// One row of a pin table.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PinTableRow {
+    /// The pin number, e.g. `A1` or `12`.
+    pub number: String,
+
+    /// The pin name, e.g. `PA0`.
+    pub name: String,
+
+    /// The electrical type of the pin.
+    pub electrical_type: SymbolPinElectricalType,
+
+    /// The side of the body to place the pin on. Pins on each side are placed in table order, from
+    /// top to bottom or left to right.
+    pub side: PinSide,
+}
+
+/// Options controlling the layout of a generated symbol. Distances are in nanometers.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SymbolGeneratorOptions {
+    /// The distance between adjacent pins. Pin positions are multiples of this value.
+    pub pin_spacing: i64,
+
+    /// The length of each pin.
+    pub pin_length: i64,
+
+    /// The height and width of pin name and number text.
+    pub text_size: u64,
+
+    /// The prefix of the reference designator, e.g. `U`.
+    pub reference_prefix: String,
+}
+
+impl Default for SymbolGeneratorOptions {
+    fn default() -> Self {
+        Self {
+            pin_spacing: 2_540_000,
+            pin_length: 2_540_000,
+            text_size: 1_270_000,
+            reference_prefix: "U".to_string(),
+        }
+    }
+}
+
+impl FromStr for PinSide {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "left" | "l" => Ok(Self::Left),
+            "right" | "r" => Ok(Self::Right),
+            "top" | "t" => Ok(Self::Top),
+            "bottom" | "b" => Ok(Self::Bottom),
+            _ => Err(ParseError::InvalidElement("pin side".to_string(), format!("{s:?}"))),
+        }
+    }
+}
+
+/// Parse a pin table from CSV with `number,name,type,side` columns and a header row.
+///
+/// Types use KiCad's names (`input`, `power_in`, ...) and sides are `left`, `right`, `top`, or
+/// `bottom`. Fields may be quoted.
+pub fn parse_pin_table(csv: &str) -> Result<Vec<PinTableRow>, ParseError> {
+    let mut rows = Vec::new();
+
+    for (i, line) in csv.lines().enumerate().skip(1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let row = format!("pin table line {}", i + 1);
+        let fields = split_csv_line(line);
+        let [number, name, electrical_type, side] = &fields[..] else {
+            return Err(ParseError::InvalidElement(row, format!("expected 4 fields, found {}", fields.len())));
+        };
+
+        let electrical_type = SymbolPinElectricalType::from_str(electrical_type)
+            .map_err(|_| ParseError::InvalidElement(row.clone(), format!("invalid pin type {electrical_type:?}")))?;
+        let side = side.parse().map_err(|e: ParseError| e.within(row))?;
+
+        rows.push(PinTableRow {
+            number: number.clone(),
+            name: name.clone(),
+            electrical_type,
+            side,
+        });
+    }
+
+    Ok(rows)
+}
+
+/// Build a symbol with a rectangular body and the pins from a pin table.
+///
+/// The body is sized to fit the pins on each side and the longest pin names. All pin connection
+/// points lie on multiples of [`SymbolGeneratorOptions::pin_spacing`] from the symbol origin.
+pub fn generate_symbol(id: &str, pins: &[PinTableRow], options: &SymbolGeneratorOptions) -> Symbol {
+    let spacing = options.pin_spacing;
+    let count = |side| pins.iter().filter(|pin| pin.side == side).count() as i64;
+    let longest_name = |side| {
+        pins.iter().filter(|pin| pin.side == side).map(|pin| pin.name.chars().count()).max().unwrap_or_default() as i64
+    };
+
+    // Leave room for the names on opposite sides, assuming glyphs are about as wide as they are tall.
+    let name_width = (longest_name(PinSide::Left) + longest_name(PinSide::Right) + 2) * options.text_size as i64;
+    let half_width = round_up((count(PinSide::Top).max(count(PinSide::Bottom)) + 1) * spacing / 2, spacing)
+        .max(round_up(name_width / 2, spacing));
+    let half_height = round_up((count(PinSide::Left).max(count(PinSide::Right)) + 1) * spacing / 2, spacing);
+
+    let text_effects = TextEffects {
+        font: Some(Font {
+            face: None,
+            size: Size::new(Nm(options.text_size as i64), Nm(options.text_size as i64)),
+            thickness: None,
+            bold: false,
+            italic: false,
+            line_spacing: None,
+            color: None,
+        }),
+        justify: None,
+        hide: false,
+    };
+
+    let mut symbol_pins = Vec::new();
+    for side in [PinSide::Left, PinSide::Right, PinSide::Top, PinSide::Bottom] {
+        for (i, pin) in pins.iter().filter(|pin| pin.side == side).enumerate() {
+            let step = (i as i64 + 1) * spacing;
+            let reach = options.pin_length;
+
+            // Library symbols use a Y-up coordinate system; the angle points from the connection
+            // point toward the body.
+            let (x, y, angle) = match side {
+                PinSide::Left => (-half_width - reach, half_height - step, 0.0),
+                PinSide::Right => (half_width + reach, half_height - step, 180.0),
+                PinSide::Top => (-half_width + step, half_height + reach, 270.0),
+                PinSide::Bottom => (-half_width + step, -half_height - reach, 90.0),
+            };
+
+            symbol_pins.push(SymbolPin {
+                electrical_type: pin.electrical_type,
+                graphical_style: SymbolPinGraphicalStyle::Line,
+                position: Position {
+                    x: Nm(x),
+                    y: Nm(y),
+                    angle: Some(Angle::from_degrees(angle)),
+                },
+                length: Nm(options.pin_length),
+                name: SymbolPinName {
+                    name: pin.name.clone(),
+                    text_effects: text_effects.clone(),
+                },
+                number: SymbolPinNumber {
+                    number: pin.number.clone(),
+                    text_effects: text_effects.clone(),
+                },
+                hide: false,
+            });
+        }
+    }
+
+    let property = |key: &str, value: &str, y: i64, hide: bool| SymbolProperty {
+        key: key.to_string(),
+        value: value.to_string(),
+        identifier: None,
+        position: Some(Position {
+            x: Nm(-half_width),
+            y: Nm(y),
+            angle: Some(Angle::ZERO),
+        }),
+        text_effects: Some(TextEffects {
+            hide,
+            ..text_effects.clone()
+        }),
+        show_name: false,
+        do_not_autoplace: false,
+    };
+
+    let label_offset = half_height + spacing / 2;
+    Symbol {
+        id: id.to_string(),
+        extends: None,
+        power: false,
+        pin_numbers: SymbolPinNumberDefaults::default(),
+        pin_names: SymbolPinNameDefaults {
+            offset: spacing / 2,
+            hide: false,
+        },
+        exclude_from_sim: Some(false),
+        in_bom: Some(true),
+        on_board: Some(true),
+        embedded_fonts: None,
+        keywords: Vec::new(),
+        description: None,
+        fp_filters: Vec::new(),
+        properties: vec![
+            property("Reference", &options.reference_prefix, label_offset, false),
+            property("Value", id, -label_offset, false),
+            property("Footprint", "", -label_offset - spacing, true),
+            property("Datasheet", "", -label_offset - spacing, true),
+        ],
+        graphics: vec![SymbolGraphic::Rectangle(SymbolGraphicRectangle {
+            start: Point::new(Nm(-half_width), Nm(half_height)),
+            end: Point::new(Nm(half_width), Nm(-half_height)),
+            stroke: Stroke {
+                width: Some(Nm(254_000)),
+                line_style: None,
+                color: None,
+            },
+            fill: Fill {
+                fill_type: FillType::Background,
+                color: None,
+            },
+        })],
+        pins: symbol_pins,
+        units: Vec::new(),
+    }
+}
+
+/// Write symbols as the contents of a `.kicad_sym` library file.
+pub fn write_symbol_library(symbols: &[Symbol]) -> String {
+    let mut items = vec![
+        sexpr_list("version", [Value::from(SYMBOL_LIB_VERSION)]),
+        sexpr_list("generator", [Value::symbol("kanga")]),
+    ];
+    items.extend(symbols.iter().map(Value::from));
+    to_kicad_string(&sexpr_list("kicad_symbol_lib", items))
+}
+
+/// Round a non-negative value up to a multiple of `step`.
+fn round_up(value: i64, step: i64) -> i64 {
+    (value + step - 1) / step * step
+}
+
+/// Split a CSV line into fields, removing quotes.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+
+    fields.into_iter().map(|field| field.trim().to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_symbol() {
+        let pins = parse_pin_table(
+            "number,name,type,side\n1,VDD,power_in,top\n2,PA0,bidirectional,left\n3,\"PA1\",bidirectional,left\n\
+             4,GND,power_in,bottom\n5,NRST,input,right\n",
+        )
+        .unwrap();
+        assert_eq!(pins.len(), 5);
+        assert!(parse_pin_table("number,name,type,side\n1,VDD,power,top\n").is_err());
+
+        let symbol = generate_symbol("MCU", &pins, &SymbolGeneratorOptions::default());
+        assert!(symbol.pins.iter().all(|pin| pin.position.x.0 % 2_540_000 == 0 && pin.position.y.0 % 2_540_000 == 0));
+        assert_eq!(symbol.pins[0].name.name, "PA0");
+        assert_eq!(symbol.pins[0].position.y, Nm(2_540_000));
+
+        let library = write_symbol_library(&[symbol]);
+        assert!(library.starts_with("(kicad_symbol_lib\n\t(version 20231120)\n\t(generator kanga)\n\t(symbol \"MCU\""));
+
+        let value = lexpr::from_str(&library).unwrap();
+        let element =
+            value.list_iter().unwrap().find(|e| e.as_cons().is_some_and(|c| c.car().as_symbol() == Some("symbol")));
+        let parsed = Symbol::try_from(element.unwrap()).unwrap();
+        assert_eq!(parsed.pins.len(), 5);
+        assert_eq!(parsed.graphics.len(), 1);
+    }
+}
@@ -0,0 +1,139 @@
+use {
+    crate::{common::Symbol, impl_try_from_cons_value, version::FormatVersion, LexprExt, ParseError, ParseOptions},
+    lexpr::{Cons, Value},
+};
+
+/// KiCad symbol library (`.kicad_sym`) file format.
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-symbol-lib/index.html)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename = "kicad_symbol_lib", deny_unknown_fields))]
+pub struct SymbolLibrary {
+    /// The library version, as a YYYYMMDD integer.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub version: Option<i64>,
+
+    /// The program used to generate this library (`kicad_symbol_editor` for KiCad's symbol editor).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "String::is_empty"))]
+    pub generator: String,
+
+    /// The version of the program used to generate this library.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "String::is_empty"))]
+    pub generator_version: String,
+
+    /// The symbols in the library, in file order.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub symbols: Vec<Symbol>,
+}
+
+impl SymbolLibrary {
+    /// Return the symbol with the given name, without a library nickname.
+    pub fn symbol(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.iter().find(|symbol| symbol.id == name)
+    }
+
+    /// Return the KiCad release whose format the library declares, if it declares a version.
+    pub fn format_version(&self) -> Option<FormatVersion> {
+        self.version.map(FormatVersion::nearest)
+    }
+}
+
+impl TryFrom<&Cons> for SymbolLibrary {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let mut library = Self {
+            version: None,
+            generator: String::new(),
+            generator_version: String::new(),
+            symbols: Vec::new(),
+        };
+
+        let rest = cons.expect_cons_with_symbol_head("kicad_symbol_lib")?;
+        for element in rest.list_iter().into_iter().flatten() {
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "version" => {
+                    let version = cdr.expect_cons_with_any_int_head()?.0;
+                    FormatVersion::check(Some(version), FormatVersion::Kicad6, &ParseOptions::default())?;
+                    library.version = Some(version);
+                }
+                "generator" => library.generator = text_arg(cdr)?,
+                "generator_version" => library.generator_version = text_arg(cdr)?,
+                "symbol" => library.symbols.push(Symbol::try_from(element)?),
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        Ok(library)
+    }
+}
+
+impl_try_from_cons_value!(SymbolLibrary);
+
+/// Return the single argument of a header element, which KiCad writes as a string or, before
+/// KiCad 8, a bare symbol.
+fn text_arg(args: &Value) -> Result<String, ParseError> {
+    let value = args.expect_cons()?.car();
+    value
+        .as_str()
+        .or_else(|| value.as_symbol())
+        .map(str::to_string)
+        .ok_or_else(|| ParseError::Unexpected(value.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{common::SymbolPinElectricalType, parse_str},
+        lexpr::sexp,
+    };
+
+    #[test]
+    fn test_symbol_library() {
+        let library = sexp!((kicad_symbol_lib (version 20231120) (generator "kicad_symbol_editor") (generator_version "8.0")
+            (symbol "R" (property "Reference" "R" (at 0 0 0) (effects (font (size 1.27 1.27)))))
+            (symbol "C" (property "Reference" "C" (at 0 0 0) (effects (font (size 1.27 1.27))))
+                (property "ki_keywords" "cap capacitor" (at 0 0 0) (effects (font (size 1.27 1.27)) hide))
+                (property "ki_description" "Unpolarized capacitor" (at 0 0 0) (effects (font (size 1.27 1.27)) hide))
+                (property "ki_fp_filters" "C_*" (at 0 0 0) (effects (font (size 1.27 1.27)) hide)))));
+
+        let library = SymbolLibrary::try_from(&library).unwrap();
+        assert_eq!(library.version, Some(20231120));
+        assert_eq!(library.generator, "kicad_symbol_editor");
+        assert_eq!(library.symbols.len(), 2);
+
+        // The hidden `ki_` properties become fields and are written back as properties.
+        let capacitor = library.symbol("C").unwrap();
+        assert_eq!(capacitor.keywords, ["cap", "capacitor"]);
+        assert_eq!(capacitor.description(), Some("Unpolarized capacitor"));
+        assert_eq!(capacitor.fp_filters, ["C_*"]);
+        assert_eq!(capacitor.properties.len(), 1);
+        let written = Value::from(capacitor);
+        assert_eq!(Symbol::try_from(&written).unwrap().fp_filters, ["C_*"]);
+
+        let legacy = sexp!((kicad_symbol_lib (version 20211014) (generator kicad_symbol_editor) (sheet)));
+        assert!(SymbolLibrary::try_from(&legacy).is_err());
+    }
+    #[test]
+    fn test_symbol_library_file() {
+        let library: SymbolLibrary = parse_str(include_str!("../tests/power.kicad_sym")).unwrap();
+        assert_eq!(library.generator, "kicad_symbol_editor");
+        assert_eq!(library.symbols.len(), 4);
+
+        let ground = library.symbol("GND").unwrap();
+        assert!(ground.power);
+        assert_eq!(ground.description(), Some("Power symbol creates a global label with name \"GND\" , ground"));
+        assert_eq!(ground.units.iter().map(|unit| unit.id.as_str()).collect::<Vec<_>>(), ["GND_0_1", "GND_1_1"]);
+
+        let pins: Vec<_> = ground.unit_pins(Some(1)).collect();
+        assert_eq!(pins.len(), 1);
+        assert_eq!(pins[0].number.number, "1");
+        assert_eq!(pins[0].electrical_type, SymbolPinElectricalType::PowerIn);
+        assert!(ground.pin_names.hide);
+    }
+}
@@ -0,0 +1,178 @@
+use {
+    crate::{
+        vars::{expand_strict, EnvVars, VarProvider},
+        ParseError,
+    },
+    std::path::{Path, PathBuf},
+};
+
+#[cfg(feature = "json")]
+use crate::{vars::ProjectVars, Project};
+
+/// The variable KiCad sets to the directory containing the project.
+pub const PROJECT_DIR_VAR: &str = "KIPRJMOD";
+
+/// Resolves library and 3D model URIs, as written in library tables and documents, to paths.
+///
+/// URIs are resolved the same way on every platform: `\` and `/` are both separators, `${VAR}`
+/// and `$(VAR)` are expanded, and relative paths are resolved against the project directory. A
+/// URI written on Windows therefore resolves on POSIX systems and vice versa.
+///
+/// `${KIPRJMOD}` always expands to the project directory; other variables come from a
+/// [`VarProvider`], the process environment by default.
+#[derive(Clone, Debug)]
+pub struct UriResolver<V = EnvVars> {
+    project_dir: PathBuf,
+    vars: V,
+}
+
+impl UriResolver {
+    /// Create a resolver for a project directory that reads variables from the environment.
+    pub fn new<P>(project_dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self {
+            project_dir: project_dir.into(),
+            vars: EnvVars,
+        }
+    }
+
+    /// Create a resolver for a loaded project that reads the project's text variables, then the
+    /// environment.
+    #[cfg(feature = "json")]
+    pub fn for_project(project: &Project) -> UriResolver<(ProjectVars, EnvVars)> {
+        Self::new(&project.dir).with_vars((ProjectVars::new(project), EnvVars))
+    }
+}
+
+impl<V: VarProvider> UriResolver<V> {
+    /// Replace the source of variables.
+    pub fn with_vars<W: VarProvider>(self, vars: W) -> UriResolver<W> {
+        UriResolver {
+            project_dir: self.project_dir,
+            vars,
+        }
+    }
+
+    /// Return the directory relative URIs are resolved against.
+    pub fn project_dir(&self) -> &Path {
+        &self.project_dir
+    }
+
+    /// Expand the variables in a URI, leaving it otherwise unchanged.
+    pub fn expand(&self, uri: &str) -> Result<String, ParseError> {
+        expand_strict(uri, self)
+    }
+
+    /// Resolve a URI to a path, resolving relative paths against the project directory.
+    pub fn resolve(&self, uri: &str) -> Result<PathBuf, ParseError> {
+        self.resolve_from(uri, &self.project_dir)
+    }
+
+    /// Resolve a URI to a path, resolving relative paths against `base`. This is used for URIs
+    /// that KiCad resolves against the referencing document, such as sheet files.
+    pub fn resolve_from(&self, uri: &str, base: &Path) -> Result<PathBuf, ParseError> {
+        let expanded = normalize_separators(&self.expand(uri)?);
+        if is_absolute(&expanded) {
+            return Ok(normalize_components(&expanded));
+        }
+
+        let base = normalize_separators(&base.to_string_lossy());
+        if base.is_empty() {
+            Ok(normalize_components(&expanded))
+        } else {
+            Ok(normalize_components(&format!("{}/{expanded}", base.trim_end_matches('/'))))
+        }
+    }
+}
+
+impl<V: VarProvider> VarProvider for UriResolver<V> {
+    fn var(&self, name: &str) -> Option<String> {
+        if name == PROJECT_DIR_VAR {
+            Some(self.project_dir.to_string_lossy().to_string())
+        } else {
+            self.vars.var(name)
+        }
+    }
+}
+
+/// Replace Windows `\` separators with `/`.
+pub fn normalize_separators(uri: &str) -> String {
+    uri.replace('\\', "/")
+}
+
+/// Indicates whether a URI with `/` separators is absolute on any platform: a POSIX root, a UNC
+/// share, or a Windows drive.
+pub fn is_absolute(uri: &str) -> bool {
+    let bytes = uri.as_bytes();
+    uri.starts_with('/') || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+}
+
+/// Build a path from a URI with `/` separators, removing `.` components and `..` components that
+/// follow a named one.
+fn normalize_components(uri: &str) -> PathBuf {
+    let (prefix, rest) = match uri.strip_prefix("//") {
+        Some(rest) => ("//", rest),
+        None => match uri.strip_prefix('/') {
+            Some(rest) => ("/", rest),
+            None => ("", uri),
+        },
+    };
+
+    let mut components: Vec<&str> = Vec::new();
+    for component in rest.split('/') {
+        match component {
+            "" | "." => (),
+            ".." if components.last().is_some_and(|last| *last != ".." && !last.ends_with(':')) => {
+                components.pop();
+            }
+            ".." if !prefix.is_empty() => (),
+            _ => components.push(component),
+        }
+    }
+
+    let mut path = PathBuf::from(prefix);
+    for component in components {
+        // A drive letter needs its separator to stay absolute on Windows.
+        if component.ends_with(':') && path.as_os_str().is_empty() {
+            path.push(format!("{component}/"));
+        } else {
+            path.push(component);
+        }
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::vars::StaticVars};
+
+    #[test]
+    fn test_resolve() {
+        let vars = StaticVars::new().with("KICAD8_3DMODEL_DIR", "/usr/share/kicad/3d");
+        let resolver = UriResolver::new("/work/demo").with_vars(vars);
+
+        assert_eq!(
+            resolver.resolve("${KIPRJMOD}/libs/Demo.kicad_sym").unwrap(),
+            PathBuf::from("/work/demo/libs/Demo.kicad_sym")
+        );
+        assert_eq!(
+            resolver.resolve("$(KICAD8_3DMODEL_DIR)\\Resistor_SMD.3dshapes\\R_0603.wrl").unwrap(),
+            PathBuf::from("/usr/share/kicad/3d/Resistor_SMD.3dshapes/R_0603.wrl")
+        );
+        assert_eq!(
+            resolver.resolve("..\\shared\\.\\Common.pretty").unwrap(),
+            PathBuf::from("/work/shared/Common.pretty")
+        );
+        assert_eq!(
+            resolver.resolve_from("power.kicad_sch", Path::new("/work/demo/sub")).unwrap(),
+            PathBuf::from("/work/demo/sub/power.kicad_sch")
+        );
+        assert!(is_absolute("C:/kicad/share"));
+        assert_eq!(resolver.expand("cost $5").unwrap(), "cost $5");
+        assert!(
+            matches!(resolver.resolve("${MISSING}/x"), Err(ParseError::UndefinedVariable(name)) if name == "MISSING")
+        );
+    }
+}
@@ -0,0 +1,346 @@
+use {
+    crate::{
+        common::{SymbolProperty, TitleBlock},
+        sch::Schematic,
+        ParseError,
+    },
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        env,
+    },
+};
+
+#[cfg(feature = "json")]
+use crate::Project;
+
+/// A source of values for `${VAR}` references in paths and text.
+///
+/// Resolvers take a provider instead of reading the process environment, so that lookups can be
+/// scoped to a project, made deterministic in tests, or supplied on platforms without an
+/// environment such as WASM. A pair of providers looks up the first, then the second.
+pub trait VarProvider {
+    /// Return the value of a variable, or `None` if it is not defined.
+    fn var(&self, name: &str) -> Option<String>;
+}
+
+/// Variables from the process environment.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EnvVars;
+
+/// Variables defined by a project: `KIPRJMOD`, `PROJECTNAME`, and the text variables in the
+/// project settings.
+#[derive(Clone, Debug, Default)]
+pub struct ProjectVars {
+    vars: BTreeMap<String, String>,
+}
+
+/// A fixed set of variables.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StaticVars {
+    vars: BTreeMap<String, String>,
+}
+
+/// Expands text variables in the text, labels, and fields of a schematic, as KiCad does when it
+/// draws them.
+///
+/// A reference is looked up first among the fields of the element it appears in, so that a
+/// symbol field can refer to `${REFERENCE}` or `${VALUE}` and a sheet field to `${SHEETNAME}`;
+/// then among the title block variables, `${TITLE}`, `${ISSUE_DATE}`, `${REVISION}`,
+/// `${COMPANY}`, and `${COMMENT1}` through `${COMMENT9}`; and then in the provider, which usually
+/// holds the project's text variables.
+#[derive(Clone, Debug, Default)]
+pub struct TextVariableResolver<V> {
+    vars: V,
+    title_block: StaticVars,
+}
+
+/// The fields of an element, looked up by upper-cased name as KiCad does.
+struct FieldVars<'a>(&'a [SymbolProperty]);
+
+impl VarProvider for EnvVars {
+    fn var(&self, name: &str) -> Option<String> {
+        env::var(name).ok()
+    }
+}
+
+#[cfg(feature = "json")]
+impl ProjectVars {
+    /// Collect the variables defined by a project.
+    pub fn new(project: &Project) -> Self {
+        let mut vars = BTreeMap::new();
+        vars.insert("KIPRJMOD".to_string(), project.dir.to_string_lossy().to_string());
+        vars.insert("PROJECTNAME".to_string(), project.name.clone());
+
+        vars.extend(project.text_variables());
+
+        Self {
+            vars,
+        }
+    }
+}
+
+impl VarProvider for ProjectVars {
+    fn var(&self, name: &str) -> Option<String> {
+        self.vars.get(name).cloned()
+    }
+}
+
+impl StaticVars {
+    /// Create an empty set of variables.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Define a variable.
+    pub fn with<N, V>(mut self, name: N, value: V) -> Self
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        self.vars.insert(name.into(), value.into());
+        self
+    }
+}
+
+impl<N, V> FromIterator<(N, V)> for StaticVars
+where
+    N: Into<String>,
+    V: Into<String>,
+{
+    fn from_iter<I: IntoIterator<Item = (N, V)>>(iter: I) -> Self {
+        Self {
+            vars: iter.into_iter().map(|(name, value)| (name.into(), value.into())).collect(),
+        }
+    }
+}
+
+impl VarProvider for StaticVars {
+    fn var(&self, name: &str) -> Option<String> {
+        self.vars.get(name).cloned()
+    }
+}
+
+impl<V: VarProvider> TextVariableResolver<V> {
+    /// Create a resolver that looks up variables in a provider.
+    pub fn new(vars: V) -> Self {
+        Self {
+            vars,
+            title_block: StaticVars::new(),
+        }
+    }
+
+    /// Create a resolver for a schematic, defining the variables of its title block. A title block
+    /// that cannot be converted defines no variables.
+    pub fn for_schematic(schematic: &Schematic, vars: V) -> Self {
+        let resolver = Self::new(vars);
+        match schematic.title_block.as_ref().and_then(|title_block| title_block.get().ok()) {
+            Some(title_block) => resolver.title_block(title_block),
+            None => resolver,
+        }
+    }
+
+    /// Define the variables of a title block.
+    pub fn title_block(mut self, title_block: &TitleBlock) -> Self {
+        let mut vars = StaticVars::new()
+            .with("TITLE", &title_block.title)
+            .with("ISSUE_DATE", &title_block.date)
+            .with("REVISION", &title_block.rev)
+            .with("COMPANY", &title_block.company);
+
+        for comment in &title_block.comments {
+            vars = vars.with(format!("COMMENT{}", comment.number), &comment.text);
+        }
+
+        self.title_block = vars;
+        self
+    }
+
+    /// Expand the variables in text that is not part of an element with fields.
+    pub fn resolve(&self, text: &str) -> String {
+        expand_text(text, &(&self.title_block, &self.vars))
+    }
+
+    /// Expand the variables in text belonging to an element with fields.
+    pub fn resolve_with_fields(&self, text: &str, fields: &[SymbolProperty]) -> String {
+        expand_text(text, &(FieldVars(fields), (&self.title_block, &self.vars)))
+    }
+
+    /// Expand the variables in every text item, label, and symbol and sheet field of a schematic.
+    /// References to undefined variables are left as written.
+    pub fn resolve_schematic(&self, schematic: &mut Schematic) {
+        for text in &mut schematic.texts {
+            text.text = self.resolve(&text.text);
+        }
+
+        for label in &mut schematic.labels {
+            label.text = self.resolve(&label.text);
+        }
+
+        for label in &mut schematic.global_labels {
+            label.text = self.resolve(&label.text);
+        }
+
+        let field_lists = schematic.symbols.iter_mut().map(|symbol| &mut symbol.properties);
+        for fields in field_lists.chain(schematic.sheets.iter_mut().map(|sheet| &mut sheet.properties)) {
+            // Fields refer to each other as written, not as already expanded.
+            let original = fields.clone();
+            for field in fields.iter_mut() {
+                field.value = self.resolve_with_fields(&field.value, &original);
+            }
+        }
+    }
+
+    /// Return the names of the variables referred to in a schematic that none of its elements,
+    /// its title block, or the provider define.
+    pub fn unresolved(&self, schematic: &Schematic) -> BTreeSet<String> {
+        let mut names = BTreeSet::new();
+        let no_fields: &[SymbolProperty] = &[];
+
+        let texts = schematic.texts.iter().map(|text| (text.text.as_str(), no_fields));
+        let labels = schematic.labels.iter().map(|label| (label.text.as_str(), no_fields));
+        let global_labels = schematic.global_labels.iter().map(|label| (label.text.as_str(), no_fields));
+        let field_lists = schematic.symbols.iter().map(|symbol| &symbol.properties);
+        let fields = field_lists
+            .chain(schematic.sheets.iter().map(|sheet| &sheet.properties))
+            .flat_map(|fields| fields.iter().map(move |field| (field.value.as_str(), fields.as_slice())));
+
+        for (text, fields) in texts.chain(labels).chain(global_labels).chain(fields) {
+            let vars = (FieldVars(fields), (&self.title_block, &self.vars));
+            let mut rest = text;
+            while let Some((_, _, name, after)) = next_reference(rest) {
+                if let Some(name) = name.filter(|name| vars.var(name).is_none()) {
+                    names.insert(name.to_string());
+                }
+                rest = after;
+            }
+        }
+
+        names
+    }
+}
+
+impl VarProvider for FieldVars<'_> {
+    fn var(&self, name: &str) -> Option<String> {
+        self.0.iter().find(|field| field.key.eq_ignore_ascii_case(name)).map(|field| field.value.clone())
+    }
+}
+
+impl<T: VarProvider + ?Sized> VarProvider for &T {
+    fn var(&self, name: &str) -> Option<String> {
+        (**self).var(name)
+    }
+}
+
+impl<A: VarProvider, B: VarProvider> VarProvider for (A, B) {
+    fn var(&self, name: &str) -> Option<String> {
+        self.0.var(name).or_else(|| self.1.var(name))
+    }
+}
+
+/// Expand `${VAR}` and `$(VAR)` references in text. References to undefined variables are left
+/// as written, as KiCad displays them.
+pub fn expand_text(text: &str, vars: &dyn VarProvider) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some((before, reference, name, after)) = next_reference(rest) {
+        result.push_str(before);
+        match name.and_then(|name| vars.var(name)) {
+            Some(value) => result.push_str(&value),
+            None => result.push_str(reference),
+        }
+        rest = after;
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Expand `${VAR}` and `$(VAR)` references in a path or URI, failing on undefined variables and
+/// unterminated references.
+pub fn expand_strict(text: &str, vars: &dyn VarProvider) -> Result<String, ParseError> {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some((before, reference, name, after)) = next_reference(rest) {
+        let Some(name) = name else {
+            return Err(ParseError::InvalidElement(format!("variable {reference}"), format!("unterminated in {text}")));
+        };
+
+        result.push_str(before);
+        result.push_str(&vars.var(name).ok_or_else(|| ParseError::UndefinedVariable(name.to_string()))?);
+        rest = after;
+    }
+
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Find the next variable reference, returning the text before it, the reference as written,
+/// the variable name, and the text after it. An unterminated reference runs to the end of the
+/// text and has no name.
+fn next_reference(text: &str) -> Option<(&str, &str, Option<&str>, &str)> {
+    let mut offset = 0;
+    loop {
+        let start = offset + text[offset..].find('$')?;
+        let close = match text[start + 1..].chars().next() {
+            Some('{') => '}',
+            Some('(') => ')',
+            _ => {
+                offset = start + 1;
+                continue;
+            }
+        };
+
+        let name_start = start + 2;
+        let (name, end) = match text[name_start..].find(close) {
+            Some(i) => (Some(&text[name_start..name_start + i]), name_start + i + 1),
+            None => (None, text.len()),
+        };
+        return Some((&text[..start], &text[start..end], name, &text[end..]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::parse_str};
+
+    #[test]
+    fn test_expand() {
+        let vars = StaticVars::new().with("REVISION", "B").with("LIB_DIR", "/libs");
+        let fallback: StaticVars = [("COMPANY", "Kanga")].into_iter().collect();
+        let chained = (&vars, fallback);
+
+        assert_eq!(
+            expand_text("Rev ${REVISION} by $(COMPANY) for $5 ${UNSET}", &chained),
+            "Rev B by Kanga for $5 ${UNSET}"
+        );
+        assert_eq!(expand_strict("${LIB_DIR}/Device.kicad_sym", &chained).unwrap(), "/libs/Device.kicad_sym");
+        assert!(
+            matches!(expand_strict("${UNSET}/x", &chained), Err(ParseError::UndefinedVariable(name)) if name == "UNSET")
+        );
+        assert_eq!(expand_text("${LIB_DIR", &chained), "${LIB_DIR");
+        assert!(expand_strict("$(LIB_DIR}", &chained).is_err());
+    }
+
+    #[test]
+    fn test_text_variable_resolver() {
+        let text = r#"(kicad_sch (version 20231120) (generator "eeschema")
+            (title_block (title "Power Supply") (rev "C") (comment 1 "Checked"))
+            (text "${TITLE} rev ${REVISION}, ${COMMENT1} by ${ENGINEER}" (at 0 0 0) (effects (font (size 1.27 1.27)))
+                (uuid "00000000-0000-0000-0000-000000000001"))
+            (label "${RAIL}_EN" (at 0 0 0) (effects (font (size 1.27 1.27))) (uuid "00000000-0000-0000-0000-000000000002"))
+            (symbol (lib_id "Device:R") (at 0 0 0) (unit 1) (uuid "00000000-0000-0000-0000-000000000003")
+                (property "Reference" "R1" (at 0 0 0)) (property "Value" "10k" (at 0 0 0))
+                (property "Note" "${REFERENCE} is ${VALUE} on ${RAIL}, ${MISSING}" (at 0 0 0))))"#;
+
+        let mut schematic: Schematic = parse_str(text).unwrap();
+        let resolver = TextVariableResolver::for_schematic(&schematic, StaticVars::new().with("RAIL", "3V3"));
+        assert_eq!(resolver.unresolved(&schematic).into_iter().collect::<Vec<_>>(), ["ENGINEER", "MISSING"]);
+
+        resolver.resolve_schematic(&mut schematic);
+        assert_eq!(schematic.texts[0].text, "Power Supply rev C, Checked by ${ENGINEER}");
+        assert_eq!(schematic.labels[0].text, "3V3_EN");
+        assert_eq!(schematic.symbols[0].properties[2].value, "R1 is 10k on 3V3, ${MISSING}");
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{ParseError, ParseOptions};
+
+/// The oldest format version this crate reads: the KiCad 5 board format.
+pub const OLDEST_FORMAT_VERSION: i64 = 20171130;
+
+/// The newest format version this crate has been checked against: the KiCad 9 schematic format.
+pub const NEWEST_FORMAT_VERSION: i64 = 20250114;
+
+/// The KiCad release whose file format a document uses, from its `version` element.
+///
+/// Each file type has its own version sequence, but all are dates that fall within the same
+/// window for each release. Syntax that differs between releases is read in every form this
+/// crate supports, so the format version does not change how a document is converted; it
+/// determines whether a document can be read at all and what a writer should produce.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum FormatVersion {
+    /// KiCad 5, which used s-expressions only for boards and footprints.
+    Kicad5,
+
+    /// KiCad 6: the first s-expression schematics and symbol libraries.
+    Kicad6,
+
+    /// KiCad 7: adds `dnp`, text boxes, and directive labels.
+    Kicad7,
+
+    /// KiCad 8: adds `exclude_from_sim`, `generator_version`, color fills, and `yes`/`no` values
+    /// for flags such as `fields_autoplaced`.
+    Kicad8,
+
+    /// KiCad 9: adds `(hide yes)`, embedded files, and hatched fills.
+    Kicad9,
+}
+
+impl FormatVersion {
+    /// Return the release that writes a format version, or [`ParseError::UnsupportedVersion`] if
+    /// the version is older than [`OLDEST_FORMAT_VERSION`] or newer than
+    /// [`NEWEST_FORMAT_VERSION`].
+    pub fn from_version(version: i64) -> Result<Self, ParseError> {
+        match version {
+            OLDEST_FORMAT_VERSION..=20210000 => Ok(Self::Kicad5),
+            20210001..=20220200 => Ok(Self::Kicad6),
+            20220201..=20231100 => Ok(Self::Kicad7),
+            20231101..=20241200 => Ok(Self::Kicad8),
+            20241201..=NEWEST_FORMAT_VERSION => Ok(Self::Kicad9),
+            _ => Err(ParseError::UnsupportedVersion(version)),
+        }
+    }
+
+    /// Return the release closest to a format version: the oldest or newest supported release for
+    /// versions outside the supported range.
+    pub fn nearest(version: i64) -> Self {
+        match Self::from_version(version) {
+            Ok(release) => release,
+            Err(_) if version > NEWEST_FORMAT_VERSION => Self::Kicad9,
+            Err(_) => Self::Kicad5,
+        }
+    }
+
+    /// Return the release of a document's declared version, checking that it can be read.
+    ///
+    /// `oldest` is the first release that wrote the file type. Documents without a version are
+    /// accepted. Without [`ParseOptions::strict`], documents newer than this crate knows are read
+    /// as the newest release, on the assumption that most of their syntax is unchanged; with it,
+    /// documents newer than [`ParseOptions::version_profile`] are rejected.
+    pub fn check(version: Option<i64>, oldest: Self, options: &ParseOptions) -> Result<Option<Self>, ParseError> {
+        let Some(version) = version else {
+            return Ok(None);
+        };
+
+        match Self::from_version(version) {
+            Ok(release) if options.strict && options.version_profile.is_some_and(|newest| release > newest) => {
+                Err(ParseError::UnsupportedVersion(version))
+            }
+            Ok(release) if release >= oldest => Ok(Some(release)),
+            Err(_) if !options.strict && version > NEWEST_FORMAT_VERSION => Ok(Some(Self::Kicad9)),
+            _ => Err(ParseError::UnsupportedVersion(version)),
+        }
+    }
+
+    /// Return the version of KiCad that writes this format, e.g. `8`.
+    pub fn major(&self) -> u32 {
+        match self {
+            Self::Kicad5 => 5,
+            Self::Kicad6 => 6,
+            Self::Kicad7 => 7,
+            Self::Kicad8 => 8,
+            Self::Kicad9 => 9,
+        }
+    }
+
+    /// Indicates whether flags are written with a `yes` or `no` value, as in
+    /// `(fields_autoplaced yes)`, rather than present or absent.
+    pub fn has_flag_values(&self) -> bool {
+        *self >= Self::Kicad8
+    }
+
+    /// Indicates whether visibility is written as a `(hide yes)` element rather than a bare `hide`.
+    pub fn has_hide_element(&self) -> bool {
+        *self >= Self::Kicad9
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{parse_str, sch::Schematic},
+    };
+
+    #[test]
+    fn test_format_versions() {
+        // KiCad 6: bare flags and a bare hide.
+        let kicad6 = "(kicad_sch (version 20211123) (generator eeschema)
+            (label \"CLK\" (at 10 20 0) (fields_autoplaced)
+                (effects (font (size 1.27 1.27)) (justify left bottom) hide)
+                (uuid \"c290e417-b246-4481-96d9-22242c5e4431\")))";
+        let schematic: Schematic = parse_str(kicad6).unwrap();
+        assert_eq!(schematic.format_version(), Some(FormatVersion::Kicad6));
+        assert!(schematic.labels[0].fields_autoplaced);
+        assert!(schematic.labels[0].text_effects.hide);
+
+        // KiCad 8: flag values and color fills.
+        let kicad8 = "(kicad_sch (version 20231120) (generator \"eeschema\") (generator_version \"8.0\")
+            (label \"CLK\" (at 10 20 0) (fields_autoplaced no)
+                (effects (font (size 1.27 1.27)) (justify left bottom))
+                (uuid \"c290e417-b246-4481-96d9-22242c5e4431\")))";
+        let schematic: Schematic = parse_str(kicad8).unwrap();
+        assert_eq!(schematic.format_version().map(|version| version.major()), Some(8));
+        assert!(!schematic.labels[0].fields_autoplaced);
+
+        let fill = crate::common::Fill::try_from(&lexpr::from_str("(fill (type color) (color 255 0 0 1))").unwrap());
+        assert!(matches!(fill.unwrap().fill_type, crate::common::FillType::Color));
+
+        // Schematics did not exist before KiCad 6, and KiCad 10 is not yet supported.
+        for version in [20171130, 20260101] {
+            let text = format!("(kicad_sch (version {version}) (generator \"eeschema\"))");
+            let err = parse_str::<Schematic>(&text).unwrap_err();
+            assert!(
+                matches!(err.as_parse_error().unwrap().root_cause(), ParseError::UnsupportedVersion(v) if *v == version),
+                "{err}"
+            );
+        }
+
+        let lenient = ParseOptions {
+            strict: false,
+            ..ParseOptions::default()
+        };
+        let value = lexpr::from_str("(kicad_sch (version 20260101) (generator \"eeschema\"))").unwrap();
+        let schematic = Schematic::parse(value.as_cons().unwrap(), &lenient).unwrap();
+        assert_eq!(schematic.format_version(), Some(FormatVersion::Kicad9));
+
+        // A version profile rejects documents from newer releases.
+        let kicad7 = ParseOptions::default().version_profile(FormatVersion::Kicad7);
+        let value = lexpr::from_str(kicad8).unwrap();
+        let err = Schematic::parse(value.as_cons().unwrap(), &kicad7).unwrap_err();
+        assert!(matches!(err.root_cause(), ParseError::UnsupportedVersion(20231120)), "{err}");
+        let value = lexpr::from_str(kicad6).unwrap();
+        assert!(Schematic::parse(value.as_cons().unwrap(), &kicad7).is_ok());
+    }
+}
@@ -0,0 +1,164 @@
+use {crate::LexprExt, lexpr::Value, std::mem::replace};
+
+/// What [`walk_mut`] should do with an element after its visitor returns.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Edit {
+    /// Keep the element, including any changes made in place, and visit its children.
+    Keep,
+
+    /// Keep the element but do not visit its children.
+    SkipChildren,
+
+    /// Replace the element with a new value. The replacement's children are not visited.
+    Replace(Value),
+
+    /// Remove the element from its parent list.
+    Remove,
+}
+
+/// Visit every element of a document, depth first in document order.
+///
+/// An element is a list whose head is a symbol, such as `(wire ...)`. `f` is called with the heads
+/// of the element and its ancestors, from the root down, and the element itself. If it returns
+/// `false`, the element's children are skipped. Atoms and lists without a symbol head are not
+/// visited.
+pub fn walk<F>(value: &Value, mut f: F)
+where
+    F: FnMut(&[&str], &Value) -> bool,
+{
+    walk_element(value, &mut Vec::new(), &mut f);
+}
+
+/// Visit every element of a document, depth first in document order, with mutable access.
+///
+/// `f` is called as in [`walk`], but may change the element in place and returns an [`Edit`]
+/// saying whether to keep, replace, or remove it. Elements that follow a removed element in the
+/// same list are still visited. Removing the root element leaves an empty list.
+pub fn walk_mut<F>(value: &mut Value, mut f: F)
+where
+    F: FnMut(&[String], &mut Value) -> Edit,
+{
+    if walk_element_mut(value, &mut Vec::new(), &mut f) {
+        *value = Value::Null;
+    }
+}
+
+/// Visit an element and its children.
+fn walk_element<'a, F>(value: &'a Value, path: &mut Vec<&'a str>, f: &mut F)
+where
+    F: FnMut(&[&str], &Value) -> bool,
+{
+    let Ok((head, args)) = value.expect_cons_with_any_symbol_head() else {
+        return;
+    };
+
+    path.push(head);
+    if f(path, value) {
+        for child in args.list_iter().into_iter().flatten() {
+            walk_element(child, path, f);
+        }
+    }
+    path.pop();
+}
+
+/// Visit an element and its children, returning `true` if the element should be removed.
+fn walk_element_mut<F>(value: &mut Value, path: &mut Vec<String>, f: &mut F) -> bool
+where
+    F: FnMut(&[String], &mut Value) -> Edit,
+{
+    let Ok((head, _)) = value.expect_cons_with_any_symbol_head() else {
+        return false;
+    };
+
+    path.push(head.to_string());
+    let edit = f(path, value);
+
+    let remove = match edit {
+        Edit::Keep => {
+            if let Value::Cons(cons) = value {
+                walk_list_mut(cons.cdr_mut(), path, f);
+            }
+            false
+        }
+        Edit::SkipChildren => false,
+        Edit::Replace(replacement) => {
+            *value = replacement;
+            false
+        }
+        Edit::Remove => true,
+    };
+
+    path.pop();
+    remove
+}
+
+/// Visit the elements of a list, unlinking those that should be removed.
+fn walk_list_mut<F>(list: &mut Value, path: &mut Vec<String>, f: &mut F)
+where
+    F: FnMut(&[String], &mut Value) -> Edit,
+{
+    let mut cursor = list;
+    loop {
+        let remove = match cursor {
+            Value::Cons(cons) => walk_element_mut(cons.car_mut(), path, f),
+            _ => return,
+        };
+
+        if remove {
+            let rest = match cursor {
+                Value::Cons(cons) => replace(cons.cdr_mut(), Value::Null),
+                _ => return,
+            };
+            *cursor = rest;
+        } else {
+            cursor = match cursor {
+                Value::Cons(cons) => cons.cdr_mut(),
+                _ => return,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, lexpr::sexp};
+
+    #[test]
+    fn test_walk_mut() {
+        let mut schematic = sexp!((kicad_sch (version 20231120)
+            (label "CLK" (at 10 20 0))
+            (junction (at 10 20))
+            (label "DATA" (at 30 20 0))
+            (wire (pts (xy 0 0) (xy 10 0)))));
+
+        let mut paths = Vec::new();
+        walk(&schematic, |path, _| {
+            paths.push(path.join(" > "));
+            path.len() < 3
+        });
+        assert_eq!(paths[0], "kicad_sch");
+        assert!(paths.contains(&"kicad_sch > wire > pts".to_string()));
+        assert!(!paths.contains(&"kicad_sch > wire > pts > xy".to_string()));
+
+        walk_mut(&mut schematic, |path, element| match path.last().map(String::as_str) {
+            Some("junction") => Edit::Remove,
+            Some("label") => {
+                if let Some(name) = element.as_cons_mut().and_then(|cons| cons.cdr_mut().as_cons_mut()) {
+                    let renamed = format!("BUS_{}", name.car().as_str().unwrap_or_default());
+                    name.set_car(Value::string(renamed));
+                }
+                Edit::SkipChildren
+            }
+            Some("wire") => Edit::Replace(sexp!((bus (pts (xy 0 0) (xy 10 0))))),
+            _ => Edit::Keep,
+        });
+
+        assert_eq!(
+            schematic,
+            sexp!((kicad_sch (version 20231120)
+                (label "BUS_CLK" (at 10 20 0))
+                (label "BUS_DATA" (at 30 20 0))
+                (bus (pts (xy 0 0) (xy 10 0)))))
+        );
+    }
+}
@@ -0,0 +1,829 @@
+use {
+    crate::{
+        common::{nm_to_mm_value, sexpr_list, Angle, Nm, Offset, Paper, Size},
+        impl_try_from_cons_value, LexprExt, ParseError,
+    },
+    kanga_sexpr::ToSexpr,
+    lexpr::{Cons, Value},
+};
+
+/// The distance between the page border and the outer frame of a KiCad drawing sheet.
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-worksheet/index.html#_setup)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct WorksheetMargins {
+    /// Left margin in nanometers.
+    pub left: u64,
+
+    /// Right margin in nanometers.
+    pub right: u64,
+
+    /// Top margin in nanometers.
+    pub top: u64,
+
+    /// Bottom margin in nanometers.
+    pub bottom: u64,
+}
+
+/// The frame geometry of a drawing sheet placed on a page.
+///
+/// Renderers use this to draw the sheet border and to keep content placement within the margins.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct WorksheetFrame {
+    /// The size of the full page in nanometers.
+    pub page: Size,
+
+    /// The margins between the page border and the frame.
+    pub margins: WorksheetMargins,
+}
+
+impl Default for WorksheetMargins {
+    /// The margins used by KiCad's default drawing sheet: 10 mm on each side.
+    fn default() -> Self {
+        Self::uniform(10_000_000)
+    }
+}
+
+impl WorksheetMargins {
+    /// Create margins that are the same on all sides, in nanometers.
+    pub fn uniform(margin: u64) -> Self {
+        Self {
+            left: margin,
+            right: margin,
+            top: margin,
+            bottom: margin,
+        }
+    }
+}
+
+impl WorksheetFrame {
+    /// Create the frame for the given paper settings and margins.
+    pub fn new(paper: &Paper, margins: WorksheetMargins) -> Self {
+        Self {
+            page: paper.page_size(),
+            margins,
+        }
+    }
+
+    /// Return the top-left corner of the area inside the frame, relative to the page origin.
+    pub fn origin(&self) -> Offset {
+        Offset {
+            x: Nm(self.margins.left as i64),
+            y: Nm(self.margins.top as i64),
+        }
+    }
+
+    /// Return the size of the area inside the frame.
+    ///
+    /// If the margins exceed the page size, the corresponding dimension is zero.
+    pub fn drawing_size(&self) -> Size {
+        let width = self.page.width - Nm((self.margins.left + self.margins.right) as i64);
+        let height = self.page.height - Nm((self.margins.top + self.margins.bottom) as i64);
+        Size::new(width.max(Nm::ZERO), height.max(Nm::ZERO))
+    }
+
+    /// Indicates whether the point `(x, y)`, in nanometers from the page origin, lies within the
+    /// frame.
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        let origin = self.origin();
+        let size = self.drawing_size();
+
+        let (x, y) = (Nm(x), Nm(y));
+        x >= origin.x && y >= origin.y && x <= origin.x + size.width && y <= origin.y + size.height
+    }
+}
+
+/// The file format version written to generated drawing sheets.
+pub const WORKSHEET_VERSION: i64 = 20231118;
+
+/// The title block rows added by [`Worksheet::add_title_block`] for a typical sheet. Values use
+/// KiCad text variables, which are substituted when the sheet is drawn.
+pub const DEFAULT_TITLE_BLOCK_ROWS: &[(&str, &str)] = &[
+    ("Company", "${COMPANY}"),
+    ("Title", "${TITLE}"),
+    ("Sheet", "${SHEETNAME}"),
+    ("Date", "${ISSUE_DATE}"),
+    ("Rev", "${REVISION}"),
+    ("Page", "${#}/${##}"),
+];
+
+/// The frame corner that a drawing sheet coordinate is measured from. Coordinates increase
+/// inward from the corner.
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-worksheet/index.html#_position_identifier)
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum WorksheetCorner {
+    /// The top-left corner.
+    LeftTop,
+
+    /// The top-right corner.
+    RightTop,
+
+    /// The bottom-left corner.
+    LeftBottom,
+
+    /// The bottom-right corner, where title blocks are usually drawn.
+    #[default]
+    RightBottom,
+}
+
+/// A point on a drawing sheet.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct WorksheetPoint {
+    /// The distance from the corner along the X axis, in nanometers.
+    pub x: i64,
+
+    /// The distance from the corner along the Y axis, in nanometers.
+    pub y: i64,
+
+    /// The corner the point is measured from.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub corner: WorksheetCorner,
+}
+
+/// Repetition of a drawing sheet item, e.g. for frame ticks.
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-worksheet/index.html#_repeat_parameters)
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct WorksheetRepeat {
+    /// The number of times the item is drawn.
+    pub count: u32,
+
+    /// The X offset between repetitions, in nanometers.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub step_x: i64,
+
+    /// The Y offset between repetitions, in nanometers.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub step_y: i64,
+
+    /// The increment applied to a trailing number or letter in repeated text.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub step_label: i64,
+}
+
+/// A line or rectangle on a drawing sheet.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct WorksheetLine {
+    /// The start point, or one corner of a rectangle.
+    pub start: WorksheetPoint,
+
+    /// The end point, or the opposite corner of a rectangle.
+    pub end: WorksheetPoint,
+
+    /// The line width in nanometers, or `None` for the sheet default.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub width: Option<i64>,
+
+    /// The repetition of the item.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub repeat: Option<WorksheetRepeat>,
+}
+
+/// Text on a drawing sheet, which may include text variables such as `${TITLE}`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct WorksheetText {
+    /// The text to draw.
+    pub text: String,
+
+    /// The position of the text anchor.
+    pub position: WorksheetPoint,
+
+    /// The text size, or `None` for the sheet default.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub size: Option<Size>,
+
+    /// Whether the text is bold.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub bold: bool,
+
+    /// The repetition of the item.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub repeat: Option<WorksheetRepeat>,
+}
+
+/// Filled polygons on a drawing sheet, used for logos and artwork.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct WorksheetPolygon {
+    /// The position of the polygon origin.
+    pub position: WorksheetPoint,
+
+    /// The rotation of the polygons about their origin.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rotation: Angle,
+
+    /// The outline width in nanometers, or `None` for the sheet default.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub width: Option<i64>,
+
+    /// The polygon outlines, as points in nanometers relative to the origin. The Y axis points down.
+    pub outlines: Vec<Vec<(i64, i64)>>,
+}
+
+/// An item drawn on a drawing sheet.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum WorksheetItem {
+    /// A line.
+    Line(WorksheetLine),
+
+    /// A rectangle.
+    Rect(WorksheetLine),
+
+    /// Text.
+    Text(WorksheetText),
+
+    /// Filled polygons.
+    Polygon(WorksheetPolygon),
+}
+
+/// A drawing sheet template that can be written as a `.kicad_wks` file.
+///
+/// [Reference](https://dev-docs.kicad.org/en/file-formats/sexpr-worksheet/index.html)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Worksheet {
+    /// The margins between the page border and the frame.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub margins: WorksheetMargins,
+
+    /// The default text size.
+    pub text_size: Size,
+
+    /// The default line width, in nanometers.
+    pub line_width: i64,
+
+    /// The default text stroke width, in nanometers.
+    pub text_line_width: i64,
+
+    /// The items drawn on the sheet.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub items: Vec<WorksheetItem>,
+}
+
+impl WorksheetCorner {
+    /// Return the KiCad name of this corner.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::LeftTop => "ltcorner",
+            Self::RightTop => "rtcorner",
+            Self::LeftBottom => "lbcorner",
+            Self::RightBottom => "rbcorner",
+        }
+    }
+
+    /// Return the corner with the given KiCad name.
+    fn from_name(name: &str) -> Option<Self> {
+        [Self::LeftTop, Self::RightTop, Self::LeftBottom, Self::RightBottom]
+            .into_iter()
+            .find(|corner| corner.as_str() == name)
+    }
+}
+
+impl WorksheetPoint {
+    /// Create a point measured from a corner, in nanometers.
+    pub fn new(x: i64, y: i64, corner: WorksheetCorner) -> Self {
+        Self {
+            x,
+            y,
+            corner,
+        }
+    }
+
+    /// Build a `(<head> x y [corner])` element. The corner is omitted when it is the default.
+    fn to_value(self, head: &str) -> Value {
+        let corner = (self.corner != WorksheetCorner::RightBottom).then(|| Value::symbol(self.corner.as_str()));
+        sexpr_list(head, [nm_to_mm_value(self.x), nm_to_mm_value(self.y)].into_iter().chain(corner))
+    }
+
+    /// Parse the `x y [corner]` arguments of a point element.
+    fn try_from_args(args: &Value) -> Result<Self, ParseError> {
+        let (x, rest) = args.expect_cons_with_any_length_head()?;
+        let (y, rest) = rest.expect_cons_with_any_length_head()?;
+        let corner = match rest.expect_cons_with_any_symbol_head() {
+            Ok((corner, cdr)) => {
+                cdr.expect_null()?;
+                WorksheetCorner::from_name(corner).ok_or_else(|| ParseError::Unexpected(rest.clone()))?
+            }
+            Err(_) => {
+                rest.expect_null()?;
+                WorksheetCorner::default()
+            }
+        };
+
+        Ok(Self::new(x.0, y.0, corner))
+    }
+}
+
+impl WorksheetRepeat {
+    /// Return the `repeat` and increment elements.
+    fn to_values(&self) -> Vec<Value> {
+        let mut values = vec![sexpr_list("repeat", [Value::from(self.count)])];
+        if self.step_x != 0 {
+            values.push(sexpr_list("incrx", [nm_to_mm_value(self.step_x)]));
+        }
+        if self.step_y != 0 {
+            values.push(sexpr_list("incry", [nm_to_mm_value(self.step_y)]));
+        }
+        if self.step_label != 0 {
+            values.push(sexpr_list("incrlabel", [Value::from(self.step_label)]));
+        }
+        values
+    }
+
+    /// Apply a `repeat`, `incrx`, `incry`, or `incrlabel` element to an item's repetition.
+    fn apply(repeat: &mut Option<Self>, key: &str, args: &Value) -> Result<(), ParseError> {
+        let repeat = repeat.get_or_insert_with(Self::default);
+        match key {
+            "repeat" => repeat.count = args.expect_cons_with_any_int_head()?.0.try_into().unwrap_or_default(),
+            "incrx" => repeat.step_x = line_width(args)?,
+            "incry" => repeat.step_y = line_width(args)?,
+            _ => repeat.step_label = args.expect_cons_with_any_int_head()?.0,
+        }
+        Ok(())
+    }
+}
+
+/// Item attributes that KiCad writes but the model does not represent. They are skipped when
+/// reading.
+const IGNORED_ITEM_ATTRIBUTES: &[&str] = &["name", "comment", "option", "justify", "rotate", "maxlen", "maxheight"];
+
+/// Parse the length argument of an element such as `(linewidth w)` as nanometers.
+fn line_width(args: &Value) -> Result<i64, ParseError> {
+    let (Nm(width), _) = args.expect_cons_with_any_length_head()?;
+    Ok(width)
+}
+
+impl WorksheetLine {
+    /// Parse the attributes of a `line` or `rect` element.
+    fn try_from_args(cons: &Cons) -> Result<Self, ParseError> {
+        let mut start = None;
+        let mut end = None;
+        let mut width = None;
+        let mut repeat = None;
+
+        for element in cons.cdr().list_iter().into_iter().flatten() {
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "start" => start = Some(WorksheetPoint::try_from_args(cdr)?),
+                "end" => end = Some(WorksheetPoint::try_from_args(cdr)?),
+                "linewidth" => width = Some(line_width(cdr)?),
+                "repeat" | "incrx" | "incry" | "incrlabel" => WorksheetRepeat::apply(&mut repeat, key, cdr)?,
+                key if IGNORED_ITEM_ATTRIBUTES.contains(&key) => (),
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        let head = cons.car().as_symbol().unwrap_or_default();
+        let value = || Value::Cons(cons.clone());
+        Ok(Self {
+            start: start.ok_or_else(|| ParseError::missing_field(head, "start", value()))?,
+            end: end.ok_or_else(|| ParseError::missing_field(head, "end", value()))?,
+            width,
+            repeat,
+        })
+    }
+}
+
+impl TryFrom<&Cons> for WorksheetText {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let rest = cons.expect_cons_with_symbol_head("tbtext")?;
+        let (text, rest) = rest.expect_cons_with_any_str_head()?;
+        let mut position = None;
+        let mut size = None;
+        let mut bold = false;
+        let mut repeat = None;
+
+        for element in rest.list_iter().into_iter().flatten() {
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "pos" => position = Some(WorksheetPoint::try_from_args(cdr)?),
+                "font" => {
+                    for attribute in cdr.list_iter().into_iter().flatten() {
+                        match attribute.as_symbol() {
+                            Some("bold") => bold = true,
+                            Some("italic") => (),
+                            _ => match attribute.expect_cons_with_any_symbol_head()?.0 {
+                                "size" => size = Some(Size::try_from(attribute)?),
+                                "linewidth" | "color" => (),
+                                _ => return Err(ParseError::Unexpected(attribute.clone())),
+                            },
+                        }
+                    }
+                }
+                "repeat" | "incrx" | "incry" | "incrlabel" => WorksheetRepeat::apply(&mut repeat, key, cdr)?,
+                key if IGNORED_ITEM_ATTRIBUTES.contains(&key) => (),
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        Ok(Self {
+            text: text.to_string(),
+            position: position.ok_or_else(|| ParseError::missing_field("tbtext", "pos", Value::Cons(cons.clone())))?,
+            size,
+            bold,
+            repeat,
+        })
+    }
+}
+
+impl_try_from_cons_value!(WorksheetText);
+
+impl TryFrom<&Cons> for WorksheetPolygon {
+    type Error = ParseError;
+
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        let rest = cons.expect_cons_with_symbol_head("polygon")?;
+        let mut position = None;
+        let mut rotation = Angle::ZERO;
+        let mut width = None;
+        let mut outlines = Vec::new();
+
+        for element in rest.list_iter().into_iter().flatten() {
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+
+            match key {
+                "pos" => position = Some(WorksheetPoint::try_from_args(cdr)?),
+                "rotate" => rotation = Angle::from_degrees(cdr.expect_cons_with_any_float_head()?.0),
+                "linewidth" => width = Some(line_width(cdr)?),
+                "pts" => {
+                    let points = cdr.list_iter().into_iter().flatten().map(|xy| {
+                        let (x, rest) = xy.expect_cons_with_symbol_head("xy")?.expect_cons_with_any_length_head()?;
+                        let (y, _) = rest.expect_cons_with_any_length_head()?;
+                        Ok((x.0, y.0))
+                    });
+                    outlines.push(points.collect::<Result<_, ParseError>>()?);
+                }
+                "repeat" | "incrx" | "incry" | "incrlabel" => (),
+                key if IGNORED_ITEM_ATTRIBUTES.contains(&key) => (),
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        Ok(Self {
+            position: position.ok_or_else(|| ParseError::missing_field("polygon", "pos", Value::Cons(cons.clone())))?,
+            rotation,
+            width,
+            outlines,
+        })
+    }
+}
+
+impl_try_from_cons_value!(WorksheetPolygon);
+
+impl Default for Worksheet {
+    /// An empty sheet with KiCad's default margins, 1.5 mm text, and 0.15 mm lines.
+    fn default() -> Self {
+        Self {
+            margins: WorksheetMargins::default(),
+            text_size: Size::new(Nm(1_500_000), Nm(1_500_000)),
+            line_width: 150_000,
+            text_line_width: 150_000,
+            items: Vec::new(),
+        }
+    }
+}
+
+impl Worksheet {
+    /// Add the sheet frame: a rectangle at the margins and a second rectangle `border`
+    /// nanometers inside it, with reference ticks every 50 mm labeled `1, 2, ...` along the top
+    /// and `A, B, ...` down the left.
+    pub fn add_frame(&mut self, border: i64) {
+        let corner = |x, y, corner| WorksheetPoint::new(x, y, corner);
+        let (lt, rb) = (WorksheetCorner::LeftTop, WorksheetCorner::RightBottom);
+
+        for inset in [0, border] {
+            self.items.push(WorksheetItem::Rect(WorksheetLine {
+                start: corner(inset, inset, lt),
+                end: corner(inset, inset, rb),
+                width: None,
+                repeat: None,
+            }));
+        }
+
+        let tick = 50_000_000;
+        let repeat = |step_x, step_y, count| {
+            Some(WorksheetRepeat {
+                count,
+                step_x,
+                step_y,
+                step_label: 1,
+            })
+        };
+
+        self.items.push(WorksheetItem::Line(WorksheetLine {
+            start: corner(tick, 0, lt),
+            end: corner(tick, border, lt),
+            width: None,
+            repeat: repeat(tick, 0, 30),
+        }));
+        self.items.push(WorksheetItem::Line(WorksheetLine {
+            start: corner(0, tick, lt),
+            end: corner(border, tick, lt),
+            width: None,
+            repeat: repeat(0, tick, 30),
+        }));
+
+        for (text, x, y, step_x, step_y) in [("1", tick / 2, border / 2, tick, 0), ("A", border / 2, tick / 2, 0, tick)]
+        {
+            self.items.push(WorksheetItem::Text(WorksheetText {
+                text: text.to_string(),
+                position: corner(x, y, lt),
+                size: Some(Size::new(Nm(1_300_000), Nm(1_300_000))),
+                bold: false,
+                repeat: repeat(step_x, step_y, 30),
+            }));
+        }
+    }
+
+    /// Add a title block in the bottom-right corner of the frame, `width` nanometers wide, with
+    /// one `label: value` row per entry from top to bottom. Values may use text variables, as in
+    /// [`DEFAULT_TITLE_BLOCK_ROWS`].
+    pub fn add_title_block(&mut self, width: i64, row_height: i64, rows: &[(&str, &str)]) {
+        let height = row_height * rows.len() as i64;
+        let point = |x, y| WorksheetPoint::new(x, y, WorksheetCorner::RightBottom);
+
+        self.items.push(WorksheetItem::Rect(WorksheetLine {
+            start: point(width, height),
+            end: point(0, 0),
+            width: None,
+            repeat: None,
+        }));
+
+        for (i, (label, value)) in rows.iter().enumerate() {
+            let top = height - i as i64 * row_height;
+            if i > 0 {
+                self.items.push(WorksheetItem::Line(WorksheetLine {
+                    start: point(width, top),
+                    end: point(0, top),
+                    width: None,
+                    repeat: None,
+                }));
+            }
+
+            self.items.push(WorksheetItem::Text(WorksheetText {
+                text: format!("{label}: {value}"),
+                position: point(width - 1_000_000, top - row_height / 2),
+                size: None,
+                bold: *label == "Title",
+                repeat: None,
+            }));
+        }
+    }
+
+    /// Render the sheet as the contents of a `.kicad_wks` file.
+    pub fn to_kicad_wks(&self) -> String {
+        format!("{}\n", Value::from(self))
+    }
+}
+
+impl From<&WorksheetItem> for Value {
+    fn from(item: &WorksheetItem) -> Self {
+        let name = sexpr_list("name", [Value::string("")]);
+
+        match item {
+            WorksheetItem::Line(line) | WorksheetItem::Rect(line) => {
+                let head = if matches!(item, WorksheetItem::Line(_)) {
+                    "line"
+                } else {
+                    "rect"
+                };
+                let mut items = vec![name, line.start.to_value("start"), line.end.to_value("end")];
+                items.extend(line.width.map(|width| sexpr_list("linewidth", [nm_to_mm_value(width)])));
+                items.extend(line.repeat.iter().flat_map(WorksheetRepeat::to_values));
+                sexpr_list(head, items)
+            }
+
+            WorksheetItem::Text(text) => {
+                let mut items = vec![Value::string(text.text.as_str()), name, text.position.to_value("pos")];
+                if text.size.is_some() || text.bold {
+                    let font = text
+                        .size
+                        .as_ref()
+                        .map(ToSexpr::to_value)
+                        .into_iter()
+                        .chain(text.bold.then(|| Value::symbol("bold")));
+                    items.push(sexpr_list("font", font));
+                }
+                items.extend(text.repeat.iter().flat_map(WorksheetRepeat::to_values));
+                sexpr_list("tbtext", items)
+            }
+
+            WorksheetItem::Polygon(polygon) => {
+                let mut items = vec![name, polygon.position.to_value("pos")];
+                if polygon.rotation != Angle::ZERO {
+                    items.push(sexpr_list("rotate", [Value::from(polygon.rotation.degrees())]));
+                }
+                items.extend(polygon.width.map(|width| sexpr_list("linewidth", [nm_to_mm_value(width)])));
+                for outline in &polygon.outlines {
+                    let points = outline.iter().map(|&(x, y)| sexpr_list("xy", [nm_to_mm_value(x), nm_to_mm_value(y)]));
+                    items.push(sexpr_list("pts", points));
+                }
+                sexpr_list("polygon", items)
+            }
+        }
+    }
+}
+
+impl From<&Worksheet> for Value {
+    fn from(worksheet: &Worksheet) -> Self {
+        let margins = &worksheet.margins;
+        let setup = sexpr_list(
+            "setup",
+            [
+                sexpr_list(
+                    "textsize",
+                    [nm_to_mm_value(worksheet.text_size.width), nm_to_mm_value(worksheet.text_size.height)],
+                ),
+                sexpr_list("linewidth", [nm_to_mm_value(worksheet.line_width)]),
+                sexpr_list("textlinewidth", [nm_to_mm_value(worksheet.text_line_width)]),
+                sexpr_list("left_margin", [nm_to_mm_value(margins.left as i64)]),
+                sexpr_list("right_margin", [nm_to_mm_value(margins.right as i64)]),
+                sexpr_list("top_margin", [nm_to_mm_value(margins.top as i64)]),
+                sexpr_list("bottom_margin", [nm_to_mm_value(margins.bottom as i64)]),
+            ],
+        );
+
+        let mut items = vec![
+            sexpr_list("version", [Value::from(WORKSHEET_VERSION)]),
+            sexpr_list("generator", [Value::symbol("kanga")]),
+            setup,
+        ];
+        items.extend(worksheet.items.iter().map(Value::from));
+        sexpr_list("kicad_wks", items)
+    }
+}
+
+impl Worksheet {
+    /// Apply the elements of a `setup` section.
+    fn apply_setup(&mut self, setup: &Value) -> Result<(), ParseError> {
+        for element in setup.list_iter().into_iter().flatten() {
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+            let margin = |cdr: &Value| line_width(cdr).map(|margin| margin.max(0) as u64);
+
+            match key {
+                "textsize" => {
+                    let (width, rest) = cdr.expect_cons_with_any_length_head()?;
+                    let (height, _) = rest.expect_cons_with_any_length_head()?;
+                    self.text_size = Size::try_new(width, height)?;
+                }
+                "linewidth" => self.line_width = line_width(cdr)?,
+                "textlinewidth" => self.text_line_width = line_width(cdr)?,
+                "left_margin" => self.margins.left = margin(cdr)?,
+                "right_margin" => self.margins.right = margin(cdr)?,
+                "top_margin" => self.margins.top = margin(cdr)?,
+                "bottom_margin" => self.margins.bottom = margin(cdr)?,
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<&Cons> for Worksheet {
+    type Error = ParseError;
+
+    /// Read a `.kicad_wks` drawing sheet, or the `page_layout` sheets of KiCad 5. Bitmaps and item
+    /// attributes the model does not represent, such as text justification, are skipped.
+    fn try_from(cons: &Cons) -> Result<Self, Self::Error> {
+        if !matches!(cons.car().as_symbol(), Some("kicad_wks" | "page_layout")) {
+            return Err(ParseError::ExpectedSymbol(Value::Cons(cons.clone()), "kicad_wks".to_string()));
+        }
+
+        let mut worksheet = Self::default();
+        for element in cons.cdr().list_iter().into_iter().flatten() {
+            let (key, cdr) = element.expect_cons_with_any_symbol_head()?;
+            let item_cons = element.expect_cons()?;
+
+            match key {
+                "version" | "generator" | "generator_version" | "bitmap" => (),
+                "setup" => worksheet.apply_setup(cdr)?,
+                "line" => worksheet.items.push(WorksheetItem::Line(
+                    WorksheetLine::try_from_args(item_cons).map_err(|e| e.within_element(item_cons))?,
+                )),
+                "rect" => worksheet.items.push(WorksheetItem::Rect(
+                    WorksheetLine::try_from_args(item_cons).map_err(|e| e.within_element(item_cons))?,
+                )),
+                "tbtext" => worksheet.items.push(WorksheetItem::Text(WorksheetText::try_from(element)?)),
+                "polygon" => worksheet.items.push(WorksheetItem::Polygon(WorksheetPolygon::try_from(element)?)),
+                _ => return Err(ParseError::Unexpected(element.clone())),
+            }
+        }
+
+        Ok(worksheet)
+    }
+}
+
+impl_try_from_cons_value!(Worksheet);
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::common::PaperSize};
+
+    #[test]
+    fn test_worksheet() {
+        let mut worksheet = Worksheet::default();
+        worksheet.add_frame(2_000_000);
+        worksheet.add_title_block(110_000_000, 5_000_000, DEFAULT_TITLE_BLOCK_ROWS);
+        worksheet.items.push(WorksheetItem::Polygon(WorksheetPolygon {
+            position: WorksheetPoint::new(100_000_000, 25_000_000, WorksheetCorner::RightBottom),
+            rotation: Angle::ZERO,
+            width: Some(10_000),
+            outlines: vec![vec![(0, 0), (4_000_000, 0), (2_000_000, -3_000_000)]],
+        }));
+
+        let text = worksheet.to_kicad_wks();
+        assert!(text.starts_with(
+            "(kicad_wks (version 20231118) (generator kanga) (setup (textsize 1.5 1.5) (linewidth 0.15) \
+             (textlinewidth 0.15) (left_margin 10.0)"
+        ));
+        assert!(text.contains("(rect (name \"\") (start 0.0 0.0 ltcorner) (end 0.0 0.0))"));
+        assert!(text.contains(
+            "(tbtext \"1\" (name \"\") (pos 25.0 1.0 ltcorner) (font (size 1.3 1.3)) (repeat 30) (incrx 50.0) \
+             (incrlabel 1))"
+        ));
+        assert!(text.contains("(tbtext \"Title: ${TITLE}\" (name \"\") (pos 109.0 22.5) (font bold))"));
+        assert!(text.contains("(pts (xy 0.0 0.0) (xy 4.0 0.0) (xy 2.0 -3.0))"));
+        assert!(lexpr::from_str(&text).is_ok());
+
+        let parsed: Worksheet = crate::parse_str(&text).unwrap();
+        assert_eq!(parsed.to_kicad_wks(), text);
+    }
+
+    #[test]
+    fn test_read_kicad_worksheet() {
+        let worksheet: Worksheet = crate::parse_str(
+            r#"(kicad_wks (version 20220228) (generator pl_editor)
+                (setup (textsize 1.5 1.5) (linewidth 0.15) (textlinewidth 0.15)
+                    (left_margin 10) (right_margin 10) (top_margin 10) (bottom_margin 10))
+                (rect (name "") (start 110 34) (end 2 2) (comment "rect around the title block"))
+                (line (name "") (start 50 2 ltcorner) (end 50 0 ltcorner) (repeat 30) (incrx 50))
+                (tbtext "Title: ${TITLE}" (name "") (pos 109 13.5) (option page1only)
+                    (font (linewidth 0.3) (size 2 2) bold italic) (justify left) (maxlen 100))
+                (bitmap (name "") (pos 50 50) (scale 1) (data "00"))
+            )"#,
+        )
+        .unwrap();
+
+        assert_eq!(worksheet.margins.left, 10_000_000);
+        assert_eq!(worksheet.items.len(), 3);
+        let WorksheetItem::Line(line) = &worksheet.items[1] else {
+            panic!("Expected a line: {:?}", worksheet.items[1]);
+        };
+        assert_eq!(line.start, WorksheetPoint::new(50_000_000, 2_000_000, WorksheetCorner::LeftTop));
+        assert_eq!(line.repeat.as_ref().map(|repeat| (repeat.count, repeat.step_x)), Some((30, 50_000_000)));
+        let WorksheetItem::Text(text) = &worksheet.items[2] else {
+            panic!("Expected text: {:?}", worksheet.items[2]);
+        };
+        assert_eq!(text.text, "Title: ${TITLE}");
+        assert!(text.bold);
+        assert_eq!(text.size.as_ref().map(|size| size.width), Some(Nm(2_000_000)));
+    }
+
+    #[test]
+    fn test_worksheet_frame() {
+        let paper = Paper::new(PaperSize::IsoA4, false);
+
+        let frame = WorksheetFrame::new(&paper, WorksheetMargins::default());
+        let origin = frame.origin();
+        assert_eq!((origin.x, origin.y), (Nm(10_000_000), Nm(10_000_000)));
+        let size = frame.drawing_size();
+        assert_eq!((size.width, size.height), (Nm(277_000_000), Nm(190_000_000)));
+        assert!(frame.contains(10_000_000, 10_000_000));
+        assert!(frame.contains(287_000_000, 200_000_000));
+        assert!(!frame.contains(5_000_000, 100_000_000));
+        assert!(!frame.contains(100_000_000, 205_000_000));
+
+        let margins = WorksheetMargins {
+            left: 20_000_000,
+            right: 5_000_000,
+            top: 15_000_000,
+            bottom: 0,
+        };
+        let frame = WorksheetFrame::new(&paper, margins);
+        let origin = frame.origin();
+        assert_eq!((origin.x, origin.y), (Nm(20_000_000), Nm(15_000_000)));
+        let size = frame.drawing_size();
+        assert_eq!((size.width, size.height), (Nm(272_000_000), Nm(195_000_000)));
+
+        // Margins wider than the page leave no drawing area.
+        let frame = WorksheetFrame::new(&paper, WorksheetMargins::uniform(200_000_000));
+        let size = frame.drawing_size();
+        assert_eq!((size.width, size.height), (Nm::ZERO, Nm::ZERO));
+    }
+}
@@ -0,0 +1,301 @@
+use {
+    lexpr::{Number, Value},
+    std::io::{Result as IoResult, Write},
+};
+
+/// Lists whose contents KiCad keeps on one line when saving in short form.
+const SHORT_FORM_KEYS: &[&str] = &["font", "stroke", "fill", "teardrop", "offset", "rotate", "scale"];
+
+/// The column up to which consecutive `(xy ...)` points share a line.
+const XY_COLUMN_LIMIT: usize = 99;
+
+/// The column after which the atoms of a list are wrapped onto new lines.
+const TOKEN_WRAP_COLUMN: usize = 72;
+
+/// Writes s-expressions the way KiCad saves them, so that a file written by this crate differs
+/// from the same file saved by KiCad as little as possible.
+///
+/// Each list starts on a new line indented with one tab per level, except that runs of
+/// `(xy ...)` points share a line up to column 99. A list that contains other lists closes on its
+/// own line. Numbers are written without trailing zeros or exponents, strings are always quoted,
+/// and booleans are written as `yes` and `no`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KicadWriter {
+    short_form: bool,
+}
+
+impl KicadWriter {
+    /// Create a writer using KiCad's formatting for saved files.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep `font`, `stroke`, `fill`, and similar lists on one line, as KiCad does when saving in
+    /// its compact form.
+    pub fn short_form(mut self, short_form: bool) -> Self {
+        self.short_form = short_form;
+        self
+    }
+
+    /// Format a document, ending with a newline.
+    pub fn to_string(&self, value: &Value) -> String {
+        let mut compact = String::new();
+        write_compact(&mut compact, value);
+        self.prettify(&compact)
+    }
+
+    /// Write a document, ending with a newline.
+    pub fn write<W: Write>(&self, writer: &mut W, value: &Value) -> IoResult<()> {
+        writer.write_all(self.to_string(value).as_bytes())
+    }
+
+    /// Reformat s-expression text with KiCad's indentation and line breaks. Whitespace between
+    /// tokens is replaced; the tokens themselves are copied unchanged.
+    pub fn prettify(&self, source: &str) -> String {
+        let source = source.as_bytes();
+        let mut formatted: Vec<u8> = Vec::with_capacity(source.len() * 2);
+
+        let mut depth = 0;
+        let mut last_non_whitespace = 0;
+        let mut in_quote = false;
+        let mut inserted_space = false;
+        let mut in_multi_line_list = false;
+        let mut in_xy = false;
+        let mut in_short_form = false;
+        let mut short_form_depth = 0;
+        let mut column = 0;
+        let mut backslashes = 0;
+
+        let newline = |formatted: &mut Vec<u8>, depth: usize| {
+            formatted.push(b'\n');
+            formatted.extend(std::iter::repeat_n(b'\t', depth));
+        };
+
+        for (i, &c) in source.iter().enumerate() {
+            if c.is_ascii_whitespace() && !in_quote {
+                let next = source[i..].iter().copied().find(|c| !c.is_ascii_whitespace()).unwrap_or(0);
+
+                if !inserted_space && depth > 0 && last_non_whitespace != b'(' && next != b')' && next != b'(' {
+                    if in_xy || column < TOKEN_WRAP_COLUMN || in_short_form {
+                        formatted.push(b' ');
+                        column += 1;
+                    } else {
+                        newline(&mut formatted, depth);
+                        column = depth;
+                        in_multi_line_list = true;
+                    }
+
+                    inserted_space = true;
+                }
+
+                continue;
+            }
+
+            inserted_space = false;
+
+            match c {
+                b'(' if !in_quote => {
+                    let is_xy = source[i + 1..].starts_with(b"xy ");
+                    let is_short_form = self.short_form && {
+                        let head = &source[i + 1..];
+                        let len = head.iter().take_while(|c| c.is_ascii_alphabetic()).count();
+                        SHORT_FORM_KEYS.iter().any(|key| key.as_bytes() == &head[..len])
+                    };
+
+                    if formatted.is_empty() {
+                        formatted.push(b'(');
+                        column += 1;
+                    } else if (in_xy && is_xy && column < XY_COLUMN_LIMIT) || in_short_form {
+                        formatted.extend_from_slice(b" (");
+                        column += 2;
+                    } else {
+                        newline(&mut formatted, depth);
+                        formatted.push(b'(');
+                        column = depth + 1;
+                    }
+
+                    in_xy = is_xy;
+
+                    if is_short_form && !in_short_form {
+                        in_short_form = true;
+                        short_form_depth = depth;
+                    }
+
+                    depth += 1;
+                }
+
+                b')' if !in_quote => {
+                    depth = depth.saturating_sub(1);
+
+                    if in_short_form || (last_non_whitespace != b')' && !in_multi_line_list) {
+                        formatted.push(b')');
+                        column += 1;
+                    } else {
+                        newline(&mut formatted, depth);
+                        formatted.push(b')');
+                        column = depth + 1;
+                        in_multi_line_list = false;
+                    }
+
+                    if in_short_form && short_form_depth == depth {
+                        in_short_form = false;
+                    }
+                }
+
+                _ => {
+                    // A quote preceded by an odd number of backslashes is escaped.
+                    match c {
+                        b'\\' => backslashes += 1,
+                        b'"' => {
+                            if backslashes % 2 == 0 {
+                                in_quote = !in_quote;
+                            }
+                            backslashes = 0;
+                        }
+                        _ => backslashes = 0,
+                    }
+
+                    formatted.push(c);
+                    column += 1;
+                }
+            }
+
+            last_non_whitespace = c;
+        }
+
+        formatted.push(b'\n');
+        String::from_utf8(formatted).unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned())
+    }
+}
+
+/// Format a document the way KiCad saves it. See [`KicadWriter`].
+pub fn to_kicad_string(value: &Value) -> String {
+    KicadWriter::new().to_string(value)
+}
+
+/// Format a number as KiCad does: fixed point, without trailing zeros.
+pub fn format_number(value: f64) -> String {
+    let mut text = if value != 0.0 && value.abs() <= 0.0001 {
+        format!("{value:.16}")
+    } else {
+        format!("{value:.10}")
+    };
+
+    if text.contains('.') {
+        text.truncate(text.trim_end_matches('0').trim_end_matches('.').len());
+    }
+
+    if text == "-0" {
+        text.remove(0);
+    }
+
+    text
+}
+
+/// Quote a string as KiCad does.
+pub fn quote_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            _ => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+/// Write a value on a single line with one space between tokens.
+fn write_compact(out: &mut String, value: &Value) {
+    match value {
+        Value::Cons(_) | Value::Null | Value::Nil => {
+            out.push('(');
+            let mut first = true;
+            let mut rest = value;
+
+            while let Value::Cons(cons) = rest {
+                if !first {
+                    out.push(' ');
+                }
+                write_compact(out, cons.car());
+                first = false;
+                rest = cons.cdr();
+            }
+
+            if !matches!(rest, Value::Null | Value::Nil) {
+                out.push_str(" . ");
+                write_compact(out, rest);
+            }
+
+            out.push(')');
+        }
+        Value::Vector(items) => {
+            out.push_str("#(");
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(' ');
+                }
+                write_compact(out, item);
+            }
+            out.push(')');
+        }
+        Value::Number(number) => out.push_str(&write_number(number)),
+        Value::String(text) => out.push_str(&quote_string(text)),
+        Value::Symbol(symbol) => out.push_str(symbol),
+        Value::Keyword(keyword) => {
+            out.push_str("#:");
+            out.push_str(keyword);
+        }
+        Value::Bool(flag) => out.push_str(if *flag {
+            "yes"
+        } else {
+            "no"
+        }),
+        Value::Char(c) => out.push_str(&quote_string(&c.to_string())),
+        Value::Bytes(bytes) => out.push_str(&quote_string(&String::from_utf8_lossy(bytes))),
+    }
+}
+
+/// Format a number from a document.
+fn write_number(number: &Number) -> String {
+    if let Some(int) = number.as_i64() {
+        int.to_string()
+    } else if let Some(int) = number.as_u64() {
+        int.to_string()
+    } else {
+        format_number(number.as_f64().unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, lexpr::sexp};
+
+    #[test]
+    fn test_kicad_writer() {
+        let wire = sexp!((kicad_sch (version 20231120) (generator "eeschema")
+            (wire (pts (xy 114.3 78.74) (xy 127.0 78.74)) (stroke (width 0) (type default))
+                (uuid "70c61c93-461b-4c4f-b27f-ea416ca6dd6a"))
+            (text "Say \"hi\"\nthere" (at -0.0 1e-5 0.5))));
+
+        assert_eq!(
+            to_kicad_string(&wire),
+            "(kicad_sch\n\t(version 20231120)\n\t(generator \"eeschema\")\n\t(wire\n\t\t(pts\n\t\t\t(xy 114.3 78.74) \
+             (xy 127 78.74)\n\t\t)\n\t\t(stroke\n\t\t\t(width 0)\n\t\t\t(type default)\n\t\t)\n\t\t(uuid \
+             \"70c61c93-461b-4c4f-b27f-ea416ca6dd6a\")\n\t)\n\t(text \"Say \\\"hi\\\"\\nthere\"\n\t\t(at 0 0.00001 0.5)\n\t)\n)\n"
+        );
+
+        let short = KicadWriter::new().short_form(true).to_string(&sexp!((effects (font (size 1.27 1.27) (bold yes)))));
+        assert_eq!(short, "(effects\n\t(font (size 1.27 1.27) (bold yes))\n)\n");
+
+        assert_eq!(format_number(1.0), "1");
+        assert_eq!(format_number(-2.54), "-2.54");
+        assert_eq!(format_number(0.00001), "0.00001");
+    }
+}